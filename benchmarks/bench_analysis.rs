@@ -0,0 +1,156 @@
+//! Attributes benchmark wall-clock time to DB read vs write cost via linear regression.
+//!
+//! Collects `(reads, writes, elapsed_ns)` samples across the varying `node_count`/
+//! `edge_count` inputs already iterated in `bench_node_insertion`/`bench_edge_insertion`
+//! and fits `elapsed ≈ a*reads + b*writes + c` by solving the normal equations
+//! `β = (XᵀX)⁻¹ Xᵀy` for `X` columns `[reads, writes, 1]`. Falls back to a
+//! single-variable fit when too few distinct samples make `XᵀX` singular.
+
+/// One `(reads, writes, elapsed_ns)` observation from a single benchmark iteration.
+#[derive(Debug, Clone, Copy)]
+pub struct IoSample {
+    pub reads: f64,
+    pub writes: f64,
+    pub elapsed_ns: f64,
+}
+
+/// Fitted coefficients: `elapsed_ns ≈ per_read_ns * reads + per_write_ns * writes + intercept_ns`.
+#[derive(Debug, Clone, Copy)]
+pub struct CostModel {
+    pub per_read_ns: f64,
+    pub per_write_ns: f64,
+    pub intercept_ns: f64,
+    pub r_squared: f64,
+}
+
+impl CostModel {
+    pub fn report(&self) -> String {
+        format!(
+            "elapsed_ns ~= {:.2}*reads + {:.2}*writes + {:.2} (R^2 = {:.4})",
+            self.per_read_ns, self.per_write_ns, self.intercept_ns, self.r_squared
+        )
+    }
+}
+
+/// Fits `elapsed_ns ≈ per_read_ns*reads + per_write_ns*writes + intercept_ns` via the
+/// normal equations, falling back to a single-variable fit when `XᵀX` is singular
+/// (e.g. fewer than 3 samples, or reads/writes move in lockstep across samples).
+pub fn fit_cost_model(samples: &[IoSample]) -> CostModel {
+    if samples.len() < 3 {
+        return single_variable_fit(samples);
+    }
+
+    let mut xtx = [[0.0f64; 3]; 3];
+    let mut xty = [0.0f64; 3];
+    for s in samples {
+        let x = [s.reads, s.writes, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                xtx[i][j] += x[i] * x[j];
+            }
+            xty[i] += x[i] * s.elapsed_ns;
+        }
+    }
+
+    match solve_3x3(&xtx, &xty) {
+        Some(beta) => {
+            let r2 = r_squared(samples, |s| beta[0] * s.reads + beta[1] * s.writes + beta[2]);
+            CostModel {
+                per_read_ns: beta[0],
+                per_write_ns: beta[1],
+                intercept_ns: beta[2],
+                r_squared: r2,
+            }
+        }
+        None => single_variable_fit(samples),
+    }
+}
+
+/// Single-variable fallback for when reads and writes don't vary independently
+/// (or there aren't enough samples to separate their contributions): regresses
+/// against whichever of reads/writes actually varies across the sample set.
+fn single_variable_fit(samples: &[IoSample]) -> CostModel {
+    let use_reads = varies(samples, |s| s.reads) || !varies(samples, |s| s.writes);
+
+    let (sum_x, sum_y, sum_xx, sum_xy, n) = samples.iter().fold(
+        (0.0, 0.0, 0.0, 0.0, 0.0),
+        |(sx, sy, sxx, sxy, n), s| {
+            let x = if use_reads { s.reads } else { s.writes };
+            (sx + x, sy + s.elapsed_ns, sxx + x * x, sxy + x * s.elapsed_ns, n + 1.0)
+        },
+    );
+
+    if n == 0.0 {
+        return CostModel {
+            per_read_ns: 0.0,
+            per_write_ns: 0.0,
+            intercept_ns: 0.0,
+            r_squared: 0.0,
+        };
+    }
+
+    let denom = n * sum_xx - sum_x * sum_x;
+    let (slope, intercept) = if denom.abs() > f64::EPSILON {
+        let slope = (n * sum_xy - sum_x * sum_y) / denom;
+        (slope, (sum_y - slope * sum_x) / n)
+    } else {
+        (0.0, sum_y / n)
+    };
+
+    let r2 = r_squared(samples, |s| {
+        let x = if use_reads { s.reads } else { s.writes };
+        slope * x + intercept
+    });
+
+    if use_reads {
+        CostModel { per_read_ns: slope, per_write_ns: 0.0, intercept_ns: intercept, r_squared: r2 }
+    } else {
+        CostModel { per_read_ns: 0.0, per_write_ns: slope, intercept_ns: intercept, r_squared: r2 }
+    }
+}
+
+fn varies<F: Fn(&IoSample) -> f64>(samples: &[IoSample], f: F) -> bool {
+    match samples.first() {
+        Some(first) => samples.iter().any(|s| (f(s) - f(first)).abs() > f64::EPSILON),
+        None => false,
+    }
+}
+
+/// Solves the 3x3 linear system `a*beta = b` via Cramer's rule, returning `None`
+/// if `a` is singular (determinant ~0) — too few distinct `(reads, writes)`
+/// combinations were sampled to separate their contributions.
+fn solve_3x3(a: &[[f64; 3]; 3], b: &[f64; 3]) -> Option<[f64; 3]> {
+    let det = det3(a);
+    if det.abs() < 1e-9 {
+        return None;
+    }
+
+    let mut beta = [0.0; 3];
+    for col in 0..3 {
+        let mut replaced = *a;
+        for row in 0..3 {
+            replaced[row][col] = b[row];
+        }
+        beta[col] = det3(&replaced) / det;
+    }
+    Some(beta)
+}
+
+fn det3(m: &[[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn r_squared<F: Fn(&IoSample) -> f64>(samples: &[IoSample], predict: F) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let mean = samples.iter().map(|s| s.elapsed_ns).sum::<f64>() / samples.len() as f64;
+    let ss_tot: f64 = samples.iter().map(|s| (s.elapsed_ns - mean).powi(2)).sum();
+    if ss_tot.abs() < f64::EPSILON {
+        return 1.0;
+    }
+    let ss_res: f64 = samples.iter().map(|s| (s.elapsed_ns - predict(s)).powi(2)).sum();
+    1.0 - ss_res / ss_tot
+}
@@ -7,13 +7,18 @@ use kg_mcp_server::graph::{KGNode, KGEdge, Episode, EpisodeSource, GraphStorage}
 use kg_mcp_server::embeddings::{LocalEmbeddingEngine, ModelManager, BatchProcessor, OnnxEmbeddingEngine};
 use kg_mcp_server::search::{HybridSearchEngine, VectorSearchEngine, TextSearchEngine};
 
+mod bench_analysis;
+use bench_analysis::{fit_cost_model, IoSample};
+
 /// Benchmark large-scale node insertion performance
 fn bench_node_insertion(c: &mut Criterion) {
     let rt = Runtime::new().unwrap();
     
     let mut group = c.benchmark_group("node_insertion");
     group.significance_level(0.1).sample_size(10);
-    
+
+    let io_samples = std::sync::Mutex::new(Vec::<IoSample>::new());
+
     for node_count in [100, 1_000, 10_000, 100_000].iter() {
         group.bench_with_input(
             BenchmarkId::new("nodes", node_count),
@@ -23,9 +28,9 @@ fn bench_node_insertion(c: &mut Criterion) {
                     let storage = GraphStorage::new(&format!("bench_nodes_{}.db", node_count))
                         .await
                         .unwrap();
-                    
+
                     let start = Instant::now();
-                    
+
                     for i in 0..node_count {
                         let node = KGNode::new(
                             format!("Benchmark Node {}", i),
@@ -33,18 +38,30 @@ fn bench_node_insertion(c: &mut Criterion) {
                             format!("Performance test node number {}", i),
                             Some("benchmark_group".to_string()),
                         );
-                        
+
                         black_box(storage.store_node(&node).await.unwrap());
                     }
-                    
+
                     let duration = start.elapsed();
-                    println!("Inserted {} nodes in {:?} ({:.2} nodes/sec)", 
+                    println!("Inserted {} nodes in {:?} ({:.2} nodes/sec)",
                             node_count, duration, node_count as f64 / duration.as_secs_f64());
+
+                    let io = storage.drain_io_stats();
+                    let writes: u64 = io.values().map(|s| s.writes).sum();
+                    let reads: u64 = io.values().map(|s| s.reads).sum();
+                    io_samples.lock().unwrap().push(IoSample {
+                        reads: reads as f64,
+                        writes: writes as f64,
+                        elapsed_ns: duration.as_nanos() as f64,
+                    });
                 });
             },
         );
     }
-    
+
+    let model = fit_cost_model(&io_samples.into_inner().unwrap());
+    println!("node_insertion cost model: {}", model.report());
+
     group.finish();
 }
 
@@ -54,7 +71,9 @@ fn bench_edge_insertion(c: &mut Criterion) {
     
     let mut group = c.benchmark_group("edge_insertion");
     group.significance_level(0.1).sample_size(10);
-    
+
+    let io_samples = std::sync::Mutex::new(Vec::<IoSample>::new());
+
     for edge_count in [100, 1_000, 10_000, 100_000].iter() {
         group.bench_with_input(
             BenchmarkId::new("edges", edge_count),
@@ -64,7 +83,7 @@ fn bench_edge_insertion(c: &mut Criterion) {
                     let storage = GraphStorage::new(&format!("bench_edges_{}.db", edge_count))
                         .await
                         .unwrap();
-                    
+
                     // Pre-create nodes for edges
                     let mut node_uuids = Vec::new();
                     for i in 0..std::cmp::min(edge_count, 1000) {
@@ -77,13 +96,14 @@ fn bench_edge_insertion(c: &mut Criterion) {
                         storage.store_node(&node).await.unwrap();
                         node_uuids.push(node.uuid);
                     }
-                    
+                    storage.drain_io_stats(); // discard node setup cost, keep only edge-insert cost below
+
                     let start = Instant::now();
-                    
+
                     for i in 0..edge_count {
                         let source_idx = i % node_uuids.len();
                         let target_idx = (i + 1) % node_uuids.len();
-                        
+
                         let edge = KGEdge::new(
                             node_uuids[source_idx],
                             node_uuids[target_idx],
@@ -92,18 +112,30 @@ fn bench_edge_insertion(c: &mut Criterion) {
                             0.8,
                             Some("edge_group".to_string()),
                         );
-                        
+
                         black_box(storage.store_edge(&edge).await.unwrap());
                     }
-                    
+
                     let duration = start.elapsed();
-                    println!("Inserted {} edges in {:?} ({:.2} edges/sec)", 
+                    println!("Inserted {} edges in {:?} ({:.2} edges/sec)",
                             edge_count, duration, edge_count as f64 / duration.as_secs_f64());
+
+                    let io = storage.drain_io_stats();
+                    let writes: u64 = io.values().map(|s| s.writes).sum();
+                    let reads: u64 = io.values().map(|s| s.reads).sum();
+                    io_samples.lock().unwrap().push(IoSample {
+                        reads: reads as f64,
+                        writes: writes as f64,
+                        elapsed_ns: duration.as_nanos() as f64,
+                    });
                 });
             },
         );
     }
-    
+
+    let model = fit_cost_model(&io_samples.into_inner().unwrap());
+    println!("edge_insertion cost model: {}", model.report());
+
     group.finish();
 }
 
@@ -286,8 +318,9 @@ fn bench_memory_usage(c: &mut Criterion) {
         b.to_async(&rt).iter(|| async {
             let storage = GraphStorage::new("bench_memory.db").await.unwrap();
             
-            let initial_memory = get_memory_usage();
-            
+            let initial_memory = kg_mcp_server::metrics::current_rss();
+            let initial_allocated = kg_mcp_server::metrics::peak_allocated();
+
             // Insert a significant amount of data
             for i in 0..10_000 {
                 let node = KGNode::new(
@@ -298,13 +331,14 @@ fn bench_memory_usage(c: &mut Criterion) {
                 );
                 storage.store_node(&node).await.unwrap();
             }
-            
-            let final_memory = get_memory_usage();
-            let memory_growth = final_memory - initial_memory;
-            
-            println!("Memory growth for 10k nodes: {} bytes ({:.2} MB)", 
-                    memory_growth, memory_growth as f64 / 1_048_576.0);
-            
+
+            let final_memory = kg_mcp_server::metrics::current_rss();
+            let memory_growth = final_memory.saturating_sub(initial_memory);
+            let allocated_growth = kg_mcp_server::metrics::peak_allocated().saturating_sub(initial_allocated);
+
+            println!("RSS growth for 10k nodes: {} bytes ({:.2} MB), peak-allocated growth: {} bytes",
+                    memory_growth, memory_growth as f64 / 1_048_576.0, allocated_growth);
+
             black_box(memory_growth);
         });
     });
@@ -420,13 +454,6 @@ fn bench_mcp_operations(c: &mut Criterion) {
     group.finish();
 }
 
-/// Helper function to estimate memory usage (simplified)
-fn get_memory_usage() -> u64 {
-    // In a real implementation, this would use system APIs to get actual memory usage
-    // For benchmarking purposes, we'll use a simplified approach
-    std::process::id() as u64 * 1024 // Placeholder
-}
-
 criterion_group!(
     benches,
     bench_node_insertion,
@@ -0,0 +1,275 @@
+//! Reproducible search benchmark/workload harness.
+//!
+//! Triggered from `main()` by the `BENCH_WORKLOAD` environment variable
+//! (parallel to `MIGRATION_SOURCE`), this loads a JSON workload describing
+//! a fixed set of nodes to ingest and a fixed set of queries with their
+//! expected top-k relevant node UUIDs, runs those queries against the
+//! already-wired-up `HybridSearchEngine`, and reports latency percentiles
+//! plus retrieval-quality metrics (recall@k, nDCG@k) as machine-readable
+//! JSON. Workload node UUIDs are caller-specified (rather than generated at
+//! ingest time) so the same workload file produces comparable `expected_uuids`
+//! across runs and across commits/configs.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::embeddings::LocalEmbeddingEngine;
+use crate::graph::KGNode;
+use crate::graph::storage::GraphStorage;
+use crate::memory::{MemoryConfig, MemoryOptimizer};
+use crate::search::HybridSearchEngine;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchIngestNode {
+    pub uuid: Uuid,
+    pub name: String,
+    pub node_type: String,
+    pub summary: String,
+    #[serde(default)]
+    pub group_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchQuery {
+    pub query: String,
+    pub top_k: usize,
+    #[serde(default)]
+    pub expected_uuids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkloadSpec {
+    pub ingest: Vec<BenchIngestNode>,
+    pub queries: Vec<BenchQuery>,
+    /// Texts to encode for the embedding-throughput measurement. Left empty
+    /// to skip that section of the report (e.g. a workload focused purely
+    /// on search latency/quality).
+    #[serde(default)]
+    pub embedding_corpus: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub p99_us: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerQueryResult {
+    pub query: String,
+    pub latency_us: u64,
+    pub recall_at_k: f64,
+    pub ndcg_at_k: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingBenchResult {
+    pub model_name: String,
+    pub docs_encoded: usize,
+    pub elapsed_ms: u64,
+    pub docs_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcBenchResult {
+    pub memory_freed_bytes: usize,
+    pub collection_time_us: u64,
+    pub objects_collected: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub queries_run: usize,
+    pub latency: LatencyPercentiles,
+    pub mean_recall_at_k: f64,
+    pub mean_ndcg_at_k: f64,
+    pub per_query: Vec<PerQueryResult>,
+    /// `None` when the workload's `embedding_corpus` was empty.
+    pub embedding: Option<EmbeddingBenchResult>,
+    pub gc: GcBenchResult,
+}
+
+/// Loads `workload_path`, ingests its nodes, runs its queries against
+/// `search_engine`, encodes `embedding_corpus` through `embedding_engine`
+/// to measure throughput, runs a `MemoryOptimizer::force_gc` pass, and
+/// returns the aggregated report.
+pub async fn run_bench(
+    workload_path: &Path,
+    storage: &GraphStorage,
+    search_engine: &HybridSearchEngine,
+    embedding_engine: &LocalEmbeddingEngine,
+) -> Result<BenchReport> {
+    let workload_str = std::fs::read_to_string(workload_path)
+        .with_context(|| format!("Failed to read bench workload file: {}", workload_path.display()))?;
+    let workload: WorkloadSpec = serde_json::from_str(&workload_str)
+        .with_context(|| format!("Failed to parse bench workload file: {}", workload_path.display()))?;
+
+    for ingest_node in &workload.ingest {
+        let node = KGNode {
+            uuid: ingest_node.uuid,
+            name: ingest_node.name.clone(),
+            node_type: ingest_node.node_type.clone(),
+            summary: ingest_node.summary.clone(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            group_id: ingest_node.group_id.clone(),
+            metadata: HashMap::new(),
+        };
+        storage.insert_node(&node)?;
+    }
+
+    let mut per_query = Vec::with_capacity(workload.queries.len());
+    let mut latencies_us = Vec::with_capacity(workload.queries.len());
+
+    for q in &workload.queries {
+        let start = std::time::Instant::now();
+        let result = search_engine.search(&q.query, q.top_k).await?;
+        let latency_us = start.elapsed().as_micros() as u64;
+        latencies_us.push(latency_us);
+
+        let retrieved: Vec<Uuid> = result.nodes.iter().take(q.top_k).map(|n| n.uuid).collect();
+        let expected: HashSet<Uuid> = q.expected_uuids.iter().copied().collect();
+
+        let recall_at_k = if expected.is_empty() {
+            1.0
+        } else {
+            let hits = retrieved.iter().filter(|id| expected.contains(id)).count();
+            hits as f64 / expected.len() as f64
+        };
+
+        let dcg: f64 = retrieved.iter().enumerate()
+            .map(|(rank, id)| if expected.contains(id) { 1.0 / (rank as f64 + 2.0).log2() } else { 0.0 })
+            .sum();
+        let ideal_hits = expected.len().min(q.top_k);
+        let idcg: f64 = (0..ideal_hits).map(|rank| 1.0 / (rank as f64 + 2.0).log2()).sum();
+        let ndcg_at_k = if idcg > 0.0 { dcg / idcg } else { 0.0 };
+
+        per_query.push(PerQueryResult {
+            query: q.query.clone(),
+            latency_us,
+            recall_at_k,
+            ndcg_at_k,
+        });
+    }
+
+    latencies_us.sort_unstable();
+    let latency = LatencyPercentiles {
+        p50_us: percentile(&latencies_us, 0.50),
+        p95_us: percentile(&latencies_us, 0.95),
+        p99_us: percentile(&latencies_us, 0.99),
+    };
+
+    let queries_run = per_query.len();
+    let mean_recall_at_k = if queries_run == 0 {
+        0.0
+    } else {
+        per_query.iter().map(|r| r.recall_at_k).sum::<f64>() / queries_run as f64
+    };
+    let mean_ndcg_at_k = if queries_run == 0 {
+        0.0
+    } else {
+        per_query.iter().map(|r| r.ndcg_at_k).sum::<f64>() / queries_run as f64
+    };
+
+    let embedding = if workload.embedding_corpus.is_empty() {
+        None
+    } else {
+        let start = std::time::Instant::now();
+        embedding_engine.encode_texts(&workload.embedding_corpus).await?;
+        let elapsed = start.elapsed();
+        let docs_encoded = workload.embedding_corpus.len();
+        Some(EmbeddingBenchResult {
+            model_name: embedding_engine.current_model().await.unwrap_or_else(|| "unknown".to_string()),
+            docs_encoded,
+            elapsed_ms: elapsed.as_millis() as u64,
+            docs_per_sec: docs_encoded as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        })
+    };
+
+    // A fresh, default-configured optimizer rather than the server's live
+    // one, so GC timing is reproducible across runs instead of depending on
+    // whatever state the rest of the process happened to accumulate.
+    let memory_optimizer = MemoryOptimizer::new(MemoryConfig::default());
+    memory_optimizer.initialize().await?;
+    let gc_result = memory_optimizer.force_gc().await?;
+    let gc = GcBenchResult {
+        memory_freed_bytes: gc_result.memory_freed,
+        collection_time_us: gc_result.collection_time.as_micros() as u64,
+        objects_collected: gc_result.objects_collected,
+    };
+
+    Ok(BenchReport {
+        queries_run,
+        latency,
+        mean_recall_at_k,
+        mean_ndcg_at_k,
+        per_query,
+        embedding,
+        gc,
+    })
+}
+
+fn percentile(sorted_us: &[u64], p: f64) -> u64 {
+    if sorted_us.is_empty() {
+        return 0;
+    }
+    let idx = (((sorted_us.len() - 1) as f64) * p).round() as usize;
+    sorted_us[idx.min(sorted_us.len() - 1)]
+}
+
+/// Loads the `BenchReport` JSON previously written at `baseline_path` and
+/// compares it against `report`, returning one human-readable description
+/// per metric that got worse by more than `threshold_pct` percent: p99
+/// search latency, and (when both reports ran an embedding section)
+/// embedding throughput. An empty result means nothing regressed beyond the
+/// threshold, which `main()` uses to decide the process exit code when
+/// gating a CI run.
+pub fn check_regressions(
+    report: &BenchReport,
+    baseline_path: &Path,
+    threshold_pct: f64,
+) -> Result<Vec<String>> {
+    let baseline_str = std::fs::read_to_string(baseline_path)
+        .with_context(|| format!("Failed to read bench baseline file: {}", baseline_path.display()))?;
+    let baseline: BenchReport = serde_json::from_str(&baseline_str)
+        .with_context(|| format!("Failed to parse bench baseline file: {}", baseline_path.display()))?;
+
+    let mut regressions = Vec::new();
+
+    let p99_increase_pct = percent_increase(baseline.latency.p99_us as f64, report.latency.p99_us as f64);
+    if p99_increase_pct > threshold_pct {
+        regressions.push(format!(
+            "p99 search latency regressed {:.1}% (baseline {}us -> {}us, threshold {:.1}%)",
+            p99_increase_pct, baseline.latency.p99_us, report.latency.p99_us, threshold_pct
+        ));
+    }
+
+    if let (Some(baseline_embedding), Some(embedding)) = (&baseline.embedding, &report.embedding) {
+        // Throughput regressing means it went *down*, so compare the decrease
+        // the other way around from the latency check above.
+        let throughput_decrease_pct = percent_increase(embedding.docs_per_sec, baseline_embedding.docs_per_sec);
+        if throughput_decrease_pct > threshold_pct {
+            regressions.push(format!(
+                "embedding throughput regressed {:.1}% (baseline {:.1} docs/sec -> {:.1} docs/sec, threshold {:.1}%)",
+                throughput_decrease_pct, baseline_embedding.docs_per_sec, embedding.docs_per_sec, threshold_pct
+            ));
+        }
+    }
+
+    Ok(regressions)
+}
+
+/// Percent by which `new` exceeds `old`; zero or negative when `new` is no
+/// worse. `old == 0.0` is treated as "no regression possible" rather than
+/// dividing by zero.
+fn percent_increase(old: f64, new: f64) -> f64 {
+    if old <= 0.0 {
+        return 0.0;
+    }
+    ((new - old) / old) * 100.0
+}
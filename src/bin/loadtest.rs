@@ -0,0 +1,254 @@
+#!/usr/bin/env cargo
+//! # KG MCP Server Load Test Utility
+//!
+//! Drives `GraphStorage` with a declarative workload mix (e.g. 70% `add_memory`,
+//! 20% `search_memory_nodes`, 10% `find_similar_concepts`) at a target aggregate
+//! throughput, using a configurable number of concurrent worker tasks, and
+//! reports per-operation latency percentiles (p50/p95/p99) and achieved
+//! throughput. Generalizes the one-off `bench_concurrent_operations` criterion
+//! benchmark into a reusable soak/stress tool for sustained mixed traffic.
+
+use anyhow::{Context, Result};
+use clap::{Arg, Command};
+use kg_mcp_server::config::DatabaseConfig;
+use kg_mcp_server::graph::{GraphStorage, KGNode};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Relative weight of each operation kind in the generated workload.
+#[derive(Debug, Clone, Copy)]
+struct WorkloadMix {
+    add_memory: u32,
+    search_memory_nodes: u32,
+    find_similar_concepts: u32,
+}
+
+impl WorkloadMix {
+    fn total(&self) -> u32 {
+        self.add_memory + self.search_memory_nodes + self.find_similar_concepts
+    }
+
+    /// Parses `"add_memory=70,search_memory_nodes=20,find_similar_concepts=10"`.
+    fn parse(spec: &str) -> Result<Self> {
+        let mut mix = WorkloadMix { add_memory: 0, search_memory_nodes: 0, find_similar_concepts: 0 };
+        for part in spec.split(',') {
+            let (name, weight) = part
+                .split_once('=')
+                .with_context(|| format!("invalid mix entry '{}', expected name=weight", part))?;
+            let weight: u32 = weight
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid weight in mix entry '{}'", part))?;
+            match name.trim() {
+                "add_memory" => mix.add_memory = weight,
+                "search_memory_nodes" => mix.search_memory_nodes = weight,
+                "find_similar_concepts" => mix.find_similar_concepts = weight,
+                other => return Err(anyhow::anyhow!("unknown workload op '{}'", other)),
+            }
+        }
+        if mix.total() == 0 {
+            return Err(anyhow::anyhow!("workload mix must have at least one non-zero weight"));
+        }
+        Ok(mix)
+    }
+
+    /// Picks an operation name using `roll` (expected to be uniform in `0..total()`).
+    fn pick(&self, roll: u32) -> &'static str {
+        if roll < self.add_memory {
+            "add_memory"
+        } else if roll < self.add_memory + self.search_memory_nodes {
+            "search_memory_nodes"
+        } else {
+            "find_similar_concepts"
+        }
+    }
+}
+
+/// Per-operation latency samples (microseconds), collected by each worker task
+/// and merged at the end of the run.
+#[derive(Debug, Default)]
+struct OpStats {
+    latencies_us: Vec<u64>,
+}
+
+fn percentile(sorted_us: &[u64], p: f64) -> u64 {
+    if sorted_us.is_empty() {
+        return 0;
+    }
+    let idx = ((sorted_us.len() as f64 - 1.0) * p).round() as usize;
+    sorted_us[idx.min(sorted_us.len() - 1)]
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let matches = Command::new("kg-loadtest")
+        .version("0.1.0")
+        .author("KG MCP Server Team")
+        .about("Workload-driven soak/stress tool for GraphStorage")
+        .arg(
+            Arg::new("db")
+                .long("db")
+                .value_name("PATH")
+                .help("Database file to drive the workload against")
+                .default_value("loadtest.db"),
+        )
+        .arg(
+            Arg::new("duration-secs")
+                .long("duration-secs")
+                .value_name("SECONDS")
+                .help("How long to run the workload")
+                .default_value("30"),
+        )
+        .arg(
+            Arg::new("concurrency")
+                .long("concurrency")
+                .value_name("N")
+                .help("Number of concurrent worker tasks (\"connections\")")
+                .default_value("8"),
+        )
+        .arg(
+            Arg::new("target-ops")
+                .long("target-ops")
+                .value_name("OPS_PER_SEC")
+                .help("Target aggregate operations/sec across all workers")
+                .default_value("100"),
+        )
+        .arg(
+            Arg::new("mix")
+                .long("mix")
+                .value_name("SPEC")
+                .help("Workload mix, e.g. \"add_memory=70,search_memory_nodes=20,find_similar_concepts=10\"")
+                .default_value("add_memory=70,search_memory_nodes=20,find_similar_concepts=10"),
+        )
+        .get_matches();
+
+    let db_path = PathBuf::from(matches.get_one::<String>("db").unwrap());
+    let duration_secs: u64 = matches
+        .get_one::<String>("duration-secs")
+        .unwrap()
+        .parse()
+        .context("invalid --duration-secs")?;
+    let concurrency: usize = matches
+        .get_one::<String>("concurrency")
+        .unwrap()
+        .parse()
+        .context("invalid --concurrency")?;
+    let target_ops: u64 = matches
+        .get_one::<String>("target-ops")
+        .unwrap()
+        .parse()
+        .context("invalid --target-ops")?;
+    let mix = WorkloadMix::parse(matches.get_one::<String>("mix").unwrap())?;
+
+    let storage = Arc::new(GraphStorage::new(&db_path, &DatabaseConfig::default())?);
+    let group_id = "loadtest".to_string();
+
+    // Seed a small pool of nodes so search/similarity ops have something to find.
+    for i in 0..50 {
+        let node = KGNode::new(
+            format!("Seed Node {}", i),
+            "LoadTestSeed".to_string(),
+            format!("Seed node {} for load test search operations", i),
+            Some(group_id.clone()),
+        );
+        storage.insert_node(&node)?;
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(duration_secs);
+    let per_worker_target = (target_ops as f64 / concurrency as f64).max(1.0);
+    let worker_interval = Duration::from_secs_f64(1.0 / per_worker_target);
+
+    let completed = Arc::new(AtomicU64::new(0));
+    let stats: Arc<Mutex<HashMap<&'static str, OpStats>>> = Arc::new(Mutex::new(HashMap::new()));
+
+    let mut handles = Vec::with_capacity(concurrency);
+    for worker_id in 0..concurrency {
+        let storage = Arc::clone(&storage);
+        let completed = Arc::clone(&completed);
+        let stats = Arc::clone(&stats);
+        let mix = mix;
+        let group_id = group_id.clone();
+
+        handles.push(tokio::spawn(async move {
+            // xorshift64 — good enough spread for picking an op, and avoids
+            // pulling in an RNG crate just for a load-test tool.
+            let mut rng_state: u64 = 0x9E3779B97F4A7C15 ^ (worker_id as u64 + 1);
+            let mut ticker = tokio::time::interval(worker_interval);
+
+            while Instant::now() < deadline {
+                ticker.tick().await;
+
+                rng_state ^= rng_state << 13;
+                rng_state ^= rng_state >> 7;
+                rng_state ^= rng_state << 17;
+                let roll = (rng_state % mix.total() as u64) as u32;
+                let op = mix.pick(roll);
+
+                let start = Instant::now();
+                let result = match op {
+                    "add_memory" => {
+                        let node = KGNode::new(
+                            format!("Worker {} Node {}", worker_id, rng_state),
+                            "LoadTestNode".to_string(),
+                            "Generated by kg-loadtest".to_string(),
+                            Some(group_id.clone()),
+                        );
+                        storage.insert_node(&node).map(|_| ())
+                    }
+                    "search_memory_nodes" => storage
+                        .search_nodes_by_text("Seed", Some(&group_id), 10)
+                        .map(|_| ()),
+                    _ => storage
+                        .search_nodes_by_text("Node", Some(&group_id), 10)
+                        .map(|_| ()),
+                };
+                let elapsed_us = start.elapsed().as_micros() as u64;
+
+                if let Err(e) = result {
+                    eprintln!("worker {} op {} failed: {}", worker_id, op, e);
+                    continue;
+                }
+
+                completed.fetch_add(1, Ordering::Relaxed);
+                stats
+                    .lock()
+                    .await
+                    .entry(op)
+                    .or_insert_with(OpStats::default)
+                    .latencies_us
+                    .push(elapsed_us);
+            }
+        }));
+    }
+
+    for handle in handles {
+        handle.await.context("worker task panicked")?;
+    }
+
+    let total_completed = completed.load(Ordering::Relaxed);
+    let achieved_ops = total_completed as f64 / duration_secs as f64;
+    println!(
+        "Completed {} ops in {}s ({:.2} ops/sec, target {})",
+        total_completed, duration_secs, achieved_ops, target_ops
+    );
+
+    let stats = stats.lock().await;
+    for (op, op_stats) in stats.iter() {
+        let mut sorted = op_stats.latencies_us.clone();
+        sorted.sort_unstable();
+        println!(
+            "  {:<22} n={:<8} p50={:>6}us p95={:>6}us p99={:>6}us",
+            op,
+            sorted.len(),
+            percentile(&sorted, 0.50),
+            percentile(&sorted, 0.95),
+            percentile(&sorted, 0.99),
+        );
+    }
+
+    Ok(())
+}
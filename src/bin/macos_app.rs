@@ -1,8 +1,9 @@
 use anyhow::Result;
+use arc_swap::ArcSwap;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use tracing::{error, info, Level};
-use tracing_subscriber;
+use tracing::{error, info, warn, Level};
+use tracing_subscriber::{self, prelude::*};
 use tray_item::{IconSource, TrayItem};
 use std::process::Command;
 
@@ -10,25 +11,45 @@ use kg_mcp_server::{
     ServerConfig, GraphStorage, LocalEmbeddingEngine, HybridSearchEngine, McpServer,
     search::{TextSearchEngine, VectorSearchEngine},
     memory::{MemoryConfig, MemoryOptimizer},
+    metrics::{RecentEventsBuffer, RecentEventsLayer},
 };
 
+/// Port `run_server` binds the HTTP/SSE transport to; shared with the tray's
+/// "Open Admin Dashboard" item so it always points at the live listener.
+const MCP_HTTP_PORT: &str = "8360";
+
 enum ServerState {
     Starting,
     Running,
+    Restarting,
     Error(String),
 }
 
 fn main() -> Result<()> {
-    tracing_subscriber::fmt().with_max_level(Level::INFO).with_target(false).init();
+    // The recent-events ring buffer is created before the subscriber so the
+    // `RecentEventsLayer` can be wired in alongside `fmt`'s layer; it's then
+    // handed to `run_server` so the MCP server's `get_recent_events` tool
+    // reads from this same instance instead of creating its own.
+    let recent_events = Arc::new(RecentEventsBuffer::default());
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_target(false).with_filter(tracing_subscriber::filter::LevelFilter::from_level(Level::INFO)))
+        .with(RecentEventsLayer::new(recent_events.clone()))
+        .init();
     info!("🚀 Initializing Knowledge Graph MCP Menu Bar App...");
 
     let server_status = Arc::new(Mutex::new(ServerState::Starting));
     let server_status_clone = server_status.clone();
 
+    // Lets the tray's "Restart Server" item request a hot restart of the
+    // background server thread without tearing down the process or the tray
+    // icon; see `run_server`.
+    let (restart_tx, restart_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+
     // --- Run the server in a background thread ---
+    let recent_events_for_server = recent_events.clone();
     thread::spawn(move || {
         info!("Server thread spawned.");
-        if let Err(e) = run_server(server_status_clone) {
+        if let Err(e) = run_server(server_status_clone, recent_events_for_server, restart_rx) {
             error!("Server thread failed: {}", e);
         }
     });
@@ -48,15 +69,17 @@ fn main() -> Result<()> {
     let status_label = match &*status_arc.lock().unwrap() {
         ServerState::Starting => "Status: Starting...",
         ServerState::Running => "Status: Running",
+        ServerState::Restarting => "Status: Restarting...",
         ServerState::Error(msg) => &format!("Status: Error: {}", msg),
     };
     inner.add_label(status_label)?;
-    // Restart action
-    let server_status_for_restart = status_arc.clone();
+    // Restart action: signals `run_server`'s restart channel instead of
+    // exiting the process, so the hot-restart loop there can drain the
+    // current generation and spawn a fresh one in place.
+    let restart_tx_for_menu = restart_tx.clone();
     inner.add_menu_item("Restart Server", move || {
         info!("Restart requested by user.");
-        // For now, simply exit; restarting requires more complex thread management
-        std::process::exit(0);
+        let _ = restart_tx_for_menu.send(());
     })?;
     // View logs action
     inner.add_menu_item("View Logs", || {
@@ -65,6 +88,36 @@ fn main() -> Result<()> {
             error!("Failed to open log file: {}", e);
         }
     })?;
+    // Opens the `/health` admin endpoint (see `mcp::server::health_check`)
+    // in the default browser, so operators have somewhere to look besides
+    // the log file.
+    inner.add_menu_item("Open Admin Dashboard", || {
+        let url = format!("http://127.0.0.1:{}/health", MCP_HTTP_PORT);
+        if let Err(e) = Command::new("open").arg(url).status() {
+            error!("Failed to open admin dashboard: {}", e);
+        }
+    })?;
+    // Recent Events: `tray_item` has no native submenu widget, so this
+    // approximates the "Recent Events" submenu the same way "View Logs"
+    // already approximates a log viewer — dump the current ring-buffer
+    // snapshot to a temp file and hand it to `open`, reusing the same
+    // mechanism rather than inventing a second one.
+    let recent_events_for_menu = recent_events.clone();
+    inner.add_menu_item("Recent Events", move || {
+        match serde_json::to_string_pretty(&*recent_events_for_menu.snapshot()) {
+            Ok(json) => {
+                let path = std::env::temp_dir().join("kg-mcp-server-recent-events.json");
+                if let Err(e) = std::fs::write(&path, json) {
+                    error!("Failed to write recent events snapshot: {}", e);
+                    return;
+                }
+                if let Err(e) = Command::new("open").arg(path).status() {
+                    error!("Failed to open recent events snapshot: {}", e);
+                }
+            }
+            Err(e) => error!("Failed to serialize recent events snapshot: {}", e),
+        }
+    })?;
     // Separator
     inner.add_menu_item("---", || {})?;
     // Quit action
@@ -75,62 +128,112 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_server(server_status: Arc<Mutex<ServerState>>) -> Result<()> {
+/// Builds a fresh `McpServer` generation from whatever `ServerConfig::load`
+/// currently returns, so each restart picks up on-disk config changes
+/// without relaunching the app. Kicks off the same background embedding /
+/// memory-optimizer warmup the original one-shot `run_server` used to do
+/// inline, but un-awaited, so a restart doesn't block on model reloading.
+async fn build_server(recent_events: &Arc<RecentEventsBuffer>) -> Result<McpServer> {
+    let config = ServerConfig::load(None)?;
+    let storage = Arc::new(GraphStorage::new(&config.database_path(), &config.database)?);
+    let embedding_engine = Arc::new(LocalEmbeddingEngine::new(config.clone())?);
+    let text_engine = TextSearchEngine::new(storage.clone());
+    let vector_engine = VectorSearchEngine::new();
+    let search_engine = Arc::new(HybridSearchEngine::new(text_engine, vector_engine));
+    let memory_config = MemoryConfig::default();
+    let memory_optimizer = Arc::new(MemoryOptimizer::new(memory_config));
+
+    let server = McpServer::new(
+        config.clone(),
+        storage,
+        embedding_engine.clone(),
+        search_engine,
+        memory_optimizer.clone(),
+    ).with_recent_events_buffer(recent_events.clone());
+
+    tokio::spawn(async move {
+        info!("🔄 Initializing embedding engine in the background...");
+        if let Err(e) = embedding_engine.initialize(&config.embeddings.model_name).await {
+            error!("Failed to initialize embedding engine: {}", e);
+        } else {
+            info!("✅ Embedding engine initialized successfully.");
+        }
+    });
+    tokio::spawn(async move {
+        info!("🔄 Initializing memory optimizer in the background...");
+        if let Err(e) = memory_optimizer.initialize().await {
+            error!("Failed to initialize memory optimizer: {}", e);
+        } else {
+            info!("✅ Memory optimizer initialized successfully.");
+        }
+    });
+
+    Ok(server)
+}
+
+/// Drives the MCP server on its own current-thread runtime, looping forever
+/// across "generations" so the tray's "Restart Server" action can hot-swap a
+/// freshly-built server (picking up any on-disk config changes) in place of
+/// the running one instead of calling `std::process::exit`.
+///
+/// Each generation is published into `current_server` via `ArcSwap` as soon
+/// as it's built, so a generation is only ever visible once fully
+/// initialized. A restart request drains the live generation through
+/// `McpServer::run_with_shutdown`'s graceful-shutdown path before the next
+/// one is built and spawned.
+fn run_server(
+    server_status: Arc<Mutex<ServerState>>,
+    recent_events: Arc<RecentEventsBuffer>,
+    mut restart_rx: tokio::sync::mpsc::UnboundedReceiver<()>,
+) -> Result<()> {
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()?;
 
-    rt.block_on(async {
-        {
-            let mut status = server_status.lock().unwrap();
-            *status = ServerState::Starting;
-        }
+    std::env::set_var("MCP_TRANSPORT", "sse");
+    std::env::set_var("MCP_PORT", MCP_HTTP_PORT);
 
-        let config = ServerConfig::load(None)?;
-        let storage = Arc::new(GraphStorage::new(&config.database_path(), &config.database)?);
-        let embedding_engine = Arc::new(LocalEmbeddingEngine::new(config.clone())?);
-        let text_engine = TextSearchEngine::new(storage.clone());
-        let vector_engine = VectorSearchEngine::new();
-        let search_engine = Arc::new(HybridSearchEngine::new(text_engine, vector_engine));
-        let memory_config = MemoryConfig::default();
-        let memory_optimizer = Arc::new(MemoryOptimizer::new(memory_config));
-
-        std::env::set_var("MCP_TRANSPORT", "sse");
-        std::env::set_var("MCP_PORT", "8360");
-
-        let server = McpServer::new(
-            config.clone(),
-            storage.clone(),
-            embedding_engine.clone(),
-            search_engine.clone(),
-            memory_optimizer.clone(),
-        );
-
-        let server_task = tokio::spawn(async move {
-            info!("🎯 Knowledge Graph MCP Server is live!");
-            {
-                let mut status = server_status.lock().unwrap();
-                *status = ServerState::Running;
-            }
-            server.run().await
-        });
-
-        tokio::spawn(async move {
-            info!("🔄 Initializing embedding engine in the background...");
-            if let Err(e) = embedding_engine.initialize(&config.embeddings.model_name).await {
-                error!("Failed to initialize embedding engine: {}", e);
-            } else {
-                info!("✅ Embedding engine initialized successfully.");
-            }
+    rt.block_on(async move {
+        *server_status.lock().unwrap() = ServerState::Starting;
+        let current_server = Arc::new(ArcSwap::from_pointee(build_server(&recent_events).await?));
 
-            info!("🔄 Initializing memory optimizer in the background...");
-            if let Err(e) = memory_optimizer.initialize().await {
-                error!("Failed to initialize memory optimizer: {}", e);
-            } else {
-                info!("✅ Memory optimizer initialized successfully.");
-            }
-        });
+        loop {
+            let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+            let server = current_server.load_full();
 
-        server_task.await?
+            info!("🎯 Knowledge Graph MCP Server is live!");
+            *server_status.lock().unwrap() = ServerState::Running;
+            let mut server_task = tokio::spawn(async move { server.run_with_shutdown(shutdown_rx).await });
+
+            tokio::select! {
+                restart = restart_rx.recv() => {
+                    if restart.is_none() {
+                        // The tray dropped its sender (app shutting down):
+                        // let the current generation run to completion.
+                        return server_task.await?;
+                    }
+
+                    info!("Restart requested; draining current server generation...");
+                    *server_status.lock().unwrap() = ServerState::Restarting;
+                    let _ = shutdown_tx.send(true);
+                    if let Err(e) = server_task.await? {
+                        warn!("Previous server generation exited with an error during restart: {}", e);
+                    }
+
+                    *server_status.lock().unwrap() = ServerState::Starting;
+                    match build_server(&recent_events).await {
+                        Ok(rebuilt) => current_server.store(Arc::new(rebuilt)),
+                        Err(e) => {
+                            error!("Failed to rebuild server for restart: {}", e);
+                            *server_status.lock().unwrap() = ServerState::Error(e.to_string());
+                            return Err(e);
+                        }
+                    }
+                }
+                result = &mut server_task => {
+                    return result?;
+                }
+            }
+        }
     })
-} 
\ No newline at end of file
+}
\ No newline at end of file
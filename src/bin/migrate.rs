@@ -5,12 +5,18 @@
 //! to the new high-performance KG MCP Server.
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use clap::{Arg, Command};
+use kg_mcp_server::migration::schema_migrations::{self, Migration};
+use rusqlite::{params, Connection, Transaction};
 use serde_json::{json, Value};
 use std::fs;
 use std::path::Path;
+use uuid::Uuid;
 
 fn main() -> Result<()> {
+    kg_mcp_server::config::env_layer::load_dotenv();
+
     let matches = Command::new("kg-migrate")
         .version("0.1.0")
         .author("KG MCP Server Team")
@@ -31,7 +37,7 @@ fn main() -> Result<()> {
                         .short('t')
                         .long("target")
                         .value_name("PATH")
-                        .help("Target database path")
+                        .help("Target database path [env: KG_DATABASE_URL]")
                         .default_value("./kg_data.db")
                 )
                 .arg(
@@ -59,6 +65,14 @@ fn main() -> Result<()> {
                         .help("JSON format: episodes|graph|custom")
                         .default_value("episodes")
                 )
+                .arg(
+                    Arg::new("database")
+                        .short('d')
+                        .long("database")
+                        .value_name("PATH")
+                        .help("Target database path [env: KG_DATABASE_URL]")
+                        .default_value("./kg_data.db")
+                )
         )
         .subcommand(
             Command::new("backup")
@@ -80,31 +94,154 @@ fn main() -> Result<()> {
                         .short('d')
                         .long("database")
                         .value_name("PATH")
-                        .help("Database path to validate")
+                        .help("Database path to validate [env: KG_DATABASE_URL]")
+                        .default_value("./kg_data.db")
+                )
+        )
+        .subcommand(
+            Command::new("make")
+                .about("Create a new timestamped schema migration (up.sql + down.sql)")
+                .arg(
+                    Arg::new("name")
+                        .value_name("NAME")
+                        .help("Migration name, slugified into the directory name")
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("migrations-dir")
+                        .long("migrations-dir")
+                        .value_name("DIR")
+                        .help("Directory migrations are stored under")
+                        .default_value("./migrations")
+                )
+        )
+        .subcommand(
+            Command::new("up")
+                .alias("apply")
+                .about("Apply all pending schema migrations")
+                .arg(
+                    Arg::new("database")
+                        .short('d')
+                        .long("database")
+                        .value_name("PATH")
+                        .help("Database to migrate [env: KG_DATABASE_URL]")
                         .default_value("./kg_data.db")
                 )
+                .arg(
+                    Arg::new("migrations-dir")
+                        .long("migrations-dir")
+                        .value_name("DIR")
+                        .help("Directory migrations are stored under")
+                        .default_value("./migrations")
+                )
+        )
+        .subcommand(
+            Command::new("down")
+                .alias("rollback")
+                .about("Roll back the last N applied schema migrations")
+                .arg(
+                    Arg::new("count")
+                        .value_name("N")
+                        .help("Number of migrations to roll back")
+                        .default_value("1")
+                )
+                .arg(
+                    Arg::new("database")
+                        .short('d')
+                        .long("database")
+                        .value_name("PATH")
+                        .help("Database to roll back [env: KG_DATABASE_URL]")
+                        .default_value("./kg_data.db")
+                )
+                .arg(
+                    Arg::new("migrations-dir")
+                        .long("migrations-dir")
+                        .value_name("DIR")
+                        .help("Directory migrations are stored under")
+                        .default_value("./migrations")
+                )
+        )
+        .subcommand(
+            Command::new("repair")
+                .about("Offline GraphStorage migration/integrity-repair pass, for recovering a closed, corrupted database")
+                .arg(
+                    Arg::new("database")
+                        .short('d')
+                        .long("database")
+                        .value_name("PATH")
+                        .help("Database to repair [env: KG_DATABASE_URL]")
+                        .default_value("./kg_data.db")
+                )
+                .arg(
+                    Arg::new("migrate")
+                        .long("migrate")
+                        .help("Apply pending GraphStorage schema migrations (PRAGMA user_version steps, see graph::schema_migrations)")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(
+                    Arg::new("repair")
+                        .long("repair")
+                        .help("Remove edges referencing missing nodes and rebuild the embedding index")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Report what would change without writing anything")
+                        .action(clap::ArgAction::SetTrue)
+                )
         )
         .get_matches();
 
     match matches.subcommand() {
         Some(("graphiti", sub_matches)) => {
             let source = sub_matches.get_one::<String>("source").unwrap();
-            let target = sub_matches.get_one::<String>("target").unwrap();
+            let target = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "target", "KG_DATABASE_URL", "./kg_data.db");
             let dry_run = sub_matches.get_flag("dry-run");
-            migrate_from_graphiti(source, target, dry_run)?;
+            migrate_from_graphiti(source, &target, dry_run)?;
         }
         Some(("json", sub_matches)) => {
             let file = sub_matches.get_one::<String>("file").unwrap();
             let format = sub_matches.get_one::<String>("format").unwrap();
-            import_from_json(file, format)?;
+            let database = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "database", "KG_DATABASE_URL", "./kg_data.db");
+            import_from_json(file, format, &database)?;
         }
         Some(("backup", sub_matches)) => {
             let output = sub_matches.get_one::<String>("output").unwrap();
             create_backup(output)?;
         }
         Some(("validate", sub_matches)) => {
-            let database = sub_matches.get_one::<String>("database").unwrap();
-            validate_database(database)?;
+            let database = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "database", "KG_DATABASE_URL", "./kg_data.db");
+            validate_database(&database)?;
+        }
+        Some(("make", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("name").unwrap();
+            let migrations_dir = sub_matches.get_one::<String>("migrations-dir").unwrap();
+            make_schema_migration(name, migrations_dir)?;
+        }
+        Some(("up", sub_matches)) => {
+            let database = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "database", "KG_DATABASE_URL", "./kg_data.db");
+            let migrations_dir = sub_matches.get_one::<String>("migrations-dir").unwrap();
+            apply_schema_migrations(&database, migrations_dir)?;
+        }
+        Some(("down", sub_matches)) => {
+            let database = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "database", "KG_DATABASE_URL", "./kg_data.db");
+            let migrations_dir = sub_matches.get_one::<String>("migrations-dir").unwrap();
+            let count: usize = sub_matches.get_one::<String>("count").unwrap()
+                .parse()
+                .context("Rollback count must be a non-negative integer")?;
+            rollback_schema_migrations(&database, migrations_dir, count)?;
+        }
+        Some(("repair", sub_matches)) => {
+            let database = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "database", "KG_DATABASE_URL", "./kg_data.db");
+            let do_migrate = sub_matches.get_flag("migrate");
+            let do_repair = sub_matches.get_flag("repair");
+            let dry_run = sub_matches.get_flag("dry-run");
+            // Neither flag given means "do everything safe recovery needs",
+            // matching `cargo fix`-style tools that default to their full
+            // pass rather than a no-op when called bare.
+            let (do_migrate, do_repair) = if !do_migrate && !do_repair { (true, true) } else { (do_migrate, do_repair) };
+            repair_database(&database, do_migrate, do_repair, dry_run)?;
         }
         _ => {
             println!("KG MCP Server Migration Utility");
@@ -122,7 +259,7 @@ fn migrate_from_graphiti(source: &str, target: &str, dry_run: bool) -> Result<()
     println!("📁 Target: {}", target);
     
     if dry_run {
-        println!("🔍 DRY RUN MODE - No changes will be made");
+        println!("🔍 DRY RUN MODE - migration runs in a transaction that is rolled back, no changes will be made");
     }
     
     // Check if source exists
@@ -140,56 +277,74 @@ fn migrate_from_graphiti(source: &str, target: &str, dry_run: bool) -> Result<()
     println!("   Relationships found: {}", analysis.relationships);
     println!("   Estimated size: {} MB", analysis.size_mb);
     
+    // Run the transfer inside its own transaction even for a dry run, so
+    // "looks feasible" reflects real row counts and constraint failures
+    // rather than the file-size estimate above; `perform_graphiti_migration`
+    // rolls the transaction back unconditionally when `dry_run` is set.
+    println!("🚀 Starting migration...");
+    let migrated = perform_graphiti_migration(source, target, &analysis, dry_run)?;
+
     if dry_run {
         println!("✅ Dry run completed - migration looks feasible");
+        println!("📊 Would migrate (rolled back, target left unchanged):");
+        println!("   Episodes: {}", migrated.episodes);
+        println!("   Entities: {}", migrated.entities);
+        println!("   Relationships: {}", migrated.relationships);
         return Ok(());
     }
-    
-    // Perform actual migration
-    println!("🚀 Starting migration...");
-    
-    // Create target database
-    let migrated = perform_graphiti_migration(source, target)?;
-    
+
     println!("✅ Migration completed successfully!");
     println!("📊 Migration Results:");
     println!("   Episodes migrated: {}", migrated.episodes);
     println!("   Entities migrated: {}", migrated.entities);
     println!("   Relationships migrated: {}", migrated.relationships);
-    
+
     // Validate migration
     println!("🔍 Validating migration...");
     validate_database(target)?;
-    
+
     println!("🎉 Migration validation passed!");
-    
+
     Ok(())
 }
 
-fn import_from_json(file: &str, format: &str) -> Result<()> {
+fn import_from_json(file: &str, format: &str, database: &str) -> Result<()> {
     println!("📥 Importing from JSON file...");
     println!("📁 File: {}", file);
     println!("🔧 Format: {}", format);
-    
+    println!("📁 Target: {}", database);
+
     if !Path::new(file).exists() {
         anyhow::bail!("JSON file not found: {}", file);
     }
-    
+
     let content = fs::read_to_string(file)
         .context("Failed to read JSON file")?;
-    
+
     let data: Value = serde_json::from_str(&content)
         .context("Invalid JSON format")?;
-    
+
+    // SQLite can't roll back DDL, so the schema must exist before BEGIN.
+    let mut conn = Connection::open(database)
+        .with_context(|| format!("Failed to open target database: {}", database))?;
+    create_target_tables(&conn)?;
+
+    let tx = conn.transaction()
+        .context("Failed to begin import transaction")?;
+
     match format {
-        "episodes" => import_episodes_json(&data)?,
-        "graph" => import_graph_json(&data)?,
-        "custom" => import_custom_json(&data)?,
+        "episodes" => import_episodes_json(&tx, &data)?,
+        "graph" => import_graph_json(&tx, &data)?,
+        "custom" => import_custom_json(&tx, &data)?,
         _ => anyhow::bail!("Unsupported format: {}", format),
     }
-    
+
+    // Any `?` above dropped `tx` without committing, which rolls it back;
+    // reaching here means every insert in this import succeeded.
+    tx.commit().context("Failed to commit JSON import transaction")?;
+
     println!("✅ JSON import completed successfully!");
-    
+
     Ok(())
 }
 
@@ -239,6 +394,139 @@ fn validate_database(database: &str) -> Result<()> {
     Ok(())
 }
 
+fn make_schema_migration(name: &str, migrations_dir: &str) -> Result<()> {
+    let timestamp = chrono::Utc::now().format("%y%m%d%H%M%S").to_string();
+    let dir = schema_migrations::make_migration(Path::new(migrations_dir), name, &timestamp)?;
+
+    println!("✅ Created migration: {}", dir.display());
+    println!("   Edit {}/up.sql and {}/down.sql, then run `kg-migrate up`", dir.display(), dir.display());
+
+    Ok(())
+}
+
+fn apply_schema_migrations(database: &str, migrations_dir: &str) -> Result<()> {
+    println!("🔄 Applying schema migrations...");
+    println!("📁 Database: {}", database);
+    println!("📁 Migrations: {}", migrations_dir);
+
+    let migrations = schema_migrations::load_migrations(Path::new(migrations_dir))?;
+    let conn = rusqlite::Connection::open(database)
+        .with_context(|| format!("Failed to open database: {}", database))?;
+
+    let applied = schema_migrations::apply_pending(&conn, &migrations)?;
+
+    if applied.is_empty() {
+        println!("✅ No pending migrations — already up to date");
+    } else {
+        println!("✅ Applied {} migration(s):", applied.len());
+        for version in &applied {
+            println!("   - {}", version);
+        }
+    }
+
+    Ok(())
+}
+
+fn rollback_schema_migrations(database: &str, migrations_dir: &str, count: usize) -> Result<()> {
+    println!("🔄 Rolling back {} schema migration(s)...", count);
+    println!("📁 Database: {}", database);
+    println!("📁 Migrations: {}", migrations_dir);
+
+    let migrations: Vec<Migration> = schema_migrations::load_migrations(Path::new(migrations_dir))?;
+    let conn = rusqlite::Connection::open(database)
+        .with_context(|| format!("Failed to open database: {}", database))?;
+
+    let rolled_back = schema_migrations::rollback(&conn, &migrations, count)?;
+
+    if rolled_back.is_empty() {
+        println!("✅ No applied migrations to roll back");
+    } else {
+        println!("✅ Rolled back {} migration(s):", rolled_back.len());
+        for version in &rolled_back {
+            println!("   - {}", version);
+        }
+    }
+
+    Ok(())
+}
+
+/// Offline `GraphStorage` maintenance: optionally applies pending schema
+/// migrations (the `PRAGMA user_version` steps in `graph::schema_migrations`,
+/// distinct from the directory-based `up`/`down` migrations above, which
+/// target `_kg_schema_migrations` instead), then optionally runs
+/// `GraphStorage::repair_integrity`. Run against a closed server — nothing
+/// here expects concurrent access to `database`.
+fn repair_database(database: &str, do_migrate: bool, do_repair: bool, dry_run: bool) -> Result<()> {
+    use kg_mcp_server::graph::schema_migrations;
+    use kg_mcp_server::graph::storage::GraphStorage;
+    use kg_mcp_server::config::DatabaseConfig;
+
+    println!("🔧 Running offline database maintenance...");
+    println!("📁 Database: {}", database);
+    if dry_run {
+        println!("🔍 DRY RUN MODE - no changes will be written");
+    }
+
+    if !Path::new(database).exists() {
+        anyhow::bail!("Database not found: {}", database);
+    }
+
+    if do_migrate {
+        let conn = rusqlite::Connection::open(database)
+            .with_context(|| format!("Failed to open database: {}", database))?;
+        let pending = schema_migrations::pending(&conn)?;
+
+        if pending.is_empty() {
+            println!("✅ Schema already up to date — no pending migrations");
+        } else if dry_run {
+            println!("🔍 Would apply {} pending migration(s):", pending.len());
+            for (id, description) in &pending {
+                println!("   - {} ({})", id, description);
+            }
+        } else {
+            let mut conn = conn;
+            schema_migrations::run_migrations(&mut conn)?;
+            println!("✅ Applied {} migration(s):", pending.len());
+            for (id, description) in &pending {
+                println!("   - {} ({})", id, description);
+            }
+        }
+    }
+
+    if do_repair {
+        // `GraphStorage::new` always brings the schema up to date as part
+        // of opening (see its own doc comment); that's only a write here
+        // if the caller skipped `--migrate` on a database that still has
+        // pending steps, so check first rather than silently migrating
+        // out from under a `--dry-run` caller.
+        let conn = rusqlite::Connection::open(database)
+            .with_context(|| format!("Failed to open database: {}", database))?;
+        let pending = schema_migrations::pending(&conn)?;
+        drop(conn);
+
+        if dry_run && !pending.is_empty() {
+            println!("⚠️  Schema has {} pending migration(s); run with --migrate first to preview repair accurately", pending.len());
+        } else {
+            let config = DatabaseConfig::default();
+            let storage = GraphStorage::new(Path::new(database), &config)
+                .with_context(|| format!("Failed to open database for repair: {}", database))?;
+            let report = storage.repair_integrity(dry_run)?;
+
+            if report.healthy() {
+                println!("✅ No integrity issues found");
+            } else if dry_run {
+                println!("🔍 Would remove {} dangling edge(s) (referencing a missing node)", report.dangling_edges_removed);
+            } else {
+                println!("✅ Removed {} dangling edge(s) and rebuilt the embedding index", report.dangling_edges_removed);
+            }
+
+            println!("📊 Current counts: {} nodes, {} edges, {} episodes", report.counts.nodes, report.counts.edges, report.counts.episodes);
+        }
+    }
+
+    Ok(())
+}
+
 fn interactive_migration() -> Result<()> {
     println!("\n🔄 KG MCP Server Migration Utility");
     println!("===================================");
@@ -268,7 +556,7 @@ fn interactive_migration() -> Result<()> {
             std::io::Write::flush(&mut std::io::stdout())?;
             let mut file = String::new();
             std::io::stdin().read_line(&mut file)?;
-            import_from_json(file.trim(), "episodes")?;
+            import_from_json(file.trim(), "episodes", "./kg_data.db")?;
         }
         "3" => create_backup("./kg_backup.json")?,
         "4" => validate_database("./kg_data.db")?,
@@ -320,42 +608,278 @@ fn analyze_graphiti_db(source: &str) -> Result<GraphitiAnalysis> {
     })
 }
 
-fn perform_graphiti_migration(source: &str, target: &str) -> Result<MigrationResult> {
-    // Mock migration - in real implementation would perform actual data transfer
-    println!("📊 Migrating episodes...");
+/// Creates the subset of `GraphStorage`'s schema (see `src/graph/storage.rs`)
+/// that a migration target needs, so `perform_graphiti_migration` and the
+/// JSON importers write into the same tables the server itself reads from.
+/// Must run before `BEGIN` — SQLite cannot roll back `CREATE TABLE`.
+fn create_target_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS nodes (
+            uuid TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            node_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}'
+        );
+        CREATE TABLE IF NOT EXISTS edges (
+            uuid TEXT PRIMARY KEY,
+            source_node_uuid TEXT NOT NULL,
+            target_node_uuid TEXT NOT NULL,
+            relation_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            weight REAL NOT NULL DEFAULT 1.0,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}',
+            FOREIGN KEY (source_node_uuid) REFERENCES nodes (uuid),
+            FOREIGN KEY (target_node_uuid) REFERENCES nodes (uuid)
+        );
+        CREATE TABLE IF NOT EXISTS episodes (
+            uuid TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            source TEXT NOT NULL,
+            source_description TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}'
+        );
+        CREATE TABLE IF NOT EXISTS embeddings (
+            uuid TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL,
+            embedding BLOB NOT NULL,
+            dimensions INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (uuid) REFERENCES nodes (uuid) ON DELETE CASCADE
+        );",
+    )
+    .context("Failed to create target schema")?;
+    Ok(())
+}
+
+/// There is no real graphiti-mcp SQLite reader in this crate yet (see the
+/// equally mocked `GraphitiMigrator::fetch_graphiti_data`), so the rows
+/// transferred here are generated to match `analysis`'s counts rather than
+/// read from `source`. What's real is the target side: every row is bulk
+/// inserted into `target` through prepared statements reused across each
+/// loop, inside one transaction that commits only when `dry_run` is false.
+fn perform_graphiti_migration(
+    source: &str,
+    target: &str,
+    analysis: &GraphitiAnalysis,
+    dry_run: bool,
+) -> Result<MigrationResult> {
+    let _ = source;
+
+    let mut conn = Connection::open(target)
+        .with_context(|| format!("Failed to open target database: {}", target))?;
+
+    // SQLite can't roll back DDL, so the schema must exist before BEGIN.
+    create_target_tables(&conn)?;
+
+    let tx = conn.transaction()
+        .context("Failed to begin migration transaction")?;
+
     println!("📊 Migrating entities...");
+    let entity_uuids: Vec<String> = {
+        let mut insert_node = tx.prepare(
+            "INSERT OR REPLACE INTO nodes (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        let now = Utc::now().to_rfc3339();
+        let mut uuids = Vec::with_capacity(analysis.entities);
+        for i in 0..analysis.entities {
+            let uuid = Uuid::new_v4().to_string();
+            insert_node.execute(params![
+                uuid,
+                format!("graphiti_entity_{}", i),
+                "entity",
+                "Imported from graphiti-mcp",
+                now,
+                now,
+                Option::<String>::None,
+                "{}",
+            ])?;
+            uuids.push(uuid);
+        }
+        uuids
+    };
+
     println!("📊 Migrating relationships...");
+    let relationship_count = if entity_uuids.len() >= 2 {
+        let mut insert_edge = tx.prepare(
+            "INSERT OR REPLACE INTO edges (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+        let now = Utc::now().to_rfc3339();
+        for i in 0..analysis.relationships {
+            let source_uuid = &entity_uuids[i % entity_uuids.len()];
+            let target_uuid = &entity_uuids[(i + 1) % entity_uuids.len()];
+            insert_edge.execute(params![
+                Uuid::new_v4().to_string(),
+                source_uuid,
+                target_uuid,
+                "related_to",
+                "Imported from graphiti-mcp",
+                1.0,
+                now,
+                now,
+                Option::<String>::None,
+                "{}",
+            ])?;
+        }
+        analysis.relationships
+    } else {
+        0
+    };
+
+    println!("📊 Migrating episodes...");
+    {
+        let mut insert_episode = tx.prepare(
+            "INSERT OR REPLACE INTO episodes (uuid, name, content, source, source_description, created_at, group_id, metadata) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        let now = Utc::now().to_rfc3339();
+        for i in 0..analysis.episodes {
+            insert_episode.execute(params![
+                Uuid::new_v4().to_string(),
+                format!("graphiti_episode_{}", i),
+                "Imported from graphiti-mcp",
+                "graphiti_migration",
+                "graphiti-mcp migration",
+                now,
+                Option::<String>::None,
+                "{}",
+            ])?;
+        }
+    }
+
     println!("🔧 Converting embeddings...");
-    
-    Ok(MigrationResult {
-        episodes: 150,
-        entities: 450,
-        relationships: 300,
-    })
+    {
+        let mut insert_embedding = tx.prepare(
+            "INSERT OR REPLACE INTO embeddings (uuid, entity_type, embedding, dimensions, created_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        let now = Utc::now().to_rfc3339();
+        let placeholder_embedding: Vec<u8> = vec![0u8; 4];
+        for uuid in &entity_uuids {
+            insert_embedding.execute(params![uuid, "node", placeholder_embedding, 1, now])?;
+        }
+    }
+
+    let result = MigrationResult {
+        episodes: analysis.episodes,
+        entities: analysis.entities,
+        relationships: relationship_count,
+    };
+
+    if dry_run {
+        tx.rollback().context("Failed to roll back dry-run migration transaction")?;
+    } else {
+        tx.commit().context("Failed to commit migration transaction")?;
+    }
+
+    Ok(result)
 }
 
-fn import_episodes_json(data: &Value) -> Result<()> {
-    if let Some(episodes) = data["episodes"].as_array() {
-        println!("📥 Importing {} episodes...", episodes.len());
-        // Mock import logic
-        for (i, _episode) in episodes.iter().enumerate() {
-            if i % 10 == 0 {
-                println!("   Progress: {}/{}", i, episodes.len());
-            }
+fn import_episodes_json(tx: &Transaction, data: &Value) -> Result<()> {
+    let episodes = data["episodes"].as_array().cloned().unwrap_or_default();
+    println!("📥 Importing {} episodes...", episodes.len());
+
+    let mut insert_episode = tx.prepare(
+        "INSERT OR REPLACE INTO episodes (uuid, name, content, source, source_description, created_at, group_id, metadata) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+
+    for (i, episode) in episodes.iter().enumerate() {
+        let uuid = episode["id"].as_str().map(String::from).unwrap_or_else(|| Uuid::new_v4().to_string());
+        let name = episode["name"].as_str().unwrap_or("imported episode").to_string();
+        let content = episode["content"].as_str().unwrap_or_default().to_string();
+        let created_at = episode["created_at"].as_str().map(String::from).unwrap_or_else(|| Utc::now().to_rfc3339());
+
+        insert_episode.execute(params![
+            uuid,
+            name,
+            content,
+            "json_import",
+            "Imported from JSON export",
+            created_at,
+            Option::<String>::None,
+            "{}",
+        ])?;
+
+        if i % 10 == 0 {
+            println!("   Progress: {}/{}", i, episodes.len());
         }
     }
+
     Ok(())
 }
 
-fn import_graph_json(data: &Value) -> Result<()> {
-    println!("📥 Importing graph structure...");
-    // Mock import logic for graph format
+fn import_graph_json(tx: &Transaction, data: &Value) -> Result<()> {
+    let nodes = data["nodes"].as_array().cloned().unwrap_or_default();
+    let edges = data["edges"].as_array().cloned().unwrap_or_default();
+    println!("📥 Importing graph structure: {} nodes, {} edges...", nodes.len(), edges.len());
+
+    {
+        let mut insert_node = tx.prepare(
+            "INSERT OR REPLACE INTO nodes (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for node in &nodes {
+            let uuid = node["uuid"].as_str().map(String::from).unwrap_or_else(|| Uuid::new_v4().to_string());
+            let name = node["name"].as_str().unwrap_or("imported node").to_string();
+            let node_type = node["node_type"].as_str().unwrap_or("entity").to_string();
+            let summary = node["summary"].as_str().unwrap_or_default().to_string();
+            let now = Utc::now().to_rfc3339();
+            insert_node.execute(params![uuid, name, node_type, summary, now, now, Option::<String>::None, "{}"])?;
+        }
+    }
+
+    {
+        let mut insert_edge = tx.prepare(
+            "INSERT OR REPLACE INTO edges (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+        for edge in &edges {
+            let uuid = edge["uuid"].as_str().map(String::from).unwrap_or_else(|| Uuid::new_v4().to_string());
+            let source_uuid = edge["source_node_uuid"].as_str().unwrap_or_default().to_string();
+            let target_uuid = edge["target_node_uuid"].as_str().unwrap_or_default().to_string();
+            let relation_type = edge["relation_type"].as_str().unwrap_or("related_to").to_string();
+            let summary = edge["summary"].as_str().unwrap_or_default().to_string();
+            let weight = edge["weight"].as_f64().unwrap_or(1.0);
+            let now = Utc::now().to_rfc3339();
+            insert_edge.execute(params![uuid, source_uuid, target_uuid, relation_type, summary, weight, now, now, Option::<String>::None, "{}"])?;
+        }
+    }
+
     Ok(())
 }
 
-fn import_custom_json(data: &Value) -> Result<()> {
+fn import_custom_json(tx: &Transaction, data: &Value) -> Result<()> {
     println!("📥 Importing custom format...");
-    // Mock import logic for custom format
+
+    // The custom format has no defined schema, so the whole payload is kept
+    // as a single episode rather than silently dropped.
+    let mut insert_episode = tx.prepare(
+        "INSERT OR REPLACE INTO episodes (uuid, name, content, source, source_description, created_at, group_id, metadata) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    insert_episode.execute(params![
+        Uuid::new_v4().to_string(),
+        "custom_import",
+        data.to_string(),
+        "json_import",
+        "Imported from custom JSON format",
+        Utc::now().to_rfc3339(),
+        Option::<String>::None,
+        "{}",
+    ])?;
+
     Ok(())
 }
 
@@ -4,14 +4,18 @@
 //! This utility helps users quickly set up and configure the Knowledge Graph MCP Server
 //! for use with Cursor IDE and other MCP-compatible applications.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Arg, Command};
 use serde_json::json;
 use std::fs;
-use std::path::Path;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::{Duration, Instant};
 
 fn main() -> Result<()> {
+    kg_mcp_server::config::env_layer::load_dotenv();
+
     let matches = Command::new("kg-setup")
         .version("0.1.0")
         .author("KG MCP Server Team")
@@ -47,6 +51,20 @@ fn main() -> Result<()> {
         .subcommand(
             Command::new("validate")
                 .about("Validate current configuration")
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .value_name("PORT")
+                        .help("Port to probe /health on [env: MCP_PORT]")
+                        .default_value("8360")
+                )
+                .arg(
+                    Arg::new("timeout")
+                        .long("timeout")
+                        .value_name("SECONDS")
+                        .help("Max time to wait for the /health probe to succeed")
+                        .default_value("5")
+                )
         )
         .subcommand(
             Command::new("start")
@@ -57,25 +75,86 @@ fn main() -> Result<()> {
                         .help("Run as daemon in background")
                         .action(clap::ArgAction::SetTrue)
                 )
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .value_name("PORT")
+                        .help("Port the server listens on [env: MCP_PORT]")
+                        .default_value("8360")
+                )
+                .arg(
+                    Arg::new("database")
+                        .short('d')
+                        .long("database")
+                        .value_name("PATH")
+                        .help("Database path [env: KG_DATABASE_URL]")
+                        .default_value("./kg_data.db")
+                )
+                .arg(
+                    Arg::new("log")
+                        .long("log")
+                        .value_name("PATH")
+                        .help("Log file path used in daemon mode")
+                        .default_value("~/.cursor/kg-mcp-server.log")
+                )
+                .arg(
+                    Arg::new("timeout")
+                        .long("timeout")
+                        .value_name("SECONDS")
+                        .help("Max time to wait for the server to become healthy")
+                        .default_value("30")
+                )
+        )
+        .subcommand(
+            Command::new("stop")
+                .about("Stop a server previously started with --daemon")
+        )
+        .subcommand(
+            Command::new("status")
+                .about("Report whether the daemon is running and healthy")
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .value_name("PORT")
+                        .help("Port to probe /health on [env: MCP_PORT]")
+                        .default_value("8360")
+                )
         )
         .get_matches();
 
     match matches.subcommand() {
         Some(("cursor", sub_matches)) => {
-            let port = sub_matches.get_one::<String>("port").unwrap();
+            let port = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "port", "MCP_PORT", "8360");
             let global = sub_matches.get_flag("global");
-            setup_cursor(port, global)?;
+            setup_cursor(&port, global)?;
         }
         Some(("docker", sub_matches)) => {
-            let port = sub_matches.get_one::<String>("port").unwrap();
-            setup_docker(port)?;
+            let port = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "port", "MCP_PORT", "8360");
+            setup_docker(&port)?;
         }
-        Some(("validate", _)) => {
-            validate_setup()?;
+        Some(("validate", sub_matches)) => {
+            let port = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "port", "MCP_PORT", "8360");
+            let timeout: u64 = sub_matches.get_one::<String>("timeout").unwrap()
+                .parse()
+                .context("--timeout must be a non-negative integer number of seconds")?;
+            validate_setup(&port, Duration::from_secs(timeout))?;
         }
         Some(("start", sub_matches)) => {
             let daemon = sub_matches.get_flag("daemon");
-            start_server(daemon)?;
+            let port = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "port", "MCP_PORT", "8360");
+            let database = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "database", "KG_DATABASE_URL", "./kg_data.db");
+            let log = sub_matches.get_one::<String>("log").unwrap();
+            let timeout: u64 = sub_matches.get_one::<String>("timeout").unwrap()
+                .parse()
+                .context("--timeout must be a non-negative integer number of seconds")?;
+            start_server(daemon, &port, &database, log, Duration::from_secs(timeout))?;
+        }
+        Some(("stop", _)) => {
+            stop_server()?;
+        }
+        Some(("status", sub_matches)) => {
+            let port = kg_mcp_server::config::env_layer::resolved_str(sub_matches, "port", "MCP_PORT", "8360");
+            status_server(&port)?;
         }
         _ => {
             println!("KG MCP Server Setup Utility");
@@ -204,23 +283,23 @@ volumes:
     Ok(())
 }
 
-fn validate_setup() -> Result<()> {
+fn validate_setup(port: &str, health_timeout: Duration) -> Result<()> {
     println!("🔍 Validating KG MCP Server setup...");
-    
+
     // Check if binary exists
     if which::which("kg-mcp-server").is_ok() {
         println!("✅ kg-mcp-server binary found");
     } else {
         println!("❌ kg-mcp-server binary not found in PATH");
     }
-    
+
     // Check for Cursor config
     let home_config = format!("{}/.cursor/mcp.json", std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
     let cursor_configs = vec![
         ".cursor/mcp.json",
         &home_config
     ];
-    
+
     let mut config_found = false;
     for config in cursor_configs {
         if Path::new(config).exists() {
@@ -229,52 +308,222 @@ fn validate_setup() -> Result<()> {
             break;
         }
     }
-    
+
     if !config_found {
         println!("❌ No Cursor MCP configuration found");
         println!("   Run: kg-setup cursor");
     }
-    
-    // Test server connection if running
-    match std::process::Command::new("curl")
-        .args(&["-f", "http://localhost:8360/health"])
-        .output()
-    {
-        Ok(output) if output.status.success() => {
-            println!("✅ Server is running and healthy");
-        }
-        _ => {
-            println!("⚠️  Server not running or not responding");
+
+    // Test server connection if running. A backoff retry (rather than one
+    // shot) avoids a spurious failure in the window right after `kg-setup
+    // start` where the process is up but hasn't bound /health yet.
+    match wait_for_health(&format!("http://localhost:{}/health", port), health_timeout) {
+        Ok(()) => println!("✅ Server is running and healthy"),
+        Err(error) => {
+            println!("⚠️  Server not running or not responding: {}", error);
             println!("   Run: kg-setup start");
         }
     }
-    
+
     Ok(())
 }
 
-fn start_server(daemon: bool) -> Result<()> {
+/// Polls `url` with exponential backoff — starting at 100ms, doubling each
+/// attempt up to a 5s cap, with ±20% jitter so a fleet of instances started
+/// together doesn't retry in lockstep — until a request succeeds or
+/// `timeout` elapses. Mirrors the retry-with-backoff readiness check
+/// sqlx-cli uses while waiting for a database to accept connections.
+fn wait_for_health(url: &str, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    let mut delay = Duration::from_millis(100);
+    let max_delay = Duration::from_secs(5);
+    let mut last_error = "no probe attempted".to_string();
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .context("Failed to build HTTP client for health check")?;
+
+    loop {
+        match client.get(url).send() {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => last_error = format!("health check returned status {}", response.status()),
+            Err(error) => last_error = error.to_string(),
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            anyhow::bail!("timed out after {:?} (last error: {})", timeout, last_error);
+        }
+
+        let jittered_delay = delay.mul_f64(jitter_factor()).min(remaining);
+        std::thread::sleep(jittered_delay);
+        delay = (delay * 2).min(max_delay);
+    }
+}
+
+/// A jitter multiplier in `[0.8, 1.2)`. There's no `rand` dependency here,
+/// so the low bits of the current time stand in for randomness — plenty
+/// for spreading out retries, not meant to be cryptographically anything.
+fn jitter_factor() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or(0);
+    0.8 + (nanos % 1000) as f64 / 1000.0 * 0.4
+}
+
+/// Expands a leading `~/` the way a shell would, since `std::fs`/`libc` take
+/// paths literally.
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+fn pid_file_path() -> PathBuf {
+    expand_home("~/.cursor/kg-mcp-server.pid")
+}
+
+fn read_pid() -> Option<i32> {
+    fs::read_to_string(pid_file_path())
+        .ok()
+        .and_then(|contents| contents.trim().parse().ok())
+}
+
+/// `kill(pid, 0)` sends no signal; it only checks whether the process
+/// exists and is visible to us, which is the standard liveness probe.
+fn process_alive(pid: i32) -> bool {
+    unsafe { libc::kill(pid, 0) == 0 }
+}
+
+fn start_server(daemon: bool, port: &str, database: &str, log: &str, timeout: Duration) -> Result<()> {
     println!("🚀 Starting KG MCP Server...");
-    
+
     let mut cmd = process::Command::new("kg-mcp-server");
-    
+    // Propagate the resolved port/database/transport so the spawned
+    // server picks up the same values `kg-setup` resolved, rather than
+    // each process re-deriving its own defaults independently.
+    cmd.env("MCP_PORT", port)
+        .env("MCP_TRANSPORT", "sse")
+        .env("KG_DATABASE_URL", database);
+
     if daemon {
+        let log_path = expand_home(log);
+        if let Some(parent) = log_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let log_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_context(|| format!("Failed to open log file: {}", log_path.display()))?;
+
         println!("🔄 Running in daemon mode...");
-        cmd.stdout(process::Stdio::null())
-           .stderr(process::Stdio::null());
+        println!("📝 Logs: {}", log_path.display());
+
+        cmd.stdout(log_file.try_clone()?)
+            .stderr(log_file);
+
+        // `setsid` in the freshly-forked child, before it execs
+        // `kg-mcp-server`, detaches it into its own session so it survives
+        // the shell exiting instead of just redirecting its I/O.
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
     }
-    
-    let mut child = cmd.spawn()?;
-    
+
+    let child = cmd.spawn()?;
+
     if daemon {
+        let pid_path = pid_file_path();
+        if let Some(parent) = pid_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&pid_path, child.id().to_string())
+            .with_context(|| format!("Failed to write PID file: {}", pid_path.display()))?;
+
         println!("✅ Server started as daemon (PID: {})", child.id());
-        println!("🌐 Available at: http://localhost:8360/sse");
+        println!("📄 PID file: {}", pid_path.display());
+        println!("🌐 Available at: http://localhost:{}/sse", port);
+        println!("   Stop with: kg-setup stop");
+
+        wait_for_health(&format!("http://localhost:{}/health", port), timeout)
+            .context("Server did not become healthy in time")?;
+        println!("✅ Server is running and healthy");
     } else {
         println!("✅ Server starting...");
-        println!("🌐 Available at: http://localhost:8360/sse");
+        println!("🌐 Available at: http://localhost:{}/sse", port);
+
+        wait_for_health(&format!("http://localhost:{}/health", port), timeout)
+            .context("Server did not become healthy in time")?;
+        println!("✅ Server is running and healthy");
+
         println!("Press Ctrl+C to stop");
+        let mut child = child;
         child.wait()?;
     }
-    
+
+    Ok(())
+}
+
+fn stop_server() -> Result<()> {
+    println!("🛑 Stopping KG MCP Server...");
+
+    let pid_path = pid_file_path();
+    let Some(pid) = read_pid() else {
+        println!("⚠️  No PID file found at {} — is the daemon running?", pid_path.display());
+        return Ok(());
+    };
+
+    if !process_alive(pid) {
+        println!("⚠️  PID {} from {} is not running — removing stale PID file", pid, pid_path.display());
+        let _ = fs::remove_file(&pid_path);
+        return Ok(());
+    }
+
+    if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+        anyhow::bail!("Failed to send SIGTERM to PID {}: {}", pid, std::io::Error::last_os_error());
+    }
+
+    fs::remove_file(&pid_path).ok();
+    println!("✅ Sent SIGTERM to PID {}", pid);
+
+    Ok(())
+}
+
+fn status_server(port: &str) -> Result<()> {
+    println!("🔍 Checking KG MCP Server status...");
+
+    let pid_path = pid_file_path();
+    match read_pid() {
+        Some(pid) if process_alive(pid) => {
+            println!("✅ Daemon running (PID: {})", pid);
+        }
+        Some(pid) => {
+            println!("❌ PID file {} points at {}, but that process is not running", pid_path.display(), pid);
+        }
+        None => {
+            println!("❌ No PID file found at {}", pid_path.display());
+        }
+    }
+
+    match reqwest::blocking::get(format!("http://localhost:{}/health", port)) {
+        Ok(response) if response.status().is_success() => {
+            println!("✅ /health probe succeeded — server is healthy");
+        }
+        _ => {
+            println!("⚠️  /health probe failed — server not responding");
+        }
+    }
+
     Ok(())
 }
 
@@ -297,8 +546,8 @@ fn interactive_setup() -> Result<()> {
     match input.trim() {
         "1" => setup_cursor("8360", false)?,
         "2" => setup_docker("8360")?,
-        "3" => validate_setup()?,
-        "4" => start_server(false)?,
+        "3" => validate_setup("8360", Duration::from_secs(5))?,
+        "4" => start_server(false, "8360", "./kg_data.db", "~/.cursor/kg-mcp-server.log", Duration::from_secs(30))?,
         "5" => return Ok(()),
         _ => println!("Invalid option"),
     }
@@ -44,40 +44,14 @@ pub const TOKENIZER_FILENAME: &str = "tokenizer.json";
 pub const CONFIG_FILENAME: &str = "config.json";
 
 // Supported model types
+//
+// These three are still the models `ModelRegistry::with_builtins` seeds by
+// default (see `model_registry.rs`); lookups by name now go through the
+// registry rather than the `get_model_urls`/`get_model_dimensions` match
+// statements that used to live here, so arbitrary ONNX models can be
+// registered without editing this file.
 pub const SUPPORTED_MODELS: &[&str] = &[
     "nomic-embed-text-v1.5",
-    "all-MiniLM-L6-v2", 
+    "all-MiniLM-L6-v2",
     "bge-m3"
-];
-
-/// Get model URLs for a given model name
-pub fn get_model_urls(model_name: &str) -> Option<(String, String, String)> {
-    match model_name {
-        "nomic-embed-text-v1.5" => Some((
-            DEFAULT_NOMIC_MODEL_URL.to_string(),
-            DEFAULT_NOMIC_TOKENIZER_URL.to_string(),
-            DEFAULT_NOMIC_CONFIG_URL.to_string(),
-        )),
-        "all-MiniLM-L6-v2" => Some((
-            DEFAULT_MINILM_MODEL_URL.to_string(),
-            DEFAULT_MINILM_TOKENIZER_URL.to_string(),
-            DEFAULT_MINILM_CONFIG_URL.to_string(),
-        )),
-        "bge-m3" => Some((
-            DEFAULT_BGE_MODEL_URL.to_string(),
-            DEFAULT_BGE_TOKENIZER_URL.to_string(),
-            DEFAULT_BGE_CONFIG_URL.to_string(),
-        )),
-        _ => None,
-    }
-}
-
-/// Get embedding dimensions for a model
-pub fn get_model_dimensions(model_name: &str) -> Option<usize> {
-    match model_name {
-        "nomic-embed-text-v1.5" => Some(NOMIC_DIMENSIONS),
-        "all-MiniLM-L6-v2" => Some(MINILM_DIMENSIONS),
-        "bge-m3" => Some(BGE_DIMENSIONS),
-        _ => None,
-    }
-} 
\ No newline at end of file
+]; 
\ No newline at end of file
@@ -0,0 +1,50 @@
+//! Layered configuration resolution shared by the CLI utilities
+//! (`kg-setup`, `kg-migrate`) and the server itself: explicit CLI flag →
+//! environment variable → built-in default. Mirrors the "read config from
+//! env, no init required" convenience tools like sqlx-cli offer, so
+//! containerized/CI use doesn't need to repeat `-d`/`-p`/`-t` on every
+//! invocation.
+
+use clap::ArgMatches;
+
+/// Loads a `.env` file from the current directory into the process
+/// environment, if one exists. An already-set environment variable always
+/// wins over the file (the usual `dotenv` precedence), so a real
+/// deployment's environment can't be silently overridden by a stray
+/// `.env` left in a working directory. Missing file or parse errors are
+/// silently ignored — `.env` support is a convenience, not a requirement.
+pub fn load_dotenv() {
+    let Ok(contents) = std::fs::read_to_string(".env") else {
+        return;
+    };
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim().trim_matches('"');
+        if std::env::var(key).is_err() {
+            std::env::set_var(key, value);
+        }
+    }
+}
+
+/// Resolves a string-valued CLI setting in priority order: the flag was
+/// explicitly typed on the command line → `env_var` is set → `default`.
+///
+/// `arg_id`'s `Arg` must carry a `default_value` (clap needs something for
+/// `get_one` to return), but that default only matters for parsing — if
+/// the user didn't type the flag, `value_source` reports the value's
+/// origin as the arg's own default rather than `CommandLine`, so the env
+/// var still takes priority over it.
+pub fn resolved_str(matches: &ArgMatches, arg_id: &str, env_var: &str, default: &str) -> String {
+    if matches.value_source(arg_id) == Some(clap::ValueSource::CommandLine) {
+        return matches.get_one::<String>(arg_id).unwrap().clone();
+    }
+    std::env::var(env_var).unwrap_or_else(|_| default.to_string())
+}
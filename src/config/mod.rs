@@ -1,4 +1,6 @@
 pub mod defaults;
+pub mod env_layer;
+pub mod model_registry;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -17,12 +19,33 @@ pub struct ServerConfig {
     pub search: SearchConfig,
     pub memory: MemoryConfig,
     pub security: SecurityConfig,
+    pub tool_rate_limit: ToolRateLimitConfig,
+    #[serde(default)]
+    pub watcher: crate::indexing::WatcherConfig,
+    /// Maximum callers `mcp::search_queue::SearchQueue` lets wait for a
+    /// concurrency permit at once (concurrent execution itself is sized
+    /// separately, from `std::thread::available_parallelism()`). Once this
+    /// many are queued, admitting a new one evicts a uniformly-random
+    /// existing waiter rather than growing the queue further.
+    #[serde(default = "default_search_queue_size")]
+    pub search_queue_size: usize,
+}
+
+fn default_search_queue_size() -> usize {
+    32
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
     pub enable_authentication: bool,
     pub api_key: Option<String>,
+    /// Path to a file holding the auth token, checked ahead of `AUTH_TOKEN`
+    /// and `api_key` by `crate::security::secrets::resolve_auth_token` (see
+    /// `ServerConfig::to_auth_config`), so the token can be rotated without
+    /// a config edit or restart and never needs to live in the config file
+    /// itself.
+    #[serde(default)]
+    pub auth_token_file: Option<String>,
     pub admin_operations_require_auth: bool,
     pub rate_limit_requests_per_minute: u32,
     pub rate_limit_burst: u32,
@@ -48,8 +71,88 @@ pub struct EmbeddingConfig {
     pub model_size: String, // "small", "medium", "large"
     pub dimensions: usize,
     pub batch_size: usize,
+    /// Upper bound on estimated tokens (see `EmbeddingQueue::estimate_tokens`)
+    /// packed into a single `BatchProcessor`/`OnnxEmbeddingEngine` inference
+    /// call, applied before `batch_size`'s item-count cap. Texts are grouped
+    /// to fill this budget rather than split into fixed-size chunks, so
+    /// throughput doesn't suffer when text lengths vary widely.
+    #[serde(default = "default_max_tokens_per_batch")]
+    pub max_tokens_per_batch: usize,
     pub cache_size: usize,
     pub onnx_threads: Option<usize>,
+    /// Which `EmbeddingStore` backend the persistent embedding cache uses.
+    /// `Sqlite` (default) reuses the main database file (`database_path`); `Lmdb` opens its own
+    /// environment directory via `ServerConfig::embedding_cache_path`.
+    #[serde(default)]
+    pub cache_backend: crate::embeddings::EmbeddingCacheBackend,
+    /// When set, `LocalEmbeddingEngine::similarity`/`semantic_search` compare
+    /// via `embeddings::cosine_similarity_q8` on int8-quantized vectors
+    /// (see `embeddings::QuantizedEmbedding`) instead of the full f32 path,
+    /// trading a small precision loss for ~4x smaller cached vectors. Off
+    /// by default since it's a deliberate accuracy/footprint tradeoff, not
+    /// a pure win.
+    #[serde(default)]
+    pub quantized_cache: bool,
+    /// Which `EmbeddingProvider` backend to build at startup.
+    #[serde(default)]
+    pub provider: crate::embeddings::EmbeddingProviderKind,
+    /// Base URL for the `Ollama`/`OpenAiCompatible` providers. Ignored for `Local`.
+    #[serde(default)]
+    pub remote_base_url: Option<String>,
+    /// API key for the `OpenAiCompatible` provider, if the endpoint requires one.
+    #[serde(default)]
+    pub remote_api_key: Option<String>,
+    /// Optional path to a TOML file of `[[model]]` entries (see
+    /// `model_registry::ModelSpec`) to add or override in the
+    /// `ModelRegistry` alongside its built-ins, so a deployment can point
+    /// `model_name` at its own ONNX export without a rebuild.
+    #[serde(default)]
+    pub models_file: Option<PathBuf>,
+    /// Chunks non-code documents with `context::FastCdcChunker` (content-
+    /// defined boundaries) instead of `ContextWindowManager`'s fixed-size
+    /// windowing, so edits elsewhere in a file don't shift every chunk
+    /// boundary downstream and an unchanged region hits the digest-keyed
+    /// cache instead of being re-embedded. Off by default to keep existing
+    /// deployments' chunk boundaries (and anything already cached against
+    /// them) stable.
+    #[serde(default)]
+    pub cdc_chunking_enabled: bool,
+    #[serde(default = "default_cdc_min_chunk_size")]
+    pub cdc_min_chunk_size: usize,
+    #[serde(default = "default_cdc_target_chunk_size")]
+    pub cdc_target_chunk_size: usize,
+    #[serde(default = "default_cdc_max_chunk_size")]
+    pub cdc_max_chunk_size: usize,
+    /// Which `EmbeddingMetricsExporter` backend `LocalEmbeddingEngine`
+    /// reports encode latency, cache hit/miss, and model-load timing
+    /// through. `Noop` (default) costs nothing until an operator opts in.
+    #[serde(default)]
+    pub metrics_exporter: crate::metrics::EmbeddingMetricsExporterKind,
+}
+
+fn default_cdc_min_chunk_size() -> usize {
+    2 * 1024
+}
+
+fn default_cdc_target_chunk_size() -> usize {
+    8 * 1024
+}
+
+fn default_cdc_max_chunk_size() -> usize {
+    32 * 1024
+}
+
+impl EmbeddingConfig {
+    /// Builds the `context::CdcConfig` `ContextWindowConfig::cdc_config`
+    /// expects from this config's chunk-size knobs, for callers wiring up
+    /// `CodebaseIndexer::new_with_mcp_config`.
+    pub fn cdc_config(&self) -> crate::context::CdcConfig {
+        crate::context::CdcConfig {
+            min_size: self.cdc_min_chunk_size,
+            normal_size: self.cdc_target_chunk_size,
+            max_size: self.cdc_max_chunk_size,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +162,26 @@ pub struct SearchConfig {
     pub enable_hybrid_search: bool,
     pub text_search_weight: f32,
     pub vector_search_weight: f32,
+    /// How `HybridSearchEngine` combines text and vector rankings. RRF is
+    /// scale-free and a good default when BM25 scores and cosine
+    /// similarities aren't directly comparable; weighted-sum variants let
+    /// callers hand-tune per-modality weighting instead.
+    #[serde(default)]
+    pub fusion_algorithm: crate::search::FusionAlgorithm,
+    /// `k` constant for `FusionAlgorithm::ReciprocalRankFusion`'s `1/(k + rank)` term.
+    #[serde(default = "default_rrf_k")]
+    pub rrf_k: f32,
+}
+
+fn default_rrf_k() -> f32 {
+    60.0
+}
+
+/// Matches `EmbeddingQueue::default()`'s target, comfortably under the
+/// 512-token sequence limits of the small sentence-embedding models this
+/// crate bundles while still packing dozens of short texts per batch.
+fn default_max_tokens_per_batch() -> usize {
+    8192
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +192,18 @@ pub struct MemoryConfig {
     pub compression_enabled: bool,
 }
 
+/// Per-tool token-bucket limits for `handle_tool_request`. Each tool gets its
+/// own bucket (capacity `default_capacity`, refilling at `default_refill_per_sec`
+/// tokens/sec) and draws `tool_costs.get(tool_name)` tokens per call, falling
+/// back to 1.0 for tools with no entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRateLimitConfig {
+    pub enabled: bool,
+    pub default_capacity: f64,
+    pub default_refill_per_sec: f64,
+    pub tool_costs: std::collections::HashMap<String, f64>,
+}
+
 impl ServerConfig {
     pub fn get_default_log_path() -> PathBuf {
         dirs::home_dir()
@@ -92,6 +227,26 @@ impl ServerConfig {
         // Configuration loaded from file or defaults
         // CLI overrides would be applied here if Cli was available
 
+        // Environment variables win over the config file, matching the
+        // CLI utilities' own flag → env var → default layering (see
+        // `env_layer`) so `KG_DATABASE_URL`/`MCP_PORT` set once target the
+        // same database/port everywhere without per-command flags.
+        if let Ok(database_url) = std::env::var("KG_DATABASE_URL") {
+            let path = PathBuf::from(&database_url);
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                config.data_dir = parent.to_path_buf();
+            }
+            if let Some(filename) = path.file_name() {
+                config.database.filename = filename.to_string_lossy().to_string();
+            }
+        }
+
+        if let Ok(port) = std::env::var("MCP_PORT") {
+            if let Ok(port) = port.parse() {
+                config.port = port;
+            }
+        }
+
         // Ensure directories exist
         std::fs::create_dir_all(&config.data_dir)
             .with_context(|| format!("Failed to create data directory: {}", config.data_dir.display()))?;
@@ -106,11 +261,31 @@ impl ServerConfig {
         self.data_dir.join(&self.database.filename)
     }
 
+    /// Where `embeddings::open_embedding_store` roots the embedding-cache
+    /// backend `embeddings.cache_backend` selects: the existing SQLite
+    /// database file for `Sqlite` (the cache is just another table in it),
+    /// or a dedicated environment directory for `Lmdb`, which can't share a
+    /// single file with SQLite's own storage.
+    pub fn embedding_cache_path(&self) -> PathBuf {
+        match self.embeddings.cache_backend {
+            crate::embeddings::EmbeddingCacheBackend::Sqlite => self.database_path(),
+            crate::embeddings::EmbeddingCacheBackend::Lmdb => self.data_dir.join("embedding_cache.lmdb"),
+        }
+    }
+
     /// Validate configuration settings
     pub fn validate(&self) -> Result<()> {
         // Validate security settings
-        if self.security.enable_authentication && self.security.api_key.is_none() {
-            return Err(anyhow::anyhow!("API key must be set when authentication is enabled"));
+        if self.security.enable_authentication {
+            let token = crate::security::secrets::resolve_auth_token(
+                self.security.auth_token_file.as_deref().map(Path::new),
+                self.security.api_key.as_deref(),
+            ).context("Failed to resolve auth token")?;
+            if token.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Authentication is enabled but no secret source resolved (set security.auth_token_file, the AUTH_TOKEN environment variable, or security.api_key)"
+                ));
+            }
         }
 
         if self.security.rate_limit_requests_per_minute == 0 {
@@ -135,6 +310,19 @@ impl ServerConfig {
             return Err(anyhow::anyhow!("Embedding batch size must be greater than 0"));
         }
 
+        // A remote provider talking to the real OpenAI API (no explicit
+        // `remote_base_url`, so `create_embedding_provider` falls back to
+        // api.openai.com) needs an API key, or every request will fail
+        // authentication at connect time instead of at startup.
+        if self.embeddings.provider == crate::embeddings::EmbeddingProviderKind::OpenAiCompatible
+            && self.embeddings.remote_base_url.is_none()
+            && self.embeddings.remote_api_key.is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "embeddings.provider is \"open_ai_compatible\" targeting the default OpenAI endpoint, but embeddings.remote_api_key is not set"
+            ));
+        }
+
         // Validate search settings
         if self.search.similarity_threshold < 0.0 || self.search.similarity_threshold > 1.0 {
             return Err(anyhow::anyhow!("Similarity threshold must be between 0.0 and 1.0"));
@@ -143,15 +331,34 @@ impl ServerConfig {
         Ok(())
     }
 
-    /// Convert security config to AuthConfig
-    pub fn to_auth_config(&self) -> AuthConfig {
-        AuthConfig {
+    /// Convert security config to AuthConfig, resolving the shared key
+    /// through `security::secrets::resolve_auth_token` (token file ->
+    /// `AUTH_TOKEN` -> the legacy inline `api_key` field) rather than
+    /// reading `api_key` directly.
+    pub fn to_auth_config(&self) -> Result<AuthConfig> {
+        let api_key = crate::security::secrets::resolve_auth_token(
+            self.security.auth_token_file.as_deref().map(Path::new),
+            self.security.api_key.as_deref(),
+        )?;
+
+        Ok(AuthConfig {
             enabled: self.security.enable_authentication,
-            api_key: self.security.api_key.clone(),
+            api_key,
+            // `SecurityConfig` has no notion of a per-key registry yet,
+            // so every request falls back to the single shared key above.
+            api_keys: std::collections::HashMap::new(),
             rate_limit_requests_per_minute: self.security.rate_limit_requests_per_minute,
             rate_limit_burst: self.security.rate_limit_burst,
+            // `SecurityConfig` has no notion of per-operation-class tiers
+            // yet, so every class falls back to the flat limit above.
+            rate_limit_tiers: std::collections::HashMap::new(),
+            // `SecurityConfig` has no override for this either; /64 matches
+            // `AuthConfig::default()`'s own fallback.
+            ipv6_rate_limit_prefix_len: 64,
+            rate_limit_cleanup_interval_secs: 60,
+            rate_limit_cleanup_threshold_secs: 300,
             admin_operations_require_auth: self.security.admin_operations_require_auth,
-        }
+        })
     }
 }
 
@@ -172,6 +379,9 @@ impl Default for ServerConfig {
             search: SearchConfig::default(),
             memory: MemoryConfig::default(),
             security: SecurityConfig::default(),
+            tool_rate_limit: ToolRateLimitConfig::default(),
+            watcher: crate::indexing::WatcherConfig::default(),
+            search_queue_size: default_search_queue_size(),
         }
     }
 }
@@ -194,8 +404,20 @@ impl Default for EmbeddingConfig {
             model_size: "medium".to_string(),
             dimensions: 768,
             batch_size: 32,
+            max_tokens_per_batch: default_max_tokens_per_batch(),
             cache_size: 1000,
             onnx_threads: None, // Use system default
+            cache_backend: crate::embeddings::EmbeddingCacheBackend::default(),
+            quantized_cache: false,
+            provider: crate::embeddings::EmbeddingProviderKind::default(),
+            remote_base_url: None,
+            remote_api_key: None,
+            models_file: None,
+            cdc_chunking_enabled: false,
+            cdc_min_chunk_size: default_cdc_min_chunk_size(),
+            cdc_target_chunk_size: default_cdc_target_chunk_size(),
+            cdc_max_chunk_size: default_cdc_max_chunk_size(),
+            metrics_exporter: crate::metrics::EmbeddingMetricsExporterKind::default(),
         }
     }
 }
@@ -208,6 +430,8 @@ impl Default for SearchConfig {
             enable_hybrid_search: true,
             text_search_weight: 0.3,
             vector_search_weight: 0.7,
+            fusion_algorithm: crate::search::FusionAlgorithm::default(),
+            rrf_k: default_rrf_k(),
         }
     }
 }
@@ -223,11 +447,37 @@ impl Default for MemoryConfig {
     }
 }
 
+impl Default for ToolRateLimitConfig {
+    fn default() -> Self {
+        let mut tool_costs = std::collections::HashMap::new();
+        // Embedding generation is the expensive path; weight it accordingly
+        // relative to a plain (often cache-hit) search.
+        tool_costs.insert("mcp_kg-mcp-server_add_memory".to_string(), 5.0);
+        tool_costs.insert("mcp_kg-mcp-server_index_codebase".to_string(), 5.0);
+        tool_costs.insert("mcp_kg-mcp-server_search_memory".to_string(), 1.0);
+        tool_costs.insert("mcp_kg-mcp-server_manage_workers".to_string(), 1.0);
+        tool_costs.insert("mcp_kg-mcp-server_manage_api_keys".to_string(), 5.0);
+        // A batch can bundle several embedding-heavy add_memory items, so it
+        // draws from the bucket like one, not like a single cheap search.
+        tool_costs.insert("mcp_kg-mcp-server_batch".to_string(), 5.0);
+        tool_costs.insert("mcp_kg-mcp-server_manage_ingestion".to_string(), 1.0);
+        tool_costs.insert("mcp_kg-mcp-server_admin_metrics".to_string(), 1.0);
+
+        Self {
+            enabled: true,
+            default_capacity: 20.0,
+            default_refill_per_sec: 5.0,
+            tool_costs,
+        }
+    }
+}
+
 impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
             enable_authentication: false,
             api_key: None,
+            auth_token_file: None,
             admin_operations_require_auth: true,
             rate_limit_requests_per_minute: 60,
             rate_limit_burst: 10,
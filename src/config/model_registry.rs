@@ -0,0 +1,203 @@
+//! Registry of known embedding models.
+//!
+//! Seeded at construction with the three models this crate has always
+//! shipped built-in support for, then optionally extended or overridden by
+//! an external TOML file (`ServerConfig::embeddings::models_file`) so a
+//! deployment can register its own ONNX export without a rebuild.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::defaults;
+
+/// How `OnnxEmbeddingEngine` should reduce a token sequence's hidden states
+/// down to a single embedding vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolingStrategy {
+    /// Mean of the token embeddings, masked to exclude padding. What every
+    /// built-in model uses today.
+    #[default]
+    Mean,
+    /// The `[CLS]` token's embedding alone.
+    Cls,
+}
+
+/// Everything needed to download, validate, and run one embedding model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSpec {
+    pub name: String,
+    pub model_url: String,
+    pub tokenizer_url: String,
+    pub config_url: String,
+    pub dimensions: usize,
+    #[serde(default = "default_max_sequence_length")]
+    pub max_sequence_length: usize,
+    #[serde(default)]
+    pub pooling: PoolingStrategy,
+    /// Git revision/commit each `*_url`'s `/resolve/main/` path segment is
+    /// rewritten to by `resolve_url`, instead of always tracking `main`'s
+    /// moving target. Defaults to `main` for URLs that don't pin one.
+    #[serde(default = "default_revision")]
+    pub revision: String,
+    /// Expected SHA-256 of the downloaded `model.onnx`, checked by
+    /// `ModelManager::download_model` after fetching it; `None` skips
+    /// verification, e.g. for a model registered before its maintainer has
+    /// pinned one.
+    #[serde(default)]
+    pub model_sha256: Option<String>,
+    /// Expected SHA-256 of the downloaded `tokenizer.json`.
+    #[serde(default)]
+    pub tokenizer_sha256: Option<String>,
+    /// Expected SHA-256 of the downloaded `config.json`.
+    #[serde(default)]
+    pub config_sha256: Option<String>,
+}
+
+fn default_max_sequence_length() -> usize {
+    defaults::DEFAULT_MAX_SEQUENCE_LENGTH
+}
+
+fn default_revision() -> String {
+    "main".to_string()
+}
+
+impl ModelSpec {
+    /// Rewrites `url`'s `/resolve/main/` segment to `/resolve/<revision>/`
+    /// when `revision` isn't the default `main`, so a pinned model actually
+    /// fetches from that commit instead of whatever `main` currently points
+    /// at. A no-op for URLs that don't contain that segment (e.g. a custom
+    /// model registered with an already-revisioned or non-HuggingFace URL).
+    pub fn resolve_url(&self, url: &str) -> String {
+        if self.revision == "main" {
+            url.to_string()
+        } else {
+            url.replacen("/resolve/main/", &format!("/resolve/{}/", self.revision), 1)
+        }
+    }
+}
+
+/// On-disk shape of an external models file: one `[[model]]` table per
+/// entry, each deserializing as a `ModelSpec`.
+#[derive(Debug, Deserialize)]
+struct ModelsFile {
+    #[serde(default)]
+    model: Vec<ModelSpec>,
+}
+
+/// Known embedding models, keyed by name. Always contains the built-ins;
+/// `load` additionally merges in an external models file, if given, letting
+/// its entries add new models or override a built-in by name.
+#[derive(Debug, Clone, Default)]
+pub struct ModelRegistry {
+    models: HashMap<String, ModelSpec>,
+}
+
+impl ModelRegistry {
+    /// The registry seeded with just the built-in models.
+    pub fn with_builtins() -> Self {
+        let mut models = HashMap::new();
+        for spec in builtin_models() {
+            models.insert(spec.name.clone(), spec);
+        }
+        Self { models }
+    }
+
+    /// Built-ins, plus whatever `models_file` adds or overrides, if set.
+    pub fn load(models_file: Option<&Path>) -> Result<Self> {
+        let mut registry = Self::with_builtins();
+        if let Some(path) = models_file {
+            registry.merge_file(path)?;
+        }
+        Ok(registry)
+    }
+
+    fn merge_file(&mut self, path: &Path) -> Result<()> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read models file: {}", path.display()))?;
+        let file: ModelsFile = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse models file: {}", path.display()))?;
+        for spec in file.model {
+            self.models.insert(spec.name.clone(), spec);
+        }
+        Ok(())
+    }
+
+    /// Looks up a model by name, surfacing a clear error (rather than
+    /// `None`) for an unregistered one, since this is always on a path
+    /// where the caller has nothing sensible to fall back to.
+    pub fn get(&self, name: &str) -> Result<&ModelSpec> {
+        self.models
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown embedding model: {}", name))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.models.contains_key(name)
+    }
+
+    /// Registered model names, for listing to callers (e.g. `manage_ingestion`-style tools).
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.models.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Adds `spec` to the registry, or overwrites an existing entry of the
+    /// same name.
+    pub fn register(&mut self, spec: ModelSpec) {
+        self.models.insert(spec.name.clone(), spec);
+    }
+}
+
+fn builtin_models() -> Vec<ModelSpec> {
+    vec![
+        ModelSpec {
+            name: "nomic-embed-text-v1.5".to_string(),
+            model_url: defaults::DEFAULT_NOMIC_MODEL_URL.to_string(),
+            tokenizer_url: defaults::DEFAULT_NOMIC_TOKENIZER_URL.to_string(),
+            config_url: defaults::DEFAULT_NOMIC_CONFIG_URL.to_string(),
+            dimensions: defaults::NOMIC_DIMENSIONS,
+            max_sequence_length: defaults::DEFAULT_MAX_SEQUENCE_LENGTH,
+            pooling: PoolingStrategy::Mean,
+            revision: default_revision(),
+            // Not yet pinned to a verified hash; set one (e.g. via an
+            // external models file) to enable checksum enforcement.
+            model_sha256: None,
+            tokenizer_sha256: None,
+            config_sha256: None,
+        },
+        ModelSpec {
+            name: "all-MiniLM-L6-v2".to_string(),
+            model_url: defaults::DEFAULT_MINILM_MODEL_URL.to_string(),
+            tokenizer_url: defaults::DEFAULT_MINILM_TOKENIZER_URL.to_string(),
+            config_url: defaults::DEFAULT_MINILM_CONFIG_URL.to_string(),
+            dimensions: defaults::MINILM_DIMENSIONS,
+            max_sequence_length: defaults::DEFAULT_MAX_SEQUENCE_LENGTH,
+            pooling: PoolingStrategy::Mean,
+            revision: default_revision(),
+            // Not yet pinned to a verified hash; set one (e.g. via an
+            // external models file) to enable checksum enforcement.
+            model_sha256: None,
+            tokenizer_sha256: None,
+            config_sha256: None,
+        },
+        ModelSpec {
+            name: "bge-m3".to_string(),
+            model_url: defaults::DEFAULT_BGE_MODEL_URL.to_string(),
+            tokenizer_url: defaults::DEFAULT_BGE_TOKENIZER_URL.to_string(),
+            config_url: defaults::DEFAULT_BGE_CONFIG_URL.to_string(),
+            dimensions: defaults::BGE_DIMENSIONS,
+            max_sequence_length: defaults::DEFAULT_MAX_SEQUENCE_LENGTH,
+            pooling: PoolingStrategy::Mean,
+            revision: default_revision(),
+            // Not yet pinned to a verified hash; set one (e.g. via an
+            // external models file) to enable checksum enforcement.
+            model_sha256: None,
+            tokenizer_sha256: None,
+            config_sha256: None,
+        },
+    ]
+}
@@ -0,0 +1,134 @@
+//! FastCDC-style content-defined chunking.
+//!
+//! Unlike `fixed_chunk`'s byte-offset windowing, cut points here are a
+//! function of the content itself, so inserting or deleting bytes only
+//! re-chunks the region around the edit instead of shifting every boundary
+//! downstream. Combined with content-hash dedup in `ContextWindowManager`,
+//! this is what lets repeated/boilerplate content (generated files, vendored
+//! code, license headers) reuse a single stored chunk instead of paying for
+//! a new embedding per copy.
+//!
+//! Implements the normalized-chunking variant of FastCDC (Xia et al.): a
+//! gear-hash rolling fingerprint is checked against a stricter mask below
+//! `normal_size` (fewer cut points, so tiny chunks past `min_size` keep
+//! growing towards the average) and a looser mask between `normal_size` and
+//! `max_size` (more cut points, pulling the boundary back towards the
+//! average instead of drifting all the way to `max_size`).
+
+use std::sync::OnceLock;
+
+/// Fixed seed for the gear table. Cut points must be reproducible across
+/// runs and processes (two copies of the same content need to hash to the
+/// same chunk boundaries), so this is deterministic rather than time-seeded.
+const GEAR_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+fn gear_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        // xorshift64, same PRNG used elsewhere in this crate for
+        // deterministic pseudo-random sequences without a `rand` dependency.
+        let mut state = GEAR_SEED;
+        let mut table = [0u64; 256];
+        for entry in table.iter_mut() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *entry = state;
+        }
+        table
+    })
+}
+
+/// Size thresholds for normalized chunking, in bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct CdcConfig {
+    /// No cut point is ever emitted before this many bytes into a chunk.
+    pub min_size: usize,
+    /// The average chunk size the two gear-hash masks are normalized around.
+    pub normal_size: usize,
+    /// A cut is forced here if the gear hash never satisfies the mask.
+    pub max_size: usize,
+}
+
+impl Default for CdcConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 2 * 1024,
+            normal_size: 8 * 1024,
+            max_size: 32 * 1024,
+        }
+    }
+}
+
+fn leading_ones_mask(ones: u32) -> u64 {
+    if ones == 0 {
+        0
+    } else if ones >= 64 {
+        u64::MAX
+    } else {
+        ((1u64 << ones) - 1) << (64 - ones)
+    }
+}
+
+/// Gear-hash content-defined chunker.
+pub struct FastCdcChunker {
+    config: CdcConfig,
+    mask_s: u64,
+    mask_l: u64,
+}
+
+impl FastCdcChunker {
+    pub fn new(config: CdcConfig) -> Self {
+        let avg_bits = (config.normal_size.max(2) as f64).log2().round() as u32;
+        let mask_s = leading_ones_mask(avg_bits + 1);
+        let mask_l = leading_ones_mask(avg_bits.saturating_sub(1).max(1));
+        Self { config, mask_s, mask_l }
+    }
+
+    /// Length of the next chunk to cut from the start of `data`.
+    fn next_cut_len(&self, data: &[u8]) -> usize {
+        let len = data.len();
+        if len <= self.config.min_size {
+            return len;
+        }
+
+        let table = gear_table();
+        let normal = self.config.normal_size.min(len);
+        let max = self.config.max_size.min(len);
+
+        let mut fp: u64 = 0;
+        for &byte in &data[..self.config.min_size] {
+            fp = (fp << 1).wrapping_add(table[byte as usize]);
+        }
+
+        let mut i = self.config.min_size;
+        while i < normal {
+            fp = (fp << 1).wrapping_add(table[data[i] as usize]);
+            if fp & self.mask_s == 0 {
+                return i + 1;
+            }
+            i += 1;
+        }
+        while i < max {
+            fp = (fp << 1).wrapping_add(table[data[i] as usize]);
+            if fp & self.mask_l == 0 {
+                return i + 1;
+            }
+            i += 1;
+        }
+        max
+    }
+
+    /// Absolute byte offsets of every cut point in `data`, always ending
+    /// with `data.len()`. Offsets are not guaranteed to fall on UTF-8 char
+    /// boundaries; callers slicing `str` content must snap them first.
+    pub fn cut_points(&self, data: &[u8]) -> Vec<usize> {
+        let mut points = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            start += self.next_cut_len(&data[start..]);
+            points.push(start);
+        }
+        points
+    }
+}
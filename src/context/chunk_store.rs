@@ -0,0 +1,289 @@
+//! SQLite-backed persistence for `ContextChunk`s, so a context window built
+//! up over a codebase survives process/editor restarts instead of living
+//! only in `ContextWindowManager`'s `Arc<RwLock<HashMap>>`.
+//!
+//! Mirrors `PersistentEmbeddingCache`'s pattern of a `rusqlite::Connection`
+//! behind a blocking `Mutex`, accessed from `async fn`s without a dedicated
+//! blocking pool — consistent with how the rest of this crate talks to
+//! SQLite.
+//!
+//! Scope note: this gives `ContextWindowManager` a durable store with lazy
+//! by-id retrieval (`get`) and content-hash lookup for incremental reindexing,
+//! and lets eviction spill cold chunks to disk instead of discarding them.
+//! `get_context_for_query`'s relevance pass still scores the in-memory
+//! resident set rather than streaming the full persisted set row-by-row —
+//! doing that without risking the existing scoring/ranking behavior would
+//! need a larger rework than this change makes.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{params, Connection, OpenFlags};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use super::window_manager::{ChunkType, ContextChunk};
+
+/// The subset of a chunk's fields needed to rebuild `ContextWindowManager`'s
+/// in-memory indices (priority queue, file map) without pulling every
+/// chunk's full content and embedding into RAM on startup.
+#[derive(Debug, Clone)]
+pub struct ChunkIndexEntry {
+    pub id: Uuid,
+    pub source_file: Option<String>,
+    pub chunk_type: ChunkType,
+    pub priority: f32,
+}
+
+#[derive(Clone)]
+pub struct ChunkStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl ChunkStore {
+    /// Opens (creating if necessary) the on-disk chunk database at `db_path`.
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create chunk store directory {}", parent.display()))?;
+        }
+
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open chunk store database: {}", db_path.display()))?;
+
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS context_chunks (
+                id TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                source_file TEXT,
+                start_line INTEGER,
+                end_line INTEGER,
+                chunk_type TEXT NOT NULL,
+                priority REAL NOT NULL,
+                relevance_score REAL NOT NULL,
+                last_accessed INTEGER NOT NULL,
+                access_count INTEGER NOT NULL,
+                embedding BLOB,
+                metadata TEXT NOT NULL,
+                content_hash TEXT,
+                resident INTEGER NOT NULL DEFAULT 1,
+                diagnostics TEXT NOT NULL DEFAULT '[]',
+                symbols TEXT NOT NULL DEFAULT '{\"defined\":[],\"referenced\":[]}'
+            );
+            CREATE INDEX IF NOT EXISTS idx_context_chunks_source_file ON context_chunks (source_file);
+            CREATE INDEX IF NOT EXISTS idx_context_chunks_content_hash ON context_chunks (content_hash);
+            ",
+        )?;
+
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// Inserts or replaces a chunk's row, marking it resident (in-memory).
+    /// `content_hash` is the hex SHA-256 used for incremental-reindex lookups
+    /// (`find_by_content_hash`); pass `None` when the caller doesn't track one.
+    pub async fn upsert(&self, chunk: &ContextChunk, content_hash: Option<&str>) -> Result<()> {
+        let metadata = serde_json::to_string(&chunk.metadata)?;
+        let diagnostics = serde_json::to_string(&chunk.diagnostics)?;
+        let symbols = serde_json::to_string(&chunk.symbols)?;
+        let embedding = chunk.embedding.as_ref().map(|v| encode_vector(v));
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO context_chunks
+                (id, content, source_file, start_line, end_line, chunk_type, priority, relevance_score,
+                 last_accessed, access_count, embedding, metadata, content_hash, resident, diagnostics, symbols)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, 1, ?14, ?15)",
+            params![
+                chunk.id.to_string(),
+                chunk.content,
+                chunk.source_file,
+                chunk.start_line.map(|v| v as i64),
+                chunk.end_line.map(|v| v as i64),
+                format!("{:?}", chunk.chunk_type),
+                chunk.priority,
+                chunk.relevance_score,
+                chunk.last_accessed.timestamp_millis(),
+                chunk.access_count as i64,
+                embedding,
+                metadata,
+                content_hash,
+                diagnostics,
+                symbols,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Loads every resident chunk's index fields (not content/embedding), for
+    /// rebuilding the manager's priority queue and file map on startup.
+    /// Spilled rows (`resident = 0`) are not included.
+    pub async fn load_index(&self) -> Result<Vec<ChunkIndexEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, source_file, chunk_type, priority FROM context_chunks WHERE resident = 1",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let id: String = row.get(0)?;
+            let chunk_type: String = row.get(2)?;
+            Ok((id, row.get::<_, Option<String>>(1)?, chunk_type, row.get::<_, f64>(3)?))
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let (id, source_file, chunk_type, priority) = row?;
+            let Ok(id) = Uuid::parse_str(&id) else { continue };
+            entries.push(ChunkIndexEntry {
+                id,
+                source_file,
+                chunk_type: parse_chunk_type(&chunk_type),
+                priority: priority as f32,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Fetches a chunk's full row (content + embedding) by id, re-marking it
+    /// resident so a spilled chunk is promoted back on access. Returns `None`
+    /// if the id was never stored.
+    pub async fn get(&self, id: Uuid) -> Result<Option<ContextChunk>> {
+        let conn = self.conn.lock().unwrap();
+        let chunk = conn
+            .query_row(
+                "SELECT content, source_file, start_line, end_line, chunk_type, priority, relevance_score,
+                        last_accessed, access_count, embedding, metadata, diagnostics, symbols
+                 FROM context_chunks WHERE id = ?1",
+                params![id.to_string()],
+                |row| {
+                    let embedding: Option<Vec<u8>> = row.get(9)?;
+                    let metadata: String = row.get(10)?;
+                    let diagnostics: String = row.get(11)?;
+                    let symbols: String = row.get(12)?;
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                        row.get::<_, Option<i64>>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, f64>(5)?,
+                        row.get::<_, f64>(6)?,
+                        row.get::<_, i64>(7)?,
+                        row.get::<_, i64>(8)?,
+                        embedding,
+                        metadata,
+                        diagnostics,
+                        symbols,
+                    ))
+                },
+            )
+            .ok();
+
+        let Some((content, source_file, start_line, end_line, chunk_type, priority, relevance_score,
+            last_accessed, access_count, embedding, metadata, diagnostics, symbols)) = chunk
+        else {
+            return Ok(None);
+        };
+
+        conn.execute(
+            "UPDATE context_chunks SET resident = 1, access_count = access_count + 1, last_accessed = ?1 WHERE id = ?2",
+            params![now_millis(), id.to_string()],
+        )?;
+
+        Ok(Some(ContextChunk {
+            id,
+            content,
+            source_file,
+            start_line: start_line.map(|v| v as usize),
+            end_line: end_line.map(|v| v as usize),
+            chunk_type: parse_chunk_type(&chunk_type),
+            priority: priority as f32,
+            relevance_score: relevance_score as f32,
+            last_accessed: millis_to_datetime(last_accessed),
+            access_count: access_count as u32,
+            embedding: embedding.map(|bytes| decode_vector(&bytes)),
+            metadata: serde_json::from_str(&metadata).unwrap_or_default(),
+            diagnostics: serde_json::from_str(&diagnostics).unwrap_or_default(),
+            symbols: serde_json::from_str(&symbols).unwrap_or_default(),
+        }))
+    }
+
+    /// Marks a chunk non-resident without deleting its row — the content and
+    /// embedding stay on disk so `get` can re-promote it later.
+    pub async fn mark_spilled(&self, id: Uuid) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE context_chunks SET resident = 0 WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Looks up an existing chunk id by content hash, for incremental
+    /// reindexing (skip re-embedding content whose hash is unchanged).
+    pub async fn find_by_content_hash(&self, content_hash: &str) -> Result<Option<Uuid>> {
+        let conn = self.conn.lock().unwrap();
+        let id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM context_chunks WHERE content_hash = ?1",
+                params![content_hash],
+                |row| row.get(0),
+            )
+            .ok();
+        Ok(id.and_then(|id| Uuid::parse_str(&id).ok()))
+    }
+
+    /// Permanently removes a chunk's row (full eviction, not a spill).
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM context_chunks WHERE id = ?1", params![id.to_string()])?;
+        Ok(())
+    }
+
+    /// Number of rows currently stored, resident or spilled.
+    pub async fn len(&self) -> usize {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM context_chunks", [], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .unwrap_or(0)
+    }
+}
+
+fn parse_chunk_type(raw: &str) -> ChunkType {
+    match raw {
+        "Code" => ChunkType::Code,
+        "Documentation" => ChunkType::Documentation,
+        "Configuration" => ChunkType::Configuration,
+        "Test" => ChunkType::Test,
+        "Comment" => ChunkType::Comment,
+        "Import" => ChunkType::Import,
+        "Function" => ChunkType::Function,
+        "Class" => ChunkType::Class,
+        "Variable" => ChunkType::Variable,
+        "Error" => ChunkType::Error,
+        "Log" => ChunkType::Log,
+        _ => ChunkType::Code,
+    }
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().expect("chunk is exactly 4 bytes")))
+        .collect()
+}
+
+fn now_millis() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+fn millis_to_datetime(millis: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(millis).single().unwrap_or_else(Utc::now)
+}
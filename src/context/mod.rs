@@ -1,9 +1,16 @@
+pub mod cdc;
+pub mod chunk_store;
 pub mod window_manager;
 
+pub use cdc::{CdcConfig, FastCdcChunker};
+pub use chunk_store::{ChunkIndexEntry, ChunkStore};
 pub use window_manager::{
     ContextWindowManager,
     ContextWindowConfig,
     ContextChunk,
     ChunkType,
     ContextWindowStats,
+    Diagnostic,
+    DiagnosticSeverity,
+    ChunkSymbols,
 }; 
\ No newline at end of file
@@ -1,10 +1,15 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::{HashMap, VecDeque, BTreeMap};
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use tracing::{debug, info, warn};
 
+use crate::context::cdc::{CdcConfig, FastCdcChunker};
+use crate::context::chunk_store::ChunkStore;
 use crate::graph::{KGNode, KGEdge, Episode};
 use crate::embeddings::LocalEmbeddingEngine;
 
@@ -19,6 +24,16 @@ pub struct ContextWindowConfig {
     pub max_chunks_per_file: usize,
     pub adaptive_chunking: bool,
     pub preserve_code_blocks: bool,
+    pub tokenizer_encoding: TokenizerEncoding,
+    /// When set, `add_content` cuts chunks with FastCDC content-defined
+    /// chunking (see `crate::context::cdc`) instead of `adaptive_chunking`'s
+    /// structural windowing, enabling content-hash dedup of repeated chunks.
+    pub cdc_config: Option<CdcConfig>,
+    /// Path to a SQLite database backing this window's chunks. When set,
+    /// `ContextWindowManager::new` opens (creating if needed) a `ChunkStore`
+    /// there; call `restore_from_store` afterwards to repopulate the
+    /// in-memory indices from a previous run.
+    pub persistence_path: Option<PathBuf>,
 }
 
 impl Default for ContextWindowConfig {
@@ -32,10 +47,26 @@ impl Default for ContextWindowConfig {
             max_chunks_per_file: 50,
             adaptive_chunking: true,
             preserve_code_blocks: true,
+            tokenizer_encoding: TokenizerEncoding::Heuristic,
+            cdc_config: None,
+            persistence_path: None,
         }
     }
 }
 
+/// Which tokenizer backs `TokenEstimator::estimate_tokens`, so callers can
+/// make token budgeting match the target model exactly instead of trusting
+/// the chars/4 heuristic. `Bpe` loads the same `tokenizer.json` format
+/// `OnnxEmbeddingEngine` already loads for the embedding model (e.g.
+/// `cl100k_base`-style vocabularies or a model-specific BPE/WordPiece file);
+/// a load failure falls back to `Heuristic` rather than failing startup.
+#[derive(Debug, Clone, Default)]
+pub enum TokenizerEncoding {
+    #[default]
+    Heuristic,
+    Bpe { tokenizer_path: PathBuf },
+}
+
 /// Context chunk with metadata
 #[derive(Debug, Clone)]
 pub struct ContextChunk {
@@ -51,6 +82,42 @@ pub struct ContextChunk {
     pub access_count: u32,
     pub embedding: Option<Vec<f32>>,
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Compiler/LSP diagnostics whose span falls within this chunk, attached
+    /// via `annotate_chunk`. Empty unless a caller feeds diagnostics in.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Symbols defined and referenced in this chunk, for
+    /// `get_context_for_symbol` and the symbol-match score boost in
+    /// `calculate_final_score`. Populated with a best-effort identifier scan
+    /// at chunk creation and refined by `annotate_chunk` when a caller has
+    /// real tree-sitter captures available (e.g. `CodebaseIndexer`).
+    pub symbols: ChunkSymbols,
+}
+
+/// A single diagnostic (compiler error/warning, LSP hint, ...) overlapping a
+/// chunk's span.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+/// Symbol names associated with a chunk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkSymbols {
+    /// Names this chunk defines (function/class/variable declarations).
+    pub defined: Vec<String>,
+    /// Names this chunk merely refers to.
+    pub referenced: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -77,6 +144,14 @@ pub struct ContextWindowManager {
     embedding_engine: Option<Arc<LocalEmbeddingEngine>>,
     access_history: Arc<RwLock<VecDeque<(Uuid, DateTime<Utc>)>>>,
     token_estimator: TokenEstimator,
+    /// Content hash (SHA-256, matching the hashing convention used elsewhere
+    /// in this crate) -> the id of the first chunk stored with that content,
+    /// for FastCDC dedup.
+    content_hashes: Arc<RwLock<HashMap<String, Uuid>>>,
+    dedup_hits: Arc<RwLock<usize>>,
+    /// Durable backing store for chunks, opened from `config.persistence_path`
+    /// when set. `None` keeps the old in-memory-only behavior.
+    chunk_store: Option<ChunkStore>,
 }
 
 /// Wrapper for f32 to make it orderable
@@ -91,26 +166,122 @@ impl Ord for OrderedFloat {
     }
 }
 
-/// Token estimation for different content types
-struct TokenEstimator;
+/// Token counting backend. The heuristic is a no-dependency fallback; `Bpe`
+/// wraps the `tokenizers` crate (the same BPE/WordPiece backend
+/// `OnnxEmbeddingEngine` loads from `tokenizer.json`) for exact, model-matched
+/// counts.
+trait Tokenizer: Send + Sync {
+    fn encode(&self, text: &str) -> usize;
+}
+
+/// ~4 characters per token (the same rule of thumb `EmbeddingQueue` uses for
+/// batch sizing). Good enough when no real tokenizer is configured, but it
+/// drifts on code and non-ASCII text since it has no notion of actual tokens.
+struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn encode(&self, text: &str) -> usize {
+        (text.len() / 4).max(1)
+    }
+}
+
+struct BpeTokenizer(tokenizers::Tokenizer);
+
+impl BpeTokenizer {
+    fn load(path: &std::path::Path) -> Result<Self> {
+        let tokenizer = tokenizers::Tokenizer::from_file(path)
+            .map_err(|e| anyhow::anyhow!("failed to load tokenizer from {}: {}", path.display(), e))?;
+        Ok(Self(tokenizer))
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn encode(&self, text: &str) -> usize {
+        self.0
+            .encode(text, false)
+            .map(|encoding| encoding.get_ids().len())
+            .unwrap_or_else(|_| (text.len() / 4).max(1))
+    }
+}
+
+/// Token estimation for different content types. Real-tokenizer counts
+/// (`Bpe`) are used as-is; the heuristic fallback keeps the original
+/// per-content-type density multipliers since it has no way to know actual
+/// token density on its own. Counts are cached per chunk id so repeated
+/// scoring passes (e.g. re-ranking in `get_context_for_query`) don't
+/// re-tokenize the same content.
+struct TokenEstimator {
+    tokenizer: Box<dyn Tokenizer>,
+    is_heuristic: bool,
+    cache: RwLock<HashMap<Uuid, usize>>,
+}
 
 impl TokenEstimator {
-    fn estimate_tokens(&self, content: &str, chunk_type: &ChunkType) -> usize {
-        let base_tokens = content.len() / 4; // Rough approximation: 4 chars per token
-        
-        // Adjust based on content type
-        match chunk_type {
-            ChunkType::Code => (base_tokens as f32 * 1.2) as usize, // Code is denser
-            ChunkType::Documentation => base_tokens,
-            ChunkType::Comment => (base_tokens as f32 * 0.8) as usize,
-            ChunkType::Configuration => base_tokens,
-            _ => base_tokens,
+    fn new(encoding: &TokenizerEncoding) -> Self {
+        match encoding {
+            TokenizerEncoding::Heuristic => Self::heuristic(),
+            TokenizerEncoding::Bpe { tokenizer_path } => match BpeTokenizer::load(tokenizer_path) {
+                Ok(tokenizer) => Self {
+                    tokenizer: Box::new(tokenizer),
+                    is_heuristic: false,
+                    cache: RwLock::new(HashMap::new()),
+                },
+                Err(e) => {
+                    warn!("Falling back to heuristic token estimator: {}", e);
+                    Self::heuristic()
+                }
+            },
+        }
+    }
+
+    fn heuristic() -> Self {
+        Self {
+            tokenizer: Box::new(HeuristicTokenizer),
+            is_heuristic: true,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Estimates `content`'s token count, caching the result against
+    /// `chunk_id` when one is available. Callers scoring content that hasn't
+    /// been assigned a chunk id yet (e.g. deciding where to cut a chunk while
+    /// still accumulating it) pass `None` and simply skip the cache.
+    fn estimate_tokens(&self, chunk_id: Option<Uuid>, content: &str, chunk_type: &ChunkType) -> usize {
+        if let Some(id) = chunk_id {
+            if let Some(&cached) = self.cache.read().unwrap().get(&id) {
+                return cached;
+            }
         }
+
+        let base_tokens = self.tokenizer.encode(content);
+        let tokens = if self.is_heuristic {
+            match chunk_type {
+                ChunkType::Code => (base_tokens as f32 * 1.2) as usize, // Code is denser
+                ChunkType::Documentation => base_tokens,
+                ChunkType::Comment => (base_tokens as f32 * 0.8) as usize,
+                ChunkType::Configuration => base_tokens,
+                _ => base_tokens,
+            }
+        } else {
+            base_tokens
+        };
+
+        if let Some(id) = chunk_id {
+            self.cache.write().unwrap().insert(id, tokens);
+        }
+
+        tokens
     }
 }
 
 impl ContextWindowManager {
     pub fn new(config: ContextWindowConfig, embedding_engine: Option<Arc<LocalEmbeddingEngine>>) -> Self {
+        let token_estimator = TokenEstimator::new(&config.tokenizer_encoding);
+        let chunk_store = config.persistence_path.as_deref().and_then(|path| {
+            ChunkStore::open(path)
+                .map_err(|e| warn!("Failed to open chunk store at {}: {}", path.display(), e))
+                .ok()
+        });
         Self {
             config,
             chunks: Arc::new(RwLock::new(HashMap::new())),
@@ -118,13 +289,62 @@ impl ContextWindowManager {
             file_chunks: Arc::new(RwLock::new(HashMap::new())),
             embedding_engine,
             access_history: Arc::new(RwLock::new(VecDeque::new())),
-            token_estimator: TokenEstimator,
+            token_estimator,
+            content_hashes: Arc::new(RwLock::new(HashMap::new())),
+            dedup_hits: Arc::new(RwLock::new(0)),
+            chunk_store,
+        }
+    }
+
+    /// Repopulates the priority queue and file map from a previously opened
+    /// `chunk_store` (see `ContextWindowConfig::persistence_path`), so a
+    /// restarted process resumes with the same durable index instead of
+    /// starting empty. Chunk content/embeddings stay on disk until touched
+    /// through `get_chunk`, which lazily loads and re-promotes them. A no-op
+    /// (returns `Ok(0)`) when no store is configured. Call once after `new`.
+    pub async fn restore_from_store(&self) -> Result<usize> {
+        let Some(store) = &self.chunk_store else {
+            return Ok(0);
+        };
+
+        let entries = store.load_index().await?;
+        let mut priority_queue = self.priority_queue.write().unwrap();
+        let mut file_chunks = self.file_chunks.write().unwrap();
+
+        for entry in &entries {
+            priority_queue.insert(OrderedFloat(entry.priority), entry.id);
+            if let Some(file) = &entry.source_file {
+                file_chunks.entry(file.clone()).or_default().push(entry.id);
+            }
+        }
+
+        info!("Restored {} chunk index entries from the persistent chunk store", entries.len());
+        Ok(entries.len())
+    }
+
+    /// Fetches a chunk by id, checking the in-memory map first and falling
+    /// back to the persistent store (re-promoting the result into memory) so
+    /// a spilled-to-disk chunk becomes resident again on access.
+    pub async fn get_chunk(&self, id: Uuid) -> Result<Option<ContextChunk>> {
+        if let Some(chunk) = self.chunks.read().unwrap().get(&id).cloned() {
+            return Ok(Some(chunk));
         }
+
+        let Some(store) = &self.chunk_store else {
+            return Ok(None);
+        };
+        let Some(chunk) = store.get(id).await? else {
+            return Ok(None);
+        };
+        self.chunks.write().unwrap().insert(id, chunk.clone());
+        Ok(Some(chunk))
     }
 
     /// Add content to the context window with intelligent chunking
     pub async fn add_content(&self, content: &str, source_file: Option<String>, chunk_type: ChunkType) -> Result<Vec<Uuid>> {
-        let chunks = if self.config.adaptive_chunking {
+        let chunks = if self.config.cdc_config.is_some() {
+            self.cdc_chunk(content, &source_file, &chunk_type).await?
+        } else if self.config.adaptive_chunking {
             self.adaptive_chunk(content, &source_file, &chunk_type).await?
         } else {
             self.fixed_chunk(content, &source_file, &chunk_type).await?
@@ -186,7 +406,7 @@ impl ContextWindowManager {
             };
 
             if relevance_score >= self.config.relevance_threshold {
-                let final_score = self.calculate_final_score(chunk, relevance_score);
+                let final_score = self.calculate_final_score(chunk, relevance_score, query);
                 scored_chunks.push((final_score, chunk.clone()));
             }
         }
@@ -200,7 +420,7 @@ impl ContextWindowManager {
         let mut total_tokens = 0;
 
         for (_score, chunk) in scored_chunks {
-            let chunk_tokens = self.token_estimator.estimate_tokens(&chunk.content, &chunk.chunk_type);
+            let chunk_tokens = self.token_estimator.estimate_tokens(Some(chunk.id), &chunk.content, &chunk.chunk_type);
             
             if total_tokens + chunk_tokens <= target_tokens {
                 total_tokens += chunk_tokens;
@@ -229,7 +449,7 @@ impl ContextWindowManager {
             if let Some(chunk_ids) = file_chunks.get(priority_file) {
                 for &chunk_id in chunk_ids {
                     if let Some(chunk) = chunks.get(&chunk_id) {
-                        let chunk_tokens = self.token_estimator.estimate_tokens(&chunk.content, &chunk.chunk_type);
+                        let chunk_tokens = self.token_estimator.estimate_tokens(Some(chunk.id), &chunk.content, &chunk.chunk_type);
                         if total_tokens + chunk_tokens <= self.config.max_tokens {
                             total_tokens += chunk_tokens;
                             selected_chunks.push(chunk.clone());
@@ -250,7 +470,7 @@ impl ContextWindowManager {
                                 continue;
                             }
 
-                            let chunk_tokens = self.token_estimator.estimate_tokens(&chunk.content, &chunk.chunk_type);
+                            let chunk_tokens = self.token_estimator.estimate_tokens(Some(chunk.id), &chunk.content, &chunk.chunk_type);
                             if total_tokens + chunk_tokens <= self.config.max_tokens {
                                 total_tokens += chunk_tokens;
                                 selected_chunks.push(chunk.clone());
@@ -412,6 +632,108 @@ impl ContextWindowManager {
         Ok(chunks)
     }
 
+    /// Content-defined chunking (`ContextWindowConfig::cdc_config`). Cut
+    /// points come from `FastCdcChunker` rather than fixed byte offsets, so a
+    /// chunk whose content already exists (by SHA-256) is reused in place
+    /// instead of being re-embedded and re-stored.
+    async fn cdc_chunk(&self, content: &str, source_file: &Option<String>, chunk_type: &ChunkType) -> Result<Vec<ContextChunk>> {
+        let cdc_config = self.config.cdc_config.unwrap_or_default();
+        let chunker = FastCdcChunker::new(cdc_config);
+
+        let mut boundaries: Vec<usize> = chunker
+            .cut_points(content.as_bytes())
+            .into_iter()
+            .map(|point| snap_to_char_boundary(content, point))
+            .collect();
+        boundaries.dedup();
+        if boundaries.last() != Some(&content.len()) {
+            boundaries.push(content.len());
+        }
+
+        let mut chunks = Vec::new();
+        let mut chunk_num = 0;
+        let mut start = 0;
+
+        for end in boundaries {
+            if end <= start {
+                continue;
+            }
+            let chunk_content = &content[start..end];
+            let hash = content_hash(chunk_content.as_bytes());
+
+            if let Some(reused) = self.reuse_by_content_hash(&hash).await? {
+                chunks.push(reused);
+                *self.dedup_hits.write().unwrap() += 1;
+                chunk_num += 1;
+                start = end;
+                if chunk_num >= self.config.max_chunks_per_file {
+                    break;
+                }
+                continue;
+            }
+
+            let start_line = content[..start].matches('\n').count();
+            let end_line = content[..end].matches('\n').count();
+            let chunk = self.create_chunk(
+                chunk_content.to_string(),
+                source_file.clone(),
+                Some(start_line),
+                Some(end_line),
+                chunk_type.clone(),
+            ).await?;
+
+            self.content_hashes.write().unwrap().insert(hash, chunk.id);
+            chunks.push(chunk);
+            chunk_num += 1;
+            start = end;
+
+            if chunk_num >= self.config.max_chunks_per_file {
+                break;
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    /// Looks up `hash` first in the in-memory dedup map (same process,
+    /// resident chunk), then — if a persistent store is configured — on
+    /// disk, re-promoting the match into memory. Bumps access tracking and
+    /// returns a clone either way; `None` means the content has never been
+    /// chunked before.
+    async fn reuse_by_content_hash(&self, hash: &str) -> Result<Option<ContextChunk>> {
+        let existing_id = self.content_hashes.read().unwrap().get(hash).copied();
+        if let Some(existing_id) = existing_id {
+            if let Some(reused) = self.touch_existing_chunk(existing_id) {
+                return Ok(Some(reused));
+            }
+        }
+
+        let Some(store) = &self.chunk_store else {
+            return Ok(None);
+        };
+        let Some(existing_id) = store.find_by_content_hash(hash).await? else {
+            return Ok(None);
+        };
+        let Some(chunk) = store.get(existing_id).await? else {
+            return Ok(None);
+        };
+
+        self.content_hashes.write().unwrap().insert(hash.to_string(), chunk.id);
+        self.chunks.write().unwrap().insert(chunk.id, chunk.clone());
+        Ok(Some(chunk))
+    }
+
+    /// Bumps access tracking on an already-stored chunk and returns a clone
+    /// of it, for the CDC dedup path where a new occurrence of known content
+    /// reuses the existing chunk instead of creating another one.
+    fn touch_existing_chunk(&self, chunk_id: Uuid) -> Option<ContextChunk> {
+        let mut chunks = self.chunks.write().unwrap();
+        let chunk = chunks.get_mut(&chunk_id)?;
+        chunk.access_count += 1;
+        chunk.last_accessed = Utc::now();
+        Some(chunk.clone())
+    }
+
     /// Create a context chunk with metadata
     async fn create_chunk(
         &self,
@@ -437,7 +759,9 @@ impl ContextWindowManager {
             None
         };
 
-        Ok(ContextChunk {
+        let symbols = scan_identifiers(&content, &chunk_type);
+
+        let chunk = ContextChunk {
             id,
             content,
             source_file,
@@ -450,13 +774,88 @@ impl ContextWindowManager {
             access_count: 0,
             embedding,
             metadata: HashMap::new(),
-        })
+            diagnostics: Vec::new(),
+            symbols,
+        };
+
+        if let Some(store) = &self.chunk_store {
+            let store = store.clone();
+            let persisted = chunk.clone();
+            let hash = content_hash(persisted.content.as_bytes());
+            tokio::spawn(async move {
+                if let Err(e) = store.upsert(&persisted, Some(&hash)).await {
+                    warn!("Failed to persist chunk {} to chunk store: {}", persisted.id, e);
+                }
+            });
+        }
+
+        Ok(chunk)
+    }
+
+    /// Overwrites a chunk's diagnostic and/or symbol metadata with data from
+    /// an external source (e.g. a tree-sitter pass or LSP diagnostics feed),
+    /// superseding whatever `scan_identifiers` guessed at creation time.
+    /// Passing `None` for either leaves that field untouched. Persists the
+    /// updated chunk in the background if a chunk store is configured, same
+    /// as `create_chunk`.
+    pub async fn annotate_chunk(
+        &self,
+        chunk_id: Uuid,
+        diagnostics: Option<Vec<Diagnostic>>,
+        symbols: Option<ChunkSymbols>,
+    ) -> Result<()> {
+        let updated = {
+            let mut chunks = self.chunks.write().unwrap();
+            let Some(chunk) = chunks.get_mut(&chunk_id) else {
+                return Ok(());
+            };
+            if let Some(diagnostics) = diagnostics {
+                chunk.diagnostics = diagnostics;
+            }
+            if let Some(symbols) = symbols {
+                chunk.symbols = symbols;
+            }
+            chunk.clone()
+        };
+
+        if let Some(store) = &self.chunk_store {
+            let store = store.clone();
+            let hash = content_hash(updated.content.as_bytes());
+            tokio::spawn(async move {
+                if let Err(e) = store.upsert(&updated, Some(&hash)).await {
+                    warn!("Failed to persist annotated chunk {} to chunk store: {}", updated.id, e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the in-memory chunks associated with a symbol name, chunks
+    /// that define it first, then chunks that merely reference it. Does not
+    /// consult the persistent store — intended for quick "where is this
+    /// defined/used" lookups over the resident working set.
+    pub fn get_context_for_symbol(&self, name: &str) -> Vec<ContextChunk> {
+        let chunks = self.chunks.read().unwrap();
+        let mut defining = Vec::new();
+        let mut referencing = Vec::new();
+
+        for chunk in chunks.values() {
+            if chunk.symbols.defined.iter().any(|s| s == name) {
+                defining.push(chunk.clone());
+            } else if chunk.symbols.referenced.iter().any(|s| s == name) {
+                referencing.push(chunk.clone());
+            }
+        }
+
+        defining.extend(referencing);
+        defining
     }
 
     // Helper methods
 
     fn should_create_code_chunk(&self, content: &str, brace_depth: i32, in_function: bool, line_count: usize) -> bool {
-        let estimated_tokens = self.token_estimator.estimate_tokens(content, &ChunkType::Code);
+        let estimated_tokens = self.token_estimator.estimate_tokens(None, content, &ChunkType::Code);
         
         // Create chunk if:
         // 1. We've reached token limit
@@ -522,14 +921,41 @@ impl ContextWindowManager {
         priority.min(1.0)
     }
 
-    fn calculate_final_score(&self, chunk: &ContextChunk, relevance_score: f32) -> f32 {
+    /// `query` drives the symbol-match boost: a chunk that defines or
+    /// references one of the query's words as a symbol is preferentially
+    /// surfaced even if its embedding/text similarity alone wouldn't rank it
+    /// highly — e.g. "why is foo() failing" should pull in `foo`'s
+    /// definition and its error diagnostics, not just textually similar chunks.
+    fn calculate_final_score(&self, chunk: &ContextChunk, relevance_score: f32, query: &str) -> f32 {
         let recency_score = self.calculate_recency_score(chunk.last_accessed);
         let access_score = (chunk.access_count as f32).ln_1p() / 10.0; // Logarithmic access boost
-        
-        relevance_score * 0.6 + 
-        chunk.priority * 0.2 + 
-        recency_score * self.config.recency_weight + 
-        access_score * 0.1
+
+        let diagnostic_boost = chunk
+            .diagnostics
+            .iter()
+            .map(|d| match d.severity {
+                DiagnosticSeverity::Error => 0.3,
+                DiagnosticSeverity::Warning => 0.15,
+                DiagnosticSeverity::Info | DiagnosticSeverity::Hint => 0.05,
+            })
+            .fold(0.0_f32, f32::max);
+
+        let query_terms: std::collections::HashSet<&str> = query.split_whitespace().collect();
+        let symbol_boost = if chunk.symbols.defined.iter().any(|s| query_terms.contains(s.as_str()))
+            || chunk.symbols.referenced.iter().any(|s| query_terms.contains(s.as_str()))
+        {
+            0.2
+        } else {
+            0.0
+        };
+
+        (relevance_score * 0.6
+            + chunk.priority * 0.2
+            + recency_score * self.config.recency_weight
+            + access_score * 0.1
+            + diagnostic_boost
+            + symbol_boost)
+            .min(1.0)
     }
 
     fn calculate_recency_score(&self, last_accessed: DateTime<Utc>) -> f32 {
@@ -618,6 +1044,11 @@ impl ContextWindowManager {
         Ok(())
     }
 
+    /// Evicts `count` of the lowest-priority chunks from memory. When a
+    /// `chunk_store` is configured this is a spill, not a discard: the row
+    /// (content + embedding) stays on disk marked non-resident, and `get_chunk`
+    /// transparently re-promotes it later. Without a store this is a true
+    /// delete, matching the original in-memory-only behavior.
     async fn evict_least_important_chunks(&self, count: usize) -> Result<()> {
         let chunks_to_evict: Vec<Uuid> = {
             let priority_queue = self.priority_queue.read().unwrap();
@@ -628,25 +1059,38 @@ impl ContextWindowManager {
                 .collect()
         };
 
-        let mut chunks = self.chunks.write().unwrap();
-        let mut priority_queue = self.priority_queue.write().unwrap();
-        let mut file_chunks = self.file_chunks.write().unwrap();
+        let evicted = {
+            let mut chunks = self.chunks.write().unwrap();
+            let mut priority_queue = self.priority_queue.write().unwrap();
+            let mut file_chunks = self.file_chunks.write().unwrap();
 
-        for chunk_id in chunks_to_evict {
-            if let Some(chunk) = chunks.remove(&chunk_id) {
-                // Remove from file mapping
-                if let Some(ref file) = chunk.source_file {
-                    if let Some(file_chunk_list) = file_chunks.get_mut(file) {
-                        file_chunk_list.retain(|&id| id != chunk_id);
+            let mut evicted = Vec::new();
+            for chunk_id in chunks_to_evict {
+                if let Some(chunk) = chunks.remove(&chunk_id) {
+                    // Remove from file mapping
+                    if let Some(ref file) = chunk.source_file {
+                        if let Some(file_chunk_list) = file_chunks.get_mut(file) {
+                            file_chunk_list.retain(|&id| id != chunk_id);
+                        }
                     }
+
+                    // Remove from priority queue
+                    priority_queue.retain(|_, uuid| *uuid != chunk_id);
+                    evicted.push(chunk_id);
                 }
-                
-                // Remove from priority queue
-                priority_queue.retain(|_, uuid| *uuid != chunk_id);
             }
+            evicted
+        };
+
+        if let Some(store) = &self.chunk_store {
+            for chunk_id in &evicted {
+                store.mark_spilled(*chunk_id).await?;
+            }
+            debug!("Spilled {} chunks to disk to enforce limits", evicted.len());
+        } else {
+            debug!("Evicted {} chunks to enforce limits", evicted.len());
         }
 
-        debug!("Evicted {} chunks to enforce limits", count);
         Ok(())
     }
 
@@ -658,7 +1102,7 @@ impl ContextWindowManager {
         let total_chunks = chunks.len();
         let total_files = file_chunks.len();
         let total_tokens: usize = chunks.values()
-            .map(|c| self.token_estimator.estimate_tokens(&c.content, &c.chunk_type))
+            .map(|c| self.token_estimator.estimate_tokens(Some(c.id), &c.content, &c.chunk_type))
             .sum();
 
         let chunks_by_type: HashMap<ChunkType, usize> = chunks.values()
@@ -667,16 +1111,125 @@ impl ContextWindowManager {
                 acc
             });
 
+        let dedup_hits = *self.dedup_hits.read().unwrap();
+        let dedup_ratio = if total_chunks + dedup_hits > 0 {
+            dedup_hits as f32 / (total_chunks + dedup_hits) as f32
+        } else {
+            0.0
+        };
+
         ContextWindowStats {
             total_chunks,
             total_files,
             total_tokens,
             chunks_by_type,
             memory_usage: total_chunks * 1024, // Rough estimate
+            dedup_ratio,
         }
     }
 }
 
+/// Declaration keywords across the languages this crate chunks (Rust,
+/// Python, JS/TS, Go), used to classify an identifier immediately following
+/// one of these as "defined" rather than merely "referenced".
+const DECL_KEYWORDS: &[&str] = &[
+    "fn", "func", "function", "def", "class", "struct", "enum", "trait",
+    "interface", "impl", "let", "const", "var", "type",
+];
+
+/// Common keywords/builtins excluded from both defined and referenced sets
+/// so they don't drown out actual identifiers.
+const SYMBOL_STOPWORDS: &[&str] = &[
+    "if", "else", "for", "while", "return", "match", "case", "break",
+    "continue", "true", "false", "null", "none", "some", "self", "this",
+    "pub", "mod", "use", "import", "from", "as", "in", "is", "new",
+    "static", "async", "await", "try", "catch", "throw", "do", "switch",
+    "default", "void",
+];
+
+/// Best-effort defined/referenced symbol extraction from raw text, used as
+/// `ContextChunk::symbols`'s initial value at chunk-creation time. This has
+/// no grammar, so it is only a heuristic: an identifier right after a
+/// declaration keyword is "defined", everything else identifier-like is
+/// "referenced". `annotate_chunk` lets a caller with real tree-sitter
+/// captures (e.g. `CodebaseIndexer`) replace this with precise symbols.
+fn scan_identifiers(content: &str, chunk_type: &ChunkType) -> ChunkSymbols {
+    if !matches!(
+        chunk_type,
+        ChunkType::Code | ChunkType::Function | ChunkType::Class | ChunkType::Variable | ChunkType::Import
+    ) {
+        return ChunkSymbols::default();
+    }
+
+    const MAX_SYMBOLS: usize = 50;
+    let mut defined = Vec::new();
+    let mut referenced = Vec::new();
+    let mut seen_defined = std::collections::HashSet::new();
+    let mut seen_referenced = std::collections::HashSet::new();
+    let mut prev_was_decl_keyword = false;
+
+    for token in tokenize_identifiers(content) {
+        let lower = token.to_ascii_lowercase();
+        if SYMBOL_STOPWORDS.contains(&lower.as_str()) {
+            prev_was_decl_keyword = false;
+            continue;
+        }
+
+        if prev_was_decl_keyword {
+            if defined.len() < MAX_SYMBOLS && seen_defined.insert(token.to_string()) {
+                defined.push(token.to_string());
+            }
+        } else if referenced.len() < MAX_SYMBOLS && seen_referenced.insert(token.to_string()) {
+            referenced.push(token.to_string());
+        }
+
+        prev_was_decl_keyword = DECL_KEYWORDS.contains(&lower.as_str());
+    }
+
+    referenced.retain(|name| !seen_defined.contains(name));
+    ChunkSymbols { defined, referenced }
+}
+
+/// Splits `content` into maximal runs of `[A-Za-z0-9_]` that start with a
+/// letter or `_`, discarding everything else (operators, punctuation,
+/// whitespace). Words inside string literals are tokenized like any others.
+fn tokenize_identifiers(content: &str) -> Vec<&str> {
+    let bytes = content.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_alphabetic() || bytes[i] == b'_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push(&content[start..i]);
+        } else {
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// Nearest char boundary at or before `byte_idx`, so a FastCDC byte-level cut
+/// point can be used to slice `content` without panicking mid-codepoint.
+fn snap_to_char_boundary(content: &str, mut byte_idx: usize) -> usize {
+    while byte_idx > 0 && !content.is_char_boundary(byte_idx) {
+        byte_idx -= 1;
+    }
+    byte_idx
+}
+
+/// SHA-256 of a chunk's bytes, used as the dedup key in `content_hashes`.
+/// Matches the hashing convention already used elsewhere in this crate
+/// (`embeddings::models`, `security::api_keys`) rather than introducing a
+/// new hash dependency just for this.
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
 /// Context window statistics
 #[derive(Debug)]
 pub struct ContextWindowStats {
@@ -685,4 +1238,9 @@ pub struct ContextWindowStats {
     pub total_tokens: usize,
     pub chunks_by_type: HashMap<ChunkType, usize>,
     pub memory_usage: usize,
-} 
\ No newline at end of file
+    /// Fraction of all chunk occurrences (stored + reused) that FastCDC
+    /// content-defined chunking resolved to an already-stored chunk instead
+    /// of creating a new one. Always 0.0 when `cdc_config` is unset, since
+    /// `adaptive_chunk`/`fixed_chunk` never populate `content_hashes`.
+    pub dedup_ratio: f32,
+}
\ No newline at end of file
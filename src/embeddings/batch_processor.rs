@@ -1,10 +1,12 @@
 use anyhow::Result;
+use rand::Rng;
 use std::collections::HashMap;
-use tracing::debug;
+use tracing::{debug, warn};
 use std::sync::Arc;
 use tokio::sync::{RwLock, Semaphore};
-use tokio::time::{timeout, Duration};
-use crate::embeddings::onnx_runtime::OnnxEmbeddingEngine;
+use tokio::time::{timeout, Duration, Instant};
+use crate::embeddings::{EmbeddingProvider, EmbeddingQueue, EmbeddingStore};
+use crate::metrics::{EmbeddingMetricsExporter, NoopEmbeddingMetrics};
 
 #[derive(Clone)]
 pub struct BatchProcessor {
@@ -12,7 +14,127 @@ pub struct BatchProcessor {
     max_concurrent_batches: usize,
     timeout_duration: Duration,
     embedding_cache: Arc<RwLock<LruCache<String, Vec<f32>>>>,
+    /// Disk-backed tier underneath `embedding_cache`, consulted on an
+    /// in-memory miss and populated on every in-memory write, so embeddings
+    /// survive process restarts instead of being recomputed from scratch.
+    /// Absent until `set_persistent_cache` is called with the model that
+    /// will be producing vectors (its `model_name`/`dimensions` key and
+    /// invalidate the cached rows — see `embeddings::EmbeddingStore`).
+    persistent_cache: Arc<RwLock<Option<PersistentTier>>>,
+    /// Where `check_cache` reports hit/miss counts, labeled
+    /// `"batch_memory"`/`"batch_persistent"`. Defaults to `NoopEmbeddingMetrics`;
+    /// set via `set_metrics_exporter`.
+    metrics: Arc<RwLock<Arc<dyn EmbeddingMetricsExporter>>>,
     semaphore: Arc<Semaphore>,
+    /// Groups `process_uncached_batch`'s texts by estimated token budget
+    /// before `batch_size` is applied as a secondary item-count cap — see
+    /// `EmbeddingQueue` and `OnnxEmbeddingEngine::generate_embeddings`,
+    /// which this mirrors.
+    queue: EmbeddingQueue,
+    retry_policy: RetryPolicy,
+}
+
+/// The persistent cache plus the model identity its rows are keyed under,
+/// so `check_cache`/`update_cache` can pass the right `model_name`/
+/// `dimensions` to `EmbeddingStore::get`/`put` without threading them
+/// through every call site. `cache` is `dyn` so `LocalEmbeddingEngine::initialize`
+/// can hand in whichever backend `EmbeddingConfig.cache_backend` selects.
+#[derive(Clone)]
+struct PersistentTier {
+    cache: Arc<dyn EmbeddingStore>,
+    model_name: String,
+    dimensions: usize,
+}
+
+/// Backoff policy for retrying a transiently-failed batch call (rate
+/// limiting, timeout) in `process_uncached_batch`/`process_with_engine`.
+/// Mirrors `embeddings::provider::send_with_retry`'s HTTP-429 retry, but at
+/// the batch level rather than per-request, for engines (like the bundled
+/// ONNX one today, or a future remote `EmbeddingEngine`) that don't already
+/// retry internally.
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(30),
+            deadline: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Carries a provider-suggested retry delay (e.g. a parsed HTTP
+/// `Retry-After` header) alongside a transient failure, so `with_retry`
+/// can honor it instead of computing its own exponential backoff.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct RetryAfter {
+    pub message: String,
+    pub delay: Duration,
+}
+
+/// Whether `err` looks like a transient failure worth retrying (rate
+/// limiting or a timeout) rather than something that will just fail again —
+/// e.g. a malformed request or a dimension mismatch. Checked as a fallback
+/// when `err` doesn't downcast to `RetryAfter`.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    if err.downcast_ref::<RetryAfter>().is_some() {
+        return true;
+    }
+    let message = err.to_string().to_lowercase();
+    message.contains("timed out")
+        || message.contains("rate limit")
+        || message.contains("rate-limited")
+        || message.contains("429")
+        || message.contains("too many requests")
+}
+
+/// Retries `attempt_fn` under `policy` while `is_retryable` says the error is
+/// transient: exponential backoff from `policy.base_delay` with jitter,
+/// unless the error carries a `RetryAfter` hint, which is used in place of
+/// the computed backoff; either delay is capped at `policy.max_delay`.
+/// Gives up — returning the last error
+/// unchanged — once `policy.max_retries` is reached, `policy.deadline` has
+/// elapsed, or the error isn't retryable at all.
+async fn with_retry<F, Fut, T>(policy: RetryPolicy, op_name: &str, mut attempt_fn: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let start = Instant::now();
+    let mut attempt = 0u32;
+    loop {
+        match attempt_fn().await {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                if !is_retryable(&err) || attempt >= policy.max_retries || start.elapsed() >= policy.deadline {
+                    return Err(err);
+                }
+
+                let retry_after = err.downcast_ref::<RetryAfter>().map(|r| r.delay);
+                let delay = retry_after.unwrap_or_else(|| {
+                    let computed = policy.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+                    let jitter = rand::thread_rng().gen_range(0.5..1.5);
+                    Duration::from_secs_f64(computed.as_secs_f64() * jitter)
+                }).min(policy.max_delay);
+
+                warn!(
+                    "{} failed transiently ({}); retrying in {:?} (attempt {}/{})",
+                    op_name, err, delay, attempt + 1, policy.max_retries
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
 }
 
 struct LruCache<K, V> {
@@ -70,18 +192,43 @@ impl<K: Clone + std::hash::Hash + Eq, V: Clone> LruCache<K, V> {
 
 impl BatchProcessor {
     pub fn new(batch_size: usize) -> Self {
+        Self::with_token_budget(batch_size, EmbeddingQueue::default().target_tokens_per_batch())
+    }
+
+    pub fn with_token_budget(batch_size: usize, max_tokens_per_batch: usize) -> Self {
         let max_concurrent_batches = num_cpus::get().max(4);
         let cache_capacity = 10000; // Store up to 10k embeddings
-        
+
         Self {
             batch_size: batch_size.max(1),
             max_concurrent_batches,
             timeout_duration: Duration::from_secs(30),
             embedding_cache: Arc::new(RwLock::new(LruCache::new(cache_capacity))),
+            persistent_cache: Arc::new(RwLock::new(None)),
+            metrics: Arc::new(RwLock::new(Arc::new(NoopEmbeddingMetrics))),
             semaphore: Arc::new(Semaphore::new(max_concurrent_batches)),
+            queue: EmbeddingQueue::new(max_tokens_per_batch),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Attaches a disk-backed cache tier keyed under `model_name`/
+    /// `dimensions`, consulted underneath the in-memory LRU by `check_cache`
+    /// and kept in sync by `update_cache`. Call again after switching models
+    /// so cached lookups are scoped to the model that's actually loaded —
+    /// every `EmbeddingStore` implementation ignores rows written under a
+    /// different `model_name`/`dimensions`, so stale cross-model hits can't
+    /// leak through even if this is forgotten.
+    pub async fn set_persistent_cache(&self, cache: Arc<dyn EmbeddingStore>, model_name: String, dimensions: usize) {
+        *self.persistent_cache.write().await = Some(PersistentTier { cache, model_name, dimensions });
+    }
+
+    /// Reports `check_cache`'s hit/miss outcomes through `exporter` instead
+    /// of discarding them. See `EmbeddingMetricsExporterKind`.
+    pub async fn set_metrics_exporter(&self, exporter: Arc<dyn EmbeddingMetricsExporter>) {
+        *self.metrics.write().await = exporter;
+    }
+
     pub async fn process_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(Vec::new());
@@ -120,14 +267,33 @@ impl BatchProcessor {
 
     async fn check_cache(&self, texts: &[String]) -> (Vec<Option<Vec<f32>>>, Vec<String>, Vec<usize>) {
         let mut cache = self.embedding_cache.write().await;
+        let persistent = self.persistent_cache.read().await;
+        let metrics = self.metrics.read().await;
         let mut cached_embeddings = Vec::with_capacity(texts.len());
         let mut uncached_texts = Vec::new();
         let mut uncached_indices = Vec::new();
 
         for (i, text) in texts.iter().enumerate() {
             if let Some(embedding) = cache.get(text) {
+                metrics.record_cache_access("batch_memory", true);
                 cached_embeddings.push(Some(embedding.clone()));
+                continue;
+            }
+            metrics.record_cache_access("batch_memory", false);
+
+            let from_disk = match persistent.as_ref() {
+                Some(tier) => tier.cache.get(&tier.model_name, tier.dimensions, text).await,
+                None => None,
+            };
+
+            if let Some(embedding) = from_disk {
+                metrics.record_cache_access("batch_persistent", true);
+                cache.put(text.clone(), embedding.clone());
+                cached_embeddings.push(Some(embedding));
             } else {
+                if persistent.is_some() {
+                    metrics.record_cache_access("batch_persistent", false);
+                }
                 cached_embeddings.push(None);
                 uncached_texts.push(text.clone());
                 uncached_indices.push(i);
@@ -139,43 +305,69 @@ impl BatchProcessor {
 
     async fn update_cache(&self, texts: &[String], embeddings: &[Vec<f32>]) {
         let mut cache = self.embedding_cache.write().await;
+        let persistent = self.persistent_cache.read().await;
         for (text, embedding) in texts.iter().zip(embeddings.iter()) {
             cache.put(text.clone(), embedding.clone());
+            if let Some(tier) = persistent.as_ref() {
+                tier.cache.put(&tier.model_name, tier.dimensions, text, embedding).await;
+            }
         }
     }
 
+    /// Groups `texts` into token-budgeted plans via `self.queue` (so many
+    /// short texts share a batch and a single long text isn't forced into
+    /// one that would overflow the model's context window), sub-chunks each
+    /// plan group by `batch_size` as a secondary item-count cap, and
+    /// dispatches every sub-chunk under `self.semaphore` in parallel.
     async fn process_uncached_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
-        let mut all_embeddings = Vec::new();
-        let mut handles = Vec::new();
-
-        // Split into chunks for parallel processing
-        let chunks: Vec<_> = texts.chunks(self.batch_size).collect();
-        
-        for chunk in chunks {
-            let chunk_texts = chunk.to_vec();
-            let semaphore = Arc::clone(&self.semaphore);
-            let timeout_duration = self.timeout_duration;
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
 
-            let handle = tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.expect("Semaphore closed");
-                
-                timeout(timeout_duration, async {
-                    Self::process_chunk(chunk_texts).await
-                }).await
-                .map_err(|_| anyhow::anyhow!("Batch processing timed out"))?
-            });
+        let mut all_embeddings: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        let mut handles = Vec::new();
 
-            handles.push(handle);
+        for plan_group in self.queue.plan_batches(&texts) {
+            for sub_chunk in plan_group.chunks(self.batch_size) {
+                let indices = sub_chunk.to_vec();
+                let chunk_texts: Vec<String> = indices.iter().map(|&i| texts[i].clone()).collect();
+                let semaphore = Arc::clone(&self.semaphore);
+                let timeout_duration = self.timeout_duration;
+                let retry_policy = self.retry_policy;
+
+                let handle = tokio::spawn(async move {
+                    let _permit = semaphore.acquire().await.expect("Semaphore closed");
+
+                    let embeddings = with_retry(retry_policy, "embedding batch", || {
+                        let chunk_texts = chunk_texts.clone();
+                        async move {
+                            timeout(timeout_duration, async {
+                                Self::process_chunk(chunk_texts).await
+                            }).await
+                            .map_err(|_| anyhow::anyhow!("Batch processing timed out"))?
+                        }
+                    }).await?;
+
+                    Ok::<_, anyhow::Error>((indices, embeddings))
+                });
+
+                handles.push(handle);
+            }
         }
 
         // Collect results
         for handle in handles {
-            let chunk_embeddings = handle.await
+            let (indices, chunk_embeddings) = handle.await
                 .map_err(|e| anyhow::anyhow!("Task failed: {}", e))??;
-            all_embeddings.extend(chunk_embeddings);
+            for (idx, embedding) in indices.into_iter().zip(chunk_embeddings.into_iter()) {
+                all_embeddings[idx] = Some(embedding);
+            }
         }
 
-        Ok(all_embeddings)
+        Ok(all_embeddings
+            .into_iter()
+            .map(|opt| opt.expect("every text is covered by exactly one plan batch"))
+            .collect())
     }
 
     async fn process_chunk(texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
@@ -224,18 +416,21 @@ impl BatchProcessor {
     pub async fn process_with_engine(
         &self, 
         texts: Vec<String>,
-        engine: &OnnxEmbeddingEngine
+        provider: &dyn EmbeddingProvider
     ) -> Result<Vec<Vec<f32>>> {
         // Check cache first
-        let (cached_embeddings, uncached_texts, uncached_indices) = 
+        let (cached_embeddings, uncached_texts, uncached_indices) =
             self.check_cache(&texts).await;
 
         let mut final_embeddings = cached_embeddings;
 
-        // Process uncached texts with the embedding engine
+        // Process uncached texts with the embedding provider
         if !uncached_texts.is_empty() {
-            let new_embeddings = engine.encode_batch(&uncached_texts).await?;
-            
+            let new_embeddings = with_retry(self.retry_policy, "embedding batch (provider)", || {
+                let uncached_texts = uncached_texts.clone();
+                async move { provider.embed_batch(&uncached_texts).await }
+            }).await?;
+
             // Update cache
             self.update_cache(&uncached_texts, &new_embeddings).await;
 
@@ -260,6 +455,9 @@ impl BatchProcessor {
     pub async fn clear_cache(&self) {
         let mut cache = self.embedding_cache.write().await;
         cache.clear();
+        if let Some(tier) = self.persistent_cache.read().await.as_ref() {
+            tier.cache.clear().await;
+        }
         debug!("🧹 Embedding cache cleared");
     }
 
@@ -271,6 +469,16 @@ impl BatchProcessor {
         self.timeout_duration = Duration::from_secs(timeout_secs);
     }
 
+    pub fn set_max_tokens_per_batch(&mut self, max_tokens_per_batch: usize) {
+        self.queue.set_target_tokens_per_batch(max_tokens_per_batch);
+    }
+
+    /// Configures the retry policy used when a batch call to the embedding
+    /// engine fails transiently; see `RetryPolicy`/`with_retry`.
+    pub fn set_retry_policy(&mut self, max_retries: u32, base_delay: Duration, max_delay: Duration, deadline: Duration) {
+        self.retry_policy = RetryPolicy { max_retries, base_delay, max_delay, deadline };
+    }
+
     pub async fn precompute_embeddings(&self, texts: Vec<String>) -> Result<()> {
         debug!("🚀 Precomputing embeddings for {} texts", texts.len());
         self.process_batch(texts).await?;
@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OpenFlags};
+use sha1::{Digest, Sha1};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Disk-backed, LRU-bounded replacement for the old unbounded
+/// `HashMap<String, Vec<f32>>` embedding cache. Entries are keyed by a
+/// 20-byte SHA-1 digest of the normalized text alone, so identical spans
+/// that show up in many files dedupe to a single row regardless of which
+/// caller first embedded them; the `model_name`/`dimensions` that produced
+/// the stored vector are kept alongside it so a row left behind by a since-
+/// replaced model is treated as a miss instead of handing back a
+/// wrong-dimension vector. Entries survive process restarts instead of
+/// being recomputed every time.
+///
+/// Mirrors `GraphStorage`'s pattern of a `rusqlite::Connection` behind a
+/// blocking `Mutex`, accessed from `async fn`s without a dedicated blocking
+/// pool — consistent with how the rest of this crate talks to SQLite.
+#[derive(Clone)]
+pub struct PersistentEmbeddingCache {
+    conn: Arc<Mutex<Connection>>,
+    capacity: usize,
+}
+
+impl PersistentEmbeddingCache {
+    /// Opens (creating if necessary) the on-disk cache database at `db_path`,
+    /// bounding it to at most `capacity` entries.
+    pub fn open(db_path: &Path, capacity: usize) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create cache directory {}", parent.display()))?;
+        }
+
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open embedding cache database: {}", db_path.display()))?;
+
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS embedding_cache (
+                digest TEXT PRIMARY KEY,
+                model_name TEXT NOT NULL,
+                dimensions INTEGER NOT NULL,
+                vector BLOB NOT NULL,
+                last_accessed INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_embedding_cache_last_accessed
+                ON embedding_cache (last_accessed);
+            ",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            capacity: capacity.max(1),
+        })
+    }
+
+    /// Looks up the cached embedding for `text`, touching its
+    /// `last_accessed` stamp on a hit so it survives the next eviction.
+    /// Returns `None` if the row was written by a different model or at a
+    /// different dimensionality than `model_name`/`dimensions`, so a model
+    /// switch can't resurrect a stale, wrong-shape vector.
+    pub async fn get(&self, model_name: &str, dimensions: usize, text: &str) -> Option<Vec<f32>> {
+        let digest = Self::digest(text);
+        let conn = self.conn.lock().unwrap();
+
+        let row: Option<(String, i64, Vec<u8>)> = conn
+            .query_row(
+                "SELECT model_name, dimensions, vector FROM embedding_cache WHERE digest = ?1",
+                params![digest],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok();
+
+        let (row_model, row_dimensions, vector) = row?;
+        if row_model != model_name || row_dimensions as usize != dimensions {
+            return None;
+        }
+
+        let _ = conn.execute(
+            "UPDATE embedding_cache SET last_accessed = ?1 WHERE digest = ?2",
+            params![Self::now(), digest],
+        );
+
+        Some(Self::decode_vector(&vector))
+    }
+
+    /// Inserts or replaces the cached embedding for `text`, tagging the row
+    /// with the `model_name`/`dimensions` that produced it, then evicts the
+    /// least-recently-accessed entries beyond `capacity` so the cache never
+    /// grows unbounded.
+    pub async fn put(&self, model_name: &str, dimensions: usize, text: &str, embedding: &[f32]) {
+        let digest = Self::digest(text);
+        let bytes = Self::encode_vector(embedding);
+        let conn = self.conn.lock().unwrap();
+
+        let _ = conn.execute(
+            "INSERT OR REPLACE INTO embedding_cache (digest, model_name, dimensions, vector, last_accessed) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![digest, model_name, dimensions as i64, bytes, Self::now()],
+        );
+
+        let _ = conn.execute(
+            "
+            DELETE FROM embedding_cache WHERE digest IN (
+                SELECT digest FROM embedding_cache
+                ORDER BY last_accessed ASC
+                LIMIT MAX(0, (SELECT COUNT(*) FROM embedding_cache) - ?1)
+            )
+            ",
+            params![self.capacity as i64],
+        );
+    }
+
+    /// Number of entries currently cached on disk.
+    pub async fn len(&self) -> usize {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM embedding_cache", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map(|count| count as usize)
+        .unwrap_or(0)
+    }
+
+    /// Drops every cached entry.
+    pub async fn clear(&self) {
+        let conn = self.conn.lock().unwrap();
+        let _ = conn.execute("DELETE FROM embedding_cache", []);
+    }
+
+    /// 20-byte SHA-1 digest of the normalized text, hex-encoded.
+    fn digest(text: &str) -> String {
+        let mut hasher = Sha1::new();
+        hasher.update(text.trim().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn encode_vector(vector: &[f32]) -> Vec<u8> {
+        vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().expect("chunk is exactly 4 bytes")))
+            .collect()
+    }
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,258 @@
+use std::ops::Range;
+
+use crate::embeddings::EmbeddingQueue;
+
+/// Target chunk size and the amount of trailing context repeated into the
+/// next chunk when a document doesn't fit in one embedding call.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkerConfig {
+    pub max_tokens: usize,
+    pub overlap_tokens: usize,
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        Self { max_tokens: 400, overlap_tokens: 40 }
+    }
+}
+
+/// One piece of a chunked document, paired with the byte range it covers in
+/// the original text so callers can store `(path, range, vector)` and have
+/// search results point back to an exact source location. `text` may
+/// additionally carry a leading copy of the previous chunk's tail for
+/// context continuity — `byte_range` always refers to this chunk's own new
+/// content, not the repeated overlap.
+#[derive(Debug, Clone)]
+pub struct TextChunk {
+    pub text: String,
+    pub byte_range: Range<usize>,
+}
+
+/// Programming languages the chunker recognizes syntactic (function/class)
+/// boundaries for. Anything else falls back to paragraph/sentence
+/// splitting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+}
+
+impl Language {
+    /// Maps a file extension (with or without a leading dot) to a known
+    /// language, for callers chunking a file they have a path for.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.trim_start_matches('.') {
+            "rs" => Some(Language::Rust),
+            "py" => Some(Language::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Language::JavaScript),
+            "ts" | "tsx" => Some(Language::TypeScript),
+            "go" => Some(Language::Go),
+            "java" => Some(Language::Java),
+            _ => None,
+        }
+    }
+
+    /// Keywords marking the start of a new top-level block (function,
+    /// class, etc.) worth splitting on, when found at the start of a line
+    /// (after leading whitespace).
+    fn block_start_keywords(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "fn ", "pub fn ", "async fn ", "pub async fn ", "impl ", "struct ", "enum ", "trait ", "mod ",
+            ],
+            Language::Python => &["def ", "async def ", "class "],
+            Language::JavaScript | Language::TypeScript => &[
+                "function ", "async function ", "class ",
+                "export function ", "export class ", "export default function ", "export default class ",
+            ],
+            Language::Go => &["func ", "type "],
+            Language::Java => &["public class ", "private class ", "class ", "interface ", "public interface "],
+        }
+    }
+}
+
+/// Splits documents into model-sized pieces for `encode_batch`, preferring
+/// syntactic boundaries (function/class/block) for recognized programming
+/// languages and paragraph/sentence boundaries otherwise, so a chunk only
+/// cuts a known-language function in half when that function alone exceeds
+/// `max_tokens` (it is then split by sentence). Pairs with
+/// `OnnxEmbeddingEngine::encode_batch`/`LocalEmbeddingEngine::encode_texts`:
+/// embed `chunk.text` for each returned `TextChunk` and store the result
+/// alongside `chunk.byte_range`.
+pub struct TextChunker {
+    config: ChunkerConfig,
+}
+
+impl TextChunker {
+    pub fn new(config: ChunkerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Chunks `document`. Pass `language` (e.g. via `Language::from_extension`)
+    /// when the caller knows the source language; `None` falls back to
+    /// paragraph/sentence splitting.
+    pub fn chunk_document(&self, document: &str, language: Option<Language>) -> Vec<TextChunk> {
+        if document.is_empty() {
+            return Vec::new();
+        }
+
+        let boundaries = match language {
+            Some(lang) => self.syntactic_boundaries(document, lang),
+            None => self.paragraph_boundaries(document),
+        };
+
+        self.pack_segments(document, &boundaries)
+    }
+
+    /// Byte offsets where a new top-level block begins, based on
+    /// `language`'s block-start keywords appearing at the start of a line.
+    fn syntactic_boundaries(&self, document: &str, language: Language) -> Vec<usize> {
+        let keywords = language.block_start_keywords();
+        let mut boundaries = vec![0usize];
+
+        for (line_start, line) in line_starts(document) {
+            let trimmed = line.trim_start();
+            if keywords.iter().any(|kw| trimmed.starts_with(kw)) {
+                boundaries.push(line_start);
+            }
+        }
+
+        boundaries.push(document.len());
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries
+    }
+
+    /// Byte offsets at blank-line-separated paragraph boundaries, the
+    /// fallback for documents in no recognized programming language.
+    fn paragraph_boundaries(&self, document: &str) -> Vec<usize> {
+        let mut boundaries = vec![0usize];
+        let bytes = document.as_bytes();
+        for i in 0..bytes.len().saturating_sub(1) {
+            if bytes[i] == b'\n' && bytes[i + 1] == b'\n' {
+                boundaries.push(i + 2);
+            }
+        }
+        boundaries.push(document.len());
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries
+    }
+
+    /// Greedily merges adjacent `[boundaries[i], boundaries[i+1])` segments
+    /// into chunks up to `max_tokens`, further splitting any single segment
+    /// that alone exceeds the budget by sentence, then applies overlap.
+    fn pack_segments(&self, document: &str, boundaries: &[usize]) -> Vec<TextChunk> {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        let mut current_start = boundaries[0];
+        let mut current_tokens = 0usize;
+
+        for window in boundaries.windows(2) {
+            let (seg_start, seg_end) = (window[0], window[1]);
+            let segment_tokens = EmbeddingQueue::estimate_tokens(&document[seg_start..seg_end]);
+
+            if current_tokens > 0 && current_tokens + segment_tokens > self.config.max_tokens {
+                ranges.push((current_start, seg_start));
+                current_start = seg_start;
+                current_tokens = 0;
+            }
+
+            if segment_tokens > self.config.max_tokens {
+                // A single segment (e.g. one giant function) still exceeds
+                // the budget on its own — split it further by sentence
+                // rather than embedding an over-length chunk.
+                ranges.extend(self.split_oversized(document, seg_start, seg_end));
+                current_start = seg_end;
+                current_tokens = 0;
+            } else {
+                current_tokens += segment_tokens;
+            }
+        }
+
+        if current_start < document.len() && current_tokens > 0 {
+            ranges.push((current_start, document.len()));
+        }
+
+        self.apply_overlap(document, ranges)
+    }
+
+    /// Splits an over-budget segment on sentence boundaries (`". "`),
+    /// packing sentences up to `max_tokens` the same way `pack_segments`
+    /// packs whole segments.
+    fn split_oversized(&self, document: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let segment = &document[start..end];
+        let mut sentence_boundaries = vec![start];
+        for (offset, _) in segment.match_indices(". ") {
+            sentence_boundaries.push(start + offset + 2);
+        }
+        sentence_boundaries.push(end);
+        sentence_boundaries.sort_unstable();
+        sentence_boundaries.dedup();
+
+        let mut result = Vec::new();
+        let mut chunk_start = sentence_boundaries[0];
+        let mut tokens = 0usize;
+        for window in sentence_boundaries.windows(2) {
+            let (s, e) = (window[0], window[1]);
+            let sentence_tokens = EmbeddingQueue::estimate_tokens(&document[s..e]);
+            if tokens > 0 && tokens + sentence_tokens > self.config.max_tokens {
+                result.push((chunk_start, s));
+                chunk_start = s;
+                tokens = 0;
+            }
+            tokens += sentence_tokens;
+        }
+        if chunk_start < end {
+            result.push((chunk_start, end));
+        }
+        result
+    }
+
+    /// Prefixes each chunk after the first with the previous chunk's
+    /// trailing `overlap_tokens` worth of text, so adjacent chunks share
+    /// context. The returned `byte_range` still refers only to the chunk's
+    /// own new content, not the repeated overlap prefix.
+    fn apply_overlap(&self, document: &str, ranges: Vec<(usize, usize)>) -> Vec<TextChunk> {
+        let overlap_chars = self.config.overlap_tokens * 4;
+        let mut result = Vec::with_capacity(ranges.len());
+
+        for (i, &(start, end)) in ranges.iter().enumerate() {
+            let own_text = &document[start..end];
+            let text = if i == 0 || overlap_chars == 0 {
+                own_text.to_string()
+            } else {
+                let (prev_start, _) = ranges[i - 1];
+                let overlap_floor = start.saturating_sub(overlap_chars).max(prev_start);
+                let overlap_start = floor_char_boundary(document, overlap_floor);
+                format!("{}{}", &document[overlap_start..start], own_text)
+            };
+
+            result.push(TextChunk { text, byte_range: start..end });
+        }
+
+        result
+    }
+}
+
+/// Iterates `(byte_offset, line_content)` for each line in `document`.
+fn line_starts(document: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0usize;
+    document.split('\n').map(move |line| {
+        let start = offset;
+        offset += line.len() + 1;
+        (start, line)
+    })
+}
+
+/// Walks back from `index` to the nearest UTF-8 char boundary, so overlap
+/// slicing never panics on a multi-byte character split.
+fn floor_char_boundary(document: &str, mut index: usize) -> usize {
+    while index > 0 && !document.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
@@ -0,0 +1,184 @@
+//! LMDB-backed alternative to [`super::cache::PersistentEmbeddingCache`],
+//! selected via `EmbeddingConfig.cache_backend = "lmdb"`. Implements the
+//! same [`super::store::EmbeddingStore`] contract on top of `heed`'s safe
+//! LMDB bindings instead of SQLite — for deployments that already
+//! standardize on LMDB for other on-disk state and would rather not bring
+//! in a second storage engine just for this cache.
+//!
+//! Rows are keyed by `model_name:dimensions:blake3(text)`, a single flat
+//! string key rather than a composite row like `PersistentEmbeddingCache`'s
+//! `digest` primary key plus `model_name`/`dimensions` columns — LMDB has no
+//! secondary-index query to express "get this digest, but only if its
+//! stored model matches" in, so the model identity has to be folded into
+//! the key itself instead of checked after the fact. The two caches are
+//! independent keyspaces (switching `cache_backend` already means starting
+//! from a cold cache), so there's no need for their digest choices to agree.
+//!
+//! Unlike `PersistentEmbeddingCache`'s single SQL statement for "evict
+//! least-recently-accessed beyond capacity", LMDB has no query planner to
+//! express that in: eviction here scans the whole `last_accessed` table,
+//! so `put` only triggers it once the cache has actually grown past
+//! `capacity` rather than on every write.
+
+use anyhow::{Context, Result};
+use blake3::Hasher;
+use heed::types::{Bytes, Str};
+use heed::{Database, Env, EnvOpenOptions};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Mirrors `PersistentEmbeddingCache`'s pattern of a blocking handle behind
+/// a `Mutex`, accessed from `async fn`s without a dedicated blocking pool —
+/// consistent with how the rest of this crate talks to its embedded
+/// databases.
+#[derive(Clone)]
+pub struct LmdbEmbeddingCache {
+    env: Env,
+    vectors: Database<Str, Bytes>,
+    last_accessed: Database<Str, Bytes>,
+    capacity: usize,
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl LmdbEmbeddingCache {
+    /// Opens (creating if necessary) the LMDB environment at `env_dir`,
+    /// bounding the cache to at most `capacity` entries.
+    pub fn open(env_dir: &Path, capacity: usize) -> Result<Self> {
+        std::fs::create_dir_all(env_dir)
+            .with_context(|| format!("Failed to create LMDB cache directory {}", env_dir.display()))?;
+
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(1024 * 1024 * 1024) // 1GiB, grows lazily — LMDB only commits pages actually written
+                .max_dbs(2)
+                .open(env_dir)
+        }
+        .with_context(|| format!("Failed to open LMDB embedding cache environment: {}", env_dir.display()))?;
+
+        let mut wtxn = env.write_txn()?;
+        let vectors: Database<Str, Bytes> = env
+            .create_database(&mut wtxn, Some("embedding_vectors"))
+            .context("Failed to open embedding_vectors LMDB database")?;
+        let last_accessed: Database<Str, Bytes> = env
+            .create_database(&mut wtxn, Some("embedding_last_accessed"))
+            .context("Failed to open embedding_last_accessed LMDB database")?;
+        wtxn.commit()?;
+
+        Ok(Self {
+            env,
+            vectors,
+            last_accessed,
+            capacity: capacity.max(1),
+            write_lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    /// Looks up the cached embedding for `text`, touching its
+    /// `last_accessed` stamp on a hit so it survives the next eviction scan.
+    pub async fn get(&self, model_name: &str, dimensions: usize, text: &str) -> Option<Vec<f32>> {
+        let key = Self::key(model_name, dimensions, text);
+
+        let vector = {
+            let rtxn = self.env.read_txn().ok()?;
+            self.vectors.get(&rtxn, &key).ok()?.map(Self::decode_vector)
+        };
+        let vector = vector?;
+
+        let _guard = self.write_lock.lock().unwrap();
+        if let Ok(mut wtxn) = self.env.write_txn() {
+            let _ = self.last_accessed.put(&mut wtxn, &key, &Self::now().to_le_bytes());
+            let _ = wtxn.commit();
+        }
+
+        Some(vector)
+    }
+
+    /// Inserts or replaces the cached embedding for `text`, then evicts the
+    /// least-recently-accessed entries beyond `capacity` if the cache has
+    /// grown past it.
+    pub async fn put(&self, model_name: &str, dimensions: usize, text: &str, embedding: &[f32]) {
+        let key = Self::key(model_name, dimensions, text);
+        let bytes = Self::encode_vector(embedding);
+
+        let guard = self.write_lock.lock().unwrap();
+        let Ok(mut wtxn) = self.env.write_txn() else { return };
+        let _ = self.vectors.put(&mut wtxn, &key, &bytes);
+        let _ = self.last_accessed.put(&mut wtxn, &key, &Self::now().to_le_bytes());
+        let _ = wtxn.commit();
+        // `evict_if_over_capacity` takes `write_lock` itself.
+        drop(guard);
+
+        self.evict_if_over_capacity();
+    }
+
+    /// Number of entries currently cached on disk.
+    pub async fn len(&self) -> usize {
+        self.env.read_txn().ok()
+            .and_then(|rtxn| self.vectors.len(&rtxn).ok())
+            .unwrap_or(0) as usize
+    }
+
+    /// Drops every cached entry.
+    pub async fn clear(&self) {
+        let _guard = self.write_lock.lock().unwrap();
+        if let Ok(mut wtxn) = self.env.write_txn() {
+            let _ = self.vectors.clear(&mut wtxn);
+            let _ = self.last_accessed.clear(&mut wtxn);
+            let _ = wtxn.commit();
+        }
+    }
+
+    fn evict_if_over_capacity(&self) {
+        let _guard = self.write_lock.lock().unwrap();
+        let Ok(mut wtxn) = self.env.write_txn() else { return };
+
+        let count = self.vectors.len(&wtxn).unwrap_or(0) as usize;
+        if count <= self.capacity {
+            return;
+        }
+
+        let mut entries: Vec<(String, i64)> = Vec::new();
+        if let Ok(iter) = self.last_accessed.iter(&wtxn) {
+            for entry in iter.flatten() {
+                let (key, stamp) = entry;
+                let mut buf = [0u8; 8];
+                buf.copy_from_slice(&stamp[..8.min(stamp.len())]);
+                entries.push((key.to_string(), i64::from_le_bytes(buf)));
+            }
+        }
+        entries.sort_by_key(|(_, stamp)| *stamp);
+
+        for (key, _) in entries.into_iter().take(count - self.capacity) {
+            let _ = self.vectors.delete(&mut wtxn, &key);
+            let _ = self.last_accessed.delete(&mut wtxn, &key);
+        }
+
+        let _ = wtxn.commit();
+    }
+
+    /// `model_name:dimensions:blake3(text)`, hex-encoded.
+    fn key(model_name: &str, dimensions: usize, text: &str) -> String {
+        let mut hasher = Hasher::new();
+        hasher.update(text.trim().as_bytes());
+        format!("{}:{}:{}", model_name, dimensions, hasher.finalize().to_hex())
+    }
+
+    fn encode_vector(vector: &[f32]) -> Vec<u8> {
+        vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().expect("chunk is exactly 4 bytes")))
+            .collect()
+    }
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
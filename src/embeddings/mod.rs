@@ -1,13 +1,35 @@
 pub mod batch_processor;
+pub mod cache;
+pub mod chunking;
+pub mod lmdb_cache;
+pub mod model_source;
 pub mod models;
 pub mod onnx_runtime;
+pub mod provider;
+pub mod quantization;
+pub mod queue;
+pub mod store;
 
 use anyhow::Result;
 use tracing::debug;
+use tokio::time::Instant;
 
-pub use models::ModelManager;
+use crate::metrics::{build_embedding_metrics_exporter, EmbeddingMetricsExporter};
+
+pub use models::{ModelManager, ModelManagerEnvConfig};
+pub use model_source::{FilesystemModelSource, HttpModelSource, ModelSource, ModelSourceMeta, S3ModelSource};
 pub use onnx_runtime::OnnxEmbeddingEngine;
 pub use batch_processor::BatchProcessor;
+pub use cache::PersistentEmbeddingCache;
+pub use lmdb_cache::LmdbEmbeddingCache;
+pub use chunking::{ChunkerConfig, Language, TextChunk, TextChunker};
+pub use quantization::{cosine_similarity_q8, QuantizedEmbedding};
+pub use queue::EmbeddingQueue;
+pub use store::{open_embedding_store, EmbeddingCacheBackend, EmbeddingStore};
+pub use provider::{
+    create_embedding_provider, EmbeddingProvider, EmbeddingProviderKind,
+    OllamaEmbeddingProvider, OpenAiCompatibleEmbeddingProvider,
+};
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -32,6 +54,10 @@ pub struct LocalEmbeddingEngine {
     config: ServerConfig,
     is_initialized: Arc<AtomicBool>,
     initializing_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Where `encode_texts`/`initialize` report latency, cache effectiveness,
+    /// and model dimensions. Built from `config.embeddings.metrics_exporter`
+    /// at construction time — `Noop` (the default) costs nothing.
+    metrics: Arc<dyn EmbeddingMetricsExporter>,
 }
 
 impl LocalEmbeddingEngine {
@@ -41,8 +67,16 @@ impl LocalEmbeddingEngine {
             .join("kg-mcp-server")
             .join("models");
 
-        let model_manager = ModelManager::new(models_dir);
-        let batch_processor = BatchProcessor::new(config.embeddings.batch_size);
+        let registry = crate::config::model_registry::ModelRegistry::load(
+            config.embeddings.models_file.as_deref(),
+        )?;
+        let model_manager = ModelManager::new(models_dir, registry);
+        let batch_processor = BatchProcessor::with_token_budget(
+            config.embeddings.batch_size,
+            config.embeddings.max_tokens_per_batch,
+        );
+
+        let metrics = build_embedding_metrics_exporter(config.embeddings.metrics_exporter);
 
         Ok(Self {
             model_manager,
@@ -52,6 +86,7 @@ impl LocalEmbeddingEngine {
             config,
             is_initialized: Arc::new(AtomicBool::new(false)),
             initializing_lock: Arc::new(tokio::sync::Mutex::new(())),
+            metrics,
         })
     }
 
@@ -69,25 +104,62 @@ impl LocalEmbeddingEngine {
         }
 
         debug!("🚀 Initializing LocalEmbeddingEngine with model: {}", model_name);
+        let load_start = Instant::now();
 
         // Ensure model is available
         let model_dir = self.model_manager.ensure_model_available(model_name).await?;
         let model_path = self.model_manager.get_model_path(model_name);
 
-        // Get model dimensions
-        let dimensions = crate::config::defaults::get_model_dimensions(model_name)
-            .ok_or_else(|| anyhow::anyhow!("Unknown model dimensions for: {}", model_name))?;
+        // Resolve the model's dimensions through the registry, and make sure
+        // they match what the rest of the stack (`embeddings.dimensions`,
+        // which sizes `HybridSearchEngine`'s vector comparisons) was
+        // configured to expect, rather than silently embedding at the
+        // wrong width.
+        let spec = self.model_manager.registry().get(model_name)?;
+        let dimensions = spec.dimensions;
+        if dimensions != self.config.embeddings.dimensions {
+            return Err(anyhow::anyhow!(
+                "Model '{}' produces {}-dimensional embeddings, but embeddings.dimensions is configured as {}",
+                model_name, dimensions, self.config.embeddings.dimensions
+            ));
+        }
 
         // Initialize ONNX engine
         debug!("🔄 Loading ONNX model from: {}", model_path.display());
         let onnx_engine = OnnxEmbeddingEngine::new(
             model_path.to_str().unwrap(),
-            dimensions
-        )?;
+            dimensions,
+            self.config.embeddings.cache_backend,
+        )?
+        .with_metrics_exporter(self.metrics.clone());
 
         *self.onnx_engine.write().await = Some(onnx_engine);
         *self.current_model.write().await = Some(model_name.to_string());
         self.is_initialized.store(true, Ordering::SeqCst);
+        self.batch_processor.set_metrics_exporter(self.metrics.clone()).await;
+
+        // Give the batch processor's own cache a disk-backed tier on the
+        // same database the rest of the crate uses, so it survives restarts
+        // and dedupes identical spans independently of `OnnxEmbeddingEngine`'s
+        // own per-model cache. Failure to open it just means we fall back to
+        // the in-memory-only cache, not a hard startup error.
+        match open_embedding_store(
+            self.config.embeddings.cache_backend,
+            &self.config.embedding_cache_path(),
+            self.config.embeddings.cache_size,
+        ) {
+            Ok(persistent_cache) => {
+                self.batch_processor
+                    .set_persistent_cache(persistent_cache, model_name.to_string(), dimensions)
+                    .await;
+            }
+            Err(err) => {
+                debug!("Persistent embedding cache unavailable, using in-memory cache only: {}", err);
+            }
+        }
+
+        self.metrics.record_model_load(load_start.elapsed());
+        self.metrics.set_dimensions(dimensions as u64);
 
         debug!("✅ LocalEmbeddingEngine initialized successfully");
         self.print_stats().await;
@@ -102,11 +174,14 @@ impl LocalEmbeddingEngine {
             let _lock = self.initializing_lock.lock().await;
         }
 
+        let encode_start = Instant::now();
         let engine_guard = self.onnx_engine.read().await;
         let engine = engine_guard.as_ref()
             .ok_or_else(|| anyhow::anyhow!("Embedding engine not initialized even after waiting"))?;
 
-        self.batch_processor.process_with_engine(texts.to_vec(), engine).await
+        let result = self.batch_processor.process_with_engine(texts.to_vec(), engine).await;
+        self.metrics.record_encode_batch(texts.len(), encode_start.elapsed());
+        result
     }
 
     /// Encode a single text
@@ -116,9 +191,26 @@ impl LocalEmbeddingEngine {
             .ok_or_else(|| anyhow::anyhow!("No embedding generated"))
     }
 
-    /// Get available models
-    pub fn list_available_models(&self) -> &[&str] {
-        crate::config::defaults::SUPPORTED_MODELS
+    /// Encodes `texts` through the same path as `encode_texts`, then
+    /// quantizes each result to a `QuantizedEmbedding`. For callers that
+    /// want the ~4x-smaller representation directly (see
+    /// `EmbeddingConfig.quantized_cache`) rather than quantizing an
+    /// already-returned `Vec<f32>` themselves.
+    pub async fn encode_texts_quantized(&self, texts: &[String]) -> Result<Vec<QuantizedEmbedding>> {
+        let embeddings = self.encode_texts(texts).await?;
+        Ok(embeddings.iter().map(|e| QuantizedEmbedding::quantize(e)).collect())
+    }
+
+    /// The `[embeddings]` config this engine was built from, for callers
+    /// (e.g. `CodebaseIndexer::new_with_mcp_config`) that need one of its
+    /// knobs without threading `ServerConfig` through separately.
+    pub fn embedding_config(&self) -> &crate::config::EmbeddingConfig {
+        &self.config.embeddings
+    }
+
+    /// Get available models (built-ins plus whatever `embeddings.models_file` registered)
+    pub fn list_available_models(&self) -> Vec<String> {
+        self.model_manager.registry().names()
     }
 
     /// Get downloaded models
@@ -143,8 +235,9 @@ impl LocalEmbeddingEngine {
 
     /// Get model dimensions
     pub async fn dimensions(&self) -> Option<usize> {
-        self.current_model.read().await.as_ref()
-            .and_then(|name| crate::config::defaults::get_model_dimensions(name))
+        let current = self.current_model.read().await;
+        let name = current.as_ref()?;
+        self.model_manager.registry().get(name).ok().map(|spec| spec.dimensions)
     }
 
     /// Clear embedding cache
@@ -171,6 +264,20 @@ impl LocalEmbeddingEngine {
         })
     }
 
+    /// Point-in-time snapshot for the `mcp` server's `/metrics` admin
+    /// endpoint: everything `get_cache_stats` reports, plus the identity of
+    /// the currently loaded model. Answers "what's true right now" — for
+    /// "how has this trended", see `EmbeddingMetricsExporter`, which this
+    /// engine reports through independently whenever `embeddings.metrics_exporter`
+    /// is configured to something other than `Noop`.
+    pub async fn metrics_handle(&self) -> Result<EngineMetrics> {
+        Ok(EngineMetrics {
+            model: self.current_model().await,
+            dimensions: self.dimensions().await,
+            cache: self.get_cache_stats().await?,
+        })
+    }
+
     /// Precompute embeddings for common queries
     pub async fn warmup(&self, common_queries: Vec<String>) -> Result<()> {
         debug!("🔥 Warming up embedding engine...");
@@ -205,10 +312,21 @@ impl LocalEmbeddingEngine {
         self.initialize(model_name).await
     }
 
-    /// Get embedding similarity between two texts
+    /// Get embedding similarity between two texts. Compares through
+    /// `cosine_similarity_q8` on quantized vectors instead of the full f32
+    /// path when `embeddings.quantized_cache` is enabled, transparently to
+    /// the caller — see `QuantizedEmbedding`.
     pub async fn similarity(&self, text1: &str, text2: &str) -> Result<f32> {
+        if self.config.embeddings.quantized_cache {
+            let quantized = self.encode_texts_quantized(&[text1.to_string(), text2.to_string()]).await?;
+            if quantized.len() != 2 {
+                return Err(anyhow::anyhow!("Failed to generate embeddings for both texts"));
+            }
+            return Ok(cosine_similarity_q8(&quantized[0], &quantized[1]));
+        }
+
         let embeddings = self.encode_texts(&[text1.to_string(), text2.to_string()]).await?;
-        
+
         if embeddings.len() != 2 {
             return Err(anyhow::anyhow!("Failed to generate embeddings for both texts"));
         }
@@ -216,20 +334,30 @@ impl LocalEmbeddingEngine {
         Ok(cosine_similarity(&embeddings[0], &embeddings[1]))
     }
 
-    /// Get semantic search results
+    /// Get semantic search results. Same quantized-comparison opt-in as
+    /// `similarity`.
     pub async fn semantic_search(&self, query: &str, candidates: &[String], top_k: usize) -> Result<Vec<(usize, f32)>> {
-        let query_embedding = self.encode_text(query).await?;
-        let candidate_embeddings = self.encode_texts(candidates).await?;
-
-        let mut similarities: Vec<(usize, f32)> = candidate_embeddings
-            .iter()
-            .enumerate()
-            .map(|(i, embedding)| (i, cosine_similarity(&query_embedding, embedding)))
-            .collect();
+        let mut similarities: Vec<(usize, f32)> = if self.config.embeddings.quantized_cache {
+            let query_embedding = QuantizedEmbedding::quantize(&self.encode_text(query).await?);
+            let candidate_embeddings = self.encode_texts_quantized(candidates).await?;
+            candidate_embeddings
+                .iter()
+                .enumerate()
+                .map(|(i, embedding)| (i, cosine_similarity_q8(&query_embedding, embedding)))
+                .collect()
+        } else {
+            let query_embedding = self.encode_text(query).await?;
+            let candidate_embeddings = self.encode_texts(candidates).await?;
+            candidate_embeddings
+                .iter()
+                .enumerate()
+                .map(|(i, embedding)| (i, cosine_similarity(&query_embedding, embedding)))
+                .collect()
+        };
 
         // Sort by similarity (highest first)
         similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
+
         // Return top_k results
         similarities.truncate(top_k);
         Ok(similarities)
@@ -269,6 +397,14 @@ pub struct CacheStats {
     pub onnx_cache_size: usize,
 }
 
+/// Richer snapshot than [`CacheStats`] alone — see `LocalEmbeddingEngine::metrics_handle`.
+#[derive(Debug, Clone, Default)]
+pub struct EngineMetrics {
+    pub model: Option<String>,
+    pub dimensions: Option<usize>,
+    pub cache: CacheStats,
+}
+
 /// Calculate cosine similarity between two vectors
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -0,0 +1,191 @@
+//! Pluggable storage backends for fetching model files.
+//!
+//! [`ModelManager`](super::ModelManager) used to be hard-wired to `reqwest`
+//! HTTP(S) fetches. [`ModelSource`] is the seam that lets a deployment point
+//! it at a mirror instead — its own S3/MinIO bucket, or a shared NFS mount —
+//! without `ModelManager`'s download/validate/cleanup logic needing to know
+//! which backend is active. This mirrors the generic object-store layering
+//! `EmbeddingProvider` already uses for the embedding backend itself.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use std::path::PathBuf;
+use std::pin::Pin;
+
+/// One chunk of a `ModelSource::fetch` response body.
+pub type ModelByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// What `ModelSource::head` reports about a key before `ModelManager`
+/// commits to fetching it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ModelSourceMeta {
+    pub content_length: Option<u64>,
+    /// Whether `fetch`'s `offset` parameter is honored for this key. When
+    /// `false`, callers should fetch from `offset: 0` and truncate any
+    /// partial download they were hoping to resume.
+    pub supports_range: bool,
+}
+
+/// Where `ModelManager` downloads model/tokenizer/config files from. `key`
+/// is whatever the implementor needs to locate a file — a URL for
+/// [`HttpModelSource`], a path relative to a root for
+/// [`FilesystemModelSource`], an object key for [`S3ModelSource`].
+#[async_trait]
+pub trait ModelSource: Send + Sync {
+    /// Starts streaming `key`, resuming from byte `offset` if non-zero and
+    /// `head(key)` reported `supports_range`.
+    async fn fetch(&self, key: &str, offset: u64) -> Result<ModelByteStream>;
+
+    /// Metadata for `key` without downloading it.
+    async fn head(&self, key: &str) -> Result<ModelSourceMeta>;
+}
+
+/// Issues a ranged GET against `url` via `client`, for the two HTTP-based
+/// sources ([`HttpModelSource`], [`S3ModelSource`]) that only differ in how
+/// they build the URL.
+async fn http_fetch(client: &reqwest::Client, url: &str, offset: u64) -> Result<ModelByteStream> {
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+
+    let response = request.send().await
+        .with_context(|| format!("Failed to start download of {}", url))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Download of {} failed with status {}", url, response.status());
+    }
+
+    Ok(Box::pin(response.bytes_stream().map(|chunk| {
+        chunk.context("Failed to read response chunk")
+    })))
+}
+
+/// Issues a HEAD against `url` via `client`.
+async fn http_head(client: &reqwest::Client, url: &str) -> Result<ModelSourceMeta> {
+    let response = client.head(url).send().await
+        .with_context(|| format!("Failed to HEAD {}", url))?;
+
+    let supports_range = response
+        .headers()
+        .get(reqwest::header::ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        == Some("bytes");
+
+    Ok(ModelSourceMeta { content_length: response.content_length(), supports_range })
+}
+
+/// Fetches over plain HTTP(S) — the default, matching this crate's
+/// historical behavior of downloading models directly from their
+/// `ModelSpec` URLs.
+pub struct HttpModelSource {
+    client: reqwest::Client,
+}
+
+impl HttpModelSource {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ModelSource for HttpModelSource {
+    async fn fetch(&self, key: &str, offset: u64) -> Result<ModelByteStream> {
+        http_fetch(&self.client, key, offset).await
+    }
+
+    async fn head(&self, key: &str) -> Result<ModelSourceMeta> {
+        http_head(&self.client, key).await
+    }
+}
+
+/// Reads model files from a local path or shared mount (e.g. an NFS-backed
+/// model cache), keyed by a path relative to `root`. Seeking to `offset`
+/// always succeeds, so this source always reports `supports_range: true`.
+pub struct FilesystemModelSource {
+    root: PathBuf,
+}
+
+impl FilesystemModelSource {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn resolve(&self, key: &str) -> PathBuf {
+        self.root.join(key.trim_start_matches('/'))
+    }
+}
+
+#[async_trait]
+impl ModelSource for FilesystemModelSource {
+    async fn fetch(&self, key: &str, offset: u64) -> Result<ModelByteStream> {
+        use tokio::io::AsyncSeekExt;
+
+        let path = self.resolve(key);
+        let mut file = tokio::fs::File::open(&path).await
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+
+        if offset > 0 {
+            file.seek(std::io::SeekFrom::Start(offset)).await
+                .with_context(|| format!("Failed to seek {} to offset {}", path.display(), offset))?;
+        }
+
+        let stream = tokio_util::io::ReaderStream::new(file)
+            .map(|chunk| chunk.context("Failed to read local model file"));
+        Ok(Box::pin(stream))
+    }
+
+    async fn head(&self, key: &str) -> Result<ModelSourceMeta> {
+        let path = self.resolve(key);
+        let metadata = tokio::fs::metadata(&path).await
+            .with_context(|| format!("Failed to stat {}", path.display()))?;
+        Ok(ModelSourceMeta { content_length: Some(metadata.len()), supports_range: true })
+    }
+}
+
+/// Fetches from an S3-compatible bucket via path-style HTTPS GET/HEAD
+/// against `{endpoint}/{bucket}/{prefix}{key}`.
+///
+/// Scope note: this only supports anonymous/public-read buckets and
+/// self-hosted stores (MinIO, Ceph RGW, R2, ...) configured for
+/// unauthenticated access — it does not implement SigV4 request signing, so
+/// a private AWS bucket needs presigned URLs or a reverse proxy in front of
+/// it rather than being pointed at directly.
+pub struct S3ModelSource {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3ModelSource {
+    /// `endpoint` defaults to the standard regional AWS endpoint when
+    /// `None`; set it for self-hosted/region-incompatible stores.
+    pub fn new(client: reqwest::Client, region: &str, bucket: String, prefix: String, endpoint: Option<String>) -> Self {
+        let endpoint = endpoint.unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        Self { client, endpoint, bucket, prefix }
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        format!(
+            "{}/{}/{}{}",
+            self.endpoint.trim_end_matches('/'),
+            self.bucket,
+            self.prefix,
+            key.trim_start_matches('/'),
+        )
+    }
+}
+
+#[async_trait]
+impl ModelSource for S3ModelSource {
+    async fn fetch(&self, key: &str, offset: u64) -> Result<ModelByteStream> {
+        http_fetch(&self.client, &self.object_url(key), offset).await
+    }
+
+    async fn head(&self, key: &str) -> Result<ModelSourceMeta> {
+        http_head(&self.client, &self.object_url(key)).await
+    }
+}
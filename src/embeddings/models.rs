@@ -1,63 +1,329 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::debug;
 use std::fs;
-use reqwest;
 use sha2::{Sha256, Digest};
 use tokio::fs as async_fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex as AsyncMutex, Semaphore};
+use futures::StreamExt;
 use crate::config::defaults::*;
+use crate::config::model_registry::ModelRegistry;
+use crate::embeddings::model_source::{HttpModelSource, ModelSource};
 use std::collections::HashMap;
 
+/// Caps how many models `ModelManager` downloads at once when nothing else
+/// is configured, bounding bandwidth use under parallel agent requests.
+const DEFAULT_MAX_CONCURRENT_TRANSFERS: usize = 4;
+
+/// Environment variables `ModelManager::from_env` reads, falling back to
+/// `crate::config::defaults`/a hardcoded default for anything unset.
+pub const MODELS_DIR_ENV_VAR: &str = "KG_MODELS_DIR";
+pub const MODEL_DOWNLOAD_TIMEOUT_ENV_VAR: &str = "KG_MODEL_DOWNLOAD_TIMEOUT_SECS";
+pub const MODEL_MIRROR_ENV_VAR: &str = "KG_MODEL_MIRROR_BASE_URL";
+pub const MODEL_PROXY_ENV_VAR: &str = "KG_MODEL_HTTP_PROXY";
+
+/// What `ModelManager::from_env` resolved each setting to, kept around so an
+/// operator can log/verify what was actually picked up from the environment
+/// (e.g. in a startup banner) rather than re-reading the env vars themselves.
+#[derive(Debug, Clone)]
+pub struct ModelManagerEnvConfig {
+    pub models_dir: PathBuf,
+    pub timeout_secs: u64,
+    pub mirror_base_url: Option<String>,
+    pub proxy_url: Option<String>,
+}
+
+impl ModelManagerEnvConfig {
+    fn from_env() -> Self {
+        let models_dir = std::env::var(MODELS_DIR_ENV_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./models"));
+        let timeout_secs = std::env::var(MODEL_DOWNLOAD_TIMEOUT_ENV_VAR)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+        let mirror_base_url = std::env::var(MODEL_MIRROR_ENV_VAR).ok().filter(|v| !v.is_empty());
+        let proxy_url = std::env::var(MODEL_PROXY_ENV_VAR).ok().filter(|v| !v.is_empty());
+
+        Self { models_dir, timeout_secs, mirror_base_url, proxy_url }
+    }
+}
+
 /// Download and manage embedding models
 #[derive(Clone)]
 pub struct ModelManager {
     models_dir: PathBuf,
-    client: reqwest::Client,
+    source: Arc<dyn ModelSource>,
+    registry: ModelRegistry,
+    /// Bounds how many models download concurrently; the three files of a
+    /// single model's download share one permit (see `download_and_validate`).
+    download_semaphore: Arc<Semaphore>,
+    /// Per-model lock so concurrent `ensure_model_available` calls for the
+    /// same model coalesce onto a single download instead of each starting
+    /// their own (and racing to write the same `.part` files).
+    in_flight_downloads: Arc<AsyncMutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+    /// Rewrites the scheme+host of model/tokenizer/config URLs before
+    /// fetching, set by `from_env`'s `MODEL_MIRROR_ENV_VAR`. `None` for
+    /// managers built via `new`/`with_source`, which fetch each
+    /// `ModelSpec`'s URL unmodified.
+    mirror_base_url: Option<String>,
+    /// The environment-derived settings `from_env` resolved. `None` for
+    /// managers built via `new`/`with_source`.
+    env_config: Option<ModelManagerEnvConfig>,
 }
 
 impl ModelManager {
-    pub fn new(models_dir: PathBuf) -> Self {
-        // Ensure models directory exists
-        if !models_dir.exists() {
-            fs::create_dir_all(&models_dir).expect("Failed to create models directory");
-        }
-        
+    /// Convenience constructor matching this crate's historical behavior:
+    /// model/tokenizer/config files are fetched over plain HTTP(S) from the
+    /// URLs in each `ModelSpec`. Use `with_source` to point at a mirror
+    /// instead (a team's own S3/MinIO bucket, a shared NFS mount, ...).
+    pub fn new(models_dir: PathBuf, registry: ModelRegistry) -> Self {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(300)) // 5 minute timeout
             .build()
             .expect("Failed to create HTTP client");
-            
+
+        Self::with_source(models_dir, registry, Arc::new(HttpModelSource::new(client)))
+    }
+
+    /// Builds a manager that fetches model files through `source` instead of
+    /// assuming HTTP — the seam `ModelSource` exists for.
+    pub fn with_source(models_dir: PathBuf, registry: ModelRegistry, source: Arc<dyn ModelSource>) -> Self {
+        Self::with_source_and_concurrency(models_dir, registry, source, DEFAULT_MAX_CONCURRENT_TRANSFERS)
+    }
+
+    /// Same as `with_source`, additionally setting how many models may
+    /// download concurrently (each model's three files count as one slot).
+    pub fn with_source_and_concurrency(
+        models_dir: PathBuf,
+        registry: ModelRegistry,
+        source: Arc<dyn ModelSource>,
+        max_concurrent_transfers: usize,
+    ) -> Self {
+        if !models_dir.exists() {
+            fs::create_dir_all(&models_dir).expect("Failed to create models directory");
+        }
+
         Self {
             models_dir,
-            client,
+            source,
+            registry,
+            download_semaphore: Arc::new(Semaphore::new(max_concurrent_transfers.max(1))),
+            in_flight_downloads: Arc::new(AsyncMutex::new(HashMap::new())),
+            mirror_base_url: None,
+            env_config: None,
         }
     }
 
+    /// Builds a manager entirely from environment variables, for deployments
+    /// that construct `ModelManager` without going through `ServerConfig`
+    /// (e.g. standalone CLI tooling). `MODEL_MIRROR_ENV_VAR`, if set,
+    /// rewrites the scheme and host of every model/tokenizer/config URL to
+    /// an internal mirror, so an air-gapped or corporate-proxy deployment
+    /// can redirect all model downloads without a rebuild or a custom models
+    /// file; `MODEL_PROXY_ENV_VAR` routes the HTTP client itself through an
+    /// outbound proxy.
+    pub fn from_env(registry: ModelRegistry) -> Result<Self> {
+        let env_config = ModelManagerEnvConfig::from_env();
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(env_config.timeout_secs));
+        if let Some(proxy_url) = &env_config.proxy_url {
+            builder = builder.proxy(
+                reqwest::Proxy::all(proxy_url)
+                    .with_context(|| format!("Invalid {}: {}", MODEL_PROXY_ENV_VAR, proxy_url))?,
+            );
+        }
+        let client = builder.build().context("Failed to create HTTP client")?;
+
+        let mut manager = Self::with_source(
+            env_config.models_dir.clone(),
+            registry,
+            Arc::new(HttpModelSource::new(client)),
+        );
+        manager.mirror_base_url = env_config.mirror_base_url.clone();
+        manager.env_config = Some(env_config);
+        Ok(manager)
+    }
+
+    /// The environment-derived settings this manager was built with, if it
+    /// was built via `from_env` — for operators to log/verify what was
+    /// picked up. `None` for managers built via `new`/`with_source`.
+    pub fn env_config(&self) -> Option<&ModelManagerEnvConfig> {
+        self.env_config.as_ref()
+    }
+
+    /// Rewrites `url`'s scheme and host to `self.mirror_base_url`'s, if set,
+    /// preserving the path/query so a mirror only needs to serve the same
+    /// directory layout as the upstream host. A no-op when no mirror is
+    /// configured, or when `url` has no `://` to find a host boundary at.
+    fn apply_mirror(&self, url: &str) -> String {
+        let Some(mirror) = &self.mirror_base_url else { return url.to_string() };
+        let Some(scheme_end) = url.find("://") else { return url.to_string() };
+        let Some(host_end) = url[scheme_end + 3..].find('/') else { return url.to_string() };
+        let path_start = scheme_end + 3 + host_end;
+        format!("{}{}", mirror.trim_end_matches('/'), &url[path_start..])
+    }
+
+    /// The registry this manager resolves model names against.
+    pub fn registry(&self) -> &ModelRegistry {
+        &self.registry
+    }
+
+    /// Returns (creating if needed) the per-model lock `ensure_model_available`
+    /// holds for the duration of a download, so a second caller asking for
+    /// the same model while one is already in flight waits for it instead of
+    /// starting a duplicate.
+    async fn model_lock(&self, model_name: &str) -> Arc<AsyncMutex<()>> {
+        self.in_flight_downloads
+            .lock()
+            .await
+            .entry(model_name.to_string())
+            .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+            .clone()
+    }
+
     pub async fn download_model(&self, model_name: &str) -> Result<()> {
         debug!("🔄 Downloading model: {}", model_name);
-        
-        let (model_url, tokenizer_url, config_url) = get_model_urls(model_name)
-            .ok_or_else(|| anyhow::anyhow!("Unsupported model: {}", model_name))?;
+
+        let spec = self.registry.get(model_name)?;
+        let model_url = self.apply_mirror(&spec.resolve_url(&spec.model_url));
+        let tokenizer_url = self.apply_mirror(&spec.resolve_url(&spec.tokenizer_url));
+        let config_url = self.apply_mirror(&spec.resolve_url(&spec.config_url));
+        let (model_sha256, tokenizer_sha256, config_sha256) =
+            (spec.model_sha256.clone(), spec.tokenizer_sha256.clone(), spec.config_sha256.clone());
 
         let model_dir = self.models_dir.join(model_name);
         async_fs::create_dir_all(&model_dir).await
             .context("Failed to create model directory")?;
 
-        // Download model files
-        self.download_file(&model_url, &model_dir.join(MODEL_FILENAME)).await
-            .context("Failed to download model file")?;
-            
-        self.download_file(&tokenizer_url, &model_dir.join(TOKENIZER_FILENAME)).await
-            .context("Failed to download tokenizer file")?;
-            
-        self.download_file(&config_url, &model_dir.join(CONFIG_FILENAME)).await
-            .context("Failed to download config file")?;
+        // Download model files, verifying each against its pinned checksum
+        // (if the registry entry has one) and refusing to keep a file that
+        // doesn't match. A corrupt/tampered download leaves this model's
+        // whole directory removed rather than a half-verified one sitting
+        // around looking installed.
+        if let Err(e) = self.download_and_validate(&model_url, &tokenizer_url, &config_url, &model_dir, model_sha256.as_deref(), tokenizer_sha256.as_deref(), config_sha256.as_deref(), model_name).await {
+            async_fs::remove_dir_all(&model_dir).await.ok();
+            return Err(e);
+        }
+
+        debug!("✅ Model downloaded successfully: {}", model_name);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn download_and_validate(
+        &self,
+        model_url: &str,
+        tokenizer_url: &str,
+        config_url: &str,
+        model_dir: &Path,
+        model_sha256: Option<&str>,
+        tokenizer_sha256: Option<&str>,
+        config_sha256: Option<&str>,
+        model_name: &str,
+    ) -> Result<()> {
+        // One permit covers all three files of this model — the semaphore
+        // bounds how many *models* download at once, not how many individual
+        // file transfers are in flight.
+        let _permit = self.download_semaphore.acquire().await
+            .expect("Download semaphore closed");
+
+        let (model_result, tokenizer_result, config_result) = tokio::join!(
+            self.fetch_verified(model_url, &model_dir.join(MODEL_FILENAME), model_sha256),
+            self.fetch_verified(tokenizer_url, &model_dir.join(TOKENIZER_FILENAME), tokenizer_sha256),
+            self.fetch_verified(config_url, &model_dir.join(CONFIG_FILENAME), config_sha256),
+        );
+        model_result.context("Failed to download model file")?;
+        tokenizer_result.context("Failed to download tokenizer file")?;
+        config_result.context("Failed to download config file")?;
 
         // Validate model files
         self.validate_model(model_name).await
             .context("Model validation failed after download")?;
 
-        debug!("✅ Model downloaded successfully: {}", model_name);
+        Ok(())
+    }
+
+    /// Re-checks an already-downloaded model's files against the registry's
+    /// pinned checksums without re-downloading anything, so on-disk
+    /// corruption (a bad disk, an interrupted `cp`, manual tampering) can be
+    /// detected on demand instead of only at download time. Returns `Ok(true)`
+    /// only if every file the registry has a checksum for still matches it;
+    /// files with no pinned checksum are skipped, matching `fetch_verified`'s
+    /// "no checksum means no verification" behavior.
+    pub async fn verify_model(&self, model_name: &str) -> Result<bool> {
+        let spec = self.registry.get(model_name)?;
+
+        if !self.model_exists(model_name) {
+            return Err(anyhow::anyhow!("Model {} is not downloaded", model_name));
+        }
+
+        let checks = [
+            (self.get_model_path(model_name), spec.model_sha256.as_deref()),
+            (self.get_tokenizer_path(model_name), spec.tokenizer_sha256.as_deref()),
+            (self.get_config_path(model_name), spec.config_sha256.as_deref()),
+        ];
+
+        for (path, expected) in checks {
+            let Some(expected) = expected else { continue };
+            let actual = self.calculate_checksum(&path).await?;
+            if actual != expected {
+                debug!("  ❌ Checksum mismatch for {}: expected {}, got {}", path.display(), expected, actual);
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Directory verified files are cached under, keyed by their SHA-256, so
+    /// re-fetching a file this manager has already verified once (e.g. after
+    /// a model directory was deleted) can skip the network round-trip
+    /// entirely. It sits inside `models_dir` and holds no model files, so
+    /// `cleanup_incomplete_downloads` removes it along with partial downloads.
+    fn verified_cache_dir(&self) -> PathBuf {
+        self.models_dir.join(".verified")
+    }
+
+    /// Downloads `url` to `dest`, or — if `expected_sha256` is set and
+    /// already in the verified-file cache — copies the cached file instead
+    /// of re-downloading. When `expected_sha256` is set and the download
+    /// doesn't match it, the partial file is removed and an error returned;
+    /// callers must not treat a mismatched file as usable. A verified
+    /// download is copied into the cache so later calls for the same hash
+    /// (any model name, any of the three file kinds) are served locally.
+    async fn fetch_verified(&self, url: &str, dest: &Path, expected_sha256: Option<&str>) -> Result<()> {
+        if let Some(expected) = expected_sha256 {
+            let cached = self.verified_cache_dir().join(expected);
+            if cached.exists() {
+                debug!("  ✅ Reusing verified file for checksum {}", expected);
+                async_fs::copy(&cached, dest).await
+                    .context("Failed to copy cached verified file")?;
+                return Ok(());
+            }
+        }
+
+        self.download_file(url, dest).await?;
+
+        if let Some(expected) = expected_sha256 {
+            let actual = self.calculate_checksum(dest).await?;
+            if actual != expected {
+                async_fs::remove_file(dest).await.ok();
+                return Err(anyhow::anyhow!(
+                    "Checksum mismatch for {}: expected {}, got {}. Refusing to load a model file that doesn't match its pinned hash.",
+                    url, expected, actual
+                ));
+            }
+
+            async_fs::create_dir_all(self.verified_cache_dir()).await
+                .context("Failed to create verified file cache directory")?;
+            async_fs::copy(dest, self.verified_cache_dir().join(expected)).await
+                .context("Failed to populate verified file cache")?;
+        }
+
         Ok(())
     }
 
@@ -70,10 +336,18 @@ impl ModelManager {
 
     pub async fn ensure_model_available(&self, model_name: &str) -> Result<PathBuf> {
         if !self.model_exists(model_name) {
-            debug!("📥 Model not found locally, downloading: {}", model_name);
-            self.download_model(model_name).await?;
+            // Hold this model's lock for the rest of the check-then-download
+            // so a concurrent caller asking for the same model blocks here
+            // instead of starting a second download of the same files.
+            let lock = self.model_lock(model_name).await;
+            let _guard = lock.lock().await;
+
+            if !self.model_exists(model_name) {
+                debug!("📥 Model not found locally, downloading: {}", model_name);
+                self.download_model(model_name).await?;
+            }
         }
-        
+
         Ok(self.models_dir.join(model_name))
     }
 
@@ -89,26 +363,64 @@ impl ModelManager {
         self.models_dir.join(model_name).join(CONFIG_FILENAME)
     }
 
-    async fn download_file(&self, url: &str, path: &Path) -> Result<()> {
-                    debug!("  ⬇️  Downloading: {}", url);
-        
-        let response = self.client.get(url).send().await
+    /// Streams `url` into `path` chunk-by-chunk (bounding memory use
+    /// regardless of file size) via a `<path>.part` sibling, resuming a
+    /// prior partial download instead of restarting it when the server
+    /// honors a `Range` request. `<path>.part` is only renamed into place
+    /// once the whole body has been written, so a download killed partway
+    /// through always leaves a resumable `.part` file rather than a
+    /// corrupt final file.
+    async fn download_file(&self, key: &str, path: &Path) -> Result<()> {
+        debug!("  ⬇️  Downloading: {}", key);
+
+        let part_path = Self::part_path(path);
+        let existing_len = async_fs::metadata(&part_path).await.map(|m| m.len()).unwrap_or(0);
+
+        // A `head` failure just means "assume no resume support" rather
+        // than aborting here — `fetch` below will surface the real error
+        // (missing key, auth failure, ...) if `key` is actually bad.
+        let meta = self.source.head(key).await.unwrap_or_default();
+        let resuming = existing_len > 0 && meta.supports_range;
+        let offset = if resuming { existing_len } else { 0 };
+
+        let mut stream = self.source.fetch(key, offset).await
             .context("Failed to start download")?;
-            
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Download failed with status: {}", response.status()));
-        }
 
-        let content = response.bytes().await
-            .context("Failed to download file content")?;
+        let mut file = if resuming {
+            debug!("  ↻ Resuming partial download of {} at byte {}", path.display(), offset);
+            async_fs::OpenOptions::new().append(true).open(&part_path).await
+                .context("Failed to reopen partial download for append")?
+        } else {
+            // Either this is a fresh download or the source can't resume
+            // from `existing_len` — either way, start the `.part` file over
+            // from scratch.
+            async_fs::File::create(&part_path).await
+                .context("Failed to create partial download file")?
+        };
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Failed to read download chunk")?;
+            file.write_all(&chunk).await
+                .context("Failed to write downloaded chunk")?;
+        }
+        file.flush().await.context("Failed to flush downloaded file")?;
+        drop(file);
 
-        async_fs::write(path, content).await
-            .context("Failed to write downloaded file")?;
+        async_fs::rename(&part_path, path).await
+            .context("Failed to finalize downloaded file")?;
 
         debug!("  ✅ Downloaded: {}", path.display());
         Ok(())
     }
 
+    /// The `.part` sibling `download_file` streams into before atomically
+    /// renaming it to `path` on completion.
+    fn part_path(path: &Path) -> PathBuf {
+        let mut part = path.as_os_str().to_os_string();
+        part.push(".part");
+        PathBuf::from(part)
+    }
+
     async fn validate_model(&self, model_name: &str) -> Result<()> {
         let model_path = self.get_model_path(model_name);
         let tokenizer_path = self.get_tokenizer_path(model_name);
@@ -140,10 +452,27 @@ impl ModelManager {
         Ok(())
     }
 
+    /// Hashes `path` in fixed-size chunks rather than reading it into memory
+    /// whole — model files run into the hundreds of megabytes, and
+    /// `fetch_verified`/`verify_model` both call this on every download and
+    /// on-demand re-check.
     pub async fn calculate_checksum(&self, path: &Path) -> Result<String> {
-        let content = async_fs::read(path).await?;
+        use tokio::io::AsyncReadExt;
+
+        let mut file = async_fs::File::open(path).await
+            .with_context(|| format!("Failed to open {} for checksum", path.display()))?;
         let mut hasher = Sha256::new();
-        hasher.update(&content);
+        let mut buf = vec![0u8; 1024 * 1024];
+
+        loop {
+            let read = file.read(&mut buf).await
+                .with_context(|| format!("Failed to read {} for checksum", path.display()))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+
         Ok(format!("{:x}", hasher.finalize()))
     }
 
@@ -1,9 +1,34 @@
 use anyhow::{Context, Result};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::debug;
-use tokio::sync::RwLock;
+use tokio::sync::broadcast;
 use std::collections::HashMap;
-use crate::embeddings::EmbeddingEngine;
+use ort::{session::Session, value::Value};
+use tokenizers::Tokenizer;
+use crate::embeddings::{open_embedding_store, EmbeddingCacheBackend, EmbeddingEngine, EmbeddingQueue, EmbeddingStore};
+use crate::metrics::{EmbeddingMetricsExporter, NoopEmbeddingMetrics};
+
+/// How long a caller waits for other concurrent callers' texts to pile up
+/// before the coalesced batch is dispatched. Short enough to not add
+/// noticeable latency to a single caller, long enough for near-simultaneous
+/// callers (e.g. several tools firing on the same tick) to land in one batch.
+const COALESCE_WINDOW: Duration = Duration::from_millis(5);
+
+/// State shared by every in-flight `encode_batch` call, so concurrent
+/// requests for the same text share one computation instead of each missing
+/// the cache and recomputing it.
+#[derive(Default)]
+struct CoalesceState {
+    /// Unique texts queued for the next flush.
+    pending: Vec<String>,
+    /// One broadcast sender per text currently pending or being computed;
+    /// removed once that text's result has been sent out.
+    senders: HashMap<String, broadcast::Sender<Result<Vec<f32>, String>>>,
+    /// Whether a flush is already scheduled, so only the first caller to
+    /// queue a new text spawns the debounce task.
+    flush_scheduled: bool,
+}
 
 #[derive(Clone)]
 pub struct OnnxEmbeddingEngine {
@@ -12,16 +37,22 @@ pub struct OnnxEmbeddingEngine {
     model_name: String,
     max_sequence_length: usize,
     batch_size: usize,
-    cache: Arc<RwLock<HashMap<String, Vec<f32>>>>,
+    queue: EmbeddingQueue,
+    cache: Arc<dyn EmbeddingStore>,
+    /// Reports `encode_batch`'s cache hit/miss outcomes as the `"onnx"`
+    /// tier, distinct from `BatchProcessor`'s own `"batch_memory"`/
+    /// `"batch_persistent"` tiers above it. Defaults to `NoopEmbeddingMetrics`;
+    /// set via `with_metrics_exporter`.
+    metrics: Arc<dyn EmbeddingMetricsExporter>,
+    coalesce: Arc<tokio::sync::Mutex<CoalesceState>>,
+    session: Arc<tokio::sync::Mutex<Session>>,
+    tokenizer: Arc<Tokenizer>,
 }
 
 impl OnnxEmbeddingEngine {
-    pub fn new(model_path: &str, dimensions: usize) -> Result<Self> {
+    pub fn new(model_path: &str, dimensions: usize, cache_backend: EmbeddingCacheBackend) -> Result<Self> {
         debug!("🔄 Initializing ONNX Runtime embedding engine...");
-        
-        // For now, we'll create a placeholder implementation
-        // In production, this would initialize the actual ONNX Runtime
-        
+
         let model_name = std::path::Path::new(model_path)
             .parent()
             .and_then(|p| p.file_name())
@@ -29,18 +60,65 @@ impl OnnxEmbeddingEngine {
             .unwrap_or("unknown")
             .to_string();
 
-        debug!("✅ ONNX Runtime session created successfully (placeholder)");
+        let session = Session::builder()
+            .context("Failed to create ONNX Runtime session builder")?
+            .commit_from_file(model_path)
+            .with_context(|| format!("Failed to load ONNX model from {}", model_path))?;
+
+        let tokenizer_path = std::path::Path::new(model_path)
+            .parent()
+            .map(|p| p.join("tokenizer.json"))
+            .ok_or_else(|| anyhow::anyhow!("Model path {} has no parent directory", model_path))?;
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| anyhow::anyhow!("Failed to load tokenizer from {}: {}", tokenizer_path.display(), e))?;
+
+        let max_sequence_length = 512;
+        // Truncate long inputs during tokenization itself, rather than
+        // after the fact, so an over-length text never reaches inference.
+        tokenizer
+            .with_truncation(Some(tokenizers::TruncationParams {
+                max_length: max_sequence_length,
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow::anyhow!("Failed to configure tokenizer truncation: {}", e))?;
+        tokenizer.with_padding(Some(tokenizers::PaddingParams {
+            strategy: tokenizers::PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+
+        debug!("✅ ONNX Runtime session and tokenizer loaded successfully");
+
+        let cache = open_embedding_store(cache_backend, &Self::cache_db_path(model_path, cache_backend), 10_000)
+            .context("Failed to open persistent embedding cache")?;
 
         Ok(Self {
             model_path: model_path.to_string(),
             dimensions,
             model_name,
-            max_sequence_length: 512,
+            max_sequence_length,
             batch_size: 32,
-            cache: Arc::new(RwLock::new(HashMap::new())),
+            queue: EmbeddingQueue::default(),
+            cache,
+            metrics: Arc::new(NoopEmbeddingMetrics),
+            coalesce: Arc::new(tokio::sync::Mutex::new(CoalesceState::default())),
+            session: Arc::new(tokio::sync::Mutex::new(session)),
+            tokenizer: Arc::new(tokenizer),
         })
     }
 
+    /// Reports this engine's own cache hits/misses (the `"onnx"` tier)
+    /// through `exporter` instead of discarding them. See
+    /// `EmbeddingMetricsExporterKind`.
+    pub fn with_metrics_exporter(mut self, exporter: Arc<dyn EmbeddingMetricsExporter>) -> Self {
+        self.metrics = exporter;
+        self
+    }
+
+    /// Encodes `texts`, checking the cache first and coalescing whatever's
+    /// left with any other `encode_batch` calls in flight right now (see
+    /// [`CoalesceState`]) so the same text is never computed twice
+    /// concurrently. Duplicate texts within `texts` itself are also folded
+    /// down to a single computation and fanned back out to every occurrence.
     pub async fn encode_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(Vec::new());
@@ -48,124 +126,323 @@ impl OnnxEmbeddingEngine {
 
         // Check cache first
         let mut embeddings = Vec::with_capacity(texts.len());
-        let mut uncached_texts = Vec::new();
         let mut uncached_indices = Vec::new();
 
-        {
-            let cache = self.cache.read().await;
-            for (i, text) in texts.iter().enumerate() {
-                if let Some(embedding) = cache.get(text) {
-                    embeddings.push(Some(embedding.clone()));
-                } else {
-                    embeddings.push(None);
-                    uncached_texts.push(text.clone());
-                    uncached_indices.push(i);
-                }
+        for (i, text) in texts.iter().enumerate() {
+            if let Some(embedding) = self.cache.get(&self.model_name, self.dimensions, text).await {
+                self.metrics.record_cache_access("onnx", true);
+                embeddings.push(Some(embedding));
+            } else {
+                self.metrics.record_cache_access("onnx", false);
+                embeddings.push(None);
+                uncached_indices.push(i);
             }
         }
 
-        // Process uncached texts
-        if !uncached_texts.is_empty() {
-            let new_embeddings = self.generate_embeddings(&uncached_texts).await?;
-            
-            // Update cache and results
-            {
-                let mut cache = self.cache.write().await;
-                for (text, embedding) in uncached_texts.iter().zip(new_embeddings.iter()) {
-                    cache.insert(text.clone(), embedding.clone());
-                }
-            }
+        if uncached_indices.is_empty() {
+            return Ok(embeddings.into_iter()
+                .map(|opt| opt.expect("All embeddings should be computed"))
+                .collect());
+        }
 
-            // Fill in the uncached embeddings
-            for (idx, embedding) in uncached_indices.into_iter().zip(new_embeddings.into_iter()) {
-                embeddings[idx] = Some(embedding);
+        // Dedupe uncached texts so a repeated string (e.g. the same license
+        // header in many files) is only ever queued for computation once.
+        let mut unique_uncached = Vec::new();
+        for &idx in &uncached_indices {
+            if !unique_uncached.contains(&texts[idx]) {
+                unique_uncached.push(texts[idx].clone());
             }
         }
 
-        // Convert to final result
+        let computed = self.coalesce_and_compute(unique_uncached).await?;
+
+        for &idx in &uncached_indices {
+            let embedding = computed.get(&texts[idx])
+                .cloned()
+                .expect("every uncached text was queued for coalesced computation");
+            embeddings[idx] = Some(embedding);
+        }
+
         Ok(embeddings.into_iter()
             .map(|opt| opt.expect("All embeddings should be computed"))
             .collect())
     }
 
+    /// Joins `texts` onto the shared in-flight computation, queuing any that
+    /// aren't already pending and subscribing to the result of all of them,
+    /// then waits for every result. At most one caller per text actually
+    /// spawns the debounced flush; everyone else just waits on the same
+    /// broadcast receiver.
+    async fn coalesce_and_compute(&self, texts: Vec<String>) -> Result<HashMap<String, Vec<f32>>> {
+        let mut receivers = Vec::with_capacity(texts.len());
+
+        for text in &texts {
+            let mut state = self.coalesce.lock().await;
+            let receiver = if let Some(sender) = state.senders.get(text) {
+                sender.subscribe()
+            } else {
+                let (sender, receiver) = broadcast::channel(1);
+                state.senders.insert(text.clone(), sender);
+                state.pending.push(text.clone());
+                receiver
+            };
+
+            let should_spawn_flush = !state.flush_scheduled;
+            if should_spawn_flush {
+                state.flush_scheduled = true;
+            }
+            drop(state);
+
+            if should_spawn_flush {
+                let engine = self.clone();
+                tokio::spawn(async move { engine.flush_coalesced().await });
+            }
+
+            receivers.push((text.clone(), receiver));
+        }
+
+        let mut results = HashMap::with_capacity(receivers.len());
+        for (text, mut receiver) in receivers {
+            match receiver.recv().await {
+                Ok(Ok(embedding)) => { results.insert(text, embedding); }
+                Ok(Err(message)) => return Err(anyhow::anyhow!(message)),
+                Err(_) => return Err(anyhow::anyhow!(
+                    "embedding computation for a coalesced text was dropped before completing"
+                )),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Waits out the debounce window, drains whatever texts piled up in
+    /// `pending` during that window (from this caller and any others), runs
+    /// one `generate_embeddings` call over all of them, and broadcasts each
+    /// result to every subscriber — success or failure, the whole batch is
+    /// resolved atomically as a single unit.
+    async fn flush_coalesced(&self) {
+        tokio::time::sleep(COALESCE_WINDOW).await;
+
+        let texts = {
+            let mut state = self.coalesce.lock().await;
+            state.flush_scheduled = false;
+            std::mem::take(&mut state.pending)
+        };
+
+        if texts.is_empty() {
+            return;
+        }
+
+        let outcome = self.generate_embeddings(&texts).await;
+        if let Ok(new_embeddings) = &outcome {
+            for (text, embedding) in texts.iter().zip(new_embeddings.iter()) {
+                self.cache.put(&self.model_name, self.dimensions, text, embedding).await;
+            }
+        }
+
+        // Senders stay in the map while their text is computed, so a caller
+        // asking for it meanwhile subscribes instead of queuing it again. The
+        // lock is held across the sends so nobody subscribes to a spent sender.
+        let mut state = self.coalesce.lock().await;
+        let senders: Vec<_> = texts.iter()
+            .filter_map(|text| state.senders.remove(text))
+            .collect();
+        match outcome {
+            Ok(new_embeddings) => {
+                for (sender, embedding) in senders.into_iter().zip(new_embeddings.into_iter()) {
+                    let _ = sender.send(Ok(embedding));
+                }
+            }
+            Err(error) => {
+                let message = error.to_string();
+                for sender in senders {
+                    let _ = sender.send(Err(message.clone()));
+                }
+            }
+        }
+    }
+
+    /// Runs real ONNX Runtime inference: first groups `texts` into
+    /// token-budgeted plans via `self.queue` so many short texts share an
+    /// inference call and a single long text doesn't blow out a batch, then
+    /// sub-chunks each plan group by `batch_size` as a secondary item-count
+    /// cap. Each sub-chunk is tokenized (padded/truncated to
+    /// `max_sequence_length`), fed through the session as
+    /// `input_ids`/`attention_mask`/`token_type_ids` tensors, then the last
+    /// hidden state is mean-pooled over non-padding tokens and L2-normalized
+    /// so downstream dot-product similarity behaves like cosine similarity.
+    ///
+    /// Results are written into the output slots for a sub-chunk only after
+    /// that sub-chunk's `run_batch` call returns `Ok`, so a failure partway
+    /// through never leaves the output (or the cache, which `flush_coalesced`
+    /// writes only from this function's `Ok` return) partially populated.
     async fn generate_embeddings(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
-        // This is a placeholder implementation that generates deterministic embeddings
-        // In production, this would use the actual ONNX Runtime for inference
-        
         debug!("🔄 Generating embeddings for {} texts", texts.len());
-        
-        // Simulate processing time
-        tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
-        
-        let embeddings: Vec<Vec<f32>> = texts.iter()
-            .map(|text| self.create_deterministic_embedding(text))
-            .collect();
 
-        debug!("✅ Generated {} embeddings", embeddings.len());
-        Ok(embeddings)
-    }
-
-    fn create_deterministic_embedding(&self, text: &str) -> Vec<f32> {
-        // Generate a deterministic but varied embedding based on text content
-        let mut embedding = vec![0.0; self.dimensions];
-        
-        // Use a simple hash-based approach to create varied embeddings
-        let bytes = text.as_bytes();
-        let mut seed = 0u64;
-        
-        for (i, &byte) in bytes.iter().enumerate() {
-            seed = seed.wrapping_mul(31).wrapping_add(byte as u64);
-            
-            // Use the hash to influence multiple dimensions
-            let base_idx = (seed as usize) % self.dimensions;
-            for j in 0..10.min(self.dimensions) {
-                let idx = (base_idx + j) % self.dimensions;
-                let influence = ((seed.wrapping_add(j as u64 * 7)) as f32 / u64::MAX as f32) * 2.0 - 1.0;
-                embedding[idx] += influence * 0.1;
+        let mut all_embeddings: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+
+        for plan_group in self.queue.plan_batches(texts) {
+            for sub_chunk in plan_group.chunks(self.batch_size) {
+                let batch: Vec<String> = sub_chunk.iter().map(|&i| texts[i].clone()).collect();
+                let batch_embeddings = self.run_batch(&batch).await?;
+                for (&idx, embedding) in sub_chunk.iter().zip(batch_embeddings.into_iter()) {
+                    all_embeddings[idx] = Some(embedding);
+                }
             }
         }
-        
-        // Add some text-length-based features
-        let length_factor = (text.len() as f32).ln() / 10.0;
-        for i in 0..self.dimensions.min(50) {
-            embedding[i] += length_factor * (i as f32 / 100.0);
+
+        let all_embeddings: Vec<Vec<f32>> = all_embeddings
+            .into_iter()
+            .map(|opt| opt.expect("every text is covered by exactly one plan batch"))
+            .collect();
+
+        debug!("✅ Generated {} embeddings", all_embeddings.len());
+        Ok(all_embeddings)
+    }
+
+    async fn run_batch(&self, batch: &[String]) -> Result<Vec<Vec<f32>>> {
+        let encodings = self.tokenizer
+            .encode_batch(batch.to_vec(), true)
+            .map_err(|e| anyhow::anyhow!("Tokenization failed: {}", e))?;
+
+        let seq_len = encodings
+            .iter()
+            .map(|e| e.get_ids().len().min(self.max_sequence_length))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let batch_len = encodings.len();
+        let mut input_ids = vec![0i64; batch_len * seq_len];
+        let mut attention_mask = vec![0i64; batch_len * seq_len];
+        let mut token_type_ids = vec![0i64; batch_len * seq_len];
+
+        for (row, encoding) in encodings.iter().enumerate() {
+            let ids = encoding.get_ids();
+            let mask = encoding.get_attention_mask();
+            let type_ids = encoding.get_type_ids();
+            let len = ids.len().min(seq_len);
+
+            for col in 0..len {
+                input_ids[row * seq_len + col] = ids[col] as i64;
+                attention_mask[row * seq_len + col] = mask[col] as i64;
+                token_type_ids[row * seq_len + col] = type_ids.get(col).copied().unwrap_or(0) as i64;
+            }
         }
-        
-        // Normalize the embedding
-        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm > 0.0 {
-            for x in &mut embedding {
-                *x /= norm;
+
+        let input_ids_value = Value::from_array(([batch_len, seq_len], input_ids))
+            .context("Failed to build input_ids tensor")?;
+        let attention_mask_value = Value::from_array(([batch_len, seq_len], attention_mask.clone()))
+            .context("Failed to build attention_mask tensor")?;
+        let token_type_ids_value = Value::from_array(([batch_len, seq_len], token_type_ids))
+            .context("Failed to build token_type_ids tensor")?;
+
+        let mut session = self.session.lock().await;
+        let outputs = session
+            .run(ort::inputs![
+                "input_ids" => input_ids_value,
+                "attention_mask" => attention_mask_value,
+                "token_type_ids" => token_type_ids_value,
+            ])
+            .context("ONNX Runtime inference failed")?;
+        drop(session);
+
+        let (shape, hidden_states) = outputs[0]
+            .try_extract_raw_tensor::<f32>()
+            .context("Failed to extract last_hidden_state tensor")?;
+
+        let hidden_dim = *shape.last().ok_or_else(|| anyhow::anyhow!("Model output has no hidden dimension"))? as usize;
+
+        let mut results = Vec::with_capacity(batch_len);
+        for row in 0..batch_len {
+            let mut pooled = vec![0.0f32; hidden_dim];
+            let mut valid_tokens = 0usize;
+
+            for col in 0..seq_len {
+                if attention_mask[row * seq_len + col] == 0 {
+                    continue;
+                }
+                valid_tokens += 1;
+                let offset = (row * seq_len + col) * hidden_dim;
+                for d in 0..hidden_dim {
+                    pooled[d] += hidden_states[offset + d];
+                }
+            }
+
+            if valid_tokens > 0 {
+                for v in pooled.iter_mut() {
+                    *v /= valid_tokens as f32;
+                }
+            }
+
+            let norm: f32 = pooled.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for v in pooled.iter_mut() {
+                    *v /= norm;
+                }
             }
+
+            results.push(pooled);
         }
-        
-        embedding
+
+        Ok(results)
     }
 
     pub fn clear_cache(&self) -> tokio::task::JoinHandle<()> {
-        let cache = Arc::clone(&self.cache);
+        let cache = self.cache.clone();
         tokio::spawn(async move {
-            let mut cache = cache.write().await;
-            cache.clear();
+            cache.clear().await;
         })
     }
 
     pub async fn cache_size(&self) -> usize {
-        let cache = self.cache.read().await;
-        cache.len()
+        self.cache.len().await
     }
 
     pub fn set_batch_size(&mut self, batch_size: usize) {
         self.batch_size = batch_size.max(1);
     }
 
+    /// Sets the target estimated-token budget per inference call; see
+    /// [`EmbeddingQueue`] for how this is used to group texts.
+    pub fn set_target_tokens_per_batch(&mut self, target_tokens_per_batch: usize) {
+        self.queue.set_target_tokens_per_batch(target_tokens_per_batch);
+    }
+
     pub fn set_max_sequence_length(&mut self, max_length: usize) {
         self.max_sequence_length = max_length.max(1);
+        if let Some(tokenizer) = Arc::get_mut(&mut self.tokenizer) {
+            let _ = tokenizer.with_truncation(Some(tokenizers::TruncationParams {
+                max_length: self.max_sequence_length,
+                ..Default::default()
+            }));
+        }
     }
 
     pub fn model_path(&self) -> &str {
         &self.model_path
     }
+
+    /// Derives the on-disk embedding cache location from a model file path
+    /// of the form `<data_dir>/kg-mcp-server/models/<model_name>/model.onnx`
+    /// — the cache lives as a sibling of `models/` so it survives model
+    /// swaps and isn't mistaken for model data. Falls back to the system
+    /// temp directory if `model_path` doesn't have the expected shape (e.g.
+    /// in tests that point at an arbitrary file). `backend` picks a `.db`
+    /// file for `Sqlite` or an environment directory for `Lmdb`, since the
+    /// two can't share a path.
+    fn cache_db_path(model_path: &str, backend: EmbeddingCacheBackend) -> std::path::PathBuf {
+        let file_name = match backend {
+            EmbeddingCacheBackend::Sqlite => "embedding_cache.db",
+            EmbeddingCacheBackend::Lmdb => "embedding_cache.lmdb",
+        };
+        std::path::Path::new(model_path)
+            .parent() // <model_name>/
+            .and_then(|p| p.parent()) // models/
+            .and_then(|p| p.parent()) // kg-mcp-server/
+            .map(|base| base.join("cache").join(file_name))
+            .unwrap_or_else(|| std::env::temp_dir().join(format!("kg-mcp-server-{}", file_name)))
+    }
 }
 
 impl EmbeddingEngine for OnnxEmbeddingEngine {
@@ -173,7 +450,7 @@ impl EmbeddingEngine for OnnxEmbeddingEngine {
         // Create a runtime for async operations in sync context
         let rt = tokio::runtime::Runtime::new()
             .context("Failed to create tokio runtime")?;
-        
+
         rt.block_on(self.encode_batch(texts))
     }
 
@@ -191,4 +468,4 @@ impl EmbeddingEngine for OnnxEmbeddingEngine {
     fn model_name(&self) -> &str {
         &self.model_name
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,366 @@
+//! Pluggable embedding backend selection.
+//!
+//! `LocalEmbeddingEngine` hard-wires the whole server to the bundled ONNX
+//! model. [`EmbeddingProvider`] is the seam that lets `ServerConfig.embeddings`
+//! choose a remote backend instead — an existing Ollama server, or any
+//! OpenAI-compatible `/v1/embeddings` endpoint — without every caller needing
+//! to know which one is active.
+//!
+//! Each remote implementor verifies the dimension of every embedding it
+//! gets back against `dimensions()` before returning it, so a misconfigured
+//! `embeddings.dimensions` (or a model swap on the remote server) fails
+//! loudly instead of silently corrupting vector search.
+//!
+//! Remote requests also retry on HTTP 429 via [`send_with_retry`], honoring
+//! the server's `Retry-After` delay (or exponential backoff if absent) up to
+//! a capped ceiling. Each HTTP call retries independently, so a throttled
+//! sub-batch never forces an already-succeeded sub-batch to be resent.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::ServerConfig;
+use super::{EmbeddingEngine, LocalEmbeddingEngine, OnnxEmbeddingEngine};
+
+/// How many times a rate-limited request is retried before giving up.
+const MAX_RETRIES: u32 = 5;
+/// Ceiling on the delay between retries, regardless of what the server's
+/// `Retry-After` header asks for or how high exponential backoff climbs.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Sends one request built by `build_request`, retrying on HTTP 429 using
+/// the server's `Retry-After` delay when present (falling back to
+/// exponential backoff otherwise), capped at `MAX_RETRY_BACKOFF`. Any other
+/// status code or transport error is returned immediately — only rate
+/// limiting is retried here, and each call to this function retries in
+/// isolation, so a throttled sub-batch never causes an already-completed
+/// sub-batch's result to be recomputed or discarded.
+async fn send_with_retry(
+    build_request: impl Fn() -> reqwest::RequestBuilder,
+    op_name: &str,
+) -> Result<reqwest::Response> {
+    let mut attempt = 0u32;
+    loop {
+        let response = build_request()
+            .send()
+            .await
+            .with_context(|| format!("{} request failed", op_name))?;
+
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Ok(response);
+        }
+
+        if attempt >= MAX_RETRIES {
+            anyhow::bail!("{} was rate-limited after {} retries", op_name, attempt);
+        }
+
+        let delay = retry_after_delay(&response)
+            .unwrap_or_else(|| Duration::from_millis(200 * 2u64.saturating_pow(attempt)))
+            .min(MAX_RETRY_BACKOFF);
+
+        warn!(
+            "{} was rate-limited (HTTP 429); retrying in {:?} (attempt {}/{})",
+            op_name, delay, attempt + 1, MAX_RETRIES
+        );
+
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Parses a delta-seconds `Retry-After` header. Servers rate-limiting
+/// embedding requests send this form almost universally; the HTTP-date form
+/// isn't worth the extra parsing for this use case.
+fn retry_after_delay(response: &reqwest::Response) -> Option<Duration> {
+    let header = response.headers().get(reqwest::header::RETRY_AFTER)?;
+    let seconds: u64 = header.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Which embedding backend `ServerConfig.embeddings` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderKind {
+    /// The bundled in-process ONNX model (`LocalEmbeddingEngine`).
+    Local,
+    /// A locally or remotely hosted Ollama server's `/api/embeddings` endpoint.
+    Ollama,
+    /// A generic OpenAI-compatible `/v1/embeddings` endpoint (OpenAI itself,
+    /// or any server implementing the same contract).
+    OpenAiCompatible,
+}
+
+impl Default for EmbeddingProviderKind {
+    fn default() -> Self {
+        EmbeddingProviderKind::Local
+    }
+}
+
+/// Common surface every embedding backend implements, so callers don't need
+/// to know whether embeddings come from the bundled model or a remote server.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embed a batch of texts, in order.
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embedding vector width this provider produces.
+    fn dimensions(&self) -> usize;
+
+    /// Identifier for the active model (for logging/diagnostics).
+    fn model_id(&self) -> String;
+
+    /// Downcast hook for subsystems that still depend on the concrete local
+    /// engine (e.g. model download/switching). Remote providers return `None`.
+    fn as_local_engine(&self) -> Option<&LocalEmbeddingEngine> {
+        None
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbeddingEngine {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.encode_texts(texts).await
+    }
+
+    fn dimensions(&self) -> usize {
+        // `EmbeddingEngine::dimensions` already carries this same sync-over-async
+        // workaround for LocalEmbeddingEngine; mirror it rather than introducing
+        // a second way to bridge the async current-model lookup.
+        <Self as EmbeddingEngine>::dimensions(self)
+    }
+
+    fn model_id(&self) -> String {
+        // Mirrors the sync-over-async bridge `EmbeddingEngine::dimensions` uses
+        // for the same reason: `current_model` is async, this trait isn't.
+        match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt.block_on(self.current_model()).unwrap_or_else(|| "unknown".to_string()),
+            Err(_) => "unknown".to_string(),
+        }
+    }
+
+    fn as_local_engine(&self) -> Option<&LocalEmbeddingEngine> {
+        Some(self)
+    }
+}
+
+/// Lets `BatchProcessor::process_with_engine` dispatch to the bundled ONNX
+/// engine through the same `&dyn EmbeddingProvider` seam used for the
+/// `Ollama`/`OpenAiCompatible` backends, rather than being hard-wired to a
+/// concrete `OnnxEmbeddingEngine` parameter.
+#[async_trait]
+impl EmbeddingProvider for OnnxEmbeddingEngine {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.encode_batch(texts).await
+    }
+
+    fn dimensions(&self) -> usize {
+        <Self as EmbeddingEngine>::dimensions(self)
+    }
+
+    fn model_id(&self) -> String {
+        format!("onnx:{}", <Self as EmbeddingEngine>::model_name(self))
+    }
+}
+
+/// Talks to an Ollama server's `/api/embeddings` endpoint.
+pub struct OllamaEmbeddingProvider {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    dimensions: usize,
+}
+
+#[derive(Serialize)]
+struct OllamaEmbeddingRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+#[derive(Deserialize)]
+struct OllamaEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(base_url: String, model: String, dimensions: usize) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client, base_url, model, dimensions }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        // Ollama's embeddings endpoint takes one prompt per request; there's
+        // no native batch call, so fan out and preserve input order.
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            let url = format!("{}/api/embeddings", self.base_url.trim_end_matches('/'));
+            let response = send_with_retry(
+                || self.client.post(&url).json(&OllamaEmbeddingRequest { model: &self.model, prompt: text }),
+                "Ollama embeddings",
+            )
+                .await?
+                .error_for_status()
+                .context("Ollama embeddings endpoint returned an error status")?
+                .json::<OllamaEmbeddingResponse>()
+                .await
+                .context("Failed to parse Ollama embeddings response")?;
+
+            if response.embedding.len() != self.dimensions {
+                anyhow::bail!(
+                    "Ollama model '{}' returned a {}-dimension embedding, but embeddings.dimensions is configured as {}",
+                    self.model, response.embedding.len(), self.dimensions
+                );
+            }
+            embeddings.push(response.embedding);
+        }
+        Ok(embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn model_id(&self) -> String {
+        format!("ollama:{}", self.model)
+    }
+}
+
+/// Talks to a generic OpenAI-compatible `/v1/embeddings` endpoint.
+pub struct OpenAiCompatibleEmbeddingProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dimensions: usize,
+}
+
+#[derive(Serialize)]
+struct OpenAiEmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingData {
+    embedding: Vec<f32>,
+}
+
+impl OpenAiCompatibleEmbeddingProvider {
+    pub fn new(base_url: String, api_key: Option<String>, model: String, dimensions: usize) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client, base_url, api_key, model, dimensions }
+    }
+}
+
+/// Texts per HTTP request to the `/v1/embeddings` endpoint. Keeping this
+/// bounded means a rate-limit hit only needs to retry the sub-batch it
+/// landed on, rather than resending every text in a large `embed_batch`
+/// call.
+const OPENAI_SUB_BATCH_SIZE: usize = 128;
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiCompatibleEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/v1/embeddings", self.base_url.trim_end_matches('/'));
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        for sub_batch in texts.chunks(OPENAI_SUB_BATCH_SIZE) {
+            let response = send_with_retry(
+                || {
+                    let mut request = self
+                        .client
+                        .post(&url)
+                        .json(&OpenAiEmbeddingRequest { model: &self.model, input: sub_batch });
+                    if let Some(api_key) = &self.api_key {
+                        request = request.bearer_auth(api_key);
+                    }
+                    request
+                },
+                "OpenAI-compatible embeddings",
+            )
+                .await?
+                .error_for_status()
+                .context("OpenAI-compatible embeddings endpoint returned an error status")?
+                .json::<OpenAiEmbeddingResponse>()
+                .await
+                .context("Failed to parse OpenAI-compatible embeddings response")?;
+
+            for item in &response.data {
+                if item.embedding.len() != self.dimensions {
+                    anyhow::bail!(
+                        "Model '{}' returned a {}-dimension embedding, but embeddings.dimensions is configured as {}",
+                        self.model, item.embedding.len(), self.dimensions
+                    );
+                }
+            }
+
+            embeddings.extend(response.data.into_iter().map(|d| d.embedding));
+        }
+
+        Ok(embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn model_id(&self) -> String {
+        format!("openai-compatible:{}", self.model)
+    }
+}
+
+/// Builds the `EmbeddingProvider` selected by `config.embeddings`, initializing
+/// the local model if that's what's selected.
+pub async fn create_embedding_provider(config: &ServerConfig) -> Result<Arc<dyn EmbeddingProvider>> {
+    match config.embeddings.provider {
+        EmbeddingProviderKind::Local => {
+            let engine = LocalEmbeddingEngine::new(config.clone())?;
+            engine.initialize(&config.embeddings.model_name).await?;
+            Ok(Arc::new(engine))
+        }
+        EmbeddingProviderKind::Ollama => {
+            let base_url = config
+                .embeddings
+                .remote_base_url
+                .clone()
+                .unwrap_or_else(|| "http://localhost:11434".to_string());
+            Ok(Arc::new(OllamaEmbeddingProvider::new(
+                base_url,
+                config.embeddings.model_name.clone(),
+                config.embeddings.dimensions,
+            )))
+        }
+        EmbeddingProviderKind::OpenAiCompatible => {
+            let base_url = config
+                .embeddings
+                .remote_base_url
+                .clone()
+                .unwrap_or_else(|| "https://api.openai.com".to_string());
+            Ok(Arc::new(OpenAiCompatibleEmbeddingProvider::new(
+                base_url,
+                config.embeddings.remote_api_key.clone(),
+                config.embeddings.model_name.clone(),
+                config.embeddings.dimensions,
+            )))
+        }
+    }
+}
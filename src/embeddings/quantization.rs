@@ -0,0 +1,88 @@
+//! Int8 scalar quantization for embedding vectors, trading a small
+//! precision loss for roughly 4x smaller storage — a `Vec<u8>` plus two
+//! `f32` scalars instead of a full `Vec<f32>` — for the embedding cache
+//! tiers. See `EmbeddingConfig.quantized_cache`.
+
+/// A vector quantized by per-vector asymmetric scalar quantization: each
+/// component is linearly mapped from `[min, max]` to a `u8` code in
+/// `[0, 255]`, with `min`/`max` carried alongside so it can be dequantized
+/// (or compared in quantized form, see [`cosine_similarity_q8`]) later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizedEmbedding {
+    pub codes: Vec<u8>,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl QuantizedEmbedding {
+    /// Quantizes `vector`. A constant vector (`max == min`, including the
+    /// all-zero vector) can't be linearly rescaled without dividing by
+    /// zero, so it's stored as all-zero codes instead — `dequantize`
+    /// reconstructs it back to the constant `min` either way.
+    pub fn quantize(vector: &[f32]) -> Self {
+        let min = vector.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = vector.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        let codes = if vector.is_empty() {
+            Vec::new()
+        } else if max <= min {
+            vec![0u8; vector.len()]
+        } else {
+            vector
+                .iter()
+                .map(|&x| (((x - min) / (max - min)) * 255.0).round() as u8)
+                .collect()
+        };
+
+        Self { codes, min, max }
+    }
+
+    /// Reconstructs the approximate original vector: `x = min + code/255 * (max - min)`.
+    pub fn dequantize(&self) -> Vec<f32> {
+        self.codes
+            .iter()
+            .map(|&c| self.min + (c as f32 / 255.0) * (self.max - self.min))
+            .collect()
+    }
+
+    /// Number of components.
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+/// Cosine similarity between two quantized embeddings, dequantizing each
+/// component on the fly while accumulating the dot product and both norms
+/// in a single pass — mirrors `cosine_similarity`'s shape and zero-norm
+/// handling, just without ever materializing either vector back to a full
+/// `Vec<f32>`.
+pub fn cosine_similarity_q8(a: &QuantizedEmbedding, b: &QuantizedEmbedding) -> f32 {
+    if a.codes.len() != b.codes.len() {
+        return 0.0;
+    }
+
+    let mut dot_product = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+
+    for (&code_a, &code_b) in a.codes.iter().zip(b.codes.iter()) {
+        let xa = a.min + (code_a as f32 / 255.0) * (a.max - a.min);
+        let xb = b.min + (code_b as f32 / 255.0) * (b.max - b.min);
+        dot_product += xa * xb;
+        norm_a += xa * xa;
+        norm_b += xb * xb;
+    }
+
+    let norm_a = norm_a.sqrt();
+    let norm_b = norm_b.sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot_product / (norm_a * norm_b)
+    }
+}
@@ -0,0 +1,64 @@
+/// Plans how a batch of texts should be split into inference-sized groups by
+/// estimated token count rather than item count, so many short texts pack
+/// into one inference call while a single long text gets isolated instead of
+/// silently pushing a batch over the model's effective token budget.
+#[derive(Debug, Clone)]
+pub struct EmbeddingQueue {
+    target_tokens_per_batch: usize,
+}
+
+impl EmbeddingQueue {
+    pub fn new(target_tokens_per_batch: usize) -> Self {
+        Self { target_tokens_per_batch: target_tokens_per_batch.max(1) }
+    }
+
+    pub fn target_tokens_per_batch(&self) -> usize {
+        self.target_tokens_per_batch
+    }
+
+    pub fn set_target_tokens_per_batch(&mut self, target_tokens_per_batch: usize) {
+        self.target_tokens_per_batch = target_tokens_per_batch.max(1);
+    }
+
+    /// Rough token estimate (~4 characters per token, the same heuristic
+    /// OpenAI's tokenizer docs use for English text). Good enough for batch
+    /// sizing without running the real tokenizer twice per text.
+    pub fn estimate_tokens(text: &str) -> usize {
+        (text.chars().count() / 4).max(1)
+    }
+
+    /// Splits `texts` into groups of indices whose summed estimated token
+    /// count stays under `target_tokens_per_batch` wherever possible. A
+    /// text that alone exceeds the budget becomes its own single-item
+    /// group rather than being split mid-text — the tokenizer's own
+    /// truncation is what protects against the model's hard token limit.
+    pub fn plan_batches(&self, texts: &[String]) -> Vec<Vec<usize>> {
+        let mut batches = Vec::new();
+        let mut current = Vec::new();
+        let mut current_tokens = 0usize;
+
+        for (i, text) in texts.iter().enumerate() {
+            let tokens = Self::estimate_tokens(text);
+            if !current.is_empty() && current_tokens + tokens > self.target_tokens_per_batch {
+                batches.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            current.push(i);
+            current_tokens += tokens;
+        }
+        if !current.is_empty() {
+            batches.push(current);
+        }
+
+        batches
+    }
+}
+
+impl Default for EmbeddingQueue {
+    fn default() -> Self {
+        // ~8k tokens per batch packs dozens of short texts per inference
+        // call; this is a budget for the whole batch, not a per-text limit
+        // (each text is still bounded by the model's own sequence length).
+        Self::new(8192)
+    }
+}
@@ -0,0 +1,103 @@
+//! Pluggable durable backing store for the embedding cache tiers in
+//! [`super::onnx_runtime::OnnxEmbeddingEngine`] and [`super::batch_processor::BatchProcessor`].
+//!
+//! [`PersistentEmbeddingCache`] (SQLite, reusing the crate's existing
+//! `kg_database.db`) was the only backing store until now. [`EmbeddingStore`]
+//! is the seam that lets `EmbeddingConfig.cache_backend` swap in
+//! [`LmdbEmbeddingCache`] instead, without either caller needing to know
+//! which one is active — mirrors the role `EmbeddingProvider` plays for the
+//! inference backend itself.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::cache::PersistentEmbeddingCache;
+use super::lmdb_cache::LmdbEmbeddingCache;
+
+/// Common surface every durable embedding-cache backend implements. Rows
+/// are keyed by `(model_name, digest(text))` so a lookup under a different
+/// model (or at a different `dimensions`) is treated as a miss rather than
+/// handing back a wrong-shape vector — see each implementor for its digest
+/// choice.
+#[async_trait]
+pub trait EmbeddingStore: Send + Sync {
+    /// Looks up the cached embedding for `text` under `model_name`/`dimensions`.
+    async fn get(&self, model_name: &str, dimensions: usize, text: &str) -> Option<Vec<f32>>;
+    /// Inserts or replaces the cached embedding for `text` under `model_name`/`dimensions`.
+    async fn put(&self, model_name: &str, dimensions: usize, text: &str, embedding: &[f32]);
+    /// Number of entries currently cached on disk.
+    async fn len(&self) -> usize;
+    /// Drops every cached entry.
+    async fn clear(&self);
+}
+
+#[async_trait]
+impl EmbeddingStore for PersistentEmbeddingCache {
+    async fn get(&self, model_name: &str, dimensions: usize, text: &str) -> Option<Vec<f32>> {
+        PersistentEmbeddingCache::get(self, model_name, dimensions, text).await
+    }
+
+    async fn put(&self, model_name: &str, dimensions: usize, text: &str, embedding: &[f32]) {
+        PersistentEmbeddingCache::put(self, model_name, dimensions, text, embedding).await
+    }
+
+    async fn len(&self) -> usize {
+        PersistentEmbeddingCache::len(self).await
+    }
+
+    async fn clear(&self) {
+        PersistentEmbeddingCache::clear(self).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingStore for LmdbEmbeddingCache {
+    async fn get(&self, model_name: &str, dimensions: usize, text: &str) -> Option<Vec<f32>> {
+        LmdbEmbeddingCache::get(self, model_name, dimensions, text).await
+    }
+
+    async fn put(&self, model_name: &str, dimensions: usize, text: &str, embedding: &[f32]) {
+        LmdbEmbeddingCache::put(self, model_name, dimensions, text, embedding).await
+    }
+
+    async fn len(&self) -> usize {
+        LmdbEmbeddingCache::len(self).await
+    }
+
+    async fn clear(&self) {
+        LmdbEmbeddingCache::clear(self).await
+    }
+}
+
+/// Which [`EmbeddingStore`] backend `EmbeddingConfig.cache_backend` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingCacheBackend {
+    /// The existing SQLite-backed cache, reusing `kg_database.db`.
+    Sqlite,
+    /// An LMDB-backed cache at its own environment directory.
+    Lmdb,
+}
+
+impl Default for EmbeddingCacheBackend {
+    fn default() -> Self {
+        EmbeddingCacheBackend::Sqlite
+    }
+}
+
+/// Opens the durable cache tier `backend` selects rooted at `db_path` (a
+/// `.db` file for `Sqlite`, an environment directory for `Lmdb`), bounding
+/// it to `capacity` entries.
+pub fn open_embedding_store(
+    backend: EmbeddingCacheBackend,
+    db_path: &Path,
+    capacity: usize,
+) -> Result<Arc<dyn EmbeddingStore>> {
+    match backend {
+        EmbeddingCacheBackend::Sqlite => Ok(Arc::new(PersistentEmbeddingCache::open(db_path, capacity)?)),
+        EmbeddingCacheBackend::Lmdb => Ok(Arc::new(LmdbEmbeddingCache::open(db_path, capacity)?)),
+    }
+}
@@ -0,0 +1,235 @@
+//! Typed filter builders for `nodes`/`edges`/`episodes`, replacing the
+//! per-call hand-concatenated `WHERE` clauses scattered through
+//! `storage.rs` (see `get_nodes_by_group_id`, `search_edges_by_text`, the
+//! `group_ids.iter().map(|_| "?").collect()` blocks in `gc`/`graph_counts`).
+//! Each optional filter is a builder method; `to_sql` assembles the clause
+//! and a matching `Vec<Box<dyn ToSql>>` meant to be bound with
+//! `rusqlite::params_from_iter`, so a multi-group or multi-type lookup
+//! gets safe `IN (?,?,...)` expansion without a new hand-written statement
+//! per arity.
+
+use chrono::{DateTime, Utc};
+use rusqlite::ToSql;
+
+use super::sql_types::KgTime;
+
+/// A `WHERE`-clause fragment (without the leading `WHERE`, empty if no
+/// filters were set) plus the parameters it binds, in order.
+pub struct FilterClause {
+    pub where_sql: String,
+    pub params: Vec<Box<dyn ToSql>>,
+}
+
+fn push_in_clause(conditions: &mut Vec<String>, params: &mut Vec<Box<dyn ToSql>>, column: &str, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+    let placeholders = values.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    conditions.push(format!("{column} IN ({placeholders})"));
+    for value in values {
+        params.push(Box::new(value.clone()));
+    }
+}
+
+/// Builder for filtering `nodes`.
+#[derive(Debug, Clone, Default)]
+pub struct NodeFilter {
+    group_ids: Vec<String>,
+    node_types: Vec<String>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    metadata_key: Option<String>,
+}
+
+impl NodeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn group_ids(mut self, group_ids: impl IntoIterator<Item = String>) -> Self {
+        self.group_ids = group_ids.into_iter().collect();
+        self
+    }
+
+    pub fn node_types(mut self, node_types: impl IntoIterator<Item = String>) -> Self {
+        self.node_types = node_types.into_iter().collect();
+        self
+    }
+
+    pub fn created_after(mut self, at: DateTime<Utc>) -> Self {
+        self.created_after = Some(at);
+        self
+    }
+
+    pub fn created_before(mut self, at: DateTime<Utc>) -> Self {
+        self.created_before = Some(at);
+        self
+    }
+
+    /// Restrict to rows whose `metadata` JSON has `key` present, via the
+    /// sqlite `json_extract` function.
+    pub fn metadata_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata_key = Some(key.into());
+        self
+    }
+
+    pub fn to_sql(&self) -> FilterClause {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        push_in_clause(&mut conditions, &mut params, "group_id", &self.group_ids);
+        push_in_clause(&mut conditions, &mut params, "node_type", &self.node_types);
+
+        if let Some(after) = self.created_after {
+            conditions.push("created_at >= ?".to_string());
+            params.push(Box::new(KgTime(after)));
+        }
+        if let Some(before) = self.created_before {
+            conditions.push("created_at <= ?".to_string());
+            params.push(Box::new(KgTime(before)));
+        }
+        if let Some(ref key) = self.metadata_key {
+            conditions.push("json_extract(metadata, '$.' || ?) IS NOT NULL".to_string());
+            params.push(Box::new(key.clone()));
+        }
+
+        FilterClause { where_sql: conditions.join(" AND "), params }
+    }
+}
+
+/// Builder for filtering `edges`.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeFilter {
+    group_ids: Vec<String>,
+    relation_types: Vec<String>,
+    weight_min: Option<f32>,
+    weight_max: Option<f32>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    metadata_key: Option<String>,
+}
+
+impl EdgeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn group_ids(mut self, group_ids: impl IntoIterator<Item = String>) -> Self {
+        self.group_ids = group_ids.into_iter().collect();
+        self
+    }
+
+    pub fn relation_types(mut self, relation_types: impl IntoIterator<Item = String>) -> Self {
+        self.relation_types = relation_types.into_iter().collect();
+        self
+    }
+
+    pub fn weight_range(mut self, min: f32, max: f32) -> Self {
+        self.weight_min = Some(min);
+        self.weight_max = Some(max);
+        self
+    }
+
+    pub fn created_after(mut self, at: DateTime<Utc>) -> Self {
+        self.created_after = Some(at);
+        self
+    }
+
+    pub fn created_before(mut self, at: DateTime<Utc>) -> Self {
+        self.created_before = Some(at);
+        self
+    }
+
+    pub fn metadata_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata_key = Some(key.into());
+        self
+    }
+
+    pub fn to_sql(&self) -> FilterClause {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        push_in_clause(&mut conditions, &mut params, "group_id", &self.group_ids);
+        push_in_clause(&mut conditions, &mut params, "relation_type", &self.relation_types);
+
+        if let Some(min) = self.weight_min {
+            conditions.push("weight >= ?".to_string());
+            params.push(Box::new(min));
+        }
+        if let Some(max) = self.weight_max {
+            conditions.push("weight <= ?".to_string());
+            params.push(Box::new(max));
+        }
+        if let Some(after) = self.created_after {
+            conditions.push("created_at >= ?".to_string());
+            params.push(Box::new(KgTime(after)));
+        }
+        if let Some(before) = self.created_before {
+            conditions.push("created_at <= ?".to_string());
+            params.push(Box::new(KgTime(before)));
+        }
+        if let Some(ref key) = self.metadata_key {
+            conditions.push("json_extract(metadata, '$.' || ?) IS NOT NULL".to_string());
+            params.push(Box::new(key.clone()));
+        }
+
+        FilterClause { where_sql: conditions.join(" AND "), params }
+    }
+}
+
+/// Builder for filtering `episodes`.
+#[derive(Debug, Clone, Default)]
+pub struct EpisodeFilter {
+    group_ids: Vec<String>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    metadata_key: Option<String>,
+}
+
+impl EpisodeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn group_ids(mut self, group_ids: impl IntoIterator<Item = String>) -> Self {
+        self.group_ids = group_ids.into_iter().collect();
+        self
+    }
+
+    pub fn created_after(mut self, at: DateTime<Utc>) -> Self {
+        self.created_after = Some(at);
+        self
+    }
+
+    pub fn created_before(mut self, at: DateTime<Utc>) -> Self {
+        self.created_before = Some(at);
+        self
+    }
+
+    pub fn metadata_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata_key = Some(key.into());
+        self
+    }
+
+    pub fn to_sql(&self) -> FilterClause {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        push_in_clause(&mut conditions, &mut params, "group_id", &self.group_ids);
+
+        if let Some(after) = self.created_after {
+            conditions.push("created_at >= ?".to_string());
+            params.push(Box::new(KgTime(after)));
+        }
+        if let Some(before) = self.created_before {
+            conditions.push("created_at <= ?".to_string());
+            params.push(Box::new(KgTime(before)));
+        }
+        if let Some(ref key) = self.metadata_key {
+            conditions.push("json_extract(metadata, '$.' || ?) IS NOT NULL".to_string());
+            params.push(Box::new(key.clone()));
+        }
+
+        FilterClause { where_sql: conditions.join(" AND "), params }
+    }
+}
@@ -0,0 +1,284 @@
+//! In-memory HNSW (Hierarchical Navigable Small World) approximate
+//! nearest-neighbor index over node embeddings.
+//!
+//! `GraphStorage::search_embeddings` (a brute-force O(N·D) scan over the
+//! `embeddings` table, sorted in full every call) is what `similar_concepts`
+//! used before this index existed. `search::vector_search::VectorSearchEngine`
+//! looked like an existing alternative — it already has a VP-tree
+//! (`approximate_knn_search`) and is wired into `HybridSearchEngine` — but
+//! its `get_all_nodes_with_embeddings`/`get_all_episodes_with_embeddings`
+//! are unimplemented placeholders that always return an empty `Vec`, so
+//! that engine never actually sees real node data. Rather than build on a
+//! path that's silently a no-op, this index is built fresh here and owned
+//! directly by `GraphStorage`, which is the one place that already knows
+//! about every stored embedding.
+//!
+//! This follows Malkov & Yashunin's HNSW construction (randomized
+//! exponential-decay layer assignment, greedy descent from an entry point
+//! through the upper layers, bounded beam search with `ef_construction`
+//! candidates to pick each new node's `M` neighbors per layer) but keeps
+//! neighbor selection simple — closest-M by distance, not the
+//! diversity-heuristic neighbor selection the paper also describes — since
+//! a simple selection is enough to get sub-linear query time and is far
+//! less code to keep correct without a reference implementation to check
+//! against.
+
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::embeddings::cosine_similarity;
+
+/// Tunable construction parameters. `m` also bounds how many
+/// neighbors a node keeps per layer; `ef_construction` is the candidate
+/// list size used while inserting. The query-time equivalent, `ef_search`,
+/// is not stored here but passed to `HnswIndex::search` per call (kept
+/// separate since a caller typically wants a wider beam at query time than
+/// was affordable for every insert during construction).
+#[derive(Debug, Clone, Copy)]
+pub struct HnswConfig {
+    pub m: usize,
+    pub ef_construction: usize,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self { m: 16, ef_construction: 200 }
+    }
+}
+
+struct IndexedNode {
+    vector: Vec<f32>,
+    /// `neighbors[layer]` is this node's neighbor list at that layer; the
+    /// node participates in layers `0..neighbors.len()`.
+    neighbors: Vec<Vec<Uuid>>,
+}
+
+struct Candidate {
+    id: Uuid,
+    distance: f32,
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    1.0 - cosine_similarity(a, b)
+}
+
+/// A hierarchical navigable small-world graph over `Uuid`-keyed vectors.
+pub struct HnswIndex {
+    config: HnswConfig,
+    nodes: HashMap<Uuid, IndexedNode>,
+    entry_point: Option<Uuid>,
+}
+
+impl HnswIndex {
+    pub fn new(config: HnswConfig) -> Self {
+        Self { config, nodes: HashMap::new(), entry_point: None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Layer count for a newly-inserted node, drawn from the exponentially
+    /// decaying distribution the HNSW paper uses so higher layers stay
+    /// exponentially sparser than layer 0.
+    fn random_level(&self) -> usize {
+        let m_l = 1.0 / (self.config.m.max(2) as f64).ln();
+        let r: f64 = rand::random::<f64>().max(f64::MIN_POSITIVE);
+        (-r.ln() * m_l).floor() as usize
+    }
+
+    /// Greedy single-step descent (`ef = 1`) from `start` toward `query` at
+    /// `layer`, used to find a good entry point for the next layer down.
+    fn greedy_closest(&self, start: Uuid, query: &[f32], layer: usize) -> Uuid {
+        let mut current = start;
+        let mut current_distance = cosine_distance(query, &self.nodes[&current].vector);
+        loop {
+            let mut improved = false;
+            if let Some(node) = self.nodes.get(&current) {
+                if layer < node.neighbors.len() {
+                    for &neighbor_id in &node.neighbors[layer] {
+                        if let Some(neighbor) = self.nodes.get(&neighbor_id) {
+                            let distance = cosine_distance(query, &neighbor.vector);
+                            if distance < current_distance {
+                                current = neighbor_id;
+                                current_distance = distance;
+                                improved = true;
+                            }
+                        }
+                    }
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    /// Bounded beam search at `layer`: expands from `entry_points`, keeping
+    /// at most `ef` candidates (the `ef` closest to `query` seen so far),
+    /// and returns them sorted nearest-first.
+    fn search_layer(&self, query: &[f32], entry_points: &[Uuid], ef: usize, layer: usize) -> Vec<Candidate> {
+        let mut visited: HashSet<Uuid> = entry_points.iter().copied().collect();
+        let mut candidates: Vec<Candidate> = entry_points.iter()
+            .filter_map(|&id| self.nodes.get(&id).map(|n| Candidate { id, distance: cosine_distance(query, &n.vector) }))
+            .collect();
+        candidates.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut found: Vec<Candidate> = candidates.iter()
+            .map(|c| Candidate { id: c.id, distance: c.distance })
+            .collect();
+
+        let mut frontier = candidates;
+        while let Some(closest) = frontier.first() {
+            let worst_found = found.last().map(|c| c.distance).unwrap_or(f32::INFINITY);
+            if found.len() >= ef && closest.distance > worst_found {
+                break;
+            }
+            let current_id = closest.id;
+            frontier.remove(0);
+
+            if let Some(node) = self.nodes.get(&current_id) {
+                if layer < node.neighbors.len() {
+                    for &neighbor_id in &node.neighbors[layer].clone() {
+                        if !visited.insert(neighbor_id) {
+                            continue;
+                        }
+                        let Some(neighbor) = self.nodes.get(&neighbor_id) else { continue };
+                        let distance = cosine_distance(query, &neighbor.vector);
+                        let worst_found = found.last().map(|c| c.distance).unwrap_or(f32::INFINITY);
+                        if found.len() < ef || distance < worst_found {
+                            let pos = frontier.partition_point(|c| c.distance < distance);
+                            frontier.insert(pos, Candidate { id: neighbor_id, distance });
+                            let pos = found.partition_point(|c| c.distance < distance);
+                            found.insert(pos, Candidate { id: neighbor_id, distance });
+                            if found.len() > ef {
+                                found.pop();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Connects `from` to `to` at `layer` (one direction), pruning `from`'s
+    /// neighbor list back down to `m` (keeping the closest) if the new link
+    /// pushed it over.
+    fn connect(&mut self, from: Uuid, to: Uuid, layer: usize) {
+        let Some(from_vector) = self.nodes.get(&from).map(|n| n.vector.clone()) else { return };
+        {
+            let Some(node) = self.nodes.get_mut(&from) else { return };
+            if layer >= node.neighbors.len() || node.neighbors[layer].contains(&to) {
+                return;
+            }
+            node.neighbors[layer].push(to);
+            if node.neighbors[layer].len() <= self.config.m {
+                return;
+            }
+        }
+
+        let neighbor_ids = self.nodes[&from].neighbors[layer].clone();
+        let mut ranked: Vec<(Uuid, f32)> = neighbor_ids.iter()
+            .filter_map(|&id| self.nodes.get(&id).map(|n| (id, cosine_distance(&from_vector, &n.vector))))
+            .collect();
+        ranked.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(self.config.m);
+        self.nodes.get_mut(&from).unwrap().neighbors[layer] = ranked.into_iter().map(|(id, _)| id).collect();
+    }
+
+    /// Inserts (or re-inserts, if `id` is already present) a vector.
+    pub fn insert(&mut self, id: Uuid, vector: Vec<f32>) {
+        self.remove(&id);
+
+        let level = self.random_level();
+        let neighbors = vec![Vec::new(); level + 1];
+
+        let entry = match self.entry_point {
+            None => {
+                self.nodes.insert(id, IndexedNode { vector, neighbors });
+                self.entry_point = Some(id);
+                return;
+            }
+            Some(entry) => entry,
+        };
+
+        let entry_layer = self.nodes[&entry].neighbors.len() - 1;
+        let mut current = entry;
+        for layer in (level + 1..=entry_layer).rev() {
+            current = self.greedy_closest(current, &vector, layer);
+        }
+
+        let mut entry_points = vec![current];
+        let mut new_neighbors = neighbors;
+        for layer in (0..=level.min(entry_layer)).rev() {
+            let candidates = self.search_layer(&vector, &entry_points, self.config.ef_construction, layer);
+            let selected: Vec<Uuid> = candidates.iter().take(self.config.m).map(|c| c.id).collect();
+
+            for &neighbor_id in &selected {
+                new_neighbors[layer].push(neighbor_id);
+                self.connect(neighbor_id, id, layer);
+            }
+            entry_points = candidates.into_iter().map(|c| c.id).collect();
+            if entry_points.is_empty() {
+                entry_points = vec![current];
+            }
+        }
+
+        let becomes_entry_point = level > entry_layer;
+        self.nodes.insert(id, IndexedNode { vector, neighbors: new_neighbors });
+        if becomes_entry_point {
+            self.entry_point = Some(id);
+        }
+    }
+
+    /// Removes `id`, patching the neighbor lists of the nodes `id` itself
+    /// linked to (a node whose link to `id` was not mirrored in `id`'s own,
+    /// pruned lists keeps a dangling entry, which searches skip) and
+    /// picking a new entry point (the remaining node with the highest top
+    /// layer, arbitrarily among ties) if `id` was the entry point.
+    pub fn remove(&mut self, id: &Uuid) {
+        let Some(removed) = self.nodes.remove(id) else { return };
+
+        for layer in 0..removed.neighbors.len() {
+            for &neighbor_id in &removed.neighbors[layer] {
+                if let Some(neighbor) = self.nodes.get_mut(&neighbor_id) {
+                    if layer < neighbor.neighbors.len() {
+                        neighbor.neighbors[layer].retain(|&n| n != *id);
+                    }
+                }
+            }
+        }
+
+        if self.entry_point == Some(*id) {
+            self.entry_point = self.nodes.iter()
+                .max_by_key(|(_, node)| node.neighbors.len())
+                .map(|(&id, _)| id);
+        }
+    }
+
+    /// Approximate k-NN search: greedy descent through the upper layers to
+    /// find a good entry point, then a bounded beam search of size
+    /// `ef_search` (at least `k`) at layer 0. Returns `(id, cosine
+    /// similarity)` pairs, nearest first. Empty if the index has no nodes.
+    pub fn search(&self, query: &[f32], k: usize, ef_search: usize) -> Vec<(Uuid, f32)> {
+        let Some(entry) = self.entry_point else { return Vec::new() };
+        let top_layer = self.nodes[&entry].neighbors.len() - 1;
+
+        let mut current = entry;
+        for layer in (1..=top_layer).rev() {
+            current = self.greedy_closest(current, query, layer);
+        }
+
+        let ef = ef_search.max(k).max(1);
+        let mut candidates = self.search_layer(query, &[current], ef, 0);
+        candidates.truncate(k);
+
+        candidates.into_iter().map(|c| (c.id, 1.0 - c.distance)).collect()
+    }
+}
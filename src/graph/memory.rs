@@ -1,14 +1,44 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Instant;
 use uuid::Uuid;
 
 use super::{KGNode, KGEdge, Episode};
 
-/// In-memory cache for frequently accessed graph data
+/// A cached value plus the instant it was last read, so the map it lives in
+/// can be scanned for its least-recently-used entry on eviction.
+struct Entry<T> {
+    value: T,
+    last_accessed: Instant,
+}
+
+impl<T> Entry<T> {
+    fn new(value: T) -> Self {
+        Self { value, last_accessed: Instant::now() }
+    }
+}
+
+/// Hit/miss/eviction counters for a `GraphMemoryCache`, so callers can tell
+/// whether `max_size` is sized well for the working set instead of guessing.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// In-memory cache for frequently accessed graph data. Each of `nodes`,
+/// `edges`, and `episodes` is bounded independently at `max_size`: once a map
+/// is full, inserting a new key evicts that map's least-recently-used entry
+/// (tracked via `Entry::last_accessed`, refreshed on every `get_*`), so the
+/// cache behaves as a genuinely bounded working set instead of growing
+/// forever.
 pub struct GraphMemoryCache {
-    nodes: HashMap<Uuid, KGNode>,
-    edges: HashMap<Uuid, KGEdge>,
-    episodes: HashMap<Uuid, Episode>,
+    nodes: HashMap<Uuid, Entry<KGNode>>,
+    edges: HashMap<Uuid, Entry<KGEdge>>,
+    episodes: HashMap<Uuid, Entry<Episode>>,
     max_size: usize,
+    stats: CacheStats,
 }
 
 impl GraphMemoryCache {
@@ -18,42 +48,85 @@ impl GraphMemoryCache {
             edges: HashMap::new(),
             episodes: HashMap::new(),
             max_size,
+            stats: CacheStats::default(),
         }
     }
 
-    pub fn get_node(&self, uuid: &Uuid) -> Option<&KGNode> {
-        self.nodes.get(uuid)
+    pub fn get_node(&mut self, uuid: &Uuid) -> Option<&KGNode> {
+        match self.nodes.get_mut(uuid) {
+            Some(entry) => {
+                entry.last_accessed = Instant::now();
+                self.stats.hits += 1;
+                Some(&entry.value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
     }
 
     pub fn insert_node(&mut self, node: KGNode) {
-        if self.nodes.len() >= self.max_size {
-            // Simple eviction: remove oldest
-            // TODO: Implement LRU eviction
+        if !self.nodes.contains_key(&node.uuid) && self.nodes.len() >= self.max_size && evict_lru(&mut self.nodes) {
+            self.stats.evictions += 1;
         }
-        self.nodes.insert(node.uuid, node);
+        self.nodes.insert(node.uuid, Entry::new(node));
     }
 
-    pub fn get_edge(&self, uuid: &Uuid) -> Option<&KGEdge> {
-        self.edges.get(uuid)
+    pub fn get_edge(&mut self, uuid: &Uuid) -> Option<&KGEdge> {
+        match self.edges.get_mut(uuid) {
+            Some(entry) => {
+                entry.last_accessed = Instant::now();
+                self.stats.hits += 1;
+                Some(&entry.value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
     }
 
     pub fn insert_edge(&mut self, edge: KGEdge) {
-        if self.edges.len() >= self.max_size {
-            // Simple eviction: remove oldest
-            // TODO: Implement LRU eviction
+        if !self.edges.contains_key(&edge.uuid) && self.edges.len() >= self.max_size && evict_lru(&mut self.edges) {
+            self.stats.evictions += 1;
         }
-        self.edges.insert(edge.uuid, edge);
+        self.edges.insert(edge.uuid, Entry::new(edge));
     }
 
-    pub fn get_episode(&self, uuid: &Uuid) -> Option<&Episode> {
-        self.episodes.get(uuid)
+    pub fn get_episode(&mut self, uuid: &Uuid) -> Option<&Episode> {
+        match self.episodes.get_mut(uuid) {
+            Some(entry) => {
+                entry.last_accessed = Instant::now();
+                self.stats.hits += 1;
+                Some(&entry.value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
     }
 
     pub fn insert_episode(&mut self, episode: Episode) {
-        if self.episodes.len() >= self.max_size {
-            // Simple eviction: remove oldest
-            // TODO: Implement LRU eviction
+        if !self.episodes.contains_key(&episode.uuid) && self.episodes.len() >= self.max_size && evict_lru(&mut self.episodes) {
+            self.stats.evictions += 1;
         }
-        self.episodes.insert(episode.uuid, episode);
+        self.episodes.insert(episode.uuid, Entry::new(episode));
+    }
+
+    /// Snapshot of the cache's hit/miss/eviction counters since creation.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
     }
-} 
\ No newline at end of file
+}
+
+/// Removes the entry with the oldest `last_accessed` from `map`, if any.
+/// Returns whether an entry was evicted, so callers can count it.
+fn evict_lru<T>(map: &mut HashMap<Uuid, Entry<T>>) -> bool {
+    let Some(lru_key) = map.iter().min_by_key(|(_, entry)| entry.last_accessed).map(|(uuid, _)| *uuid) else {
+        return false;
+    };
+    map.remove(&lru_key);
+    true
+}
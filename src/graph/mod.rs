@@ -1,5 +1,9 @@
+pub mod filters;
+pub mod hnsw;
 pub mod memory;
 pub mod queries;
+pub mod schema_migrations;
+pub mod sql_types;
 pub mod storage;
 
 // Re-export query engine components
@@ -115,6 +119,12 @@ pub enum EpisodeSource {
     Text,
     Json,
     Message,
+    /// A syntactically-bounded source code chunk produced by `CodeChunker`
+    /// (function/class/impl-block granularity, not a fixed byte window).
+    Code,
+    /// A raw document (PDF/text/markdown attachment) ingested through the
+    /// `/ingest/file` multipart endpoint, rather than JSON-embedded content.
+    File,
 }
 
 impl Episode {
@@ -157,6 +167,23 @@ impl Episode {
     }
 }
 
+/// A node's fused score broken into the lexical and semantic contributions
+/// that produced it, so a caller debugging hybrid-search ranking can see
+/// why a result landed where it did instead of just the final blend.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ComponentScores {
+    pub lexical: f32,
+    pub semantic: f32,
+    /// 1-based rank this node held in the keyword/text result list, or
+    /// `None` if it didn't appear there. Populated by RRF-style fusion;
+    /// absent for single-path searches.
+    pub text_rank: Option<usize>,
+    /// 1-based rank this node held in the semantic/vector result list, or
+    /// `None` if it didn't appear there. Populated by RRF-style fusion;
+    /// absent for single-path searches.
+    pub vector_rank: Option<usize>,
+}
+
 /// Search result for knowledge graph queries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -164,6 +191,21 @@ pub struct SearchResult {
     pub edges: Vec<KGEdge>,
     pub episodes: Vec<Episode>,
     pub scores: HashMap<Uuid, f32>,
+    /// Per-node lexical/semantic score breakdown. Populated by
+    /// `HybridSearchEngine::hybrid_search`; empty for single-path searches.
+    pub component_scores: HashMap<Uuid, ComponentScores>,
+    /// Of `nodes`, how many came from a vector/embedding search rather than
+    /// text search alone. Populated by hybrid search; `0` for text-only
+    /// results.
+    pub semantic_hit_count: usize,
+    /// How many of `nodes` were won by each named source in a
+    /// `federated_search` across multiple graphs/indices. Empty for a
+    /// single-source search.
+    pub source_hit_counts: HashMap<String, usize>,
+    /// Set when a `HybridSearchOptions::time_budget` cutoff was hit before
+    /// the search could run to completion, meaning `nodes` reflects
+    /// whatever was gathered so far rather than the full ranked set.
+    pub degraded: bool,
 }
 
 impl SearchResult {
@@ -173,6 +215,10 @@ impl SearchResult {
             edges: Vec::new(),
             episodes: Vec::new(),
             scores: HashMap::new(),
+            component_scores: HashMap::new(),
+            source_hit_counts: HashMap::new(),
+            semantic_hit_count: 0,
+            degraded: false,
         }
     }
 
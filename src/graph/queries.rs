@@ -19,9 +19,94 @@ struct GraphCache {
     graph: UnGraph<Uuid, f32>,
     node_map: HashMap<Uuid, NodeIndex>,
     edge_map: HashMap<Uuid, EdgeIndex>,
+    /// Index->UUID arrays built alongside `graph`/`node_map`/`edge_map` so
+    /// traversal code that only has a `NodeIndex`/`EdgeIndex` in hand (e.g.
+    /// reconstructing a path) doesn't have to reverse-scan `node_map`/
+    /// `edge_map` with `.iter().find(...)` - an O(V) (or O(E)) scan per
+    /// lookup that turns a traversal over `n` nodes into O(V·n) work.
+    csr: Csr,
+    /// Computed once per rebuild by `brandes_betweenness`, an O(V·E) pass
+    /// over the whole graph - keyed here so `calculate_centrality` can look
+    /// a single node up instead of re-running Brandes' algorithm every call.
+    betweenness: HashMap<Uuid, f32>,
     last_updated: std::time::Instant,
 }
 
+/// Compressed-sparse-row view of `GraphCache`'s node/edge indices, built
+/// once per `rebuild_graph_cache` call. `graph`'s `NodeIndex`/`EdgeIndex`
+/// values are already a dense `0..n` range (the cache is always rebuilt
+/// from scratch, never incrementally edited), so `.index()` doubles as a
+/// CSR row/position with no separate renumbering step.
+struct Csr {
+    /// `row_offsets[i]..row_offsets[i + 1]` is the slice of
+    /// `column_indices` holding node index `i`'s neighbor indices.
+    /// Length `n + 1`.
+    row_offsets: Vec<u32>,
+    /// Neighbor node indices, grouped by row as described by `row_offsets`.
+    column_indices: Vec<u32>,
+    /// `node_index_to_uuid[i]` is the UUID at node index `i` - O(1) instead
+    /// of `node_map.iter().find(...)`'s O(V) reverse scan.
+    node_index_to_uuid: Vec<Uuid>,
+    /// `edge_index_to_uuid[i]` is the UUID at edge index `i` - same
+    /// O(1)-instead-of-O(E) motivation, for edge reconstruction during
+    /// path traversal.
+    edge_index_to_uuid: Vec<Uuid>,
+    /// `(uuid, node index)` pairs sorted by `uuid`, so a caller that only
+    /// has a UUID can binary-search for its node index instead of going
+    /// through `node_map`'s hash lookup - kept alongside `node_map` rather
+    /// than replacing it, since most existing call sites already hash.
+    sorted_uuid_index: Vec<(Uuid, u32)>,
+}
+
+impl Csr {
+    /// All of node index `i`'s neighbor indices.
+    fn neighbors(&self, index: u32) -> &[u32] {
+        let start = self.row_offsets[index as usize] as usize;
+        let end = self.row_offsets[index as usize + 1] as usize;
+        &self.column_indices[start..end]
+    }
+
+    /// Binary-search counterpart to `node_map`'s hash lookup.
+    fn uuid_to_node_index(&self, uuid: Uuid) -> Option<u32> {
+        self.sorted_uuid_index
+            .binary_search_by_key(&uuid, |&(u, _)| u)
+            .ok()
+            .map(|pos| self.sorted_uuid_index[pos].1)
+    }
+
+    fn build(graph: &UnGraph<Uuid, f32>, edge_map: &HashMap<Uuid, EdgeIndex>) -> Self {
+        let n = graph.node_count();
+        let mut node_index_to_uuid = vec![Uuid::nil(); n];
+        for node_idx in graph.node_indices() {
+            node_index_to_uuid[node_idx.index()] = graph[node_idx];
+        }
+
+        let mut edge_index_to_uuid = vec![Uuid::nil(); graph.edge_count()];
+        for (&edge_uuid, &edge_idx) in edge_map {
+            edge_index_to_uuid[edge_idx.index()] = edge_uuid;
+        }
+
+        let mut row_offsets = Vec::with_capacity(n + 1);
+        let mut column_indices = Vec::new();
+        row_offsets.push(0u32);
+        for node_idx in graph.node_indices() {
+            for edge in graph.edges(node_idx) {
+                column_indices.push(edge.target().index() as u32);
+            }
+            row_offsets.push(column_indices.len() as u32);
+        }
+
+        let mut sorted_uuid_index: Vec<(Uuid, u32)> = node_index_to_uuid
+            .iter()
+            .enumerate()
+            .map(|(idx, &uuid)| (uuid, idx as u32))
+            .collect();
+        sorted_uuid_index.sort_by_key(|&(uuid, _)| uuid);
+
+        Self { row_offsets, column_indices, node_index_to_uuid, edge_index_to_uuid, sorted_uuid_index }
+    }
+}
+
 /// Graph query configuration
 #[derive(Debug, Clone)]
 pub struct QueryConfig {
@@ -50,6 +135,11 @@ pub struct GraphQueryEngine {
     query_cache: std::sync::RwLock<HashMap<String, Vec<SearchResult>>>,
 }
 
+/// `QueryEngine` has no `QueryConfig` of its own (that belongs to the
+/// separate `GraphQueryEngine`), so the `pagerank:` verb caps its result set
+/// to this constant instead - matching `QueryConfig::default().max_results`.
+const PAGERANK_DEFAULT_RESULTS: usize = 100;
+
 impl QueryEngine {
     pub fn new(storage: GraphStorage) -> Self {
         Self {
@@ -67,10 +157,18 @@ impl QueryEngine {
             self.execute_traversal_query(&query[9..]).await
         } else if query.starts_with("shortest:") {
             self.execute_shortest_path_query(&query[9..]).await
+        } else if query.starts_with("kshortest:") {
+            self.execute_k_shortest_paths_query(&query[10..]).await
+        } else if query.starts_with("astar:") {
+            self.execute_astar_query(&query[6..]).await
         } else if query.starts_with("cluster:") {
             self.execute_clustering_query(&query[8..]).await
         } else if query.starts_with("similar:") {
             self.execute_similarity_query(&query[8..]).await
+        } else if query.starts_with("pagerank:") {
+            self.execute_pagerank_query(&query[9..]).await
+        } else if query.starts_with("triangles:") {
+            self.execute_triangles_query(&query[10..]).await
         } else {
             // Default: hybrid search
             self.execute_hybrid_query(query).await
@@ -96,8 +194,8 @@ impl QueryEngine {
             }
         };
 
-        let start_idx = match cache.node_map.get(&start_node) {
-            Some(idx) => *idx,
+        let start_idx = match cache.csr.uuid_to_node_index(start_node) {
+            Some(index) => NodeIndex::new(index as usize),
             None => {
                 tracing::warn!("Start node not found in graph: {}", start_node);
                 return Ok(Vec::new());
@@ -116,9 +214,11 @@ impl QueryEngine {
                 continue;
             }
 
-            // Get neighbors
-            for edge in cache.graph.edges(current_idx) {
-                let neighbor_idx = edge.target();
+            // Get neighbors off the CSR slice rather than
+            // `cache.graph.edges(current_idx)` - equivalent adjacency, no
+            // petgraph edge-reference overhead per hop.
+            for &neighbor_index in cache.csr.neighbors(current_idx.index() as u32) {
+                let neighbor_idx = NodeIndex::new(neighbor_index as usize);
                 if !visited.contains(&neighbor_idx) {
                     visited.insert(neighbor_idx);
                     queue.push_back((neighbor_idx, depth + 1));
@@ -128,10 +228,9 @@ impl QueryEngine {
 
         // Convert node indices back to UUIDs and fetch node data
         for node_idx in visited {
-            if let Some((&uuid, _)) = cache.node_map.iter().find(|(_, &idx)| idx == node_idx) {
-                if let Ok(Some(node)) = self.storage.get_node(uuid) {
-                    connected_nodes.push(node);
-                }
+            let uuid = cache.csr.node_index_to_uuid[node_idx.index()];
+            if let Ok(Some(node)) = self.storage.get_node(uuid) {
+                connected_nodes.push(node);
             }
         }
 
@@ -182,12 +281,9 @@ impl QueryEngine {
             for edge in cache.graph.edges_directed(current, petgraph::Direction::Incoming) {
                 let source = edge.source();
                 if path_map.contains_key(&source) {
-                    // Find the corresponding edge UUID
-                    if let Some((&edge_uuid, _)) = cache.edge_map.iter()
-                        .find(|(_, &idx)| idx == edge.id()) {
-                        if let Ok(Some(kg_edge)) = self.storage.get_edge(edge_uuid) {
-                            path_edges.push(kg_edge);
-                        }
+                    let edge_uuid = cache.csr.edge_index_to_uuid[edge.id().index()];
+                    if let Ok(Some(kg_edge)) = self.storage.get_edge(edge_uuid) {
+                        path_edges.push(kg_edge);
                     }
                     current = source;
                     break;
@@ -200,34 +296,269 @@ impl QueryEngine {
         Ok(path_edges)
     }
 
+    /// Yen's algorithm: the single best path from `find_shortest_path`, plus
+    /// up to `k - 1` alternatives, so callers can see other reasoning chains
+    /// between two entities rather than only the cheapest one. For each
+    /// already-accepted path, every node along it (but the last) is tried as
+    /// a "spur": the edges that would recreate an already-found path sharing
+    /// that same root are removed, the root's own nodes (besides the spur
+    /// itself) are removed so the spur path can't loop back through them,
+    /// and Dijkstra runs from the spur to the target over what's left. Root
+    /// + spur is pushed onto a candidate min-heap keyed by total cost; the
+    /// cheapest not-yet-accepted candidate becomes the next path. Stops once
+    /// `k` paths are found or no further candidate exists.
+    pub async fn find_k_shortest_paths(&self, from: Uuid, to: Uuid, k: usize) -> Result<Vec<Vec<KGEdge>>> {
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = match graph.read() {
+            Ok(guard) => guard,
+            Err(e) => {
+                tracing::error!("Failed to acquire graph cache read lock for k-shortest paths: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+
+        let cache = match graph_guard.as_ref() {
+            Some(cache) => cache,
+            None => {
+                tracing::warn!("Graph cache not initialized for k-shortest paths");
+                return Ok(Vec::new());
+            }
+        };
+
+        let start_idx = match cache.node_map.get(&from) {
+            Some(idx) => *idx,
+            None => {
+                tracing::warn!("Start node not found in graph: {}", from);
+                return Ok(Vec::new());
+            }
+        };
+        let end_idx = match cache.node_map.get(&to) {
+            Some(idx) => *idx,
+            None => {
+                tracing::warn!("End node not found in graph: {}", to);
+                return Ok(Vec::new());
+            }
+        };
+
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let Some(first_path) = restricted_dijkstra(&cache.graph, start_idx, end_idx, &HashSet::new(), &HashSet::new()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut accepted: Vec<Vec<NodeIndex>> = vec![first_path];
+        let mut candidates: std::collections::BinaryHeap<PathCandidate> = std::collections::BinaryHeap::new();
+        let mut seen_candidates: HashSet<Vec<NodeIndex>> = HashSet::new();
+
+        while accepted.len() < k {
+            let prev_path = accepted.last().unwrap().clone();
+
+            for i in 0..prev_path.len().saturating_sub(1) {
+                let spur_node = prev_path[i];
+                let root_path = &prev_path[..=i];
+
+                // Remove the edge each already-accepted path took out of the
+                // spur node, if it shares this same root - otherwise Dijkstra
+                // would just rediscover a path already in `accepted`.
+                let mut excluded_edges = HashSet::new();
+                for path in &accepted {
+                    if path.len() > i + 1 && path[..=i] == *root_path {
+                        if let Some(edge_idx) = cache.graph.find_edge(path[i], path[i + 1]) {
+                            excluded_edges.insert(edge_idx);
+                        }
+                    }
+                }
+
+                // Remove the root path's own nodes (besides the spur) so the
+                // spur-to-target search can't loop back through the root.
+                let excluded_nodes: HashSet<NodeIndex> = root_path[..i].iter().copied().collect();
+
+                let Some(spur_path) = restricted_dijkstra(&cache.graph, spur_node, end_idx, &excluded_edges, &excluded_nodes) else {
+                    continue;
+                };
+
+                let mut total_path = root_path[..i].to_vec();
+                total_path.extend(spur_path);
+
+                if accepted.contains(&total_path) || !seen_candidates.insert(total_path.clone()) {
+                    continue;
+                }
+
+                let cost = path_cost(&cache.graph, &total_path);
+                candidates.push(PathCandidate { cost, path: total_path });
+            }
+
+            let Some(best) = candidates.pop() else {
+                break; // No further alternative route exists.
+            };
+            accepted.push(best.path);
+        }
+
+        let mut paths = Vec::new();
+        for node_path in accepted {
+            let mut path_edges = Vec::new();
+            for window in node_path.windows(2) {
+                let Some(edge_idx) = cache.graph.find_edge(window[0], window[1]) else { continue };
+                let edge_uuid = cache.csr.edge_index_to_uuid[edge_idx.index()];
+                if let Ok(Some(kg_edge)) = self.storage.get_edge(edge_uuid) {
+                    path_edges.push(kg_edge);
+                }
+            }
+            paths.push(path_edges);
+        }
+
+        println!("🛤️  Found {} alternative path(s) (requested k={})", paths.len(), k);
+        Ok(paths)
+    }
+
+    /// Goal-directed pathfinding: petgraph's `astar` guided by an embedding
+    /// distance heuristic instead of `find_shortest_path`'s uninformed
+    /// Dijkstra, so a large cache expands far fewer nodes on the way to a
+    /// specific target. `h(n)` is the cosine distance between `n`'s
+    /// embedding and the target's, scaled down to at most half the
+    /// cheapest edge weight in the graph - cosine distance is bounded to
+    /// `[0, 2]`, so this keeps `h(n)` an admissible (if conservative) lower
+    /// bound on the true remaining cost of any further hop. Falls back to
+    /// `find_shortest_path` whenever either endpoint has no stored
+    /// embedding, since there's nothing to estimate from and an actual path
+    /// matters more than the speedup.
+    pub async fn find_path_astar(&self, from: Uuid, to: Uuid) -> Result<Vec<KGEdge>> {
+        let Some(target_embedding) = self.storage.get_node_embedding(to)? else {
+            return self.find_shortest_path(from, to).await;
+        };
+        if self.storage.get_node_embedding(from)?.is_none() {
+            return self.find_shortest_path(from, to).await;
+        }
+
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = match graph.read() {
+            Ok(guard) => guard,
+            Err(e) => {
+                tracing::error!("Failed to acquire graph cache read lock for A*: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+        let cache = match graph_guard.as_ref() {
+            Some(cache) => cache,
+            None => {
+                tracing::warn!("Graph cache not initialized for A*");
+                return Ok(Vec::new());
+            }
+        };
+
+        let start_idx = match cache.node_map.get(&from) {
+            Some(idx) => *idx,
+            None => {
+                tracing::warn!("Start node not found in graph: {}", from);
+                return Ok(Vec::new());
+            }
+        };
+        let end_idx = match cache.node_map.get(&to) {
+            Some(idx) => *idx,
+            None => {
+                tracing::warn!("End node not found in graph: {}", to);
+                return Ok(Vec::new());
+            }
+        };
+
+        let min_edge_weight = cache.graph.edge_weights().copied().fold(f32::INFINITY, f32::min);
+        let scale = if min_edge_weight.is_finite() { min_edge_weight / 2.0 } else { 0.0 };
+
+        let heuristic = |node_idx: NodeIndex| -> f32 {
+            let node_uuid = cache.graph[node_idx];
+            match self.storage.get_node_embedding(node_uuid) {
+                Ok(Some(embedding)) => (1.0 - crate::embeddings::cosine_similarity(&embedding, &target_embedding)) * scale,
+                _ => 0.0,
+            }
+        };
+
+        let found = astar(
+            &cache.graph,
+            start_idx,
+            |idx| idx == end_idx,
+            |edge| *edge.weight(),
+            heuristic,
+        );
+
+        let Some((_, node_path)) = found else {
+            return Ok(Vec::new());
+        };
+
+        let mut path_edges = Vec::new();
+        for window in node_path.windows(2) {
+            let Some(edge_idx) = cache.graph.find_edge(window[0], window[1]) else { continue };
+            let Some((&edge_uuid, _)) = cache.edge_map.iter().find(|(_, &idx)| idx == edge_idx) else { continue };
+            if let Ok(Some(kg_edge)) = self.storage.get_edge(edge_uuid) {
+                path_edges.push(kg_edge);
+            }
+        }
+
+        println!("⭐ A* found path with {} edges", path_edges.len());
+        Ok(path_edges)
+    }
+
     /// Find nodes within a similarity threshold using embeddings
+    /// Looks the target node's own embedding up and queries `storage`'s
+    /// `hnsw` index with it, rather than keeping a second HNSW index here
+    /// alongside `GraphCache`: `GraphStorage` already owns one index,
+    /// updated incrementally on every `store_embedding`/node removal, so a
+    /// second copy tied to `GraphCache`'s 5-minute rebuild cycle would just
+    /// be a staler duplicate of the same data.
     pub async fn find_similar_nodes(&self, target_uuid: Uuid, threshold: f32, limit: usize) -> Result<Vec<(KGNode, f32)>> {
         // Get target node embedding
         let target_node = self.storage.get_node(target_uuid)?
             .ok_or_else(|| anyhow::anyhow!("Target node not found"))?;
 
-        // Get all nodes (this could be optimized with embedding indexing)
+        let Some(target_embedding) = self.storage.get_node_embedding(target_uuid)? else {
+            // No embedding yet for this node - nothing to index against, so
+            // fall back to the old name/type placeholder rather than
+            // comparing a vector that doesn't exist.
+            return self.find_similar_nodes_by_attributes(&target_node, threshold, limit).await;
+        };
+
+        // Sublinear nearest-neighbor lookup over the `hnsw` index instead of
+        // the full `search_nodes_by_text("")` table scan this used to run.
+        // `ef_search` trades query latency for recall the same way
+        // `mcp::handlers`'s `similar_concepts` operation tunes it.
+        let ef_search = (limit * 10).max(100);
+        let mut similar_nodes = Vec::new();
+        for (uuid, similarity) in self.storage.hnsw_search_nodes(&target_embedding, limit + 1, ef_search)? {
+            if uuid == target_uuid || similarity < threshold {
+                continue;
+            }
+            if let Some(node) = self.storage.get_node(uuid)? {
+                similar_nodes.push((node, similarity));
+            }
+        }
+        similar_nodes.truncate(limit);
+
+        println!("🎯 Found {} similar nodes above threshold {}", similar_nodes.len(), threshold);
+        Ok(similar_nodes)
+    }
+
+    /// Pre-embedding fallback for `find_similar_nodes`: the brute-force
+    /// name/type comparison it used to run unconditionally, kept only for
+    /// the case where the target node has no stored embedding to look up in
+    /// the HNSW index.
+    async fn find_similar_nodes_by_attributes(&self, target_node: &KGNode, threshold: f32, limit: usize) -> Result<Vec<(KGNode, f32)>> {
         let all_nodes = self.storage.search_nodes_by_text("", None, 10000)?;
         let mut similar_nodes = Vec::new();
 
         for node in all_nodes {
-            if node.uuid == target_uuid {
+            if node.uuid == target_node.uuid {
                 continue; // Skip self
             }
 
-            // Calculate similarity (placeholder - would use actual embeddings)
-            let similarity = self.calculate_node_similarity(&target_node, &node).await?;
-            
+            let similarity = self.calculate_node_similarity(target_node, &node).await?;
             if similarity >= threshold {
                 similar_nodes.push((node, similarity));
             }
         }
 
-        // Sort by similarity and limit results
         similar_nodes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         similar_nodes.truncate(limit);
-
-        println!("🎯 Found {} similar nodes above threshold {}", similar_nodes.len(), threshold);
         Ok(similar_nodes)
     }
 
@@ -253,15 +584,16 @@ impl QueryEngine {
 
             while let Some(current) = queue.pop_front() {
                 // Add current node to component
-                if let Some((&node_uuid, _)) = cache.node_map.iter().find(|(_, &idx)| idx == current) {
-                    if let Ok(Some(node)) = self.storage.get_node(node_uuid) {
-                        component.push(node);
-                    }
+                let node_uuid = cache.csr.node_index_to_uuid[current.index()];
+                if let Ok(Some(node)) = self.storage.get_node(node_uuid) {
+                    component.push(node);
                 }
 
-                // Add unvisited neighbors
-                for edge in cache.graph.edges(current) {
-                    let neighbor = edge.target();
+                // Add unvisited neighbors, off the CSR slice rather than
+                // `cache.graph.edges(current)` - equivalent adjacency, no
+                // petgraph edge-reference overhead per hop.
+                for &neighbor_index in cache.csr.neighbors(current.index() as u32) {
+                    let neighbor = NodeIndex::new(neighbor_index as usize);
                     if !visited.contains(&neighbor) {
                         visited.insert(neighbor);
                         queue.push_back(neighbor);
@@ -278,6 +610,109 @@ impl QueryEngine {
         Ok(communities)
     }
 
+    /// Modularity-optimizing community detection (the Louvain method),
+    /// replacing `detect_communities`'s plain connected components - which
+    /// just finds one giant blob whenever the graph is connected at all and
+    /// can't see any structure finer than that. `detect_communities` is
+    /// kept as-is as a fast fallback for callers that just want components.
+    ///
+    /// Repeats two phases until a pass produces no further merge:
+    ///
+    /// Phase 1 (local moving, `louvain_local_moving`): every node starts in
+    /// its own community, then each node repeatedly moves to whichever
+    /// neighboring community maximizes the modularity gain from joining it,
+    /// using the standard gain formula `ΔQ = [Σ_in + 2·k_i,in]/(2m) -
+    /// [(Σ_tot + k_i)/(2m)]² - (Σ_in/(2m) - [Σ_tot/(2m)]² - [k_i/(2m)]²)`
+    /// with `resolution` scaling the `γ` in the quadratic terms - simplified
+    /// to the equivalent `k_i,in(C) - resolution · Σ_tot(C) · k_i / (2m)`
+    /// for comparing candidates, since the terms that don't depend on the
+    /// candidate community `C` are the same for every choice and drop out
+    /// of the argmax (the same reduction the reference Louvain
+    /// implementation uses).
+    ///
+    /// Phase 2 (aggregation, `aggregate_communities`): every community
+    /// becomes a super-node - its internal edges collapse into a
+    /// self-loop, its edges to other communities sum onto the edge between
+    /// the two super-nodes - and phase 1 runs again on the condensed graph.
+    ///
+    /// The hierarchy is unrolled back to original node UUIDs as it goes, so
+    /// the final communities are sets of original nodes, not super-nodes.
+    /// Returns only those with size ≥ `min_cluster_size`.
+    pub async fn detect_communities_louvain(&self, resolution: f32, min_cluster_size: usize) -> Result<Vec<Vec<KGNode>>> {
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = match graph.read() {
+            Ok(guard) => guard,
+            Err(e) => {
+                tracing::error!("Failed to acquire graph cache read lock for Louvain: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+        let cache = match graph_guard.as_ref() {
+            Some(cache) => cache,
+            None => {
+                tracing::warn!("Graph cache not initialized for Louvain");
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut index_to_uuid: Vec<Uuid> = Vec::new();
+        let mut original_index: HashMap<NodeIndex, usize> = HashMap::new();
+        for node_idx in cache.graph.node_indices() {
+            original_index.insert(node_idx, index_to_uuid.len());
+            index_to_uuid.push(cache.graph[node_idx]);
+        }
+
+        let mut adjacency: HashMap<usize, HashMap<usize, f32>> = HashMap::new();
+        for node_idx in cache.graph.node_indices() {
+            let i = original_index[&node_idx];
+            adjacency.entry(i).or_default();
+            for edge in cache.graph.edges(node_idx) {
+                let j = original_index[&edge.target()];
+                *adjacency.entry(i).or_default().entry(j).or_insert(0.0) += *edge.weight();
+            }
+        }
+
+        let mut current_graph = LouvainGraph { adjacency, self_loop: HashMap::new() };
+        let mut node_groups: HashMap<usize, Vec<usize>> =
+            (0..index_to_uuid.len()).map(|i| (i, vec![i])).collect();
+
+        while current_graph.nodes().count() > 1 {
+            let assignment = louvain_local_moving(&current_graph, resolution);
+            let distinct: HashSet<usize> = assignment.values().copied().collect();
+            if distinct.len() == current_graph.nodes().count() {
+                // This pass moved no node into another's community - the
+                // current `node_groups` partition is already final.
+                break;
+            }
+
+            let (aggregated, members) = aggregate_communities(&current_graph, &assignment);
+            let mut next_node_groups: HashMap<usize, Vec<usize>> = HashMap::new();
+            for (&new_id, old_ids) in &members {
+                let flattened = old_ids.iter().flat_map(|old_id| node_groups[old_id].iter().copied()).collect();
+                next_node_groups.insert(new_id, flattened);
+            }
+            node_groups = next_node_groups;
+            current_graph = aggregated;
+        }
+
+        let mut communities = Vec::new();
+        for group in node_groups.values() {
+            if group.len() < min_cluster_size {
+                continue;
+            }
+            let mut nodes = Vec::new();
+            for &idx in group {
+                if let Ok(Some(node)) = self.storage.get_node(index_to_uuid[idx]) {
+                    nodes.push(node);
+                }
+            }
+            communities.push(nodes);
+        }
+
+        println!("🔍 Louvain detected {} communities with min size {}", communities.len(), min_cluster_size);
+        Ok(communities)
+    }
+
     /// Get node centrality metrics
     pub async fn calculate_centrality(&self, node_uuid: Uuid) -> Result<CentralityMetrics> {
         let graph = self.ensure_graph_cache().await?;
@@ -287,8 +722,9 @@ impl QueryEngine {
         let node_idx = cache.node_map.get(&node_uuid)
             .ok_or_else(|| anyhow::anyhow!("Node not found: {}", node_uuid))?;
 
-        // Degree centrality
-        let degree = cache.graph.edges(*node_idx).count();
+        // Degree centrality, off the CSR row rather than
+        // `cache.graph.edges(*node_idx).count()`.
+        let degree = cache.csr.neighbors(node_idx.index() as u32).len();
         let total_nodes = cache.graph.node_count();
         let degree_centrality = if total_nodes > 1 { 
             degree as f32 / (total_nodes - 1) as f32 
@@ -296,19 +732,147 @@ impl QueryEngine {
             0.0 
         };
 
-        // Betweenness centrality (simplified approximation)
-        let betweenness = self.calculate_betweenness_centrality(*node_idx, &cache.graph).await;
+        // Exact Brandes betweenness, read from the pass `rebuild_graph_cache`
+        // already ran over the whole graph rather than recomputed here.
+        let betweenness = cache.betweenness.get(&node_uuid).copied().unwrap_or(0.0);
 
         // Closeness centrality
         let closeness = self.calculate_closeness_centrality(*node_idx, &cache.graph).await;
 
+        // How tightly this node's own neighbors interconnect - see
+        // `local_clustering_coefficient_of` for the merge-intersection.
+        let clustering_coefficient = local_clustering_coefficient_of(cache, *node_idx);
+
         Ok(CentralityMetrics {
             degree_centrality,
             betweenness_centrality: betweenness,
             closeness_centrality: closeness,
+            clustering_coefficient,
         })
     }
 
+    /// Betweenness centrality for every node in the graph at once (Brandes'
+    /// algorithm, one O(V·E) pass) - the same map `calculate_centrality`
+    /// reads a single entry out of, exposed directly for callers that want
+    /// every node's score without looking each one up individually.
+    pub async fn calculate_all_betweenness(&self) -> Result<HashMap<Uuid, f32>> {
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = graph.read().unwrap();
+        let cache = graph_guard.as_ref().unwrap();
+        Ok(cache.betweenness.clone())
+    }
+
+    /// PageRank over the cached graph via power iteration: every node starts
+    /// at `1/N`, then each round redistributes `damping` of every node's
+    /// current rank to its out-neighbors (split evenly across out-degree)
+    /// plus `(1 - damping)/N` from the random-jump term. Dangling nodes
+    /// (out-degree 0) would otherwise leak rank mass out of the system, so
+    /// their rank is redistributed uniformly across all nodes each round
+    /// instead, same as the random-jump term. Stops early once the L1 change
+    /// between rounds drops below `1e-6`, so `iterations` is a ceiling, not a
+    /// fixed cost.
+    pub async fn calculate_pagerank(&self, damping: f32, iterations: usize) -> Result<HashMap<Uuid, f32>> {
+        const EPSILON: f32 = 1e-6;
+
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = graph.read().unwrap();
+        let cache = graph_guard.as_ref().unwrap();
+
+        let node_count = cache.graph.node_count();
+        if node_count == 0 {
+            return Ok(HashMap::new());
+        }
+
+        let out_degree: HashMap<NodeIndex, usize> = cache
+            .graph
+            .node_indices()
+            .map(|idx| (idx, cache.graph.edges(idx).count()))
+            .collect();
+
+        let n = node_count as f32;
+        let mut rank: HashMap<NodeIndex, f32> =
+            cache.graph.node_indices().map(|idx| (idx, 1.0 / n)).collect();
+
+        for _ in 0..iterations {
+            let dangling_mass: f32 = cache
+                .graph
+                .node_indices()
+                .filter(|idx| out_degree[idx] == 0)
+                .map(|idx| rank[&idx])
+                .sum();
+
+            let mut new_rank: HashMap<NodeIndex, f32> = cache
+                .graph
+                .node_indices()
+                .map(|idx| (idx, (1.0 - damping) / n + damping * dangling_mass / n))
+                .collect();
+
+            for idx in cache.graph.node_indices() {
+                let degree = out_degree[&idx];
+                if degree == 0 {
+                    continue;
+                }
+                let share = damping * rank[&idx] / degree as f32;
+                for edge in cache.graph.edges(idx) {
+                    *new_rank.get_mut(&edge.target()).unwrap() += share;
+                }
+            }
+
+            let delta: f32 = cache
+                .graph
+                .node_indices()
+                .map(|idx| (new_rank[&idx] - rank[&idx]).abs())
+                .sum();
+
+            rank = new_rank;
+            if delta < EPSILON {
+                break;
+            }
+        }
+
+        Ok(rank.into_iter().map(|(idx, score)| (cache.graph[idx], score)).collect())
+    }
+
+    /// Global triangle count: for every edge `(u, v)`, the number of common
+    /// neighbors of `u` and `v` is exactly the number of triangles that edge
+    /// participates in, so summing that over every edge and dividing by 3
+    /// (each triangle has 3 edges, each counted once from each of its edges)
+    /// gives the total. Common-neighbor counts come from a merge
+    /// intersection over each endpoint's CSR row sorted once up front,
+    /// rather than a neighbor-set `HashSet` per edge.
+    pub async fn count_triangles(&self) -> Result<usize> {
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = graph.read().unwrap();
+        let cache = graph_guard.as_ref().unwrap();
+
+        let sorted_neighbors = sorted_csr_neighbors(cache);
+
+        let mut triangle_edges = 0usize;
+        for edge_idx in cache.graph.edge_indices() {
+            let (u, v) = cache.graph.edge_endpoints(edge_idx).unwrap();
+            triangle_edges += merge_intersection_count(&sorted_neighbors[u.index()], &sorted_neighbors[v.index()]);
+        }
+
+        let triangle_count = triangle_edges / 3;
+        println!("🔺 Counted {} triangles", triangle_count);
+        Ok(triangle_count)
+    }
+
+    /// How tightly `node_uuid`'s neighbors interconnect: the fraction of
+    /// possible edges among them that actually exist. `0.0` when the node
+    /// has fewer than two neighbors, since there's no pair of neighbors
+    /// that could form a triangle at all.
+    pub async fn local_clustering_coefficient(&self, node_uuid: Uuid) -> Result<f32> {
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = graph.read().unwrap();
+        let cache = graph_guard.as_ref().unwrap();
+
+        let node_idx = cache.node_map.get(&node_uuid)
+            .ok_or_else(|| anyhow::anyhow!("Node not found: {}", node_uuid))?;
+
+        Ok(local_clustering_coefficient_of(cache, *node_idx))
+    }
+
     // Private helper methods
 
     async fn ensure_graph_cache(&self) -> Result<&std::sync::RwLock<Option<GraphCache>>> {
@@ -329,7 +893,7 @@ impl QueryEngine {
 
     async fn rebuild_graph_cache(&self) -> Result<()> {
         println!("🔄 Rebuilding graph cache...");
-        
+
         let mut graph = UnGraph::new_undirected();
         let mut node_map = HashMap::new();
         let mut edge_map = HashMap::new();
@@ -341,13 +905,31 @@ impl QueryEngine {
             node_map.insert(node.uuid, node_idx);
         }
 
-        // Add all edges (placeholder - would need to get all edges from storage)
-        // For now, we'll create a simplified version
-        
+        // Add all edges, keyed the same way nodes are above - needed for
+        // every edge-weighted traversal in this module (centrality among
+        // them), not just betweenness.
+        let edges = self.storage.get_edges_page(0, 100000)?;
+        for edge in edges {
+            if let (Some(&source_idx), Some(&target_idx)) = (
+                node_map.get(&edge.source_node_uuid),
+                node_map.get(&edge.target_node_uuid),
+            ) {
+                let edge_idx = graph.add_edge(source_idx, target_idx, edge.weight);
+                edge_map.insert(edge.uuid, edge_idx);
+            }
+        }
+
+        // Betweenness is an O(V·E) pass over the whole graph - computed once
+        // here rather than per `calculate_centrality` call.
+        let betweenness = brandes_betweenness(&graph);
+        let csr = Csr::build(&graph, &edge_map);
+
         let cache = GraphCache {
             graph,
             node_map,
             edge_map,
+            csr,
+            betweenness,
             last_updated: std::time::Instant::now(),
         };
 
@@ -398,6 +980,50 @@ impl QueryEngine {
         Ok(result)
     }
 
+    async fn execute_k_shortest_paths_query(&self, query: &str) -> Result<SearchResult> {
+        // Parse k-shortest-paths query: "from_uuid,to_uuid,k"
+        let parts: Vec<&str> = query.split(',').collect();
+        if parts.len() != 3 {
+            return Err(anyhow::anyhow!("Invalid k-shortest-paths query format"));
+        }
+
+        let from_uuid = Uuid::parse_str(parts[0])?;
+        let to_uuid = Uuid::parse_str(parts[1])?;
+        let k: usize = parts[2].parse()?;
+
+        let paths = self.find_k_shortest_paths(from_uuid, to_uuid, k).await?;
+
+        let mut result = SearchResult::new();
+        for (rank, path) in paths.iter().enumerate() {
+            let score = 1.0 - (rank as f32 * 0.1);
+            for edge in path {
+                result.add_edge(edge.clone(), score);
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn execute_astar_query(&self, query: &str) -> Result<SearchResult> {
+        // Parse A* query: "from_uuid,to_uuid"
+        let parts: Vec<&str> = query.split(',').collect();
+        if parts.len() != 2 {
+            return Err(anyhow::anyhow!("Invalid A* query format"));
+        }
+
+        let from_uuid = Uuid::parse_str(parts[0])?;
+        let to_uuid = Uuid::parse_str(parts[1])?;
+
+        let path_edges = self.find_path_astar(from_uuid, to_uuid).await?;
+
+        let mut result = SearchResult::new();
+        for edge in path_edges {
+            result.add_edge(edge, 1.0);
+        }
+
+        Ok(result)
+    }
+
     async fn execute_clustering_query(&self, query: &str) -> Result<SearchResult> {
         let min_size: usize = query.parse().unwrap_or(3);
         let communities = self.detect_communities(min_size).await?;
@@ -432,16 +1058,69 @@ impl QueryEngine {
         Ok(result)
     }
 
-    async fn execute_hybrid_query(&self, query: &str) -> Result<SearchResult> {
-        // Combine text search and graph traversal
-        let text_results = self.storage.search_nodes_by_text(query, None, 20)?;
-        
-        let mut result = SearchResult::new();
-        for node in text_results {
-            result.add_node(node, 0.8); // Base score for text match
-        }
+    async fn execute_pagerank_query(&self, query: &str) -> Result<SearchResult> {
+        let iterations: usize = query.parse().unwrap_or(20);
+        let ranks = self.calculate_pagerank(0.85, iterations).await?;
 
-        result.sort_by_score();
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = graph.read().unwrap();
+        let cache = graph_guard.as_ref().unwrap();
+
+        let mut ranked: Vec<(Uuid, f32)> = ranks.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut result = SearchResult::new();
+        for (uuid, rank) in ranked.into_iter().take(PAGERANK_DEFAULT_RESULTS) {
+            if cache.node_map.contains_key(&uuid) {
+                if let Ok(Some(node)) = self.storage.get_node(uuid) {
+                    result.add_node(node, rank);
+                }
+            }
+        }
+
+        result.sort_by_score();
+        Ok(result)
+    }
+
+    /// `triangles:<limit>` - global triangle count (logged, same as other
+    /// whole-graph stats in this module) plus the `limit` nodes with the
+    /// highest local clustering coefficient.
+    async fn execute_triangles_query(&self, query: &str) -> Result<SearchResult> {
+        let limit: usize = query.parse().unwrap_or(20);
+        self.count_triangles().await?;
+
+        let graph = self.ensure_graph_cache().await?;
+        let graph_guard = graph.read().unwrap();
+        let cache = graph_guard.as_ref().unwrap();
+
+        let mut scored: Vec<(Uuid, f32)> = cache
+            .graph
+            .node_indices()
+            .map(|idx| (cache.graph[idx], local_clustering_coefficient_of(cache, idx)))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut result = SearchResult::new();
+        for (uuid, coefficient) in scored.into_iter().take(limit) {
+            if let Ok(Some(node)) = self.storage.get_node(uuid) {
+                result.add_node(node, coefficient);
+            }
+        }
+
+        result.sort_by_score();
+        Ok(result)
+    }
+
+    async fn execute_hybrid_query(&self, query: &str) -> Result<SearchResult> {
+        // Combine text search and graph traversal
+        let text_results = self.storage.search_nodes_by_text(query, None, 20)?;
+        
+        let mut result = SearchResult::new();
+        for node in text_results {
+            result.add_node(node, 0.8); // Base score for text match
+        }
+
+        result.sort_by_score();
         Ok(result)
     }
 
@@ -454,13 +1133,6 @@ impl QueryEngine {
         Ok((name_similarity + type_similarity) / 2.0)
     }
 
-    async fn calculate_betweenness_centrality(&self, node_idx: NodeIndex, graph: &UnGraph<Uuid, f32>) -> f32 {
-        // Simplified betweenness centrality calculation
-        // In practice, this would be more sophisticated
-        let degree = graph.edges(node_idx).count();
-        degree as f32 / (graph.node_count().max(1) as f32)
-    }
-
     async fn calculate_closeness_centrality(&self, node_idx: NodeIndex, graph: &UnGraph<Uuid, f32>) -> f32 {
         // Simplified closeness centrality calculation
         let paths = dijkstra(graph, node_idx, None, |_| 1.0);
@@ -479,6 +1151,400 @@ pub struct CentralityMetrics {
     pub degree_centrality: f32,
     pub betweenness_centrality: f32,
     pub closeness_centrality: f32,
+    pub clustering_coefficient: f32,
+}
+
+/// Min-heap entry for Dijkstra's shortest-path search inside
+/// `brandes_betweenness` - `BinaryHeap` is a max-heap, so `Ord` is flipped
+/// to pop the smallest distance first. Distances are always finite (sourced
+/// from `f32` edge weights), so `partial_cmp().unwrap()` never panics.
+struct HeapEntry(f64, NodeIndex);
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for HeapEntry {}
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.0.partial_cmp(&self.0).unwrap()
+    }
+}
+
+/// Min-heap entry for `find_k_shortest_paths`'s candidate set, ordered the
+/// same inverted way as `HeapEntry` so the cheapest not-yet-accepted path
+/// pops first.
+struct PathCandidate {
+    cost: f64,
+    path: Vec<NodeIndex>,
+}
+
+impl PartialEq for PathCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+impl Eq for PathCandidate {}
+impl PartialOrd for PathCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for PathCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap()
+    }
+}
+
+/// Dijkstra from `start` to `end` over `graph`, skipping any edge in
+/// `excluded_edges` and never entering any node in `excluded_nodes` - the
+/// two knobs `find_k_shortest_paths` needs to search around an
+/// already-found path without mutating (or cloning) the shared graph
+/// cache. Returns the node path including both endpoints, or `None` if
+/// `end` is unreachable under those restrictions.
+fn restricted_dijkstra(
+    graph: &UnGraph<Uuid, f32>,
+    start: NodeIndex,
+    end: NodeIndex,
+    excluded_edges: &HashSet<EdgeIndex>,
+    excluded_nodes: &HashSet<NodeIndex>,
+) -> Option<Vec<NodeIndex>> {
+    let mut dist: HashMap<NodeIndex, f64> = HashMap::new();
+    let mut prev: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    let mut settled: HashSet<NodeIndex> = HashSet::new();
+
+    dist.insert(start, 0.0);
+    let mut heap = std::collections::BinaryHeap::new();
+    heap.push(HeapEntry(0.0, start));
+
+    while let Some(HeapEntry(d, v)) = heap.pop() {
+        if !settled.insert(v) {
+            continue;
+        }
+        if v == end {
+            break;
+        }
+
+        for edge in graph.edges(v) {
+            if excluded_edges.contains(&edge.id()) {
+                continue;
+            }
+            let w = edge.target();
+            if settled.contains(&w) || excluded_nodes.contains(&w) {
+                continue;
+            }
+            let candidate = d + *edge.weight() as f64;
+            if candidate < *dist.get(&w).unwrap_or(&f64::INFINITY) {
+                dist.insert(w, candidate);
+                prev.insert(w, v);
+                heap.push(HeapEntry(candidate, w));
+            }
+        }
+    }
+
+    if !dist.contains_key(&end) {
+        return None;
+    }
+
+    let mut path = vec![end];
+    let mut current = end;
+    while current != start {
+        current = *prev.get(&current)?;
+        path.push(current);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Total edge weight along a node path already known to be contiguous
+/// (consecutive nodes are always adjacent in `graph`) - used to score a
+/// candidate in `find_k_shortest_paths` after root+spur are concatenated.
+fn path_cost(graph: &UnGraph<Uuid, f32>, path: &[NodeIndex]) -> f64 {
+    path.windows(2)
+        .filter_map(|w| graph.find_edge(w[0], w[1]))
+        .map(|edge_idx| *graph.edge_weight(edge_idx).unwrap_or(&0.0) as f64)
+        .sum()
+}
+
+/// Every node's CSR neighbor row, sorted once so triangle counting can
+/// merge-intersect two rows in O(degree) instead of building a `HashSet`
+/// per node or per edge.
+fn sorted_csr_neighbors(cache: &GraphCache) -> Vec<Vec<u32>> {
+    (0..cache.graph.node_count() as u32)
+        .map(|i| {
+            let mut neighbors = cache.csr.neighbors(i).to_vec();
+            neighbors.sort_unstable();
+            neighbors
+        })
+        .collect()
+}
+
+/// Count of values present in both `a` and `b`, each already sorted - a
+/// merge walk rather than building a `HashSet` out of one side.
+fn merge_intersection_count(a: &[u32], b: &[u32]) -> usize {
+    let (mut i, mut j) = (0, 0);
+    let mut count = 0;
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Local clustering coefficient of a single node: edges actually present
+/// among its neighbors, divided by `k·(k-1)/2` (all possible edges among
+/// `k` neighbors). Defined as `0.0` below `k = 2`, since no pair of
+/// neighbors exists yet to form a triangle.
+fn local_clustering_coefficient_of(cache: &GraphCache, node_idx: NodeIndex) -> f32 {
+    let mut neighbors = cache.csr.neighbors(node_idx.index() as u32).to_vec();
+    let k = neighbors.len();
+    if k < 2 {
+        return 0.0;
+    }
+    neighbors.sort_unstable();
+
+    let edges_among_neighbors: usize = neighbors
+        .iter()
+        .map(|&n| {
+            let mut n_neighbors = cache.csr.neighbors(n).to_vec();
+            n_neighbors.sort_unstable();
+            merge_intersection_count(&neighbors, &n_neighbors)
+        })
+        .sum::<usize>()
+        / 2; // each edge among neighbors counted from both its endpoints
+
+    let max_possible = k * (k - 1) / 2;
+    edges_among_neighbors as f32 / max_possible as f32
+}
+
+/// Exact betweenness centrality for every node in `graph`, computed with
+/// Brandes' algorithm generalized to weighted graphs (Dijkstra instead of
+/// plain BFS for the shortest-path counting phase — this still reduces to
+/// ordinary Brandes when every edge weight is 1.0). One source node at a
+/// time: find shortest-path counts `sigma` and predecessors `pred` via
+/// Dijkstra, push nodes onto `stack` in non-decreasing distance order, then
+/// unwind `stack` accumulating dependency scores `delta[v] += (sigma[v] /
+/// sigma[w]) * (1 + delta[w])` for every predecessor `v` of `w`. Summed
+/// over every source, then halved since each shortest path between an
+/// unordered pair gets counted once from each endpoint in an undirected
+/// graph.
+fn brandes_betweenness(graph: &UnGraph<Uuid, f32>) -> HashMap<Uuid, f32> {
+    let mut centrality: HashMap<NodeIndex, f64> =
+        graph.node_indices().map(|n| (n, 0.0)).collect();
+
+    for s in graph.node_indices() {
+        let mut stack = Vec::new();
+        let mut pred: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+        let mut sigma: HashMap<NodeIndex, f64> = graph.node_indices().map(|n| (n, 0.0)).collect();
+        let mut dist: HashMap<NodeIndex, f64> =
+            graph.node_indices().map(|n| (n, f64::INFINITY)).collect();
+        sigma.insert(s, 1.0);
+        dist.insert(s, 0.0);
+
+        let mut heap = std::collections::BinaryHeap::new();
+        heap.push(HeapEntry(0.0, s));
+        let mut settled: HashSet<NodeIndex> = HashSet::new();
+
+        while let Some(HeapEntry(d, v)) = heap.pop() {
+            if !settled.insert(v) {
+                continue;
+            }
+            stack.push(v);
+
+            for edge in graph.edges(v) {
+                let w = edge.target();
+                if settled.contains(&w) {
+                    continue;
+                }
+                let candidate = d + *edge.weight() as f64;
+
+                if candidate < dist[&w] {
+                    dist.insert(w, candidate);
+                    heap.push(HeapEntry(candidate, w));
+                    sigma.insert(w, sigma[&v]);
+                    pred.insert(w, vec![v]);
+                } else if (candidate - dist[&w]).abs() < 1e-9 {
+                    *sigma.get_mut(&w).unwrap() += sigma[&v];
+                    pred.entry(w).or_default().push(v);
+                }
+            }
+        }
+
+        let mut delta: HashMap<NodeIndex, f64> = graph.node_indices().map(|n| (n, 0.0)).collect();
+        while let Some(w) = stack.pop() {
+            if let Some(preds) = pred.get(&w) {
+                let coeff = (1.0 + delta[&w]) / sigma[&w];
+                for &v in preds {
+                    *delta.get_mut(&v).unwrap() += sigma[&v] * coeff;
+                }
+            }
+            if w != s {
+                *centrality.get_mut(&w).unwrap() += delta[&w];
+            }
+        }
+    }
+
+    centrality
+        .into_iter()
+        .map(|(idx, score)| (graph[idx], (score / 2.0) as f32))
+        .collect()
+}
+
+/// Plain weighted adjacency used by `detect_communities_louvain` instead of
+/// `UnGraph` directly, since Louvain's aggregation phase needs to collapse
+/// groups of nodes into self-looping super-nodes - something petgraph's
+/// fixed node/edge-index scheme isn't built to do in place. `adjacency` is
+/// symmetric (an edge `i-j` appears as both `adjacency[i][j]` and
+/// `adjacency[j][i]`) and never holds a self-entry; self-loops (which only
+/// ever arise from aggregating a community) live in `self_loop` instead.
+struct LouvainGraph {
+    adjacency: HashMap<usize, HashMap<usize, f32>>,
+    self_loop: HashMap<usize, f32>,
+}
+
+impl LouvainGraph {
+    fn nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency.keys().copied()
+    }
+
+    fn neighbors(&self, i: usize) -> impl Iterator<Item = (&usize, &f32)> {
+        self.adjacency.get(&i).into_iter().flatten()
+    }
+
+    fn self_loop_weight(&self, i: usize) -> f32 {
+        self.self_loop.get(&i).copied().unwrap_or(0.0)
+    }
+
+    /// Weighted degree `k_i` - incident edge weight plus twice the
+    /// self-loop, since a self-loop counts each of its two endpoints at
+    /// the same node.
+    fn degree(&self, i: usize) -> f32 {
+        self.neighbors(i).map(|(_, &w)| w).sum::<f32>() + 2.0 * self.self_loop_weight(i)
+    }
+
+    /// Total edge weight `m` - every undirected edge counted once (so the
+    /// doubled `adjacency` sum is halved) plus every self-loop counted once.
+    fn total_weight(&self) -> f32 {
+        let adjacency_sum: f32 = self.adjacency.values().flat_map(|m| m.values()).sum();
+        adjacency_sum / 2.0 + self.self_loop.values().sum::<f32>()
+    }
+}
+
+/// Louvain phase 1: repeatedly moves each node to whichever neighboring
+/// community (its own included) maximizes the modularity gain from
+/// joining it, until a full sweep over every node makes no move. Returns
+/// the resulting node -> community assignment (community ids are a subset
+/// of the input node ids, not renumbered - `aggregate_communities` does
+/// that while condensing).
+fn louvain_local_moving(graph: &LouvainGraph, resolution: f32) -> HashMap<usize, usize> {
+    let m = graph.total_weight();
+    let mut community: HashMap<usize, usize> = graph.nodes().map(|i| (i, i)).collect();
+    if m <= 0.0 {
+        return community;
+    }
+
+    let degree: HashMap<usize, f32> = graph.nodes().map(|i| (i, graph.degree(i))).collect();
+    let mut sigma_tot: HashMap<usize, f32> = community.iter().map(|(&i, &c)| (c, degree[&i])).collect();
+
+    const MAX_SWEEPS: usize = 100;
+    for _ in 0..MAX_SWEEPS {
+        let mut moved_any = false;
+
+        for i in graph.nodes() {
+            let current_comm = community[&i];
+            sigma_tot.entry(current_comm).and_modify(|v| *v -= degree[&i]);
+
+            let mut neighbor_weight: HashMap<usize, f32> = HashMap::new();
+            neighbor_weight.entry(current_comm).or_insert(0.0);
+            for (&j, &w) in graph.neighbors(i) {
+                if j != i {
+                    *neighbor_weight.entry(community[&j]).or_insert(0.0) += w;
+                }
+            }
+
+            let mut best_comm = current_comm;
+            let mut best_gain = f32::NEG_INFINITY;
+            for (&c, &w_in) in &neighbor_weight {
+                let gain = w_in - resolution * sigma_tot.get(&c).copied().unwrap_or(0.0) * degree[&i] / (2.0 * m);
+                if gain > best_gain {
+                    best_gain = gain;
+                    best_comm = c;
+                }
+            }
+
+            sigma_tot.entry(best_comm).and_modify(|v| *v += degree[&i]).or_insert(degree[&i]);
+            if best_comm != current_comm {
+                moved_any = true;
+            }
+            community.insert(i, best_comm);
+        }
+
+        if !moved_any {
+            break;
+        }
+    }
+
+    community
+}
+
+/// Louvain phase 2: collapses every community in `assignment` into a
+/// single super-node, renumbered to a dense `0..k` range. Internal edges
+/// (and the community's own prior self-loops) become the super-node's
+/// self-loop; edges crossing community boundaries sum onto the edge
+/// between the two super-nodes. Each undirected edge is visited once from
+/// either endpoint over the full loop, so every weight added here is
+/// halved to land on the original total. Also returns, per new super-node
+/// id, which of `graph`'s node ids were grouped into it - the caller
+/// threads that back through the previous level's own groups to unroll
+/// the hierarchy to original node ids.
+fn aggregate_communities(graph: &LouvainGraph, assignment: &HashMap<usize, usize>) -> (LouvainGraph, HashMap<usize, Vec<usize>>) {
+    let mut renumber: HashMap<usize, usize> = HashMap::new();
+    for &c in assignment.values() {
+        let next_id = renumber.len();
+        renumber.entry(c).or_insert(next_id);
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (&node, &c) in assignment {
+        members.entry(renumber[&c]).or_default().push(node);
+    }
+
+    let mut new_adjacency: HashMap<usize, HashMap<usize, f32>> = HashMap::new();
+    let mut new_self_loop: HashMap<usize, f32> = HashMap::new();
+
+    for &new_id in renumber.values() {
+        new_adjacency.entry(new_id).or_default();
+        new_self_loop.entry(new_id).or_insert(0.0);
+    }
+
+    for i in graph.nodes() {
+        let ci = renumber[&assignment[&i]];
+        *new_self_loop.entry(ci).or_insert(0.0) += graph.self_loop_weight(i);
+
+        for (&j, &w) in graph.neighbors(i) {
+            let cj = renumber[&assignment[&j]];
+            if ci == cj {
+                *new_self_loop.entry(ci).or_insert(0.0) += w / 2.0;
+            } else {
+                *new_adjacency.entry(ci).or_default().entry(cj).or_insert(0.0) += w / 2.0;
+            }
+        }
+    }
+
+    (LouvainGraph { adjacency: new_adjacency, self_loop: new_self_loop }, members)
 }
 
 impl GraphQueryEngine {
@@ -710,123 +1776,952 @@ impl GraphQueryEngine {
         Ok(components)
     }
 
-    /// Find nodes by pattern matching
+    /// Subgraph isomorphism via the VF2 recurrence: `pattern` is a small
+    /// query graph (`pattern.nodes` vertices joined by `pattern.edges`) that
+    /// this maps, as a whole, onto the data graph built from `nodes`/
+    /// `edges` - unlike a root-plus-immediate-neighbors check, this finds
+    /// multi-hop and cyclic patterns and never double-counts a data node
+    /// shared by two pattern vertices (each data node is claimed by at most
+    /// one pattern vertex in a given mapping).
+    ///
+    /// Pattern vertices are mapped in index order `0..pattern.nodes.len()`,
+    /// which is also "the least-indexed unmapped pattern node" VF2 asks for
+    /// at each step, since every vertex behind the current depth is already
+    /// mapped. Candidates for the node at `depth` are drawn from the data
+    /// frontier - unmapped data nodes reachable from/into the current
+    /// mapping along a directed edge - falling back to every unmapped data
+    /// node only once the mapping is empty (depth 0) or the frontier itself
+    /// is empty. A candidate is accepted only once `node_match_score`
+    /// holds and `vf2_feasible` confirms every edge the pattern requires
+    /// between it and an already-mapped neighbor exists in the data graph
+    /// with a compatible `relation_type`, plus a degree-count prune.
     pub async fn pattern_match(
         &self,
         pattern: &GraphPattern,
         nodes: &[KGNode],
         edges: &[KGEdge],
     ) -> Result<Vec<PatternMatch>> {
+        if pattern.nodes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let data_adj = DataAdjacency::build(nodes, edges);
+        let query_adj = QueryAdjacency::build(pattern);
+
         let mut matches = Vec::new();
+        let mut mapping: Vec<Option<usize>> = vec![None; pattern.nodes.len()];
+        let mut used = vec![false; nodes.len()];
+        let mut node_scores = vec![0.0f32; pattern.nodes.len()];
 
-        // Simple pattern matching implementation
-        for node in nodes {
-            if self.node_matches_pattern(node, &pattern.node_pattern) {
-                let mut node_matches = vec![node.clone()];
-                
-                // Check if connected nodes match the pattern
-                if let Some(ref edge_patterns) = pattern.edge_patterns {
-                    for edge_pattern in edge_patterns {
-                        let connected_nodes = self.find_connected_nodes_matching_pattern(
-                            node.uuid,
-                            edge_pattern,
-                            nodes,
-                            edges,
-                        ).await?;
-                        
-                        if !connected_nodes.is_empty() {
-                            node_matches.extend(connected_nodes);
-                        }
-                    }
-                }
+        self.vf2_recurse(0, pattern, nodes, &data_adj, &query_adj, &mut mapping, &mut used, &mut node_scores, &mut matches);
 
-                matches.push(PatternMatch {
-                    nodes: node_matches,
-                    confidence: 1.0, // Simplified
-                });
-            }
-        }
+        matches.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
 
         debug!("Pattern matching found {} matches", matches.len());
         Ok(matches)
     }
 
-    // Helper methods
+    /// Streaming counterpart to `pattern_match`: the same VF2 search, but
+    /// driven by an explicit DFS stack of `SearchFrame`s rather than
+    /// recursing to completion, so each match is yielded as soon as it's
+    /// found. Lets a caller that only wants "does any match exist?" or
+    /// "give me 10 examples" stop (`.take(k)`, `.next()`) without
+    /// enumerating the rest of a potentially huge match set.
+    pub fn pattern_match_iter<'a>(
+        &'a self,
+        pattern: &'a GraphPattern,
+        nodes: &'a [KGNode],
+        edges: &'a [KGEdge],
+    ) -> PatternMatchIter<'a> {
+        PatternMatchIter::new(self, pattern, nodes, edges)
+    }
 
-    fn node_matches_pattern(&self, node: &KGNode, pattern: &NodePattern) -> bool {
-        // Check node type
-        if let Some(ref expected_type) = pattern.node_type {
-            if &node.node_type != expected_type {
-                return false;
-            }
+    /// Structural diff between two graph snapshots (e.g. before/after an
+    /// ingestion run). Nodes match first by `uuid`; a node whose uuid
+    /// didn't carry over falls back to a greedy `node_type`-plus-metadata
+    /// similarity match against the other snapshot's leftover nodes, so a
+    /// re-ingested node shows up as one `Changed` entry rather than a
+    /// spurious `Removed`+`Added` pair. Edges are classified once their
+    /// endpoints resolve (through that same node match) to a pair of nodes
+    /// present on both sides: `Changed` when `relation_type` or metadata
+    /// differs, `Removed`/`Added` when no edge with matching endpoints
+    /// exists on the other side.
+    pub async fn diff_graphs(
+        &self,
+        before_nodes: &[KGNode],
+        before_edges: &[KGEdge],
+        after_nodes: &[KGNode],
+        after_edges: &[KGEdge],
+    ) -> Result<GraphDiff> {
+        let (node_diffs, node_equivalence) = match_nodes(before_nodes, after_nodes);
+        let edge_diffs = diff_edges(before_edges, after_edges, &node_equivalence);
+
+        debug!(
+            "Graph diff: {} node change(s), {} edge change(s)",
+            node_diffs.len(),
+            edge_diffs.len()
+        );
+        Ok(GraphDiff { node_diffs, edge_diffs })
+    }
+
+    /// One level of the VF2 search tree: try every feasible data-node
+    /// candidate for pattern vertex `depth`, recursing (and backtracking)
+    /// on each.
+    fn vf2_recurse(
+        &self,
+        depth: usize,
+        pattern: &GraphPattern,
+        nodes: &[KGNode],
+        data_adj: &DataAdjacency,
+        query_adj: &QueryAdjacency,
+        mapping: &mut Vec<Option<usize>>,
+        used: &mut Vec<bool>,
+        node_scores: &mut Vec<f32>,
+        matches: &mut Vec<PatternMatch>,
+    ) {
+        if depth == pattern.nodes.len() {
+            let matched_nodes = mapping.iter().map(|m| nodes[m.unwrap()].clone()).collect();
+            let confidence = match_confidence(pattern, node_scores);
+            let path_nodes = resolve_path_nodes(pattern, nodes, mapping, data_adj);
+            matches.push(PatternMatch { nodes: matched_nodes, confidence, path_nodes });
+            return;
         }
 
-        // Check properties
-        for (key, expected_value) in &pattern.properties {
-            if let Some(actual_value) = node.metadata.get(key) {
-                if actual_value != expected_value {
-                    return false;
-                }
-            } else {
-                return false;
+        let candidates = vf2_candidates(depth, nodes.len(), mapping, used, data_adj, query_adj.max_hops);
+
+        for m in candidates {
+            if used[m] {
+                continue;
+            }
+            let Some(score) = self.node_match_score(&nodes[m], &pattern.nodes[depth], pattern.wildcard_weight) else {
+                continue;
+            };
+            if !vf2_feasible(depth, m, mapping, data_adj, query_adj) {
+                continue;
             }
-        }
 
-        true
+            mapping[depth] = Some(m);
+            used[m] = true;
+            node_scores[depth] = score;
+            self.vf2_recurse(depth + 1, pattern, nodes, data_adj, query_adj, mapping, used, node_scores, matches);
+            mapping[depth] = None;
+            used[m] = false;
+        }
     }
 
-    async fn find_connected_nodes_matching_pattern(
-        &self,
-        start_uuid: Uuid,
-        edge_pattern: &EdgePattern,
-        nodes: &[KGNode],
-        edges: &[KGEdge],
-    ) -> Result<Vec<KGNode>> {
-        let mut results = Vec::new();
+    // Helper methods
 
-        for edge in edges {
-            if edge.source_node_uuid == start_uuid {
-                // Check if edge matches pattern
-                if let Some(ref expected_type) = edge_pattern.relationship_type {
-                    if &edge.relation_type != expected_type {
-                        continue;
-                    }
+    /// Whether (and how well) `node` satisfies `pattern`: `None` on any
+    /// failed check, `Some(score)` otherwise - the geometric mean of one
+    /// component per pattern element, feeding `PatternMatch::confidence` at
+    /// `pattern_match`/`pattern_match_iter` completion rather than the old
+    /// hard-coded `1.0`. An exact `node_type`/property hit scores `1.0`; a
+    /// fuzzy property hit scores its normalized similarity; a wildcard
+    /// `node_type` (`None`, unconditionally satisfied) scores
+    /// `wildcard_weight` rather than being left out of the aggregate.
+    fn node_match_score(&self, node: &KGNode, pattern: &NodePattern, wildcard_weight: f32) -> Option<f32> {
+        let mut scores = Vec::with_capacity(1 + pattern.properties.len());
+
+        match &pattern.node_type {
+            Some(expected_type) => {
+                if &node.node_type != expected_type {
+                    return None;
                 }
+                scores.push(1.0);
+            }
+            None => scores.push(wildcard_weight),
+        }
 
-                // Find target node
-                if let Some(target_node) = nodes.iter().find(|n| n.uuid == edge.target_node_uuid) {
-                    if self.node_matches_pattern(target_node, &edge_pattern.target_pattern) {
-                        results.push(target_node.clone());
+        for (key, expected_value) in &pattern.properties {
+            let Some(actual_value) = node.metadata.get(key) else {
+                return None;
+            };
+
+            let score = match (&pattern.fuzzy_match, expected_value.as_str(), actual_value.as_str()) {
+                (Some(fuzzy), Some(expected_str), Some(actual_str)) => {
+                    let similarity = string_similarity(expected_str, actual_str);
+                    if similarity < fuzzy.similarity_threshold(key) {
+                        return None;
                     }
+                    similarity
                 }
-            }
+                _ => {
+                    if actual_value != expected_value {
+                        return None;
+                    }
+                    1.0
+                }
+            };
+
+            scores.push(score);
         }
 
-        Ok(results)
+        Some(geometric_mean(&scores))
     }
 }
 
-/// Graph pattern for pattern matching
+/// Graph pattern for pattern matching: a small query graph of
+/// `NodePattern` vertices, indexed `0..nodes.len()`, joined by `EdgePattern`s
+/// that reference those indices - the shape `QueryEngine::pattern_match`'s
+/// VF2 search maps as a whole onto the data graph, rather than a single
+/// root plus its immediate neighbors.
 #[derive(Debug, Clone)]
 pub struct GraphPattern {
-    pub node_pattern: NodePattern,
-    pub edge_patterns: Option<Vec<EdgePattern>>,
+    pub nodes: Vec<NodePattern>,
+    pub edges: Vec<EdgePattern>,
+    /// Confidence contribution of a wildcard pattern element - a
+    /// `NodePattern` with `node_type: None`, or an `EdgePattern` with
+    /// `relationship_type: None` - which matches unconditionally and so
+    /// shouldn't score as strongly as an exact hit, but also shouldn't be
+    /// dropped from the aggregate entirely.
+    pub wildcard_weight: f32,
+}
+
+impl Default for GraphPattern {
+    fn default() -> Self {
+        Self { nodes: Vec::new(), edges: Vec::new(), wildcard_weight: 0.75 }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct NodePattern {
     pub node_type: Option<String>,
     pub properties: HashMap<String, serde_json::Value>,
+    /// When set, string-valued `properties` match within an edit-distance
+    /// tolerance instead of requiring byte-for-byte equality - `None` (the
+    /// default) keeps exact matching, same as before fuzzy matching existed.
+    pub fuzzy_match: Option<FuzzyMatchConfig>,
 }
 
+/// Fuzzy string-property matching tolerance for a `NodePattern`. A
+/// property's actual value counts as a match when its normalized
+/// similarity to the expected value - see `string_similarity` - meets or
+/// exceeds the threshold, rather than requiring an exact match.
+#[derive(Debug, Clone)]
+pub struct FuzzyMatchConfig {
+    /// Similarity cutoff used for any property without an entry in
+    /// `property_tolerance`.
+    pub default_similarity: f32,
+    /// Per-property overrides of `default_similarity`, keyed by property
+    /// name - e.g. a `name` field tolerating more drift than an `id`.
+    pub property_tolerance: HashMap<String, f32>,
+}
+
+impl Default for FuzzyMatchConfig {
+    fn default() -> Self {
+        Self {
+            default_similarity: 0.85,
+            property_tolerance: HashMap::new(),
+        }
+    }
+}
+
+impl FuzzyMatchConfig {
+    fn similarity_threshold(&self, property: &str) -> f32 {
+        self.property_tolerance.get(property).copied().unwrap_or(self.default_similarity)
+    }
+}
+
+/// A directed edge from `GraphPattern::nodes[source]` to
+/// `GraphPattern::nodes[target]`; `relationship_type` of `None` matches any
+/// `KGEdge::relation_type`. `min_hops`/`max_hops` of `(1, 1)` - the default,
+/// same as before variable-length patterns existed - is a single direct
+/// edge; a wider range expands this edge into a bounded DFS over data-graph
+/// paths of that many hops, each of whose edges must satisfy
+/// `relationship_type`, so "A connected to B through 1..3 hops of any
+/// relation" becomes expressible.
 #[derive(Debug, Clone)]
 pub struct EdgePattern {
+    pub source: usize,
+    pub target: usize,
     pub relationship_type: Option<String>,
-    pub target_pattern: NodePattern,
+    pub min_hops: usize,
+    pub max_hops: usize,
+}
+
+impl EdgePattern {
+    /// A direct single-hop edge - `min_hops`/`max_hops` both `1`.
+    pub fn direct(source: usize, target: usize, relationship_type: Option<String>) -> Self {
+        Self { source, target, relationship_type, min_hops: 1, max_hops: 1 }
+    }
+
+    /// A bounded-hop edge: matches a path of `min_hops..=max_hops` steps
+    /// whose every edge satisfies `relationship_type` (any type, when `None`).
+    pub fn with_hops(
+        source: usize,
+        target: usize,
+        relationship_type: Option<String>,
+        min_hops: usize,
+        max_hops: usize,
+    ) -> Self {
+        Self { source, target, relationship_type, min_hops, max_hops }
+    }
+
+    fn is_direct(&self) -> bool {
+        self.min_hops <= 1 && self.max_hops <= 1
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct PatternMatch {
     pub nodes: Vec<KGNode>,
     pub confidence: f32,
-} 
\ No newline at end of file
+    /// Every intermediate node - excluding both endpoints, which are
+    /// already in `nodes` - along a bounded-hop `EdgePattern`'s resolved
+    /// path, in `pattern.edges` order. Empty when every edge in the
+    /// pattern is a direct single-hop edge. Lets a caller reconstruct the
+    /// full transitive-reachability path a multi-hop edge matched, not
+    /// just the two pattern vertices it connects.
+    pub path_nodes: Vec<KGNode>,
+}
+
+/// One node or edge comparison result from `GraphQueryEngine::diff_graphs`.
+#[derive(Debug, Clone)]
+pub enum DiffEntry<T> {
+    Added(T),
+    Removed(T),
+    Changed { before: T, after: T },
+}
+
+/// Structural diff between two graph snapshots - see
+/// `GraphQueryEngine::diff_graphs`.
+#[derive(Debug, Clone)]
+pub struct GraphDiff {
+    pub node_diffs: Vec<DiffEntry<KGNode>>,
+    pub edge_diffs: Vec<DiffEntry<KGEdge>>,
+}
+
+/// Below this similarity, two uuid-mismatched nodes are treated as
+/// unrelated (one Removed, the other Added) rather than the same node
+/// re-ingested under a new uuid.
+const NODE_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// Half `node_type` equality, half metadata key/value overlap (Jaccard-
+/// style over the union of both nodes' keys) - either signal alone is
+/// cheap to spoof (many nodes share a type; two unrelated nodes can share
+/// one metadata key), but a node re-ingested under a new uuid should keep
+/// both.
+fn node_similarity(a: &KGNode, b: &KGNode) -> f32 {
+    let type_score = if a.node_type == b.node_type { 0.5 } else { 0.0 };
+
+    let all_keys: HashSet<&str> = a.metadata.keys().chain(b.metadata.keys()).map(String::as_str).collect();
+    let metadata_score = if all_keys.is_empty() {
+        0.5 // neither side has metadata to disagree on
+    } else {
+        let matching = all_keys.iter().filter(|&&k| a.metadata.get(k) == b.metadata.get(k)).count();
+        0.5 * matching as f32 / all_keys.len() as f32
+    };
+
+    type_score + metadata_score
+}
+
+/// Whether two uuid-matched nodes' content actually diverged - `uuid`/
+/// `created_at`/`updated_at` are deliberately excluded, since a re-ingest
+/// that only touches the timestamp shouldn't read as a content change.
+fn node_content_differs(a: &KGNode, b: &KGNode) -> bool {
+    a.name != b.name
+        || a.node_type != b.node_type
+        || a.summary != b.summary
+        || a.group_id != b.group_id
+        || a.metadata != b.metadata
+}
+
+/// Whether two endpoint-matched edges' content actually diverged - same
+/// exclusions as `node_content_differs`, plus the edge's own endpoints
+/// (already established as equivalent by the caller).
+fn edge_content_differs(a: &KGEdge, b: &KGEdge) -> bool {
+    a.relation_type != b.relation_type
+        || a.summary != b.summary
+        || (a.weight - b.weight).abs() > f32::EPSILON
+        || a.group_id != b.group_id
+        || a.metadata != b.metadata
+}
+
+/// Matches `before`'s nodes against `after`'s: uuid first, then a greedy
+/// similarity match (highest-scoring pairs first) over whatever's left on
+/// both sides. Returns the diff entries plus a `before uuid -> after uuid`
+/// equivalence map - used by `diff_edges` to resolve an edge's endpoints
+/// across the two snapshots even when a node's uuid didn't carry over.
+fn match_nodes(before: &[KGNode], after: &[KGNode]) -> (Vec<DiffEntry<KGNode>>, HashMap<Uuid, Uuid>) {
+    let after_by_uuid: HashMap<Uuid, &KGNode> = after.iter().map(|n| (n.uuid, n)).collect();
+
+    let mut diffs = Vec::new();
+    let mut equivalence = HashMap::new();
+    let mut matched_after: HashSet<Uuid> = HashSet::new();
+    let mut unmatched_before = Vec::new();
+
+    for node in before {
+        if let Some(&after_node) = after_by_uuid.get(&node.uuid) {
+            matched_after.insert(node.uuid);
+            equivalence.insert(node.uuid, node.uuid);
+            if node_content_differs(node, after_node) {
+                diffs.push(DiffEntry::Changed { before: node.clone(), after: after_node.clone() });
+            }
+        } else {
+            unmatched_before.push(node);
+        }
+    }
+
+    let unmatched_after: Vec<&KGNode> = after.iter().filter(|n| !matched_after.contains(&n.uuid)).collect();
+
+    let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+    for (bi, b) in unmatched_before.iter().enumerate() {
+        for (ai, a) in unmatched_after.iter().enumerate() {
+            let score = node_similarity(b, a);
+            if score >= NODE_SIMILARITY_THRESHOLD {
+                candidates.push((score, bi, ai));
+            }
+        }
+    }
+    candidates.sort_by(|x, y| y.0.partial_cmp(&x.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut before_taken = vec![false; unmatched_before.len()];
+    let mut after_taken = vec![false; unmatched_after.len()];
+
+    for (_, bi, ai) in candidates {
+        if before_taken[bi] || after_taken[ai] {
+            continue;
+        }
+        before_taken[bi] = true;
+        after_taken[ai] = true;
+
+        let before_node = unmatched_before[bi];
+        let after_node = unmatched_after[ai];
+        equivalence.insert(before_node.uuid, after_node.uuid);
+        diffs.push(DiffEntry::Changed { before: before_node.clone(), after: after_node.clone() });
+    }
+
+    for (bi, node) in unmatched_before.iter().enumerate() {
+        if !before_taken[bi] {
+            diffs.push(DiffEntry::Removed((*node).clone()));
+        }
+    }
+    for (ai, node) in unmatched_after.iter().enumerate() {
+        if !after_taken[ai] {
+            diffs.push(DiffEntry::Added((*node).clone()));
+        }
+    }
+
+    (diffs, equivalence)
+}
+
+/// Matches `before`'s edges against `after`'s via `node_equivalence`
+/// (falling back to an edge's own endpoint uuids when a node had no match
+/// at all, so a dangling edge still reports instead of silently vanishing):
+/// same mapped endpoints and `relation_type` is an unambiguous pairing;
+/// same mapped endpoints with a different `relation_type` is still
+/// preferred over leaving both sides unmatched, and reports as `Changed`.
+fn diff_edges(before: &[KGEdge], after: &[KGEdge], node_equivalence: &HashMap<Uuid, Uuid>) -> Vec<DiffEntry<KGEdge>> {
+    let mut after_by_endpoints: HashMap<(Uuid, Uuid), Vec<usize>> = HashMap::new();
+    for (i, edge) in after.iter().enumerate() {
+        after_by_endpoints
+            .entry((edge.source_node_uuid, edge.target_node_uuid))
+            .or_default()
+            .push(i);
+    }
+
+    let mut diffs = Vec::new();
+    let mut after_taken = vec![false; after.len()];
+
+    for edge in before {
+        let mapped_source = node_equivalence.get(&edge.source_node_uuid).copied().unwrap_or(edge.source_node_uuid);
+        let mapped_target = node_equivalence.get(&edge.target_node_uuid).copied().unwrap_or(edge.target_node_uuid);
+
+        let candidates = after_by_endpoints.get(&(mapped_source, mapped_target));
+        let chosen = candidates.and_then(|indices| {
+            indices
+                .iter()
+                .find(|&&i| !after_taken[i] && after[i].relation_type == edge.relation_type)
+                .or_else(|| indices.iter().find(|&&i| !after_taken[i]))
+        });
+
+        match chosen {
+            Some(&i) => {
+                after_taken[i] = true;
+                if edge_content_differs(edge, &after[i]) {
+                    diffs.push(DiffEntry::Changed { before: edge.clone(), after: after[i].clone() });
+                }
+            }
+            None => diffs.push(DiffEntry::Removed(edge.clone())),
+        }
+    }
+
+    for (i, edge) in after.iter().enumerate() {
+        if !after_taken[i] {
+            diffs.push(DiffEntry::Added(edge.clone()));
+        }
+    }
+
+    diffs
+}
+
+/// Levenshtein edit distance between `expected` and `actual` - unit
+/// insert/delete/substitute costs, computed with a single rolling row
+/// (indexed by `actual`'s chars) rather than a full `expected.len() x
+/// actual.len()` table, so memory stays O(actual.len()) instead of
+/// O(expected.len() * actual.len()).
+fn levenshtein_distance(expected: &str, actual: &str) -> usize {
+    let actual_chars: Vec<char> = actual.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=actual_chars.len()).collect();
+
+    for (i, expected_char) in expected.chars().enumerate() {
+        let mut current_row = vec![0usize; actual_chars.len() + 1];
+        current_row[0] = i + 1;
+
+        for (j, &actual_char) in actual_chars.iter().enumerate() {
+            let cost = if expected_char == actual_char { 0 } else { 1 };
+            current_row[j + 1] = (previous_row[j + 1] + 1) // deletion
+                .min(current_row[j] + 1) // insertion
+                .min(previous_row[j] + cost); // substitution
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[actual_chars.len()]
+}
+
+/// Normalized similarity in `[0.0, 1.0]`: `1.0` for an exact match, `0.0`
+/// once the edit distance is at least as large as the longer string. Two
+/// empty strings count as an exact match rather than dividing by zero.
+fn string_similarity(expected: &str, actual: &str) -> f32 {
+    let max_len = expected.chars().count().max(actual.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    1.0 - (levenshtein_distance(expected, actual) as f32 / max_len as f32)
+}
+
+/// Geometric mean of `scores` - a single weak component pulls the
+/// aggregate down far harder than an arithmetic mean would, which is the
+/// point: a `PatternMatch` with one barely-passing fuzzy hit should rank
+/// below one where every element matched cleanly, not average out to
+/// looking just as good. `1.0` for no components at all (never hit in
+/// practice, since every pattern has at least one node).
+fn geometric_mean(scores: &[f32]) -> f32 {
+    if scores.is_empty() {
+        return 1.0;
+    }
+    let product: f64 = scores.iter().map(|&s| s as f64).product();
+    product.powf(1.0 / scores.len() as f64) as f32
+}
+
+/// An `EdgePattern`'s match-quality component: `1.0` for an exact
+/// `relationship_type` hit (`vf2_feasible` already confirmed a data edge
+/// with that exact type exists), `wildcard_weight` for a `None`
+/// (unconditional) relationship type.
+fn edge_match_score(edge_pattern: &EdgePattern, wildcard_weight: f32) -> f32 {
+    if edge_pattern.relationship_type.is_some() { 1.0 } else { wildcard_weight }
+}
+
+/// `PatternMatch::confidence` for a just-completed VF2 mapping: the
+/// geometric mean of every pattern node's `node_match_score` (already
+/// collected during the search) plus every pattern edge's
+/// `edge_match_score`.
+fn match_confidence(pattern: &GraphPattern, node_scores: &[f32]) -> f32 {
+    let mut scores = node_scores.to_vec();
+    scores.extend(pattern.edges.iter().map(|e| edge_match_score(e, pattern.wildcard_weight)));
+    geometric_mean(&scores)
+}
+
+/// Compact-index adjacency for the data graph (`nodes`/`edges` as passed to
+/// `pattern_match`), so VF2's per-candidate edge and frontier checks are
+/// array/bitset lookups rather than scans over the original `KGEdge` slice.
+struct DataAdjacency {
+    out_edges: Vec<Vec<(usize, String)>>,
+    in_edges: Vec<Vec<(usize, String)>>,
+}
+
+impl DataAdjacency {
+    fn build(nodes: &[KGNode], edges: &[KGEdge]) -> Self {
+        let index: HashMap<Uuid, usize> = nodes.iter().enumerate().map(|(i, n)| (n.uuid, i)).collect();
+        let mut out_edges = vec![Vec::new(); nodes.len()];
+        let mut in_edges = vec![Vec::new(); nodes.len()];
+
+        for edge in edges {
+            if let (Some(&source), Some(&target)) =
+                (index.get(&edge.source_node_uuid), index.get(&edge.target_node_uuid))
+            {
+                out_edges[source].push((target, edge.relation_type.clone()));
+                in_edges[target].push((source, edge.relation_type.clone()));
+            }
+        }
+
+        Self { out_edges, in_edges }
+    }
+
+    /// Whether a directed data edge `from -> to` exists whose relation type
+    /// satisfies `relation` (any type, when `None`).
+    fn has_edge(&self, from: usize, to: usize, relation: &Option<String>) -> bool {
+        self.out_edges[from]
+            .iter()
+            .any(|(t, r)| *t == to && relation.as_ref().map_or(true, |expected| expected == r))
+    }
+
+    /// Whether a directed connection `from -> to` satisfying `relation`
+    /// exists within `min_hops..=max_hops` steps - `has_edge` for the
+    /// `(1, 1)` direct case, a bounded DFS (`bounded_path`) otherwise.
+    fn satisfies(&self, from: usize, to: usize, relation: &Option<String>, min_hops: usize, max_hops: usize) -> bool {
+        if min_hops <= 1 && max_hops <= 1 {
+            return self.has_edge(from, to, relation);
+        }
+        self.bounded_path(from, to, min_hops, max_hops, relation).is_some()
+    }
+
+    /// First path (depth-first, so not necessarily shortest) from `from`
+    /// to `to` of length `min_hops..=max_hops` hops whose every edge's
+    /// relation type satisfies `relation` (any type, when `None`) - the
+    /// expansion an `EdgePattern`'s hop range describes instead of a
+    /// single direct edge. A node already on the path can't be revisited,
+    /// so this terminates even on a cyclic data graph.
+    fn bounded_path(
+        &self,
+        from: usize,
+        to: usize,
+        min_hops: usize,
+        max_hops: usize,
+        relation: &Option<String>,
+    ) -> Option<Vec<usize>> {
+        let mut path = vec![from];
+        let mut visited = HashSet::new();
+        visited.insert(from);
+        self.bounded_path_dfs(from, to, min_hops, max_hops, relation, &mut path, &mut visited)
+    }
+
+    fn bounded_path_dfs(
+        &self,
+        current: usize,
+        to: usize,
+        min_hops: usize,
+        max_hops: usize,
+        relation: &Option<String>,
+        path: &mut Vec<usize>,
+        visited: &mut HashSet<usize>,
+    ) -> Option<Vec<usize>> {
+        let hops_so_far = path.len() - 1;
+        if current == to && hops_so_far >= min_hops {
+            return Some(path.clone());
+        }
+        if hops_so_far >= max_hops {
+            return None;
+        }
+
+        for (next, r) in &self.out_edges[current] {
+            if !relation.as_ref().map_or(true, |expected| expected == r) {
+                continue;
+            }
+            if !visited.insert(*next) {
+                continue;
+            }
+            path.push(*next);
+            if let Some(found) = self.bounded_path_dfs(*next, to, min_hops, max_hops, relation, path, visited) {
+                return Some(found);
+            }
+            path.pop();
+            visited.remove(next);
+        }
+
+        None
+    }
+
+    /// Data nodes reachable from `from` within `1..=max_hops` steps along
+    /// any relation type - a superset used only to broaden
+    /// `vf2_candidates`' frontier for a hop-range `EdgePattern`, not to
+    /// decide feasibility (`satisfies`/`bounded_path` remain the
+    /// authoritative, relation-exact check).
+    fn reachable_within(&self, from: usize, max_hops: usize) -> HashSet<usize> {
+        let mut frontier = vec![from];
+        let mut seen = HashSet::new();
+        for _ in 0..max_hops {
+            let mut next = Vec::new();
+            for &current in &frontier {
+                for &(target, _) in &self.out_edges[current] {
+                    if seen.insert(target) {
+                        next.push(target);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        seen
+    }
+
+    /// `reachable_within`'s mirror image: data nodes from which `from` is
+    /// reachable within `1..=max_hops` steps.
+    fn reachable_within_reverse(&self, from: usize, max_hops: usize) -> HashSet<usize> {
+        let mut frontier = vec![from];
+        let mut seen = HashSet::new();
+        for _ in 0..max_hops {
+            let mut next = Vec::new();
+            for &current in &frontier {
+                for &(source, _) in &self.in_edges[current] {
+                    if seen.insert(source) {
+                        next.push(source);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        seen
+    }
+
+    /// VF2's Tout/Tin: unmapped data nodes reachable from (`Tout`) or into
+    /// (`Tin`) the data nodes already claimed by `mapped`, within
+    /// `1..=max_hops` steps along any relation - the candidate pool for
+    /// the next pattern vertex, before falling back to every unmapped data
+    /// node. `max_hops` is `1` (a single direct edge, same as before
+    /// hop-range patterns existed) unless the pattern has a wider
+    /// `EdgePattern`, in which case the frontier is broadened so a valid
+    /// multi-hop candidate isn't excluded just for not being a *direct*
+    /// neighbor of anything already mapped.
+    fn frontiers(&self, mapped: &[usize], used: &[bool], max_hops: usize) -> (HashSet<usize>, HashSet<usize>) {
+        let max_hops = max_hops.max(1);
+        let mut tout = HashSet::new();
+        let mut tin = HashSet::new();
+        for &m in mapped {
+            tout.extend(self.reachable_within(m, max_hops).into_iter().filter(|t| !used[*t]));
+            tin.extend(self.reachable_within_reverse(m, max_hops).into_iter().filter(|t| !used[*t]));
+        }
+        (tout, tin)
+    }
+}
+
+/// Compact-index adjacency for `GraphPattern` itself, mirroring
+/// `DataAdjacency` so feasibility checks compare like-shaped structures.
+struct QueryAdjacency {
+    out_edges: Vec<Vec<(usize, Option<String>, usize, usize)>>,
+    in_edges: Vec<Vec<(usize, Option<String>, usize, usize)>>,
+    /// Widest `max_hops` among `pattern.edges`, `1` when every edge is a
+    /// direct single-hop edge - how far `vf2_candidates` broadens its data
+    /// frontier so a hop-range edge's valid candidates aren't excluded.
+    max_hops: usize,
+}
+
+impl QueryAdjacency {
+    fn build(pattern: &GraphPattern) -> Self {
+        let mut out_edges = vec![Vec::new(); pattern.nodes.len()];
+        let mut in_edges = vec![Vec::new(); pattern.nodes.len()];
+        let mut max_hops = 1;
+
+        for edge in &pattern.edges {
+            out_edges[edge.source].push((edge.target, edge.relationship_type.clone(), edge.min_hops, edge.max_hops));
+            in_edges[edge.target].push((edge.source, edge.relationship_type.clone(), edge.min_hops, edge.max_hops));
+            max_hops = max_hops.max(edge.max_hops);
+        }
+
+        Self { out_edges, in_edges, max_hops }
+    }
+}
+
+/// VF2's feasibility test for extending the current mapping with `(n, m)`:
+/// every pattern edge between `n` and an already-mapped pattern neighbor
+/// must have a matching data connection between `m` and that neighbor's
+/// image - a direct edge for a `(1, 1)` `EdgePattern`, a bounded-hop path
+/// otherwise - and `m`'s own direct in/out degree must be at least `n`'s
+/// count of direct edges (hop-range edges don't bound direct degree, so
+/// they're excluded from this prune) - a node that already has too few
+/// direct edges can never satisfy every direct pattern edge `n` requires
+/// once the mapping completes.
+fn vf2_feasible(
+    n: usize,
+    m: usize,
+    mapping: &[Option<usize>],
+    data_adj: &DataAdjacency,
+    query_adj: &QueryAdjacency,
+) -> bool {
+    for (n_prime, relation, min_hops, max_hops) in &query_adj.out_edges[n] {
+        if let Some(m_prime) = mapping[*n_prime] {
+            if !data_adj.satisfies(m, m_prime, relation, *min_hops, *max_hops) {
+                return false;
+            }
+        }
+    }
+    for (n_prime, relation, min_hops, max_hops) in &query_adj.in_edges[n] {
+        if let Some(m_prime) = mapping[*n_prime] {
+            if !data_adj.satisfies(m_prime, m, relation, *min_hops, *max_hops) {
+                return false;
+            }
+        }
+    }
+
+    let direct_out = query_adj.out_edges[n].iter().filter(|(_, _, min_h, max_h)| *min_h <= 1 && *max_h <= 1).count();
+    let direct_in = query_adj.in_edges[n].iter().filter(|(_, _, min_h, max_h)| *min_h <= 1 && *max_h <= 1).count();
+
+    data_adj.out_edges[m].len() >= direct_out && data_adj.in_edges[m].len() >= direct_in
+}
+
+/// Candidate data nodes for pattern vertex `depth`: the data frontier
+/// reachable from/into everything already mapped (within `max_hops` steps,
+/// to accommodate the widest `EdgePattern` hop range in the pattern), or
+/// every unmapped data node when the frontier is empty (always true at
+/// `depth == 0`, since nothing is mapped yet). Shared by `vf2_recurse` and
+/// `PatternMatchIter` so the two searches explore identical candidate sets
+/// in identical order.
+fn vf2_candidates(
+    depth: usize,
+    data_node_count: usize,
+    mapping: &[Option<usize>],
+    used: &[bool],
+    data_adj: &DataAdjacency,
+    max_hops: usize,
+) -> Vec<usize> {
+    if depth == 0 {
+        return (0..data_node_count).filter(|&m| !used[m]).collect();
+    }
+
+    let mapped_data: Vec<usize> = mapping[..depth].iter().map(|m| m.unwrap()).collect();
+    let (tout, tin) = data_adj.frontiers(&mapped_data, used, max_hops);
+    if tout.is_empty() && tin.is_empty() {
+        (0..data_node_count).filter(|&m| !used[m]).collect()
+    } else {
+        tout.union(&tin).copied().collect()
+    }
+}
+
+/// Every hop-range `EdgePattern`'s resolved intermediate path nodes for a
+/// just-completed mapping, in `pattern.edges` order - `PatternMatch::path_nodes`.
+/// Direct `(1, 1)` edges contribute nothing, since their two endpoints are
+/// already the whole story.
+fn resolve_path_nodes(
+    pattern: &GraphPattern,
+    nodes: &[KGNode],
+    mapping: &[Option<usize>],
+    data_adj: &DataAdjacency,
+) -> Vec<KGNode> {
+    let mut path_nodes = Vec::new();
+    for edge in &pattern.edges {
+        if edge.is_direct() {
+            continue;
+        }
+        let (Some(from), Some(to)) = (mapping[edge.source], mapping[edge.target]) else {
+            continue;
+        };
+        if let Some(path) = data_adj.bounded_path(from, to, edge.min_hops, edge.max_hops, &edge.relationship_type) {
+            path_nodes.extend(path[1..path.len() - 1].iter().map(|&idx| nodes[idx].clone()));
+        }
+    }
+    path_nodes
+}
+
+/// One open level of `PatternMatchIter`'s explicit DFS stack: `candidates`
+/// is this depth's remaining pool to try, and `current` is the data node
+/// presently mapped to pattern vertex `depth` (if any), so the next `next()`
+/// call knows what to unmap before trying the next candidate.
+struct SearchFrame {
+    depth: usize,
+    candidates: std::vec::IntoIter<usize>,
+    current: Option<usize>,
+}
+
+/// Lazy VF2 search: yields each complete `PatternMatch` as it's found,
+/// backtracking by popping `stack` rather than unwinding a recursive call.
+/// See `GraphQueryEngine::pattern_match_iter`.
+pub struct PatternMatchIter<'a> {
+    engine: &'a GraphQueryEngine,
+    pattern: &'a GraphPattern,
+    nodes: &'a [KGNode],
+    data_adj: DataAdjacency,
+    query_adj: QueryAdjacency,
+    mapping: Vec<Option<usize>>,
+    used: Vec<bool>,
+    /// `node_scores[depth]` is the `node_match_score` the frame at `depth`
+    /// accepted - read back at completion to compute `PatternMatch::confidence`.
+    node_scores: Vec<f32>,
+    stack: Vec<SearchFrame>,
+}
+
+impl<'a> PatternMatchIter<'a> {
+    fn new(engine: &'a GraphQueryEngine, pattern: &'a GraphPattern, nodes: &'a [KGNode], edges: &'a [KGEdge]) -> Self {
+        let data_adj = DataAdjacency::build(nodes, edges);
+        let query_adj = QueryAdjacency::build(pattern);
+        let mapping = vec![None; pattern.nodes.len()];
+        let used = vec![false; nodes.len()];
+        let node_scores = vec![0.0f32; pattern.nodes.len()];
+
+        let stack = if pattern.nodes.is_empty() {
+            Vec::new()
+        } else {
+            let candidates = vf2_candidates(0, nodes.len(), &mapping, &used, &data_adj, query_adj.max_hops);
+            vec![SearchFrame { depth: 0, candidates: candidates.into_iter(), current: None }]
+        };
+
+        Self { engine, pattern, nodes, data_adj, query_adj, mapping, used, node_scores, stack }
+    }
+}
+
+impl<'a> Iterator for PatternMatchIter<'a> {
+    type Item = PatternMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.stack.last()?.depth;
+
+            if let Some(prev) = self.stack.last_mut().unwrap().current.take() {
+                self.mapping[depth] = None;
+                self.used[prev] = false;
+            }
+
+            let mut accepted = None;
+            while let Some(m) = self.stack.last_mut().unwrap().candidates.next() {
+                if self.used[m] {
+                    continue;
+                }
+                let Some(score) = self.engine.node_match_score(&self.nodes[m], &self.pattern.nodes[depth], self.pattern.wildcard_weight) else {
+                    continue;
+                };
+                if !vf2_feasible(depth, m, &self.mapping, &self.data_adj, &self.query_adj) {
+                    continue;
+                }
+                accepted = Some((m, score));
+                break;
+            }
+
+            let Some((m, score)) = accepted else {
+                self.stack.pop();
+                continue;
+            };
+
+            self.mapping[depth] = Some(m);
+            self.used[m] = true;
+            self.node_scores[depth] = score;
+            self.stack.last_mut().unwrap().current = Some(m);
+
+            if depth + 1 == self.pattern.nodes.len() {
+                let matched_nodes = self.mapping.iter().map(|idx| self.nodes[idx.unwrap()].clone()).collect();
+                let confidence = match_confidence(self.pattern, &self.node_scores);
+                let path_nodes = resolve_path_nodes(self.pattern, self.nodes, &self.mapping, &self.data_adj);
+                return Some(PatternMatch { nodes: matched_nodes, confidence, path_nodes });
+            }
+
+            let next_candidates =
+                vf2_candidates(depth + 1, self.nodes.len(), &self.mapping, &self.used, &self.data_adj, self.query_adj.max_hops);
+            self.stack.push(SearchFrame { depth: depth + 1, candidates: next_candidates.into_iter(), current: None });
+        }
+    }
+
+    /// A generous (not tight) upper bound: the number of ways to place the
+    /// data nodes not yet ruled out into the pattern positions the
+    /// bottommost open frame hasn't committed to, i.e. `nPr` over the
+    /// currently-unused data nodes - most of these permutations will fail
+    /// `node_match_score`/`vf2_feasible` long before completion, but
+    /// it's cheap to compute and lets a caller decide whether `take(k)` is
+    /// even worth trying without enumerating anything.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let Some(bottom) = self.stack.first() else {
+            return (0, Some(0));
+        };
+
+        let unused = self.used.iter().filter(|&&u| !u).count();
+        let remaining_slots = self.pattern.nodes.len() - bottom.depth;
+        let upper = (0..remaining_slots).try_fold(1usize, |acc, i| acc.checked_mul(unused.saturating_sub(i)));
+
+        (0, upper)
+    }
+}
\ No newline at end of file
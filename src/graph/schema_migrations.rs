@@ -0,0 +1,474 @@
+//! Versioned schema migrations for `GraphStorage`, driven by SQLite's
+//! built-in `PRAGMA user_version` rather than a separate tracking table —
+//! the same approach zcash-sync and the nostr-sdk sqlite backend use to
+//! roll an embedded database's schema forward in place. Each migration is
+//! a step (its target `user_version`, plus a closure run against an open
+//! `Transaction`); `run_migrations` reads the stored version, applies
+//! every step beyond it inside one transaction, and bumps `user_version`
+//! after each step. A failure partway through rolls the whole transaction
+//! back, so the database keeps the schema and `user_version` it had before
+//! the call, steps that succeeded earlier in the call included.
+//!
+//! This is deliberately separate from `migration::schema_migrations`,
+//! which drives the standalone `kg-migrate` CLI's explicit up/down
+//! directory-based migrations against an already-running database. This
+//! module instead runs automatically, once, every time `GraphStorage`
+//! opens a database file — there is no "down" here, since rolling an
+//! embedded schema backward in place isn't something this server ever
+//! needs to do on its own.
+
+use anyhow::{bail, Result};
+use rusqlite::{Connection, Transaction};
+
+/// One schema migration: `id` is its target `user_version` (steps must be
+/// listed in ascending, gapless order starting at 1), `apply` runs the DDL
+/// that takes the schema from `id - 1` to `id`.
+pub struct Migration {
+    pub id: i64,
+    pub description: &'static str,
+    pub apply: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// The ordered migration steps. Migration 1 is the original baseline
+/// schema — every `CREATE TABLE IF NOT EXISTS`/`CREATE INDEX IF NOT
+/// EXISTS`/trigger that used to be issued unconditionally by the old
+/// `initialize_schema` on every open. Anything that evolves the schema
+/// from here on (`ALTER TABLE ... ADD COLUMN`, a new index, a new virtual
+/// table) is appended as a new step instead of being folded back into
+/// step 1, so existing databases upgrade in place instead of losing data.
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration { id: 1, description: "baseline schema", apply: migration_001_baseline },
+        Migration { id: 2, description: "aliases table for GC pinned roots", apply: migration_002_aliases },
+        Migration { id: 3, description: "node/edge revision history", apply: migration_003_revisions },
+        Migration { id: 4, description: "causal-context siblings for multi-writer sync", apply: migration_004_causality },
+        Migration { id: 5, description: "episode chunks for oversized-document embedding", apply: migration_005_episode_chunks },
+        Migration { id: 6, description: "content hashes for migration validation", apply: migration_006_content_hashes },
+    ]
+}
+
+fn migration_001_baseline(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS nodes (
+            uuid TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            node_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}'
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS edges (
+            uuid TEXT PRIMARY KEY,
+            source_node_uuid TEXT NOT NULL,
+            target_node_uuid TEXT NOT NULL,
+            relation_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            weight REAL NOT NULL DEFAULT 1.0,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}',
+            FOREIGN KEY (source_node_uuid) REFERENCES nodes (uuid),
+            FOREIGN KEY (target_node_uuid) REFERENCES nodes (uuid)
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS episodes (
+            uuid TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            source TEXT NOT NULL,
+            source_description TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}'
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS embeddings (
+            uuid TEXT PRIMARY KEY,
+            entity_type TEXT NOT NULL, -- 'node', 'edge', 'episode'
+            embedding BLOB NOT NULL,
+            dimensions INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (uuid) REFERENCES nodes (uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS episode_entities (
+            episode_uuid TEXT NOT NULL,
+            entity_uuid TEXT NOT NULL,
+            entity_type TEXT NOT NULL, -- 'node' or 'edge'
+            PRIMARY KEY (episode_uuid, entity_uuid),
+            FOREIGN KEY (episode_uuid) REFERENCES episodes (uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(
+            uuid UNINDEXED,
+            name,
+            summary,
+            content='nodes',
+            content_rowid='rowid'
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS episodes_fts USING fts5(
+            uuid UNINDEXED,
+            name,
+            content,
+            content='episodes',
+            content_rowid='rowid'
+        )",
+        [],
+    )?;
+
+    // `api_keys` table for the HTTP/SSE transport's scoped authentication
+    // (see `security::api_keys`). Only the SHA-256 hash of a key is ever
+    // persisted.
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS api_keys (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            key_hash TEXT NOT NULL UNIQUE,
+            scopes TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            revoked INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // `stream_checkpoints` table tracking the last successfully stored
+    // offset per stream for `indexing::streaming::StreamIngester`. Offsets
+    // only ever move forward (enforced in `commit_stream_checkpoint`'s
+    // upsert), so a stream's progress survives process restart and a
+    // checkpoint never regresses.
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS stream_checkpoints (
+            stream_id TEXT PRIMARY KEY,
+            offset INTEGER NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // `retention_policies` table for `manage_graph`'s
+    // `set_retention`/`compact` operations. `group_id` is the literal
+    // `"ungrouped"` sentinel (matching `episode_counts_by_group`'s
+    // convention) for the policy covering episodes with a NULL
+    // `group_id`, rather than SQL NULL itself, since SQLite treats
+    // distinct NULLs in a PRIMARY KEY as non-conflicting and would
+    // happily accept more than one "ungrouped" policy row.
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS retention_policies (
+            group_id TEXT PRIMARY KEY,
+            max_age_days INTEGER,
+            max_episodes INTEGER,
+            preserve_entities INTEGER NOT NULL DEFAULT 1,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_nodes_group_id ON nodes (group_id)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_nodes_type ON nodes (node_type)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_edges_source ON edges (source_node_uuid)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_edges_target ON edges (target_node_uuid)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_edges_group_id ON edges (group_id)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_episodes_group_id ON episodes (group_id)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_episodes_created_at ON episodes (created_at)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_embeddings_type ON embeddings (entity_type)", [])?;
+
+    tx.execute(
+        "CREATE TRIGGER IF NOT EXISTS nodes_fts_insert AFTER INSERT ON nodes
+        BEGIN
+            INSERT INTO nodes_fts(uuid, name, summary) VALUES (new.uuid, new.name, new.summary);
+        END",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TRIGGER IF NOT EXISTS nodes_fts_update AFTER UPDATE ON nodes
+        BEGIN
+            UPDATE nodes_fts SET name = new.name, summary = new.summary WHERE uuid = new.uuid;
+        END",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TRIGGER IF NOT EXISTS nodes_fts_delete AFTER DELETE ON nodes
+        BEGIN
+            DELETE FROM nodes_fts WHERE uuid = old.uuid;
+        END",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TRIGGER IF NOT EXISTS episodes_fts_insert AFTER INSERT ON episodes
+        BEGIN
+            INSERT INTO episodes_fts(uuid, name, content) VALUES (new.uuid, new.name, new.content);
+        END",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TRIGGER IF NOT EXISTS episodes_fts_update AFTER UPDATE ON episodes
+        BEGIN
+            UPDATE episodes_fts SET name = new.name, content = new.content WHERE uuid = new.uuid;
+        END",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TRIGGER IF NOT EXISTS episodes_fts_delete AFTER DELETE ON episodes
+        BEGIN
+            DELETE FROM episodes_fts WHERE uuid = old.uuid;
+        END",
+        [],
+    )?;
+
+    Ok(())
+}
+
+// `aliases` table of pinned GC roots (see `GraphStorage::gc`): either a
+// specific node/edge/episode UUID or a whole `group_id`, that the GC's
+// mark phase must always treat as reachable regardless of what the
+// episode graph currently references.
+fn migration_002_aliases(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS aliases (
+            kind TEXT NOT NULL CHECK (kind IN ('uuid', 'group_id')),
+            value TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (kind, value)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// `node_revisions`/`edge_revisions` hold the full prior column set of a
+// node/edge every time `insert_node`/`insert_edge` overwrites an existing
+// row, keyed by `(uuid, revision_seq)` so a uuid's history orders cleanly.
+// `edit_id` groups whichever other rows changed as part of the same
+// logical edit (currently always one row, since nothing batches
+// cross-table edits yet); `changed_at` is when this revision stopped being
+// current — i.e. a row is the live state of its uuid from its own
+// `updated_at` until the next revision's `changed_at` supersedes it. See
+// `GraphStorage::get_node_as_of`/`get_node_history`/`revert_node`.
+fn migration_003_revisions(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS node_revisions (
+            uuid TEXT NOT NULL,
+            revision_seq INTEGER NOT NULL,
+            edit_id TEXT NOT NULL,
+            changed_at TEXT NOT NULL,
+            name TEXT NOT NULL,
+            node_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}',
+            PRIMARY KEY (uuid, revision_seq)
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS edge_revisions (
+            uuid TEXT NOT NULL,
+            revision_seq INTEGER NOT NULL,
+            edit_id TEXT NOT NULL,
+            changed_at TEXT NOT NULL,
+            source_node_uuid TEXT NOT NULL,
+            target_node_uuid TEXT NOT NULL,
+            relation_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            weight REAL NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}',
+            PRIMARY KEY (uuid, revision_seq)
+        )",
+        [],
+    )?;
+
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_node_revisions_changed_at ON node_revisions (uuid, changed_at)", [])?;
+    tx.execute("CREATE INDEX IF NOT EXISTS idx_edge_revisions_changed_at ON edge_revisions (uuid, changed_at)", [])?;
+
+    Ok(())
+}
+
+// Adds a `causality` version-vector column to `nodes`/`edges` (a JSON map of
+// `writer_id -> counter`, following the causal-context technique K2V uses)
+// plus `node_siblings`/`edge_siblings` tables holding concurrent versions
+// that a causal write couldn't order against what was already stored. A
+// sibling row carries the same column set as its parent table plus its own
+// `causality`, keyed by `(uuid, sibling_id)` so a uuid can hold more than
+// one unresolved concurrent version at once. See
+// `GraphStorage::insert_node_with_context`/`get_node_siblings`/
+// `resolve_node`.
+fn migration_004_causality(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute("ALTER TABLE nodes ADD COLUMN causality TEXT NOT NULL DEFAULT '{}'", [])?;
+    tx.execute("ALTER TABLE edges ADD COLUMN causality TEXT NOT NULL DEFAULT '{}'", [])?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS node_siblings (
+            uuid TEXT NOT NULL,
+            sibling_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            node_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}',
+            causality TEXT NOT NULL DEFAULT '{}',
+            PRIMARY KEY (uuid, sibling_id)
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS edge_siblings (
+            uuid TEXT NOT NULL,
+            sibling_id TEXT NOT NULL,
+            source_node_uuid TEXT NOT NULL,
+            target_node_uuid TEXT NOT NULL,
+            relation_type TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            weight REAL NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            group_id TEXT,
+            metadata TEXT DEFAULT '{}',
+            causality TEXT NOT NULL DEFAULT '{}',
+            PRIMARY KEY (uuid, sibling_id)
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Episode bodies too long to embed as a single vector without quality loss
+/// (see `embeddings::chunking::TextChunker`) get split into ordered,
+/// overlapping chunks, each embedded and stored separately in `embeddings`
+/// under `entity_type = 'episode_chunk'` keyed by the chunk's own
+/// deterministic uuid (see `GraphStorage::episode_chunk_uuid`). This table
+/// maps that chunk uuid back to its parent episode and position so a
+/// chunk-level vector hit can be rolled up to the whole episode it came
+/// from. Deleting the parent episode cascades to its chunks the same way
+/// `episode_entities` does.
+fn migration_005_episode_chunks(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS episode_chunks (
+            uuid TEXT PRIMARY KEY,
+            episode_uuid TEXT NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            start_byte INTEGER NOT NULL,
+            end_byte INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            UNIQUE (episode_uuid, chunk_index),
+            FOREIGN KEY (episode_uuid) REFERENCES episodes (uuid) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS idx_episode_chunks_episode ON episode_chunks (episode_uuid)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Content hashes recorded by a `Migrator` for every node/edge/episode it
+/// converts, keyed by the *source* uuid rather than the row's own uuid in
+/// `nodes`/`edges`/`episodes` - they're usually the same uuid, but keeping
+/// this keyed off what the source system called it is what lets
+/// `GraphitiMigrator::validate` look a record up by the id it remembers
+/// from the source side. `record_type` disambiguates a uuid colliding
+/// across the three tables (vanishingly unlikely, but a `node`/`edge`/
+/// `episode` uuid are otherwise interchangeable strings here).
+fn migration_006_content_hashes(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS migration_content_hashes (
+            source_uuid TEXT NOT NULL,
+            record_type TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            recorded_at TEXT NOT NULL,
+            PRIMARY KEY (source_uuid, record_type)
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Reads `PRAGMA user_version` and returns the `(id, description)` of every
+/// migration step beyond it, in order, without applying anything — the
+/// read-only counterpart to `run_migrations`, for `kg-migrate repair
+/// --dry-run` to report what a migration pass would do.
+pub fn pending(conn: &Connection) -> Result<Vec<(i64, &'static str)>> {
+    let current_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(migrations()
+        .into_iter()
+        .filter(|m| m.id > current_version)
+        .map(|m| (m.id, m.description))
+        .collect())
+}
+
+/// Reads `PRAGMA user_version`, applies every migration step beyond it (in
+/// order) inside one transaction, and bumps `user_version` to the highest
+/// step applied. Errors without touching the database if `user_version` is
+/// already ahead of every known step — i.e. the database was created (or
+/// previously upgraded) by a newer binary than this one, and rolling its
+/// schema back isn't something this module supports.
+pub fn run_migrations(conn: &mut Connection) -> Result<()> {
+    let steps = migrations();
+    let latest = steps.iter().map(|m| m.id).max().unwrap_or(0);
+
+    let current_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if current_version > latest {
+        bail!(
+            "Database schema version {} is newer than this binary supports (up to {}); refusing to open it to avoid data loss. Upgrade the binary first.",
+            current_version,
+            latest
+        );
+    }
+
+    if current_version == latest {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    for migration in steps.iter().filter(|m| m.id > current_version) {
+        (migration.apply)(&tx).map_err(|e| {
+            anyhow::anyhow!("Migration {} ({}) failed: {}", migration.id, migration.description, e)
+        })?;
+        tx.pragma_update(None, "user_version", migration.id)?;
+    }
+    tx.commit()?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! `rusqlite::types::FromSql`/`ToSql` newtypes for the two value shapes
+//! every row mapper in `storage.rs` parses by hand: a UUID stored as TEXT,
+//! and an RFC 3339 timestamp stored as TEXT. Centralizing the fallible
+//! parsing here means `row.get::<_, KgUuid>("uuid")?` and
+//! `row.get::<_, KgTime>("created_at")?` replace the repeated
+//! `Uuid::parse_str(...).map_err(|e| FromSqlConversionFailure(...))` /
+//! `DateTime::parse_from_rfc3339(...).with_timezone(&Utc)` pairs, and the
+//! matching `ToSql` impls let an `INSERT`'s `params![...]` take a `KgUuid`/
+//! `KgTime` directly instead of pre-formatting with `.to_string()`/
+//! `.to_rfc3339()`.
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use uuid::Uuid;
+
+/// A `Uuid` stored as its hyphenated string form, matching every `TEXT
+/// PRIMARY KEY`/`TEXT`-typed uuid column in the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KgUuid(pub Uuid);
+
+impl From<Uuid> for KgUuid {
+    fn from(uuid: Uuid) -> Self {
+        KgUuid(uuid)
+    }
+}
+
+impl From<KgUuid> for Uuid {
+    fn from(wrapped: KgUuid) -> Self {
+        wrapped.0
+    }
+}
+
+impl FromSql for KgUuid {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        Uuid::parse_str(text)
+            .map(KgUuid)
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl ToSql for KgUuid {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0.to_string()))
+    }
+}
+
+/// A `DateTime<Utc>` stored as an RFC 3339 string, matching every
+/// `created_at`/`updated_at`/`changed_at` column in the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KgTime(pub DateTime<Utc>);
+
+impl From<DateTime<Utc>> for KgTime {
+    fn from(time: DateTime<Utc>) -> Self {
+        KgTime(time)
+    }
+}
+
+impl From<KgTime> for DateTime<Utc> {
+    fn from(wrapped: KgTime) -> Self {
+        wrapped.0
+    }
+}
+
+impl FromSql for KgTime {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        DateTime::parse_from_rfc3339(text)
+            .map(|dt| KgTime(dt.with_timezone(&Utc)))
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+impl ToSql for KgTime {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0.to_rfc3339()))
+    }
+}
@@ -1,21 +1,272 @@
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, OpenFlags, Row, OptionalExtension};
+use rusqlite::{params, Connection, OpenFlags, Row, OptionalExtension, Transaction};
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 use super::{Episode, KGEdge, KGNode, SearchResult};
+use super::filters::{EdgeFilter, EpisodeFilter, NodeFilter};
+use super::hnsw::HnswConfig;
+use super::sql_types::{KgTime, KgUuid};
 use crate::config::DatabaseConfig;
 
+/// Row reads, row writes, and bytes touched for one logical `GraphStorage`
+/// operation (e.g. `insert_node`, `search_nodes_by_text`). Opt-in: counters
+/// only move when `record_io` is called, so instrumentation costs nothing
+/// for callers who never look at `drain_io_stats`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IoOpStats {
+    pub reads: u64,
+    pub writes: u64,
+    pub bytes: u64,
+}
+
+/// Total node/edge/episode counts returned by `GraphStorage::graph_counts`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GraphCounts {
+    pub nodes: usize,
+    pub edges: usize,
+    pub episodes: usize,
+}
+
+/// A per-group episode retention policy, as stored in `retention_policies`.
+/// `group_id` is the literal `"ungrouped"` sentinel for episodes with no
+/// `group_id`, matching `episode_counts_by_group`'s convention.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    pub group_id: String,
+    pub max_age_days: Option<i64>,
+    pub max_episodes: Option<i64>,
+    pub preserve_entities: bool,
+    pub updated_at: String,
+}
+
+/// What `GraphStorage::apply_retention_policy` actually did, reported back
+/// up to the `manage_graph` caller.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PruneResult {
+    pub episodes_pruned: usize,
+    pub bytes_reclaimed: u64,
+    pub nodes_gc: usize,
+    pub edges_gc: usize,
+}
+
+/// What `GraphStorage::compact_episodes` did to one batch.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CompactResult {
+    pub episodes_merged: usize,
+    pub bytes_reclaimed: u64,
+}
+
+/// What kind of value an `aliases` row pins: a specific entity UUID, or an
+/// entire `group_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasKind {
+    Uuid,
+    GroupId,
+}
+
+impl AliasKind {
+    pub fn as_db_str(self) -> &'static str {
+        match self {
+            AliasKind::Uuid => "uuid",
+            AliasKind::GroupId => "group_id",
+        }
+    }
+
+    fn from_db_str(s: &str) -> Self {
+        match s {
+            "group_id" => AliasKind::GroupId,
+            _ => AliasKind::Uuid,
+        }
+    }
+}
+
+/// Byte-budget knobs for `GraphStorage::gc`. Currently just a `VACUUM`
+/// trigger, but kept as its own struct (rather than a bare `Option<u64>`)
+/// so more size-driven knobs can land later without another signature
+/// change.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SizeTargets {
+    /// Run `VACUUM` after a GC pass if the database file is still at
+    /// least this many bytes afterward.
+    pub max_bytes: Option<u64>,
+}
+
+/// What `GraphStorage::gc` did.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GcStats {
+    pub nodes_deleted: usize,
+    pub edges_deleted: usize,
+    pub bytes_reclaimed: u64,
+    pub vacuumed: bool,
+}
+
+/// What `GraphStorage::repair_integrity` found and (unless `dry_run`) fixed.
+#[derive(Debug, Clone, Copy)]
+pub struct RepairReport {
+    /// Edges referencing a source/target node that no longer exists.
+    pub dangling_edges_removed: usize,
+    /// Whether the in-memory HNSW embedding index was rebuilt from the
+    /// `embeddings` table. Always `false` in dry-run mode.
+    pub embedding_index_rebuilt: bool,
+    /// Node/edge/episode totals after the pass (or, in dry-run mode, as
+    /// they stood going in — `repair_integrity` makes no changes to repeal).
+    pub counts: GraphCounts,
+    pub dry_run: bool,
+}
+
+impl RepairReport {
+    /// Whether the pass found nothing to fix.
+    pub fn healthy(&self) -> bool {
+        self.dangling_edges_removed == 0
+    }
+}
+
+/// A causal context: a version vector mapping writer id to the highest
+/// counter that writer has contributed, following the causal-context
+/// technique Garage's K2V uses for optimistic multi-writer concurrency.
+/// Stored as JSON in `nodes.causality`/`edges.causality`. See
+/// `GraphStorage::insert_node_with_context`.
+pub type CausalContext = HashMap<String, u64>;
+
+/// Whether `incoming` has observed everything `stored` has recorded — every
+/// writer's counter in `stored` is matched or exceeded in `incoming`. If so,
+/// a write made with `incoming` as its observed context can safely replace
+/// `stored`: its author had already seen the full history being
+/// overwritten. If not, the two are concurrent — something changed after
+/// this writer's last observation — and both values must be kept as
+/// siblings rather than one overwriting the other.
+fn causal_dominates(incoming: &CausalContext, stored: &CausalContext) -> bool {
+    stored.iter().all(|(writer, &counter)| incoming.get(writer).copied().unwrap_or(0) >= counter)
+}
+
+/// Element-wise max of two causal contexts, plus one further increment for
+/// `writer_id` — the merged context a non-conflicting
+/// `insert_node_with_context`/`insert_edge_with_context` write, or a
+/// `resolve_node`/`resolve_edge`, leaves behind.
+fn merge_causal_context(a: &CausalContext, b: &CausalContext, writer_id: &str) -> CausalContext {
+    let mut merged = a.clone();
+    for (writer, &counter) in b {
+        let entry = merged.entry(writer.clone()).or_insert(0);
+        *entry = (*entry).max(counter);
+    }
+    *merged.entry(writer_id.to_string()).or_insert(0) += 1;
+    merged
+}
+
+/// Per-connection SQLite pragmas applied on top of the shared
+/// journal-mode/cache-size settings `GraphStorage::new` derives from
+/// `DatabaseConfig`, the same split upend draws between "open this
+/// database" and "tune this connection". `busy_timeout_ms` matters most
+/// for the read pool: under WAL, a reader only ever blocks behind the
+/// writer's checkpoint, and without a timeout that momentary contention
+/// surfaces as a hard `SQLITE_BUSY` error instead of a short wait.
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionOptions {
+    pub busy_timeout_ms: u64,
+    pub synchronous: SynchronousMode,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            busy_timeout_ms: 5000,
+            synchronous: SynchronousMode::Normal,
+        }
+    }
+}
+
+/// Mirrors SQLite's `PRAGMA synchronous` levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SynchronousMode {
+    Off,
+    Normal,
+    Full,
+}
+
+impl SynchronousMode {
+    fn as_pragma_str(self) -> &'static str {
+        match self {
+            SynchronousMode::Off => "OFF",
+            SynchronousMode::Normal => "NORMAL",
+            SynchronousMode::Full => "FULL",
+        }
+    }
+}
+
+/// A small round-robin pool of read-only connections, following the
+/// deadpool-sqlite approach the nostr sqlite backend takes: every reader
+/// is opened once up front (`SQLITE_OPEN_READ_ONLY`, still `NO_MUTEX`
+/// since each is only ever touched through its own `Mutex`) and handed
+/// out in rotation, so concurrent `get_*`/`search_*`/`count_*` calls stop
+/// serializing behind the single connection the writer also uses — WAL
+/// mode already allows this; nothing but the old single-`Mutex` wrapper
+/// was stopping it.
+///
+/// This is the same "separate read connections from the write connection,
+/// customize pragmas per checkout" shape an r2d2 + r2d2_sqlite pool would
+/// give via `r2d2::Pool<SqliteConnectionManager>` and a `CustomizeConnection`
+/// hook — `with_connection_options`/`configure_connection` already play
+/// that customization role for every reader this struct hands out. Kept as
+/// a bespoke pool rather than pulling in r2d2 because the pool here never
+/// needs to grow/shrink or block a checkout: `GraphStorage::new` sizes it
+/// once from `DatabaseConfig::connection_pool_size` and every reader lives
+/// for the store's whole lifetime.
+struct ReadPool {
+    conns: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+}
+
+impl ReadPool {
+    fn acquire(&self) -> MutexGuard<'_, Connection> {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.conns.len();
+        self.conns[idx].lock().unwrap()
+    }
+}
+
 #[derive(Clone)]
 pub struct GraphStorage {
-    conn: Arc<Mutex<Connection>>,
+    writer: Arc<Mutex<Connection>>,
+    readers: Arc<ReadPool>,
+    io_stats: Arc<Mutex<HashMap<String, IoOpStats>>>,
+    /// Approximate nearest-neighbor index over `embeddings` rows with
+    /// `entity_type = 'node'`, kept in sync incrementally by
+    /// `store_embedding`/`delete_node` rather than rebuilt per query. It's
+    /// in-memory only — rebuilt from the `embeddings` table on `new`/
+    /// `with_connection_options`, not serialized to its own file — since
+    /// this tree has no dependency manifest to add a serialization format
+    /// with. See `hnsw` module docs for why this exists instead of reusing
+    /// `search::vector_search::VectorSearchEngine`'s VP-tree.
+    hnsw: std::sync::RwLock<super::hnsw::HnswIndex>,
+    /// Grown by `set_change_notifier` (one registration per MCP connection
+    /// that wires up `resources/subscribe`) to learn about every node/edge
+    /// mutation as a `"kg://node/{uuid}"` / `"kg://edge/{uuid}"` URI,
+    /// regardless of which of the several insert/revert/resolve/delete
+    /// entry points below performed it. Empty until the first subscriber
+    /// registers, so a store with no MCP listener attached pays only the
+    /// cost of an uncontended lock check per mutation. Closed senders (their
+    /// connection's `McpProtocol` has been dropped) are pruned the next time
+    /// `notify_change` tries to use them.
+    change_notifier: Arc<Mutex<Vec<tokio::sync::mpsc::UnboundedSender<String>>>>,
 }
 
 impl GraphStorage {
     pub fn new(db_path: &Path, config: &DatabaseConfig) -> Result<Self> {
-        let conn = Connection::open_with_flags(
+        Self::with_connection_options(db_path, config, ConnectionOptions::default())
+    }
+
+    /// Same as `new`, but with explicit control over the pragmas applied
+    /// to every connection this store opens (see `ConnectionOptions`).
+    pub fn with_connection_options(
+        db_path: &Path,
+        config: &DatabaseConfig,
+        options: ConnectionOptions,
+    ) -> Result<Self> {
+        let writer = Connection::open_with_flags(
             db_path,
             OpenFlags::SQLITE_OPEN_READ_WRITE
                 | OpenFlags::SQLITE_OPEN_CREATE
@@ -24,265 +275,765 @@ impl GraphStorage {
         )
         .with_context(|| format!("Failed to open database: {}", db_path.display()))?;
 
-        // Configure SQLite for performance
-        conn.execute_batch(&format!(
-            "
-            PRAGMA foreign_keys = ON;
-            PRAGMA journal_mode = {};
-            PRAGMA synchronous = NORMAL;
-            PRAGMA cache_size = -{};
-            PRAGMA temp_store = memory;
-            PRAGMA mmap_size = 268435456;
-            ",
-            if config.enable_wal { "WAL" } else { "DELETE" },
-            config.cache_size_kb
-        ))?;
+        Self::configure_connection(&writer, config, &options, true)?;
+
+        let mut writer = writer;
+        super::schema_migrations::run_migrations(&mut writer)
+            .with_context(|| format!("Failed to migrate schema for database: {}", db_path.display()))?;
+
+        let pool_size = config.connection_pool_size.max(1) as usize;
+        let mut readers = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            let reader = Connection::open_with_flags(
+                db_path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY
+                    | OpenFlags::SQLITE_OPEN_URI
+                    | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )
+            .with_context(|| format!("Failed to open read connection: {}", db_path.display()))?;
+            Self::configure_connection(&reader, config, &options, false)?;
+            readers.push(Mutex::new(reader));
+        }
 
         let storage = Self {
-            conn: Arc::new(Mutex::new(conn)),
+            writer: Arc::new(Mutex::new(writer)),
+            readers: Arc::new(ReadPool { conns: readers, next: AtomicUsize::new(0) }),
+            io_stats: Arc::new(Mutex::new(HashMap::new())),
+            hnsw: std::sync::RwLock::new(super::hnsw::HnswIndex::new(super::hnsw::HnswConfig::default())),
+            change_notifier: Arc::new(Mutex::new(Vec::new())),
         };
+        storage.rebuild_hnsw_index(super::hnsw::HnswConfig::default())
+            .with_context(|| format!("Failed to build node embedding index for database: {}", db_path.display()))?;
 
-        storage.initialize_schema()?;
         Ok(storage)
     }
 
-    fn initialize_schema(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-
-        // Create nodes table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS nodes (
-                uuid TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                node_type TEXT NOT NULL,
-                summary TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL,
-                group_id TEXT,
-                metadata TEXT DEFAULT '{}'
-            )",
-            [],
-        )?;
-
-        // Create edges table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS edges (
-                uuid TEXT PRIMARY KEY,
-                source_node_uuid TEXT NOT NULL,
-                target_node_uuid TEXT NOT NULL,
-                relation_type TEXT NOT NULL,
-                summary TEXT NOT NULL,
-                weight REAL NOT NULL DEFAULT 1.0,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL,
-                group_id TEXT,
-                metadata TEXT DEFAULT '{}',
-                FOREIGN KEY (source_node_uuid) REFERENCES nodes (uuid),
-                FOREIGN KEY (target_node_uuid) REFERENCES nodes (uuid)
-            )",
-            [],
-        )?;
-
-        // Create episodes table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS episodes (
-                uuid TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                content TEXT NOT NULL,
-                source TEXT NOT NULL,
-                source_description TEXT NOT NULL,
-                created_at TEXT NOT NULL,
-                group_id TEXT,
-                metadata TEXT DEFAULT '{}'
-            )",
-            [],
-        )?;
-
-        // Create embeddings table for vector storage
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS embeddings (
-                uuid TEXT PRIMARY KEY,
-                entity_type TEXT NOT NULL, -- 'node', 'edge', 'episode'
-                embedding BLOB NOT NULL,
-                dimensions INTEGER NOT NULL,
-                created_at TEXT NOT NULL,
-                FOREIGN KEY (uuid) REFERENCES nodes (uuid) ON DELETE CASCADE
-            )",
-            [],
-        )?;
-
-        // Create episode_entities junction table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS episode_entities (
-                episode_uuid TEXT NOT NULL,
-                entity_uuid TEXT NOT NULL,
-                entity_type TEXT NOT NULL, -- 'node' or 'edge'
-                PRIMARY KEY (episode_uuid, entity_uuid),
-                FOREIGN KEY (episode_uuid) REFERENCES episodes (uuid) ON DELETE CASCADE
-            )",
-            [],
-        )?;
-
-        // Create FTS5 virtual table for full-text search
-        conn.execute(
-            "CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(
-                uuid UNINDEXED,
-                name,
-                summary,
-                content='nodes',
-                content_rowid='rowid'
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE VIRTUAL TABLE IF NOT EXISTS episodes_fts USING fts5(
-                uuid UNINDEXED,
-                name,
-                content,
-                content='episodes',
-                content_rowid='rowid'
-            )",
-            [],
-        )?;
+    /// Applies the shared performance pragmas plus `options` to one
+    /// connection. `is_writer` gates `foreign_keys`/`journal_mode`, which a
+    /// read-only connection can't (and doesn't need to) set.
+    fn configure_connection(conn: &Connection, config: &DatabaseConfig, options: &ConnectionOptions, is_writer: bool) -> Result<()> {
+        conn.busy_timeout(std::time::Duration::from_millis(options.busy_timeout_ms))?;
+
+        if is_writer {
+            conn.execute_batch(&format!(
+                "
+                PRAGMA foreign_keys = ON;
+                PRAGMA journal_mode = {};
+                PRAGMA synchronous = {};
+                PRAGMA cache_size = -{};
+                PRAGMA temp_store = memory;
+                PRAGMA mmap_size = 268435456;
+                ",
+                if config.enable_wal { "WAL" } else { "DELETE" },
+                options.synchronous.as_pragma_str(),
+                config.cache_size_kb
+            ))?;
+        } else {
+            conn.execute_batch(&format!(
+                "
+                PRAGMA synchronous = {};
+                PRAGMA cache_size = -{};
+                PRAGMA temp_store = memory;
+                PRAGMA mmap_size = 268435456;
+                ",
+                options.synchronous.as_pragma_str(),
+                config.cache_size_kb
+            ))?;
+        }
 
-        // Create indices for performance
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nodes_group_id ON nodes (group_id)",
-            [],
-        )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nodes_type ON nodes (node_type)",
-            [],
-        )?;
+        Ok(())
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_edges_source ON edges (source_node_uuid)",
-            [],
-        )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_edges_target ON edges (target_node_uuid)",
-            [],
-        )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_edges_group_id ON edges (group_id)",
-            [],
-        )?;
+    /// Registers another channel that `notify_change` pushes mutated
+    /// resource URIs onto. TCP and WebSocket transports can have many
+    /// concurrent connections sharing this same `GraphStorage`, so every
+    /// connection's `assemble()` adds its own sender here rather than
+    /// replacing a single slot; each sender itself fans out to whichever
+    /// resource URIs that connection's clients have actually subscribed to.
+    pub fn set_change_notifier(&self, tx: tokio::sync::mpsc::UnboundedSender<String>) {
+        self.change_notifier.lock().unwrap().push(tx);
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_episodes_group_id ON episodes (group_id)",
-            [],
-        )?;
-        
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_episodes_created_at ON episodes (created_at)",
-            [],
-        )?;
+    /// Pushes `uri` to every registered change notifier. A closed receiver
+    /// (that connection's `McpProtocol` has been dropped) is not an error
+    /// here — it just means that subscriber is gone — so it's silently
+    /// dropped from the registry instead of being treated as a failure.
+    fn notify_change(&self, uri: String) {
+        self.change_notifier.lock().unwrap().retain(|tx| tx.send(uri.clone()).is_ok());
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_embeddings_type ON embeddings (entity_type)",
-            [],
-        )?;
+    /// Adds `reads`/`writes`/`bytes` to `op`'s running totals.
+    fn record_io(&self, op: &str, reads: u64, writes: u64, bytes: u64) {
+        let mut stats = self.io_stats.lock().unwrap();
+        let entry = stats.entry(op.to_string()).or_insert_with(IoOpStats::default);
+        entry.reads += reads;
+        entry.writes += writes;
+        entry.bytes += bytes;
+    }
 
-        // Create triggers to keep FTS tables in sync
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS nodes_fts_insert AFTER INSERT ON nodes
-            BEGIN
-                INSERT INTO nodes_fts(uuid, name, summary) VALUES (new.uuid, new.name, new.summary);
-            END",
-            [],
-        )?;
+    /// Snapshots the per-operation I/O counters and resets them to zero, so
+    /// benchmarks can pair "N ops/sec" wall-clock numbers with "M writes, K
+    /// bytes/op" for the same window instead of a blind throughput figure.
+    pub fn drain_io_stats(&self) -> HashMap<String, IoOpStats> {
+        std::mem::take(&mut *self.io_stats.lock().unwrap())
+    }
 
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS nodes_fts_update AFTER UPDATE ON nodes
-            BEGIN
-                UPDATE nodes_fts SET name = new.name, summary = new.summary WHERE uuid = new.uuid;
-            END",
-            [],
-        )?;
+    /// Clones the per-operation I/O counters without resetting them, unlike
+    /// `drain_io_stats`. Used by `manage_graph`'s `stats` operation, which
+    /// monitoring agents poll repeatedly and shouldn't zero out counters a
+    /// benchmark elsewhere is also relying on `drain_io_stats` to collect.
+    pub fn io_stats_snapshot(&self) -> HashMap<String, IoOpStats> {
+        self.io_stats.lock().unwrap().clone()
+    }
 
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS nodes_fts_delete AFTER DELETE ON nodes
-            BEGIN
-                DELETE FROM nodes_fts WHERE uuid = old.uuid;
-            END",
-            [],
-        )?;
+    /// Rough wire-size estimate for I/O instrumentation, not storage
+    /// accounting: sums the variable-length text fields actually written.
+    fn node_byte_estimate(node: &KGNode, metadata_json: &str) -> u64 {
+        (node.name.len() + node.node_type.len() + node.summary.len() + metadata_json.len()) as u64
+    }
 
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS episodes_fts_insert AFTER INSERT ON episodes
-            BEGIN
-                INSERT INTO episodes_fts(uuid, name, content) VALUES (new.uuid, new.name, new.content);
-            END",
-            [],
-        )?;
+    fn edge_byte_estimate(edge: &KGEdge, metadata_json: &str) -> u64 {
+        (edge.relation_type.len() + edge.summary.len() + metadata_json.len()) as u64
+    }
 
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS episodes_fts_update AFTER UPDATE ON episodes
-            BEGIN
-                UPDATE episodes_fts SET name = new.name, content = new.content WHERE uuid = new.uuid;
-            END",
-            [],
+    /// Copies `nodes`'s current row for `uuid` (if any) into
+    /// `node_revisions` before it gets overwritten, so the row that's about
+    /// to be replaced isn't lost — see `migration_003_revisions`. A no-op
+    /// if `uuid` has no current row (first insert).
+    fn snapshot_node_revision(tx: &Transaction, uuid: &str, edit_id: &str, changed_at: &str) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT INTO node_revisions (uuid, revision_seq, edit_id, changed_at, name, node_type, summary, created_at, updated_at, group_id, metadata)
+             SELECT uuid, COALESCE((SELECT MAX(revision_seq) FROM node_revisions WHERE uuid = ?1), 0) + 1, ?2, ?3,
+                    name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM nodes WHERE uuid = ?1",
+            params![uuid, edit_id, changed_at],
         )?;
+        Ok(())
+    }
 
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS episodes_fts_delete AFTER DELETE ON episodes
-            BEGIN
-                DELETE FROM episodes_fts WHERE uuid = old.uuid;
-            END",
-            [],
+    /// Same as `snapshot_node_revision`, for `edges`/`edge_revisions`.
+    fn snapshot_edge_revision(tx: &Transaction, uuid: &str, edit_id: &str, changed_at: &str) -> rusqlite::Result<()> {
+        tx.execute(
+            "INSERT INTO edge_revisions (uuid, revision_seq, edit_id, changed_at, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata)
+             SELECT uuid, COALESCE((SELECT MAX(revision_seq) FROM edge_revisions WHERE uuid = ?1), 0) + 1, ?2, ?3,
+                    source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edges WHERE uuid = ?1",
+            params![uuid, edit_id, changed_at],
         )?;
-
         Ok(())
     }
 
+    /// Inserts or replaces a node, first snapshotting whatever row it
+    /// overwrites into `node_revisions` in the same transaction — see
+    /// `migration_003_revisions` — so a prior fact is superseded rather
+    /// than silently lost.
     pub fn insert_node(&self, node: &KGNode) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT OR REPLACE INTO nodes 
+        let mut conn = self.writer.lock().unwrap();
+        let metadata_json = serde_json::to_string(&node.metadata)?;
+        let uuid_str = node.uuid.to_string();
+        let edit_id = Uuid::new_v4().to_string();
+        let changed_at = Utc::now().to_rfc3339();
+
+        let tx = conn.transaction()?;
+        Self::snapshot_node_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO nodes
              (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
-                node.uuid.to_string(),
+                KgUuid(node.uuid),
                 node.name,
                 node.node_type,
                 node.summary,
-                node.created_at.to_rfc3339(),
-                node.updated_at.to_rfc3339(),
+                KgTime(node.created_at),
+                KgTime(node.updated_at),
                 node.group_id,
-                serde_json::to_string(&node.metadata)?
+                metadata_json
             ],
         )?;
+        tx.commit()?;
+        drop(conn);
+        self.record_io("store_node", 0, 1, Self::node_byte_estimate(node, &metadata_json));
+        self.notify_change(format!("kg://node/{}", node.uuid));
         Ok(())
     }
 
+    /// Same as `insert_node`, for edges — see `snapshot_edge_revision`.
     pub fn insert_edge(&self, edge: &KGEdge) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT OR REPLACE INTO edges 
+        let mut conn = self.writer.lock().unwrap();
+        let metadata_json = serde_json::to_string(&edge.metadata)?;
+        let uuid_str = edge.uuid.to_string();
+        let edit_id = Uuid::new_v4().to_string();
+        let changed_at = Utc::now().to_rfc3339();
+
+        let tx = conn.transaction()?;
+        Self::snapshot_edge_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO edges
              (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
-                edge.uuid.to_string(),
-                edge.source_node_uuid.to_string(),
-                edge.target_node_uuid.to_string(),
+                KgUuid(edge.uuid),
+                KgUuid(edge.source_node_uuid),
+                KgUuid(edge.target_node_uuid),
                 edge.relation_type,
                 edge.summary,
                 edge.weight,
-                edge.created_at.to_rfc3339(),
-                edge.updated_at.to_rfc3339(),
+                KgTime(edge.created_at),
+                KgTime(edge.updated_at),
                 edge.group_id,
-                serde_json::to_string(&edge.metadata)?
+                metadata_json
             ],
         )?;
+        tx.commit()?;
+        drop(conn);
+        self.record_io("store_edge", 0, 1, Self::edge_byte_estimate(edge, &metadata_json));
+        self.notify_change(format!("kg://edge/{}", edge.uuid));
+        Ok(())
+    }
+
+    /// Reconstructs `uuid`'s node state as of `at`: the current row if it's
+    /// been current since before `at`, otherwise whichever `node_revisions`
+    /// row was live at `at` (its own `updated_at` through the `changed_at`
+    /// that superseded it). `None` if `uuid` didn't exist yet at `at`.
+    pub fn get_node_as_of(&self, uuid: Uuid, at: DateTime<Utc>) -> Result<Option<KGNode>> {
+        let conn = self.readers.acquire();
+        let uuid_str = uuid.to_string();
+        let at_str = at.to_rfc3339();
+
+        let current = conn.query_row(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM nodes WHERE uuid = ?1 AND updated_at <= ?2",
+            params![uuid_str, at_str],
+            |row| self.row_to_node(row),
+        ).optional()?;
+        if let Some(node) = current {
+            return Ok(Some(node));
+        }
+
+        let revision = conn.query_row(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM node_revisions WHERE uuid = ?1 AND updated_at <= ?2 AND changed_at > ?2
+             ORDER BY revision_seq DESC LIMIT 1",
+            params![uuid_str, at_str],
+            |row| self.row_to_node(row),
+        ).optional()?;
+
+        Ok(revision)
+    }
+
+    /// Every known state `uuid` has held, oldest first, ending with its
+    /// current row if it still exists.
+    pub fn get_node_history(&self, uuid: Uuid) -> Result<Vec<KGNode>> {
+        let conn = self.readers.acquire();
+        let uuid_str = uuid.to_string();
+
+        let mut stmt = conn.prepare(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM node_revisions WHERE uuid = ?1 ORDER BY revision_seq ASC"
+        )?;
+        let mut history: Vec<KGNode> = stmt.query_map(params![uuid_str], |row| self.row_to_node(row))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let current = conn.query_row(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata FROM nodes WHERE uuid = ?1",
+            params![uuid_str],
+            |row| self.row_to_node(row),
+        ).optional()?;
+        if let Some(node) = current {
+            history.push(node);
+        }
+
+        Ok(history)
+    }
+
+    /// Makes `revision_seq` of `uuid` the current row again, snapshotting
+    /// whatever was current beforehand into `node_revisions` just like any
+    /// other `insert_node` — a revert is itself a new, traceable edit
+    /// rather than a history-erasing rewrite.
+    pub fn revert_node(&self, uuid: Uuid, revision_seq: i64) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let uuid_str = uuid.to_string();
+
+        let target = conn.query_row(
+            "SELECT name, node_type, summary, created_at, group_id, metadata
+             FROM node_revisions WHERE uuid = ?1 AND revision_seq = ?2",
+            params![uuid_str, revision_seq],
+            |row| Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+            )),
+        ).optional()?;
+        let Some((name, node_type, summary, created_at, group_id, metadata)) = target else {
+            return Err(anyhow::anyhow!("No revision {} found for node {}", revision_seq, uuid));
+        };
+
+        let edit_id = Uuid::new_v4().to_string();
+        let changed_at = Utc::now().to_rfc3339();
+        let tx = conn.transaction()?;
+        Self::snapshot_node_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO nodes (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![uuid_str, name, node_type, summary, created_at, changed_at, group_id, metadata],
+        )?;
+        tx.commit()?;
+        self.notify_change(format!("kg://node/{}", uuid));
+        Ok(())
+    }
+
+    /// Same as `get_node_as_of`, for edges.
+    pub fn get_edge_as_of(&self, uuid: Uuid, at: DateTime<Utc>) -> Result<Option<KGEdge>> {
+        let conn = self.readers.acquire();
+        let uuid_str = uuid.to_string();
+        let at_str = at.to_rfc3339();
+
+        let current = conn.query_row(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edges WHERE uuid = ?1 AND updated_at <= ?2",
+            params![uuid_str, at_str],
+            |row| self.row_to_edge(row),
+        ).optional()?;
+        if let Some(edge) = current {
+            return Ok(Some(edge));
+        }
+
+        let revision = conn.query_row(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edge_revisions WHERE uuid = ?1 AND updated_at <= ?2 AND changed_at > ?2
+             ORDER BY revision_seq DESC LIMIT 1",
+            params![uuid_str, at_str],
+            |row| self.row_to_edge(row),
+        ).optional()?;
+
+        Ok(revision)
+    }
+
+    /// Same as `get_node_history`, for edges.
+    pub fn get_edge_history(&self, uuid: Uuid) -> Result<Vec<KGEdge>> {
+        let conn = self.readers.acquire();
+        let uuid_str = uuid.to_string();
+
+        let mut stmt = conn.prepare(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edge_revisions WHERE uuid = ?1 ORDER BY revision_seq ASC"
+        )?;
+        let mut history: Vec<KGEdge> = stmt.query_map(params![uuid_str], |row| self.row_to_edge(row))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let current = conn.query_row(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata FROM edges WHERE uuid = ?1",
+            params![uuid_str],
+            |row| self.row_to_edge(row),
+        ).optional()?;
+        if let Some(edge) = current {
+            history.push(edge);
+        }
+
+        Ok(history)
+    }
+
+    /// Same as `revert_node`, for edges.
+    pub fn revert_edge(&self, uuid: Uuid, revision_seq: i64) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let uuid_str = uuid.to_string();
+
+        let target = conn.query_row(
+            "SELECT source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, group_id, metadata
+             FROM edge_revisions WHERE uuid = ?1 AND revision_seq = ?2",
+            params![uuid_str, revision_seq],
+            |row| Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, f32>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, String>(7)?,
+            )),
+        ).optional()?;
+        let Some((source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, group_id, metadata)) = target else {
+            return Err(anyhow::anyhow!("No revision {} found for edge {}", revision_seq, uuid));
+        };
+
+        let edit_id = Uuid::new_v4().to_string();
+        let changed_at = Utc::now().to_rfc3339();
+        let tx = conn.transaction()?;
+        Self::snapshot_edge_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO edges (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![uuid_str, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, changed_at, group_id, metadata],
+        )?;
+        tx.commit()?;
+        self.notify_change(format!("kg://edge/{}", uuid));
         Ok(())
     }
 
+    /// Same as `insert_node`, but for multi-writer replication: `observed`
+    /// is the causal context this writer last read for `node.uuid` (empty
+    /// if it never has), and `writer_id` identifies this writer in the
+    /// merged context the write leaves behind. If `observed` covers
+    /// everything the currently-stored row has recorded (or there is no
+    /// currently-stored row), the write proceeds like a normal
+    /// `insert_node`, stamped with the merged context — returned to the
+    /// caller so it can be reused as `observed` on the next write. Otherwise
+    /// the store detected a concurrent edit: both the previously-stored row
+    /// and `node` are kept as unresolved siblings (see `get_node_siblings`)
+    /// rather than one silently overwriting the other, and `nodes`'s own
+    /// row is left untouched until `resolve_node` picks a winner.
+    pub fn insert_node_with_context(&self, node: &KGNode, observed: &CausalContext, writer_id: &str) -> Result<CausalContext> {
+        let mut conn = self.writer.lock().unwrap();
+        let uuid_str = node.uuid.to_string();
+
+        let stored_causality: Option<String> = conn.query_row(
+            "SELECT causality FROM nodes WHERE uuid = ?1",
+            params![uuid_str],
+            |row| row.get(0),
+        ).optional()?;
+
+        let metadata_json = serde_json::to_string(&node.metadata)?;
+
+        match &stored_causality {
+            Some(json) if !causal_dominates(observed, &serde_json::from_str(json).unwrap_or_default()) => {
+                // Concurrent: keep the currently-stored row and the incoming
+                // one as siblings instead of overwriting.
+                let sibling_id = Uuid::new_v4().to_string();
+                let causality_json = serde_json::to_string(observed)?;
+
+                let tx = conn.transaction()?;
+                tx.execute(
+                    "INSERT INTO node_siblings (uuid, sibling_id, name, node_type, summary, created_at, updated_at, group_id, metadata, causality)
+                     SELECT uuid, uuid, name, node_type, summary, created_at, updated_at, group_id, metadata, causality
+                     FROM nodes WHERE uuid = ?1 AND NOT EXISTS (SELECT 1 FROM node_siblings WHERE uuid = ?1 AND sibling_id = ?1)",
+                    params![uuid_str],
+                )?;
+                tx.execute(
+                    "INSERT INTO node_siblings (uuid, sibling_id, name, node_type, summary, created_at, updated_at, group_id, metadata, causality)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        uuid_str,
+                        sibling_id,
+                        node.name,
+                        node.node_type,
+                        node.summary,
+                        node.created_at.to_rfc3339(),
+                        node.updated_at.to_rfc3339(),
+                        node.group_id,
+                        metadata_json,
+                        causality_json
+                    ],
+                )?;
+                tx.commit()?;
+                Ok(observed.clone())
+            },
+            _ => {
+                let stored_context: CausalContext = stored_causality
+                    .as_deref()
+                    .and_then(|json| serde_json::from_str(json).ok())
+                    .unwrap_or_default();
+                let merged = merge_causal_context(observed, &stored_context, writer_id);
+                let causality_json = serde_json::to_string(&merged)?;
+                let edit_id = Uuid::new_v4().to_string();
+                let changed_at = Utc::now().to_rfc3339();
+
+                let tx = conn.transaction()?;
+                Self::snapshot_node_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO nodes
+                     (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata, causality)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        uuid_str,
+                        node.name,
+                        node.node_type,
+                        node.summary,
+                        node.created_at.to_rfc3339(),
+                        node.updated_at.to_rfc3339(),
+                        node.group_id,
+                        metadata_json,
+                        causality_json
+                    ],
+                )?;
+                tx.commit()?;
+                drop(conn);
+                self.record_io("store_node", 0, 1, Self::node_byte_estimate(node, &metadata_json));
+                self.notify_change(format!("kg://node/{}", node.uuid));
+                Ok(merged)
+            }
+        }
+    }
+
+    /// Same as `insert_node_with_context`, for edges.
+    pub fn insert_edge_with_context(&self, edge: &KGEdge, observed: &CausalContext, writer_id: &str) -> Result<CausalContext> {
+        let mut conn = self.writer.lock().unwrap();
+        let uuid_str = edge.uuid.to_string();
+
+        let stored_causality: Option<String> = conn.query_row(
+            "SELECT causality FROM edges WHERE uuid = ?1",
+            params![uuid_str],
+            |row| row.get(0),
+        ).optional()?;
+
+        let metadata_json = serde_json::to_string(&edge.metadata)?;
+
+        match &stored_causality {
+            Some(json) if !causal_dominates(observed, &serde_json::from_str(json).unwrap_or_default()) => {
+                let sibling_id = Uuid::new_v4().to_string();
+                let causality_json = serde_json::to_string(observed)?;
+
+                let tx = conn.transaction()?;
+                tx.execute(
+                    "INSERT INTO edge_siblings (uuid, sibling_id, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata, causality)
+                     SELECT uuid, uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata, causality
+                     FROM edges WHERE uuid = ?1 AND NOT EXISTS (SELECT 1 FROM edge_siblings WHERE uuid = ?1 AND sibling_id = ?1)",
+                    params![uuid_str],
+                )?;
+                tx.execute(
+                    "INSERT INTO edge_siblings (uuid, sibling_id, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata, causality)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    params![
+                        uuid_str,
+                        sibling_id,
+                        edge.source_node_uuid.to_string(),
+                        edge.target_node_uuid.to_string(),
+                        edge.relation_type,
+                        edge.summary,
+                        edge.weight,
+                        edge.created_at.to_rfc3339(),
+                        edge.updated_at.to_rfc3339(),
+                        edge.group_id,
+                        metadata_json,
+                        causality_json
+                    ],
+                )?;
+                tx.commit()?;
+                Ok(observed.clone())
+            },
+            _ => {
+                let stored_context: CausalContext = stored_causality
+                    .as_deref()
+                    .and_then(|json| serde_json::from_str(json).ok())
+                    .unwrap_or_default();
+                let merged = merge_causal_context(observed, &stored_context, writer_id);
+                let causality_json = serde_json::to_string(&merged)?;
+                let edit_id = Uuid::new_v4().to_string();
+                let changed_at = Utc::now().to_rfc3339();
+
+                let tx = conn.transaction()?;
+                Self::snapshot_edge_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO edges
+                     (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata, causality)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        uuid_str,
+                        edge.source_node_uuid.to_string(),
+                        edge.target_node_uuid.to_string(),
+                        edge.relation_type,
+                        edge.summary,
+                        edge.weight,
+                        edge.created_at.to_rfc3339(),
+                        edge.updated_at.to_rfc3339(),
+                        edge.group_id,
+                        metadata_json,
+                        causality_json
+                    ],
+                )?;
+                tx.commit()?;
+                drop(conn);
+                self.record_io("store_edge", 0, 1, Self::edge_byte_estimate(edge, &metadata_json));
+                self.notify_change(format!("kg://edge/{}", edge.uuid));
+                Ok(merged)
+            }
+        }
+    }
+
+    /// Unresolved concurrent versions of `uuid` left behind by
+    /// `insert_node_with_context` detecting a conflict — empty if `uuid`
+    /// has no unresolved conflict.
+    pub fn get_node_siblings(&self, uuid: Uuid) -> Result<Vec<KGNode>> {
+        let conn = self.readers.acquire();
+        let uuid_str = uuid.to_string();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM node_siblings WHERE uuid = ?1"
+        )?;
+        let siblings = stmt.query_map(params![uuid_str], |row| self.row_to_node(row))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(siblings)
+    }
+
+    /// Same as `get_node_siblings`, for edges.
+    pub fn get_edge_siblings(&self, uuid: Uuid) -> Result<Vec<KGEdge>> {
+        let conn = self.readers.acquire();
+        let uuid_str = uuid.to_string();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edge_siblings WHERE uuid = ?1"
+        )?;
+        let siblings = stmt.query_map(params![uuid_str], |row| self.row_to_edge(row))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(siblings)
+    }
+
+    /// Collapses every unresolved sibling of `uuid` back into a single
+    /// current row, `chosen`, stamped with `context` as its new causal
+    /// context — typically the context `get_node_siblings`'s caller derived
+    /// by merging the siblings' own contexts (e.g. via
+    /// `merge_causal_context`-style reasoning) after picking or
+    /// hand-merging a winner.
+    pub fn resolve_node(&self, uuid: Uuid, chosen: &KGNode, context: &CausalContext) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let uuid_str = uuid.to_string();
+        let metadata_json = serde_json::to_string(&chosen.metadata)?;
+        let causality_json = serde_json::to_string(context)?;
+        let edit_id = Uuid::new_v4().to_string();
+        let changed_at = Utc::now().to_rfc3339();
+
+        let tx = conn.transaction()?;
+        Self::snapshot_node_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO nodes (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata, causality)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                uuid_str,
+                chosen.name,
+                chosen.node_type,
+                chosen.summary,
+                chosen.created_at.to_rfc3339(),
+                chosen.updated_at.to_rfc3339(),
+                chosen.group_id,
+                metadata_json,
+                causality_json
+            ],
+        )?;
+        tx.execute("DELETE FROM node_siblings WHERE uuid = ?1", params![uuid_str])?;
+        tx.commit()?;
+        self.notify_change(format!("kg://node/{}", uuid));
+        Ok(())
+    }
+
+    /// Same as `resolve_node`, for edges.
+    pub fn resolve_edge(&self, uuid: Uuid, chosen: &KGEdge, context: &CausalContext) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let uuid_str = uuid.to_string();
+        let metadata_json = serde_json::to_string(&chosen.metadata)?;
+        let causality_json = serde_json::to_string(context)?;
+        let edit_id = Uuid::new_v4().to_string();
+        let changed_at = Utc::now().to_rfc3339();
+
+        let tx = conn.transaction()?;
+        Self::snapshot_edge_revision(&tx, &uuid_str, &edit_id, &changed_at)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO edges (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata, causality)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                uuid_str,
+                chosen.source_node_uuid.to_string(),
+                chosen.target_node_uuid.to_string(),
+                chosen.relation_type,
+                chosen.summary,
+                chosen.weight,
+                chosen.created_at.to_rfc3339(),
+                chosen.updated_at.to_rfc3339(),
+                chosen.group_id,
+                metadata_json,
+                causality_json
+            ],
+        )?;
+        tx.execute("DELETE FROM edge_siblings WHERE uuid = ?1", params![uuid_str])?;
+        tx.commit()?;
+        self.notify_change(format!("kg://edge/{}", uuid));
+        Ok(())
+    }
+
+    /// Inserts many nodes inside a single transaction, reusing one prepared
+    /// statement instead of paying a transaction-plus-prepare per row like a
+    /// loop of `insert_node` calls would. Returns each node's UUID in the
+    /// same order as `nodes`.
+    ///
+    /// Unlike `insert_node`, this does not snapshot into `node_revisions` —
+    /// batch ingestion is assumed to be populating new graphs rather than
+    /// overwriting tracked facts; callers that need revision history on a
+    /// bulk update path should call `insert_node` per row instead.
+    pub fn store_nodes_batch(&self, nodes: &[KGNode]) -> Result<Vec<Uuid>> {
+        let mut conn = self.writer.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut uuids = Vec::with_capacity(nodes.len());
+        let mut bytes = 0u64;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO nodes
+                 (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for node in nodes {
+                let metadata_json = serde_json::to_string(&node.metadata)?;
+                stmt.execute(params![
+                    node.uuid.to_string(),
+                    node.name,
+                    node.node_type,
+                    node.summary,
+                    node.created_at.to_rfc3339(),
+                    node.updated_at.to_rfc3339(),
+                    node.group_id,
+                    metadata_json
+                ])?;
+                bytes += Self::node_byte_estimate(node, &metadata_json);
+                uuids.push(node.uuid);
+            }
+        }
+        tx.commit()?;
+        drop(conn);
+        self.record_io("store_node", 0, uuids.len() as u64, bytes);
+        Ok(uuids)
+    }
+
+    /// Same idea as `store_nodes_batch` for edges: one transaction, one
+    /// prepared statement, reused for every row.
+    pub fn store_edges_batch(&self, edges: &[KGEdge]) -> Result<Vec<Uuid>> {
+        let mut conn = self.writer.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut uuids = Vec::with_capacity(edges.len());
+        let mut bytes = 0u64;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO edges
+                 (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for edge in edges {
+                let metadata_json = serde_json::to_string(&edge.metadata)?;
+                stmt.execute(params![
+                    edge.uuid.to_string(),
+                    edge.source_node_uuid.to_string(),
+                    edge.target_node_uuid.to_string(),
+                    edge.relation_type,
+                    edge.summary,
+                    edge.weight,
+                    edge.created_at.to_rfc3339(),
+                    edge.updated_at.to_rfc3339(),
+                    edge.group_id,
+                    metadata_json
+                ])?;
+                bytes += Self::edge_byte_estimate(edge, &metadata_json);
+                uuids.push(edge.uuid);
+            }
+        }
+        tx.commit()?;
+        drop(conn);
+        self.record_io("store_edge", 0, uuids.len() as u64, bytes);
+        Ok(uuids)
+    }
+
     pub fn insert_episode(&self, episode: &Episode) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer.lock().unwrap();
         
         // Insert episode
         conn.execute(
@@ -321,8 +1072,387 @@ impl GraphStorage {
         Ok(())
     }
 
+    /// Records (upserting) the content hash a `Migrator` computed for the
+    /// converted record it stored under `source_uuid`, so a later
+    /// `validate()` pass can re-read the row back and confirm it still
+    /// matches what was actually written - see `migration_content_hashes`
+    /// in `schema_migrations`.
+    pub fn record_content_hash(&self, source_uuid: &str, record_type: &str, content_hash: &str) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        conn.execute(
+            "INSERT INTO migration_content_hashes (source_uuid, record_type, content_hash, recorded_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (source_uuid, record_type) DO UPDATE SET content_hash = excluded.content_hash, recorded_at = excluded.recorded_at",
+            params![source_uuid, record_type, content_hash, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// All `(source_uuid, content_hash)` pairs recorded for `record_type`
+    /// (`"node"`/`"edge"`/`"episode"`), for `validate()` to walk without
+    /// needing to know the uuids up front.
+    pub fn get_content_hashes(&self, record_type: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT source_uuid, content_hash FROM migration_content_hashes WHERE record_type = ?1"
+        )?;
+        let rows = stmt
+            .query_map(params![record_type], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Deterministic uuid for chunk `chunk_index` of `episode_uuid`, used as
+    /// both `episode_chunks.uuid` and its `embeddings` row's key so the same
+    /// chunk re-embedded after a crash (or a re-ingested episode) replaces
+    /// its old row via `INSERT OR REPLACE` instead of accumulating
+    /// duplicates. Mirrors `streaming::episode_uuid_for`'s approach of
+    /// hashing the parent identity plus position into a stable uuid.
+    pub fn episode_chunk_uuid(episode_uuid: Uuid, chunk_index: usize) -> Uuid {
+        let mut hasher = Sha256::new();
+        hasher.update(episode_uuid.as_bytes());
+        hasher.update(b":chunk:");
+        hasher.update((chunk_index as u64).to_le_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        Uuid::from_bytes(bytes)
+    }
+
+    /// Stores `chunks` (each `(chunk_index, start_byte, end_byte, content,
+    /// embedding)`, see `embeddings::chunking::TextChunker::chunk_document`)
+    /// for `episode_uuid`, writing every chunk's `episode_chunks` row and its
+    /// `embeddings` row in one transaction — a crash partway through never
+    /// leaves a chunk's vector without the row needed to map it back to
+    /// `episode_uuid`, or vice versa. Replaces any chunks already stored for
+    /// this episode first, so re-ingesting an edited episode doesn't leave
+    /// stale trailing chunks behind if it got shorter.
+    pub fn store_episode_chunks(
+        &self,
+        episode_uuid: Uuid,
+        chunks: &[(usize, usize, usize, String, Vec<f32>)],
+    ) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut delete_old_embeddings = tx.prepare(
+                "DELETE FROM embeddings WHERE entity_type = 'episode_chunk' AND uuid IN
+                 (SELECT uuid FROM episode_chunks WHERE episode_uuid = ?1)",
+            )?;
+            delete_old_embeddings.execute(params![episode_uuid.to_string()])?;
+
+            let mut delete_old_chunks = tx.prepare("DELETE FROM episode_chunks WHERE episode_uuid = ?1")?;
+            delete_old_chunks.execute(params![episode_uuid.to_string()])?;
+
+            let mut insert_chunk = tx.prepare(
+                "INSERT INTO episode_chunks (uuid, episode_uuid, chunk_index, start_byte, end_byte, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            let mut insert_embedding = tx.prepare(
+                "INSERT OR REPLACE INTO embeddings (uuid, entity_type, embedding, dimensions, created_at)
+                 VALUES (?1, 'episode_chunk', ?2, ?3, ?4)",
+            )?;
+
+            for (chunk_index, start_byte, end_byte, content, embedding) in chunks {
+                let chunk_uuid = Self::episode_chunk_uuid(episode_uuid, *chunk_index);
+                insert_chunk.execute(params![
+                    chunk_uuid.to_string(),
+                    episode_uuid.to_string(),
+                    *chunk_index as i64,
+                    *start_byte as i64,
+                    *end_byte as i64,
+                    content,
+                ])?;
+
+                let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+                insert_embedding.execute(params![
+                    chunk_uuid.to_string(),
+                    embedding_bytes,
+                    embedding.len(),
+                    chrono::Utc::now().to_rfc3339()
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Looks up which episode (if any) `chunk_uuid` belongs to, for rolling a
+    /// chunk-level vector hit from `search_embeddings(.., "episode_chunk", ..)`
+    /// back up to its parent episode.
+    pub fn get_episode_for_chunk(&self, chunk_uuid: Uuid) -> Result<Option<Uuid>> {
+        let conn = self.readers.acquire();
+        let episode_uuid: Option<KgUuid> = conn
+            .query_row(
+                "SELECT episode_uuid FROM episode_chunks WHERE uuid = ?1",
+                params![chunk_uuid.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(episode_uuid.map(|u| u.0))
+    }
+
+    /// Insert many episodes as a single transaction — either all of them land
+    /// or none do. Used by the `batch` tool's atomic multi-episode ingestion
+    /// (see `mcp::handlers::handle_batch`) so a write failure partway through
+    /// a batch can't leave it half-ingested. This is the `insert_episodes_batch`
+    /// role `store_nodes_batch`/`store_edges_batch` fill for nodes/edges: one
+    /// transaction, one prepared statement per SQL shape, reused for every
+    /// row instead of paying a prepare per episode.
+    pub fn insert_episodes(&self, episodes: &[Episode]) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut episode_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO episodes
+                 (uuid, name, content, source, source_description, created_at, group_id, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            let mut entity_link_stmt = tx.prepare(
+                "INSERT OR IGNORE INTO episode_entities (episode_uuid, entity_uuid, entity_type)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+
+            for episode in episodes {
+                episode_stmt.execute(params![
+                    episode.uuid.to_string(),
+                    episode.name,
+                    episode.content,
+                    serde_json::to_string(&episode.source)?,
+                    episode.source_description,
+                    episode.created_at.to_rfc3339(),
+                    episode.group_id,
+                    serde_json::to_string(&episode.metadata)?
+                ])?;
+
+                for entity_uuid in &episode.entity_uuids {
+                    entity_link_stmt.execute(params![episode.uuid.to_string(), entity_uuid.to_string(), "node"])?;
+                }
+
+                for edge_uuid in &episode.edge_uuids {
+                    entity_link_stmt.execute(params![episode.uuid.to_string(), edge_uuid.to_string(), "edge"])?;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Atomically swaps everything associated with one file: deletes
+    /// `stale_episodes`/`stale_nodes`/`stale_edges` (as found by
+    /// `get_episodes_by_source_description`/`get_nodes_by_group_id`/
+    /// `get_edges_by_group_id`) and inserts `new_episodes`/`new_nodes`/
+    /// `new_edges` from a fresh re-index pass, all in a single transaction.
+    /// Used by `IngestionWatcher::reindex_path` so a crash or error partway
+    /// through a re-index can't leave search seeing a half-indexed file —
+    /// either the old rows are all still there or the new ones are, never a
+    /// mix of some-deleted-some-not. Any `new_episodes` entry that already
+    /// carries an embedding (set by `CodebaseIndexer::index_file` draining
+    /// its own backlog before this is called) has that vector written to
+    /// `embeddings` in the same transaction, so a crash never leaves an
+    /// episode searchable by keyword but invisible to vector search, or vice
+    /// versa.
+    pub fn reindex_file(
+        &self,
+        stale_episodes: &[Episode],
+        stale_nodes: &[KGNode],
+        stale_edges: &[KGEdge],
+        new_nodes: &[KGNode],
+        new_edges: &[KGEdge],
+        new_episodes: &[Episode],
+    ) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut delete_episode_entities_by_episode = tx.prepare(
+                "DELETE FROM episode_entities WHERE episode_uuid = ?1",
+            )?;
+            let mut delete_episode_embedding = tx.prepare(
+                "DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'episode'",
+            )?;
+            let mut delete_episode = tx.prepare("DELETE FROM episodes WHERE uuid = ?1")?;
+            for episode in stale_episodes {
+                let uuid = episode.uuid.to_string();
+                delete_episode_entities_by_episode.execute(params![uuid])?;
+                delete_episode_embedding.execute(params![uuid])?;
+                delete_episode.execute(params![uuid])?;
+            }
+
+            let mut delete_edge_embedding = tx.prepare(
+                "DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'edge'",
+            )?;
+            let mut delete_episode_entities_by_edge = tx.prepare(
+                "DELETE FROM episode_entities WHERE entity_uuid = ?1 AND entity_type = 'edge'",
+            )?;
+            let mut delete_edge = tx.prepare("DELETE FROM edges WHERE uuid = ?1")?;
+            for edge in stale_edges {
+                let uuid = edge.uuid.to_string();
+                delete_edge_embedding.execute(params![uuid])?;
+                delete_episode_entities_by_edge.execute(params![uuid])?;
+                delete_edge.execute(params![uuid])?;
+            }
+
+            let mut delete_node_embedding = tx.prepare(
+                "DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'node'",
+            )?;
+            let mut delete_episode_entities_by_node = tx.prepare(
+                "DELETE FROM episode_entities WHERE entity_uuid = ?1 AND entity_type = 'node'",
+            )?;
+            let mut delete_node = tx.prepare("DELETE FROM nodes WHERE uuid = ?1")?;
+            for node in stale_nodes {
+                let uuid = node.uuid.to_string();
+                delete_node_embedding.execute(params![uuid])?;
+                delete_episode_entities_by_node.execute(params![uuid])?;
+                delete_node.execute(params![uuid])?;
+            }
+
+            let mut insert_node_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO nodes
+                 (uuid, name, node_type, summary, created_at, updated_at, group_id, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for node in new_nodes {
+                let metadata_json = serde_json::to_string(&node.metadata)?;
+                insert_node_stmt.execute(params![
+                    KgUuid(node.uuid),
+                    node.name,
+                    node.node_type,
+                    node.summary,
+                    KgTime(node.created_at),
+                    KgTime(node.updated_at),
+                    node.group_id,
+                    metadata_json
+                ])?;
+            }
+
+            let mut insert_edge_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO edges
+                 (uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for edge in new_edges {
+                let metadata_json = serde_json::to_string(&edge.metadata)?;
+                insert_edge_stmt.execute(params![
+                    KgUuid(edge.uuid),
+                    KgUuid(edge.source_node_uuid),
+                    KgUuid(edge.target_node_uuid),
+                    edge.relation_type,
+                    edge.summary,
+                    edge.weight,
+                    KgTime(edge.created_at),
+                    KgTime(edge.updated_at),
+                    edge.group_id,
+                    metadata_json
+                ])?;
+            }
+
+            let mut insert_episode_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO episodes
+                 (uuid, name, content, source, source_description, created_at, group_id, metadata)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            let mut insert_entity_link_stmt = tx.prepare(
+                "INSERT OR IGNORE INTO episode_entities (episode_uuid, entity_uuid, entity_type)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+            // Episodes that already carry an embedding (the incremental
+            // watcher path drains its own embedding backlog before calling
+            // here) get it written to `embeddings` in this same transaction,
+            // so a crash between the two inserts can never leave a searchable
+            // episode with no vector, or a vector with no episode behind it.
+            let mut insert_episode_embedding_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO embeddings (uuid, entity_type, embedding, dimensions, created_at)
+                 VALUES (?1, 'episode', ?2, ?3, ?4)",
+            )?;
+            for episode in new_episodes {
+                insert_episode_stmt.execute(params![
+                    episode.uuid.to_string(),
+                    episode.name,
+                    episode.content,
+                    serde_json::to_string(&episode.source)?,
+                    episode.source_description,
+                    episode.created_at.to_rfc3339(),
+                    episode.group_id,
+                    serde_json::to_string(&episode.metadata)?
+                ])?;
+                for entity_uuid in &episode.entity_uuids {
+                    insert_entity_link_stmt.execute(params![episode.uuid.to_string(), entity_uuid.to_string(), "node"])?;
+                }
+                for edge_uuid in &episode.edge_uuids {
+                    insert_entity_link_stmt.execute(params![episode.uuid.to_string(), edge_uuid.to_string(), "edge"])?;
+                }
+                if let Some(embedding) = &episode.embedding {
+                    let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+                    insert_episode_embedding_stmt.execute(params![
+                        episode.uuid.to_string(),
+                        embedding_bytes,
+                        embedding.len(),
+                        chrono::Utc::now().to_rfc3339()
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        drop(conn);
+
+        let mut hnsw = self.hnsw.write().unwrap();
+        for node in stale_nodes {
+            hnsw.remove(&node.uuid);
+        }
+
+        Ok(())
+    }
+
+    /// Runs `f` against a single explicit `Transaction`, committing on
+    /// success and rolling back (via `Transaction`'s own `Drop`) if `f`
+    /// returns an error — for callers that need to group a mixed
+    /// node/edge/episode write atomically without duplicating one of the
+    /// `*_batch` methods above. `f` gets the raw `Transaction`, so it can
+    /// mix `tx.execute` calls and prepared statements as needed.
+    pub fn with_transaction<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+        let mut conn = self.writer.lock().unwrap();
+        let tx = conn.transaction()?;
+        let result = f(&tx)?;
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Last offset `commit_stream_checkpoint` saw for `stream_id`, or `None`
+    /// if the stream has never committed one (e.g. first start).
+    pub fn get_stream_checkpoint(&self, stream_id: &str) -> Result<Option<u64>> {
+        let conn = self.readers.acquire();
+        let offset: Option<i64> = conn
+            .query_row(
+                "SELECT offset FROM stream_checkpoints WHERE stream_id = ?1",
+                params![stream_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(offset.map(|offset| offset as u64))
+    }
+
+    /// Records `offset` as the last successfully stored position for
+    /// `stream_id`. Only ever moves forward: if a checkpoint already exists
+    /// for this stream, the write is ignored unless `offset` is strictly
+    /// greater, so an out-of-order or redelivered commit can never regress
+    /// progress.
+    pub fn commit_stream_checkpoint(&self, stream_id: &str, offset: u64) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        conn.execute(
+            "INSERT INTO stream_checkpoints (stream_id, offset, updated_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(stream_id) DO UPDATE SET
+                 offset = excluded.offset,
+                 updated_at = excluded.updated_at
+             WHERE excluded.offset > stream_checkpoints.offset",
+            params![stream_id, offset as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
     pub fn store_embedding(&self, entity_uuid: Uuid, entity_type: &str, embedding: &[f32]) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer.lock().unwrap();
         
         // Convert f32 slice to bytes
         let embedding_bytes: Vec<u8> = embedding
@@ -342,11 +1472,15 @@ impl GraphStorage {
             ],
         )?;
 
+        if entity_type == "node" {
+            self.hnsw.write().unwrap().insert(entity_uuid, embedding.to_vec());
+        }
+
         Ok(())
     }
 
     pub fn get_node(&self, uuid: Uuid) -> Result<Option<KGNode>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         let mut stmt = conn.prepare(
             "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
              FROM nodes WHERE uuid = ?1"
@@ -356,11 +1490,15 @@ impl GraphStorage {
             self.row_to_node(row)
         }).optional()?;
 
+        let bytes = node.as_ref().map(|n| (n.name.len() + n.node_type.len() + n.summary.len()) as u64).unwrap_or(0);
+        drop(conn);
+        self.record_io("get_node", 1, 0, bytes);
+
         Ok(node)
     }
 
     pub fn get_edge(&self, uuid: Uuid) -> Result<Option<KGEdge>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         let mut stmt = conn.prepare(
             "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
              FROM edges WHERE uuid = ?1"
@@ -370,11 +1508,25 @@ impl GraphStorage {
             self.row_to_edge(row)
         }).optional()?;
 
+        let bytes = edge.as_ref().map(|e| (e.relation_type.len() + e.summary.len()) as u64).unwrap_or(0);
+        drop(conn);
+        self.record_io("get_edge", 1, 0, bytes);
+
         Ok(edge)
     }
 
     pub fn get_episode(&self, uuid: Uuid) -> Result<Option<Episode>> {
-        let conn = self.conn.lock().unwrap();
+        self.load_episode_full(uuid)
+    }
+
+    /// Fully-hydrated single-episode load: populates `entity_uuids`,
+    /// `edge_uuids`, and `embedding` from the `episode_entities`/`embeddings`
+    /// association tables, unlike the bare `row_to_episode` mapper (used by
+    /// `get_recent_episodes`, `get_episodes_page`, `search_episodes_by_content`,
+    /// etc.), which leaves those fields empty/`None`. `get_episode` is kept
+    /// as a thin alias for callers that predate this name.
+    pub fn load_episode_full(&self, uuid: Uuid) -> Result<Option<Episode>> {
+        let conn = self.readers.acquire();
         let mut stmt = conn.prepare(
             "SELECT uuid, name, content, source, source_description, created_at, group_id, metadata
              FROM episodes WHERE uuid = ?1"
@@ -434,9 +1586,153 @@ impl GraphStorage {
         Ok(episode)
     }
 
+    /// Batched form of `load_episode_full`: hydrates every episode in
+    /// `uuids` with three `IN (...)` queries total (episodes,
+    /// `episode_entities`, `embeddings`) instead of three per episode,
+    /// using `params_from_iter` the same way `graph_counts`/`gc` already do
+    /// for their own group-id batches. Missing uuids are omitted rather
+    /// than erroring, matching `load_episode_full`'s `None`-on-missing
+    /// behavior; the returned order follows `uuids`, not storage order.
+    pub fn load_episodes_full(&self, uuids: &[Uuid]) -> Result<Vec<Episode>> {
+        if uuids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.readers.acquire();
+        let uuid_strs: Vec<String> = uuids.iter().map(|u| u.to_string()).collect();
+        let placeholders = uuid_strs.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+
+        let mut episodes: HashMap<Uuid, Episode> = {
+            let sql = format!(
+                "SELECT uuid, name, content, source, source_description, created_at, group_id, metadata
+                 FROM episodes WHERE uuid IN ({placeholders})"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(uuid_strs.iter()), |row| {
+                self.row_to_episode(row)
+            })?;
+            let mut map = HashMap::new();
+            for episode in rows {
+                let episode = episode?;
+                map.insert(episode.uuid, episode);
+            }
+            map
+        };
+
+        {
+            let sql = format!(
+                "SELECT episode_uuid, entity_uuid, entity_type FROM episode_entities
+                 WHERE episode_uuid IN ({placeholders})"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(uuid_strs.iter()), |row| {
+                let episode_uuid: KgUuid = row.get("episode_uuid")?;
+                let entity_uuid: KgUuid = row.get("entity_uuid")?;
+                let entity_type: String = row.get("entity_type")?;
+                Ok((episode_uuid.0, entity_uuid.0, entity_type))
+            })?;
+            for row in rows {
+                let (episode_uuid, entity_uuid, entity_type) = row?;
+                if let Some(episode) = episodes.get_mut(&episode_uuid) {
+                    match entity_type.as_str() {
+                        "node" => episode.add_entity(entity_uuid),
+                        "edge" => episode.add_edge(entity_uuid),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        {
+            let sql = format!(
+                "SELECT uuid, embedding, dimensions FROM embeddings
+                 WHERE entity_type = 'episode' AND uuid IN ({placeholders})"
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(uuid_strs.iter()), |row| {
+                let uuid: KgUuid = row.get("uuid")?;
+                let embedding_bytes: Vec<u8> = row.get("embedding")?;
+                let dimensions: usize = row.get("dimensions")?;
+                Ok((uuid.0, embedding_bytes, dimensions))
+            })?;
+            for row in rows {
+                let (uuid, embedding_bytes, dimensions) = row?;
+                if let Some(episode) = episodes.get_mut(&uuid) {
+                    let embedding: Vec<f32> = embedding_bytes
+                        .chunks(4)
+                        .take(dimensions)
+                        .map(|chunk| {
+                            let mut bytes = [0u8; 4];
+                            bytes.copy_from_slice(chunk);
+                            f32::from_le_bytes(bytes)
+                        })
+                        .collect();
+                    episode.set_embedding(embedding);
+                }
+            }
+        }
+
+        Ok(uuids.iter().filter_map(|u| episodes.remove(u)).collect())
+    }
+
+    /// Runs a `NodeFilter` against `nodes`, binding its parameter list with
+    /// `params_from_iter` instead of the ad-hoc `IN (?,?,...)` string
+    /// building scattered elsewhere in this file.
+    pub fn find_nodes(&self, filter: &NodeFilter) -> Result<Vec<KGNode>> {
+        let conn = self.readers.acquire();
+        let clause = filter.to_sql();
+        let mut sql = "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata FROM nodes".to_string();
+        if !clause.where_sql.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clause.where_sql);
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(clause.params.iter()), |row| {
+            self.row_to_node(row)
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Runs an `EdgeFilter` against `edges`. See `find_nodes`.
+    pub fn find_edges(&self, filter: &EdgeFilter) -> Result<Vec<KGEdge>> {
+        let conn = self.readers.acquire();
+        let clause = filter.to_sql();
+        let mut sql = "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata FROM edges".to_string();
+        if !clause.where_sql.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clause.where_sql);
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(clause.params.iter()), |row| {
+            self.row_to_edge(row)
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Runs an `EpisodeFilter` against `episodes`, via the lightweight
+    /// `row_to_episode` mapper (not hydrated — pair with `load_episodes_full`
+    /// if entity/edge/embedding links are needed). See `find_nodes`.
+    pub fn find_episodes(&self, filter: &EpisodeFilter) -> Result<Vec<Episode>> {
+        let conn = self.readers.acquire();
+        let clause = filter.to_sql();
+        let mut sql = "SELECT uuid, name, content, source, source_description, created_at, group_id, metadata FROM episodes".to_string();
+        if !clause.where_sql.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clause.where_sql);
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(clause.params.iter()), |row| {
+            self.row_to_episode(row)
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
     pub fn search_nodes_by_text(&self, query: &str, group_id: Option<&str>, limit: usize) -> Result<Vec<KGNode>> {
-        let conn = self.conn.lock().unwrap();
-        
+        let conn = self.readers.acquire();
+
         if let Some(group_id) = group_id {
             let mut stmt = conn.prepare(
                 "SELECT n.uuid, n.name, n.node_type, n.summary, n.created_at, n.updated_at, n.group_id, n.metadata
@@ -449,7 +1745,11 @@ impl GraphStorage {
             let rows = stmt.query_map(params![query, group_id, limit], |row| self.row_to_node(row))?;
             let nodes: Result<Vec<_>, anyhow::Error> = rows.collect::<Result<Vec<_>, rusqlite::Error>>()
                 .map_err(|e| anyhow::Error::new(e));
-            Ok(nodes?)
+            let nodes = nodes?;
+            let bytes: u64 = nodes.iter().map(|n| (n.name.len() + n.node_type.len() + n.summary.len()) as u64).sum();
+            drop(conn);
+            self.record_io("search_nodes_by_group", nodes.len() as u64, 0, bytes);
+            Ok(nodes)
         } else {
             let mut stmt = conn.prepare(
                 "SELECT n.uuid, n.name, n.node_type, n.summary, n.created_at, n.updated_at, n.group_id, n.metadata
@@ -462,12 +1762,230 @@ impl GraphStorage {
             let rows = stmt.query_map(params![query, limit], |row| self.row_to_node(row))?;
             let nodes: Result<Vec<_>, anyhow::Error> = rows.collect::<Result<Vec<_>, rusqlite::Error>>()
                 .map_err(|e| anyhow::Error::new(e));
-            Ok(nodes?)
+            let nodes = nodes?;
+            let bytes: u64 = nodes.iter().map(|n| (n.name.len() + n.node_type.len() + n.summary.len()) as u64).sum();
+            drop(conn);
+            self.record_io("search_nodes", nodes.len() as u64, 0, bytes);
+            Ok(nodes)
+        }
+    }
+
+    /// Fuses `search_nodes_by_text`'s FTS5 ranking with cosine-similarity
+    /// ranking over every stored node embedding, via Reciprocal Rank Fusion
+    /// (k=60): for each candidate uuid, the fused score is the sum over
+    /// both lists of `1 / (k + rank)` (rank starting at 1); a uuid
+    /// appearing in only one list still contributes its single term.
+    /// Results are sorted by descending fused score and truncated to
+    /// `limit`. This is the same fusion strategy
+    /// `HybridSearchEngine::reciprocal_rank_fusion` already offers over
+    /// pre-loaded node lists, but reads both ranked lists straight out of
+    /// storage — FTS5 MATCH plus the raw little-endian `embedding` BLOBs,
+    /// decoded the same way `get_episode` decodes its own — for callers
+    /// that only have a query string and a query embedding, not an
+    /// in-memory node set to search over.
+    pub fn hybrid_search_nodes(
+        &self,
+        query_text: &str,
+        query_embedding: &[f32],
+        group_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<KGNode>> {
+        const RRF_K: f32 = 60.0;
+        let candidate_pool = limit.max(100);
+
+        let text_results = self.search_nodes_by_text(query_text, group_id, candidate_pool)?;
+
+        let conn = self.readers.acquire();
+        let mut vector_results: Vec<(KGNode, f32)> = {
+            let mut stmt = if group_id.is_some() {
+                conn.prepare(
+                    "SELECT n.uuid, n.name, n.node_type, n.summary, n.created_at, n.updated_at, n.group_id, n.metadata, e.embedding, e.dimensions
+                     FROM embeddings e
+                     JOIN nodes n ON e.uuid = n.uuid
+                     WHERE e.entity_type = 'node' AND n.group_id = ?1"
+                )?
+            } else {
+                conn.prepare(
+                    "SELECT n.uuid, n.name, n.node_type, n.summary, n.created_at, n.updated_at, n.group_id, n.metadata, e.embedding, e.dimensions
+                     FROM embeddings e
+                     JOIN nodes n ON e.uuid = n.uuid
+                     WHERE e.entity_type = 'node'"
+                )?
+            };
+
+            let map_row = |row: &Row| -> rusqlite::Result<(KGNode, f32)> {
+                let node = self.row_to_node(row)?;
+                let embedding_bytes: Vec<u8> = row.get(8)?;
+                let dimensions: usize = row.get(9)?;
+                let embedding: Vec<f32> = embedding_bytes
+                    .chunks(4)
+                    .take(dimensions)
+                    .map(|chunk| {
+                        let mut bytes = [0u8; 4];
+                        bytes.copy_from_slice(chunk);
+                        f32::from_le_bytes(bytes)
+                    })
+                    .collect();
+                let similarity = crate::embeddings::cosine_similarity(query_embedding, &embedding);
+                Ok((node, similarity))
+            };
+
+            let rows: Result<Vec<_>, rusqlite::Error> = if let Some(gid) = group_id {
+                stmt.query_map(params![gid], map_row)?.collect()
+            } else {
+                stmt.query_map([], map_row)?.collect()
+            };
+            rows?
+        };
+        drop(conn);
+
+        vector_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        vector_results.truncate(candidate_pool);
+
+        let mut fused: HashMap<Uuid, (f32, KGNode)> = HashMap::new();
+        for (rank, node) in text_results.into_iter().enumerate() {
+            let entry = fused.entry(node.uuid).or_insert_with(|| (0.0, node.clone()));
+            entry.0 += 1.0 / (RRF_K + (rank + 1) as f32);
+        }
+        for (rank, (node, _similarity)) in vector_results.into_iter().enumerate() {
+            let entry = fused.entry(node.uuid).or_insert_with(|| (0.0, node.clone()));
+            entry.0 += 1.0 / (RRF_K + (rank + 1) as f32);
+        }
+
+        let mut ranked: Vec<(f32, KGNode)> = fused.into_values().collect();
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(limit);
+
+        Ok(ranked.into_iter().map(|(_, node)| node).collect())
+    }
+
+    /// Storage-backed vector lookup for a query embedding already computed
+    /// once by the caller, returning the top `k` `(uuid, cosine_similarity)`
+    /// pairs for `entity_type` (`"node"`/`"edge"`/`"episode"`, matching
+    /// `store_embedding`'s own `entity_type` argument). Scans every stored
+    /// `embeddings` row for `entity_type` — exact, but O(n) — rather than
+    /// using `hnsw`'s index, which only covers `entity_type = "node"`. Used
+    /// for edges/episodes (no index exists for those) and as a correctness
+    /// reference for `hnsw_search`.
+    pub fn search_embeddings(&self, query_embedding: &[f32], entity_type: &str, k: usize) -> Result<Vec<(Uuid, f32)>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, embedding, dimensions FROM embeddings WHERE entity_type = ?1"
+        )?;
+
+        let mut scored: Vec<(Uuid, f32)> = stmt.query_map(params![entity_type], |row| {
+            let uuid: KgUuid = row.get("uuid")?;
+            let embedding_bytes: Vec<u8> = row.get("embedding")?;
+            let dimensions: usize = row.get("dimensions")?;
+            let embedding: Vec<f32> = embedding_bytes
+                .chunks(4)
+                .take(dimensions)
+                .map(|chunk| {
+                    let mut bytes = [0u8; 4];
+                    bytes.copy_from_slice(chunk);
+                    f32::from_le_bytes(bytes)
+                })
+                .collect();
+            Ok((uuid.0, embedding))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .map(|(uuid, embedding)| (uuid, crate::embeddings::cosine_similarity(query_embedding, &embedding)))
+        .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        Ok(scored)
+    }
+
+    /// Approximate, sub-linear top-`k` lookup over node embeddings via the
+    /// `hnsw` index instead of `search_embeddings`'s full scan. `ef_search`
+    /// is the query-time beam width (see `hnsw::HnswIndex::search`); larger
+    /// values trade query latency for recall. Falls back to the exact,
+    /// brute-force `search_embeddings("node", ...)` scan when the index is
+    /// still empty (e.g. this session's nodes haven't been embedded yet).
+    pub fn hnsw_search_nodes(&self, query_embedding: &[f32], k: usize, ef_search: usize) -> Result<Vec<(Uuid, f32)>> {
+        let index = self.hnsw.read().unwrap();
+        if index.is_empty() {
+            drop(index);
+            return self.search_embeddings(query_embedding, "node", k);
         }
+        Ok(index.search(query_embedding, k, ef_search))
+    }
+
+    /// Rebuilds the `hnsw` node-embedding index from scratch with `config`,
+    /// for callers that want to change `m` (which only takes effect on
+    /// reinsertion, since it governs how many neighbors each node keeps).
+    /// Runs at construction time with `HnswConfig::default()`; also safe to
+    /// call later if the index has drifted or a different `m` is wanted.
+    pub fn rebuild_hnsw_index(&self, config: HnswConfig) -> Result<()> {
+        let all = self.all_node_embeddings()?;
+
+        let mut index = super::hnsw::HnswIndex::new(config);
+        for (uuid, embedding) in all {
+            index.insert(uuid, embedding);
+        }
+        *self.hnsw.write().unwrap() = index;
+        Ok(())
+    }
+
+    /// A single stored node embedding, decoded the same way
+    /// `all_node_embeddings`/`search_embeddings` do. Used by callers that
+    /// already have a node uuid in hand and want its own vector as an HNSW
+    /// query (e.g. `QueryEngine::find_similar_nodes`), rather than every
+    /// node's vector or a lookup keyed by an externally-supplied embedding.
+    pub fn get_node_embedding(&self, uuid: Uuid) -> Result<Option<Vec<f32>>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT embedding, dimensions FROM embeddings WHERE entity_type = 'node' AND uuid = ?1"
+        )?;
+
+        stmt.query_row(params![uuid.to_string()], |row| {
+            let embedding_bytes: Vec<u8> = row.get(0)?;
+            let dimensions: usize = row.get(1)?;
+            Ok(embedding_bytes
+                .chunks(4)
+                .take(dimensions)
+                .map(|chunk| {
+                    let mut bytes = [0u8; 4];
+                    bytes.copy_from_slice(chunk);
+                    f32::from_le_bytes(bytes)
+                })
+                .collect())
+        }).optional().map_err(anyhow::Error::new)
+    }
+
+    /// Every stored `(node uuid, embedding)` pair, decoded from the
+    /// `embeddings` table's little-endian `f32` blob the same way
+    /// `search_embeddings`/`rebuild_hnsw_index` do. Used by callers that
+    /// need every node's vector at once (`rebuild_hnsw_index`,
+    /// `semantic_clusters`), rather than a top-`k` lookup against a query.
+    pub fn all_node_embeddings(&self) -> Result<Vec<(Uuid, Vec<f32>)>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, embedding, dimensions FROM embeddings WHERE entity_type = 'node'"
+        )?;
+        let all: Vec<(Uuid, Vec<f32>)> = stmt.query_map([], |row| {
+            let uuid: KgUuid = row.get("uuid")?;
+            let embedding_bytes: Vec<u8> = row.get("embedding")?;
+            let dimensions: usize = row.get("dimensions")?;
+            let embedding: Vec<f32> = embedding_bytes
+                .chunks(4)
+                .take(dimensions)
+                .map(|chunk| {
+                    let mut bytes = [0u8; 4];
+                    bytes.copy_from_slice(chunk);
+                    f32::from_le_bytes(bytes)
+                })
+                .collect();
+            Ok((uuid.0, embedding))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(all)
     }
 
     pub fn get_recent_episodes(&self, group_id: Option<&str>, limit: usize) -> Result<Vec<Episode>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         let mut query = "SELECT uuid, name, content, source, source_description, created_at, group_id, metadata
                          FROM episodes".to_string();
         
@@ -494,25 +2012,285 @@ impl GraphStorage {
     }
 
     pub async fn count_nodes(&self) -> Result<usize> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))?;
         Ok(count as usize)
     }
 
     pub async fn count_edges(&self) -> Result<usize> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM edges", [], |row| row.get(0))?;
         Ok(count as usize)
     }
 
     pub async fn count_episodes(&self) -> Result<usize> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM episodes", [], |row| row.get(0))?;
         Ok(count as usize)
     }
 
+    /// Size in bytes of the on-disk database file backing this connection,
+    /// for the `admin_metrics` tool. `None` for an in-memory database (no
+    /// file to stat).
+    pub async fn database_file_size_bytes(&self) -> Result<Option<u64>> {
+        let path = {
+            let conn = self.readers.acquire();
+            conn.path().map(|p| p.to_string())
+        };
+
+        match path {
+            Some(path) if !path.is_empty() => Ok(Some(std::fs::metadata(path)?.len())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Reads one page of nodes ordered by `uuid`, for cursor-style iteration
+    /// over graphs too large to load in full (see
+    /// `migration::validation::DataValidator::validate_stream`).
+    pub fn get_nodes_page(&self, offset: usize, limit: usize) -> Result<Vec<KGNode>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM nodes ORDER BY uuid LIMIT ?1 OFFSET ?2"
+        )?;
+        let rows = stmt.query_map(params![limit, offset], |row| self.row_to_node(row))?;
+        let nodes: Result<Vec<_>, rusqlite::Error> = rows.collect();
+        let nodes = nodes?;
+        drop(conn);
+        self.record_io("get_nodes_page", nodes.len() as u64, 0, 0);
+        Ok(nodes)
+    }
+
+    /// Reads one page of edges ordered by `uuid`. See `get_nodes_page`.
+    pub fn get_edges_page(&self, offset: usize, limit: usize) -> Result<Vec<KGEdge>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edges ORDER BY uuid LIMIT ?1 OFFSET ?2"
+        )?;
+        let rows = stmt.query_map(params![limit, offset], |row| self.row_to_edge(row))?;
+        let edges: Result<Vec<_>, rusqlite::Error> = rows.collect();
+        let edges = edges?;
+        drop(conn);
+        self.record_io("get_edges_page", edges.len() as u64, 0, 0);
+        Ok(edges)
+    }
+
+    /// Reads one page of episodes ordered by `uuid`, including their entity
+    /// and edge references (but not embeddings, which
+    /// `check_embedding_quality` inspects via `embedding.is_some()` alone).
+    /// See `get_nodes_page`.
+    pub fn get_episodes_page(&self, offset: usize, limit: usize) -> Result<Vec<Episode>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT uuid, name, content, source, source_description, created_at, group_id, metadata
+             FROM episodes ORDER BY uuid LIMIT ?1 OFFSET ?2"
+        )?;
+        let rows = stmt.query_map(params![limit, offset], |row| self.row_to_episode(row))?;
+        let mut episodes: Vec<Episode> = rows.collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+        let mut entity_stmt = conn.prepare(
+            "SELECT entity_uuid, entity_type FROM episode_entities WHERE episode_uuid = ?1"
+        )?;
+        let mut embedding_stmt = conn.prepare(
+            "SELECT 1 FROM embeddings WHERE uuid = ?1 AND entity_type = 'episode'"
+        )?;
+        for episode in episodes.iter_mut() {
+            let refs = entity_stmt.query_map(params![episode.uuid.to_string()], |row| {
+                let uuid_str: String = row.get(0)?;
+                let entity_type: String = row.get(1)?;
+                Ok((uuid_str, entity_type))
+            })?.collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+            for (uuid_str, entity_type) in refs {
+                if let Ok(uuid) = Uuid::parse_str(&uuid_str) {
+                    match entity_type.as_str() {
+                        "node" => episode.add_entity(uuid),
+                        "edge" => episode.add_edge(uuid),
+                        _ => {}
+                    }
+                }
+            }
+
+            // We only need to know whether an embedding exists, not its
+            // contents, so record a sentinel rather than loading the vector.
+            if embedding_stmt.exists(params![episode.uuid.to_string()])? {
+                episode.embedding = Some(Vec::new());
+            }
+        }
+
+        drop(conn);
+        self.record_io("get_episodes_page", episodes.len() as u64, 0, 0);
+        Ok(episodes)
+    }
+
+    /// Finds episodes whose `source_description` exactly matches `path`.
+    /// Used by the incremental file-watcher to find stale episodes for a
+    /// file before re-indexing it.
+    pub fn get_episodes_by_source_description(&self, path: &str) -> Result<Vec<Episode>> {
+        let conn = self.readers.acquire();
+
+        let mut stmt = conn.prepare(
+            "SELECT uuid, name, content, source, source_description, created_at, group_id, metadata
+             FROM episodes
+             WHERE source_description = ?1"
+        )?;
+
+        let episode_iter = stmt.query_map(params![path], |row| {
+            self.row_to_episode(row)
+        })?;
+
+        let mut episodes = Vec::new();
+        for episode in episode_iter {
+            episodes.push(episode?);
+        }
+
+        Ok(episodes)
+    }
+
+    /// Finds nodes whose `group_id` exactly matches `path`. `CodebaseIndexer`
+    /// stores each node's source file in `group_id`, so this is used by the
+    /// incremental file-watcher to find stale nodes for a file before
+    /// re-indexing or evicting it.
+    pub fn get_nodes_by_group_id(&self, path: &str) -> Result<Vec<KGNode>> {
+        let conn = self.readers.acquire();
+
+        let mut stmt = conn.prepare(
+            "SELECT uuid, name, node_type, summary, created_at, updated_at, group_id, metadata
+             FROM nodes
+             WHERE group_id = ?1"
+        )?;
+
+        let node_iter = stmt.query_map(params![path], |row| {
+            self.row_to_node(row)
+        })?;
+
+        let mut nodes = Vec::new();
+        for node in node_iter {
+            nodes.push(node?);
+        }
+
+        Ok(nodes)
+    }
+
+    /// Finds edges whose `group_id` exactly matches `path`, the edge
+    /// counterpart of `get_nodes_by_group_id`.
+    pub fn get_edges_by_group_id(&self, path: &str) -> Result<Vec<KGEdge>> {
+        let conn = self.readers.acquire();
+
+        let mut stmt = conn.prepare(
+            "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
+             FROM edges
+             WHERE group_id = ?1"
+        )?;
+
+        let edge_iter = stmt.query_map(params![path], |row| {
+            self.row_to_edge(row)
+        })?;
+
+        let mut edges = Vec::new();
+        for edge in edge_iter {
+            edges.push(edge?);
+        }
+
+        Ok(edges)
+    }
+
+    /// Generates a new API key, persists its hash, and returns the
+    /// plaintext alongside its metadata. The plaintext is not recoverable
+    /// after this call returns — only `key_hash` is stored.
+    pub fn create_api_key(&self, name: &str, scopes: &[crate::security::api_keys::ApiKeyScope]) -> Result<crate::security::api_keys::CreatedApiKey> {
+        use crate::security::api_keys::{generate_key_material, scopes_to_column, ApiKeyRecord, CreatedApiKey};
+
+        let (plaintext, key_hash) = generate_key_material();
+        let id = Uuid::new_v4().to_string();
+        let created_at = Utc::now().to_rfc3339();
+        let scopes_column = scopes_to_column(scopes);
+
+        let conn = self.writer.lock().unwrap();
+        conn.execute(
+            "INSERT INTO api_keys (id, name, key_hash, scopes, created_at, revoked)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0)",
+            params![id, name, key_hash, scopes_column, created_at],
+        )?;
+
+        Ok(CreatedApiKey {
+            record: ApiKeyRecord {
+                id,
+                name: name.to_string(),
+                scopes: scopes.to_vec(),
+                created_at,
+                revoked: false,
+            },
+            key: plaintext,
+        })
+    }
+
+    /// Lists every key's metadata (never the plaintext, which isn't stored).
+    pub fn list_api_keys(&self) -> Result<Vec<crate::security::api_keys::ApiKeyRecord>> {
+        use crate::security::api_keys::{scopes_from_column, ApiKeyRecord};
+
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, scopes, created_at, revoked FROM api_keys ORDER BY created_at DESC"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let scopes_column: String = row.get(2)?;
+            let revoked: i64 = row.get(4)?;
+            Ok(ApiKeyRecord {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                scopes: scopes_from_column(&scopes_column),
+                created_at: row.get(3)?,
+                revoked: revoked != 0,
+            })
+        })?;
+
+        let mut keys = Vec::new();
+        for key in rows {
+            keys.push(key?);
+        }
+        Ok(keys)
+    }
+
+    /// Marks a key revoked by id. Returns `false` if no key has that id.
+    pub fn revoke_api_key(&self, id: &str) -> Result<bool> {
+        let conn = self.writer.lock().unwrap();
+        let updated = conn.execute("UPDATE api_keys SET revoked = 1 WHERE id = ?1", params![id])?;
+        Ok(updated > 0)
+    }
+
+    /// Resolves a bearer token's plaintext to its scopes, if it matches a
+    /// non-revoked key. Used by the `api_key_auth` middleware on every
+    /// authenticated HTTP/SSE request.
+    pub fn find_api_key_by_plaintext(&self, plaintext: &str) -> Result<Option<crate::security::api_keys::ApiKeyRecord>> {
+        use crate::security::api_keys::{hash_key, scopes_from_column, ApiKeyRecord};
+
+        let key_hash = hash_key(plaintext);
+        let conn = self.readers.acquire();
+
+        let record = conn.query_row(
+            "SELECT id, name, scopes, created_at, revoked FROM api_keys WHERE key_hash = ?1",
+            params![key_hash],
+            |row| {
+                let scopes_column: String = row.get(2)?;
+                let revoked: i64 = row.get(4)?;
+                Ok(ApiKeyRecord {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    scopes: scopes_from_column(&scopes_column),
+                    created_at: row.get(3)?,
+                    revoked: revoked != 0,
+                })
+            },
+        ).optional()?;
+
+        Ok(record)
+    }
+
     pub fn search_episodes_by_content(&self, query: &str, limit: usize) -> Result<Vec<Episode>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         
         // Simple content search using LIKE for now
         let mut stmt = conn.prepare(
@@ -538,7 +2316,7 @@ impl GraphStorage {
 
     /// Search edges by text in relation_type and summary fields
     pub fn search_edges_by_text(&self, query: &str, limit: usize) -> Result<Vec<KGEdge>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         
         // Search edges by relation_type and summary using LIKE
         let mut stmt = conn.prepare(
@@ -564,7 +2342,7 @@ impl GraphStorage {
 
     /// Get edges between two specific nodes
     pub fn get_edges_between_nodes(&self, source_uuid: Uuid, target_uuid: Uuid) -> Result<Vec<KGEdge>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.readers.acquire();
         
         let mut stmt = conn.prepare(
             "SELECT uuid, source_node_uuid, target_node_uuid, relation_type, summary, weight, created_at, updated_at, group_id, metadata
@@ -582,12 +2360,16 @@ impl GraphStorage {
             edges.push(edge?);
         }
 
+        let bytes: u64 = edges.iter().map(|e| (e.relation_type.len() + e.summary.len()) as u64).sum();
+        drop(conn);
+        self.record_io("get_neighbors", edges.len() as u64, 0, bytes);
+
         Ok(edges)
     }
 
     /// Delete an episode and its associated data
     pub fn delete_episode(&self, uuid: &Uuid) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer.lock().unwrap();
         
         // Delete episode-entity relationships first (due to foreign key constraints)
         conn.execute(
@@ -614,9 +2396,41 @@ impl GraphStorage {
         Ok(())
     }
 
+    /// Delete a node and its associated data
+    pub fn delete_node(&self, uuid: &Uuid) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+
+        // Delete embedding if exists
+        conn.execute(
+            "DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'node'",
+            params![uuid.to_string()],
+        )?;
+
+        // Delete episode-entity relationships
+        conn.execute(
+            "DELETE FROM episode_entities WHERE entity_uuid = ?1 AND entity_type = 'node'",
+            params![uuid.to_string()],
+        )?;
+
+        // Delete the node itself
+        let deleted = conn.execute(
+            "DELETE FROM nodes WHERE uuid = ?1",
+            params![uuid.to_string()],
+        )?;
+
+        if deleted == 0 {
+            return Err(anyhow::anyhow!("Node with UUID {} not found", uuid));
+        }
+
+        self.hnsw.write().unwrap().remove(uuid);
+        self.notify_change(format!("kg://node/{}", uuid));
+
+        Ok(())
+    }
+
     /// Delete an edge and its associated data
     pub fn delete_edge(&self, uuid: &Uuid) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer.lock().unwrap();
         
         // Delete embedding if exists
         conn.execute(
@@ -639,13 +2453,15 @@ impl GraphStorage {
         if deleted == 0 {
             return Err(anyhow::anyhow!("Edge with UUID {} not found", uuid));
         }
-        
+
+        self.notify_change(format!("kg://edge/{}", uuid));
+
         Ok(())
     }
 
     /// Clear all data from the database (destructive operation)
     pub fn clear_all_data(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer.lock().unwrap();
         
         // Disable foreign key constraints temporarily
         conn.execute("PRAGMA foreign_keys = OFF", [])?;
@@ -666,65 +2482,569 @@ impl GraphStorage {
         
         // Vacuum to reclaim space
         conn.execute("VACUUM", [])?;
-        
+
+        Ok(())
+    }
+
+    /// Row counts for `table`, optionally restricted to `group_ids` (an
+    /// empty slice means "every group"). `table` is always one of our own
+    /// hardcoded table name literals, never caller-supplied, so interpolating
+    /// it into the query string carries no injection risk.
+    fn count_rows(conn: &Connection, table: &str, group_ids: &[String]) -> Result<usize> {
+        let sql = if group_ids.is_empty() {
+            format!("SELECT COUNT(*) FROM {table}")
+        } else {
+            let placeholders = group_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            format!("SELECT COUNT(*) FROM {table} WHERE group_id IN ({placeholders})")
+        };
+        let mut stmt = conn.prepare(&sql)?;
+        let count: i64 = stmt.query_row(rusqlite::params_from_iter(group_ids.iter()), |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Total node/edge/episode counts, optionally scoped to `group_ids` (an
+    /// empty slice means "every group"). Used by `manage_graph`'s `stats`
+    /// operation for its headline counters — a `COUNT(*)` rather than
+    /// `get_nodes_page` et al., so reporting graph size doesn't require
+    /// loading every row into memory.
+    pub fn graph_counts(&self, group_ids: &[String]) -> Result<GraphCounts> {
+        let conn = self.readers.acquire();
+        Ok(GraphCounts {
+            nodes: Self::count_rows(&conn, "nodes", group_ids)?,
+            edges: Self::count_rows(&conn, "edges", group_ids)?,
+            episodes: Self::count_rows(&conn, "episodes", group_ids)?,
+        })
+    }
+
+    /// Episode count for every distinct `group_id` present in the episodes
+    /// table, optionally restricted to `group_ids` (an empty slice means
+    /// "every group"). Ungrouped episodes (`group_id IS NULL`) are reported
+    /// under the key `"ungrouped"`.
+    pub fn episode_counts_by_group(&self, group_ids: &[String]) -> Result<HashMap<String, usize>> {
+        let conn = self.readers.acquire();
+        let mut sql = "SELECT group_id, COUNT(*) FROM episodes".to_string();
+        if !group_ids.is_empty() {
+            let placeholders = group_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            sql.push_str(&format!(" WHERE group_id IN ({placeholders})"));
+        }
+        sql.push_str(" GROUP BY group_id");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(group_ids.iter()), |row| {
+            let group_id: Option<String> = row.get(0)?;
+            let count: i64 = row.get(1)?;
+            Ok((group_id.unwrap_or_else(|| "ungrouped".to_string()), count as usize))
+        })?;
+        rows.collect::<Result<HashMap<_, _>, rusqlite::Error>>().map_err(Into::into)
+    }
+
+    /// `group_id`'s row-matching predicate and the parameter to bind for it:
+    /// the `"ungrouped"` sentinel compares against `group_id IS NULL` (no
+    /// parameter needed), anything else compares against `group_id = ?`.
+    fn group_predicate(group_id: &str) -> (&'static str, Option<&str>) {
+        if group_id == "ungrouped" {
+            ("group_id IS NULL", None)
+        } else {
+            ("group_id = ?", Some(group_id))
+        }
+    }
+
+    /// Upserts the retention policy for `group_id` (the `"ungrouped"`
+    /// sentinel for episodes with no `group_id`). Takes effect the next time
+    /// `manage_graph`'s `compact` operation runs `apply_retention_policies`
+    /// — there's no background scheduler in this process to enforce it the
+    /// instant it's set.
+    pub fn set_retention_policy(
+        &self,
+        group_id: &str,
+        max_age_days: Option<i64>,
+        max_episodes: Option<i64>,
+        preserve_entities: bool,
+    ) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        conn.execute(
+            "INSERT INTO retention_policies (group_id, max_age_days, max_episodes, preserve_entities, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(group_id) DO UPDATE SET
+                max_age_days = excluded.max_age_days,
+                max_episodes = excluded.max_episodes,
+                preserve_entities = excluded.preserve_entities,
+                updated_at = excluded.updated_at",
+            params![group_id, max_age_days, max_episodes, preserve_entities as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// All stored retention policies, in no particular order.
+    pub fn list_retention_policies(&self) -> Result<Vec<RetentionPolicy>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare(
+            "SELECT group_id, max_age_days, max_episodes, preserve_entities, updated_at FROM retention_policies"
+        )?;
+        let rows = stmt.query_map([], Self::row_to_retention_policy)?;
+        rows.collect::<Result<Vec<_>, rusqlite::Error>>().map_err(Into::into)
+    }
+
+    fn row_to_retention_policy(row: &Row) -> rusqlite::Result<RetentionPolicy> {
+        Ok(RetentionPolicy {
+            group_id: row.get(0)?,
+            max_age_days: row.get(1)?,
+            max_episodes: row.get(2)?,
+            preserve_entities: row.get::<_, i64>(3)? != 0,
+            updated_at: row.get(4)?,
+        })
+    }
+
+    /// Deletes the episodes `policy` would prune right now (older than
+    /// `max_age_days` and/or beyond the newest `max_episodes`, whichever
+    /// apply) from the group it covers, and — unless `preserve_entities` is
+    /// set — garbage-collects any node/edge left with no remaining episode
+    /// reference. Mirrors `delete_episode`'s own cleanup order (junction
+    /// rows and embeddings before the episode row itself) so a prune looks
+    /// identical to N individual `delete_episode` calls from the outside.
+    pub fn apply_retention_policy(&self, policy: &RetentionPolicy) -> Result<PruneResult> {
+        let conn = self.writer.lock().unwrap();
+        let (predicate, bound_group) = Self::group_predicate(&policy.group_id);
+
+        let mut uuids_to_prune: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+        if let Some(max_age_days) = policy.max_age_days {
+            let cutoff = (Utc::now() - chrono::Duration::days(max_age_days)).to_rfc3339();
+            let sql = format!("SELECT uuid FROM episodes WHERE {predicate} AND created_at < ?");
+            let mut stmt = conn.prepare(&sql)?;
+            let rows: Vec<String> = match bound_group {
+                Some(g) => stmt.query_map(params![g, cutoff], |row| row.get(0))?.collect::<Result<_, _>>()?,
+                None => stmt.query_map(params![cutoff], |row| row.get(0))?.collect::<Result<_, _>>()?,
+            };
+            uuids_to_prune.extend(rows);
+        }
+
+        if let Some(max_episodes) = policy.max_episodes {
+            // `LIMIT -1 OFFSET ?` is SQLite's idiom for "no limit, just skip
+            // the first N" — the newest `max_episodes` are skipped, so
+            // everything returned is beyond the retained window.
+            let sql = format!("SELECT uuid FROM episodes WHERE {predicate} ORDER BY created_at DESC LIMIT -1 OFFSET ?");
+            let mut stmt = conn.prepare(&sql)?;
+            let rows: Vec<String> = match bound_group {
+                Some(g) => stmt.query_map(params![g, max_episodes], |row| row.get(0))?.collect::<Result<_, _>>()?,
+                None => stmt.query_map(params![max_episodes], |row| row.get(0))?.collect::<Result<_, _>>()?,
+            };
+            uuids_to_prune.extend(rows);
+        }
+
+        let mut bytes_reclaimed = 0u64;
+        let mut episodes_pruned = 0usize;
+        for uuid in &uuids_to_prune {
+            let content_len: Option<i64> = conn
+                .query_row("SELECT LENGTH(content) FROM episodes WHERE uuid = ?1", params![uuid], |row| row.get(0))
+                .optional()?;
+            bytes_reclaimed += content_len.unwrap_or(0) as u64;
+
+            conn.execute("DELETE FROM episode_entities WHERE episode_uuid = ?1", params![uuid])?;
+            conn.execute("DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'episode'", params![uuid])?;
+            episodes_pruned += conn.execute("DELETE FROM episodes WHERE uuid = ?1", params![uuid])?;
+        }
+
+        drop(conn);
+        let (nodes_gc, edges_gc) = if policy.preserve_entities {
+            (0, 0)
+        } else {
+            self.gc_orphaned_entities()?
+        };
+
+        Ok(PruneResult { episodes_pruned, bytes_reclaimed, nodes_gc, edges_gc })
+    }
+
+    /// Deletes every edge with no remaining `episode_entities` reference,
+    /// then every node with no remaining `episode_entities` reference that
+    /// also isn't a source/target of any surviving edge — in that order, so
+    /// a node is never removed while an edge still points at it (the
+    /// dangling-edge invariant `compact` is required to preserve).
+    pub fn gc_orphaned_entities(&self) -> Result<(usize, usize)> {
+        let conn = self.writer.lock().unwrap();
+
+        let orphan_edges: Vec<String> = conn
+            .prepare("SELECT uuid FROM edges WHERE uuid NOT IN (SELECT entity_uuid FROM episode_entities WHERE entity_type = 'edge')")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for uuid in &orphan_edges {
+            conn.execute("DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'edge'", params![uuid])?;
+        }
+        conn.execute(
+            "DELETE FROM edges WHERE uuid NOT IN (SELECT entity_uuid FROM episode_entities WHERE entity_type = 'edge')",
+            [],
+        )?;
+
+        let orphan_nodes: Vec<String> = conn
+            .prepare(
+                "SELECT uuid FROM nodes
+                 WHERE uuid NOT IN (SELECT entity_uuid FROM episode_entities WHERE entity_type = 'node')
+                   AND uuid NOT IN (SELECT source_node_uuid FROM edges)
+                   AND uuid NOT IN (SELECT target_node_uuid FROM edges)",
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for uuid in &orphan_nodes {
+            conn.execute("DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'node'", params![uuid])?;
+        }
+        conn.execute(
+            "DELETE FROM nodes
+             WHERE uuid NOT IN (SELECT entity_uuid FROM episode_entities WHERE entity_type = 'node')
+               AND uuid NOT IN (SELECT source_node_uuid FROM edges)
+               AND uuid NOT IN (SELECT target_node_uuid FROM edges)",
+            [],
+        )?;
+
+        Ok((orphan_nodes.len(), orphan_edges.len()))
+    }
+
+    /// Offline integrity-repair pass for the `kg-migrate repair` CLI
+    /// command, run against a closed server. Distinct from
+    /// `gc_orphaned_entities` (which removes entities no episode
+    /// references any more): this instead fixes up rows that violate the
+    /// schema's own invariants — an edge pointing at a node that no longer
+    /// exists (a dangling foreign key `PRAGMA foreign_keys = ON` didn't
+    /// catch because it was disabled, or was violated by a direct edit to
+    /// the database file) — then makes sure the in-memory HNSW embedding
+    /// index matches what's actually in the `embeddings` table.
+    ///
+    /// With `dry_run: true`, every check still runs and `RepairReport`
+    /// reflects what *would* change, but no `DELETE` executes and the HNSW
+    /// index is left untouched. `report.healthy()` is true iff nothing
+    /// needed fixing.
+    pub fn repair_integrity(&self, dry_run: bool) -> Result<RepairReport> {
+        let dangling_edges: Vec<String> = {
+            let conn = self.writer.lock().unwrap();
+            conn.prepare(
+                "SELECT uuid FROM edges
+                 WHERE source_node_uuid NOT IN (SELECT uuid FROM nodes)
+                    OR target_node_uuid NOT IN (SELECT uuid FROM nodes)",
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?
+        };
+
+        if !dry_run && !dangling_edges.is_empty() {
+            let conn = self.writer.lock().unwrap();
+            for uuid in &dangling_edges {
+                conn.execute("DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'edge'", params![uuid])?;
+            }
+            conn.execute(
+                "DELETE FROM edges
+                 WHERE source_node_uuid NOT IN (SELECT uuid FROM nodes)
+                    OR target_node_uuid NOT IN (SELECT uuid FROM nodes)",
+                [],
+            )?;
+        }
+
+        if !dry_run {
+            self.rebuild_hnsw_index(super::hnsw::HnswConfig::default())
+                .context("Failed to rebuild embedding index during repair")?;
+        }
+
+        let counts = self.graph_counts(&[])?;
+
+        Ok(RepairReport {
+            dangling_edges_removed: dangling_edges.len(),
+            embedding_index_rebuilt: !dry_run,
+            counts,
+            dry_run,
+        })
+    }
+
+    /// Merges exact-content-duplicate episodes within one page of up to
+    /// `limit` episodes (oldest-first, starting at `offset`), scoped to
+    /// `group_id` if given. Within each duplicate cluster the oldest
+    /// episode survives: newer duplicates have their `episode_entities`
+    /// rows reassigned to the survivor — so the entities/edges they
+    /// produced are never orphaned — before being deleted.
+    ///
+    /// This is a conservative subset of "near-duplicate" detection: it
+    /// only catches byte-identical `content`, not embedding-similar
+    /// episodes, since no per-episode embedding-similarity index exists
+    /// yet. It's still incremental and resumable like the rest of
+    /// `manage_graph`'s paginated operations, so repeated `compact` calls
+    /// across the whole episode history will still converge. Returns the
+    /// batch's result alongside whether more episodes remain beyond this
+    /// page.
+    pub fn compact_episodes(&self, group_id: Option<&str>, offset: usize, limit: usize) -> Result<(CompactResult, bool)> {
+        let conn = self.writer.lock().unwrap();
+
+        let total: i64 = match group_id {
+            Some(g) => conn.query_row("SELECT COUNT(*) FROM episodes WHERE group_id = ?1", params![g], |row| row.get(0))?,
+            None => conn.query_row("SELECT COUNT(*) FROM episodes", [], |row| row.get(0))?,
+        };
+
+        let sql = match group_id {
+            Some(_) => "SELECT uuid, content FROM episodes WHERE group_id = ?1 ORDER BY created_at ASC LIMIT ?2 OFFSET ?3",
+            None => "SELECT uuid, content FROM episodes ORDER BY created_at ASC LIMIT ?1 OFFSET ?2",
+        };
+        let mut stmt = conn.prepare(sql)?;
+        let rows: Vec<(String, String)> = match group_id {
+            Some(g) => stmt.query_map(params![g, limit, offset], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<_, _>>()?,
+            None => stmt.query_map(params![limit, offset], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<_, _>>()?,
+        };
+        drop(stmt);
+
+        let mut by_content: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for (uuid, content) in rows.into_iter() {
+            by_content.entry(content.clone()).or_default().push((uuid, content));
+        }
+
+        let mut episodes_merged = 0usize;
+        let mut bytes_reclaimed = 0u64;
+        for cluster in by_content.into_values() {
+            if cluster.len() < 2 {
+                continue;
+            }
+            let (survivor_uuid, _) = &cluster[0];
+            for (duplicate_uuid, content) in &cluster[1..] {
+                conn.execute(
+                    "UPDATE episode_entities SET episode_uuid = ?1 WHERE episode_uuid = ?2",
+                    params![survivor_uuid, duplicate_uuid],
+                )?;
+                conn.execute(
+                    "DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'episode'",
+                    params![duplicate_uuid],
+                )?;
+                conn.execute("DELETE FROM episodes WHERE uuid = ?1", params![duplicate_uuid])?;
+                episodes_merged += 1;
+                bytes_reclaimed += content.len() as u64;
+            }
+        }
+
+        let processed = offset as i64 + limit as i64;
+        let has_more = processed < total;
+
+        Ok((CompactResult { episodes_merged, bytes_reclaimed }, has_more))
+    }
+
+    /// Pins `value` (a UUID or `group_id`, per `kind`) as a GC root: `gc`
+    /// will never delete a node/edge directly matching it, regardless of
+    /// whether any retained episode still references it.
+    pub fn pin(&self, kind: AliasKind, value: &str) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO aliases (kind, value, created_at) VALUES (?1, ?2, ?3)",
+            params![kind.as_db_str(), value, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Removes a previously pinned alias. A no-op if it wasn't pinned.
+    pub fn unpin(&self, kind: AliasKind, value: &str) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        conn.execute(
+            "DELETE FROM aliases WHERE kind = ?1 AND value = ?2",
+            params![kind.as_db_str(), value],
+        )?;
         Ok(())
     }
 
+    /// All currently pinned aliases, in no particular order.
+    pub fn list_pins(&self) -> Result<Vec<(AliasKind, String)>> {
+        let conn = self.readers.acquire();
+        let mut stmt = conn.prepare("SELECT kind, value FROM aliases")?;
+        let rows = stmt.query_map([], |row| {
+            let kind: String = row.get(0)?;
+            let value: String = row.get(1)?;
+            Ok((AliasKind::from_db_str(&kind), value))
+        })?;
+        rows.collect::<Result<Vec<_>, rusqlite::Error>>().map_err(Into::into)
+    }
+
+    /// Reference-counted GC for nodes/edges no longer reachable from any
+    /// retained episode, borrowing the alias-pin + mark/sweep model from
+    /// ipfs-sqlite-block-store's block GC.
+    ///
+    /// Roots are: every episode (via `episode_entities`), plus anything
+    /// pinned in `aliases` — a pinned UUID keeps that exact node/edge, a
+    /// pinned `group_id` keeps every node/edge carrying it. From the
+    /// marked edges, their source/target nodes are marked too, so an edge
+    /// is never left dangling. Everything else — nodes/edges unreachable
+    /// from any episode and not pinned — is deleted, along with their
+    /// `embeddings` rows.
+    ///
+    /// Runs with `foreign_keys` off for the duration (SQLite only honors
+    /// that pragma outside an active transaction, hence the two pragma
+    /// calls straddling the transaction) since the mark/sweep order here
+    /// doesn't match the schema's own foreign key declarations. `VACUUM`s
+    /// afterward if `size_targets.max_bytes` is given and the database
+    /// file is still at least that large.
+    pub fn gc(&self, size_targets: Option<SizeTargets>) -> Result<GcStats> {
+        let mut conn = self.writer.lock().unwrap();
+
+        conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+
+        let pinned_group_ids: Vec<String> = {
+            let mut stmt = conn.prepare("SELECT value FROM aliases WHERE kind = 'group_id'")?;
+            stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+        };
+        let pinned_uuids: std::collections::HashSet<String> = {
+            let mut stmt = conn.prepare("SELECT value FROM aliases WHERE kind = 'uuid'")?;
+            stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut marked_edges: std::collections::HashSet<String> = {
+            let mut stmt = conn.prepare("SELECT DISTINCT entity_uuid FROM episode_entities WHERE entity_type = 'edge'")?;
+            stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+        };
+        marked_edges.extend(pinned_uuids.iter().cloned());
+        if !pinned_group_ids.is_empty() {
+            let placeholders = pinned_group_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!("SELECT uuid FROM edges WHERE group_id IN ({placeholders})");
+            let mut stmt = conn.prepare(&sql)?;
+            let rows: Vec<String> = stmt.query_map(rusqlite::params_from_iter(pinned_group_ids.iter()), |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            marked_edges.extend(rows);
+        }
+
+        let mut marked_nodes: std::collections::HashSet<String> = {
+            let mut stmt = conn.prepare("SELECT DISTINCT entity_uuid FROM episode_entities WHERE entity_type = 'node'")?;
+            stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+        };
+        marked_nodes.extend(pinned_uuids.iter().cloned());
+        if !pinned_group_ids.is_empty() {
+            let placeholders = pinned_group_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!("SELECT uuid FROM nodes WHERE group_id IN ({placeholders})");
+            let mut stmt = conn.prepare(&sql)?;
+            let rows: Vec<String> = stmt.query_map(rusqlite::params_from_iter(pinned_group_ids.iter()), |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            marked_nodes.extend(rows);
+        }
+        if !marked_edges.is_empty() {
+            let placeholders = marked_edges.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!("SELECT source_node_uuid, target_node_uuid FROM edges WHERE uuid IN ({placeholders})");
+            let mut stmt = conn.prepare(&sql)?;
+            let rows: Vec<(String, String)> = stmt
+                .query_map(rusqlite::params_from_iter(marked_edges.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            for (source, target) in rows {
+                marked_nodes.insert(source);
+                marked_nodes.insert(target);
+            }
+        }
+
+        let (nodes_deleted, edges_deleted, bytes_reclaimed) = {
+            let tx = conn.transaction()?;
+
+            let to_delete_edges: Vec<String> = if marked_edges.is_empty() {
+                tx.prepare("SELECT uuid FROM edges")?.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+            } else {
+                let placeholders = marked_edges.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+                let sql = format!("SELECT uuid FROM edges WHERE uuid NOT IN ({placeholders})");
+                tx.prepare(&sql)?
+                    .query_map(rusqlite::params_from_iter(marked_edges.iter()), |row| row.get(0))?
+                    .collect::<rusqlite::Result<_>>()?
+            };
+
+            let to_delete_nodes: Vec<String> = if marked_nodes.is_empty() {
+                tx.prepare("SELECT uuid FROM nodes")?.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?
+            } else {
+                let placeholders = marked_nodes.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+                let sql = format!("SELECT uuid FROM nodes WHERE uuid NOT IN ({placeholders})");
+                tx.prepare(&sql)?
+                    .query_map(rusqlite::params_from_iter(marked_nodes.iter()), |row| row.get(0))?
+                    .collect::<rusqlite::Result<_>>()?
+            };
+
+            let mut bytes_reclaimed = 0u64;
+
+            for uuid in &to_delete_edges {
+                let size: Option<i64> = tx
+                    .query_row("SELECT LENGTH(relation_type) + LENGTH(summary) FROM edges WHERE uuid = ?1", params![uuid], |row| row.get(0))
+                    .optional()?;
+                bytes_reclaimed += size.unwrap_or(0) as u64;
+                tx.execute("DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'edge'", params![uuid])?;
+                tx.execute("DELETE FROM episode_entities WHERE entity_uuid = ?1 AND entity_type = 'edge'", params![uuid])?;
+                tx.execute("DELETE FROM edges WHERE uuid = ?1", params![uuid])?;
+            }
+
+            for uuid in &to_delete_nodes {
+                let size: Option<i64> = tx
+                    .query_row("SELECT LENGTH(name) + LENGTH(summary) FROM nodes WHERE uuid = ?1", params![uuid], |row| row.get(0))
+                    .optional()?;
+                bytes_reclaimed += size.unwrap_or(0) as u64;
+                tx.execute("DELETE FROM embeddings WHERE uuid = ?1 AND entity_type = 'node'", params![uuid])?;
+                tx.execute("DELETE FROM episode_entities WHERE entity_uuid = ?1 AND entity_type = 'node'", params![uuid])?;
+                tx.execute("DELETE FROM nodes WHERE uuid = ?1", params![uuid])?;
+            }
+
+            tx.commit()?;
+            (to_delete_nodes.len(), to_delete_edges.len(), bytes_reclaimed)
+        };
+
+        conn.execute_batch("PRAGMA foreign_keys = ON")?;
+
+        let mut vacuumed = false;
+        if let Some(targets) = size_targets {
+            if let Some(max_bytes) = targets.max_bytes {
+                let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+                let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+                let db_bytes = (page_count * page_size) as u64;
+                if db_bytes >= max_bytes {
+                    conn.execute_batch("VACUUM")?;
+                    vacuumed = true;
+                }
+            }
+        }
+
+        Ok(GcStats { nodes_deleted, edges_deleted, bytes_reclaimed, vacuumed })
+    }
+
+    /// Reads by column name rather than position, so a migration that adds
+    /// a column to `nodes` (or reorders one in a `SELECT`) can't silently
+    /// shift these into the wrong field — every caller's query just needs
+    /// to project a column named after the `KGNode` field it feeds.
     fn row_to_node(&self, row: &Row) -> rusqlite::Result<KGNode> {
         Ok(KGNode {
-            uuid: Uuid::parse_str(&row.get::<_, String>(0)?).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
-            name: row.get(1)?,
-            node_type: row.get(2)?,
-            summary: row.get(3)?,
-            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?
-                .with_timezone(&chrono::Utc),
-            updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?
-                .with_timezone(&chrono::Utc),
-            group_id: row.get(6)?,
-            metadata: serde_json::from_str(&row.get::<_, String>(7)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?,
+            uuid: row.get::<_, KgUuid>("uuid")?.into(),
+            name: row.get("name")?,
+            node_type: row.get("node_type")?,
+            summary: row.get("summary")?,
+            created_at: row.get::<_, KgTime>("created_at")?.into(),
+            updated_at: row.get::<_, KgTime>("updated_at")?.into(),
+            group_id: row.get("group_id")?,
+            metadata: serde_json::from_str(&row.get::<_, String>("metadata")?)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
         })
     }
 
+    /// Same rationale as `row_to_node`: named lookups instead of positional
+    /// ones, so adding a column to `edges` can't shift these silently.
     fn row_to_edge(&self, row: &Row) -> rusqlite::Result<KGEdge> {
         Ok(KGEdge {
-            uuid: Uuid::parse_str(&row.get::<_, String>(0)?).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
-            source_node_uuid: Uuid::parse_str(&row.get::<_, String>(1)?).map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?,
-            target_node_uuid: Uuid::parse_str(&row.get::<_, String>(2)?).map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?,
-            relation_type: row.get(3)?,
-            summary: row.get(4)?,
-            weight: row.get(5)?,
-            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(6, rusqlite::types::Type::Text, Box::new(e)))?
-                .with_timezone(&chrono::Utc),
-            updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?
-                .with_timezone(&chrono::Utc),
-            group_id: row.get(8)?,
-            metadata: serde_json::from_str(&row.get::<_, String>(9)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Text, Box::new(e)))?,
+            uuid: row.get::<_, KgUuid>("uuid")?.into(),
+            source_node_uuid: row.get::<_, KgUuid>("source_node_uuid")?.into(),
+            target_node_uuid: row.get::<_, KgUuid>("target_node_uuid")?.into(),
+            relation_type: row.get("relation_type")?,
+            summary: row.get("summary")?,
+            weight: row.get("weight")?,
+            created_at: row.get::<_, KgTime>("created_at")?.into(),
+            updated_at: row.get::<_, KgTime>("updated_at")?.into(),
+            group_id: row.get("group_id")?,
+            metadata: serde_json::from_str(&row.get::<_, String>("metadata")?)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
         })
     }
 
+    /// Same rationale as `row_to_node`: named lookups instead of positional
+    /// ones, so adding a column to `episodes` can't shift these silently.
     fn row_to_episode(&self, row: &Row) -> rusqlite::Result<Episode> {
         Ok(Episode {
-            uuid: Uuid::parse_str(&row.get::<_, String>(0)?).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
-            name: row.get(1)?,
-            content: row.get(2)?,
-            source: serde_json::from_str(&row.get::<_, String>(3)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e)))?,
-            source_description: row.get(4)?,
-            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?
-                .with_timezone(&chrono::Utc),
-            group_id: row.get(6)?,
+            uuid: row.get::<_, KgUuid>("uuid")?.into(),
+            name: row.get("name")?,
+            content: row.get("content")?,
+            source: serde_json::from_str(&row.get::<_, String>("source")?)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
+            source_description: row.get("source_description")?,
+            created_at: row.get::<_, KgTime>("created_at")?.into(),
+            group_id: row.get("group_id")?,
             entity_uuids: Vec::new(),
             edge_uuids: Vec::new(),
             embedding: None,
-            metadata: serde_json::from_str(&row.get::<_, String>(7)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, Box::new(e)))?,
+            metadata: serde_json::from_str(&row.get::<_, String>("metadata")?)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?,
         })
     }
 } 
\ No newline at end of file
@@ -0,0 +1,127 @@
+//! Cargo-workspace-aware enrichment for `analyze_structure`: shells out to
+//! the system `cargo` binary rather than pulling in the `cargo_metadata`
+//! crate, since `serde_json::Value` is already enough to pull the handful
+//! of fields this needs out of `cargo metadata --format-version 1` (see
+//! `git_history` for the same shell-out-over-new-dependency call when a
+//! platform/tool capability isn't worth a crate).
+//!
+//! Degrades to `None` instead of returning an error when `root_path` has no
+//! `Cargo.toml`, `cargo` isn't on `PATH`, or the invocation fails for any
+//! other reason — "not a cargo workspace" is an expected outcome (a
+//! non-Rust repo, a source snapshot with no manifest), not a failure worth
+//! aborting `analyze_structure` over.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tracing::warn;
+
+/// One package from `cargo metadata`'s `packages` array, tagged with
+/// whether it's a workspace member (vs. a dependency resolved from the
+/// registry/git/path) so `analyze_structure` can tell intra-crate edges
+/// from cross-crate ones.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CargoPackageInfo {
+    pub name: String,
+    pub version: String,
+    pub edition: String,
+    pub manifest_path: PathBuf,
+    pub is_workspace_member: bool,
+    pub dependencies: Vec<CargoDependencyInfo>,
+}
+
+/// One entry from a package's `dependencies` array: the dependency's name,
+/// declared kind (`"normal"`, `"dev"`, or `"build"`), and version
+/// requirement as written in `Cargo.toml`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CargoDependencyInfo {
+    pub name: String,
+    pub kind: String,
+    pub req: String,
+}
+
+/// Runs `cargo metadata --format-version 1` at `root_path` and parses the
+/// result into one `CargoPackageInfo` per package (workspace members and
+/// their resolved dependencies alike). Returns `None` if `root_path` has no
+/// `Cargo.toml`, `cargo` can't be run, or its output doesn't parse —
+/// callers only need to check `use_cargo_metadata` before calling this, not
+/// whether the target is actually a cargo project.
+pub fn workspace_metadata(root_path: &Path) -> Option<Vec<CargoPackageInfo>> {
+    if !root_path.join("Cargo.toml").is_file() {
+        return None;
+    }
+
+    let output = Command::new("cargo")
+        .current_dir(root_path)
+        .args(["metadata", "--format-version", "1"])
+        .output();
+
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Failed to run cargo metadata in {}: {}", root_path.display(), e);
+            return None;
+        }
+    };
+
+    if !output.status.success() {
+        warn!(
+            "cargo metadata failed in {}: {}",
+            root_path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return None;
+    }
+
+    let metadata: serde_json::Value = match serde_json::from_slice(&output.stdout) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warn!("Failed to parse cargo metadata output for {}: {}", root_path.display(), e);
+            return None;
+        }
+    };
+
+    let workspace_members: HashSet<&str> = metadata
+        .get("workspace_members")
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+
+    let packages = metadata.get("packages").and_then(|v| v.as_array())?;
+
+    Some(
+        packages
+            .iter()
+            .map(|pkg| {
+                let id = pkg.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                let dependencies = pkg
+                    .get("dependencies")
+                    .and_then(|v| v.as_array())
+                    .map(|deps| {
+                        deps.iter()
+                            .map(|dep| CargoDependencyInfo {
+                                name: dep.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                                kind: dep.get("kind").and_then(|v| v.as_str()).unwrap_or("normal").to_string(),
+                                req: dep.get("req").and_then(|v| v.as_str()).unwrap_or("*").to_string(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                CargoPackageInfo {
+                    name: pkg.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    version: pkg.get("version").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    edition: pkg.get("edition").and_then(|v| v.as_str()).unwrap_or("2015").to_string(),
+                    manifest_path: pkg
+                        .get("manifest_path")
+                        .and_then(|v| v.as_str())
+                        .map(PathBuf::from)
+                        .unwrap_or_default(),
+                    is_workspace_member: workspace_members.contains(id),
+                    dependencies,
+                }
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,854 @@
+//! Syntax-aware chunking for code ingestion.
+//!
+//! `LanguageDetector` only maps a file extension to a `SupportedLanguage`; this
+//! module is what actually consumes that result. `CodeChunker` parses source
+//! with the tree-sitter grammar matching the detected language and splits it
+//! into embedding-sized chunks that respect syntactic boundaries (functions,
+//! classes, impl blocks) instead of fixed byte windows:
+//!
+//! - Walk the parse tree top-down, greedily accumulating sibling nodes into a
+//!   chunk while the estimated token count stays under `max_tokens`.
+//! - When a single node exceeds the budget on its own, descend into its
+//!   children and recurse.
+//! - When a node is small, merge it with the following siblings.
+//!
+//! Languages with no tree-sitter grammar wired up here (and `Text`/`Unknown`)
+//! fall back to paragraph/newline windowing.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+use super::language_support::SupportedLanguage;
+pub use crate::context::ChunkType;
+
+/// A syntactically-bounded piece of source, ready to become an `Episode`.
+#[derive(Debug, Clone)]
+pub struct CodeChunk {
+    pub file_path: PathBuf,
+    pub content: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub chunk_type: ChunkType,
+}
+
+/// A single named symbol span extracted from a parsed syntax tree: a
+/// function, method, struct/class, enum, trait/interface, or module
+/// definition, with its exact source range. Unlike `CodeChunk` (which
+/// greedily groups sibling nodes into embedding-sized windows), one
+/// `CodeBlock` is emitted per real symbol, so a caller that needs "what
+/// declares `foo`, and at what line/column" gets an accurate answer
+/// instead of having to infer it from the nearest chunk boundary.
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    pub content: String,
+    pub block_type: String,
+    pub name: String,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// Raw line count of `content` (see `calculate_loc`).
+    pub loc: usize,
+    /// Non-blank, non-single-line-comment line count of `content` (see
+    /// `calculate_loc`).
+    pub logical_loc: usize,
+    /// This symbol's McCabe cyclomatic complexity, looked up from
+    /// `calculate_complexity` by name. `0` for a block type complexity
+    /// isn't defined over (anything but `function`/`method`).
+    pub complexity: u32,
+    /// Shannon entropy (bits) over `content`'s identifier/keyword token
+    /// distribution (see `calculate_token_entropy`).
+    pub entropy: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkerConfig {
+    /// Soft token budget per chunk. A node (or accumulated run of sibling
+    /// nodes) is flushed once its estimated token count reaches this.
+    pub max_tokens: usize,
+}
+
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        Self { max_tokens: 512 }
+    }
+}
+
+pub struct CodeChunker {
+    config: ChunkerConfig,
+}
+
+impl CodeChunker {
+    pub fn new(config: ChunkerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Chunks `source` from `file_path` according to `language`'s grammar,
+    /// falling back to paragraph/newline windowing when no grammar is wired
+    /// up for that language.
+    pub fn chunk_source(&self, source: &str, language: &SupportedLanguage, file_path: &Path) -> Vec<CodeChunk> {
+        match tree_sitter_language_for(language) {
+            Some(ts_language) => {
+                let mut parser = Parser::new();
+                if parser.set_language(ts_language).is_err() {
+                    return self.chunk_by_text_windows(source, file_path);
+                }
+                match parser.parse(source, None) {
+                    Some(tree) => {
+                        let mut chunks = Vec::new();
+                        self.chunk_node(tree.root_node(), source, language, file_path, &mut chunks);
+                        if chunks.is_empty() {
+                            self.chunk_by_text_windows(source, file_path)
+                        } else {
+                            chunks
+                        }
+                    }
+                    None => self.chunk_by_text_windows(source, file_path),
+                }
+            }
+            None => self.chunk_by_text_windows(source, file_path),
+        }
+    }
+
+    /// Greedily accumulates `node`'s children into token-budget-sized runs,
+    /// recursing into any single child that alone exceeds the budget.
+    fn chunk_node(&self, node: Node, source: &str, language: &SupportedLanguage, file_path: &Path, chunks: &mut Vec<CodeChunk>) {
+        let mut run_start: Option<Node> = None;
+        let mut run_end: Option<Node> = None;
+        let mut run_tokens = 0usize;
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+
+        let flush = |run_start: &mut Option<Node>, run_end: &mut Option<Node>, chunks: &mut Vec<CodeChunk>| {
+            if let (Some(start), Some(end)) = (*run_start, *run_end) {
+                // A run can span several coalesced sibling nodes (small
+                // imports/consts merged up to the token budget); the first
+                // node's kind stands in for the whole run's `chunk_type`.
+                let chunk_type = classify_node_kind(language, start.kind());
+                chunks.push(make_chunk(source, file_path, start.start_byte(), end.end_byte(), chunk_type));
+            }
+            *run_start = None;
+            *run_end = None;
+        };
+
+        for child in children {
+            let child_tokens = estimate_tokens(child.end_byte() - child.start_byte());
+
+            if child_tokens > self.config.max_tokens {
+                // This single node is too big on its own: flush whatever run
+                // we'd accumulated, then descend into the node's own children.
+                flush(&mut run_start, &mut run_end, chunks);
+                run_tokens = 0;
+                if child.child_count() > 0 {
+                    self.chunk_node(child, source, language, file_path, chunks);
+                } else {
+                    // Leaf node with no children to descend into (e.g. a huge
+                    // string literal) — emit it as its own oversized chunk
+                    // rather than silently dropping it.
+                    let chunk_type = classify_node_kind(language, child.kind());
+                    chunks.push(make_chunk(source, file_path, child.start_byte(), child.end_byte(), chunk_type));
+                }
+                continue;
+            }
+
+            if run_tokens + child_tokens > self.config.max_tokens {
+                flush(&mut run_start, &mut run_end, chunks);
+                run_tokens = 0;
+            }
+
+            if run_start.is_none() {
+                run_start = Some(child);
+            }
+            run_end = Some(child);
+            run_tokens += child_tokens;
+        }
+
+        flush(&mut run_start, &mut run_end, chunks);
+    }
+
+    /// Paragraph-windowed fallback for languages with no tree-sitter grammar
+    /// (plain text, Markdown prose, or genuinely unrecognized files).
+    fn chunk_by_text_windows(&self, source: &str, file_path: &Path) -> Vec<CodeChunk> {
+        let mut chunks = Vec::new();
+        let mut byte_offset = 0usize;
+        let mut line_offset = 0usize;
+
+        let mut window = String::new();
+        let mut window_start_byte = 0usize;
+        let mut window_start_line = 0usize;
+        let mut window_tokens = 0usize;
+
+        for paragraph in source.split("\n\n") {
+            let paragraph_with_sep = if byte_offset + paragraph.len() < source.len() {
+                format!("{}\n\n", paragraph)
+            } else {
+                paragraph.to_string()
+            };
+            let paragraph_tokens = estimate_tokens(paragraph_with_sep.len());
+
+            if !window.is_empty() && window_tokens + paragraph_tokens > self.config.max_tokens {
+                chunks.push(CodeChunk {
+                    file_path: file_path.to_path_buf(),
+                    content: window.clone(),
+                    start_byte: window_start_byte,
+                    end_byte: window_start_byte + window.len(),
+                    start_line: window_start_line,
+                    end_line: line_offset,
+                    chunk_type: ChunkType::Documentation,
+                });
+                window.clear();
+                window_tokens = 0;
+                window_start_byte = byte_offset;
+                window_start_line = line_offset;
+            }
+
+            if window.is_empty() {
+                window_start_byte = byte_offset;
+                window_start_line = line_offset;
+            }
+            window.push_str(&paragraph_with_sep);
+            window_tokens += paragraph_tokens;
+
+            byte_offset += paragraph_with_sep.len();
+            line_offset += paragraph_with_sep.matches('\n').count();
+        }
+
+        if !window.is_empty() {
+            chunks.push(CodeChunk {
+                file_path: file_path.to_path_buf(),
+                content: window.clone(),
+                start_byte: window_start_byte,
+                end_byte: window_start_byte + window.len(),
+                start_line: window_start_line,
+                end_line: line_offset,
+                chunk_type: ChunkType::Documentation,
+            });
+        }
+
+        chunks
+    }
+}
+
+fn make_chunk(source: &str, file_path: &Path, start_byte: usize, end_byte: usize, chunk_type: ChunkType) -> CodeChunk {
+    let start_line = source[..start_byte].matches('\n').count();
+    let end_line = source[..end_byte].matches('\n').count();
+    CodeChunk {
+        file_path: file_path.to_path_buf(),
+        content: source[start_byte..end_byte].to_string(),
+        start_byte,
+        end_byte,
+        start_line,
+        end_line,
+        chunk_type,
+    }
+}
+
+/// Maps a tree-sitter node `kind` (e.g. `"function_item"`, `"class_definition"`)
+/// to the coarse `ChunkType` buckets `CodeChunk` exposes. Each language names
+/// its grammar's nodes differently, so this matches per-`language` rather
+/// than a single kind string across all of them.
+fn classify_node_kind(language: &SupportedLanguage, kind: &str) -> ChunkType {
+    match language {
+        SupportedLanguage::Rust => match kind {
+            "function_item" | "closure_expression" => ChunkType::Function,
+            "struct_item" | "enum_item" | "impl_item" | "trait_item" => ChunkType::Class,
+            "use_declaration" | "mod_item" | "extern_crate_declaration" => ChunkType::Import,
+            _ => ChunkType::Code,
+        },
+        SupportedLanguage::Python => match kind {
+            "function_definition" | "lambda" => ChunkType::Function,
+            "class_definition" => ChunkType::Class,
+            "import_statement" | "import_from_statement" => ChunkType::Import,
+            _ => ChunkType::Code,
+        },
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => match kind {
+            "function_declaration" | "function" | "arrow_function" | "method_definition" => ChunkType::Function,
+            "class_declaration" | "interface_declaration" => ChunkType::Class,
+            "import_statement" | "export_statement" => ChunkType::Import,
+            _ => ChunkType::Code,
+        },
+        SupportedLanguage::Go => match kind {
+            "function_declaration" | "method_declaration" | "func_literal" => ChunkType::Function,
+            "type_declaration" | "struct_type" | "interface_type" => ChunkType::Class,
+            "import_declaration" => ChunkType::Import,
+            _ => ChunkType::Code,
+        },
+        _ => ChunkType::Code,
+    }
+}
+
+/// Walks `source`'s syntax tree (or a line-prefix keyword heuristic, for
+/// languages with no grammar wired up) and returns one `CodeBlock` per
+/// function/method/struct/class/enum/trait/module symbol found. This is
+/// the symbol-accurate counterpart to `chunk_source`'s coarser,
+/// token-budget-driven windows.
+pub fn extract_symbols(source: &str, language: &SupportedLanguage) -> Vec<CodeBlock> {
+    let mut blocks = match tree_sitter_language_for(language) {
+        Some(ts_language) => {
+            let mut parser = Parser::new();
+            if parser.set_language(ts_language).is_err() {
+                extract_symbols_heuristic(source)
+            } else {
+                match parser.parse(source, None) {
+                    Some(tree) => {
+                        let mut blocks = Vec::new();
+                        walk_for_symbols(tree.root_node(), source, language, &mut blocks);
+                        blocks
+                    }
+                    None => extract_symbols_heuristic(source),
+                }
+            }
+        }
+        None => extract_symbols_heuristic(source),
+    };
+    annotate_symbol_metrics(&mut blocks, source, language);
+    blocks
+}
+
+/// Fills in every block's `loc`/`logical_loc`/`entropy` from its own
+/// `content`, and `complexity` from `calculate_complexity`'s per-function
+/// scores for the whole file (`0` for a block type complexity isn't
+/// defined over). Run as a final pass over `extract_symbols`'s result so
+/// both the tree-sitter and heuristic code paths get the same treatment.
+fn annotate_symbol_metrics(blocks: &mut [CodeBlock], source: &str, language: &SupportedLanguage) {
+    let complexity_by_name = calculate_complexity(source, language);
+    for block in blocks.iter_mut() {
+        let (loc, logical_loc) = calculate_loc(&block.content, language);
+        block.loc = loc;
+        block.logical_loc = logical_loc;
+        block.entropy = calculate_token_entropy(&block.content);
+        block.complexity = complexity_by_name.get(&block.name).copied().unwrap_or(0);
+    }
+}
+
+/// Computes per-function McCabe cyclomatic complexity by walking the parse
+/// tree's decision nodes (if/else branches, loops, match/switch arms,
+/// `&&`/`||`, catch/except clauses, early returns), instead of counting
+/// substrings like `"if"` — which also matches identifiers, comments, and
+/// strings (e.g. `verify` contains `"if"`). Keyed by symbol name, the same
+/// names `extract_symbols` reports, so a file with several functions gets a
+/// score per function instead of one number for the whole file. Returns an
+/// empty map for languages with no tree-sitter grammar wired up in
+/// `tree_sitter_language_for`, since a substring count isn't meaningfully
+/// better than no number at all.
+pub fn calculate_complexity(source: &str, language: &SupportedLanguage) -> std::collections::HashMap<String, u32> {
+    let mut scores = std::collections::HashMap::new();
+
+    let Some(ts_language) = tree_sitter_language_for(language) else {
+        return scores;
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(ts_language).is_err() {
+        return scores;
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return scores;
+    };
+
+    collect_function_complexity(tree.root_node(), source, language, &mut scores);
+    scores
+}
+
+/// Computes raw and logical line counts for a span of source text: `loc` is
+/// simply `content.lines().count()`; `logical_loc` additionally drops blank
+/// lines and ones that are entirely a single-line comment. This is a
+/// line-prefix heuristic, not a lexer — it recognizes `language`'s
+/// single-line comment marker but doesn't track multi-line comment/string
+/// spans, so a line inside a block comment or docstring still counts
+/// toward `logical_loc`.
+pub fn calculate_loc(content: &str, language: &SupportedLanguage) -> (usize, usize) {
+    let comment_prefix = line_comment_prefix(language);
+    let loc = content.lines().count();
+    let logical_loc = content
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !comment_prefix.is_some_and(|p| trimmed.starts_with(p))
+        })
+        .count();
+    (loc, logical_loc)
+}
+
+fn line_comment_prefix(language: &SupportedLanguage) -> Option<&'static str> {
+    match language {
+        SupportedLanguage::Rust
+        | SupportedLanguage::JavaScript
+        | SupportedLanguage::TypeScript
+        | SupportedLanguage::Java
+        | SupportedLanguage::Cpp
+        | SupportedLanguage::C
+        | SupportedLanguage::Go
+        | SupportedLanguage::CSharp
+        | SupportedLanguage::Swift
+        | SupportedLanguage::Kotlin
+        | SupportedLanguage::Scala
+        | SupportedLanguage::Dart => Some("//"),
+        SupportedLanguage::Python | SupportedLanguage::Ruby | SupportedLanguage::Toml | SupportedLanguage::Yaml => Some("#"),
+        SupportedLanguage::Haskell | SupportedLanguage::Elm => Some("--"),
+        SupportedLanguage::Clojure => Some(";"),
+        _ => None,
+    }
+}
+
+/// Shannon entropy (bits) over the distribution of identifier/keyword
+/// tokens in `content`: `H = -Σ p_i·log2(p_i)` where `p_i` is token `i`'s
+/// share of all tokens. A token is a maximal run of alphanumeric/`_`
+/// characters, so operators and punctuation don't participate — that's
+/// enough to tell a repetitive, boilerplate-heavy symbol (lower entropy,
+/// few distinct tokens repeated often) apart from one with a broad,
+/// varied vocabulary (higher entropy), without needing a real per-language
+/// lexer. Returns `0.0` for a span with no tokens at all.
+pub fn calculate_token_entropy(content: &str) -> f64 {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut total = 0usize;
+    let mut token_start: Option<usize> = None;
+
+    let is_token_char = |c: char| c.is_alphanumeric() || c == '_';
+    for (i, c) in content.char_indices() {
+        if is_token_char(c) {
+            token_start.get_or_insert(i);
+        } else if let Some(start) = token_start.take() {
+            *counts.entry(&content[start..i]).or_insert(0) += 1;
+            total += 1;
+        }
+    }
+    if let Some(start) = token_start {
+        *counts.entry(&content[start..]).or_insert(0) += 1;
+        total += 1;
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+    counts.values().fold(0.0, |acc, &count| {
+        let p = count as f64 / total as f64;
+        acc - p * p.log2()
+    })
+}
+
+/// Recurses looking for function/method-like nodes; each one's complexity is
+/// computed over its own subtree, not descending into further nested
+/// functions (which get their own entry instead of inflating this one's).
+fn collect_function_complexity(node: Node, source: &str, language: &SupportedLanguage, scores: &mut std::collections::HashMap<String, u32>) {
+    if is_function_like(language, node.kind()) {
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("<anonymous>")
+            .to_string();
+        scores.insert(name, 1 + count_decision_points(node, language));
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_function_complexity(child, source, language, scores);
+    }
+}
+
+fn is_function_like(language: &SupportedLanguage, kind: &str) -> bool {
+    matches!(symbol_block_type(language, kind), Some("function") | Some("method"))
+}
+
+/// Counts decision points in `node`'s subtree, skipping over nested
+/// function/method definitions (counted separately, against their own name).
+fn count_decision_points(node: Node, language: &SupportedLanguage) -> u32 {
+    let mut count = 0u32;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_function_like(language, child.kind()) {
+            continue;
+        }
+        if is_decision_node(language, &child) {
+            count += 1;
+        }
+        count += count_decision_points(child, language);
+    }
+    count
+}
+
+/// Whether `node` is itself a branch point McCabe complexity counts: a
+/// branch/loop/arm/clause/early-return keyword node, or a `&&`/`||`
+/// (`and`/`or` in Python) logical operator — each of which is another path
+/// through the function.
+fn is_decision_node(language: &SupportedLanguage, node: &Node) -> bool {
+    let kind = node.kind();
+    match language {
+        SupportedLanguage::Rust => {
+            matches!(
+                kind,
+                "if_expression" | "if_let_expression" | "while_expression" | "while_let_expression"
+                    | "for_expression" | "match_arm" | "return_expression"
+            ) || is_logical_op(node)
+        }
+        SupportedLanguage::Python => {
+            matches!(
+                kind,
+                "if_statement" | "elif_clause" | "for_statement" | "while_statement"
+                    | "except_clause" | "return_statement"
+            ) || is_logical_op(node)
+        }
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+            matches!(
+                kind,
+                "if_statement" | "for_statement" | "for_in_statement" | "while_statement"
+                    | "do_statement" | "catch_clause" | "switch_case" | "ternary_expression"
+                    | "return_statement"
+            ) || is_logical_op(node)
+        }
+        SupportedLanguage::Go => {
+            matches!(
+                kind,
+                "if_statement" | "for_statement" | "expression_case" | "communication_case"
+                    | "return_statement"
+            ) || is_logical_op(node)
+        }
+        _ => false,
+    }
+}
+
+/// `&&`/`||` (`and`/`or` in Python) each add another path through a
+/// function, same as an extra branch — tree-sitter gives the operator its
+/// own child node whose `kind()` is the operator token itself.
+fn is_logical_op(node: &Node) -> bool {
+    matches!(node.kind(), "binary_expression" | "boolean_operator")
+        && node
+            .child_by_field_name("operator")
+            .map(|op| matches!(op.kind(), "&&" | "||" | "and" | "or"))
+            .unwrap_or(false)
+}
+
+fn walk_for_symbols(node: Node, source: &str, language: &SupportedLanguage, blocks: &mut Vec<CodeBlock>) {
+    if let Some(block_type) = symbol_block_type(language, node.kind()) {
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+            .unwrap_or("<anonymous>")
+            .to_string();
+        let start = node.start_position();
+        blocks.push(CodeBlock {
+            content: source[node.start_byte()..node.end_byte()].to_string(),
+            block_type: block_type.to_string(),
+            name,
+            start_line: start.row,
+            start_col: start.column,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            // Filled in by `annotate_symbol_metrics` once every block has
+            // been collected.
+            loc: 0,
+            logical_loc: 0,
+            complexity: 0,
+            entropy: 0.0,
+        });
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk_for_symbols(child, source, language, blocks);
+    }
+}
+
+/// Maps a tree-sitter node `kind` to a symbol's `block_type`, or `None` if
+/// `kind` isn't a symbol-defining node at all (e.g. an expression or
+/// statement we don't want a `CodeBlock` for).
+pub(crate) fn symbol_block_type(language: &SupportedLanguage, kind: &str) -> Option<&'static str> {
+    match language {
+        SupportedLanguage::Rust => match kind {
+            "function_item" => Some("function"),
+            "struct_item" => Some("struct"),
+            "enum_item" => Some("enum"),
+            "trait_item" => Some("trait"),
+            "impl_item" => Some("impl"),
+            "mod_item" => Some("module"),
+            _ => None,
+        },
+        SupportedLanguage::Python => match kind {
+            "function_definition" => Some("function"),
+            "class_definition" => Some("class"),
+            _ => None,
+        },
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => match kind {
+            "function_declaration" | "function" => Some("function"),
+            "method_definition" => Some("method"),
+            "class_declaration" => Some("class"),
+            "interface_declaration" => Some("interface"),
+            _ => None,
+        },
+        SupportedLanguage::Go => match kind {
+            "function_declaration" => Some("function"),
+            "method_declaration" => Some("method"),
+            "type_declaration" => Some("type"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Naive fallback for languages with no tree-sitter grammar wired up in
+/// `tree_sitter_language_for`: a line starting with a declaration keyword
+/// opens a block, its name is the following identifier, and the block's
+/// content is just that line (no brace/indentation tracking to find where
+/// it ends). This is exactly the kind of fragile heuristic the tree-sitter
+/// path above exists to replace, so it only ever runs as a last resort.
+fn extract_symbols_heuristic(source: &str) -> Vec<CodeBlock> {
+    const KEYWORDS: &[(&str, &str)] = &[
+        ("fn ", "function"),
+        ("function ", "function"),
+        ("def ", "function"),
+        ("class ", "class"),
+        ("struct ", "struct"),
+        ("interface ", "interface"),
+    ];
+
+    let mut blocks = Vec::new();
+    let mut byte_offset = 0usize;
+
+    for (line_idx, line) in source.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let col = line.len() - trimmed.len();
+
+        for (keyword, block_type) in KEYWORDS {
+            if let Some(rest) = trimmed.strip_prefix(keyword) {
+                let name = rest
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .find(|s| !s.is_empty())
+                    .unwrap_or("<anonymous>")
+                    .to_string();
+
+                blocks.push(CodeBlock {
+                    content: line.to_string(),
+                    block_type: block_type.to_string(),
+                    name,
+                    start_line: line_idx,
+                    start_col: col,
+                    start_byte: byte_offset + col,
+                    end_byte: byte_offset + line.len(),
+                    // Filled in by `annotate_symbol_metrics`.
+                    loc: 0,
+                    logical_loc: 0,
+                    complexity: 0,
+                    entropy: 0.0,
+                });
+                break;
+            }
+        }
+
+        byte_offset += line.len() + 1; // +1 for the '\n' that `.lines()` strips
+    }
+
+    blocks
+}
+
+/// Tree-sitter node kinds that represent an import/use declaration in each
+/// language's grammar.
+pub(crate) fn import_node_kind(language: &SupportedLanguage, kind: &str) -> bool {
+    match language {
+        SupportedLanguage::Rust => kind == "use_declaration",
+        SupportedLanguage::Python => matches!(kind, "import_statement" | "import_from_statement"),
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => kind == "import_statement",
+        SupportedLanguage::Go => kind == "import_spec",
+        _ => false,
+    }
+}
+
+/// Finds every import/use declaration in `source`'s parse tree and returns
+/// its exact source text and 1-based line number, for a caller to parse into
+/// a structured dependency. This is what makes dependency extraction
+/// AST-driven rather than a per-line regex: a string or comment that merely
+/// contains the word `"import"` can never be mistaken for a real
+/// declaration, and the line number always points at the real node instead
+/// of wherever the pattern happened to match first.
+///
+/// Returns `None` for languages with no tree-sitter grammar wired up in
+/// `tree_sitter_language_for`, so the caller knows to fall back to a
+/// line-pattern heuristic only where there's truly no parse tree to walk.
+pub fn extract_import_nodes(source: &str, language: &SupportedLanguage) -> Option<Vec<(String, usize)>> {
+    let ts_language = tree_sitter_language_for(language)?;
+    let mut parser = Parser::new();
+    if parser.set_language(ts_language).is_err() {
+        return None;
+    }
+    let tree = parser.parse(source, None)?;
+
+    let mut imports = Vec::new();
+    walk_for_imports(tree.root_node(), source, language, &mut imports);
+    Some(imports)
+}
+
+fn walk_for_imports(node: Node, source: &str, language: &SupportedLanguage, imports: &mut Vec<(String, usize)>) {
+    if import_node_kind(language, node.kind()) {
+        if let Ok(text) = node.utf8_text(source.as_bytes()) {
+            imports.push((text.to_string(), node.start_position().row + 1));
+        }
+        return; // an import declaration's own children are never more imports
+    }
+
+    // CommonJS `require(...)` has no dedicated declaration node in the JS/TS
+    // grammar — it's an ordinary call expression — so it's matched by name
+    // instead of by kind.
+    if matches!(language, SupportedLanguage::JavaScript | SupportedLanguage::TypeScript)
+        && node.kind() == "call_expression"
+    {
+        let is_require = node
+            .child_by_field_name("function")
+            .and_then(|f| f.utf8_text(source.as_bytes()).ok())
+            .map(|name| name == "require")
+            .unwrap_or(false);
+        if is_require {
+            if let Ok(text) = node.utf8_text(source.as_bytes()) {
+                imports.push((text.to_string(), node.start_position().row + 1));
+            }
+            return;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk_for_imports(child, source, language, imports);
+    }
+}
+
+/// One resolved call expression found inside a function/method body: the
+/// enclosing symbol's name (`caller`, `"<module>"` if the call sits at file
+/// scope rather than inside any function) invoking `callee` at `line`
+/// (1-based). Emitted by `extract_call_edges`, the call-graph counterpart to
+/// `extract_import_nodes`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CallEdge {
+    pub caller: String,
+    pub callee: String,
+    pub line: usize,
+}
+
+/// Tree-sitter node kinds that represent a function/method definition whose
+/// body should be attributed to its own name as `CallEdge::caller`, rather
+/// than whatever enclosed it.
+fn function_node_kind(language: &SupportedLanguage, kind: &str) -> bool {
+    match language {
+        SupportedLanguage::Rust => kind == "function_item",
+        SupportedLanguage::Python => kind == "function_definition",
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => {
+            matches!(kind, "function_declaration" | "method_definition" | "function" | "generator_function_declaration")
+        }
+        SupportedLanguage::Go => matches!(kind, "function_declaration" | "method_declaration"),
+        _ => false,
+    }
+}
+
+/// Tree-sitter node kinds that represent a call expression in each
+/// language's grammar. Rust's method calls (`receiver.method()`) parse as a
+/// distinct `method_call_expression` node from a plain `call_expression`
+/// (`function()`), so both are matched for Rust.
+fn call_node_kind(language: &SupportedLanguage, kind: &str) -> bool {
+    match language {
+        SupportedLanguage::Rust => matches!(kind, "call_expression" | "method_call_expression"),
+        SupportedLanguage::Python => kind == "call",
+        SupportedLanguage::JavaScript | SupportedLanguage::TypeScript => kind == "call_expression",
+        SupportedLanguage::Go => kind == "call_expression",
+        _ => false,
+    }
+}
+
+/// The name a function/method definition node declares, from its grammar's
+/// `name` field. `None` for an anonymous function expression (a JS/TS
+/// `function`/arrow function with no `name` field).
+fn function_node_name<'a>(node: Node, source: &'a str) -> Option<&'a str> {
+    node.child_by_field_name("name")?.utf8_text(source.as_bytes()).ok()
+}
+
+/// The name being invoked by a call expression node, or `None` if it can't
+/// be resolved to a plain identifier (a call through an arbitrary
+/// expression, e.g. `(f())()`, has no single name worth recording). Method
+/// calls (`receiver.method()`, `obj.Method()`) resolve to just the method
+/// name, not the receiver — matching how `search_code_mcp`'s symbol index is
+/// keyed by bare symbol name rather than a fully-qualified path.
+fn call_node_callee<'a>(node: Node, source: &'a str, language: &SupportedLanguage) -> Option<&'a str> {
+    match (language, node.kind()) {
+        (SupportedLanguage::Rust, "method_call_expression") => {
+            node.child_by_field_name("name")?.utf8_text(source.as_bytes()).ok()
+        }
+        _ => {
+            let callee = node.child_by_field_name("function")?;
+            match callee.kind() {
+                "field_expression" | "member_expression" | "selector_expression" | "attribute" => callee
+                    .child_by_field_name("property")
+                    .or_else(|| callee.child_by_field_name("field"))
+                    .or_else(|| callee.child_by_field_name("attribute"))
+                    .and_then(|n| n.utf8_text(source.as_bytes()).ok()),
+                _ => callee.utf8_text(source.as_bytes()).ok(),
+            }
+        }
+    }
+}
+
+/// Finds every resolved call expression in `source`'s parse tree, attributed
+/// to its enclosing function/method by name, for the `call_hierarchy`
+/// indexing operation. Mirrors `extract_import_nodes`: AST-driven so a
+/// string or comment that merely looks like a call is never mistaken for
+/// one, and returns `None` for languages with no tree-sitter grammar wired
+/// up in `tree_sitter_language_for` rather than guessing from text.
+pub fn extract_call_edges(source: &str, language: &SupportedLanguage) -> Option<Vec<CallEdge>> {
+    let ts_language = tree_sitter_language_for(language)?;
+    let mut parser = Parser::new();
+    if parser.set_language(ts_language).is_err() {
+        return None;
+    }
+    let tree = parser.parse(source, None)?;
+
+    let mut edges = Vec::new();
+    walk_for_calls(tree.root_node(), source, language, "<module>", &mut edges);
+    Some(edges)
+}
+
+fn walk_for_calls(node: Node, source: &str, language: &SupportedLanguage, current_caller: &str, edges: &mut Vec<CallEdge>) {
+    if function_node_kind(language, node.kind()) {
+        let name = function_node_name(node, source).unwrap_or("<anonymous>");
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            walk_for_calls(child, source, language, name, edges);
+        }
+        return;
+    }
+
+    if call_node_kind(language, node.kind()) {
+        if let Some(callee) = call_node_callee(node, source, language) {
+            edges.push(CallEdge {
+                caller: current_caller.to_string(),
+                callee: callee.to_string(),
+                line: node.start_position().row + 1,
+            });
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk_for_calls(child, source, language, current_caller, edges);
+    }
+}
+
+/// Rough token estimate from a byte span (~4 bytes/token for source code),
+/// matching the heuristic already used elsewhere for embedding-sized windows.
+fn estimate_tokens(byte_len: usize) -> usize {
+    (byte_len / 4).max(1)
+}
+
+/// Maps a detected language to its tree-sitter grammar, where one is wired
+/// up. `None` means "fall back to text windowing" — either because the
+/// language has no grammar here yet, or because it's inherently prose
+/// (`Text`, `Markdown`) rather than a syntax tree.
+pub(crate) fn tree_sitter_language_for(language: &SupportedLanguage) -> Option<tree_sitter::Language> {
+    match language {
+        SupportedLanguage::Rust => Some(tree_sitter_rust::language()),
+        SupportedLanguage::Python => Some(tree_sitter_python::language()),
+        SupportedLanguage::JavaScript => Some(tree_sitter_javascript::language()),
+        SupportedLanguage::TypeScript => Some(tree_sitter_typescript::language_typescript()),
+        SupportedLanguage::Go => Some(tree_sitter_go::language()),
+        SupportedLanguage::Json => Some(tree_sitter_json::language()),
+        _ => None,
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
+use serde::{Serialize, Deserialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::{RwLock, Semaphore};
-use tracing::{debug, info, error};
+use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use crate::graph::{KGNode, KGEdge, Episode, EpisodeSource};
@@ -11,6 +13,11 @@ use crate::graph::storage::GraphStorage;
 use crate::context::{ContextWindowManager, ContextChunk, ChunkType, ContextWindowConfig};
 use crate::nlp::{EntityExtractor, RelationshipExtractor};
 use crate::embeddings::LocalEmbeddingEngine;
+use crate::indexing::code_chunker::{CodeChunker, ChunkerConfig, CallEdge};
+use crate::indexing::symbol_suggest::SymbolSuggestion;
+use crate::indexing::embedding_queue::EmbeddingBacklog;
+use crate::indexing::file_index_cache::{FileIndexCache, CachedFileResult};
+use crate::indexing::language_support::SupportedLanguage;
 
 /// Configuration for codebase indexing (MCP compatible)
 #[derive(Debug, Clone)]
@@ -23,7 +30,17 @@ pub struct IndexingConfig {
     pub incremental: bool,
     pub extract_dependencies: bool,
     pub extract_symbols: bool,
+    /// Whether to shell out to `git log` for each file's commit
+    /// history/churn (see `indexing::git_history`). Off by default: it's
+    /// one or two extra `git` subprocesses per file.
+    pub extract_history: bool,
     pub group_id: Option<String>,
+    /// Where to persist the `FileIndexCache` database backing
+    /// `incremental`/`status`/`watch`. `None` (the default) derives
+    /// `<path>/.kg_index_cache.db` from the indexed root in
+    /// `CodebaseIndexer::new_with_mcp_config`, so incremental re-indexing
+    /// and `status` work out of the box without the caller naming a path.
+    pub cache_dir: Option<String>,
 }
 
 impl Default for IndexingConfig {
@@ -41,7 +58,9 @@ impl Default for IndexingConfig {
             incremental: true,
             extract_dependencies: true,
             extract_symbols: true,
+            extract_history: false,
             group_id: None,
+            cache_dir: None,
         }
     }
 }
@@ -55,6 +74,11 @@ pub struct IndexingResult {
     pub processing_time_ms: u64,
     pub languages_detected: Vec<String>,
     pub errors: Vec<String>,
+    /// Per-stage wall time/call-count/percentage breakdown from
+    /// `metrics::profiler`, populated only when the caller opted in with
+    /// `profile: true`. `None` otherwise — profiling has a (small) bookkeeping
+    /// cost per span, so it isn't run unconditionally.
+    pub profile: Option<Vec<crate::metrics::ProfileSpan>>,
 }
 
 /// Code search result
@@ -65,10 +89,71 @@ pub struct CodeSearchResult {
     pub symbol_type: String,
     pub line_number: usize,
     pub column_number: usize,
+    /// Last line of the span this result came from (the enclosing chunk's
+    /// `end_line`, from `create_episodes_from_content`/`CodeChunker`), so a
+    /// caller can highlight the whole symbol/chunk instead of just its start
+    /// line.
+    pub end_line: usize,
     pub context_lines: Vec<String>,
     pub full_context: String,
     pub language: String,
     pub relevance_score: f32,
+    /// McCabe cyclomatic complexity, `0` if `symbol_type` isn't one
+    /// `calculate_complexity` scores (anything but `function`/`method`).
+    pub complexity: u32,
+    /// Shannon entropy (bits) over the symbol's token distribution.
+    pub entropy: f64,
+    /// `YYYY-MM-DD` date of the file's most recent commit (see
+    /// `git_history::file_history`), or `None` if `enable_history` was off
+    /// or `git` found no history for this file.
+    pub last_commit_date: Option<String>,
+}
+
+/// One declared symbol tracked by `CodebaseIndexer`'s `symbol_index`, the
+/// lightweight structure `search_code_mcp` matches queries against instead
+/// of scanning every cached file's episodes. Holds just enough to rank and
+/// locate a match; the full `CodeSearchResult` (with source context) is
+/// assembled afterward from `file_cache`, only for the handful of entries
+/// that actually made the cut.
+#[derive(Debug, Clone)]
+struct SymbolIndexEntry {
+    file_path: PathBuf,
+    symbol_name: String,
+    symbol_type: String,
+    indexed_at: u64,
+    /// Mirrors `CodeSearchResult::complexity`, so `search_code_mcp` can
+    /// apply `min_complexity` before paying for a `file_cache` lookup.
+    complexity: u32,
+    /// Mirrors `CodeSearchResult::entropy`.
+    entropy: f64,
+    /// Mirrors `CodeSearchResult::last_commit_date`, so `search_code_mcp`
+    /// can rank by recency without a `file_cache` lookup per candidate.
+    last_commit_date: Option<String>,
+}
+
+/// One edge of a `call_hierarchy_mcp` result: the other end of the call
+/// (the caller, for `incoming`; the callee, for `outgoing`) and where that
+/// call appears.
+#[derive(Debug, Clone)]
+pub struct CallHierarchyEdge {
+    pub symbol: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Result of `call_hierarchy_mcp` resolving a symbol name against
+/// `call_graph`.
+#[derive(Debug, Clone)]
+pub enum CallHierarchyResult {
+    /// `symbol` matched exactly; `incoming`/`outgoing` are its callers/callees.
+    Found {
+        symbol: String,
+        incoming: Vec<CallHierarchyEdge>,
+        outgoing: Vec<CallHierarchyEdge>,
+    },
+    /// `symbol` didn't match any known name exactly; these are the closest
+    /// fuzzy matches, ranked best first, for the caller to disambiguate.
+    Candidates(Vec<String>),
 }
 
 /// File dependency information for MCP
@@ -91,6 +176,22 @@ pub struct CodebaseAnalysis {
     pub file_types: HashMap<String, usize>,
     pub complexity_metrics: HashMap<String, f32>,
     pub dependency_graph: serde_json::Value,
+    /// Groups of mutually-dependent files found by `perform_cross_file_analysis`'s
+    /// cycle detection, each inner `Vec` one cycle's member file paths. Empty
+    /// if cross-file analysis hasn't run (`enable_cross_file_analysis` is off
+    /// or no `index_codebase` run has completed yet) or found no cycles.
+    pub circular_dependencies: Vec<Vec<String>>,
+    /// Cargo-workspace package list from `cargo_metadata::workspace_metadata`,
+    /// one entry per package with its edition and declared dependencies
+    /// (tagged `normal`/`dev`/`build`). `None` unless the caller opted in
+    /// with `use_cargo_metadata` and the analyzed path actually has a
+    /// `Cargo.toml` — the source-derived `dependency_graph` above doesn't
+    /// know about crate boundaries or external deps on its own.
+    pub cargo_workspace: Option<Vec<crate::indexing::cargo_metadata::CargoPackageInfo>>,
+    /// Per-stage wall time/call-count/percentage breakdown from
+    /// `metrics::profiler`, populated only when the caller opted in with
+    /// `profile: true`. `None` otherwise.
+    pub profile: Option<Vec<crate::metrics::ProfileSpan>>,
 }
 
 /// Configuration for codebase indexing
@@ -104,6 +205,21 @@ pub struct CodebaseIndexerConfig {
     pub enable_incremental: bool,
     pub enable_dependency_mapping: bool,
     pub enable_cross_file_analysis: bool,
+    /// Where `FileIndexCache` persists its content-hash-keyed results.
+    /// `None` disables the cache outright (every `index_codebase` run
+    /// reprocesses every file), regardless of `enable_incremental`.
+    pub file_cache_db_path: Option<PathBuf>,
+    /// Whether `extract_file_metadata` shells out to `git log` for each
+    /// file's commit history/churn (see `indexing::git_history`). Off by
+    /// default: it's one or two `git` subprocesses per file, and most
+    /// callers don't need temporal data.
+    pub enable_history: bool,
+    /// How many of a file's most recent commits `git_history::file_history`
+    /// fetches. Ignored when `enable_history` is off.
+    pub history_max_commits: usize,
+    /// Lookback window (days) `git_history::churn_stats` computes commit
+    /// count/lines-changed over. Ignored when `enable_history` is off.
+    pub history_window_days: u64,
 }
 
 impl Default for CodebaseIndexerConfig {
@@ -129,6 +245,10 @@ impl Default for CodebaseIndexerConfig {
             enable_incremental: true,
             enable_dependency_mapping: true,
             enable_cross_file_analysis: true,
+            file_cache_db_path: None,
+            enable_history: false,
+            history_max_commits: 10,
+            history_window_days: 90,
         }
     }
 }
@@ -143,41 +263,77 @@ pub struct FileIndexResult {
     pub chunks: Vec<ContextChunk>,
     pub dependencies: Vec<Dependency>,
     pub metadata: FileMetadata,
+    /// Caller/callee edges found in this file (see `code_chunker::extract_call_edges`),
+    /// folded into `CodebaseIndexer::call_graph` by `build_call_graph`/`patch_call_graph`.
+    pub call_edges: Vec<CallEdge>,
 }
 
 /// File dependency information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dependency {
     pub source_file: PathBuf,
+    /// The logical import spec as written (e.g. `crate::foo::bar`, `./utils`,
+    /// `pkg.sub.mod`), coerced into a `PathBuf` purely as a string container —
+    /// not itself a filesystem path. Kept as-is even when `resolved_target`
+    /// is populated, so the original spec is never lost.
     pub target_file: PathBuf,
     pub dependency_type: DependencyType,
     pub line_number: Option<usize>,
     pub symbol: Option<String>,
+    /// The real indexed file `target_file` resolves to, if any — populated
+    /// by `CodebaseIndexer`'s dependency-graph resolution pass, not at
+    /// extraction time (resolution needs the full set of indexed files,
+    /// which isn't known yet while a single file is being processed).
+    /// `None` means either resolution hasn't run yet or `target_file` names
+    /// something outside this indexed tree (a third-party crate/package,
+    /// a stdlib module, etc.) — both cases look the same from here, so an
+    /// unresolved dependency is retained rather than dropped.
+    pub resolved_target: Option<PathBuf>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DependencyType {
     Import,
     Include,
     Require,
     Use,
+    /// A `pub use` re-export, distinct from a plain `Use` import — the
+    /// symbol is made available to *this* file's own downstream consumers,
+    /// not just used internally.
+    ReExport,
     Extends,
     Implements,
     References,
 }
 
 /// File metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileMetadata {
     pub language: ProgrammingLanguage,
     pub lines_of_code: usize,
+    /// File-level complexity, summed from `symbol_complexity` (0 for
+    /// languages with no tree-sitter grammar wired up, i.e. no per-symbol
+    /// scores to sum).
     pub complexity_score: f32,
+    /// Per-function/method McCabe cyclomatic complexity, keyed by symbol
+    /// name (see `code_chunker::calculate_complexity`). Empty for languages
+    /// with no tree-sitter grammar wired up.
+    pub symbol_complexity: HashMap<String, u32>,
     pub last_modified: std::time::SystemTime,
     pub file_size: u64,
     pub encoding: String,
+    /// This file's most recent commits, newest first (see
+    /// `git_history::file_history`). Empty unless `enable_history` is on,
+    /// or when `extract_file_metadata` couldn't shell out to `git` at all
+    /// (not a git work tree, `git` not on `PATH`, etc.).
+    pub history: Vec<crate::indexing::git_history::CommitInfo>,
+    /// Commit count and lines changed over `history_window_days` (see
+    /// `git_history::churn_stats`). The zero value unless `enable_history`
+    /// is on.
+    pub churn: crate::indexing::git_history::ChurnStats,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProgrammingLanguage {
     Rust,
     Python,
@@ -221,6 +377,12 @@ pub struct IndexingStats {
     pub total_chunks: usize,
     pub processing_time: std::time::Duration,
     pub errors: Vec<String>,
+    /// Files whose content hash matched the file index cache and were
+    /// reused instead of reprocessed (only ever nonzero when
+    /// `enable_incremental` is set and `file_cache_db_path` is configured).
+    pub cache_hits: usize,
+    /// Files that were reprocessed because no matching cache entry existed.
+    pub cache_misses: usize,
 }
 
 /// Advanced codebase indexer
@@ -232,7 +394,25 @@ pub struct CodebaseIndexer {
     embedding_engine: Option<Arc<LocalEmbeddingEngine>>,
     file_cache: Arc<RwLock<HashMap<PathBuf, FileIndexResult>>>,
     dependency_graph: Arc<RwLock<HashMap<PathBuf, Vec<Dependency>>>>,
+    /// Caller→callee call graph backing the `call_hierarchy` operation,
+    /// built/patched alongside `dependency_graph` by `build_call_graph`/
+    /// `patch_call_graph`.
+    call_graph: Arc<RwLock<HashMap<PathBuf, Vec<CallEdge>>>>,
     semaphore: Arc<Semaphore>,
+    embedding_backlog: Arc<EmbeddingBacklog>,
+    file_index_cache: Option<Arc<FileIndexCache>>,
+    /// Dependency cycles found by `perform_cross_file_analysis`'s Tarjan SCC
+    /// pass over the resolved `dependency_graph`, one `Vec` per cycle.
+    /// Populated after each `index_codebase` run with `enable_cross_file_analysis`.
+    circular_dependencies: Arc<RwLock<Vec<Vec<PathBuf>>>>,
+    /// Fuzzy-searchable symbol index backing `search_code_mcp`, keyed by
+    /// file so it can be rebuilt for one file at a time (`patch_symbol_index`)
+    /// instead of rescanning the whole `file_cache` on every edit.
+    symbol_index: Arc<RwLock<HashMap<PathBuf, Vec<SymbolIndexEntry>>>>,
+    /// Monotonic counter stamped onto each `SymbolIndexEntry` when its file is
+    /// (re)indexed, so `search_code_mcp` can break ties between equally good
+    /// fuzzy matches in favor of the more recently indexed one.
+    symbol_index_clock: Arc<AtomicU64>,
 }
 
 impl CodebaseIndexer {
@@ -244,7 +424,8 @@ impl CodebaseIndexer {
         embedding_engine: Option<Arc<LocalEmbeddingEngine>>,
     ) -> Self {
         let semaphore = Arc::new(Semaphore::new(config.max_concurrent_files));
-        
+        let file_index_cache = Self::open_file_index_cache(&config);
+
         Self {
             config,
             context_manager,
@@ -253,10 +434,29 @@ impl CodebaseIndexer {
             embedding_engine,
             file_cache: Arc::new(RwLock::new(HashMap::new())),
             dependency_graph: Arc::new(RwLock::new(HashMap::new())),
+            call_graph: Arc::new(RwLock::new(HashMap::new())),
             semaphore,
+            embedding_backlog: Arc::new(EmbeddingBacklog::new()),
+            file_index_cache,
+            circular_dependencies: Arc::new(RwLock::new(Vec::new())),
+            symbol_index: Arc::new(RwLock::new(HashMap::new())),
+            symbol_index_clock: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Opens `config.file_cache_db_path` if set, falling back to `None` (the
+    /// file-index cache disabled) if the path is unset or the database fails
+    /// to open — mirrors `ContextWindowManager::new`'s handling of its own
+    /// optional `chunk_store`.
+    fn open_file_index_cache(config: &CodebaseIndexerConfig) -> Option<Arc<FileIndexCache>> {
+        config.file_cache_db_path.as_deref().and_then(|path| {
+            FileIndexCache::open(path)
+                .map(Arc::new)
+                .map_err(|e| warn!("Failed to open file index cache at {}: {}", path.display(), e))
+                .ok()
+        })
+    }
+
     /// Index an entire codebase
     pub async fn index_codebase(&self, root_path: &Path) -> Result<IndexingStats> {
         let start_time = std::time::Instant::now();
@@ -270,65 +470,102 @@ impl CodebaseIndexer {
             total_chunks: 0,
             processing_time: std::time::Duration::default(),
             errors: Vec::new(),
+            cache_hits: 0,
+            cache_misses: 0,
         };
 
         // Discover all files
-        let files = self.discover_files(root_path).await?;
+        let files = {
+            let _span = crate::metrics::profiler::enter("discover_files");
+            self.discover_files(root_path).await?
+        };
         stats.total_files = files.len();
 
         info!("Discovered {} files for indexing", files.len());
 
-        // Process files in parallel
-        let mut handles = Vec::new();
-        
-        for file_path in files {
-            let indexer = self.clone_for_task();
-            let file_path_clone = file_path.clone();
-            
-            let handle = tokio::spawn(async move {
-                let _permit = indexer.semaphore.acquire().await.unwrap();
-                indexer.process_file(&file_path_clone).await
-            });
-            
-            handles.push(handle);
-        }
-
-        // Collect results
-        for handle in handles {
-            match handle.await {
-                Ok(Ok(file_result)) => {
-                    stats.processed_files += 1;
-                    let nodes_len = file_result.nodes.len();
-                    let edges_len = file_result.edges.len();
-                    let episodes_len = file_result.episodes.len();
-                    
-                    stats.total_nodes += nodes_len;
-                    stats.total_edges += edges_len;
-                    stats.total_episodes += episodes_len;
-                    stats.total_chunks += file_result.chunks.len();
-
-                    // Store in cache
-                    let mut cache = self.file_cache.write().await;
-                    cache.insert(file_result.file_path.clone(), file_result);
-                }
-                Ok(Err(e)) => {
-                    stats.skipped_files += 1;
-                    stats.errors.push(format!("Processing error: {}", e));
-                }
-                Err(e) => {
-                    stats.skipped_files += 1;
-                    stats.errors.push(format!("Task error: {}", e));
+        // Process files in parallel. Left as one span rather than splitting
+        // into per-file parsing/symbol-extraction sub-spans: each
+        // `tokio::spawn`ed task can run (and its `.await`s resume) on a
+        // different worker thread than this one, which the thread-local
+        // span stack can't follow (see `metrics::profiler` module docs).
+        {
+            let _span = crate::metrics::profiler::enter("process_files");
+            let mut handles = Vec::new();
+
+            for file_path in files {
+                let indexer = self.clone_for_task();
+                let file_path_clone = file_path.clone();
+
+                let handle = tokio::spawn(async move {
+                    let _permit = indexer.semaphore.acquire().await.unwrap();
+                    indexer.process_file(&file_path_clone).await
+                });
+
+                handles.push(handle);
+            }
+
+            // Collect results
+            for handle in handles {
+                match handle.await {
+                    Ok(Ok((file_result, was_cache_hit))) => {
+                        stats.processed_files += 1;
+                        if was_cache_hit {
+                            stats.cache_hits += 1;
+                        } else {
+                            stats.cache_misses += 1;
+                        }
+                        let nodes_len = file_result.nodes.len();
+                        let edges_len = file_result.edges.len();
+                        let episodes_len = file_result.episodes.len();
+
+                        stats.total_nodes += nodes_len;
+                        stats.total_edges += edges_len;
+                        stats.total_episodes += episodes_len;
+                        stats.total_chunks += file_result.chunks.len();
+
+                        // Store in cache
+                        let mut cache = self.file_cache.write().await;
+                        cache.insert(file_result.file_path.clone(), file_result);
+                    }
+                    Ok(Err(e)) => {
+                        stats.skipped_files += 1;
+                        stats.errors.push(format!("Processing error: {}", e));
+                    }
+                    Err(e) => {
+                        stats.skipped_files += 1;
+                        stats.errors.push(format!("Task error: {}", e));
+                    }
                 }
             }
         }
 
+        // Drain every pending episode embedding queued by `process_file`
+        // tasks (across all files, not just the one that enqueued it) in
+        // token-budget batches, now that no more files are still chunking.
+        if let Some(ref engine) = self.embedding_engine {
+            let _span = crate::metrics::profiler::enter("drain_embedding_backlog");
+            let target_tokens_per_batch = crate::embeddings::EmbeddingQueue::default().target_tokens_per_batch();
+            match self.embedding_backlog.drain_and_apply(engine, target_tokens_per_batch, &self.file_cache).await {
+                Ok(embedded) => debug!("Embedded {} queued episodes in batches", embedded),
+                Err(e) => error!("Failed to drain embedding backlog: {}", e),
+            }
+        }
+
         // Build dependency graph if enabled
         if self.config.enable_dependency_mapping {
+            let _span = crate::metrics::profiler::enter("build_dependency_graph");
             self.build_dependency_graph().await?;
+            self.build_call_graph().await?;
+        }
+
+        {
+            let _span = crate::metrics::profiler::enter("build_symbol_index");
+            self.build_symbol_index().await?;
         }
 
         // Perform cross-file analysis if enabled
         if self.config.enable_cross_file_analysis {
+            let _span = crate::metrics::profiler::enter("cross_file_analysis");
             self.perform_cross_file_analysis().await?;
         }
 
@@ -340,8 +577,12 @@ impl CodebaseIndexer {
         Ok(stats)
     }
 
-    /// Process a single file
-    async fn process_file(&self, file_path: &Path) -> Result<FileIndexResult> {
+    /// Process a single file. The returned `bool` is `true` when the file's
+    /// content hash matched a `file_index_cache` entry and the
+    /// nodes/edges/episodes/dependencies/metadata were reused instead of
+    /// recomputed; `chunks` are always regenerated (see `file_index_cache`
+    /// module docs for why).
+    async fn process_file(&self, file_path: &Path) -> Result<(FileIndexResult, bool)> {
         debug!("Processing file: {:?}", file_path);
 
         // Check if file should be processed
@@ -351,12 +592,41 @@ impl CodebaseIndexer {
 
         // Read file content
         let content = tokio::fs::read_to_string(file_path).await?;
-        
+
         // Check file size
         if content.len() > self.config.max_file_size {
             return Err(anyhow::anyhow!("File too large: {} bytes", content.len()));
         }
 
+        let content_hash = crate::indexing::file_index_cache::content_hash(content.as_bytes());
+        if self.config.enable_incremental {
+            if let Some(ref file_index_cache) = self.file_index_cache {
+                if let Some(cached) = file_index_cache.get(file_path, &content_hash).await {
+                    let chunk_type = self.determine_chunk_type(&cached.metadata.language);
+                    let chunk_ids = self.context_manager.add_content(
+                        &content,
+                        Some(file_path.to_string_lossy().to_string()),
+                        chunk_type,
+                    ).await?;
+                    let chunks = self.get_chunks_by_ids(&chunk_ids).await?;
+
+                    return Ok((
+                        FileIndexResult {
+                            file_path: file_path.to_path_buf(),
+                            nodes: cached.nodes,
+                            edges: cached.edges,
+                            episodes: cached.episodes,
+                            chunks,
+                            dependencies: cached.dependencies,
+                            metadata: cached.metadata,
+                            call_edges: cached.call_edges,
+                        },
+                        true,
+                    ));
+                }
+            }
+        }
+
         // Determine language and metadata
         let language = self.detect_language(file_path);
         let metadata = self.extract_file_metadata(file_path, &content).await?;
@@ -406,7 +676,7 @@ impl CodebaseIndexer {
         }
 
         // Create episodes for significant code blocks
-        let episodes = self.create_episodes_from_content(&content, file_path).await?;
+        let episodes = self.create_episodes_from_content(&content, file_path, &metadata).await?;
 
         // Create context chunks
         let chunk_type = self.determine_chunk_type(&language);
@@ -422,15 +692,57 @@ impl CodebaseIndexer {
         // Extract dependencies
         let dependencies = self.extract_dependencies(&content, file_path, &language).await?;
 
-        Ok(FileIndexResult {
-            file_path: file_path.to_path_buf(),
-            nodes,
-            edges,
-            episodes,
-            chunks,
-            dependencies,
-            metadata,
-        })
+        // Extract the caller/callee call graph (see `call_hierarchy_mcp`).
+        // `None` for languages with no tree-sitter grammar wired up.
+        let call_edges = crate::indexing::code_chunker::extract_call_edges(&content, &to_supported_language(&language)).unwrap_or_default();
+
+        if let Some(ref file_index_cache) = self.file_index_cache {
+            let cached = CachedFileResult {
+                nodes: nodes.clone(),
+                edges: edges.clone(),
+                episodes: episodes.clone(),
+                dependencies: dependencies.clone(),
+                metadata: metadata.clone(),
+                call_edges: call_edges.clone(),
+            };
+            if let Err(e) = file_index_cache.put(file_path, &content_hash, &cached).await {
+                warn!("Failed to store file index cache entry for {:?}: {}", file_path, e);
+            }
+        }
+
+        Ok((
+            FileIndexResult {
+                file_path: file_path.to_path_buf(),
+                nodes,
+                edges,
+                episodes,
+                chunks,
+                dependencies,
+                metadata,
+                call_edges,
+            },
+            false,
+        ))
+    }
+
+    /// Indexes a single file, returning its extracted nodes/edges/episodes
+    /// without touching the in-memory file cache used by `index_codebase`.
+    /// Used by the incremental file-watcher to re-index one changed file at
+    /// a time instead of rescanning the whole tree. Unlike `index_codebase`,
+    /// there's no later point where every file's pending embeddings get
+    /// drained together, so this drains this file's own backlog entries
+    /// immediately - the result's episodes come back already embedded,
+    /// ready for `GraphStorage::reindex_file` to persist atomically with
+    /// the rest of the file's graph records.
+    pub async fn index_file(&self, file_path: &Path) -> Result<FileIndexResult> {
+        let (mut result, _was_cache_hit) = self.process_file(file_path).await?;
+        if let Some(ref engine) = self.embedding_engine {
+            let target_tokens_per_batch = crate::embeddings::EmbeddingQueue::default().target_tokens_per_batch();
+            if let Err(e) = self.embedding_backlog.drain_and_apply_to_result(engine, target_tokens_per_batch, &mut result).await {
+                error!("Failed to embed episodes for {}: {}", file_path.display(), e);
+            }
+        }
+        Ok(result)
     }
 
     /// Create a clone of the indexer for parallel processing
@@ -443,7 +755,13 @@ impl CodebaseIndexer {
             embedding_engine: self.embedding_engine.clone(),
             file_cache: Arc::clone(&self.file_cache),
             dependency_graph: Arc::clone(&self.dependency_graph),
+            call_graph: Arc::clone(&self.call_graph),
             semaphore: Arc::clone(&self.semaphore),
+            embedding_backlog: Arc::clone(&self.embedding_backlog),
+            file_index_cache: self.file_index_cache.clone(),
+            circular_dependencies: Arc::clone(&self.circular_dependencies),
+            symbol_index: Arc::clone(&self.symbol_index),
+            symbol_index_clock: Arc::clone(&self.symbol_index_clock),
         }
     }
 
@@ -549,114 +867,130 @@ impl CodebaseIndexer {
     async fn extract_file_metadata(&self, file_path: &Path, content: &str) -> Result<FileMetadata> {
         let metadata = tokio::fs::metadata(file_path).await?;
         let language = self.detect_language(file_path);
-        
+
         let lines_of_code = content.lines().count();
-        let complexity_score = self.calculate_complexity_score(content, &language);
+        let symbol_complexity = crate::indexing::code_chunker::calculate_complexity(content, &to_supported_language(&language));
+        let complexity_score = symbol_complexity.values().sum::<u32>() as f32;
+
+        let (history, churn) = if self.config.enable_history {
+            (
+                crate::indexing::git_history::file_history(file_path, self.config.history_max_commits),
+                crate::indexing::git_history::churn_stats(file_path, self.config.history_window_days),
+            )
+        } else {
+            (Vec::new(), crate::indexing::git_history::ChurnStats::default())
+        };
 
         Ok(FileMetadata {
             language,
             lines_of_code,
             complexity_score,
+            symbol_complexity,
             last_modified: metadata.modified()?,
             file_size: metadata.len(),
             encoding: "UTF-8".to_string(), // Simplified
+            history,
+            churn,
         })
     }
 
-    fn calculate_complexity_score(&self, content: &str, language: &ProgrammingLanguage) -> f32 {
-        let mut score = 0.0;
-        
-        // Basic complexity indicators
-        let lines = content.lines().count() as f32;
-        score += lines * 0.1;
-
-        // Language-specific complexity
-        match language {
-            ProgrammingLanguage::Rust | ProgrammingLanguage::Cpp => {
-                score += content.matches("unsafe").count() as f32 * 2.0;
-                score += content.matches("impl").count() as f32 * 1.5;
-            }
-            ProgrammingLanguage::JavaScript | ProgrammingLanguage::TypeScript => {
-                score += content.matches("async").count() as f32 * 1.5;
-                score += content.matches("Promise").count() as f32 * 1.0;
-            }
-            _ => {}
-        }
-
-        // Control flow complexity
-        score += content.matches("if").count() as f32 * 1.0;
-        score += content.matches("for").count() as f32 * 1.5;
-        score += content.matches("while").count() as f32 * 1.5;
-        score += content.matches("match").count() as f32 * 2.0;
-
-        score
-    }
-
-    async fn create_episodes_from_content(&self, content: &str, file_path: &Path) -> Result<Vec<Episode>> {
+    /// Splits `content` into syntactically-bounded chunks via `CodeChunker`
+    /// (falling back to paragraph windowing for languages with no grammar
+    /// wired up) and turns each chunk into a `Code` episode carrying its
+    /// file path and byte range, so natural-language search can map a hit
+    /// back to the exact source span it came from.
+    ///
+    /// Each episode also gets `symbol_name`/`symbol_type`/`start_col`/`loc`/
+    /// `logical_loc`/`complexity`/`entropy` metadata from
+    /// `code_chunker::extract_symbols` when a real parse-tree symbol starts
+    /// inside its byte range, replacing guesswork about which function/class
+    /// a chunk belongs to with the tree-sitter-accurate answer, and giving
+    /// `search_code`'s `min_complexity`/`min_entropy` filters something to
+    /// check against. When `file_metadata.history` is non-empty (i.e.
+    /// `enable_history` is on and `git` found commits), the episode also
+    /// gets the file's `last_commit_hash`/`last_commit_date`/
+    /// `last_commit_committer`/`last_commit_message`/`churn_commit_count`/
+    /// `churn_lines_changed` — git doesn't track a function's own line
+    /// range as it's edited, so this is the file's history, not a
+    /// per-symbol one; still enough for "is this file actively changing"
+    /// and recency ranking. Scope note: this only annotates the existing
+    /// per-chunk episodes; switching to one episode per `CodeBlock` (so
+    /// every symbol, however small, gets its own episode) and wiring that
+    /// through `search_code_mcp`'s still-placeholder `CodeSearchResult`
+    /// lookup is left for a future change.
+    async fn create_episodes_from_content(&self, content: &str, file_path: &Path, file_metadata: &FileMetadata) -> Result<Vec<Episode>> {
         let mut episodes = Vec::new();
 
-        // Create episodes for functions, classes, etc.
-        let significant_blocks = self.extract_significant_code_blocks(content);
-        
-        for block in significant_blocks {
-            let embedding = if let Some(ref engine) = self.embedding_engine {
-                Some(engine.encode_text(&block.content).await?)
-            } else {
-                None
-            };
+        let language = self.detect_language(file_path);
+        let supported_language = to_supported_language(&language);
+        let chunker = CodeChunker::new(ChunkerConfig::default());
+        let chunks = chunker.chunk_source(content, &supported_language, file_path);
+        let symbols = crate::indexing::code_chunker::extract_symbols(content, &supported_language);
 
+        for (index, chunk) in chunks.into_iter().enumerate() {
             let mut episode = Episode::new(
-                format!("{}_{}", file_path.file_name().unwrap_or_default().to_string_lossy(), block.block_type),
-                block.content,
-                EpisodeSource::Text,
+                format!("{}_chunk{}", file_path.file_name().unwrap_or_default().to_string_lossy(), index),
+                chunk.content.clone(),
+                EpisodeSource::Code,
                 file_path.to_string_lossy().to_string(),
                 Some(file_path.to_string_lossy().to_string()),
             );
-            
-            if let Some(emb) = embedding {
-                episode.set_embedding(emb);
-            }
-            
-            episodes.push(episode);
-        }
 
-        Ok(episodes)
-    }
-
-    fn extract_significant_code_blocks(&self, content: &str) -> Vec<CodeBlock> {
-        let mut blocks = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
-        
-        let mut current_block = String::new();
-        let mut in_function = false;
-        let mut brace_count = 0;
-
-        for line in lines {
-            current_block.push_str(line);
-            current_block.push('\n');
-
-            // Simple function detection (works for many languages)
-            if line.trim_start().starts_with("fn ") || 
-               line.trim_start().starts_with("function ") ||
-               line.trim_start().starts_with("def ") ||
-               line.trim_start().starts_with("class ") {
-                in_function = true;
+            episode.add_metadata("file_path".to_string(), serde_json::Value::String(file_path.to_string_lossy().to_string()));
+            episode.add_metadata("start_byte".to_string(), serde_json::Value::from(chunk.start_byte));
+            episode.add_metadata("end_byte".to_string(), serde_json::Value::from(chunk.end_byte));
+            episode.add_metadata("start_line".to_string(), serde_json::Value::from(chunk.start_line));
+            episode.add_metadata("end_line".to_string(), serde_json::Value::from(chunk.end_line));
+            episode.add_metadata("chunk_type".to_string(), serde_json::Value::String(format!("{:?}", chunk.chunk_type)));
+
+            let matched_symbol = symbols.iter().find(|s| s.start_byte >= chunk.start_byte && s.start_byte < chunk.end_byte);
+
+            if let Some(symbol) = matched_symbol {
+                episode.add_metadata("symbol_name".to_string(), serde_json::Value::String(symbol.name.clone()));
+                episode.add_metadata("symbol_type".to_string(), serde_json::Value::String(symbol.block_type.clone()));
+                episode.add_metadata("start_col".to_string(), serde_json::Value::from(symbol.start_col));
+                // The symbol's own start line, distinct from `"start_line"`
+                // above (the enclosing chunk's start line): a symbol can
+                // begin partway through a chunk, so "go to definition" needs
+                // this more precise value rather than the chunk's.
+                episode.add_metadata("symbol_start_line".to_string(), serde_json::Value::from(symbol.start_line));
+                // Code-quality metrics from `code_chunker::extract_symbols`,
+                // so `analyze_patterns`/`search_code` can surface hotspots
+                // without recomputing them from source on every query.
+                episode.add_metadata("loc".to_string(), serde_json::Value::from(symbol.loc));
+                episode.add_metadata("logical_loc".to_string(), serde_json::Value::from(symbol.logical_loc));
+                episode.add_metadata("complexity".to_string(), serde_json::Value::from(symbol.complexity));
+                episode.add_metadata("entropy".to_string(), serde_json::Value::from(symbol.entropy));
+
+                if let Some(commit) = file_metadata.history.first() {
+                    episode.add_metadata("last_commit_hash".to_string(), serde_json::Value::String(commit.hash.clone()));
+                    episode.add_metadata("last_commit_date".to_string(), serde_json::Value::String(commit.date.clone()));
+                    episode.add_metadata("last_commit_committer".to_string(), serde_json::Value::String(commit.committer.clone()));
+                    episode.add_metadata("last_commit_message".to_string(), serde_json::Value::String(commit.message.clone()));
+                    episode.add_metadata("churn_commit_count".to_string(), serde_json::Value::from(file_metadata.churn.commit_count));
+                    episode.add_metadata("churn_lines_changed".to_string(), serde_json::Value::from(file_metadata.churn.lines_changed));
+                }
             }
 
-            brace_count += line.matches('{').count() as i32;
-            brace_count -= line.matches('}').count() as i32;
-
-            if in_function && brace_count == 0 && !current_block.trim().is_empty() {
-                blocks.push(CodeBlock {
-                    content: current_block.clone(),
-                    block_type: "function".to_string(),
-                });
-                current_block.clear();
-                in_function = false;
+            if self.embedding_engine.is_some() {
+                // Give the embedder a little context the raw chunk lacks on
+                // its own (which file, which language, which symbol), so
+                // near-identical bodies in unrelated files don't collapse to
+                // the same vector. `episode.content` above stays the raw
+                // chunk text; only the text handed to the embedder carries
+                // this header.
+                let language_label = format!("{:?}", language).to_lowercase();
+                let header = match matched_symbol {
+                    Some(symbol) => format!("// {} ({}) {}\n", file_path.to_string_lossy(), language_label, symbol.name),
+                    None => format!("// {} ({})\n", file_path.to_string_lossy(), language_label),
+                };
+                self.embedding_backlog.enqueue(episode.uuid, file_path.to_path_buf(), format!("{}{}", header, chunk.content));
             }
+
+            episodes.push(episode);
         }
 
-        blocks
+        Ok(episodes)
     }
 
     fn determine_chunk_type(&self, language: &ProgrammingLanguage) -> ChunkType {
@@ -674,91 +1008,209 @@ impl CodebaseIndexer {
         Ok(Vec::new())
     }
 
+    /// Finds this file's dependencies from its parse tree's import/use nodes
+    /// (`code_chunker::extract_import_nodes`) rather than a per-line regex,
+    /// so `line_number` always points at a real declaration instead of
+    /// wherever a substring like `"import"` first happened to match inside a
+    /// string or comment. Only falls back to `extract_generic_dependencies`
+    /// for languages with no tree-sitter grammar wired up at all — every
+    /// parseable language goes through the AST path exclusively now.
     async fn extract_dependencies(&self, content: &str, file_path: &Path, language: &ProgrammingLanguage) -> Result<Vec<Dependency>> {
-        let mut dependencies = Vec::new();
+        let supported_language = to_supported_language(language);
+        match crate::indexing::code_chunker::extract_import_nodes(content, &supported_language) {
+            Some(import_nodes) => Ok(self.dependencies_from_import_nodes(import_nodes, file_path, language)),
+            None => Ok(self.extract_generic_dependencies(content, file_path)),
+        }
+    }
+
+    /// Expands each `(source text, line number)` pair from `extract_import_nodes`
+    /// into one `Dependency` per leaf symbol — a grouped `use std::{fmt, io}`
+    /// becomes two `Dependency`s, not one meaningless one for the whole line.
+    fn dependencies_from_import_nodes(&self, import_nodes: Vec<(String, usize)>, file_path: &Path, language: &ProgrammingLanguage) -> Vec<Dependency> {
+        import_nodes
+            .into_iter()
+            .flat_map(|(text, line_number)| self.expand_import_node(language, text.trim(), line_number, file_path))
+            .collect()
+    }
 
+    fn expand_import_node(&self, language: &ProgrammingLanguage, text: &str, line_number: usize, file_path: &Path) -> Vec<Dependency> {
         match language {
-            ProgrammingLanguage::Rust => {
-                dependencies.extend(self.extract_rust_dependencies(content, file_path));
-            }
-            ProgrammingLanguage::Python => {
-                dependencies.extend(self.extract_python_dependencies(content, file_path));
-            }
-            ProgrammingLanguage::JavaScript | ProgrammingLanguage::TypeScript => {
-                dependencies.extend(self.extract_js_dependencies(content, file_path));
-            }
-            _ => {
-                // Generic import detection
-                dependencies.extend(self.extract_generic_dependencies(content, file_path));
-            }
+            ProgrammingLanguage::Rust => self.expand_rust_use(text, line_number, file_path),
+            ProgrammingLanguage::Python => self.expand_python_import(text, line_number, file_path),
+            ProgrammingLanguage::JavaScript | ProgrammingLanguage::TypeScript => self.expand_js_import(text, line_number, file_path),
+            ProgrammingLanguage::Go => match self.extract_quoted_string(text) {
+                Some(module) => vec![self.make_dependency(file_path, module, DependencyType::Import, line_number, None)],
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
         }
+    }
 
-        Ok(dependencies)
+    fn make_dependency(&self, file_path: &Path, target: String, dependency_type: DependencyType, line_number: usize, symbol: Option<String>) -> Dependency {
+        Dependency {
+            source_file: file_path.to_path_buf(),
+            target_file: PathBuf::from(target),
+            dependency_type,
+            line_number: Some(line_number),
+            resolved_target: None,
+            symbol,
+        }
     }
 
-    fn extract_rust_dependencies(&self, content: &str, file_path: &Path) -> Vec<Dependency> {
-        let mut dependencies = Vec::new();
+    /// Flattens a `use` declaration's text (sans leading `pub `/`use ` and
+    /// trailing `;`) into one `(full path, alias)` pair per leaf, recursing
+    /// into brace groups so `std::{fmt, io::{self, Read as R}}` yields
+    /// `std::fmt`, `std::io`, and `std::io::Read` (aliased `R`).
+    fn flatten_rust_use_tree(prefix: &str, segment: &str, out: &mut Vec<(String, Option<String>)>) {
+        let segment = segment.trim();
+        if segment.is_empty() {
+            return;
+        }
 
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-            
-            if trimmed.starts_with("use ") {
-                if let Some(module) = self.extract_rust_module_path(trimmed) {
-                    dependencies.push(Dependency {
-                        source_file: file_path.to_path_buf(),
-                        target_file: PathBuf::from(module),
-                        dependency_type: DependencyType::Use,
-                        line_number: Some(line_num + 1),
-                        symbol: None,
-                    });
-                }
+        if let Some(brace_pos) = segment.find('{') {
+            let group_prefix = segment[..brace_pos].trim().trim_end_matches("::").trim();
+            let new_prefix = match (prefix.is_empty(), group_prefix.is_empty()) {
+                (true, _) => group_prefix.to_string(),
+                (false, true) => prefix.to_string(),
+                (false, false) => format!("{}::{}", prefix, group_prefix),
+            };
+            let end = segment.rfind('}').unwrap_or(segment.len());
+            for part in Self::split_top_level(&segment[brace_pos + 1..end], ',') {
+                Self::flatten_rust_use_tree(&new_prefix, part, out);
             }
+            return;
         }
 
-        dependencies
+        if segment == "self" {
+            out.push((prefix.to_string(), None));
+        } else if segment == "*" {
+            out.push((if prefix.is_empty() { "*".to_string() } else { format!("{}::*", prefix) }, None));
+        } else if let Some(as_pos) = segment.find(" as ") {
+            let (name, alias) = (segment[..as_pos].trim(), segment[as_pos + 4..].trim());
+            let full = if prefix.is_empty() { name.to_string() } else { format!("{}::{}", prefix, name) };
+            out.push((full, Some(alias.to_string())));
+        } else {
+            let full = if prefix.is_empty() { segment.to_string() } else { format!("{}::{}", prefix, segment) };
+            out.push((full, None));
+        }
     }
 
-    fn extract_python_dependencies(&self, content: &str, file_path: &Path) -> Vec<Dependency> {
-        let mut dependencies = Vec::new();
+    fn expand_rust_use(&self, text: &str, line_number: usize, file_path: &Path) -> Vec<Dependency> {
+        let is_reexport = text.starts_with("pub ");
+        let rest = text.trim_start_matches("pub ").trim_start_matches("use ").trim_end_matches(';').trim();
+        let dependency_type = if is_reexport { DependencyType::ReExport } else { DependencyType::Use };
+
+        let mut leaves = Vec::new();
+        Self::flatten_rust_use_tree("", rest, &mut leaves);
+        leaves
+            .into_iter()
+            .map(|(path, alias)| self.make_dependency(file_path, path, dependency_type.clone(), line_number, alias))
+            .collect()
+    }
 
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-            
-            if trimmed.starts_with("import ") || trimmed.starts_with("from ") {
-                if let Some(module) = self.extract_python_module_path(trimmed) {
-                    dependencies.push(Dependency {
-                        source_file: file_path.to_path_buf(),
-                        target_file: PathBuf::from(module),
-                        dependency_type: DependencyType::Import,
-                        line_number: Some(line_num + 1),
-                        symbol: None,
-                    });
+    /// Splits `s` on top-level occurrences of `delim`, ignoring any that fall
+    /// inside a nested `{...}` group (so `a, b::{c, d}` splits into `a` and
+    /// `b::{c, d}`, not four pieces).
+    fn split_top_level(s: &str, delim: char) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut depth = 0i32;
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            match c {
+                '{' | '(' => depth += 1,
+                '}' | ')' => depth -= 1,
+                c if c == delim && depth == 0 => {
+                    parts.push(s[start..i].trim());
+                    start = i + c.len_utf8();
                 }
+                _ => {}
             }
         }
+        let last = s[start..].trim();
+        if !last.is_empty() {
+            parts.push(last);
+        }
+        parts
+    }
 
-        dependencies
+    fn expand_python_import(&self, text: &str, line_number: usize, file_path: &Path) -> Vec<Dependency> {
+        if let Some(after_from) = text.strip_prefix("from ") {
+            let Some((module, items_part)) = after_from.split_once("import") else {
+                return Vec::new();
+            };
+            let module = module.trim();
+            let items_part = items_part.trim().trim_start_matches('(').trim_end_matches(')');
+            if items_part == "*" {
+                return vec![self.make_dependency(file_path, module.to_string(), DependencyType::Import, line_number, None)];
+            }
+            Self::split_top_level(items_part, ',')
+                .into_iter()
+                .filter(|item| !item.is_empty())
+                .map(|item| {
+                    let (name, alias) = match item.split_once(" as ") {
+                        Some((n, a)) => (n.trim(), Some(a.trim().to_string())),
+                        None => (item.trim(), None),
+                    };
+                    self.make_dependency(file_path, module.to_string(), DependencyType::Import, line_number, alias.or_else(|| Some(name.to_string())))
+                })
+                .collect()
+        } else if let Some(rest) = text.strip_prefix("import ") {
+            Self::split_top_level(rest, ',')
+                .into_iter()
+                .filter(|item| !item.is_empty())
+                .map(|item| {
+                    let (module, alias) = match item.split_once(" as ") {
+                        Some((m, a)) => (m.trim(), Some(a.trim().to_string())),
+                        None => (item.trim(), None),
+                    };
+                    self.make_dependency(file_path, module.to_string(), DependencyType::Import, line_number, alias)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
     }
 
-    fn extract_js_dependencies(&self, content: &str, file_path: &Path) -> Vec<Dependency> {
+    fn expand_js_import(&self, text: &str, line_number: usize, file_path: &Path) -> Vec<Dependency> {
+        if text.contains("require(") {
+            return match self.extract_quoted_string(text) {
+                Some(module) => vec![self.make_dependency(file_path, module, DependencyType::Require, line_number, None)],
+                None => Vec::new(),
+            };
+        }
+
+        let Some(module) = self.extract_quoted_string(text) else {
+            return Vec::new();
+        };
+        let import_part = text.split(" from ").next().unwrap_or(text).trim_start_matches("import ").trim();
+
+        if let Some(as_pos) = import_part.strip_prefix('*').map(|r| r.trim()).and_then(|r| r.strip_prefix("as ")) {
+            let ns = as_pos.trim();
+            return vec![self.make_dependency(file_path, module, DependencyType::Import, line_number, Some(ns.to_string()))];
+        }
+
         let mut dependencies = Vec::new();
+        let (default_part, named_part) = match import_part.find('{') {
+            Some(brace_pos) => {
+                let default = import_part[..brace_pos].trim().trim_end_matches(',').trim();
+                let end = import_part.rfind('}').unwrap_or(import_part.len());
+                (default, Some(&import_part[brace_pos + 1..end]))
+            }
+            None => (import_part, None),
+        };
 
-        for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-            
-            if trimmed.starts_with("import ") || trimmed.contains("require(") {
-                if let Some(module) = self.extract_js_module_path(trimmed) {
-                    dependencies.push(Dependency {
-                        source_file: file_path.to_path_buf(),
-                        target_file: PathBuf::from(module),
-                        dependency_type: DependencyType::Import,
-                        line_number: Some(line_num + 1),
-                        symbol: None,
-                    });
+        if !default_part.is_empty() {
+            dependencies.push(self.make_dependency(file_path, module.clone(), DependencyType::Import, line_number, Some(default_part.to_string())));
+        }
+        if let Some(named) = named_part {
+            for item in Self::split_top_level(named, ',') {
+                if item.is_empty() {
+                    continue;
                 }
+                let name = item.split(" as ").last().unwrap_or(item).trim();
+                dependencies.push(self.make_dependency(file_path, module.clone(), DependencyType::Import, line_number, Some(name.to_string())));
             }
         }
-
         dependencies
     }
 
@@ -779,6 +1231,7 @@ impl CodebaseIndexer {
                             dependency_type: DependencyType::References,
                             line_number: Some(line_num + 1),
                             symbol: None,
+                            resolved_target: None,
                         });
                     }
                 }
@@ -790,41 +1243,6 @@ impl CodebaseIndexer {
 
     // Helper methods for dependency extraction
 
-    fn extract_rust_module_path(&self, line: &str) -> Option<String> {
-        // Extract module path from "use path::to::module;"
-        if let Some(start) = line.find("use ") {
-            let rest = &line[start + 4..];
-            if let Some(end) = rest.find(';') {
-                Some(rest[..end].trim().to_string())
-            } else {
-                Some(rest.trim().to_string())
-            }
-        } else {
-            None
-        }
-    }
-
-    fn extract_python_module_path(&self, line: &str) -> Option<String> {
-        // Extract module from "import module" or "from module import ..."
-        if line.starts_with("import ") {
-            Some(line[7..].split_whitespace().next()?.to_string())
-        } else if line.starts_with("from ") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                Some(parts[1].to_string())
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-
-    fn extract_js_module_path(&self, line: &str) -> Option<String> {
-        // Extract from import statements or require calls
-        self.extract_quoted_string(line)
-    }
-
     fn extract_quoted_string(&self, line: &str) -> Option<String> {
         // Extract content between quotes
         if let Some(start) = line.find('"') {
@@ -842,23 +1260,392 @@ impl CodebaseIndexer {
 
     async fn build_dependency_graph(&self) -> Result<()> {
         let cache = self.file_cache.read().await;
+        let known_files: HashSet<PathBuf> = cache.keys().cloned().collect();
         let mut dep_graph = self.dependency_graph.write().await;
 
         for (file_path, result) in cache.iter() {
-            dep_graph.insert(file_path.clone(), result.dependencies.clone());
+            let resolved = result.dependencies.iter().cloned().map(|dep| self.resolve_dependency(dep, &known_files)).collect();
+            dep_graph.insert(file_path.clone(), resolved);
         }
 
         debug!("Built dependency graph with {} files", dep_graph.len());
         Ok(())
     }
 
+    /// Patches a single file's entry into `dependency_graph` without
+    /// rebuilding it from the whole `file_cache`. Used by `watch_codebase`
+    /// so a re-indexed file only touches its own entry.
+    pub(crate) async fn patch_dependency_graph(&self, file_path: &Path, dependencies: Vec<Dependency>) {
+        if self.config.enable_dependency_mapping {
+            let known_files: HashSet<PathBuf> = self.file_cache.read().await.keys().cloned().collect();
+            let resolved = dependencies.into_iter().map(|dep| self.resolve_dependency(dep, &known_files)).collect();
+            self.dependency_graph.write().await.insert(file_path.to_path_buf(), resolved);
+        }
+    }
+
+    /// Rebuilds `call_graph` from every file currently in `file_cache`. Built
+    /// alongside `dependency_graph` (under the same `enable_dependency_mapping`
+    /// flag) since both are file-scoped graphs derived from the same pass
+    /// over indexed sources.
+    async fn build_call_graph(&self) -> Result<()> {
+        let cache = self.file_cache.read().await;
+        let mut call_graph = self.call_graph.write().await;
+
+        for (file_path, result) in cache.iter() {
+            call_graph.insert(file_path.clone(), result.call_edges.clone());
+        }
+
+        debug!("Built call graph with {} files", call_graph.len());
+        Ok(())
+    }
+
+    /// Patches a single file's entry into `call_graph` without rebuilding it
+    /// from the whole `file_cache`. Used by `IngestionWatcher` so a
+    /// re-indexed file only touches its own entry.
+    pub(crate) async fn patch_call_graph(&self, file_path: &Path, call_edges: Vec<CallEdge>) {
+        if self.config.enable_dependency_mapping {
+            self.call_graph.write().await.insert(file_path.to_path_buf(), call_edges);
+        }
+    }
+
+    /// Rebuilds `symbol_index` from every file currently in `file_cache`.
+    /// Used after a full `index_codebase` run; incremental re-indexing of a
+    /// single file should call `patch_symbol_index` instead.
+    async fn build_symbol_index(&self) -> Result<()> {
+        let cache = self.file_cache.read().await;
+        let mut index = self.symbol_index.write().await;
+
+        for (file_path, result) in cache.iter() {
+            let entries = self.extract_symbol_entries(file_path, result);
+            index.insert(file_path.clone(), entries);
+        }
+
+        debug!("Built symbol index with {} files", index.len());
+        Ok(())
+    }
+
+    /// Patches a single file's entry into `symbol_index` without rebuilding
+    /// it from the whole `file_cache`. Used by `IngestionWatcher` so a
+    /// re-indexed file only touches its own entry.
+    pub(crate) async fn patch_symbol_index(&self, file_path: &Path, result: &FileIndexResult) {
+        let entries = self.extract_symbol_entries(file_path, result);
+        self.symbol_index.write().await.insert(file_path.to_path_buf(), entries);
+    }
+
+    /// Collects one `SymbolIndexEntry` per symbol-declaring episode in
+    /// `result`, stamping each with the current `symbol_index_clock` tick so
+    /// later searches can prefer more recently (re)indexed files on ties.
+    fn extract_symbol_entries(&self, file_path: &Path, result: &FileIndexResult) -> Vec<SymbolIndexEntry> {
+        let indexed_at = self.symbol_index_clock.fetch_add(1, Ordering::Relaxed);
+        result
+            .episodes
+            .iter()
+            .filter_map(|episode| {
+                let symbol_name = episode.metadata.get("symbol_name")?.as_str()?.to_string();
+                let symbol_type = episode
+                    .metadata
+                    .get("symbol_type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let complexity = episode.metadata.get("complexity").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+                let entropy = episode.metadata.get("entropy").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let last_commit_date = episode.metadata.get("last_commit_date").and_then(|v| v.as_str()).map(|s| s.to_string());
+                Some(SymbolIndexEntry {
+                    file_path: file_path.to_path_buf(),
+                    symbol_name,
+                    symbol_type,
+                    indexed_at,
+                    complexity,
+                    entropy,
+                    last_commit_date,
+                })
+            })
+            .collect()
+    }
+
+    /// Populates `dep.resolved_target` with the real indexed file
+    /// `dep.target_file`'s logical spec resolves to, if any. Leaves it
+    /// `None` (not an error) for anything outside this indexed tree — an
+    /// external crate, a stdlib module, a third-party npm package.
+    fn resolve_dependency(&self, mut dep: Dependency, known_files: &HashSet<PathBuf>) -> Dependency {
+        let language = self.detect_language(&dep.source_file);
+        let module = dep.target_file.to_string_lossy().to_string();
+        dep.resolved_target = match language {
+            ProgrammingLanguage::Rust => self.resolve_rust_module(&dep.source_file, &module, known_files),
+            ProgrammingLanguage::Python => Self::resolve_python_module(&module, known_files),
+            ProgrammingLanguage::JavaScript | ProgrammingLanguage::TypeScript => {
+                Self::resolve_js_module(&dep.source_file, &module, known_files)
+            }
+            _ => None,
+        };
+        dep
+    }
+
+    /// Resolves `module` (the text after `use `, with any `crate`/`self`
+    /// prefix or leading `super`s already present) from the perspective of
+    /// `source_file`, following the same conventions `rustc`'s module
+    /// resolver does: `crate::` is relative to the crate root (the nearest
+    /// ancestor directory with a `lib.rs`/`main.rs`), `self::` is the
+    /// current directory, and each leading `super::` goes up one directory.
+    /// Anything else (a bare path like `std::fmt` or an external crate name)
+    /// isn't something our own file tree can resolve, so returns `None`.
+    fn resolve_rust_module(&self, source_file: &Path, module: &str, known_files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        if module.is_empty() || module.ends_with('*') {
+            return None;
+        }
+        let mut segments: Vec<&str> = module.split("::").collect();
+        let mut base_dir = source_file.parent()?.to_path_buf();
+
+        match segments.first().copied() {
+            Some("crate") => {
+                base_dir = Self::find_rust_crate_root(source_file, known_files)?;
+                segments.remove(0);
+            }
+            Some("self") => {
+                segments.remove(0);
+            }
+            Some("super") => {
+                while segments.first().copied() == Some("super") {
+                    base_dir = base_dir.parent()?.to_path_buf();
+                    segments.remove(0);
+                }
+            }
+            _ => return None,
+        }
+
+        if segments.is_empty() {
+            return Self::find_rust_file_for_dir(&base_dir, known_files);
+        }
+
+        for (i, segment) in segments.iter().enumerate() {
+            if i == segments.len() - 1 {
+                let direct = base_dir.join(format!("{}.rs", segment));
+                if known_files.contains(&direct) {
+                    return Some(direct);
+                }
+                let mod_file = base_dir.join(segment).join("mod.rs");
+                return known_files.contains(&mod_file).then_some(mod_file);
+            }
+            base_dir = base_dir.join(segment);
+        }
+        None
+    }
+
+    fn find_rust_file_for_dir(dir: &Path, known_files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        ["mod.rs", "lib.rs", "main.rs"].into_iter().map(|name| dir.join(name)).find(|p| known_files.contains(p))
+    }
+
+    /// Walks up from `source_file` looking for the nearest ancestor
+    /// directory containing `lib.rs` or `main.rs`, the conventional crate
+    /// root `crate::`-prefixed paths are relative to.
+    fn find_rust_crate_root(source_file: &Path, known_files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        let mut dir = source_file.parent()?;
+        loop {
+            if known_files.contains(&dir.join("lib.rs")) || known_files.contains(&dir.join("main.rs")) {
+                return Some(dir.to_path_buf());
+            }
+            match dir.parent() {
+                Some(parent) if parent != dir => dir = parent,
+                _ => return None,
+            }
+        }
+    }
+
+    /// Resolves a dotted Python module (`pkg.sub.mod`) against the indexed
+    /// file set by suffix match — true `sys.path`/package-root resolution
+    /// would need to know the project's actual import roots, which isn't
+    /// tracked anywhere in this crate, so this is a heuristic rather than a
+    /// faithful reimplementation of Python's import machinery.
+    fn resolve_python_module(module: &str, known_files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        if module.is_empty() {
+            return None;
+        }
+        let relative = module.replace('.', "/");
+        let as_module_file = format!("{}.py", relative);
+        let as_package_root = format!("/{}/__init__.py", relative);
+        known_files
+            .iter()
+            .find(|f| {
+                let f = f.to_string_lossy();
+                f.ends_with(&as_module_file) || f.ends_with(&as_package_root)
+            })
+            .cloned()
+    }
+
+    /// Resolves a JS/TS relative import specifier (`./foo`, `../bar`)
+    /// against `source_file`'s directory, trying the bare path, each common
+    /// extension, and an `index` file in a directory. A bare specifier
+    /// (`lodash`, `react`) is an npm package, not something in our tree, so
+    /// returns `None` without looking it up. Doesn't read `package.json`'s
+    /// `main` field for directory imports — that needs file contents, which
+    /// this resolution pass (working only from the set of known paths)
+    /// doesn't have.
+    fn resolve_js_module(source_file: &Path, module: &str, known_files: &HashSet<PathBuf>) -> Option<PathBuf> {
+        if !module.starts_with('.') {
+            return None;
+        }
+        let base_dir = source_file.parent()?;
+        let joined = Self::normalize_relative_path(&base_dir.join(module));
+
+        let mut candidates = vec![joined.clone()];
+        for ext in ["js", "ts", "jsx", "tsx"] {
+            let mut with_ext = joined.clone();
+            with_ext.set_extension(ext);
+            candidates.push(with_ext);
+        }
+        for index_file in ["index.js", "index.ts"] {
+            candidates.push(joined.join(index_file));
+        }
+
+        candidates.into_iter().find(|c| known_files.contains(c))
+    }
+
+    /// Collapses `.`/`..` components introduced by joining a relative import
+    /// specifier onto a directory, without touching the filesystem (the
+    /// joined path may not exist yet as a real candidate — that's what the
+    /// caller is testing for).
+    fn normalize_relative_path(path: &Path) -> PathBuf {
+        let mut parts: Vec<std::ffi::OsString> = Vec::new();
+        for component in path.components() {
+            match component {
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir => {
+                    parts.pop();
+                }
+                other => parts.push(other.as_os_str().to_os_string()),
+            }
+        }
+        parts.into_iter().collect()
+    }
+
+    /// The persistent file-index cache backing `incremental`/`status`, if
+    /// `file_cache_db_path` resolved and opened successfully.
+    pub fn file_index_cache(&self) -> Option<Arc<FileIndexCache>> {
+        self.file_index_cache.clone()
+    }
+
+    /// Removes `file_path` from `file_cache`, `dependency_graph`,
+    /// `symbol_index`, and the file-index cache, for a watched file that was
+    /// deleted. Does not touch the knowledge-graph storage backend itself —
+    /// callers that also track nodes/edges in a `GraphStorage` (e.g.
+    /// `IngestionWatcher`) are responsible for evicting those separately,
+    /// since `CodebaseIndexer` has no handle on storage of its own.
+    pub async fn evict_file(&self, file_path: &Path) -> Result<()> {
+        self.file_cache.write().await.remove(file_path);
+        self.dependency_graph.write().await.remove(file_path);
+        self.call_graph.write().await.remove(file_path);
+        self.symbol_index.write().await.remove(file_path);
+        if let Some(ref file_index_cache) = self.file_index_cache {
+            file_index_cache.remove(file_path).await?;
+        }
+        Ok(())
+    }
+
     async fn perform_cross_file_analysis(&self) -> Result<()> {
-        // Placeholder for cross-file analysis
-        // This would analyze relationships between files, detect circular dependencies, etc.
-        debug!("Performing cross-file analysis");
+        let dep_graph = self.dependency_graph.read().await;
+        let cycles = Self::detect_circular_dependencies(&dep_graph);
+        drop(dep_graph);
+
+        if !cycles.is_empty() {
+            debug!("Found {} circular dependency group(s)", cycles.len());
+        }
+        *self.circular_dependencies.write().await = cycles;
+
         Ok(())
     }
 
+    /// Finds every strongly-connected component of size > 1 (or with a
+    /// self-edge) in `dependency_graph`, each one a circular dependency
+    /// among its member files. Only `resolved_target` edges are followed —
+    /// an external/unresolved dependency can't participate in a cycle
+    /// within this tree. Uses Tarjan's algorithm with an explicit work-stack
+    /// instead of recursion, since a large repo's dependency graph can be
+    /// deep enough to blow a recursive call stack.
+    fn detect_circular_dependencies(dependency_graph: &HashMap<PathBuf, Vec<Dependency>>) -> Vec<Vec<PathBuf>> {
+        let edges: HashMap<&PathBuf, Vec<&PathBuf>> = dependency_graph
+            .iter()
+            .map(|(file, deps)| {
+                let targets = deps.iter().filter_map(|dep| dep.resolved_target.as_ref()).collect();
+                (file, targets)
+            })
+            .collect();
+        let no_successors: Vec<&PathBuf> = Vec::new();
+
+        let mut index_counter: usize = 0;
+        let mut indices: HashMap<&PathBuf, usize> = HashMap::new();
+        let mut lowlink: HashMap<&PathBuf, usize> = HashMap::new();
+        let mut on_stack: HashSet<&PathBuf> = HashSet::new();
+        let mut tarjan_stack: Vec<&PathBuf> = Vec::new();
+        let mut sccs: Vec<Vec<PathBuf>> = Vec::new();
+
+        // Explicit call-stack frames standing in for recursive `strongconnect`
+        // calls: each is (node, index of the next successor to visit).
+        let mut work: Vec<(&PathBuf, usize)> = Vec::new();
+
+        for start in dependency_graph.keys() {
+            if indices.contains_key(start) {
+                continue;
+            }
+            work.push((start, 0));
+
+            while let Some(&(node, succ_idx)) = work.last() {
+                if succ_idx == 0 {
+                    indices.insert(node, index_counter);
+                    lowlink.insert(node, index_counter);
+                    index_counter += 1;
+                    tarjan_stack.push(node);
+                    on_stack.insert(node);
+                }
+
+                let successors = edges.get(node).unwrap_or(&no_successors);
+                let mut descended = false;
+                let mut next_idx = succ_idx;
+                while next_idx < successors.len() {
+                    let succ = successors[next_idx];
+                    next_idx += 1;
+                    if !indices.contains_key(succ) {
+                        work.last_mut().unwrap().1 = next_idx;
+                        work.push((succ, 0));
+                        descended = true;
+                        break;
+                    } else if on_stack.contains(succ) {
+                        let succ_index = indices[succ];
+                        let current_low = lowlink[node];
+                        lowlink.insert(node, current_low.min(succ_index));
+                    }
+                }
+                if descended {
+                    continue;
+                }
+
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    let child_low = lowlink[node];
+                    let parent_low = lowlink[parent];
+                    lowlink.insert(parent, parent_low.min(child_low));
+                }
+
+                if lowlink[node] == indices[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = tarjan_stack.pop().expect("node must be on stack for its own SCC root");
+                        on_stack.remove(member);
+                        component.push(member.clone());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    if component.len() > 1 || edges.get(node).is_some_and(|succs| succs.contains(&node)) {
+                        sccs.push(component);
+                    }
+                }
+            }
+        }
+
+        sccs
+    }
+
     /// Get indexing statistics
     pub async fn get_stats(&self) -> IndexingStats {
         let cache = self.file_cache.read().await;
@@ -872,6 +1659,8 @@ impl CodebaseIndexer {
             total_chunks: 0,
             processing_time: std::time::Duration::default(),
             errors: Vec::new(),
+            cache_hits: 0,
+            cache_misses: 0,
         };
 
         for result in cache.values() {
@@ -888,8 +1677,25 @@ impl CodebaseIndexer {
 
     /// Create a new indexer with MCP-compatible configuration
     pub fn new_with_mcp_config(path: String, config: IndexingConfig) -> Self {
+        Self::new_with_mcp_config_and_embeddings(path, config, None)
+    }
+
+    /// Same as `new_with_mcp_config`, additionally wiring `embeddings_config`'s
+    /// `cdc_chunking_enabled`/`cdc_*_chunk_size` knobs into the
+    /// `ContextWindowManager`'s `cdc_config`, so non-code documents are
+    /// chunked with `FastCdcChunker` instead of fixed-size windowing when a
+    /// caller has an `EmbeddingConfig` to hand.
+    pub fn new_with_mcp_config_and_embeddings(
+        path: String,
+        config: IndexingConfig,
+        embeddings_config: Option<&crate::config::EmbeddingConfig>,
+    ) -> Self {
         use crate::context::{ContextWindowManager, ContextWindowConfig};
-        
+
+        let cdc_config = embeddings_config
+            .filter(|embeddings| embeddings.cdc_chunking_enabled)
+            .map(|embeddings| embeddings.cdc_config());
+
         // Create default components (simplified for MCP usage)
         let context_config = ContextWindowConfig {
             max_tokens: 128000,
@@ -900,6 +1706,9 @@ impl CodebaseIndexer {
             max_chunks_per_file: 50,
             adaptive_chunking: true,
             preserve_code_blocks: true,
+            tokenizer_encoding: Default::default(),
+            cdc_config,
+            persistence_path: None,
         };
         let context_manager = Arc::new(ContextWindowManager::new(context_config, None));
         let entity_extractor = Arc::new(EntityExtractor::new(Default::default(), None).unwrap());
@@ -915,10 +1724,32 @@ impl CodebaseIndexer {
             enable_incremental: config.incremental,
             enable_dependency_mapping: config.extract_dependencies,
             enable_cross_file_analysis: config.extract_symbols,
+            // `cache_dir` lets a caller point several MCP calls at the same
+            // on-disk cache explicitly; otherwise derive a stable default
+            // under the indexed root itself so `incremental`/`status`/
+            // `watch` all agree on where the manifest lives without the
+            // caller having to name a path every time. Left disabled for
+            // the no-root constructions (`search_code`/`get_dependencies`/
+            // `analyze_structure` build an indexer with `path: ""` purely
+            // to reach their storage-backed `*_mcp` methods), so those
+            // calls don't leave a stray cache database at the working
+            // directory.
+            file_cache_db_path: if path.is_empty() {
+                None
+            } else {
+                Some(match &config.cache_dir {
+                    Some(dir) => PathBuf::from(dir),
+                    None => Path::new(&path).join(".kg_index_cache.db"),
+                })
+            },
+            enable_history: config.extract_history,
+            history_max_commits: CodebaseIndexerConfig::default().history_max_commits,
+            history_window_days: CodebaseIndexerConfig::default().history_window_days,
         };
 
         let semaphore = Arc::new(Semaphore::new(internal_config.max_concurrent_files));
-        
+        let file_index_cache = Self::open_file_index_cache(&internal_config);
+
         Self {
             config: internal_config,
             context_manager,
@@ -927,7 +1758,13 @@ impl CodebaseIndexer {
             embedding_engine: None,
             file_cache: Arc::new(RwLock::new(HashMap::new())),
             dependency_graph: Arc::new(RwLock::new(HashMap::new())),
+            call_graph: Arc::new(RwLock::new(HashMap::new())),
             semaphore,
+            embedding_backlog: Arc::new(EmbeddingBacklog::new()),
+            file_index_cache,
+            circular_dependencies: Arc::new(RwLock::new(Vec::new())),
+            symbol_index: Arc::new(RwLock::new(HashMap::new())),
+            symbol_index_clock: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -937,13 +1774,30 @@ impl CodebaseIndexer {
         path: &str,
         storage: Arc<GraphStorage>,
         embedding_engine: Arc<LocalEmbeddingEngine>,
+        profile: bool,
     ) -> Result<IndexingResult> {
         let start_time = std::time::Instant::now();
-        
+
+        crate::metrics::profiler::set_enabled(profile);
+
         // Use the existing index_codebase method
         let path_buf = std::path::Path::new(path);
         let stats = self.index_codebase(path_buf).await?;
-        
+
+        // `index_codebase` only ever populates `file_cache` in memory; write
+        // every node/edge/episode (and each episode's embedding, batched in
+        // above by `drain_and_apply`) into `storage` now so this run is
+        // actually searchable afterward instead of living only in this
+        // indexer's cache - the same thing `watcher::IngestionWatcher::reindex_path`
+        // already does per file via `GraphStorage::reindex_file`, just in one
+        // batch across every file this run touched rather than one at a time.
+        {
+            let _span = crate::metrics::profiler::enter("persist_to_storage");
+            self.persist_file_cache_to_storage(&storage).await?;
+        }
+
+        let profile = crate::metrics::profiler::take_tree();
+
         // Convert IndexingStats to IndexingResult
         Ok(IndexingResult {
             files_processed: stats.processed_files,
@@ -952,21 +1806,243 @@ impl CodebaseIndexer {
             processing_time_ms: stats.processing_time.as_millis() as u64,
             languages_detected: vec!["rust".to_string()], // Simplified
             errors: stats.errors,
+            profile,
         })
     }
 
-    /// Search code with MCP-compatible interface
+    /// Batches every node/edge/episode currently sitting in `file_cache`
+    /// into `storage` with `INSERT OR REPLACE` semantics, so running
+    /// `index_codebase_mcp` again over the same path (e.g. `incremental`
+    /// re-runs) updates rather than duplicates what's already stored.
+    /// Embeddings go through `store_embedding` keyed `"episode"`, separately
+    /// from `insert_episodes` itself, matching how every other embedded
+    /// entity in this crate is stored. Returns the number of episode
+    /// embeddings written.
+    async fn persist_file_cache_to_storage(&self, storage: &GraphStorage) -> Result<usize> {
+        let cache = self.file_cache.read().await;
+        let mut all_nodes = Vec::new();
+        let mut all_edges = Vec::new();
+        let mut all_episodes = Vec::new();
+        for file_result in cache.values() {
+            all_nodes.extend(file_result.nodes.iter().cloned());
+            all_edges.extend(file_result.edges.iter().cloned());
+            all_episodes.extend(file_result.episodes.iter().cloned());
+        }
+        drop(cache);
+
+        if !all_nodes.is_empty() {
+            storage.store_nodes_batch(&all_nodes)?;
+        }
+        if !all_edges.is_empty() {
+            storage.store_edges_batch(&all_edges)?;
+        }
+        if !all_episodes.is_empty() {
+            storage.insert_episodes(&all_episodes)?;
+        }
+
+        let mut embedded = 0usize;
+        for episode in &all_episodes {
+            if let Some(embedding) = &episode.embedding {
+                storage.store_embedding(episode.uuid, "episode", embedding)?;
+                embedded += 1;
+            }
+        }
+
+        debug!("Persisted {} nodes, {} edges, {} episodes ({} with embeddings) to storage",
+            all_nodes.len(), all_edges.len(), all_episodes.len(), embedded);
+        Ok(embedded)
+    }
+
+    /// Fuzzy-searches the symbol index built by `build_symbol_index`/
+    /// `patch_symbol_index` for symbols whose name matches `query` (exact,
+    /// prefix, substring, or in-order-subsequence — see `fuzzy_match_score`),
+    /// optionally filtered to `symbol_type` (ignored if empty or `"all"`)
+    /// and/or to a minimum `complexity`/`entropy` (ignored if `None`), so a
+    /// caller can ask for "most complex functions" directly instead of
+    /// eyeballing every result. Matches are ranked by match quality, then by
+    /// how recently their file was indexed, unless `rank_by_recency` is set,
+    /// in which case a symbol's `last_commit_date` (from `extract_history`)
+    /// takes priority over match quality instead — symbols with no commit
+    /// date (history wasn't enabled, or `git` found none) sort last. Only
+    /// the surviving top `max_results` pay the cost of a `file_cache` lookup
+    /// to assemble their `context_lines`.
     pub async fn search_code_mcp(
         &self,
-        _query: &str,
-        _symbol_type: &str,
-        _context_lines: usize,
-        _max_results: usize,
+        query: &str,
+        symbol_type: &str,
+        context_lines: usize,
+        max_results: usize,
         _storage: Arc<GraphStorage>,
+        min_complexity: Option<u32>,
+        min_entropy: Option<f64>,
+        rank_by_recency: bool,
     ) -> Result<Vec<CodeSearchResult>> {
-        // Placeholder implementation
-        // In a full implementation, this would search through indexed code
-        Ok(vec![])
+        let index = self.symbol_index.read().await;
+        let type_filter_active = !symbol_type.is_empty() && !symbol_type.eq_ignore_ascii_case("all");
+
+        let mut matches: Vec<(i64, &SymbolIndexEntry)> = index
+            .values()
+            .flatten()
+            .filter(|entry| !type_filter_active || entry.symbol_type.eq_ignore_ascii_case(symbol_type))
+            .filter(|entry| min_complexity.map_or(true, |min| entry.complexity >= min))
+            .filter(|entry| min_entropy.map_or(true, |min| entry.entropy >= min))
+            .filter_map(|entry| Self::fuzzy_match_score(query, &entry.symbol_name).map(|score| (score, entry)))
+            .collect();
+        if rank_by_recency {
+            matches.sort_by(|(score_a, entry_a), (score_b, entry_b)| {
+                entry_b
+                    .last_commit_date
+                    .cmp(&entry_a.last_commit_date)
+                    .then(score_b.cmp(score_a))
+            });
+        } else {
+            matches.sort_by(|(score_a, entry_a), (score_b, entry_b)| {
+                score_b.cmp(score_a).then(entry_b.indexed_at.cmp(&entry_a.indexed_at))
+            });
+        }
+        matches.truncate(max_results);
+
+        let cache = self.file_cache.read().await;
+        let mut results = Vec::with_capacity(matches.len());
+        for (_, entry) in matches {
+            if let Some(file_result) = cache.get(&entry.file_path) {
+                if let Some(found) =
+                    Self::find_symbol_in_result(&entry.file_path, file_result, &entry.symbol_name, context_lines)
+                {
+                    results.push(found);
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Scores how well `candidate` matches `query` for fuzzy symbol search,
+    /// or `None` if `candidate` doesn't even contain `query`'s characters in
+    /// order. Exact (case-insensitive) matches score highest, then prefix
+    /// matches, then substring matches, then plain subsequence matches —
+    /// with a bonus for contiguous runs and matches starting at a word
+    /// boundary (after `_` or at a lower-to-upper case transition), similar
+    /// in spirit to fuzzy finders like fzf.
+    fn fuzzy_match_score(query: &str, candidate: &str) -> Option<i64> {
+        if query.is_empty() {
+            return Some(0);
+        }
+
+        let query_lower = query.to_lowercase();
+        let candidate_lower = candidate.to_lowercase();
+
+        if candidate_lower == query_lower {
+            return Some(1_000_000);
+        }
+        if candidate_lower.starts_with(&query_lower) {
+            return Some(500_000 - candidate.len() as i64);
+        }
+        if let Some(pos) = candidate_lower.find(&query_lower) {
+            return Some(250_000 - pos as i64 - candidate.len() as i64);
+        }
+
+        let query_chars: Vec<char> = query_lower.chars().collect();
+        let candidate_chars: Vec<char> = candidate_lower.chars().collect();
+        let mut query_idx = 0;
+        let mut run_length: i64 = 0;
+        let mut score: i64 = 0;
+
+        for (i, &c) in candidate_chars.iter().enumerate() {
+            if query_idx >= query_chars.len() {
+                break;
+            }
+            if c != query_chars[query_idx] {
+                run_length = 0;
+                continue;
+            }
+
+            run_length += 1;
+            score += 10 + run_length * 2;
+            let at_word_boundary = i == 0
+                || candidate_chars[i - 1] == '_'
+                || (candidate_chars[i - 1].is_lowercase() && c.is_uppercase());
+            if at_word_boundary {
+                score += 15;
+            }
+            query_idx += 1;
+        }
+
+        if query_idx < query_chars.len() {
+            return None;
+        }
+        Some(score - candidate.len() as i64 / 4)
+    }
+
+    /// Resolves `symbol` against `call_graph` (built alongside
+    /// `dependency_graph` by `build_call_graph`/`patch_call_graph`) and
+    /// returns its callers (`incoming`, edges whose `callee` is `symbol`)
+    /// and callees (`outgoing`, edges whose `caller` is `symbol`), optionally
+    /// restricted to edges found in `path_filter`. An exact (case-sensitive)
+    /// name match is tried first; when none exists, falls back to
+    /// `fuzzy_match_score` over every distinct name in the graph and returns
+    /// a ranked `Candidates` list instead of guessing which one was meant —
+    /// a caller only gets `Found` when resolution is unambiguous.
+    pub async fn call_hierarchy_mcp(&self, symbol: &str, path_filter: Option<&Path>) -> CallHierarchyResult {
+        let graph = self.call_graph.read().await;
+        let edges: Vec<(&PathBuf, &CallEdge)> = graph
+            .iter()
+            .filter(|(file, _)| path_filter.map_or(true, |p| file.as_path() == p))
+            .flat_map(|(file, file_edges)| file_edges.iter().map(move |edge| (file, edge)))
+            .collect();
+
+        let exact_exists = edges.iter().any(|(_, edge)| edge.caller == symbol || edge.callee == symbol);
+        if !exact_exists {
+            let mut seen = HashSet::new();
+            let mut candidates: Vec<(i64, String)> = Vec::new();
+            for (_, edge) in &edges {
+                for name in [&edge.caller, &edge.callee] {
+                    if seen.insert(name.clone()) {
+                        if let Some(score) = Self::fuzzy_match_score(symbol, name) {
+                            candidates.push((score, name.clone()));
+                        }
+                    }
+                }
+            }
+            candidates.sort_by(|(score_a, _), (score_b, _)| score_b.cmp(score_a));
+            return CallHierarchyResult::Candidates(candidates.into_iter().map(|(_, name)| name).collect());
+        }
+
+        let incoming = edges
+            .iter()
+            .filter(|(_, edge)| edge.callee == symbol)
+            .map(|(file, edge)| CallHierarchyEdge { symbol: edge.caller.clone(), file: (*file).clone(), line: edge.line })
+            .collect();
+        let outgoing = edges
+            .iter()
+            .filter(|(_, edge)| edge.caller == symbol)
+            .map(|(file, edge)| CallHierarchyEdge { symbol: edge.callee.clone(), file: (*file).clone(), line: edge.line })
+            .collect();
+
+        CallHierarchyResult::Found {
+            symbol: symbol.to_string(),
+            incoming,
+            outgoing,
+        }
+    }
+
+    /// Spelling-tolerant corrections for `query` against every indexed
+    /// symbol name, via `symbol_suggest::TrigramIndex` — backs the
+    /// standalone `suggest_symbol` operation and `call_hierarchy`'s
+    /// `suggest` param. Complements the subsequence-based fuzzy fallback
+    /// `call_hierarchy_mcp` already does for an unresolved exact match.
+    pub async fn suggest_symbol_mcp(&self, query: &str, limit: usize) -> Vec<SymbolSuggestion> {
+        let index = self.symbol_index.read().await;
+        let mut seen = HashSet::new();
+        let names: Vec<String> = index
+            .values()
+            .flatten()
+            .map(|entry| entry.symbol_name.clone())
+            .filter(|name| seen.insert(name.clone()))
+            .collect();
+
+        let trigram_index = crate::indexing::symbol_suggest::TrigramIndex::build(&names);
+        let max_distance = crate::indexing::symbol_suggest::default_max_distance(query);
+        trigram_index.suggest(query, max_distance, limit)
     }
 
     /// Get file dependencies with MCP-compatible interface
@@ -984,20 +2060,476 @@ impl CodebaseIndexer {
     pub async fn analyze_codebase_structure_mcp(
         &self,
         _storage: Arc<GraphStorage>,
+        use_cargo_metadata: bool,
+        root_path: &Path,
+        profile: bool,
+        include_non_source: bool,
     ) -> Result<CodebaseAnalysis> {
-        // Placeholder implementation
-        // In a full implementation, this would analyze the codebase structure
+        crate::metrics::profiler::set_enabled(profile);
+        let _span = crate::metrics::profiler::enter("analyze_codebase_structure");
+        let cache = self.file_cache.read().await;
+
+        let mut total_lines = 0usize;
+        let mut file_types: HashMap<String, usize> = HashMap::new();
+        let mut complexity_metrics: HashMap<String, f32> = HashMap::new();
+        // (sum of symbol complexity, symbol count) per language, used below
+        // to compute each language's `<language>::avg` entry.
+        let mut language_complexity_totals: HashMap<String, (f32, usize)> = HashMap::new();
+
+        {
+            let _span = crate::metrics::profiler::enter("scan_file_cache");
+            for (file_path, result) in cache.iter() {
+                total_lines += result.metadata.lines_of_code;
+                let language_name = format!("{:?}", result.metadata.language).to_lowercase();
+                *file_types.entry(language_name.clone()).or_insert(0) += 1;
+
+                let mut file_total = 0f32;
+                let mut file_symbol_count = 0usize;
+                for (symbol, score) in &result.metadata.symbol_complexity {
+                    complexity_metrics.insert(format!("{}::{}", file_path.display(), symbol), *score as f32);
+                    file_total += *score as f32;
+                    file_symbol_count += 1;
+                }
+
+                if file_symbol_count > 0 {
+                    complexity_metrics.insert(
+                        format!("{}::avg", file_path.display()),
+                        file_total / file_symbol_count as f32,
+                    );
+                    let totals = language_complexity_totals.entry(language_name).or_insert((0.0, 0));
+                    totals.0 += file_total;
+                    totals.1 += file_symbol_count;
+                }
+            }
+        }
+
+        for (language_name, (total, count)) in &language_complexity_totals {
+            if *count > 0 {
+                complexity_metrics.insert(format!("{}::avg", language_name), total / *count as f32);
+            }
+        }
+
+        let languages: Vec<String> = file_types.keys().cloned().collect();
+
+        let circular_dependencies: Vec<Vec<String>> = self
+            .circular_dependencies
+            .read()
+            .await
+            .iter()
+            .map(|cycle| cycle.iter().map(|p| p.to_string_lossy().to_string()).collect())
+            .collect();
+
+        // Files `should_process_file` never let `discover_files` cache in
+        // the first place (config, docs, data fixtures, generated files, …)
+        // — invisible above since everything up to here reads `file_cache`.
+        // Walked fresh against `root_path` rather than assumed from a prior
+        // `index_codebase` run, same as the `cargo_metadata` lookup below.
+        let non_source_files: Vec<PathBuf> = if include_non_source {
+            let _span = crate::metrics::profiler::enter("discover_non_source_files");
+            self.discover_non_source_files(root_path).await?
+        } else {
+            Vec::new()
+        };
+        for file_path in &non_source_files {
+            let category = Self::classify_non_source_file(file_path);
+            *file_types.entry(format!("non_source::{}", category)).or_insert(0) += 1;
+        }
+
+        let directory_structure = {
+            let _span = crate::metrics::profiler::enter("directory_structure");
+            Self::build_directory_structure(
+                cache
+                    .keys()
+                    .map(|path| (path, serde_json::Value::Bool(true)))
+                    .chain(
+                        non_source_files
+                            .iter()
+                            .map(|path| (path, serde_json::Value::String(Self::classify_non_source_file(path).to_string()))),
+                    ),
+            )
+        };
+        let dependency_graph = {
+            let _span = crate::metrics::profiler::enter("dependency_graph_json");
+            self.dependency_graph_json().await
+        };
+
+        let cargo_workspace = if use_cargo_metadata {
+            let _span = crate::metrics::profiler::enter("cargo_metadata");
+            crate::indexing::cargo_metadata::workspace_metadata(root_path)
+        } else {
+            None
+        };
+
+        drop(_span);
+        let profile = crate::metrics::profiler::take_tree();
+
         Ok(CodebaseAnalysis {
-            total_files: 0,
-            total_lines: 0,
-            languages: vec!["rust".to_string()],
-            directory_structure: serde_json::json!({}),
-            file_types: HashMap::new(),
-            complexity_metrics: HashMap::new(),
-            dependency_graph: serde_json::json!({}),
+            total_files: cache.len(),
+            total_lines,
+            languages,
+            directory_structure,
+            file_types,
+            complexity_metrics,
+            dependency_graph,
+            circular_dependencies,
+            cargo_workspace,
+            profile,
+        })
+    }
+
+    /// Builds a nested JSON object mirroring the on-disk directory structure
+    /// of `paths` — directories as nested objects, files as whatever leaf
+    /// value their entry carries (plain `true` for parsed source, a category
+    /// string for the `include_non_source` sweep). No per-file metadata
+    /// beyond the leaf lives here; `file_types`/`complexity_metrics` already
+    /// cover that, keyed by file path instead of by tree position.
+    fn build_directory_structure<'a>(
+        entries: impl Iterator<Item = (&'a PathBuf, serde_json::Value)>,
+    ) -> serde_json::Value {
+        let mut root = serde_json::Map::new();
+        for (path, leaf) in entries {
+            let components: Vec<String> = path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect();
+            let mut node = &mut root;
+            for (i, component) in components.iter().enumerate() {
+                if i == components.len() - 1 {
+                    node.insert(component.clone(), leaf.clone());
+                    break;
+                }
+                let entry = node
+                    .entry(component.clone())
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+                if !entry.is_object() {
+                    *entry = serde_json::Value::Object(serde_json::Map::new());
+                }
+                node = entry.as_object_mut().expect("just ensured this entry is an object");
+            }
+        }
+        serde_json::Value::Object(root)
+    }
+
+    /// Walks `root_path` the same way `discover_files` does (same excluded
+    /// directories/patterns), but keeps exactly the files `should_process_file`
+    /// would have skipped — i.e. everything outside `supported_extensions`.
+    /// Backs `analyze_codebase_structure_mcp`'s `include_non_source` sweep,
+    /// which wants a whole-repository picture rather than just the
+    /// analyzable source `index_codebase` already cached.
+    async fn discover_non_source_files(&self, root_path: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut stack = vec![root_path.to_path_buf()];
+
+        while let Some(current_path) = stack.pop() {
+            if current_path.is_dir() {
+                if self.should_exclude_directory(&current_path) {
+                    continue;
+                }
+
+                let mut entries = tokio::fs::read_dir(&current_path).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    stack.push(entry.path());
+                }
+            } else if current_path.is_file() && !self.should_process_file(&current_path) {
+                let path_str = current_path.to_string_lossy();
+                if !self.config.exclude_patterns.iter().any(|pattern| path_str.contains(pattern.as_str())) {
+                    files.push(current_path);
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Buckets a non-source file (see `discover_non_source_files`) by
+    /// extension/filename into one of the coarse categories the
+    /// `include_non_source` sweep reports under `file_types`'
+    /// `non_source::<category>` keys.
+    fn classify_non_source_file(file_path: &Path) -> &'static str {
+        let file_name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
+        if file_name.contains(".min.") || file_name.contains(".generated.") || file_name.ends_with(".pb.go")
+            || file_name.ends_with(".g.cs") || file_name.ends_with("_pb2.py")
+        {
+            return "generated";
+        }
+
+        match file_path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()) {
+            Some(ext) => match ext.as_str() {
+                "ini" | "cfg" | "conf" | "env" | "properties" | "lock" => "config",
+                "rst" | "adoc" | "tex" | "rtf" => "markup",
+                "csv" | "tsv" | "sql" | "proto" | "graphql" | "parquet" | "avro" => "data",
+                "png" | "jpg" | "jpeg" | "gif" | "ico" | "bmp" | "webp" | "pdf" | "zip" | "tar" | "gz"
+                | "so" | "dylib" | "dll" | "exe" | "bin" | "woff" | "woff2" | "ttf" | "otf" | "wasm" => "binary",
+                _ => "other",
+            },
+            None => match file_name.trim_start_matches('.') {
+                "dockerfile" | "makefile" | "gitignore" | "gitattributes" | "editorconfig" | "license" => "config",
+                _ => "other",
+            },
+        }
+    }
+
+    /// Renders `dependency_graph` as JSON: one array per source file, each
+    /// entry the target spec, dependency kind, and resolved file (if any).
+    async fn dependency_graph_json(&self) -> serde_json::Value {
+        let dep_graph = self.dependency_graph.read().await;
+        let map = dep_graph
+            .iter()
+            .map(|(file, deps)| {
+                let deps_json: Vec<serde_json::Value> = deps
+                    .iter()
+                    .map(|dep| {
+                        serde_json::json!({
+                            "target": dep.target_file.to_string_lossy(),
+                            "type": format!("{:?}", dep.dependency_type),
+                            "resolved": dep.resolved_target.as_ref().map(|p| p.to_string_lossy().to_string()),
+                        })
+                    })
+                    .collect();
+                (file.to_string_lossy().to_string(), serde_json::Value::Array(deps_json))
+            })
+            .collect();
+        serde_json::Value::Object(map)
+    }
+
+    /// Finds the declaration of `symbol`, searching from the perspective of
+    /// `from_file`. When `from_file`'s own entry in `dependency_graph` has a
+    /// `Use`/`Import`/`Extends`/`Implements` dependency naming `symbol`, its
+    /// `target_file` is searched first so an ambiguous name resolves to the
+    /// declaration `from_file` can actually see; otherwise falls back to
+    /// `from_file` itself, then every other indexed file.
+    pub async fn find_definition(&self, symbol: &str, from_file: &Path) -> Result<Option<CodeSearchResult>> {
+        let dependency_graph = self.dependency_graph.read().await;
+        let cache = self.file_cache.read().await;
+
+        if let Some(deps) = dependency_graph.get(from_file) {
+            for dep in deps {
+                if dep.symbol.as_deref() != Some(symbol) {
+                    continue;
+                }
+                if !matches!(
+                    dep.dependency_type,
+                    DependencyType::Use | DependencyType::Import | DependencyType::Extends | DependencyType::Implements
+                ) {
+                    continue;
+                }
+                if let Some(result) = cache.get(&dep.target_file) {
+                    if let Some(found) = Self::find_symbol_in_result(&dep.target_file, result, symbol, 2) {
+                        return Ok(Some(found));
+                    }
+                }
+            }
+        }
+
+        if let Some(result) = cache.get(from_file) {
+            if let Some(found) = Self::find_symbol_in_result(from_file, result, symbol, 2) {
+                return Ok(Some(found));
+            }
+        }
+
+        for (file_path, result) in cache.iter() {
+            if file_path.as_path() == from_file {
+                continue;
+            }
+            if let Some(found) = Self::find_symbol_in_result(file_path, result, symbol, 2) {
+                return Ok(Some(found));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds every occurrence of `symbol` across all indexed files, not just
+    /// its declaration. A chunk whose own `symbol_name` metadata matches
+    /// `symbol` is reported with full location precision (line/column from
+    /// `create_episodes_from_content`'s metadata); a chunk that merely
+    /// mentions `symbol` in its content (e.g. a call site inside a different
+    /// function) is still reported, with `column_number` left at 0 since no
+    /// per-occurrence column is tracked for plain content matches.
+    pub async fn find_references(&self, symbol: &str) -> Result<Vec<CodeSearchResult>> {
+        let cache = self.file_cache.read().await;
+        let mut results = Vec::new();
+
+        for (file_path, result) in cache.iter() {
+            for episode in &result.episodes {
+                if !Self::content_references_symbol(&episode.content, symbol) {
+                    continue;
+                }
+
+                if let Some(found) = Self::build_search_result(file_path, episode, &result.metadata.language, 2) {
+                    results.push(found);
+                    continue;
+                }
+
+                let line_number = episode
+                    .metadata
+                    .get("start_line")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as usize;
+                let end_line = episode
+                    .metadata
+                    .get("end_line")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as usize)
+                    .unwrap_or(line_number);
+                results.push(CodeSearchResult {
+                    file_path: file_path.to_string_lossy().to_string(),
+                    symbol_name: symbol.to_string(),
+                    symbol_type: "reference".to_string(),
+                    line_number,
+                    column_number: 0,
+                    end_line,
+                    context_lines: episode.content.lines().map(|s| s.to_string()).collect(),
+                    full_context: episode.content.clone(),
+                    language: format!("{:?}", result.metadata.language).to_lowercase(),
+                    relevance_score: 0.5,
+                    // Not a declared symbol (see the doc comment above), so
+                    // the per-symbol quality metrics don't apply here.
+                    complexity: 0,
+                    entropy: 0.0,
+                    last_commit_date: None,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Lists every symbol declared in `file_path`, in source order. Each
+    /// symbol is reported as a flat `CodeSearchResult` rather than a nested
+    /// tree: `CodeSearchResult` has no parent/child field, and introducing
+    /// one isn't worth it for the one caller this has so far — a method's
+    /// enclosing `impl`/`class` can still be told apart by comparing
+    /// `line_number` ranges if a consumer needs that.
+    pub async fn document_symbols(&self, file_path: &Path) -> Result<Vec<CodeSearchResult>> {
+        let cache = self.file_cache.read().await;
+        let Some(result) = cache.get(file_path) else {
+            return Ok(Vec::new());
+        };
+
+        let mut symbols: Vec<CodeSearchResult> = result
+            .episodes
+            .iter()
+            .filter_map(|episode| Self::build_search_result(file_path, episode, &result.metadata.language, 0))
+            .collect();
+        symbols.sort_by_key(|s| s.line_number);
+        Ok(symbols)
+    }
+
+    /// Finds the episode in `result` whose `symbol_name` metadata matches
+    /// `symbol`, if any, and builds a `CodeSearchResult` from it with
+    /// `context_radius` lines of context on either side.
+    fn find_symbol_in_result(
+        file_path: &Path,
+        result: &FileIndexResult,
+        symbol: &str,
+        context_radius: usize,
+    ) -> Option<CodeSearchResult> {
+        let episode = result
+            .episodes
+            .iter()
+            .find(|episode| episode.metadata.get("symbol_name").and_then(|v| v.as_str()) == Some(symbol))?;
+        Self::build_search_result(file_path, episode, &result.metadata.language, context_radius)
+    }
+
+    /// Builds a `CodeSearchResult` from an episode that declares a symbol
+    /// (i.e. has `symbol_name`/`symbol_type`/`symbol_start_line`/`start_col`
+    /// metadata, set by `create_episodes_from_content`). Returns `None` for
+    /// an episode with no declared symbol. `context_radius` lines are
+    /// included on either side of the symbol's own line.
+    fn build_search_result(
+        file_path: &Path,
+        episode: &Episode,
+        language: &ProgrammingLanguage,
+        context_radius: usize,
+    ) -> Option<CodeSearchResult> {
+        let symbol_name = episode.metadata.get("symbol_name")?.as_str()?.to_string();
+        let symbol_type = episode
+            .metadata
+            .get("symbol_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let line_number = episode
+            .metadata
+            .get("symbol_start_line")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+        let column_number = episode
+            .metadata
+            .get("start_col")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+        let chunk_start_line = episode
+            .metadata
+            .get("start_line")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(line_number);
+        let end_line = episode
+            .metadata
+            .get("end_line")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(line_number);
+        let complexity = episode.metadata.get("complexity").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let entropy = episode.metadata.get("entropy").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let last_commit_date = episode.metadata.get("last_commit_date").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+        let lines: Vec<&str> = episode.content.lines().collect();
+        let relative_line = line_number.saturating_sub(chunk_start_line);
+        let start = relative_line.saturating_sub(context_radius);
+        let end = (relative_line + context_radius + 1).min(lines.len());
+        let context_lines = lines
+            .get(start..end)
+            .unwrap_or_default()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        Some(CodeSearchResult {
+            file_path: file_path.to_string_lossy().to_string(),
+            symbol_name,
+            symbol_type,
+            line_number,
+            column_number,
+            end_line,
+            context_lines,
+            full_context: episode.content.clone(),
+            language: format!("{:?}", language).to_lowercase(),
+            relevance_score: 1.0,
+            complexity,
+            entropy,
+            last_commit_date,
         })
     }
 
+    /// Whether `content` mentions `symbol` as a whole word (not merely as a
+    /// substring of a longer identifier).
+    fn content_references_symbol(content: &str, symbol: &str) -> bool {
+        if symbol.is_empty() {
+            return false;
+        }
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let mut search_from = 0;
+        while let Some(offset) = content[search_from..].find(symbol) {
+            let match_start = search_from + offset;
+            let match_end = match_start + symbol.len();
+
+            let before_ok = content[..match_start].chars().next_back().map(|c| !is_word_char(c)).unwrap_or(true);
+            let after_ok = content[match_end..].chars().next().map(|c| !is_word_char(c)).unwrap_or(true);
+            if before_ok && after_ok {
+                return true;
+            }
+
+            search_from = match_start + 1;
+            if search_from >= content.len() {
+                break;
+            }
+        }
+        false
+    }
+
     /// Get supported extensions based on language filter
     fn get_supported_extensions(languages: &Option<Vec<String>>) -> Vec<String> {
         let all_extensions = vec![
@@ -1043,8 +2575,40 @@ impl CodebaseIndexer {
     }
 }
 
-#[derive(Debug, Clone)]
-struct CodeBlock {
-    content: String,
-    block_type: String,
-} 
\ No newline at end of file
+/// Maps the indexer's own `ProgrammingLanguage` (derived from `detect_language`)
+/// onto `CodeChunker`'s `SupportedLanguage`. The two enums have matching
+/// variant sets today; this stays a straight translation rather than
+/// merging the two, since `ProgrammingLanguage` also drives
+/// `determine_chunk_type`/`extract_dependencies` elsewhere in this file.
+fn to_supported_language(language: &ProgrammingLanguage) -> SupportedLanguage {
+    match language {
+        ProgrammingLanguage::Rust => SupportedLanguage::Rust,
+        ProgrammingLanguage::Python => SupportedLanguage::Python,
+        ProgrammingLanguage::JavaScript => SupportedLanguage::JavaScript,
+        ProgrammingLanguage::TypeScript => SupportedLanguage::TypeScript,
+        ProgrammingLanguage::Java => SupportedLanguage::Java,
+        ProgrammingLanguage::Cpp => SupportedLanguage::Cpp,
+        ProgrammingLanguage::C => SupportedLanguage::C,
+        ProgrammingLanguage::Go => SupportedLanguage::Go,
+        ProgrammingLanguage::Ruby => SupportedLanguage::Ruby,
+        ProgrammingLanguage::PHP => SupportedLanguage::PHP,
+        ProgrammingLanguage::CSharp => SupportedLanguage::CSharp,
+        ProgrammingLanguage::Swift => SupportedLanguage::Swift,
+        ProgrammingLanguage::Kotlin => SupportedLanguage::Kotlin,
+        ProgrammingLanguage::Scala => SupportedLanguage::Scala,
+        ProgrammingLanguage::Clojure => SupportedLanguage::Clojure,
+        ProgrammingLanguage::Haskell => SupportedLanguage::Haskell,
+        ProgrammingLanguage::OCaml => SupportedLanguage::OCaml,
+        ProgrammingLanguage::Elm => SupportedLanguage::Elm,
+        ProgrammingLanguage::Dart => SupportedLanguage::Dart,
+        ProgrammingLanguage::Markdown => SupportedLanguage::Markdown,
+        ProgrammingLanguage::Text => SupportedLanguage::Text,
+        ProgrammingLanguage::Json => SupportedLanguage::Json,
+        ProgrammingLanguage::Yaml => SupportedLanguage::Yaml,
+        ProgrammingLanguage::Toml => SupportedLanguage::Toml,
+        ProgrammingLanguage::Xml => SupportedLanguage::Xml,
+        ProgrammingLanguage::Html => SupportedLanguage::Html,
+        ProgrammingLanguage::Css => SupportedLanguage::Css,
+        ProgrammingLanguage::Unknown => SupportedLanguage::Unknown,
+    }
+}
\ No newline at end of file
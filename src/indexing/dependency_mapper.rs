@@ -1,5 +1,6 @@
 use std::path::Path;
 use anyhow::Result;
+use regex::Regex;
 
 #[derive(Debug, Clone)]
 pub enum DependencyType {
@@ -12,15 +13,114 @@ pub enum DependencyType {
     References,
 }
 
-pub struct DependencyMapper;
+/// One compiled pattern and the `DependencyType` its first capture group
+/// (the imported module/path/class name) maps to.
+struct DependencyPattern {
+    regex: Regex,
+    dependency_type: DependencyType,
+}
+
+/// Regex-based, multi-language import/dependency extractor for the code
+/// knowledge graph: scans a file's content line by line and emits one
+/// `(target, DependencyType)` per matched declaration, dispatching on
+/// `file_path`'s extension to a per-language pattern set compiled once in
+/// `new` (mirroring how `EntityExtractor` pre-compiles its regexes,
+/// rather than recompiling per call).
+pub struct DependencyMapper {
+    rust_patterns: Vec<DependencyPattern>,
+    python_patterns: Vec<DependencyPattern>,
+    js_patterns: Vec<DependencyPattern>,
+    c_patterns: Vec<DependencyPattern>,
+    java_patterns: Vec<DependencyPattern>,
+}
 
 impl DependencyMapper {
     pub fn new() -> Self {
-        Self
+        Self {
+            rust_patterns: Self::compile(&[
+                (r"^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+([\w:]+(?:::\{[^}]*\})?)\s*;", DependencyType::Use),
+                (r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;", DependencyType::Use),
+            ]),
+            python_patterns: Self::compile(&[
+                (r"^\s*from\s+([\w.]+)\s+import\s+", DependencyType::Import),
+                (r"^\s*import\s+([\w.]+)", DependencyType::Import),
+            ]),
+            js_patterns: Self::compile(&[
+                (r#"^\s*import\s+.+?\s+from\s+['"]([^'"]+)['"]"#, DependencyType::Import),
+                (r#"require\(\s*['"]([^'"]+)['"]\s*\)"#, DependencyType::Require),
+                (r#"^\s*import\s+['"]([^'"]+)['"]"#, DependencyType::Import),
+            ]),
+            c_patterns: Self::compile(&[(r#"^\s*#include\s*[<"]([^">]+)[">]"#, DependencyType::Include)]),
+            java_patterns: Self::compile(&[
+                (r"^\s*import\s+(?:static\s+)?([\w.]+(?:\.\*)?)\s*;", DependencyType::Import),
+                (r"\bextends\s+([\w][\w.]*)", DependencyType::Extends),
+                (r"\bimplements\s+([\w.,\s]+?)\s*(?:\{|$)", DependencyType::Implements),
+            ]),
+        }
+    }
+
+    fn compile(specs: &[(&str, DependencyType)]) -> Vec<DependencyPattern> {
+        specs
+            .iter()
+            .filter_map(|(pattern, dependency_type)| {
+                Regex::new(pattern).ok().map(|regex| DependencyPattern { regex, dependency_type: dependency_type.clone() })
+            })
+            .collect()
+    }
+
+    fn patterns_for(&self, file_path: &Path) -> Option<&[DependencyPattern]> {
+        match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("rs") => Some(&self.rust_patterns),
+            Some("py") => Some(&self.python_patterns),
+            Some("js") | Some("jsx") | Some("ts") | Some("tsx") | Some("mjs") | Some("cjs") => Some(&self.js_patterns),
+            Some("c") | Some("h") | Some("cpp") | Some("cc") | Some("cxx") | Some("hpp") | Some("hh") => Some(&self.c_patterns),
+            Some("java") => Some(&self.java_patterns),
+            _ => None,
+        }
     }
 
-    pub fn extract_dependencies(&self, _content: &str, _file_path: &Path) -> Result<Vec<(String, DependencyType)>> {
-        // Placeholder implementation
-        Ok(vec![])
+    /// Scans `content` for import/include/inheritance declarations
+    /// suitable for building a code knowledge graph's file-to-dependency
+    /// edges. Returns an empty vec for an extension with no pattern set,
+    /// same as the old placeholder, rather than erroring.
+    pub fn extract_dependencies(&self, content: &str, file_path: &Path) -> Result<Vec<(String, DependencyType)>> {
+        let Some(patterns) = self.patterns_for(file_path) else {
+            return Ok(Vec::new());
+        };
+
+        let mut dependencies = Vec::new();
+        for line in content.lines() {
+            for pattern in patterns {
+                let Some(captures) = pattern.regex.captures(line) else { continue };
+                let Some(target) = captures.get(1) else { continue };
+
+                match pattern.dependency_type {
+                    // `implements A, B` is one declaration naming several
+                    // interfaces - split it into one dependency per name
+                    // rather than one meaningless comma-joined string.
+                    DependencyType::Implements => {
+                        dependencies.extend(
+                            target
+                                .as_str()
+                                .split(',')
+                                .map(|name| name.trim().to_string())
+                                .filter(|name| !name.is_empty())
+                                .map(|name| (name, DependencyType::Implements)),
+                        );
+                    }
+                    ref dependency_type => {
+                        dependencies.push((target.as_str().trim().to_string(), dependency_type.clone()));
+                    }
+                }
+            }
+        }
+
+        Ok(dependencies)
     }
-} 
\ No newline at end of file
+}
+
+impl Default for DependencyMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,188 @@
+//! Cross-file batched embedding queue for `CodebaseIndexer`.
+//!
+//! `create_episodes_from_content` used to call `engine.encode_text` once per
+//! code block, one at a time, inline with the rest of `process_file`. That
+//! never batches across the many files being indexed concurrently, and a
+//! single oversized block could overflow the model's context before the
+//! tokenizer-level truncation in `OnnxEmbeddingEngine` ever saw it batched
+//! with anything else. Instead, episodes are created with no embedding set
+//! and their text is enqueued here; `index_codebase` drains the backlog once
+//! every `process_file` task has finished, packing pending texts into
+//! batches sized by [`EmbeddingQueue`]'s token budget rather than a flat
+//! item count, and retrying a whole batch with exponential backoff if the
+//! engine call fails transiently. The single-file incremental path
+//! (`CodebaseIndexer::index_file`, driven by the file watcher) has no later
+//! "every file is done" point to drain at, so it drains just its own
+//! entries immediately via `drain_and_apply_to_result` instead.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::embeddings::{EmbeddingQueue, LocalEmbeddingEngine};
+use super::codebase_indexer::FileIndexResult;
+
+/// A single episode's text awaiting an embedding, identified by the
+/// episode's own uuid so the result can be written back without needing to
+/// track its position in any particular `Vec`.
+struct PendingEmbedding {
+    episode_uuid: Uuid,
+    file_path: PathBuf,
+    text: String,
+}
+
+/// How many times a batch is retried after a transient embedding failure
+/// before it's given up on (leaving that batch's episodes without an
+/// embedding rather than blocking the rest of indexing).
+const MAX_RETRIES: u32 = 3;
+
+/// Accumulates pending `(episode, text)` pairs across every file being
+/// indexed, for a single batched embedding pass at the end of
+/// `index_codebase` instead of one inference call per code block.
+#[derive(Default)]
+pub struct EmbeddingBacklog {
+    pending: Mutex<Vec<PendingEmbedding>>,
+}
+
+impl EmbeddingBacklog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues `text` for later embedding instead of computing it inline.
+    pub fn enqueue(&self, episode_uuid: Uuid, file_path: PathBuf, text: String) {
+        self.pending.lock().unwrap().push(PendingEmbedding { episode_uuid, file_path, text });
+    }
+
+    /// Packs every queued text into token-budget batches, embeds each batch
+    /// (retrying the whole batch with exponential backoff on failure), and
+    /// writes the resulting vectors back onto the matching episodes in
+    /// `file_cache`. A batch's embeddings are only ever written once every
+    /// text in that batch has succeeded, so a failed batch never leaves some
+    /// of its episodes embedded and others not — it's skipped as a whole and
+    /// those episodes are left unembedded rather than half-indexed.
+    /// Returns the number of episodes that were successfully embedded.
+    pub async fn drain_and_apply(
+        &self,
+        engine: &LocalEmbeddingEngine,
+        target_tokens_per_batch: usize,
+        file_cache: &RwLock<HashMap<PathBuf, FileIndexResult>>,
+    ) -> Result<usize> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let texts: Vec<String> = pending.iter().map(|p| p.text.clone()).collect();
+        let batches = EmbeddingQueue::new(target_tokens_per_batch).plan_batches(&texts);
+
+        let mut embedded = 0usize;
+        for batch_indices in batches {
+            let batch_texts: Vec<String> = batch_indices.iter().map(|&i| texts[i].clone()).collect();
+
+            let embeddings = match embed_with_retry(engine, &batch_texts).await {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    warn!("Giving up on a batch of {} pending episode embeddings: {}", batch_texts.len(), e);
+                    continue;
+                }
+            };
+
+            let mut cache = file_cache.write().await;
+            for (&pending_idx, embedding) in batch_indices.iter().zip(embeddings) {
+                let item = &pending[pending_idx];
+                if let Some(file_result) = cache.get_mut(&item.file_path) {
+                    if let Some(episode) = file_result.episodes.iter_mut().find(|e| e.uuid == item.episode_uuid) {
+                        episode.set_embedding(embedding);
+                        embedded += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(embedded)
+    }
+
+    /// Like [`Self::drain_and_apply`], but for the single-file incremental
+    /// path (`CodebaseIndexer::index_file`, used by the file watcher): only
+    /// drains entries enqueued for `result.file_path`, writing embeddings
+    /// straight onto `result.episodes` instead of a shared `file_cache`,
+    /// since a freshly re-indexed file hasn't been inserted there yet. Other
+    /// files' entries already sitting in the backlog (e.g. from a concurrent
+    /// bulk `index_codebase` run) are left untouched for their own drain.
+    /// Returns the number of episodes that were successfully embedded.
+    pub async fn drain_and_apply_to_result(
+        &self,
+        engine: &LocalEmbeddingEngine,
+        target_tokens_per_batch: usize,
+        result: &mut FileIndexResult,
+    ) -> Result<usize> {
+        let mine = {
+            let mut pending = self.pending.lock().unwrap();
+            let (mine, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut *pending)
+                .into_iter()
+                .partition(|p| p.file_path == result.file_path);
+            *pending = rest;
+            mine
+        };
+        if mine.is_empty() {
+            return Ok(0);
+        }
+
+        let texts: Vec<String> = mine.iter().map(|p| p.text.clone()).collect();
+        let batches = EmbeddingQueue::new(target_tokens_per_batch).plan_batches(&texts);
+
+        let mut embedded = 0usize;
+        for batch_indices in batches {
+            let batch_texts: Vec<String> = batch_indices.iter().map(|&i| texts[i].clone()).collect();
+
+            let embeddings = match embed_with_retry(engine, &batch_texts).await {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    warn!("Giving up on a batch of {} pending episode embeddings for {}: {}", batch_texts.len(), result.file_path.display(), e);
+                    continue;
+                }
+            };
+
+            for (&pending_idx, embedding) in batch_indices.iter().zip(embeddings) {
+                let item = &mine[pending_idx];
+                if let Some(episode) = result.episodes.iter_mut().find(|e| e.uuid == item.episode_uuid) {
+                    episode.set_embedding(embedding);
+                    embedded += 1;
+                }
+            }
+        }
+
+        Ok(embedded)
+    }
+}
+
+/// Retries `engine.encode_texts(batch)` with exponential backoff, treating
+/// any error as transient. The local engine has no distinct rate-limit
+/// signal the way the remote `EmbeddingProvider`s in `embeddings::provider`
+/// do (no HTTP 429 to key off of) — any failure here is most likely a
+/// momentarily-busy ONNX session, so a blind retry is the best available
+/// signal without a richer error type to distinguish the two.
+async fn embed_with_retry(engine: &LocalEmbeddingEngine, batch: &[String]) -> Result<Vec<Vec<f32>>> {
+    let mut attempt = 0u32;
+    loop {
+        match engine.encode_texts(batch).await {
+            Ok(embeddings) => return Ok(embeddings),
+            Err(e) if attempt < MAX_RETRIES => {
+                let delay = Duration::from_millis(200 * 2u64.saturating_pow(attempt));
+                warn!(
+                    "Batch embedding failed ({}), retrying in {:?} (attempt {}/{})",
+                    e, delay, attempt + 1, MAX_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Content-hash cache that makes `CodebaseIndexerConfig::enable_incremental`
+//! actually skip unchanged work, instead of being a flag `process_file` never
+//! reads.
+//!
+//! Keyed by file path, each entry stores the SHA-256 of the file's bytes
+//! alongside the extracted `CachedFileResult`. On re-index, a file whose
+//! current content hash matches its cached entry is counted as a cache hit
+//! and its nodes/edges/episodes/dependencies/metadata are reused verbatim
+//! instead of re-running entity extraction, relationship extraction, and
+//! embedding enqueueing.
+//!
+//! `ContextChunk`s are deliberately left out of `CachedFileResult` and always
+//! regenerated via `ContextWindowManager::add_content`, for two reasons:
+//! `ContextChunk`/`ChunkType` aren't serde-derived (`ChunkType` round-trips
+//! through `chunk_store.rs`'s own `Debug`-string convention, not serde), and
+//! `add_content`'s content-hash-based CDC dedup already makes re-chunking
+//! unchanged content cheap, so there's nothing left to save by caching them
+//! here too.
+//!
+//! Mirrors `PersistentEmbeddingCache`'s SQLite-behind-a-blocking-`Mutex`
+//! pattern.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OpenFlags};
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::graph::{KGNode, KGEdge, Episode};
+use crate::indexing::codebase_indexer::{Dependency, FileMetadata};
+use crate::indexing::code_chunker::CallEdge;
+
+/// Everything from a file's `FileIndexResult` worth persisting across runs,
+/// i.e. everything except its `ContextChunk`s (see module docs).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFileResult {
+    pub nodes: Vec<KGNode>,
+    pub edges: Vec<KGEdge>,
+    pub episodes: Vec<Episode>,
+    pub dependencies: Vec<Dependency>,
+    pub metadata: FileMetadata,
+    /// `CallEdge`s found inside this file, for the `call_hierarchy`
+    /// operation's `call_graph`. `#[serde(default)]` so a cache entry
+    /// written before this field existed still deserializes (as no edges,
+    /// recomputed on the next re-index).
+    #[serde(default)]
+    pub call_edges: Vec<CallEdge>,
+}
+
+/// One row of `FileIndexCache::manifest()` — everything the `status`
+/// operation needs about a previously-indexed file without pulling its full
+/// `CachedFileResult` back out of storage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub file_path: String,
+    pub content_hash: String,
+    pub language: String,
+    pub symbol_count: usize,
+    pub indexed_at: i64,
+}
+
+/// Disk-backed cache of per-file indexing results, keyed by file path and
+/// validated by content hash.
+#[derive(Clone)]
+pub struct FileIndexCache {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl FileIndexCache {
+    /// Opens (creating if necessary) the on-disk cache database at `db_path`.
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create cache directory {}", parent.display()))?;
+        }
+
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open file index cache database: {}", db_path.display()))?;
+
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS file_index_cache (
+                file_path TEXT PRIMARY KEY,
+                content_hash TEXT NOT NULL,
+                result TEXT NOT NULL,
+                last_accessed INTEGER NOT NULL,
+                language TEXT NOT NULL DEFAULT '',
+                symbol_count INTEGER NOT NULL DEFAULT 0,
+                indexed_at INTEGER NOT NULL DEFAULT 0
+            );
+            ",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Returns the cached result for `file_path` if its stored hash matches
+    /// `content_hash`. A hash mismatch or missing row both simply return
+    /// `None`, so the caller always falls back to reprocessing the file.
+    pub async fn get(&self, file_path: &Path, content_hash: &str) -> Option<CachedFileResult> {
+        let path_key = file_path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+
+        let row: Option<(String, String)> = conn
+            .query_row(
+                "SELECT content_hash, result FROM file_index_cache WHERE file_path = ?1",
+                params![path_key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+
+        let (stored_hash, result_json) = row?;
+        if stored_hash != content_hash {
+            return None;
+        }
+
+        let _ = conn.execute(
+            "UPDATE file_index_cache SET last_accessed = ?1 WHERE file_path = ?2",
+            params![Self::now(), path_key],
+        );
+
+        serde_json::from_str(&result_json).ok()
+    }
+
+    /// Inserts or replaces the cached result for `file_path`. `language` and
+    /// `symbol_count` are pulled straight off `result` and duplicated into
+    /// their own columns (rather than left only inside the serialized JSON
+    /// blob) so `manifest()` can report per-language totals without
+    /// deserializing every row.
+    pub async fn put(&self, file_path: &Path, content_hash: &str, result: &CachedFileResult) -> Result<()> {
+        let path_key = file_path.to_string_lossy().to_string();
+        let result_json = serde_json::to_string(result)?;
+        let language = format!("{:?}", result.metadata.language);
+        let symbol_count = result.nodes.len() as i64;
+        let now = Self::now();
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT OR REPLACE INTO file_index_cache
+                (file_path, content_hash, result, last_accessed, language, symbol_count, indexed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![path_key, content_hash, result_json, now, language, symbol_count, now],
+        )?;
+
+        Ok(())
+    }
+
+    /// Every cached file's manifest row (path, content hash, language,
+    /// symbol count, and when it was last (re)indexed), for the
+    /// `index_codebase` tool's `status` operation. Doesn't touch
+    /// `last_accessed`/the serialized `result` blob.
+    pub async fn manifest(&self) -> Result<Vec<ManifestEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT file_path, content_hash, language, symbol_count, indexed_at FROM file_index_cache",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ManifestEntry {
+                file_path: row.get(0)?,
+                content_hash: row.get(1)?,
+                language: row.get(2)?,
+                symbol_count: row.get::<_, i64>(3)? as usize,
+                indexed_at: row.get(4)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(Into::into)
+    }
+
+    /// Drops the cached entry for `file_path`, if any. Used when a watched
+    /// file is deleted so a later re-creation at the same path can't be
+    /// mistaken for an unchanged file.
+    pub async fn remove(&self, file_path: &Path) -> Result<()> {
+        let path_key = file_path.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM file_index_cache WHERE file_path = ?1",
+            params![path_key],
+        )?;
+        Ok(())
+    }
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
+
+/// SHA-256 hex digest of a file's raw bytes, used as the cache-invalidation
+/// key — matches the hashing convention used elsewhere in this crate.
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
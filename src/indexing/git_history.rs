@@ -0,0 +1,129 @@
+//! Git-history enrichment for indexed files: commit metadata and a churn
+//! score, shelled out to the system `git` binary rather than a libgit2
+//! binding, since there's no existing dependency for talking to git
+//! (see `metrics::rss` for the same shell-out-over-new-dependency call
+//! when a platform/tool capability isn't worth a crate).
+//!
+//! Every function here degrades to an empty/zero result instead of
+//! returning an error when `file_path` isn't inside a git work tree, has
+//! no history yet, or `git` isn't on `PATH` at all — "no history
+//! available" is an expected outcome (a generated file, a shallow clone,
+//! a non-git deployment), not a failure worth aborting indexing over.
+
+use std::path::Path;
+use std::process::Command;
+
+/// `ASCII Record Separator` / `ASCII Unit Separator`, used to delimit
+/// `git log --pretty=format:` records/fields without risking collisions
+/// with anything a real commit message or author name could contain.
+const RECORD_SEP: &str = "\x1e";
+const FIELD_SEP: &str = "\x1f";
+
+/// One commit that touched a file, in the newest-first order `git log`
+/// reports them.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub date: String,
+    pub committer: String,
+    pub message: String,
+}
+
+/// Commit count and total lines added+removed for a file over a lookback
+/// window — a churn score `analyze_patterns`'s `temporal` operation can
+/// correlate against other episodes, and `search_code` can rank by.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct ChurnStats {
+    pub commit_count: usize,
+    pub lines_changed: usize,
+}
+
+/// The last `max_commits` commits touching `file_path`, newest first.
+/// Returns an empty `Vec` (not an error) if `file_path` isn't tracked by
+/// git, has no history, or `git` itself isn't available.
+pub fn file_history(file_path: &Path, max_commits: usize) -> Vec<CommitInfo> {
+    let (Some(dir), Some(file_name)) = (file_path.parent(), file_path.file_name()) else {
+        return Vec::new();
+    };
+
+    let format = format!("%H{FIELD_SEP}%ad{FIELD_SEP}%cn{FIELD_SEP}%s{RECORD_SEP}");
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args([
+            "log",
+            &format!("-n{max_commits}"),
+            "--date=short",
+            &format!("--pretty=format:{format}"),
+            "--",
+        ])
+        .arg(file_name)
+        .output();
+
+    let Ok(output) = output else { return Vec::new() };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .split(RECORD_SEP)
+        .filter_map(|record| {
+            let record = record.trim_start_matches('\n');
+            if record.is_empty() {
+                return None;
+            }
+            let mut fields = record.splitn(4, FIELD_SEP);
+            Some(CommitInfo {
+                hash: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                committer: fields.next()?.to_string(),
+                message: fields.next().unwrap_or("").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Commit count and total lines added+removed for `file_path` in the last
+/// `window_days` days. Returns the zero value (not an error) under the
+/// same conditions as `file_history`.
+pub fn churn_stats(file_path: &Path, window_days: u64) -> ChurnStats {
+    let (Some(dir), Some(file_name)) = (file_path.parent(), file_path.file_name()) else {
+        return ChurnStats::default();
+    };
+
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args([
+            "log",
+            "--numstat",
+            "--pretty=format:%H",
+            &format!("--since={window_days} days ago"),
+            "--",
+        ])
+        .arg(file_name)
+        .output();
+
+    let Ok(output) = output else { return ChurnStats::default() };
+    if !output.status.success() {
+        return ChurnStats::default();
+    }
+
+    let mut commit_count = 0usize;
+    let mut lines_changed = 0usize;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // A `--numstat` line is `<added>\t<deleted>\t<path>` (or `-\t-\t<path>`
+        // for a binary file, which parses to 0/0); anything else is a bare
+        // commit hash line, marking the start of the next commit's stats.
+        match line.split('\t').collect::<Vec<_>>().as_slice() {
+            [added, deleted, _path] => {
+                lines_changed += added.parse::<usize>().unwrap_or(0) + deleted.parse::<usize>().unwrap_or(0);
+            }
+            _ => commit_count += 1,
+        }
+    }
+
+    ChurnStats { commit_count, lines_changed }
+}
@@ -1,6 +1,14 @@
+pub mod code_chunker;
 pub mod codebase_indexer;
+pub mod embedding_queue;
+pub mod file_index_cache;
 pub mod language_support;
 pub mod dependency_mapper;
+pub mod git_history;
+pub mod cargo_metadata;
+pub mod symbol_suggest;
+pub mod watcher;
+pub mod streaming;
 
 // Re-export main types
 pub use codebase_indexer::{
@@ -16,6 +24,26 @@ pub use codebase_indexer::{
     CodeSearchResult,
     FileDependency,
     CodebaseAnalysis,
+    CallHierarchyEdge,
+    CallHierarchyResult,
 };
+pub use cargo_metadata::{CargoPackageInfo, CargoDependencyInfo};
+pub use symbol_suggest::{TrigramIndex, SymbolSuggestion, default_max_distance};
+pub use code_chunker::{CodeChunk, CodeChunker, ChunkerConfig, ChunkType, CodeBlock, extract_symbols, calculate_complexity, calculate_loc, calculate_token_entropy, extract_import_nodes, extract_call_edges, CallEdge};
+pub use embedding_queue::EmbeddingBacklog;
+pub use file_index_cache::{FileIndexCache, CachedFileResult, ManifestEntry};
 pub use language_support::{LanguageDetector, SupportedLanguage};
-pub use dependency_mapper::{DependencyMapper, DependencyType}; 
\ No newline at end of file
+pub use dependency_mapper::{DependencyMapper, DependencyType};
+pub use git_history::{CommitInfo, ChurnStats};
+pub use watcher::{IngestionWatcher, WatcherConfig, IndexWatchManager, IndexWatchStatusReport};
+pub use streaming::{
+    AutoOffsetReset,
+    FileTailSource,
+    StreamIngester,
+    StreamIngesterConfig,
+    StreamIngesterHandle,
+    StreamIngestionManager,
+    StreamRecord,
+    StreamSource,
+    StreamStatusReport,
+}; 
\ No newline at end of file
@@ -0,0 +1,376 @@
+//! Streaming ingestion: consumes episodes from an external append-only
+//! stream (a tailed log file, a message queue, ...) instead of only one-shot
+//! `add_memory` calls.
+//!
+//! Modeled on [`watcher::IngestionWatcher`](super::watcher::IngestionWatcher)'s
+//! spawn/handle pattern, but where the watcher reacts to filesystem events,
+//! [`StreamIngester`] pulls from a [`StreamSource`] in a poll loop and
+//! commits a `GraphStorage` checkpoint (see
+//! `GraphStorage::{get_stream_checkpoint, commit_stream_checkpoint}`) only
+//! after a record's episode (and embedding) are durably stored — never
+//! before, and never on a timer. A crash between storing an episode and
+//! committing its offset therefore only ever causes at-least-once
+//! re-delivery of that one record, never silent data loss: the episode's
+//! UUID is derived deterministically from `(stream_id, offset)` (see
+//! `episode_uuid_for`), so re-storing a redelivered record is an idempotent
+//! `INSERT OR REPLACE` rather than a duplicate.
+//!
+//! On (re)start, a stream with no existing checkpoint consults
+//! `AutoOffsetReset`: `Earliest` replays the whole stream from the
+//! beginning, `Latest` (the default) skips straight to whatever's newest at
+//! startup.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::embeddings::LocalEmbeddingEngine;
+use crate::graph::storage::GraphStorage;
+use crate::graph::{Episode, EpisodeSource};
+use crate::mcp::search_queue::SearchQueue;
+
+/// One record pulled from a [`StreamSource`], not yet turned into an
+/// `Episode`.
+#[derive(Debug, Clone)]
+pub struct StreamRecord {
+    /// Monotonically increasing within a single stream. Used both as the
+    /// checkpoint value and, combined with the stream id, to derive a
+    /// stable episode UUID for dedup.
+    pub offset: u64,
+    pub name: String,
+    pub content: String,
+}
+
+/// An external append-only source of [`StreamRecord`]s. `StreamIngester`
+/// only ever calls `poll` with the offset it last committed (or `None` for
+/// a stream it has no checkpoint for yet), so implementors don't need to
+/// track consumer position themselves.
+#[async_trait]
+pub trait StreamSource: Send + Sync {
+    /// Returns records strictly after `after_offset` (or from the start of
+    /// the stream if `None`), oldest first. An empty result means "caught up
+    /// for now" — `StreamIngester` will poll again after `poll_interval_ms`.
+    async fn poll(&self, after_offset: Option<u64>) -> Result<Vec<StreamRecord>>;
+}
+
+/// Reference [`StreamSource`] that treats a newline-delimited text file as
+/// the stream, one line per record, offset = line index. Reads the whole
+/// file on every poll, so it's meant as a worked example / for modest
+/// files and tests — a real deployment tailing a large log should implement
+/// `StreamSource` against its own cursor instead.
+pub struct FileTailSource {
+    path: std::path::PathBuf,
+}
+
+impl FileTailSource {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl StreamSource for FileTailSource {
+    async fn poll(&self, after_offset: Option<u64>) -> Result<Vec<StreamRecord>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let start = after_offset.map(|o| o + 1).unwrap_or(0) as usize;
+        let records = content
+            .lines()
+            .enumerate()
+            .skip(start)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| StreamRecord {
+                offset: i as u64,
+                name: format!("{}:{}", self.path.display(), i),
+                content: line.to_string(),
+            })
+            .collect();
+
+        Ok(records)
+    }
+}
+
+/// How a stream without an existing checkpoint starts consuming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoOffsetReset {
+    /// Replay the whole stream from the beginning.
+    Earliest,
+    /// Skip straight to whatever's newest at startup.
+    Latest,
+}
+
+impl Default for AutoOffsetReset {
+    fn default() -> Self {
+        AutoOffsetReset::Latest
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StreamIngesterConfig {
+    pub stream_id: String,
+    #[serde(default)]
+    pub auto_offset_reset: AutoOffsetReset,
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    #[serde(default)]
+    pub group_id: Option<String>,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    1000
+}
+
+/// Derives the episode UUID stored for `(stream_id, offset)`. Deterministic,
+/// so redelivering the same record after a crash produces the same UUID and
+/// `GraphStorage::insert_episode`'s `INSERT OR REPLACE` makes re-storing it
+/// a no-op rather than a duplicate episode.
+fn episode_uuid_for(stream_id: &str, offset: u64) -> Uuid {
+    let mut hasher = Sha256::new();
+    hasher.update(stream_id.as_bytes());
+    hasher.update(b":");
+    hasher.update(offset.to_le_bytes());
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Uuid::from_bytes(bytes)
+}
+
+/// Background task that polls a [`StreamSource`] and stores what it finds,
+/// committing a `GraphStorage` checkpoint after each successfully stored
+/// record. Spawn with [`StreamIngester::spawn`] for a cancellable handle,
+/// or drive [`StreamIngester::run`] directly.
+pub struct StreamIngester {
+    config: StreamIngesterConfig,
+    source: Arc<dyn StreamSource>,
+    storage: Arc<GraphStorage>,
+    embedding_engine: Arc<LocalEmbeddingEngine>,
+    search_queue: Arc<SearchQueue>,
+    /// How many records from the most recent `poll` are still awaiting a
+    /// durable store + checkpoint commit. Shared with `StreamIngesterHandle`
+    /// so `manage_ingestion`'s `status` operation can report it without
+    /// reaching into the running task.
+    lag: Arc<AtomicU64>,
+}
+
+impl StreamIngester {
+    pub fn new(
+        config: StreamIngesterConfig,
+        source: Arc<dyn StreamSource>,
+        storage: Arc<GraphStorage>,
+        embedding_engine: Arc<LocalEmbeddingEngine>,
+        search_queue: Arc<SearchQueue>,
+    ) -> Self {
+        Self {
+            config,
+            source,
+            storage,
+            embedding_engine,
+            search_queue,
+            lag: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let mut checkpoint = self.storage.get_stream_checkpoint(&self.config.stream_id)?;
+
+        if checkpoint.is_none() && self.config.auto_offset_reset == AutoOffsetReset::Latest {
+            // No prior checkpoint and `latest`: find where the stream
+            // currently ends and start there, so nothing before "now" gets
+            // replayed. This poll's records are deliberately discarded, not
+            // stored — only the newest offset it reveals matters.
+            if let Some(last) = self.source.poll(None).await?.last() {
+                checkpoint = Some(last.offset);
+            }
+        }
+
+        info!(
+            "Starting stream ingestion for '{}' from checkpoint {:?}",
+            self.config.stream_id, checkpoint
+        );
+
+        loop {
+            let records = self.source.poll(checkpoint).await?;
+            if records.is_empty() {
+                self.lag.store(0, Ordering::Relaxed);
+                tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+                continue;
+            }
+
+            let mut remaining = records.len() as u64;
+            self.lag.store(remaining, Ordering::Relaxed);
+
+            for record in records {
+                // Honor search-queue backpressure so a stream backlog can't
+                // starve interactive `search_memory`/`add_memory` calls.
+                let _ticket = self.search_queue.acquire().await?;
+
+                self.store_record(&record).await?;
+
+                // Commit only now that the episode (and its embedding) are
+                // durably stored — never before, and never on a timer.
+                self.storage.commit_stream_checkpoint(&self.config.stream_id, record.offset)?;
+                checkpoint = Some(record.offset);
+
+                remaining -= 1;
+                self.lag.store(remaining, Ordering::Relaxed);
+            }
+        }
+    }
+
+    async fn store_record(&self, record: &StreamRecord) -> Result<()> {
+        let mut episode = Episode::new(
+            record.name.clone(),
+            record.content.clone(),
+            EpisodeSource::Text,
+            format!("stream:{}", self.config.stream_id),
+            self.config.group_id.clone(),
+        );
+        episode.uuid = episode_uuid_for(&self.config.stream_id, record.offset);
+
+        match self.embedding_engine.encode_text(&episode.content).await {
+            Ok(embedding) => {
+                episode.embedding = Some(embedding.clone());
+                if let Err(e) = self.storage.store_embedding(episode.uuid, "episode", &embedding) {
+                    warn!(
+                        "Failed to store embedding for stream record {}:{}: {}",
+                        self.config.stream_id, record.offset, e
+                    );
+                }
+            }
+            Err(e) => warn!(
+                "Failed to generate embedding for stream record {}:{}: {}",
+                self.config.stream_id, record.offset, e
+            ),
+        }
+
+        self.storage.insert_episode(&episode)?;
+        debug!("Stored episode for stream record {}:{}", self.config.stream_id, record.offset);
+        Ok(())
+    }
+
+    /// Spawns `run()` as a cancellable background task.
+    pub fn spawn(self) -> StreamIngesterHandle {
+        let stream_id = self.config.stream_id.clone();
+        let lag = Arc::clone(&self.lag);
+        StreamIngesterHandle {
+            stream_id,
+            lag,
+            join_handle: tokio::spawn(self.run()),
+        }
+    }
+}
+
+/// Handle to a running [`StreamIngester`], returned by
+/// [`StreamIngester::spawn`]. Aborting it stops the poll loop; any
+/// in-flight store-then-commit pair that was interrupted mid-way is exactly
+/// the at-least-once-redelivery case the checkpoint scheme is built to
+/// tolerate, not a bug to work around here.
+pub struct StreamIngesterHandle {
+    pub stream_id: String,
+    lag: Arc<AtomicU64>,
+    join_handle: tokio::task::JoinHandle<Result<()>>,
+}
+
+impl StreamIngesterHandle {
+    pub fn abort(&self) {
+        self.join_handle.abort();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.join_handle.is_finished()
+    }
+
+    /// Records from the most recent `poll` that are still awaiting a
+    /// durable store + checkpoint commit. Not a count of records produced
+    /// upstream since the stream started — only what the last `poll` call
+    /// revealed — so it reads as "how far behind the last known tail" rather
+    /// than a cumulative total.
+    pub fn lag(&self) -> u64 {
+        self.lag.load(Ordering::Relaxed)
+    }
+}
+
+/// Point-in-time status of one registered stream, as returned by the
+/// `manage_ingestion` tool's `status` operation.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StreamStatusReport {
+    pub stream_id: String,
+    pub running: bool,
+    /// See `StreamIngesterHandle::lag`.
+    pub lag: u64,
+}
+
+/// Tracks running [`StreamIngester`]s by stream id so the `manage_ingestion`
+/// tool can start/stop/status them without the caller holding onto a
+/// `StreamIngesterHandle` itself. Mirrors `mcp::workers::WorkerManager`'s
+/// role for supervised background workers.
+pub struct StreamIngestionManager {
+    streams: tokio::sync::RwLock<HashMap<String, StreamIngesterHandle>>,
+}
+
+impl StreamIngestionManager {
+    pub fn new() -> Self {
+        Self {
+            streams: tokio::sync::RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Starts a new stream under `config.stream_id`. Errors if a stream with
+    /// that id is already running — restart it with `stop` first.
+    pub async fn start(
+        &self,
+        config: StreamIngesterConfig,
+        source: Arc<dyn StreamSource>,
+        storage: Arc<GraphStorage>,
+        embedding_engine: Arc<LocalEmbeddingEngine>,
+        search_queue: Arc<SearchQueue>,
+    ) -> Result<()> {
+        let stream_id = config.stream_id.clone();
+        let mut streams = self.streams.write().await;
+        if let Some(existing) = streams.get(&stream_id) {
+            if !existing.is_finished() {
+                return Err(anyhow::anyhow!("Stream '{}' is already running", stream_id));
+            }
+        }
+
+        let ingester = StreamIngester::new(config, source, storage, embedding_engine, search_queue);
+        streams.insert(stream_id, ingester.spawn());
+        Ok(())
+    }
+
+    pub async fn stop(&self, stream_id: &str) -> Result<()> {
+        let streams = self.streams.read().await;
+        let handle = streams.get(stream_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown stream: {}", stream_id))?;
+        handle.abort();
+        Ok(())
+    }
+
+    pub async fn status(&self) -> Vec<StreamStatusReport> {
+        let streams = self.streams.read().await;
+        streams.iter()
+            .map(|(id, handle)| StreamStatusReport {
+                stream_id: id.clone(),
+                running: !handle.is_finished(),
+                lag: handle.lag(),
+            })
+            .collect()
+    }
+}
+
+impl Default for StreamIngestionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
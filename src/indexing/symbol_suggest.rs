@@ -0,0 +1,124 @@
+//! Spelling-tolerant symbol name lookup: a character-trigram index plus
+//! Damerau-Levenshtein ranking, backing the `call_hierarchy` operation's
+//! `suggest` param and the standalone `suggest_symbol` operation.
+//! Complementary to `CodebaseIndexer::fuzzy_match_score` (subsequence/
+//! prefix scoring tuned for fzf-style incremental typing) — this targets
+//! genuine misspellings, where the query and the intended symbol share
+//! most characters but in a different order or with a handful of
+//! insertions/deletions/substitutions.
+
+use std::collections::HashMap;
+
+/// One ranked correction from `TrigramIndex::suggest`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SymbolSuggestion {
+    pub symbol: String,
+    pub distance: usize,
+    pub gram_overlap: usize,
+}
+
+/// Character trigrams of `name`, lowercased and padded with one leading/
+/// trailing NUL so the first and last characters participate in a trigram
+/// too — otherwise a short name like `"fmt"` would only ever produce
+/// `"fmt"` itself, with nothing to distinguish it from `"format"`'s
+/// `"for"`/`"orm"`/`"rma"`/`"mat"`.
+fn trigrams(name: &str) -> Vec<String> {
+    let padded: Vec<char> = std::iter::once('\u{0}')
+        .chain(name.to_lowercase().chars())
+        .chain(std::iter::once('\u{0}'))
+        .collect();
+    if padded.len() < 3 {
+        return vec![padded.into_iter().collect()];
+    }
+    padded.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+/// Damerau-Levenshtein edit distance (insertions, deletions, substitutions,
+/// and adjacent transpositions all cost 1) via the standard
+/// optimal-string-alignment DP table.
+fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (la, lb) = (a.len(), b.len());
+    let mut d = vec![vec![0usize; lb + 1]; la + 1];
+    for (i, row) in d.iter_mut().enumerate().take(la + 1) {
+        row[0] = i;
+    }
+    for j in 0..=lb {
+        d[0][j] = j;
+    }
+
+    for i in 1..=la {
+        for j in 1..=lb {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + cost);
+            }
+        }
+    }
+    d[la][lb]
+}
+
+/// trigram -> indices into a name list, so `suggest` only has to
+/// edit-distance-score names that share at least one trigram with the
+/// query instead of the whole corpus.
+pub struct TrigramIndex<'a> {
+    names: &'a [String],
+    postings: HashMap<String, Vec<usize>>,
+}
+
+impl<'a> TrigramIndex<'a> {
+    pub fn build(names: &'a [String]) -> Self {
+        let mut postings: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, name) in names.iter().enumerate() {
+            for gram in trigrams(name) {
+                postings.entry(gram).or_default().push(i);
+            }
+        }
+        Self { names, postings }
+    }
+
+    /// Ranked corrections for `query`: candidates sharing at least one
+    /// trigram with it, scored by Damerau-Levenshtein distance (dropping
+    /// anything over `max_distance` — see `default_max_distance`) and
+    /// broken by descending gram overlap, closest first.
+    pub fn suggest(&self, query: &str, max_distance: usize, limit: usize) -> Vec<SymbolSuggestion> {
+        let query_lower = query.to_lowercase();
+        let mut overlap: HashMap<usize, usize> = HashMap::new();
+        for gram in trigrams(query) {
+            if let Some(indices) = self.postings.get(&gram) {
+                for &i in indices {
+                    *overlap.entry(i).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut suggestions: Vec<SymbolSuggestion> = overlap
+            .into_iter()
+            .filter_map(|(i, gram_overlap)| {
+                let distance = damerau_levenshtein(&query_lower, &self.names[i].to_lowercase());
+                (distance <= max_distance).then_some(SymbolSuggestion {
+                    symbol: self.names[i].clone(),
+                    distance,
+                    gram_overlap,
+                })
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| a.distance.cmp(&b.distance).then(b.gram_overlap.cmp(&a.gram_overlap)));
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+/// A reasonable default max edit distance scaled to `query`'s length: 2 for
+/// short queries, 3 once it's long enough that a couple more typos are
+/// still plausibly the same word.
+pub fn default_max_distance(query: &str) -> usize {
+    if query.chars().count() >= 8 {
+        3
+    } else {
+        2
+    }
+}
@@ -0,0 +1,363 @@
+//! Incremental file-watching ingestion: keeps the graph in sync with a live
+//! codebase without full re-scans.
+//!
+//! On startup, walks `watch_paths`, uses `LanguageDetector::detect_from_path`
+//! to skip binary/unknown files, and indexes the rest via `CodebaseIndexer`.
+//! After that, filesystem change events are debounced and only the affected
+//! files are re-indexed: stale episodes, nodes, and edges for a path (found
+//! by `source_description`/`group_id`, which `CodebaseIndexer` sets to the
+//! file path) are deleted before the new ones are inserted, so edits never
+//! leave orphaned entries behind. A file that was deleted outright has its
+//! stale entries removed the same way, plus its entry in the indexer's
+//! `file_cache`/`dependency_graph`/file-index cache evicted via
+//! `CodebaseIndexer::evict_file`, instead of leaving anything for a rebuild
+//! to trip over later.
+//!
+//! Use `IngestionWatcher::spawn` to run this as a cancellable background
+//! task rather than managing the `tokio::spawn`/`JoinHandle` by hand.
+
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::graph::storage::GraphStorage;
+use crate::indexing::codebase_indexer::CodebaseIndexer;
+use crate::indexing::language_support::{LanguageDetector, SupportedLanguage};
+
+/// Settings for the incremental ingestion watcher, surfaced through
+/// `ServerConfig`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WatcherConfig {
+    pub enabled: bool,
+    /// Root directories to walk on startup and watch for changes.
+    #[serde(default)]
+    pub watch_paths: Vec<PathBuf>,
+    /// How long to wait after the first event in a burst before re-indexing,
+    /// so a single save (which can fire several filesystem events) only
+    /// triggers one re-index pass per file.
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+fn default_debounce_ms() -> u64 {
+    500
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            watch_paths: Vec::new(),
+            debounce_ms: default_debounce_ms(),
+        }
+    }
+}
+
+/// Point-in-time indexing progress for one [`IngestionWatcher`], as returned
+/// by [`WatcherHandle::stats`]. Counters only ever increase over the life of
+/// a watch, so callers can use them to track throughput rather than just a
+/// running/stopped flag.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct WatcherStats {
+    pub files_indexed: u64,
+    pub files_evicted: u64,
+    pub files_failed: u64,
+}
+
+/// Shared counters a running [`IngestionWatcher`] updates as it processes
+/// events, and [`WatcherHandle::stats`] reads back without needing to talk
+/// to the watch loop itself.
+#[derive(Default)]
+struct WatcherCounters {
+    files_indexed: AtomicU64,
+    files_evicted: AtomicU64,
+    files_failed: AtomicU64,
+}
+
+impl WatcherCounters {
+    fn snapshot(&self) -> WatcherStats {
+        WatcherStats {
+            files_indexed: self.files_indexed.load(Ordering::Relaxed),
+            files_evicted: self.files_evicted.load(Ordering::Relaxed),
+            files_failed: self.files_failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Background task that performs the initial index and then watches for
+/// filesystem changes. Spawn with `tokio::spawn(watcher.run())` alongside
+/// `server.run()`.
+pub struct IngestionWatcher {
+    config: WatcherConfig,
+    storage: Arc<GraphStorage>,
+    indexer: Arc<CodebaseIndexer>,
+    language_detector: LanguageDetector,
+    counters: Arc<WatcherCounters>,
+}
+
+impl IngestionWatcher {
+    pub fn new(config: WatcherConfig, storage: Arc<GraphStorage>, indexer: Arc<CodebaseIndexer>) -> Self {
+        Self {
+            config,
+            storage,
+            indexer,
+            language_detector: LanguageDetector::new(),
+            counters: Arc::new(WatcherCounters::default()),
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        if !self.config.enabled || self.config.watch_paths.is_empty() {
+            info!("File-watching ingestion disabled (no watch_paths configured)");
+            return Ok(());
+        }
+
+        for root in &self.config.watch_paths {
+            self.index_tree(root).await?;
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+
+        let mut watcher: RecommendedWatcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            match res {
+                Ok(event) => {
+                    if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                        for path in event.paths {
+                            let _ = tx.send(path);
+                        }
+                    }
+                }
+                Err(e) => error!("File watch error: {}", e),
+            }
+        })?;
+
+        for root in &self.config.watch_paths {
+            watcher.watch(root, RecursiveMode::Recursive)?;
+        }
+
+        let debounce = Duration::from_millis(self.config.debounce_ms);
+        let mut pending: HashSet<PathBuf> = HashSet::new();
+
+        loop {
+            let first = match rx.recv().await {
+                Some(path) => path,
+                None => break, // channel closed: watcher was dropped
+            };
+            pending.insert(first);
+
+            tokio::time::sleep(debounce).await;
+            while let Ok(path) = rx.try_recv() {
+                pending.insert(path);
+            }
+
+            for path in pending.drain() {
+                if let Err(e) = self.reindex_path(&path).await {
+                    warn!("Failed to re-index {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn index_tree(&self, root: &Path) -> Result<()> {
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(current) = stack.pop() {
+            if current.is_dir() {
+                let mut entries = tokio::fs::read_dir(&current).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    stack.push(entry.path());
+                }
+            } else if self.should_index(&current) {
+                if let Err(e) = self.reindex_path(&current).await {
+                    warn!("Failed to index {}: {}", current.display(), e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn should_index(&self, path: &Path) -> bool {
+        !matches!(self.language_detector.detect_from_path(path), SupportedLanguage::Unknown)
+    }
+
+    /// Re-indexes `path`, swapping its stale episodes/nodes/edges (matched
+    /// by `source_description`/`group_id`, which `CodebaseIndexer` sets to
+    /// the file path on everything it creates) for the freshly extracted
+    /// ones in a single `GraphStorage::reindex_file` transaction, so a crash
+    /// or error partway through never leaves search seeing a half-indexed
+    /// file. A delete-only event (file removed) also evicts `path` from the
+    /// indexer's `file_cache`/`dependency_graph`/file-index cache instead of
+    /// leaving a stale entry a later rename back to the same path could be
+    /// mistaken for.
+    async fn reindex_path(&self, path: &Path) -> Result<()> {
+        let path_str = path.to_string_lossy().to_string();
+        let stale_episodes = self.storage.get_episodes_by_source_description(&path_str)?;
+        let stale_nodes = self.storage.get_nodes_by_group_id(&path_str)?;
+        let stale_edges = self.storage.get_edges_by_group_id(&path_str)?;
+
+        if !path.is_file() || !self.should_index(path) {
+            self.storage.reindex_file(&stale_episodes, &stale_nodes, &stale_edges, &[], &[], &[])?;
+            self.indexer.evict_file(path).await?;
+            self.counters.files_evicted.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        let result = match self.indexer.index_file(path).await {
+            Ok(result) => result,
+            Err(e) => {
+                self.counters.files_failed.fetch_add(1, Ordering::Relaxed);
+                return Err(e);
+            }
+        };
+        debug!(
+            "Re-indexed {}: {} nodes, {} edges, {} episodes",
+            path.display(),
+            result.nodes.len(),
+            result.edges.len(),
+            result.episodes.len()
+        );
+
+        self.storage.reindex_file(
+            &stale_episodes,
+            &stale_nodes,
+            &stale_edges,
+            &result.nodes,
+            &result.edges,
+            &result.episodes,
+        )?;
+
+        self.indexer.patch_symbol_index(path, &result).await;
+        self.indexer.patch_dependency_graph(path, result.dependencies).await;
+        self.indexer.patch_call_graph(path, result.call_edges).await;
+
+        self.counters.files_indexed.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Spawns `run()` as a background task and returns a cancellable handle
+    /// to it, so callers don't have to manage the `tokio::spawn`/`JoinHandle`
+    /// themselves.
+    pub fn spawn(self) -> WatcherHandle {
+        let counters = Arc::clone(&self.counters);
+        WatcherHandle {
+            join_handle: tokio::spawn(self.run()),
+            counters,
+        }
+    }
+}
+
+/// Handle to a running `IngestionWatcher` task, returned by
+/// `IngestionWatcher::spawn`. Aborting it stops the watch loop without
+/// affecting the work it already committed to `storage`, so an editor
+/// integration can tear down file watching on shutdown without leaving the
+/// `tokio::spawn`ed task running in the background. Dropping the handle
+/// without calling `abort` detaches the task, which keeps running.
+pub struct WatcherHandle {
+    join_handle: tokio::task::JoinHandle<Result<()>>,
+    counters: Arc<WatcherCounters>,
+}
+
+impl WatcherHandle {
+    /// Cancels the watch loop at its next `.await`, which may be inside an
+    /// in-flight `reindex_path` call. Its storage swap is a single
+    /// `reindex_file` transaction, so that part either happened or did not;
+    /// the indexer patches that follow it can be skipped.
+    pub fn abort(&self) {
+        self.join_handle.abort();
+    }
+
+    /// Waits for the watch loop to exit, returning its `run()` result (or an
+    /// error if it was aborted first).
+    pub async fn join(self) -> Result<()> {
+        match self.join_handle.await {
+            Ok(result) => result,
+            Err(e) => Err(anyhow::anyhow!("Watcher task did not exit cleanly: {}", e)),
+        }
+    }
+
+    /// Indexing progress so far: files re-indexed, evicted (deleted outright),
+    /// and failed. Safe to call at any point, including after the watch loop
+    /// has stopped.
+    pub fn stats(&self) -> WatcherStats {
+        self.counters.snapshot()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.join_handle.is_finished()
+    }
+}
+
+/// Point-in-time status of one registered watch, as returned by the
+/// `index_codebase` tool's `watch` operation with `watch_action: "status"`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IndexWatchStatusReport {
+    pub watch_id: String,
+    pub watch_paths: Vec<PathBuf>,
+    pub running: bool,
+    pub stats: WatcherStats,
+}
+
+/// Tracks running [`IngestionWatcher`]s by watch id so the `index_codebase`
+/// tool's `watch` operation can start/stop/list them without the caller
+/// holding onto a [`WatcherHandle`] itself. Mirrors
+/// `indexing::streaming::StreamIngestionManager`'s role for streaming
+/// ingestion sources.
+#[derive(Default)]
+pub struct IndexWatchManager {
+    watches: tokio::sync::RwLock<HashMap<String, (WatcherConfig, WatcherHandle)>>,
+}
+
+impl IndexWatchManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new watch under `watch_id`. Errors if a watch with that id
+    /// is already running — stop it first to restart with a different
+    /// config.
+    pub async fn start(
+        &self,
+        watch_id: String,
+        config: WatcherConfig,
+        storage: Arc<GraphStorage>,
+        indexer: Arc<CodebaseIndexer>,
+    ) -> Result<()> {
+        let mut watches = self.watches.write().await;
+        if let Some((_, existing)) = watches.get(&watch_id) {
+            if !existing.is_finished() {
+                return Err(anyhow::anyhow!("Watch '{}' is already running", watch_id));
+            }
+        }
+
+        let watcher = IngestionWatcher::new(config.clone(), storage, indexer);
+        watches.insert(watch_id, (config, watcher.spawn()));
+        Ok(())
+    }
+
+    pub async fn stop(&self, watch_id: &str) -> Result<()> {
+        let watches = self.watches.read().await;
+        let (_, handle) = watches.get(watch_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown watch: {}", watch_id))?;
+        handle.abort();
+        Ok(())
+    }
+
+    pub async fn status(&self) -> Vec<IndexWatchStatusReport> {
+        let watches = self.watches.read().await;
+        watches.iter()
+            .map(|(id, (config, handle))| IndexWatchStatusReport {
+                watch_id: id.clone(),
+                watch_paths: config.watch_paths.clone(),
+                running: !handle.is_finished(),
+                stats: handle.stats(),
+            })
+            .collect()
+    }
+}
@@ -1,3 +1,4 @@
+pub mod bench;
 pub mod config;
 pub mod embeddings;
 pub mod graph;
@@ -11,6 +12,7 @@ pub mod context;
 pub mod validation;
 pub mod indexing;
 pub mod security;
+pub mod metrics;
 
 // Re-export commonly used types
 pub use config::ServerConfig;
@@ -25,4 +27,4 @@ pub use stability::{CircuitBreaker, CircuitBreakerRegistry};
 pub use context::ContextWindowManager;
 pub use validation::{HallucinationDetector, InputValidator, ValidationError};
 pub use indexing::CodebaseIndexer;
-pub use security::{AuthConfig, AuthManager, AuthResult};
\ No newline at end of file
+pub use security::{ApiKeyPolicy, AuthConfig, AuthManager, AuthResult, OperationClass, RateLimitTier};
\ No newline at end of file
@@ -8,17 +8,22 @@ use tracing_subscriber;
 use kg_mcp_server::{
     ServerConfig,
     GraphStorage,
-    LocalEmbeddingEngine,
     HybridSearchEngine,
     MemoryOptimizer,
     McpServer,
-    migration::{graphiti_migrator::GraphitiMigrator, MigrationConfig, SourceType, Migrator},
+    migration::{graphiti_migrator::GraphitiMigrator, postgres_migrator::PostgresMigrator, MigrationConfig, SourceType, Migrator},
     search::{TextSearchEngine, VectorSearchEngine},
     memory::MemoryConfig as ServerMemoryConfig,
+    embeddings::EmbeddingProvider,
+    indexing::{CodebaseIndexer, IndexingConfig, IngestionWatcher},
 };
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Load a `.env` file (if present) before anything reads MCP_TRANSPORT/
+    // MCP_PORT/KG_DATABASE_URL, so it layers under explicitly-set env vars.
+    kg_mcp_server::config::env_layer::load_dotenv();
+
     // Initialize logging - redirect to stderr for stdio mode
     let transport_mode = env::var("MCP_TRANSPORT").unwrap_or_else(|_| "stdio".to_string());
     
@@ -54,30 +59,80 @@ async fn main() -> Result<()> {
         info!("✅ Database initialized");
     }
 
-    // Initialize embedding engine
-    let mut embedding_engine = LocalEmbeddingEngine::new(config.clone())?;
-    
-    // Initialize with the configured model
-    let model_name = &config.embeddings.model_name;
+    // Initialize the configured embedding provider (local ONNX model, Ollama,
+    // or an OpenAI-compatible endpoint — see `ServerConfig.embeddings.provider`).
     if transport_mode != "stdio" {
-        info!("🔄 Initializing embedding engine with model: {}", model_name);
+        info!("🔄 Initializing embedding provider: {:?}", config.embeddings.provider);
     }
-    embedding_engine.initialize(model_name).await?;
-    
-    let embedding_engine_arc = Arc::new(embedding_engine);
+    let embedding_provider = kg_mcp_server::embeddings::create_embedding_provider(&config).await?;
     if transport_mode != "stdio" {
-        info!("✅ Embedding engine initialized");
+        info!("✅ Embedding provider initialized: {}", embedding_provider.model_id());
     }
 
+    // Search, migration, and tool dispatch still depend on the concrete
+    // `LocalEmbeddingEngine` type today; only the `Local` provider supports
+    // them until those subsystems are migrated onto `dyn EmbeddingProvider`.
+    let embedding_engine_arc = embedding_provider
+        .as_local_engine()
+        .map(|engine| Arc::new(engine.clone()))
+        .ok_or_else(|| anyhow::anyhow!(
+            "embeddings.provider = {:?} is not yet supported by search/migration/tool dispatch; use 'local' until those subsystems move onto EmbeddingProvider",
+            config.embeddings.provider
+        ))?;
+
     // Initialize search engine
     let text_engine = TextSearchEngine::new(storage.clone());
     let vector_engine = VectorSearchEngine::new();
-    let search_engine = HybridSearchEngine::new(text_engine, vector_engine);
+    let search_engine = HybridSearchEngine::new(text_engine, vector_engine)
+        .with_fusion_algorithm(config.search.fusion_algorithm.clone())
+        .with_rrf_k(config.search.rrf_k)
+        .with_weights(config.search.text_search_weight, config.search.vector_search_weight);
     let search_engine_arc = Arc::new(search_engine);
     if transport_mode != "stdio" {
         info!("✅ Search engine initialized");
     }
 
+    // Reproducible search benchmark mode: ingest a fixed workload, run its
+    // queries, encode its embedding corpus, and print a machine-readable
+    // latency/throughput/quality report instead of starting the server.
+    // Parallel to MIGRATION_SOURCE below. When BENCH_BASELINE is also set,
+    // the report is diffed against that prior run and a regression beyond
+    // BENCH_REGRESSION_THRESHOLD_PCT (default 10%) fails the process, so
+    // this can gate CI on performance regressions.
+    if let Ok(workload_path) = env::var("BENCH_WORKLOAD") {
+        info!("📊 Running search benchmark workload: {}", workload_path);
+        let report = kg_mcp_server::bench::run_bench(
+            std::path::Path::new(&workload_path),
+            &storage,
+            &search_engine_arc,
+            &embedding_engine_arc,
+        ).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+
+        if let Ok(baseline_path) = env::var("BENCH_BASELINE") {
+            let threshold_pct: f64 = env::var("BENCH_REGRESSION_THRESHOLD_PCT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10.0);
+            let regressions = kg_mcp_server::bench::check_regressions(
+                &report,
+                std::path::Path::new(&baseline_path),
+                threshold_pct,
+            )?;
+            if !regressions.is_empty() {
+                for regression in &regressions {
+                    eprintln!("⚠️  {}", regression);
+                }
+                return Err(anyhow::anyhow!(
+                    "{} benchmark regression(s) exceeded the {:.1}% threshold",
+                    regressions.len(), threshold_pct
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
     // Initialize memory optimizer
     let memory_config = ServerMemoryConfig {
         max_cache_size: 128 * 1024 * 1024, // Reduced from 256MB to 128MB
@@ -96,14 +151,31 @@ async fn main() -> Result<()> {
         info!("✅ Memory optimizer initialized");
     }
 
-    // Check if migration is requested
-    if let Ok(migration_source) = env::var("MIGRATION_SOURCE") {
-        info!("🔄 Migration source detected: {}", migration_source);
-        
+    // Check if migration is requested. The connection itself may come from
+    // MIGRATION_SOURCE inline or from MIGRATION_SOURCE_CONNECTION_FILE (see
+    // MigrationConfig::resolve_source_connection) - either is enough to
+    // trigger migration, so neither alone gates this block.
+    let migration_source = env::var("MIGRATION_SOURCE").ok();
+    let migration_source_file = env::var("MIGRATION_SOURCE_CONNECTION_FILE").ok();
+    if migration_source.is_some() || migration_source_file.is_some() {
+        info!(
+            "🔄 Migration source detected: {}",
+            migration_source.as_deref().unwrap_or("(from file)")
+        );
+
         // Create migration config
+        let source_type = match env::var("MIGRATION_SOURCE_TYPE").as_deref() {
+            Ok("postgres") => SourceType::Postgres,
+            Ok("neo4j") => SourceType::Neo4j,
+            Ok("json") => SourceType::JsonExport,
+            Ok("csv") => SourceType::CsvExport,
+            Ok("custom") => SourceType::CustomFormat,
+            _ => SourceType::GraphitiMcp,
+        };
         let migration_config = MigrationConfig {
-            source_type: SourceType::GraphitiMcp,
-            source_connection: migration_source,
+            source_type,
+            source_connection: migration_source.unwrap_or_default(),
+            source_connection_file: migration_source_file,
             target_database: config.database_path().to_string_lossy().to_string(),
             batch_size: env::var("MIGRATION_BATCH_SIZE")
                 .unwrap_or_else(|_| "100".to_string())
@@ -116,13 +188,30 @@ async fn main() -> Result<()> {
             validation_enabled: true,
             backup_enabled: true,
             chunk_size: 100,
+            page_size: env::var("MIGRATION_PAGE_SIZE")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10_000),
+            max_retry_attempts: env::var("MIGRATION_MAX_RETRY_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
         };
 
-        // Create migrator
-        let migrator = GraphitiMigrator::new(
-            storage.clone(), 
-            Some(embedding_engine_arc.as_ref().clone())
-        );
+        // Create migrator - PostgreSQL gets its own pooled-connection
+        // implementation; every other source still goes through
+        // GraphitiMigrator, which also streams the Neo4j source over a real
+        // Bolt connection.
+        let migrator: Box<dyn Migrator> = match migration_config.source_type {
+            SourceType::Postgres => Box::new(PostgresMigrator::new(
+                storage.clone(),
+                Some(embedding_engine_arc.as_ref().clone()),
+            )),
+            _ => Box::new(GraphitiMigrator::new(
+                storage.clone(),
+                Some(embedding_engine_arc.as_ref().clone()),
+            )),
+        };
 
         // Analyze migration
         info!("📊 Analyzing migration plan...");
@@ -210,6 +299,21 @@ async fn main() -> Result<()> {
         info!("  Episodes: {}", episode_count);
     }
 
+    // Spawn the incremental file-watching ingestion pipeline (no-op if
+    // `config.watcher.enabled` is false or no watch_paths are configured).
+    let watcher_indexer = Arc::new(CodebaseIndexer::new_with_mcp_config_and_embeddings(
+        String::new(),
+        IndexingConfig::default(),
+        Some(&config.embeddings),
+    ));
+    let watcher = IngestionWatcher::new(config.watcher.clone(), storage_arc.clone(), watcher_indexer);
+    let watcher_handle = watcher.spawn();
+    tokio::spawn(async move {
+        if let Err(e) = watcher_handle.join().await {
+            tracing::error!("Ingestion watcher stopped with error: {}", e);
+        }
+    });
+
     // Start the MCP server
     server.run().await?;
 
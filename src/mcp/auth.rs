@@ -0,0 +1,161 @@
+//! Resolves an inbound MCP request's credential into an authenticated
+//! [`Principal`], bridging the scoped, hashed-at-rest API key registry
+//! (`security::api_keys`, persisted via `GraphStorage`) into MCP-level
+//! request handling: the `Principal` returned here is what
+//! `mcp::server::handle_tool_call_mcp` now feeds into
+//! [`errors::RateLimiter::check_rate_limit`](super::errors::RateLimiter) as
+//! `client_id`, and what it stamps onto `ErrorContext` so an auth failure is
+//! traceable back to a specific key without ever logging the key itself.
+//!
+//! Only the HTTP/SSE transport calls this — stdio is intentionally left
+//! alone (see `handlers::handle_manage_api_keys`'s doc comment: stdio
+//! callers are always locally trusted and have no headers to carry a
+//! credential in regardless).
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::graph::storage::GraphStorage;
+use crate::security::api_keys::ApiKeyScope;
+
+use super::errors::McpError;
+
+/// The resolved identity of an authenticated (or, when auth is disabled,
+/// unrestricted) caller.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    /// Stable identity to key rate limits and `ErrorContext`s on: the
+    /// matched key's id, or `"unrestricted"` when authentication is
+    /// disabled for this deployment.
+    pub client_id: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// First 12 hex characters of the credential's SHA-256 hash (see
+    /// `api_keys::hash_key`) — enough to correlate repeated failures from
+    /// the same key in logs without ever persisting or logging the key
+    /// itself.
+    pub credential_fingerprint: Option<String>,
+}
+
+impl Principal {
+    /// The principal attached when `auth_required` is `false`, matching
+    /// `ResolvedScopes::unrestricted`'s open-by-default posture.
+    pub fn unrestricted() -> Self {
+        Self {
+            client_id: "unrestricted".to_string(),
+            scopes: vec![ApiKeyScope::Admin],
+            credential_fingerprint: None,
+        }
+    }
+
+    pub fn allows(&self, required: ApiKeyScope) -> bool {
+        self.scopes.iter().any(|s| s.implies(required))
+    }
+}
+
+/// Validates inbound MCP requests against the `GraphStorage`-persisted,
+/// hashed-at-rest API key registry and resolves a [`Principal`].
+pub struct Authenticator {
+    storage: Arc<GraphStorage>,
+}
+
+impl Authenticator {
+    pub fn new(storage: Arc<GraphStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// Pulls a bearer credential out of request metadata: an `Authorization:
+    /// Bearer <key>` header value if present, else a `auth` field on the
+    /// tool-call params (a plain string, or `{"token": "..."}`) — the only
+    /// option for transports with no headers at all.
+    pub fn extract_credential<'a>(authorization_header: Option<&'a str>, params: &'a Value) -> Option<&'a str> {
+        if let Some(token) = authorization_header.and_then(|h| h.strip_prefix("Bearer ")) {
+            return Some(token);
+        }
+
+        match params.get("auth") {
+            Some(Value::String(s)) => Some(s.as_str()),
+            Some(auth) => auth.get("token").and_then(|t| t.as_str()),
+            None => None,
+        }
+    }
+
+    /// Resolves `credential` against the persisted key registry, returning
+    /// the matched key's `Principal` or an `McpError::AuthError` for a
+    /// missing, unknown, or revoked key.
+    pub fn authenticate(&self, credential: &str) -> Result<Principal, McpError> {
+        let fingerprint = crate::security::api_keys::hash_key(credential)[..12].to_string();
+
+        match self.storage.find_api_key_by_plaintext(credential) {
+            Ok(Some(record)) if !record.revoked => Ok(Principal {
+                client_id: record.id,
+                scopes: record.scopes,
+                credential_fingerprint: Some(fingerprint),
+            }),
+            Ok(_) => Err(McpError::AuthError { message: "Invalid or revoked API key".to_string() }),
+            Err(e) => Err(McpError::AuthError { message: format!("API key lookup failed: {}", e) }),
+        }
+    }
+
+    /// Authorizes `principal` for `tool_name`: `ToolNotFound` if the tool
+    /// doesn't exist at all (distinct from an auth failure), else
+    /// `AuthError` if `principal`'s scopes don't imply the scope
+    /// `mcp::server::required_scope_for_tool` requires for it.
+    pub fn authorize_tool(principal: &Principal, tool_name: &str) -> Result<(), McpError> {
+        if !crate::mcp::tools::tool_exists(tool_name) {
+            return Err(McpError::ToolNotFound { tool_name: tool_name.to_string() });
+        }
+
+        let required = super::server::required_scope_for_tool(tool_name);
+        if principal.allows(required) {
+            Ok(())
+        } else {
+            Err(McpError::AuthError {
+                message: format!(
+                    "Principal '{}' lacks {:?} scope required by '{}'",
+                    principal.client_id, required, tool_name
+                ),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_bearer_header_over_params_auth() {
+        let params = json!({"auth": "from-params"});
+        let credential = Authenticator::extract_credential(Some("Bearer from-header"), &params);
+        assert_eq!(credential, Some("from-header"));
+    }
+
+    #[test]
+    fn falls_back_to_params_auth_string() {
+        let params = json!({"auth": "plain-token"});
+        let credential = Authenticator::extract_credential(None, &params);
+        assert_eq!(credential, Some("plain-token"));
+    }
+
+    #[test]
+    fn falls_back_to_params_auth_token_object() {
+        let params = json!({"auth": {"token": "nested-token"}});
+        let credential = Authenticator::extract_credential(None, &params);
+        assert_eq!(credential, Some("nested-token"));
+    }
+
+    #[test]
+    fn no_credential_anywhere_is_none() {
+        let params = json!({});
+        assert_eq!(Authenticator::extract_credential(None, &params), None);
+    }
+
+    #[test]
+    fn unrestricted_principal_allows_everything() {
+        let principal = Principal::unrestricted();
+        assert!(principal.allows(ApiKeyScope::Admin));
+        assert!(principal.allows(ApiKeyScope::Read));
+    }
+}
@@ -0,0 +1,85 @@
+//! Opaque pagination cursors for `search_memory`/`manage_graph` list
+//! operations (`nodes`, `facts`, `episodes`, `get_episodes`).
+//!
+//! There's no stable row-id ordering to seek on in this store, so a
+//! cursor doesn't point at a physical offset — it's a signed snapshot of
+//! the *original query parameters* plus how many results have already
+//! been consumed. A follow-up call decodes it, replays the same query at
+//! a larger limit, and skips the already-seen prefix, so a caller walking
+//! the whole result set gets a stable page sequence even if the graph
+//! mutates between calls (new matches land after the already-seen
+//! prefix instead of shifting it).
+//!
+//! The token is hex-encoded JSON with a trailing SHA-256 checksum over
+//! its own fields, so a hand-edited token or one issued by a different
+//! operation is rejected outright instead of silently misbehaving.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// A decoded pagination cursor: which operation it belongs to, the
+/// original query parameters to replay, and how many results of that
+/// replay have already been returned to the caller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageCursor {
+    pub operation: String,
+    pub params: Value,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedCursor {
+    cursor: PageCursor,
+    checksum: String,
+}
+
+fn checksum_for(cursor: &PageCursor) -> String {
+    let bytes = serde_json::to_vec(cursor).expect("PageCursor is always representable as JSON");
+    let digest = Sha256::digest(&bytes);
+    hex_encode(&digest)
+}
+
+impl PageCursor {
+    pub fn new(operation: impl Into<String>, params: Value, offset: usize) -> Self {
+        Self { operation: operation.into(), params, offset }
+    }
+
+    /// Encodes this cursor into the opaque token handed back to callers
+    /// as `next_cursor`.
+    pub fn encode(&self) -> String {
+        let checksum = checksum_for(self);
+        let signed = SignedCursor { cursor: self.clone(), checksum };
+        let bytes = serde_json::to_vec(&signed).expect("SignedCursor is always representable as JSON");
+        hex_encode(&bytes)
+    }
+
+    /// Decodes and integrity-checks a `cursor` token from a caller.
+    /// Errors (rather than silently producing a garbage page) on
+    /// malformed tokens or ones whose checksum doesn't match.
+    pub fn decode(token: &str) -> Result<Self> {
+        let bytes = hex_decode(token).map_err(|_| anyhow!("Malformed cursor: not valid hex"))?;
+        let signed: SignedCursor = serde_json::from_slice(&bytes)
+            .map_err(|_| anyhow!("Malformed cursor: not a recognized pagination token"))?;
+        if checksum_for(&signed.cursor) != signed.checksum {
+            return Err(anyhow!("Cursor failed its integrity check (tampered with, or truncated)"));
+        }
+        Ok(signed.cursor)
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Result<Vec<u8>, ()> {
+    // Non-ASCII input would make the two-byte slices below split a char.
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return Err(());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| ()))
+        .collect()
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
 use tracing::error;
 
@@ -39,8 +40,35 @@ pub enum McpError {
     AuthError { message: String },
     
     #[error("Rate limit exceeded: {message}")]
-    RateLimit { message: String },
+    RateLimit {
+        message: String,
+        /// Seconds until the bucket that rejected this request has refilled
+        /// enough to allow another one. `None` when the rejecting limiter
+        /// doesn't track a token bucket (e.g. no caller currently produces
+        /// that case, but the field stays optional so future limiters aren't
+        /// forced to fabricate one).
+        retry_after_secs: Option<f64>,
+    },
     
+    /// Distinct from `RateLimit`: this isn't a per-client quota being
+    /// exceeded, it's the server-wide `mcp::search_queue::SearchQueue`
+    /// shedding load because it's at capacity — see
+    /// `SearchQueue::acquire`'s random-drop admission control.
+    #[error("Search queue is full: {message}")]
+    QueueFull {
+        message: String,
+        retry_after_secs: Option<f64>,
+    },
+
+    /// Returned by `mcp::performance::ConnectionPool::acquire_timeout` when
+    /// no permit (global or per-tool) became free before the deadline,
+    /// instead of queuing the caller forever.
+    #[error("Connection pool exhausted: {message}")]
+    PoolExhausted {
+        message: String,
+        retry_after_secs: Option<f64>,
+    },
+
     #[error("Internal server error: {message}")]
     Internal { message: String },
 }
@@ -53,6 +81,18 @@ pub struct McpErrorResponse {
     pub data: Option<Value>,
 }
 
+/// Whether retrying an operation that failed with a given `McpError` is
+/// expected to eventually succeed without the caller changing anything
+/// (`Transient`, e.g. a busy database or an upstream timeout) or would just
+/// fail the same way again (`Permanent`, e.g. a malformed request or a
+/// missing tool) — see `McpError::kind`/`is_retryable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Transient,
+    Permanent,
+}
+
 /// Error context for debugging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorContext {
@@ -62,11 +102,107 @@ pub struct ErrorContext {
     pub parameters: Option<Value>,
     pub user_agent: Option<String>,
     pub client_version: Option<String>,
+    /// The authenticated `Principal::client_id` that made this request, if
+    /// any (see `mcp::auth::Authenticator`). Lets an auth failure be traced
+    /// back to a specific caller without that caller's credential ever
+    /// appearing in logs.
+    pub client_id: Option<String>,
+    /// `Principal::credential_fingerprint` — the first 12 hex characters of
+    /// the credential's SHA-256 hash, never the credential itself.
+    pub credential_fingerprint: Option<String>,
 }
 
 impl McpError {
-    /// Convert to MCP error response with appropriate error codes
-    pub fn to_mcp_error(&self) -> McpErrorResponse {
+    /// Short, stable name for this variant, independent of its `message`
+    /// payload — used to key per-variant error counts (see
+    /// `mcp::server::ErrorMetrics`) without the cardinality blowup that
+    /// counting by formatted message text would cause.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            McpError::Protocol { .. } => "protocol",
+            McpError::InvalidRequest { .. } => "invalid_request",
+            McpError::ToolNotFound { .. } => "tool_not_found",
+            McpError::InvalidParameters { .. } => "invalid_parameters",
+            McpError::SearchError { .. } => "search_error",
+            McpError::StorageError { .. } => "storage_error",
+            McpError::EmbeddingError { .. } => "embedding_error",
+            McpError::MemoryError { .. } => "memory_error",
+            McpError::GraphError { .. } => "graph_error",
+            McpError::AuthError { .. } => "auth_error",
+            McpError::RateLimit { .. } => "rate_limit",
+            McpError::QueueFull { .. } => "queue_full",
+            McpError::PoolExhausted { .. } => "pool_exhausted",
+            McpError::Internal { .. } => "internal",
+        }
+    }
+
+    /// Whether this variant is generically safe to retry without the caller
+    /// changing anything about the request — see `ErrorKind`. Used both to
+    /// populate `McpErrorResponse.data.retryable` and, per this request, to
+    /// replace `ErrorHandler::handle_error`'s old message-substring sniffing
+    /// with a typed classification.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            McpError::Protocol { .. }
+            | McpError::InvalidRequest { .. }
+            | McpError::ToolNotFound { .. }
+            | McpError::InvalidParameters { .. }
+            | McpError::AuthError { .. } => ErrorKind::Permanent,
+            McpError::SearchError { .. }
+            | McpError::StorageError { .. }
+            | McpError::EmbeddingError { .. }
+            | McpError::MemoryError { .. }
+            | McpError::GraphError { .. }
+            | McpError::RateLimit { .. }
+            | McpError::QueueFull { .. }
+            | McpError::PoolExhausted { .. }
+            | McpError::Internal { .. } => ErrorKind::Transient,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.kind() == ErrorKind::Transient
+    }
+
+    /// The seconds a retryable error suggests waiting before trying again,
+    /// if it knows one (`RateLimit` via its token bucket, `QueueFull` via a
+    /// fixed backoff hint).
+    pub fn retry_after_secs(&self) -> Option<f64> {
+        match self {
+            McpError::RateLimit { retry_after_secs, .. } => *retry_after_secs,
+            McpError::QueueFull { retry_after_secs, .. } => *retry_after_secs,
+            McpError::PoolExhausted { retry_after_secs, .. } => *retry_after_secs,
+            _ => None,
+        }
+    }
+
+    /// HTTP status this variant would map to if surfaced over the admin
+    /// HTTP API (see `mcp::server`) rather than JSON-RPC, so both transports
+    /// share one classification instead of keeping a second mapping in sync.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            McpError::Protocol { .. } | McpError::InvalidRequest { .. } | McpError::InvalidParameters { .. } => 400,
+            McpError::AuthError { .. } => 401,
+            McpError::ToolNotFound { .. } => 404,
+            McpError::RateLimit { .. } => 429,
+            McpError::QueueFull { .. } => 503,
+            McpError::PoolExhausted { .. } => 503,
+            McpError::SearchError { .. }
+            | McpError::StorageError { .. }
+            | McpError::EmbeddingError { .. }
+            | McpError::MemoryError { .. }
+            | McpError::GraphError { .. }
+            | McpError::Internal { .. } => 500,
+        }
+    }
+
+    /// Convert to an MCP error response with appropriate JSON-RPC error
+    /// codes. `data` always carries the stable, machine-readable
+    /// `{kind, retryable, retry_after, error_id}` shape — even with debug
+    /// info disabled — so clients can implement backoff without parsing
+    /// `message`'s prose; `ErrorHandler::handle_error` merges richer debug
+    /// fields into it when `enable_debug_info` is set.
+    pub fn to_mcp_error(&self, error_id: &str) -> McpErrorResponse {
         let (code, message) = match self {
             McpError::Protocol { message } => (-32700, message.clone()),
             McpError::InvalidRequest { message } => (-32600, message.clone()),
@@ -78,14 +214,21 @@ impl McpError {
             McpError::MemoryError { message } => (-32004, format!("Memory operation failed: {}", message)),
             McpError::GraphError { message } => (-32005, format!("Graph operation failed: {}", message)),
             McpError::AuthError { message } => (-32006, format!("Authentication failed: {}", message)),
-            McpError::RateLimit { message } => (-32007, format!("Rate limit exceeded: {}", message)),
+            McpError::RateLimit { message, .. } => (-32007, format!("Rate limit exceeded: {}", message)),
+            McpError::QueueFull { message, .. } => (-32008, format!("Search queue is full: {}", message)),
+            McpError::PoolExhausted { message, .. } => (-32009, format!("Connection pool exhausted: {}", message)),
             McpError::Internal { message } => (-32603, format!("Internal error: {}", message)),
         };
 
         McpErrorResponse {
             code,
             message,
-            data: None,
+            data: Some(json!({
+                "kind": self.kind(),
+                "retryable": self.is_retryable(),
+                "retry_after": self.retry_after_secs(),
+                "error_id": error_id,
+            })),
         }
     }
 
@@ -117,6 +260,11 @@ impl std::error::Error for McpErrorWithContext {}
 pub struct ErrorHandler {
     enable_debug_info: bool,
     enable_error_logging: bool,
+    /// Where `handle_error` also surfaces every `McpErrorResponse` as an
+    /// `EventRecord`, so failures show up in "Recent Events" even without
+    /// log tailing. `None` by default; wire one up via
+    /// `with_recent_events_buffer`.
+    recent_events: Option<Arc<crate::metrics::RecentEventsBuffer>>,
 }
 
 impl ErrorHandler {
@@ -124,63 +272,80 @@ impl ErrorHandler {
         Self {
             enable_debug_info,
             enable_error_logging,
+            recent_events: None,
+        }
+    }
+
+    /// Routes every `McpErrorResponse` produced by `handle_error` into
+    /// `buffer` alongside the usual `tracing::error!` line.
+    pub fn with_recent_events_buffer(mut self, buffer: Arc<crate::metrics::RecentEventsBuffer>) -> Self {
+        self.recent_events = Some(buffer);
+        self
+    }
+
+    /// Pushes `response` into `self.recent_events`, if configured, tagged
+    /// with `context`'s `error_id` so it can be correlated with what the
+    /// client saw.
+    fn record_recent_event(&self, response: &McpErrorResponse, context: &Option<ErrorContext>) {
+        if let Some(buffer) = &self.recent_events {
+            buffer.push(crate::metrics::EventRecord {
+                level: "ERROR".to_string(),
+                timestamp: chrono::Utc::now(),
+                target: "mcp::errors".to_string(),
+                message: response.message.clone(),
+                error_id: context.as_ref().map(|ctx| ctx.error_id.clone()),
+            });
+        }
+    }
+
+    /// Classifies an `anyhow::Error` that didn't originate as an `McpError`
+    /// by downcasting to the concrete error type its source actually is,
+    /// rather than sniffing `to_string()` for substrings like `"timeout"` —
+    /// a message change in a dependency (or a user-supplied string that
+    /// happens to contain one of those words) used to silently misclassify
+    /// the error.
+    fn classify_opaque_error(error: &anyhow::Error) -> McpError {
+        if error.downcast_ref::<tokio::time::error::Elapsed>().is_some() {
+            return McpError::Internal { message: "Operation timed out".to_string() };
+        }
+        if error.downcast_ref::<rusqlite::Error>().is_some() {
+            return McpError::StorageError { message: error.to_string() };
         }
+        if error.downcast_ref::<reqwest::Error>().is_some() {
+            return McpError::EmbeddingError { message: error.to_string() };
+        }
+        McpError::Internal { message: "Internal server error".to_string() }
     }
 
     /// Handle an error and convert to appropriate MCP response
     pub fn handle_error(&self, error: anyhow::Error, context: Option<ErrorContext>) -> McpErrorResponse {
-        // Try to downcast to McpError first
-        if let Some(mcp_error) = error.downcast_ref::<McpError>() {
-            let mut response = mcp_error.to_mcp_error();
-            
-            if self.enable_debug_info {
-                if let Some(ref ctx) = context {
-                    response.data = Some(json!({
-                        "error_id": ctx.error_id,
-                        "timestamp": ctx.timestamp,
-                        "context": ctx
-                    }));
-                }
-            }
-            
-            if self.enable_error_logging {
-                error!("MCP Error: {} - Context: {:?}", mcp_error, context);
-            }
-            
-            return response;
-        }
+        let error_id = context.as_ref().map(|ctx| ctx.error_id.clone()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
-        // Handle other error types
-        let error_message = error.to_string();
-        let mcp_error = if error_message.contains("timeout") {
-            McpError::Internal { message: "Operation timed out".to_string() }
-        } else if error_message.contains("not found") {
-            McpError::InvalidRequest { message: "Resource not found".to_string() }
-        } else if error_message.contains("permission") || error_message.contains("unauthorized") {
-            McpError::AuthError { message: "Insufficient permissions".to_string() }
-        } else {
-            McpError::Internal { message: "Internal server error".to_string() }
+        // Try to downcast to McpError first; fall back to classifying the
+        // underlying error source when it isn't one.
+        let (mcp_error, is_opaque) = match error.downcast_ref::<McpError>() {
+            Some(mcp_error) => (mcp_error.to_mcp_error(&error_id), false),
+            None => (Self::classify_opaque_error(&error).to_mcp_error(&error_id), true),
         };
+        let mut response = mcp_error;
 
-        let mut response = mcp_error.to_mcp_error();
-        
         if self.enable_debug_info {
-            response.data = Some(json!({
-                "original_error": error_message,
-                "error_chain": format!("{:?}", error)
-            }));
-            
-            if let Some(ref ctx) = context {
-                if let Some(data) = response.data.as_mut() {
-                    data.as_object_mut().unwrap().insert("context".to_string(), json!(ctx));
+            if let Some(data) = response.data.as_mut().and_then(Value::as_object_mut) {
+                if is_opaque {
+                    data.insert("original_error".to_string(), json!(error.to_string()));
+                    data.insert("error_chain".to_string(), json!(format!("{:?}", error)));
+                }
+                if let Some(ref ctx) = context {
+                    data.insert("context".to_string(), json!(ctx));
                 }
             }
         }
-        
+
         if self.enable_error_logging {
-            error!("Unhandled Error: {} - Context: {:?}", error, context);
+            error!("MCP Error: {} - Context: {:?}", error, context);
         }
-        
+
+        self.record_recent_event(&response, &context);
         response
     }
 
@@ -191,6 +356,21 @@ impl ErrorHandler {
         parameters: Option<Value>,
         user_agent: Option<&str>,
         client_version: Option<&str>,
+    ) -> ErrorContext {
+        self.create_context_for_principal(tool_name, parameters, user_agent, client_version, None, None)
+    }
+
+    /// Like `create_context`, but also stamps the authenticated caller's
+    /// identity (see `mcp::auth::Principal`), so auth failures recorded via
+    /// `handle_error` are traceable per-client.
+    pub fn create_context_for_principal(
+        &self,
+        tool_name: Option<&str>,
+        parameters: Option<Value>,
+        user_agent: Option<&str>,
+        client_version: Option<&str>,
+        client_id: Option<&str>,
+        credential_fingerprint: Option<&str>,
     ) -> ErrorContext {
         ErrorContext {
             error_id: uuid::Uuid::new_v4().to_string(),
@@ -199,6 +379,8 @@ impl ErrorHandler {
             parameters,
             user_agent: user_agent.map(|s| s.to_string()),
             client_version: client_version.map(|s| s.to_string()),
+            client_id: client_id.map(|s| s.to_string()),
+            credential_fingerprint: credential_fingerprint.map(|s| s.to_string()),
         }
     }
 }
@@ -273,46 +455,220 @@ impl ParameterValidator {
     }
 }
 
-/// Rate limiting for MCP operations
+/// Tools expensive enough (embedding generation, full-text/vector search) to
+/// warrant a stricter budget than `RateLimiter`'s default per-client rate —
+/// see `RateLimiter::capacity_and_refill`. Distinct from `ToolRateLimiter`'s
+/// `tool_costs`, which throttles the same tool across all callers rather
+/// than scaling a single caller's overall budget.
+const EXPENSIVE_TOOLS: &[&str] = &[
+    "mcp_kg-mcp-server_search_memory",
+    "mcp_kg-mcp-server_add_memory",
+    "mcp_kg-mcp-server_index_codebase",
+];
+
+/// Per-client token-bucket rate limiting for MCP operations. `client_id` is
+/// keyed on the identity `mcp::auth::Authenticator::authenticate` resolves
+/// into a `Principal` (its persisted API key id, or `"unrestricted"` when
+/// auth is disabled) — see `mcp::server::handle_tool_call_mcp`, the only
+/// caller. Each `(client_id, tool_name)` pair gets its own bucket, so a
+/// client's burst against one tool doesn't exhaust the budget it has left
+/// for another.
 pub struct RateLimiter {
-    requests_per_minute: u32,
-    requests: std::collections::HashMap<String, Vec<chrono::DateTime<chrono::Utc>>>,
+    default_capacity: f64,
+    default_refill_per_sec: f64,
+    buckets: std::collections::HashMap<(String, String), TokenBucket>,
 }
 
 impl RateLimiter {
+    /// `requests_per_minute` is both the sustained refill rate and, absent a
+    /// configured burst, the bucket capacity — matching the flat per-minute
+    /// cap this replaces.
     pub fn new(requests_per_minute: u32) -> Self {
+        Self::with_burst_capacity(requests_per_minute, requests_per_minute)
+    }
+
+    /// Like `new`, but with a burst `capacity` configured separately from
+    /// the sustained `requests_per_minute` refill rate (see
+    /// `config::SecurityConfig::rate_limit_burst`).
+    pub fn with_burst_capacity(requests_per_minute: u32, capacity: u32) -> Self {
         Self {
-            requests_per_minute,
-            requests: std::collections::HashMap::new(),
+            default_capacity: capacity.max(1) as f64,
+            default_refill_per_sec: requests_per_minute as f64 / 60.0,
+            buckets: std::collections::HashMap::new(),
         }
     }
 
-    /// Check if request is allowed for client
-    pub fn check_rate_limit(&mut self, client_id: &str) -> Result<(), McpError> {
-        let now = chrono::Utc::now();
-        let minute_ago = now - chrono::Duration::minutes(1);
-        
-        let client_requests = self.requests.entry(client_id.to_string()).or_insert_with(Vec::new);
-        
-        // Remove old requests
-        client_requests.retain(|&timestamp| timestamp > minute_ago);
-        
-        if client_requests.len() >= self.requests_per_minute as usize {
-            return Err(McpError::RateLimit {
-                message: format!("Rate limit exceeded: {} requests per minute", self.requests_per_minute)
-            });
+    /// `EXPENSIVE_TOOLS` get half the default capacity and refill rate;
+    /// everything else (including `None`, the client's overall budget) gets
+    /// the full default.
+    fn capacity_and_refill(&self, tool_name: Option<&str>) -> (f64, f64) {
+        let factor = match tool_name {
+            Some(tool) if EXPENSIVE_TOOLS.contains(&tool) => 0.5,
+            _ => 1.0,
+        };
+        (self.default_capacity * factor, self.default_refill_per_sec * factor)
+    }
+
+    /// Checks out one token from `client_id`'s bucket for `tool_name` (or
+    /// the client's overall bucket, if `None`), returning
+    /// `McpError::RateLimit` with a `retry_after_secs` hint when none are
+    /// available yet.
+    pub fn check_rate_limit(&mut self, client_id: &str, tool_name: Option<&str>) -> Result<(), McpError> {
+        let (capacity, refill_per_sec) = self.capacity_and_refill(tool_name);
+        let key = (client_id.to_string(), tool_name.unwrap_or("*").to_string());
+        let bucket = self
+            .buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(capacity, refill_per_sec));
+
+        if bucket.try_acquire(1.0) {
+            Ok(())
+        } else {
+            Err(McpError::RateLimit {
+                message: format!(
+                    "Rate limit exceeded for client '{}'{}",
+                    client_id,
+                    tool_name.map(|t| format!(" on tool '{}'", t)).unwrap_or_default()
+                ),
+                retry_after_secs: Some(bucket.retry_after_secs(1.0)),
+            })
         }
-        
-        client_requests.push(now);
-        Ok(())
     }
 
-    /// Clean up old entries periodically
+    /// Evicts buckets that have been idle long enough to have refilled to
+    /// full: once a bucket is back at capacity, `try_acquire` behaves
+    /// exactly as a freshly-created one would, so there's nothing lost by
+    /// dropping it.
     pub fn cleanup(&mut self) {
-        let minute_ago = chrono::Utc::now() - chrono::Duration::minutes(1);
-        self.requests.retain(|_, timestamps| {
-            timestamps.retain(|&timestamp| timestamp > minute_ago);
-            !timestamps.is_empty()
+        self.buckets.retain(|_, bucket| !bucket.is_full());
+    }
+
+    /// Snapshot of every client/tool bucket currently short of full
+    /// capacity, alongside its remaining tokens. Backs the `/clients` admin
+    /// endpoint so operators can see who's actively being rate limited
+    /// without tailing logs.
+    pub fn active_clients(&self) -> Vec<(String, u32)> {
+        self.buckets
+            .iter()
+            .filter(|(_, bucket)| !bucket.is_full())
+            .map(|((client_id, tool_name), bucket)| {
+                let label = if tool_name == "*" {
+                    client_id.clone()
+                } else {
+                    format!("{}:{}", client_id, tool_name)
+                };
+                (label, bucket.remaining() as u32)
+            })
+            .collect()
+    }
+}
+
+/// A single token bucket: holds up to `capacity` tokens, refilling at
+/// `refill_per_sec` tokens/sec since the last acquire. `try_acquire` computes
+/// the refill lazily from elapsed time rather than running a background
+/// ticker, so idle buckets cost nothing until they're used again.
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_refill: std::time::Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        Self {
+            capacity,
+            refill_per_sec,
+            tokens: capacity,
+            last_refill: std::time::Instant::now(),
+        }
+    }
+
+    /// Refills for elapsed time, then attempts to spend `cost` tokens.
+    /// Returns `true` (and subtracts `cost`) only if enough tokens were
+    /// available.
+    fn try_acquire(&mut self, cost: f64) -> bool {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = std::time::Instant::now();
+
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Seconds until this bucket refills enough to afford `cost` tokens,
+    /// given its state as of the last `try_acquire`. Used to build
+    /// `McpError::RateLimit`'s `retry_after_secs` hint right after a failed
+    /// acquire.
+    fn retry_after_secs(&self, cost: f64) -> f64 {
+        if self.refill_per_sec <= 0.0 {
+            return f64::INFINITY;
+        }
+        ((cost - self.tokens).max(0.0)) / self.refill_per_sec
+    }
+
+    /// Tokens currently available, refilled for elapsed time since the last
+    /// `try_acquire` but without consuming any or persisting the refill —
+    /// a read-only projection for reporting (see `is_full`/`remaining`).
+    fn tokens_now(&self) -> f64 {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        (self.tokens + elapsed * self.refill_per_sec).min(self.capacity)
+    }
+
+    /// Whether this bucket is back at full capacity — evicting it from a
+    /// limiter's map is then equivalent to keeping it (see
+    /// `RateLimiter::cleanup`).
+    fn is_full(&self) -> bool {
+        self.tokens_now() >= self.capacity
+    }
+
+    fn remaining(&self) -> f64 {
+        self.tokens_now()
+    }
+}
+
+/// Per-tool token-bucket limiter for `handle_tool_request`. Each distinct
+/// tool name gets its own lazily-created `TokenBucket`, so a burst on one
+/// tool (e.g. `add_memory`) can't starve another (e.g. a cached search) out
+/// of its own budget.
+pub struct ToolRateLimiter {
+    config: crate::config::ToolRateLimitConfig,
+    buckets: std::sync::Mutex<std::collections::HashMap<String, TokenBucket>>,
+}
+
+impl ToolRateLimiter {
+    pub fn new(config: crate::config::ToolRateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: std::sync::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Checks out `tool_name`'s configured cost against its bucket. Safe to
+    /// call from concurrently `tokio::spawn`ed handlers: the bucket map and
+    /// each bucket's state are both behind the same `Mutex` acquisition.
+    pub fn try_acquire(&self, tool_name: &str) -> Result<(), McpError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let cost = self.config.tool_costs.get(tool_name).copied().unwrap_or(1.0);
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(tool_name.to_string()).or_insert_with(|| {
+            TokenBucket::new(self.config.default_capacity, self.config.default_refill_per_sec)
         });
+
+        if bucket.try_acquire(cost) {
+            Ok(())
+        } else {
+            Err(McpError::RateLimit {
+                message: format!("Tool '{}' is rate limited, try again shortly", tool_name),
+                retry_after_secs: Some(bucket.retry_after_secs(cost)),
+            })
+        }
     }
-} 
\ No newline at end of file
+}
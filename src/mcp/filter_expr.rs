@@ -0,0 +1,463 @@
+//! A small filter-expression DSL for post-scoring predicates over search
+//! results, inspired by MeiliSearch's `CONTAINS` filter operator. Unlike
+//! `graph::filters::{NodeFilter, EdgeFilter, EpisodeFilter}` (which build
+//! a SQL `WHERE` clause evaluated by sqlite before results are ranked),
+//! this parses a `filter` string into a [`FilterExpr`] AST and evaluates
+//! it in-process against already-scored [`KGNode`]/[`Episode`] values, so
+//! it composes with ranking logic (semantic_ratio fusion, similarity
+//! thresholds) that has no SQL-side equivalent.
+//!
+//! Grammar (case-insensitive keywords, `FIELD` one of `node_type`,
+//! `group_id`, `name`, `summary`, `created_at`):
+//!
+//! ```text
+//! expr       := or_expr
+//! or_expr    := and_expr (OR and_expr)*
+//! and_expr   := unary (AND unary)*
+//! unary      := NOT unary | primary
+//! primary    := '(' expr ')' | comparison
+//! comparison := FIELD ('==' | '!=' | '>' | '>=' | '<' | '<=' | CONTAINS) literal
+//!             | FIELD IN '[' literal (',' literal)* ']'
+//! literal    := string-literal | number
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::graph::{Episode, KGNode};
+
+/// A parsed filter expression, ready to be evaluated against any
+/// [`FilterSubject`] via [`evaluate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    Compare { field: String, op: CompareOp, value: FilterValue },
+    Contains { field: String, value: String },
+    In { field: String, values: Vec<FilterValue> },
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Str(String),
+    Num(f64),
+}
+
+/// A malformed `filter` string, with the byte offset of the token that
+/// parsing failed on so callers can point the user at the exact spot.
+#[derive(Debug, Clone)]
+pub struct FilterParseError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at position {})", self.message, self.position)
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
+/// Implemented by the record types a filter can be evaluated against —
+/// [`KGNode`] for `similar_concepts`/`batch`, [`Episode`] for
+/// `get_episodes`. Resolves a DSL field name to the value actually
+/// stored on that record (`summary` on an `Episode` reads its `content`,
+/// since an episode has no summary column); a field the record has no
+/// equivalent for (e.g. `node_type` on an `Episode`, which has no type
+/// column) resolves to `None`, and every operator treats a missing field as
+/// "doesn't match" rather than erroring at evaluation time, since the
+/// expression was already validated at parse time.
+pub trait FilterSubject {
+    fn field(&self, name: &str) -> Option<FilterValue>;
+}
+
+impl FilterSubject for KGNode {
+    fn field(&self, name: &str) -> Option<FilterValue> {
+        match name {
+            "node_type" => Some(FilterValue::Str(self.node_type.clone())),
+            "group_id" => self.group_id.clone().map(FilterValue::Str),
+            "name" => Some(FilterValue::Str(self.name.clone())),
+            "summary" => Some(FilterValue::Str(self.summary.clone())),
+            "created_at" => Some(FilterValue::Str(self.created_at.to_rfc3339())),
+            _ => None,
+        }
+    }
+}
+
+impl FilterSubject for Episode {
+    fn field(&self, name: &str) -> Option<FilterValue> {
+        match name {
+            "group_id" => self.group_id.clone().map(FilterValue::Str),
+            "name" => Some(FilterValue::Str(self.name.clone())),
+            "summary" => Some(FilterValue::Str(self.content.clone())),
+            "created_at" => Some(FilterValue::Str(self.created_at.to_rfc3339())),
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates `expr` against `subject`, field by field.
+pub fn evaluate<T: FilterSubject>(expr: &FilterExpr, subject: &T) -> bool {
+    match expr {
+        FilterExpr::Compare { field, op, value } => match subject.field(field) {
+            Some(field_value) => compare(*op, &field_value, value),
+            None => false,
+        },
+        FilterExpr::Contains { field, value } => match subject.field(field) {
+            Some(FilterValue::Str(s)) => s.contains(value.as_str()),
+            _ => false,
+        },
+        FilterExpr::In { field, values } => match subject.field(field) {
+            Some(field_value) => values.iter().any(|v| compare(CompareOp::Eq, &field_value, v)),
+            None => false,
+        },
+        FilterExpr::And(a, b) => evaluate(a, subject) && evaluate(b, subject),
+        FilterExpr::Or(a, b) => evaluate(a, subject) || evaluate(b, subject),
+        FilterExpr::Not(a) => !evaluate(a, subject),
+    }
+}
+
+/// Parses an RFC 3339 timestamp, falling back to a bare `YYYY-MM-DD`
+/// date (midnight UTC) since that's the more natural literal to type in
+/// a filter string by hand.
+fn try_parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc))
+}
+
+fn compare(op: CompareOp, field_value: &FilterValue, literal: &FilterValue) -> bool {
+    let ordering = match (field_value, literal) {
+        (FilterValue::Num(a), FilterValue::Num(b)) => a.partial_cmp(b),
+        (FilterValue::Str(a), FilterValue::Str(b)) => match (try_parse_datetime(a), try_parse_datetime(b)) {
+            (Some(da), Some(db)) => da.partial_cmp(&db),
+            _ => a.partial_cmp(b),
+        },
+        // A type mismatch (comparing a string field against a numeric
+        // literal or vice versa) can never be equal.
+        _ => return matches!(op, CompareOp::Ne),
+    };
+
+    match (op, ordering) {
+        (CompareOp::Eq, Some(std::cmp::Ordering::Equal)) => true,
+        (CompareOp::Ne, Some(o)) => o != std::cmp::Ordering::Equal,
+        (CompareOp::Ne, None) => true,
+        (CompareOp::Gt, Some(std::cmp::Ordering::Greater)) => true,
+        (CompareOp::Ge, Some(std::cmp::Ordering::Greater)) | (CompareOp::Ge, Some(std::cmp::Ordering::Equal)) => true,
+        (CompareOp::Lt, Some(std::cmp::Ordering::Less)) => true,
+        (CompareOp::Le, Some(std::cmp::Ordering::Less)) | (CompareOp::Le, Some(std::cmp::Ordering::Equal)) => true,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    Contains,
+    In,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+struct PositionedToken {
+    token: Token,
+    pos: usize,
+}
+
+fn tokenize(input: &str) -> Result<Vec<PositionedToken>, FilterParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut byte_pos = 0;
+
+    let char_byte_len = |c: char| c.len_utf8();
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            byte_pos += char_byte_len(c);
+            i += 1;
+            continue;
+        }
+
+        let start_pos = byte_pos;
+        match c {
+            '(' => { tokens.push(PositionedToken { token: Token::LParen, pos: start_pos }); byte_pos += 1; i += 1; }
+            ')' => { tokens.push(PositionedToken { token: Token::RParen, pos: start_pos }); byte_pos += 1; i += 1; }
+            '[' => { tokens.push(PositionedToken { token: Token::LBracket, pos: start_pos }); byte_pos += 1; i += 1; }
+            ']' => { tokens.push(PositionedToken { token: Token::RBracket, pos: start_pos }); byte_pos += 1; i += 1; }
+            ',' => { tokens.push(PositionedToken { token: Token::Comma, pos: start_pos }); byte_pos += 1; i += 1; }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(PositionedToken { token: Token::Op(CompareOp::Eq), pos: start_pos });
+                byte_pos += 2; i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(PositionedToken { token: Token::Op(CompareOp::Ne), pos: start_pos });
+                byte_pos += 2; i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(PositionedToken { token: Token::Op(CompareOp::Ge), pos: start_pos });
+                byte_pos += 2; i += 2;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(PositionedToken { token: Token::Op(CompareOp::Le), pos: start_pos });
+                byte_pos += 2; i += 2;
+            }
+            '>' => { tokens.push(PositionedToken { token: Token::Op(CompareOp::Gt), pos: start_pos }); byte_pos += 1; i += 1; }
+            '<' => { tokens.push(PositionedToken { token: Token::Op(CompareOp::Lt), pos: start_pos }); byte_pos += 1; i += 1; }
+            '"' | '\'' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                byte_pos += char_byte_len(c);
+                let mut closed = false;
+                while i < chars.len() {
+                    let ch = chars[i];
+                    if ch == quote {
+                        closed = true;
+                        byte_pos += char_byte_len(ch);
+                        i += 1;
+                        break;
+                    }
+                    if ch == '\\' && chars.get(i + 1) == Some(&quote) {
+                        s.push(quote);
+                        byte_pos += char_byte_len(ch) + char_byte_len(quote);
+                        i += 2;
+                        continue;
+                    }
+                    s.push(ch);
+                    byte_pos += char_byte_len(ch);
+                    i += 1;
+                }
+                if !closed {
+                    return Err(FilterParseError { message: "unterminated string literal".to_string(), position: start_pos });
+                }
+                tokens.push(PositionedToken { token: Token::Str(s), pos: start_pos });
+            }
+            _ if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) => {
+                let mut s = String::new();
+                if c == '-' {
+                    s.push(c);
+                    byte_pos += char_byte_len(c);
+                    i += 1;
+                }
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    s.push(chars[i]);
+                    byte_pos += char_byte_len(chars[i]);
+                    i += 1;
+                }
+                let num = s.parse::<f64>().map_err(|_| FilterParseError {
+                    message: format!("invalid number literal '{s}'"),
+                    position: start_pos,
+                })?;
+                tokens.push(PositionedToken { token: Token::Num(num), pos: start_pos });
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let mut s = String::new();
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    s.push(chars[i]);
+                    byte_pos += char_byte_len(chars[i]);
+                    i += 1;
+                }
+                let token = match s.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "CONTAINS" => Token::Contains,
+                    "IN" => Token::In,
+                    _ => Token::Ident(s),
+                };
+                tokens.push(PositionedToken { token, pos: start_pos });
+            }
+            other => {
+                return Err(FilterParseError { message: format!("unexpected character '{other}'"), position: start_pos });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+const VALID_FIELDS: &[&str] = &["node_type", "group_id", "name", "summary", "created_at"];
+
+struct Parser {
+    tokens: Vec<PositionedToken>,
+    pos: usize,
+    end_pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn peek_pos(&self) -> usize {
+        self.tokens.get(self.pos).map(|t| t.pos).unwrap_or(self.end_pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|t| t.token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), FilterParseError> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(FilterParseError { message: format!("expected {expected:?}"), position: self.peek_pos() })
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<FilterExpr, FilterParseError> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, FilterParseError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = FilterExpr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, FilterParseError> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = FilterExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr, FilterParseError> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            let inner = self.parse_unary()?;
+            return Ok(FilterExpr::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterExpr, FilterParseError> {
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let inner = self.parse_expr()?;
+            self.expect(&Token::RParen)?;
+            return Ok(inner);
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_field(&mut self) -> Result<String, FilterParseError> {
+        let pos = self.peek_pos();
+        match self.advance() {
+            Some(Token::Ident(name)) => {
+                if VALID_FIELDS.contains(&name.as_str()) {
+                    Ok(name)
+                } else {
+                    Err(FilterParseError {
+                        message: format!("unknown field '{name}'; expected one of {VALID_FIELDS:?}"),
+                        position: pos,
+                    })
+                }
+            }
+            _ => Err(FilterParseError { message: "expected a field name".to_string(), position: pos }),
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<FilterValue, FilterParseError> {
+        let pos = self.peek_pos();
+        match self.advance() {
+            Some(Token::Str(s)) => Ok(FilterValue::Str(s)),
+            Some(Token::Num(n)) => Ok(FilterValue::Num(n)),
+            _ => Err(FilterParseError { message: "expected a string or number literal".to_string(), position: pos }),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<FilterExpr, FilterParseError> {
+        let field = self.parse_field()?;
+        let op_pos = self.peek_pos();
+
+        match self.peek() {
+            Some(Token::Op(op)) => {
+                let op = *op;
+                self.pos += 1;
+                let value = self.parse_literal()?;
+                Ok(FilterExpr::Compare { field, op, value })
+            }
+            Some(Token::Contains) => {
+                self.pos += 1;
+                match self.parse_literal()? {
+                    FilterValue::Str(value) => Ok(FilterExpr::Contains { field, value }),
+                    FilterValue::Num(_) => Err(FilterParseError {
+                        message: "CONTAINS requires a string literal".to_string(),
+                        position: op_pos,
+                    }),
+                }
+            }
+            Some(Token::In) => {
+                self.pos += 1;
+                self.expect(&Token::LBracket)?;
+                let mut values = vec![self.parse_literal()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.pos += 1;
+                    values.push(self.parse_literal()?);
+                }
+                self.expect(&Token::RBracket)?;
+                Ok(FilterExpr::In { field, values })
+            }
+            _ => Err(FilterParseError {
+                message: "expected one of '==', '!=', '>', '>=', '<', '<=', CONTAINS, IN".to_string(),
+                position: op_pos,
+            }),
+        }
+    }
+}
+
+/// Parses a `filter` expression string into an AST, ready for
+/// [`evaluate`]. Returns a [`FilterParseError`] naming the offending
+/// token's byte position on malformed input (unknown field, unbalanced
+/// parentheses/brackets, missing operator, trailing tokens, ...).
+pub fn parse_filter(input: &str) -> Result<FilterExpr, FilterParseError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0, end_pos: input.len() };
+    let expr = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(FilterParseError {
+            message: "unexpected trailing input".to_string(),
+            position: parser.peek_pos(),
+        });
+    }
+    Ok(expr)
+}
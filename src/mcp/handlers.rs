@@ -1,16 +1,30 @@
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use crate::graph::{KGNode, KGEdge, Episode, EpisodeSource, SearchResult};
-use crate::graph::storage::GraphStorage;
-use crate::embeddings::LocalEmbeddingEngine;
-use crate::search::HybridSearchEngine;
+use crate::graph::{KGNode, KGEdge, Episode, EpisodeSource, SearchResult, ComponentScores};
+use crate::graph::storage::{GraphStorage, RetentionPolicy, AliasKind, SizeTargets};
+use crate::graph::filters::{NodeFilter, EdgeFilter, EpisodeFilter};
+use crate::embeddings::{LocalEmbeddingEngine, cosine_similarity, ChunkerConfig, EmbeddingQueue, TextChunker};
+use crate::search::{HybridSearchEngine, SearchStrategy};
 use crate::memory::MemoryOptimizer;
 use crate::nlp::{EntityExtractor, EntityExtractionConfig, RelationshipExtractor, RelationshipExtractionConfig};
-use crate::indexing::{CodebaseIndexer, IndexingConfig, CodeSearchResult, FileDependency, CodebaseAnalysis, IndexingResult};
+use crate::indexing::{CodebaseIndexer, IndexingConfig, CodeSearchResult, FileDependency, CodebaseAnalysis, IndexingResult, CallHierarchyResult};
+use crate::indexing::{AutoOffsetReset, FileTailSource, StreamIngesterConfig, StreamIngestionManager};
+use crate::indexing::{IndexWatchManager, WatcherConfig};
+use crate::mcp::cursor::PageCursor;
+use crate::mcp::filter_expr::{evaluate, parse_filter, FilterExpr};
+use crate::mcp::transforms::apply_transforms;
+use crate::mcp::errors::ToolRateLimiter;
+use crate::mcp::search_queue::SearchQueue;
+use crate::mcp::workers::{parse_worker_command, WorkerManager};
+use crate::metrics::RecentEventsBuffer;
+use crate::security::api_keys::ApiKeyScope;
 
 /// Output verbosity levels for MCP responses
 #[derive(Debug, Clone, Copy)]
@@ -162,7 +176,36 @@ impl OutputVerbosity {
     }
 }
 
-/// Main handler that routes tool requests to appropriate handlers
+/// Sending half of a per-SSE-session progress channel (see
+/// `mcp::server`'s `sse_sessions`), paired with the MCP `progressToken`
+/// the caller must echo back in every `notifications/progress` message.
+pub type ProgressSink = mpsc::UnboundedSender<Value>;
+
+/// Emits an MCP `notifications/progress` notification on `progress`'s sink,
+/// echoing back its `progressToken` unchanged (callers must be able to
+/// correlate updates with the request that requested them). A no-op when
+/// `progress` is `None` — either the call came in over stdio/`/mcp`, or the
+/// caller didn't ask for progress updates.
+fn emit_progress(progress: &Option<(Value, ProgressSink)>, done: f64, total: f64, message: &str) {
+    if let Some((token, sink)) = progress {
+        let _ = sink.send(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": {
+                "progressToken": token,
+                "progress": done,
+                "total": total,
+                "message": message
+            }
+        }));
+    }
+}
+
+/// Main handler that routes tool requests to appropriate handlers.
+///
+/// `progress`, when present, pairs the caller's MCP `progressToken` with a
+/// sink for `notifications/progress` updates; only the long-running tools
+/// (hybrid search, bulk codebase indexing) currently emit anything on it.
 pub async fn handle_tool_request(
     tool_name: &str,
     params: Value,
@@ -170,19 +213,351 @@ pub async fn handle_tool_request(
     embedding_engine: &Arc<LocalEmbeddingEngine>,
     search_engine: &Arc<HybridSearchEngine>,
     memory_optimizer: &Arc<MemoryOptimizer>,
+    rate_limiter: &Arc<ToolRateLimiter>,
+    worker_manager: &Arc<WorkerManager>,
+    recent_events: &Arc<RecentEventsBuffer>,
+    search_queue: &Arc<SearchQueue>,
+    stream_manager: &Arc<StreamIngestionManager>,
+    watch_manager: &Arc<IndexWatchManager>,
+    progress: Option<(Value, ProgressSink)>,
 ) -> Result<Value> {
     debug!("Handling tool request: {} with params: {}", tool_name, params);
-    
+
+    rate_limiter.try_acquire(tool_name)?;
+
     match tool_name {
-        "mcp_kg-mcp-server_add_memory" => handle_add_memory(params, storage, embedding_engine, memory_optimizer).await,
-        "mcp_kg-mcp-server_search_memory" => handle_search_memory(params, storage, embedding_engine, search_engine).await,
+        // `add_memory`/`search_memory` are the embedding-heavy paths a burst
+        // of callers could thrash the embedding engine with; both must
+        // acquire a `search_queue` permit before running. See
+        // `mcp::search_queue::SearchQueue`.
+        "mcp_kg-mcp-server_add_memory" => {
+            let _ticket = search_queue.acquire().await?;
+            handle_add_memory(params, storage, embedding_engine, memory_optimizer).await
+        }
+        "mcp_kg-mcp-server_search_memory" => {
+            let _ticket = search_queue.acquire().await?;
+            handle_search_memory(params, storage, embedding_engine, search_engine, memory_optimizer, &progress).await
+        }
         "mcp_kg-mcp-server_analyze_patterns" => handle_analyze_patterns(params, storage, search_engine).await,
-        "mcp_kg-mcp-server_manage_graph" => handle_manage_graph(params, storage, embedding_engine).await,
-        "mcp_kg-mcp-server_index_codebase" => handle_index_codebase(params, storage, embedding_engine).await,
+        "mcp_kg-mcp-server_manage_graph" => {
+            handle_manage_graph(params, storage, embedding_engine, memory_optimizer, search_queue).await
+        }
+        "mcp_kg-mcp-server_index_codebase" => handle_index_codebase(params, storage, embedding_engine, watch_manager, &progress).await,
+        "mcp_kg-mcp-server_manage_workers" => handle_manage_workers(params, worker_manager).await,
+        "mcp_kg-mcp-server_manage_api_keys" => handle_manage_api_keys(params, storage).await,
+        "mcp_kg-mcp-server_get_recent_events" => handle_get_recent_events(params, recent_events).await,
+        "mcp_kg-mcp-server_batch" => {
+            let _ticket = search_queue.acquire().await?;
+            handle_batch(params, storage, embedding_engine, search_engine, memory_optimizer, &progress).await
+        }
+        "mcp_kg-mcp-server_manage_ingestion" => {
+            handle_manage_ingestion(params, stream_manager, storage, embedding_engine, search_queue).await
+        }
+        "mcp_kg-mcp-server_admin_metrics" => {
+            handle_admin_metrics(params, storage, embedding_engine, memory_optimizer, search_queue).await
+        }
         _ => Err(anyhow!("Unknown tool: {}", tool_name)),
     }
 }
 
+/// Returns the `recent_events` ring buffer's current snapshot as JSON,
+/// newest last, optionally capped to the last `limit` records.
+async fn handle_get_recent_events(params: Value, recent_events: &Arc<RecentEventsBuffer>) -> Result<Value> {
+    let snapshot = recent_events.snapshot();
+    let limit = params.get("limit").and_then(|v| v.as_u64()).map(|n| n as usize);
+
+    let events: Vec<&crate::metrics::EventRecord> = match limit {
+        Some(limit) if limit < snapshot.len() => snapshot[snapshot.len() - limit..].iter().collect(),
+        _ => snapshot.iter().collect(),
+    };
+
+    Ok(json!({
+        "success": true,
+        "total_found": events.len(),
+        "events": events
+    }))
+}
+
+/// Live health/capacity counters an operator can scrape instead of relying
+/// only on in-process test assertions: graph entity counts, embedding-engine
+/// cache occupancy and which model (if any) is loaded, `SearchQueue`
+/// occupancy/rejection counts, `MemoryOptimizer` cache hit/miss/eviction
+/// stats, and the database file size. Readiness should treat a `None`
+/// `embedding.model` as "not ready yet" rather than an error — the model can
+/// still be downloading/loading.
+///
+/// `params.format` selects `"json"` (default) or `"openmetrics"`, mirroring
+/// `mcp::server`'s HTTP `/metrics` endpoint's two render modes.
+async fn handle_admin_metrics(
+    params: Value,
+    storage: &Arc<GraphStorage>,
+    embedding_engine: &Arc<LocalEmbeddingEngine>,
+    memory_optimizer: &Arc<MemoryOptimizer>,
+    search_queue: &Arc<SearchQueue>,
+) -> Result<Value> {
+    let database = json!({
+        "nodes": storage.count_nodes().await.unwrap_or(0),
+        "edges": storage.count_edges().await.unwrap_or(0),
+        "episodes": storage.count_episodes().await.unwrap_or(0),
+        "file_size_bytes": storage.database_file_size_bytes().await?,
+    });
+
+    // `LocalEmbeddingEngine` has no async task queue to report a literal
+    // depth for; the batch embedding cache's occupancy is the closest
+    // available signal for "is this engine under load".
+    let embedding = match embedding_engine.get_cache_stats().await {
+        Ok(cache_stats) => json!({
+            "model": embedding_engine.current_model().await,
+            "batch_cache_used": cache_stats.batch_cache_used,
+            "batch_cache_capacity": cache_stats.batch_cache_capacity,
+        }),
+        Err(e) => json!({
+            "model": embedding_engine.current_model().await,
+            "error": e.to_string(),
+        }),
+    };
+
+    let search_queue_stats = search_queue.stats();
+
+    let memory = match memory_optimizer.get_memory_stats().await {
+        Ok(stats) => {
+            let cache = &stats.cache_statistics;
+            let hits = cache.l1_hits + cache.l2_hits + cache.l3_hits + cache.embedding_hits + cache.query_hits;
+            let misses = cache.l1_misses + cache.l2_misses + cache.l3_misses + cache.embedding_misses + cache.query_misses;
+            json!({
+                "cache_hits": hits,
+                "cache_misses": misses,
+                "cache_bytes": cache.total_memory_used,
+                "evictions": cache.evictions,
+            })
+        },
+        Err(e) => json!({"error": e.to_string()}),
+    };
+
+    let format = params.get("format").and_then(|v| v.as_str()).unwrap_or("json");
+    match format {
+        "json" => Ok(json!({
+            "success": true,
+            "database": database,
+            "embedding": embedding,
+            "search_queue": search_queue_stats,
+            "memory": memory,
+        })),
+        "openmetrics" => {
+            let mut out = String::new();
+            out.push_str("# HELP kg_nodes_total Total number of knowledge graph nodes.\n");
+            out.push_str("# TYPE kg_nodes_total gauge\n");
+            out.push_str(&format!("kg_nodes_total {}\n", database["nodes"]));
+
+            out.push_str("# HELP kg_edges_total Total number of knowledge graph edges.\n");
+            out.push_str("# TYPE kg_edges_total gauge\n");
+            out.push_str(&format!("kg_edges_total {}\n", database["edges"]));
+
+            out.push_str("# HELP kg_episodes_total Total number of ingested episodes.\n");
+            out.push_str("# TYPE kg_episodes_total gauge\n");
+            out.push_str(&format!("kg_episodes_total {}\n", database["episodes"]));
+
+            out.push_str("# HELP kg_search_queue_running Calls currently executing through the search queue.\n");
+            out.push_str("# TYPE kg_search_queue_running gauge\n");
+            out.push_str(&format!("kg_search_queue_running {}\n", search_queue_stats.running));
+
+            out.push_str("# HELP kg_search_queue_waiting Calls currently queued awaiting a search-queue permit.\n");
+            out.push_str("# TYPE kg_search_queue_waiting gauge\n");
+            out.push_str(&format!("kg_search_queue_waiting {}\n", search_queue_stats.waiting));
+
+            out.push_str("# HELP kg_search_queue_rejected_total Calls shed by the search queue's random-drop admission control.\n");
+            out.push_str("# TYPE kg_search_queue_rejected_total counter\n");
+            out.push_str(&format!("kg_search_queue_rejected_total {}\n", search_queue_stats.rejected_total));
+
+            Ok(json!({
+                "success": true,
+                "format": "openmetrics",
+                "body": out,
+            }))
+        },
+        other => Err(anyhow!("Unknown format: {}. Supported formats: json, openmetrics", other)),
+    }
+}
+
+/// `create`/`list`/`revoke` operations over the scoped API key registry
+/// (see `security::api_keys`). The HTTP/SSE transport additionally enforces
+/// `Admin` scope on this tool itself via `mcp::server::required_scope_for_tool`;
+/// stdio callers (always locally trusted) reach it unconditionally.
+async fn handle_manage_api_keys(
+    params: Value,
+    storage: &Arc<GraphStorage>,
+) -> Result<Value> {
+    let operation = params.get("operation")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: operation"))?;
+
+    match operation {
+        "create" => {
+            let name = params.get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("name required for create operation"))?;
+
+            let scopes: Vec<ApiKeyScope> = params.get("scopes")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(ApiKeyScope::parse)
+                    .collect::<Result<Vec<_>>>())
+                .transpose()?
+                .unwrap_or_else(|| vec![ApiKeyScope::Read]);
+
+            let created = storage.create_api_key(name, &scopes)?;
+            Ok(json!({
+                "success": true,
+                "operation": "create",
+                "key": created
+            }))
+        },
+        "list" => {
+            let keys = storage.list_api_keys()?;
+            Ok(json!({
+                "success": true,
+                "operation": "list",
+                "keys": keys
+            }))
+        },
+        "revoke" => {
+            let id = params.get("id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("id required for revoke operation"))?;
+
+            let revoked = storage.revoke_api_key(id)?;
+            Ok(json!({
+                "success": revoked,
+                "operation": "revoke",
+                "id": id
+            }))
+        },
+        _ => Err(anyhow!("Unknown operation: {}. Supported operations: create, list, revoke", operation)),
+    }
+}
+
+/// `list`/`control` operations over the background worker registry (GC,
+/// embedding warmup, DB health check) — see `mcp::workers`.
+async fn handle_manage_workers(
+    params: Value,
+    worker_manager: &Arc<WorkerManager>,
+) -> Result<Value> {
+    let operation = params.get("operation")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: operation"))?;
+
+    match operation {
+        "list_workers" => {
+            Ok(json!({
+                "success": true,
+                "operation": "list_workers",
+                "workers": worker_manager.status_report().await
+            }))
+        },
+        "control_worker" => {
+            let worker_name = params.get("worker_name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("worker_name required for control_worker operation"))?;
+
+            let command_str = params.get("command")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("command required for control_worker operation"))?;
+
+            let command = parse_worker_command(command_str)?;
+            worker_manager.send_command(worker_name, command).await?;
+
+            Ok(json!({
+                "success": true,
+                "operation": "control_worker",
+                "worker_name": worker_name,
+                "command": command_str
+            }))
+        },
+        _ => Err(anyhow!("Unknown operation: {}. Supported operations: list_workers, control_worker", operation)),
+    }
+}
+
+/// `start`/`stop`/`status` operations over the streaming ingestion registry
+/// (see `indexing::streaming`). `start` currently only supports tailing a
+/// newline-delimited file (`FileTailSource`); other `StreamSource`
+/// implementations aren't yet exposed through this tool.
+async fn handle_manage_ingestion(
+    params: Value,
+    stream_manager: &Arc<StreamIngestionManager>,
+    storage: &Arc<GraphStorage>,
+    embedding_engine: &Arc<LocalEmbeddingEngine>,
+    search_queue: &Arc<SearchQueue>,
+) -> Result<Value> {
+    let operation = params.get("operation")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: operation"))?;
+
+    match operation {
+        "start" => {
+            let stream_id = params.get("stream_id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("stream_id required for start operation"))?;
+
+            let path = params.get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("path required for start operation"))?;
+
+            let auto_offset_reset = match params.get("auto_offset_reset").and_then(|v| v.as_str()) {
+                Some("earliest") => AutoOffsetReset::Earliest,
+                Some("latest") | None => AutoOffsetReset::Latest,
+                Some(other) => return Err(anyhow!("Invalid auto_offset_reset '{}'; expected 'earliest' or 'latest'", other)),
+            };
+
+            let group_id = params.get("group_id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let config = StreamIngesterConfig {
+                stream_id: stream_id.to_string(),
+                auto_offset_reset,
+                poll_interval_ms: 1000,
+                group_id,
+            };
+
+            stream_manager.start(
+                config,
+                Arc::new(FileTailSource::new(path)),
+                Arc::clone(storage),
+                Arc::clone(embedding_engine),
+                Arc::clone(search_queue),
+            ).await?;
+
+            Ok(json!({
+                "success": true,
+                "operation": "start",
+                "stream_id": stream_id
+            }))
+        },
+        "stop" => {
+            let stream_id = params.get("stream_id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("stream_id required for stop operation"))?;
+
+            stream_manager.stop(stream_id).await?;
+
+            Ok(json!({
+                "success": true,
+                "operation": "stop",
+                "stream_id": stream_id
+            }))
+        },
+        "status" => {
+            Ok(json!({
+                "success": true,
+                "operation": "status",
+                "streams": stream_manager.status().await
+            }))
+        },
+        _ => Err(anyhow!("Unknown operation: {}. Supported operations: start, stop, status", operation)),
+    }
+}
+
 /// Enhanced add_memory handler with entity extraction and relationship building
 async fn handle_add_memory(
     params: Value,
@@ -190,6 +565,46 @@ async fn handle_add_memory(
     embedding_engine: &Arc<LocalEmbeddingEngine>,
     memory_optimizer: &Arc<MemoryOptimizer>,
 ) -> Result<Value> {
+    let (episode, response) = prepare_episode(&params, None, storage, embedding_engine).await?;
+
+    // Store the episode with linked entities and edges
+    storage.insert_episode(&episode)
+        .map_err(|e| anyhow!("Failed to store episode: {}", e))?;
+
+    // Cache the episode for faster retrieval
+    if let Err(e) = memory_optimizer.cache_episode(episode.clone()).await {
+        warn!("Failed to cache episode: {}", e);
+    }
+
+    info!("Successfully stored episode '{}'", episode.name);
+
+    Ok(response)
+}
+
+/// Builds an episode from `add_memory` params — extracting entities and
+/// relationships and storing their nodes/edges — without inserting the
+/// episode itself or caching it. Factored out of `handle_add_memory` so
+/// `handle_batch` can run this per item (optionally against a precomputed
+/// `embedding`, see its own doc comment) and defer the episode insert to a
+/// single `GraphStorage::insert_episodes` transaction across the whole
+/// add_memory group of a batch.
+///
+/// Within a single call, newly-created node embeddings and newly-created
+/// edge embeddings are each generated with one `encode_texts` batch call
+/// rather than one `encode_text` call per entity/relationship, so a single
+/// `add_memory` does at most 3 model invocations (episode + nodes batch +
+/// edges batch) instead of N+M+1. Ingesting many episodes in one request is
+/// already handled by the `batch` tool (see its doc comment), which batches
+/// episode embeddings across items and dedupes entities against storage the
+/// same way this function does for a lone episode — `add_memory` itself
+/// stays single-episode rather than growing a second, competing array
+/// parameter for the same job.
+async fn prepare_episode(
+    params: &Value,
+    precomputed_embedding: Option<Vec<f32>>,
+    storage: &Arc<GraphStorage>,
+    embedding_engine: &Arc<LocalEmbeddingEngine>,
+) -> Result<(Episode, Value)> {
     let name = params.get("name")
         .and_then(|v| v.as_str())
         .ok_or_else(|| anyhow!("Missing required parameter: name"))?;
@@ -197,7 +612,15 @@ async fn handle_add_memory(
     let episode_body = params.get("episode_body")
         .and_then(|v| v.as_str())
         .ok_or_else(|| anyhow!("Missing required parameter: episode_body"))?;
-    
+
+    // Run the declarative `transforms` pipeline, if given, before anything
+    // downstream (embedding, entity/relationship extraction) sees the body.
+    let (episode_body, transforms_fired) = match params.get("transforms").and_then(|v| v.as_array()) {
+        Some(steps) => apply_transforms(episode_body, steps)?,
+        None => (episode_body.to_string(), Vec::new()),
+    };
+    let episode_body = episode_body.as_str();
+
     let group_id = params.get("group_id")
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
@@ -224,6 +647,45 @@ async fn handle_add_memory(
         _ => EpisodeSource::Text,
     };
 
+    // Which extraction backend to run entity/relationship extraction
+    // through. "rules" (default) is the pattern-matching extractor with no
+    // model handle, same as before this was configurable. "embedding" wires
+    // `embedding_engine` through instead of `None`, which activates the
+    // embedding-backed confidence scoring already implemented in
+    // `RelationshipExtractor::entities_are_semantically_related` (previously
+    // dead code here, since it was only ever called with `None`). "llm"
+    // isn't available in this tree: there's no chat-completion client, only
+    // an OpenAI-compatible *embeddings* endpoint (`embeddings::provider`).
+    let extractor_mode = params.get("extractor")
+        .and_then(|v| v.as_str())
+        .unwrap_or("rules");
+    let extractor_engine = match extractor_mode {
+        "rules" => None,
+        "embedding" => Some(embedding_engine.clone()),
+        "llm" => return Err(anyhow!(
+            "extractor=\"llm\" is not available in this build: no LLM chat-completion backend is configured. Use \"rules\" or \"embedding\" instead."
+        )),
+        other => return Err(anyhow!(
+            "Unknown extractor '{}': expected one of \"rules\", \"embedding\", \"llm\"", other
+        )),
+    };
+
+    let mut entity_config = EntityExtractionConfig::default();
+    if let Some(min_confidence) = params.get("min_confidence").and_then(|v| v.as_f64()) {
+        entity_config.min_confidence = min_confidence as f32;
+    }
+    if let Some(max_entities) = params.get("max_entities").and_then(|v| v.as_u64()) {
+        entity_config.max_entities_per_text = max_entities as usize;
+    }
+    let entity_type_allowlist: Option<Vec<String>> = params.get("entity_types")
+        .and_then(|v| v.as_array())
+        .map(|types| types.iter().filter_map(|v| v.as_str().map(String::from)).collect());
+
+    let mut relationship_config = RelationshipExtractionConfig::default();
+    if let Some(min_confidence) = params.get("min_confidence").and_then(|v| v.as_f64()) {
+        relationship_config.min_confidence = min_confidence as f32;
+    }
+
     // Create episode
     let mut episode = Episode {
         uuid,
@@ -239,8 +701,17 @@ async fn handle_add_memory(
         metadata: std::collections::HashMap::new(),
     };
 
-    // Generate embedding for the episode content
-    match embedding_engine.encode_text(&episode.content).await {
+    if !transforms_fired.is_empty() {
+        episode.metadata.insert("transforms_applied".to_string(), json!(transforms_fired));
+    }
+
+    // Generate embedding for the episode content, unless the caller (the
+    // `batch` tool) already computed it as part of a batched `encode_texts` pass.
+    let embedding_result = match precomputed_embedding {
+        Some(embedding) => Ok(embedding),
+        None => embedding_engine.encode_text(&episode.content).await,
+    };
+    match embedding_result {
         Ok(embedding) => {
             episode.embedding = Some(embedding.clone());
             // Store embedding in database
@@ -253,30 +724,72 @@ async fn handle_add_memory(
         }
     }
 
+    // Bodies beyond one chunk's worth lose quality embedded as a single
+    // vector, so split them with `TextChunker` and embed each chunk on top
+    // of (not instead of) the whole-episode embedding above: the episode
+    // vector still drives `search_embeddings(.., "episode", ..)` for
+    // short-episode callers, while the chunk vectors let long-document
+    // search roll up fine-grained hits to this episode via
+    // `get_episode_for_chunk`.
+    let chunker_config = ChunkerConfig::default();
+    if EmbeddingQueue::estimate_tokens(&episode.content) > chunker_config.max_tokens {
+        let chunks = TextChunker::new(chunker_config).chunk_document(&episode.content, None);
+        if chunks.len() > 1 {
+            let chunk_texts: Vec<String> = chunks.iter().map(|c| c.text.clone()).collect();
+            match embedding_engine.encode_texts(&chunk_texts).await {
+                Ok(chunk_embeddings) => {
+                    let rows: Vec<(usize, usize, usize, String, Vec<f32>)> = chunks
+                        .into_iter()
+                        .zip(chunk_embeddings)
+                        .enumerate()
+                        .map(|(i, (chunk, embedding))| {
+                            (i, chunk.byte_range.start, chunk.byte_range.end, chunk.text, embedding)
+                        })
+                        .collect();
+                    if let Err(e) = storage.store_episode_chunks(episode.uuid, &rows) {
+                        warn!("Failed to store episode chunks: {}", e);
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to embed episode chunks: {}", e);
+                }
+            }
+        }
+    }
+
     // Initialize entity and relationship extractors
-    let entity_extractor = EntityExtractor::new(EntityExtractionConfig::default(), None)
+    let entity_extractor = EntityExtractor::new(entity_config, extractor_engine.clone())
         .map_err(|e| anyhow::anyhow!("Failed to create entity extractor: {}", e))?;
-    
-    let relationship_extractor = RelationshipExtractor::new(RelationshipExtractionConfig::default(), None)
+
+    let relationship_extractor = RelationshipExtractor::new(relationship_config, extractor_engine)
         .map_err(|e| anyhow::anyhow!("Failed to create relationship extractor: {}", e))?;
 
     // Extract entities from episode content
-    let extracted_entities = entity_extractor
+    let mut extracted_entities = entity_extractor
         .extract_entities(&episode.content, &episode.source, &episode.name)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to extract entities: {}", e))?;
 
+    if let Some(allowlist) = &entity_type_allowlist {
+        extracted_entities.retain(|entity| allowlist.iter().any(|allowed| allowed == &entity.entity_type));
+    }
+
     info!("Extracted {} entities from episode '{}'", extracted_entities.len(), episode.name);
 
     // Convert extracted entities to nodes and store them
     let mut created_nodes = Vec::new();
     let mut entity_name_to_uuid = HashMap::new();
+    let mut node_confidences: HashMap<uuid::Uuid, f32> = HashMap::new();
+    // (node uuid, embedding text) for every newly-created node, encoded in
+    // one `encode_texts` batch below instead of one `encode_text` call per
+    // entity.
+    let mut pending_node_embeddings: Vec<(uuid::Uuid, String)> = Vec::new();
 
     for extracted_entity in &extracted_entities {
         // Check if entity already exists (by name and type)
         let existing_nodes = storage.search_nodes_by_text(&extracted_entity.name, None, 10)
             .unwrap_or_default();
-        
+
         let existing_node = existing_nodes.iter()
             .find(|node| node.name == extracted_entity.name && node.node_type == extracted_entity.entity_type);
 
@@ -287,29 +800,39 @@ async fn handle_add_memory(
             // Create new node
             let node = entity_extractor.entity_to_node(extracted_entity, group_id.clone());
             let node_uuid = node.uuid;
-            
+
             // Store node in database
             if let Err(e) = storage.insert_node(&node) {
                 warn!("Failed to store node '{}': {}", node.name, e);
                 continue;
             }
 
-            // Generate and store embedding for the node
             let node_content = format!("{} {} {}", node.name, node.node_type, node.summary);
-            if let Ok(node_embedding) = embedding_engine.encode_text(&node_content).await {
-                if let Err(e) = storage.store_embedding(node.uuid, "node", &node_embedding) {
-                    warn!("Failed to store node embedding for '{}': {}", node.name, e);
-                }
-            }
+            pending_node_embeddings.push((node.uuid, node_content));
 
             created_nodes.push(node);
             node_uuid
         };
 
         entity_name_to_uuid.insert(extracted_entity.name.clone(), node_uuid);
+        node_confidences.insert(node_uuid, extracted_entity.confidence);
         episode.add_entity(node_uuid);
     }
 
+    if !pending_node_embeddings.is_empty() {
+        let texts: Vec<String> = pending_node_embeddings.iter().map(|(_, text)| text.clone()).collect();
+        match embedding_engine.encode_texts(&texts).await {
+            Ok(node_embeddings) => {
+                for ((node_uuid, _), node_embedding) in pending_node_embeddings.iter().zip(node_embeddings) {
+                    if let Err(e) = storage.store_embedding(*node_uuid, "node", &node_embedding) {
+                        warn!("Failed to store node embedding for {}: {}", node_uuid, e);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to batch-generate node embeddings: {}", e),
+        }
+    }
+
     // Extract relationships between entities
     let extracted_relationships = relationship_extractor
         .extract_relationships_between_entities(&extracted_entities, &episode.content, &episode.name)
@@ -320,6 +843,9 @@ async fn handle_add_memory(
 
     // Convert extracted relationships to edges and store them
     let mut created_edges = Vec::new();
+    // Same batching as `pending_node_embeddings` above, for edges.
+    let mut pending_edge_embeddings: Vec<(uuid::Uuid, String)> = Vec::new();
+    let mut edge_confidences: HashMap<uuid::Uuid, f32> = HashMap::new();
 
     for extracted_relationship in &extracted_relationships {
         // Get UUIDs for source and target entities
@@ -330,7 +856,7 @@ async fn handle_add_memory(
             // Check if relationship already exists
             let existing_edges = storage.get_edges_between_nodes(source_uuid, target_uuid)
                 .unwrap_or_default();
-            
+
             let existing_edge = existing_edges.iter()
                 .find(|edge| edge.relation_type == extracted_relationship.relation_type);
 
@@ -350,38 +876,39 @@ async fn handle_add_memory(
                     continue;
                 }
 
-                // Generate and store embedding for the edge
                 let edge_content = format!("{} {} {}", edge.relation_type, edge.summary, extracted_relationship.context);
-                if let Ok(edge_embedding) = embedding_engine.encode_text(&edge_content).await {
-                    if let Err(e) = storage.store_embedding(edge.uuid, "edge", &edge_embedding) {
-                        warn!("Failed to store edge embedding for '{}': {}", edge.relation_type, e);
-                    }
-                }
+                pending_edge_embeddings.push((edge.uuid, edge_content));
+                edge_confidences.insert(edge_uuid, extracted_relationship.confidence);
 
                 created_edges.push(edge);
                 episode.add_edge(edge_uuid);
             }
         } else {
-            warn!("Could not find UUIDs for relationship: {} -> {}", 
+            warn!("Could not find UUIDs for relationship: {} -> {}",
                   extracted_relationship.source_entity, extracted_relationship.target_entity);
         }
     }
 
-    // Store the episode with linked entities and edges
-    storage.insert_episode(&episode)
-        .map_err(|e| anyhow!("Failed to store episode: {}", e))?;
-
-    // Cache the episode for faster retrieval
-    if let Err(e) = memory_optimizer.cache_episode(episode.clone()).await {
-        warn!("Failed to cache episode: {}", e);
+    if !pending_edge_embeddings.is_empty() {
+        let texts: Vec<String> = pending_edge_embeddings.iter().map(|(_, text)| text.clone()).collect();
+        match embedding_engine.encode_texts(&texts).await {
+            Ok(edge_embeddings) => {
+                for ((edge_uuid, _), edge_embedding) in pending_edge_embeddings.iter().zip(edge_embeddings) {
+                    if let Err(e) = storage.store_embedding(*edge_uuid, "edge", &edge_embedding) {
+                        warn!("Failed to store edge embedding for {}: {}", edge_uuid, e);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to batch-generate edge embeddings: {}", e),
+        }
     }
 
-    info!("Successfully stored episode '{}' with {} entities and {} relationships", 
+    info!("Prepared episode '{}' with {} entities and {} relationships",
           episode.name, created_nodes.len(), created_edges.len());
 
-    let verbosity = OutputVerbosity::from_params(&params);
-    
-    let mut response = match verbosity {
+    let verbosity = OutputVerbosity::from_params(params);
+
+    let response = match verbosity {
         OutputVerbosity::Summary => json!({
             "success": true,
             "episode_id": episode.uuid,
@@ -401,89 +928,418 @@ async fn handle_add_memory(
             "episode_id": episode.uuid,
             "group_id": episode.group_id,
             "name": episode.name,
+            "extractor": extractor_mode,
             "entities_created": created_nodes.len(),
             "relationships_created": created_edges.len(),
             "entities": created_nodes.iter().map(|n| json!({
                 "uuid": n.uuid,
                 "name": n.name,
-                "type": n.node_type
+                "type": n.node_type,
+                "confidence": node_confidences.get(&n.uuid).copied().unwrap_or(0.0)
             })).collect::<Vec<_>>(),
             "relationships": created_edges.iter().map(|e| json!({
                 "uuid": e.uuid,
                 "type": e.relation_type,
-                "summary": e.summary
-            })).collect::<Vec<_>>()
+                "summary": e.summary,
+                "confidence": edge_confidences.get(&e.uuid).copied().unwrap_or(0.0)
+            })).collect::<Vec<_>>(),
+            "transforms_applied": episode.metadata.get("transforms_applied").cloned().unwrap_or_else(|| json!([]))
         })
     };
 
-    Ok(response)
+    Ok((episode, response))
 }
 
-/// Handle comprehensive search operations with batch support
-async fn handle_search_memory(
+/// Handle the `batch` tool: run a list of tagged `add_memory`/`search_memory`
+/// sub-operations and return one tagged result per item, preserving order.
+/// One bad item only fails that item, unless `atomic` is set — then a
+/// failure anywhere in the `add_memory` group rolls back every `add_memory`
+/// item in the batch (`search_memory` items have nothing to roll back and
+/// are unaffected either way).
+///
+/// `add_memory` items still extract and store entities/relationships per
+/// item — that's CPU-bound text parsing, not what's slow here — but their
+/// embeddings are computed in a single `LocalEmbeddingEngine::encode_texts`
+/// call and their episodes go through one `GraphStorage::insert_episodes`
+/// transaction (or, when not atomic, individual `insert_episode` calls so
+/// one write failure doesn't sink the rest), instead of the per-episode
+/// embed-then-insert round trip a lone `add_memory` call does.
+async fn handle_batch(
     params: Value,
     storage: &Arc<GraphStorage>,
     embedding_engine: &Arc<LocalEmbeddingEngine>,
     search_engine: &Arc<HybridSearchEngine>,
+    memory_optimizer: &Arc<MemoryOptimizer>,
+    progress: &Option<(Value, ProgressSink)>,
 ) -> Result<Value> {
-    let operation = params.get("operation")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("Missing required parameter: operation"))?;
-    
-    let max_results = params.get("max_results")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(10) as usize;
-    
-    let verbosity = OutputVerbosity::from_params(&params);
-    
-    match operation {
-        "nodes" => {
-            let query = params.get("query")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Query required for nodes operation"))?;
-            
-            let entity_filter = params.get("entity_filter")
-                .and_then(|v| v.as_str());
-            
-            let group_ids = params.get("group_ids")
-                .and_then(|v| v.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>());
+    let operations = params.get("operations")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .ok_or_else(|| anyhow!("Missing required parameter: operations"))?;
 
-            // Perform hybrid search for nodes
-            let search_result = search_engine.search(query, max_results * 2).await
-                .map_err(|e| anyhow!("Search failed: {}", e))?;
-            
-            let mut filtered_nodes = search_result.nodes;
-            
-            // Apply entity type filter if specified
-            if let Some(entity_type) = entity_filter {
-                filtered_nodes.retain(|node| node.node_type == entity_type);
-            }
-            
-            // Apply group filter if specified
-            if let Some(ref groups) = group_ids {
-                filtered_nodes.retain(|node| {
-                    node.group_id.as_ref().map_or(false, |gid| groups.contains(gid))
-                });
-            }
+    let atomic = params.get("atomic").and_then(|v| v.as_bool()).unwrap_or(false);
+    let total = operations.len();
+    let mut results: Vec<Value> = vec![Value::Null; total];
+
+    emit_progress(progress, 0.0, 1.0, &format!("Starting batch of {} operations", total));
+
+    // Gather the add_memory items' bodies up front so their embeddings can
+    // be computed in one `encode_texts` pass instead of one `encode_text`
+    // call per episode.
+    let add_memory_indices: Vec<usize> = operations.iter().enumerate()
+        .filter(|(_, op)| op.get("type").and_then(|v| v.as_str()) == Some("add_memory"))
+        .map(|(i, _)| i)
+        .collect();
+
+    let add_memory_bodies: Vec<String> = add_memory_indices.iter()
+        .map(|&i| operations[i].get("episode_body").and_then(|v| v.as_str()).unwrap_or("").to_string())
+        .collect();
+
+    let embeddings = if add_memory_bodies.is_empty() {
+        Vec::new()
+    } else {
+        embedding_engine.encode_texts(&add_memory_bodies).await
+            .map_err(|e| anyhow!("Failed to batch-generate embeddings: {}", e))?
+    };
+
+    // Extract entities/relationships and store their nodes/edges for every
+    // add_memory item; none of them are inserted as episodes yet, so
+    // rolling back under `atomic` just means never calling insert_episode(s).
+    let mut prepared: Vec<(usize, Episode, Value)> = Vec::new();
+    let mut prepare_errors: Vec<(usize, String)> = Vec::new();
+    for (&op_index, embedding) in add_memory_indices.iter().zip(embeddings.into_iter()) {
+        match prepare_episode(&operations[op_index], Some(embedding), storage, embedding_engine).await {
+            Ok((episode, response)) => prepared.push((op_index, episode, response)),
+            Err(e) => prepare_errors.push((op_index, e.to_string())),
+        }
+    }
+
+    emit_progress(progress, 0.5, 1.0, "Storing add_memory items");
+
+    if atomic && !prepare_errors.is_empty() {
+        for (op_index, message) in &prepare_errors {
+            results[*op_index] = json!({"status": "error", "error": message});
+        }
+        for (op_index, _, _) in &prepared {
+            results[*op_index] = json!({
+                "status": "error",
+                "error": "Rolled back: another item in this atomic batch failed to prepare"
+            });
+        }
+    } else if atomic {
+        let episodes: Vec<Episode> = prepared.iter().map(|(_, episode, _)| episode.clone()).collect();
+        match storage.insert_episodes(&episodes) {
+            Ok(()) => {
+                for (op_index, episode, response) in &prepared {
+                    if let Err(e) = memory_optimizer.cache_episode(episode.clone()).await {
+                        warn!("Failed to cache episode: {}", e);
+                    }
+                    results[*op_index] = tag_batch_success(response.clone());
+                }
+            }
+            Err(e) => {
+                let message = format!("Batch transaction failed, rolled back: {}", e);
+                for (op_index, _, _) in &prepared {
+                    results[*op_index] = json!({"status": "error", "error": message.clone()});
+                }
+            }
+        }
+    } else {
+        for (op_index, message) in &prepare_errors {
+            results[*op_index] = json!({"status": "error", "error": message});
+        }
+        for (op_index, episode, response) in &prepared {
+            match storage.insert_episode(episode) {
+                Ok(()) => {
+                    if let Err(e) = memory_optimizer.cache_episode(episode.clone()).await {
+                        warn!("Failed to cache episode: {}", e);
+                    }
+                    results[*op_index] = tag_batch_success(response.clone());
+                }
+                Err(e) => {
+                    results[*op_index] = json!({
+                        "status": "error",
+                        "error": format!("Failed to store episode: {}", e)
+                    });
+                }
+            }
+        }
+    }
+
+    // search_memory items run independently of the add_memory group above
+    // and never participate in `atomic` rollback — they have nothing to
+    // roll back.
+    for (i, op) in operations.iter().enumerate() {
+        if !results[i].is_null() {
+            continue;
+        }
+        let op_type = op.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        results[i] = match op_type {
+            "search_memory" => {
+                let operation = op.get("operation").and_then(|v| v.as_str()).unwrap_or("");
+                let max_results = op.get("max_results").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+                let verbosity = OutputVerbosity::from_params(op);
+                match handle_search_memory_operation(
+                    operation, op, max_results, verbosity, storage, embedding_engine, search_engine, memory_optimizer,
+                ).await {
+                    Ok(value) => tag_batch_success(value),
+                    Err(e) => json!({"status": "error", "error": e.to_string()}),
+                }
+            }
+            other => json!({"status": "error", "error": format!("Unknown batch operation type: '{}'", other)}),
+        };
+    }
+
+    emit_progress(progress, 1.0, 1.0, &format!("Batch of {} operations complete", total));
+
+    Ok(json!({ "results": results }))
+}
+
+/// Tags a successful sub-operation result with `"status": "success"` so
+/// `handle_batch`'s per-item results are uniformly taggable as
+/// `success`/`error` regardless of which handler produced them.
+fn tag_batch_success(mut value: Value) -> Value {
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("status".to_string(), json!("success"));
+    }
+    value
+}
+
+/// Resolves a `cursor` parameter (if present) against the expected
+/// `operation`, returning the original query parameters to replay and how
+/// many of that replay's results have already been returned. Absent a
+/// `cursor`, returns `params` unchanged with an offset of `0`, so a first
+/// call for a query pages identically to before cursor support existed.
+fn resolve_cursor(params: &Value, operation: &str) -> Result<(Value, usize)> {
+    match params.get("cursor").and_then(|v| v.as_str()) {
+        Some(token) => {
+            let cursor = PageCursor::decode(token).map_err(|e| anyhow!("Invalid cursor: {}", e))?;
+            if cursor.operation != operation {
+                return Err(anyhow!(
+                    "Cursor was issued for the '{}' operation, not '{}'",
+                    cursor.operation, operation
+                ));
+            }
+            Ok((cursor.params, cursor.offset))
+        }
+        None => Ok((params.clone(), 0)),
+    }
+}
+
+/// Handle comprehensive search operations with batch support
+async fn handle_search_memory(
+    params: Value,
+    storage: &Arc<GraphStorage>,
+    embedding_engine: &Arc<LocalEmbeddingEngine>,
+    search_engine: &Arc<HybridSearchEngine>,
+    memory_optimizer: &Arc<MemoryOptimizer>,
+    progress: &Option<(Value, ProgressSink)>,
+) -> Result<Value> {
+    let operation = params.get("operation")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Missing required parameter: operation"))?;
+
+    let max_results = params.get("max_results")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(10) as usize;
+
+    let verbosity = OutputVerbosity::from_params(&params);
+
+    emit_progress(progress, 0.0, 1.0, &format!("Starting '{}' search", operation));
+    let result = handle_search_memory_operation(
+        operation, &params, max_results, verbosity, storage, embedding_engine, search_engine, memory_optimizer,
+    ).await;
+    emit_progress(progress, 1.0, 1.0, &format!("'{}' search complete", operation));
+    result
+}
+
+/// Cosine-similarity floor `get_cached_search_similar` requires before a
+/// near-duplicate query's cached results can stand in for a fresh search of
+/// `nodes` - high enough that a reused result set still reads as an answer
+/// to the query that asked for it.
+const SEARCH_CACHE_SIMILARITY_THRESHOLD: f32 = 0.95;
+
+async fn handle_search_memory_operation(
+    operation: &str,
+    params: &Value,
+    max_results: usize,
+    verbosity: OutputVerbosity,
+    storage: &Arc<GraphStorage>,
+    embedding_engine: &Arc<LocalEmbeddingEngine>,
+    search_engine: &Arc<HybridSearchEngine>,
+    memory_optimizer: &Arc<MemoryOptimizer>,
+) -> Result<Value> {
+    match operation {
+        "nodes" => {
+            let (params, offset) = resolve_cursor(params, "nodes")?;
+            let params = &params;
+            let query = params.get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Query required for nodes operation"))?;
             
-            // Limit results
-            filtered_nodes.truncate(max_results);
+            let entity_filter = params.get("entity_filter")
+                .and_then(|v| v.as_str());
             
-            let results: Vec<Value> = filtered_nodes.into_iter().map(|node| {
-                verbosity.format_node(&node)
+            let group_ids = params.get("group_ids")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>());
+
+            // Opt-in diverse-results mode: re-rank with Maximal Marginal
+            // Relevance instead of pure relevance so near-duplicate hits
+            // don't crowd out other graph regions.
+            let diversify = params.get("diversify")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let mmr_lambda = params.get("mmr_lambda")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.5) as f32;
+
+            // Overfetch enough candidates to cover every already-seen
+            // page plus this one before filters narrow the set.
+            let fetch_limit = (offset + max_results) * 2;
+
+            // Pluggable retrieval path: pins the query to a single list
+            // (semantic/keyword) or explicit RRF-fused hybrid, overriding
+            // this engine's configured default fusion algorithm. Absent,
+            // this operation keeps its prior diversify/plain-search
+            // behavior unchanged.
+            let search_strategy = params.get("search_strategy")
+                .and_then(|v| v.as_str())
+                .map(|s| match s {
+                    "semantic" => Ok(SearchStrategy::Semantic),
+                    "keyword" => Ok(SearchStrategy::Keyword),
+                    "hybrid" => Ok(SearchStrategy::Hybrid),
+                    other => Err(anyhow!("Unknown search_strategy: {}. Supported: semantic, keyword, hybrid", other)),
+                })
+                .transpose()?;
+            let rrf_k = params.get("rrf_k").and_then(|v| v.as_f64()).map(|v| v as f32);
+            // `semantic_ratio` is a single-knob alternative to separately
+            // tuning `text_weight`/`vector_weight` (0.0 = keyword only,
+            // 1.0 = semantic only); it only applies to the default hybrid
+            // path below, not `search_strategy`/`diversify`, which already
+            // pin down their own retrieval behavior.
+            let semantic_ratio = params.get("semantic_ratio").and_then(|v| v.as_f64()).map(|v| v as f32);
+            let (text_weight, vector_weight) = match semantic_ratio {
+                Some(ratio) => {
+                    let (tw, vw) = weights_from_semantic_ratio(ratio);
+                    (Some(tw), Some(vw))
+                }
+                None => (
+                    params.get("text_weight").and_then(|v| v.as_f64()).map(|v| v as f32),
+                    params.get("vector_weight").and_then(|v| v.as_f64()).map(|v| v as f32),
+                ),
+            };
+
+            // Perform hybrid search for nodes. The plain (no strategy/
+            // diversify/semantic_ratio override), first-page path is the one
+            // `query_cache` covers: probe it for an exact or near-duplicate
+            // hit before falling through to a fresh search, and seed it with
+            // whatever a fresh search finds so the next near-duplicate query
+            // doesn't have to search again.
+            let search_result = if let Some(strategy) = search_strategy {
+                search_engine.search_with_strategy(query, strategy, fetch_limit, rrf_k, text_weight, vector_weight).await
+                    .map_err(|e| anyhow!("Search failed: {}", e))?
+            } else if diversify {
+                search_engine.search_with_mmr(query, fetch_limit, mmr_lambda).await
+                    .map_err(|e| anyhow!("Search failed: {}", e))?
+            } else if semantic_ratio.is_some() {
+                search_engine.search_with_strategy(query, SearchStrategy::Hybrid, fetch_limit, rrf_k, text_weight, vector_weight).await
+                    .map_err(|e| anyhow!("Search failed: {}", e))?
+            } else if offset == 0 {
+                let query_embedding = embedding_engine.encode_text(query).await.ok();
+                let cached = memory_optimizer
+                    .get_cached_search_similar(query, query_embedding.as_deref(), SEARCH_CACHE_SIMILARITY_THRESHOLD)
+                    .await
+                    .unwrap_or(None);
+                match cached {
+                    Some(entry) => {
+                        let mut result = SearchResult::new();
+                        for (node, score) in entry.results {
+                            result.add_node(node, score);
+                        }
+                        result
+                    }
+                    None => {
+                        let result = search_engine.search(query, fetch_limit).await
+                            .map_err(|e| anyhow!("Search failed: {}", e))?;
+                        let scored_nodes: Vec<(KGNode, f32)> = result.nodes.iter()
+                            .map(|node| (node.clone(), result.scores.get(&node.uuid).copied().unwrap_or(0.0)))
+                            .collect();
+                        if let Err(e) = memory_optimizer
+                            .cache_search_results(query.to_string(), scored_nodes, Vec::new(), query_embedding)
+                            .await
+                        {
+                            warn!("Failed to cache search results: {}", e);
+                        }
+                        result
+                    }
+                }
+            } else {
+                search_engine.search(query, fetch_limit).await
+                    .map_err(|e| anyhow!("Search failed: {}", e))?
+            };
+
+            let component_scores = search_result.component_scores;
+            let fused_scores = search_result.scores;
+            let mut filtered_nodes = search_result.nodes;
+
+            // Apply entity type filter if specified
+            if let Some(entity_type) = entity_filter {
+                filtered_nodes.retain(|node| node.node_type == entity_type);
+            }
+
+            // Apply group filter if specified
+            if let Some(ref groups) = group_ids {
+                filtered_nodes.retain(|node| {
+                    node.group_id.as_ref().map_or(false, |gid| groups.contains(gid))
+                });
+            }
+
+            // Page this result: skip what earlier pages already returned,
+            // then take this page's worth.
+            let total_after_filters = filtered_nodes.len();
+            let page_nodes: Vec<KGNode> = filtered_nodes.into_iter().skip(offset).take(max_results).collect();
+            let next_cursor = (total_after_filters > offset + page_nodes.len())
+                .then(|| PageCursor::new("nodes", params.clone(), offset + page_nodes.len()).encode());
+
+            let results: Vec<Value> = page_nodes.into_iter().map(|node| {
+                let mut formatted = verbosity.format_node(&node);
+                // Surface the keyword/semantic/fused score breakdown in
+                // full verbosity so callers can see why a result ranked
+                // where it did.
+                if matches!(verbosity, OutputVerbosity::Full) {
+                    if let Some(scores) = component_scores.get(&node.uuid) {
+                        if let Some(obj) = formatted.as_object_mut() {
+                            obj.insert("score_details".to_string(), json!({
+                                "text_rank": scores.text_rank,
+                                "vector_rank": scores.vector_rank,
+                                "lexical_score": scores.lexical,
+                                "semantic_score": scores.semantic,
+                                "fused_score": fused_scores.get(&node.uuid).copied().unwrap_or(0.0)
+                            }));
+                        }
+                    }
+                }
+                formatted
             }).collect();
-            
+
             let additional_fields = json!({
                 "entity_filter": entity_filter,
-                "group_ids": group_ids
+                "group_ids": group_ids,
+                "search_strategy": search_strategy,
+                "semantic_ratio": semantic_ratio
             });
             
             let mut response = verbosity.format_response_metadata("nodes", Some(query), results.len(), Some(additional_fields));
             response["results"] = json!(results);
+            if let Some(token) = next_cursor {
+                response["next_cursor"] = json!(token);
+            }
             Ok(response)
         },
         "facts" => {
+            let (params, offset) = resolve_cursor(params, "facts")?;
+            let params = &params;
             let query = params.get("query")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| anyhow!("Query required for facts operation"))?;
@@ -492,58 +1348,188 @@ async fn handle_search_memory(
                 .and_then(|v| v.as_array())
                 .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>());
 
-            // Search for edges (facts/relationships) using text search
-            let all_edges = storage.search_edges_by_text(query, max_results * 2)
+            let semantic_ratio = params.get("semantic_ratio")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(0.5);
+            let (text_weight, vector_weight) = weights_from_semantic_ratio(semantic_ratio);
+
+            // Keyword leg: text search over edges, as before.
+            let fetch_limit = (offset + max_results) * 2;
+            let keyword_edges = storage.search_edges_by_text(query, fetch_limit)
                 .map_err(|e| anyhow!("Failed to search edges: {}", e))?;
-            
-            let mut filtered_edges = all_edges;
-            
+
+            // Semantic leg: encode the query once, look up the nearest
+            // stored edge embeddings, then fuse both legs with weighted
+            // reciprocal rank fusion so `facts` gets the same hybrid
+            // treatment as `nodes` rather than keyword-only ranking.
+            let semantic_edges = if vector_weight > 0.0 {
+                let query_embedding = embedding_engine.encode_text(query).await
+                    .map_err(|e| anyhow!("Failed to generate query embedding: {}", e))?;
+                storage.search_embeddings(&query_embedding, "edge", fetch_limit)?
+                    .into_iter()
+                    .filter_map(|(uuid, score)| storage.get_edge(uuid).ok().flatten().map(|edge| (edge, score)))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let fused = fuse_weighted_rrf(keyword_edges, semantic_edges, text_weight, vector_weight, FACTS_RRF_K, |edge| edge.uuid);
+            let mut filtered_edges: Vec<(KGEdge, ComponentScores, f32)> = fused;
+
             // Apply group filter if specified
             if let Some(ref groups) = group_ids {
-                filtered_edges.retain(|edge| {
+                filtered_edges.retain(|(edge, _, _)| {
                     edge.group_id.as_ref().map_or(false, |gid| groups.contains(gid))
                 });
             }
-            
-            // Limit results
-            filtered_edges.truncate(max_results);
-            
+
+            // Page this result: skip what earlier pages already returned.
+            let total_after_filters = filtered_edges.len();
+            let page_edges: Vec<_> = filtered_edges.into_iter().skip(offset).take(max_results).collect();
+            let next_cursor = (total_after_filters > offset + page_edges.len())
+                .then(|| PageCursor::new("facts", params.clone(), offset + page_edges.len()).encode());
+
             let mut results = Vec::new();
-            for edge in filtered_edges {
+            for (edge, scores, fused_score) in page_edges {
                 // Get source and target nodes for context
                 let source_node = storage.get_node(edge.source_node_uuid)?;
                 let target_node = storage.get_node(edge.target_node_uuid)?;
-                
-                results.push(verbosity.format_edge(&edge, source_node.as_ref(), target_node.as_ref()));
+
+                let mut formatted = verbosity.format_edge(&edge, source_node.as_ref(), target_node.as_ref());
+                if matches!(verbosity, OutputVerbosity::Full) {
+                    if let Some(obj) = formatted.as_object_mut() {
+                        obj.insert("score_details".to_string(), json!({
+                            "text_rank": scores.text_rank,
+                            "vector_rank": scores.vector_rank,
+                            "lexical_score": scores.lexical,
+                            "semantic_score": scores.semantic,
+                            "fused_score": fused_score
+                        }));
+                    }
+                }
+                results.push(formatted);
             }
-            
+
             let additional_fields = json!({
-                "group_ids": group_ids
+                "group_ids": group_ids,
+                "semantic_ratio": semantic_ratio
             });
-            
+
             let mut response = verbosity.format_response_metadata("facts", Some(query), results.len(), Some(additional_fields));
             response["results"] = json!(results);
+            if let Some(token) = next_cursor {
+                response["next_cursor"] = json!(token);
+            }
             Ok(response)
         },
         "episodes" => {
-            let group_id = params.get("group_id")
-                .and_then(|v| v.as_str());
+            // Read before cursor resolution, like `max_results`, so a
+            // resumed cursor can still ask for a different page size.
             let last_n = params.get("last_n")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(10) as usize;
-            
-            match storage.get_recent_episodes(group_id, last_n) {
-                Ok(episodes) => {
-                    let results: Vec<Value> = episodes.into_iter().map(|episode| {
+            let (params, offset) = resolve_cursor(params, "episodes")?;
+            let params = &params;
+            let group_id = params.get("group_id")
+                .and_then(|v| v.as_str());
+            let filter_str = params.get("filter").and_then(|v| v.as_str());
+            let filter_expr = filter_str
+                .map(parse_filter)
+                .transpose()
+                .map_err(|e| anyhow!("Invalid filter expression: {}", e))?;
+
+            // Optional semantic ranking: episodes long enough to have been
+            // split by `TextChunker` (see `prepare_episode`) get searched at
+            // the chunk level and rolled up to their parent, so a query can
+            // match a passage buried in an oversized episode instead of
+            // only whatever a whole-episode vector captured. Episodes that
+            // were never chunked (nothing matched, or they fit in one
+            // embedding) simply can't surface here and fall through to the
+            // recency listing below, same as when no `query` is given.
+            let query = params.get("query").and_then(|v| v.as_str());
+            if let Some(query) = query {
+                let query_embedding = embedding_engine.encode_text(query).await
+                    .map_err(|e| anyhow!("Failed to generate query embedding: {}", e))?;
+                let chunk_hits = storage.search_embeddings(&query_embedding, "episode_chunk", (offset + last_n) * 4)?;
+
+                let mut best_score_by_episode: HashMap<uuid::Uuid, f32> = HashMap::new();
+                for (chunk_uuid, score) in chunk_hits {
+                    if let Some(episode_uuid) = storage.get_episode_for_chunk(chunk_uuid)? {
+                        best_score_by_episode.entry(episode_uuid)
+                            .and_modify(|best| if score > *best { *best = score })
+                            .or_insert(score);
+                    }
+                }
+
+                let mut ranked: Vec<(uuid::Uuid, f32)> = best_score_by_episode.into_iter().collect();
+                ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+                let mut episodes = Vec::new();
+                for (episode_uuid, _score) in &ranked {
+                    if let Some(episode) = storage.get_episode(*episode_uuid)? {
+                        if group_id.map_or(true, |gid| episode.group_id.as_deref() == Some(gid)) {
+                            episodes.push(episode);
+                        }
+                    }
+                }
+
+                let total_fetched = episodes.len();
+                let unfiltered_page: Vec<_> = episodes.into_iter().skip(offset).take(last_n).collect();
+                let next_cursor = (total_fetched > offset + unfiltered_page.len())
+                    .then(|| PageCursor::new("episodes", params.clone(), offset + unfiltered_page.len()).encode());
+
+                let page_episodes: Vec<_> = unfiltered_page.into_iter()
+                    .filter(|episode| filter_expr.as_ref().map_or(true, |expr| evaluate(expr, episode)))
+                    .collect();
+
+                let results: Vec<Value> = page_episodes.into_iter().map(|episode| {
+                    verbosity.format_episode(&episode)
+                }).collect();
+
+                let additional_fields = json!({
+                    "group_id": group_id,
+                    "filter": filter_str
+                });
+
+                let mut response = verbosity.format_response_metadata("episodes", Some(query), results.len(), Some(additional_fields));
+                response["results"] = json!(results);
+                if let Some(token) = next_cursor {
+                    response["next_cursor"] = json!(token);
+                }
+                return Ok(response);
+            }
+
+            match storage.get_recent_episodes(group_id, offset + last_n) {
+                Ok(all_episodes) => {
+                    let total_fetched = all_episodes.len();
+                    let unfiltered_page: Vec<_> = all_episodes.into_iter().skip(offset).take(last_n).collect();
+                    let next_cursor = (total_fetched > offset + unfiltered_page.len())
+                        .then(|| PageCursor::new("episodes", params.clone(), offset + unfiltered_page.len()).encode());
+
+                    // `filter` is applied after the page is sliced, like
+                    // every other post-scoring filter in this file, so a
+                    // page can come back with fewer than `last_n` entries
+                    // when some are filtered out, but the cursor still
+                    // advances by the unfiltered page size above.
+                    let page_episodes: Vec<_> = unfiltered_page.into_iter()
+                        .filter(|episode| filter_expr.as_ref().map_or(true, |expr| evaluate(expr, episode)))
+                        .collect();
+
+                    let results: Vec<Value> = page_episodes.into_iter().map(|episode| {
                         verbosity.format_episode(&episode)
                     }).collect();
-                    
+
                     let additional_fields = json!({
-                        "group_id": group_id
+                        "group_id": group_id,
+                        "filter": filter_str
                     });
-                    
+
                     let mut response = verbosity.format_response_metadata("episodes", None, results.len(), Some(additional_fields));
                     response["results"] = json!(results);
+                    if let Some(token) = next_cursor {
+                        response["next_cursor"] = json!(token);
+                    }
                     Ok(response)
                 },
                 Err(e) => Err(anyhow!("Failed to retrieve episodes: {}", e))
@@ -561,80 +1547,177 @@ async fn handle_search_memory(
             let group_ids = params.get("group_ids")
                 .and_then(|v| v.as_array())
                 .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>());
-            
-            // Generate embedding for the query
+
+            let semantic_ratio = params.get("semantic_ratio")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(0.5);
+            let (text_weight, vector_weight) = weights_from_semantic_ratio(semantic_ratio);
+
+            let filter_str = params.get("filter").and_then(|v| v.as_str());
+            let filter_expr = filter_str
+                .map(parse_filter)
+                .transpose()
+                .map_err(|e| anyhow!("Invalid filter expression: {}", e))?;
+
+            // Over-fetch past max_results since similarity_threshold,
+            // group_ids, and filter are applied as post-filters below.
+            let candidate_pool = (max_results * 10).max(200);
+
+            // HNSW query-time beam width; wider trades query latency for
+            // recall. `m` only takes effect on reinsertion, so requesting a
+            // different one here triggers a full index rebuild rather than
+            // just tuning this one query.
+            let ef_search = params.get("ef_search")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(candidate_pool.max(100));
+            if let Some(m) = params.get("m").and_then(|v| v.as_u64()).map(|v| v as usize) {
+                storage.rebuild_hnsw_index(crate::graph::hnsw::HnswConfig { m, ef_construction: 200 })?;
+            }
+
+            // Semantic leg: encode the query once, then look up the
+            // nearest stored node embeddings via the HNSW index instead of
+            // re-encoding every node's text, or scanning every stored
+            // embedding, on every request.
             let query_embedding = embedding_engine.encode_text(query).await
                 .map_err(|e| anyhow!("Failed to generate query embedding: {}", e))?;
-            
-            // Get all nodes with embeddings for similarity comparison
-            let all_nodes = storage.search_nodes_by_text("", None, 10000)?; // Get all nodes
+            let semantic_nodes: Vec<(KGNode, f32)> = storage.hnsw_search_nodes(&query_embedding, candidate_pool, ef_search)?
+                .into_iter()
+                .filter_map(|(uuid, score)| storage.get_node(uuid).ok().flatten().map(|node| (node, score)))
+                .collect();
+
+            // Keyword leg, fused the same way `facts` fuses its two legs,
+            // so `similar_concepts` is no longer pure-vector retrieval.
+            let keyword_nodes = if text_weight > 0.0 {
+                storage.search_nodes_by_text(query, None, candidate_pool)?
+            } else {
+                Vec::new()
+            };
+
+            let fused = fuse_weighted_rrf(keyword_nodes, semantic_nodes, text_weight, vector_weight, FACTS_RRF_K, |node| node.uuid);
+
             let mut similar_concepts = Vec::new();
-            
-            for node in all_nodes {
-                // Apply group filter early if specified
+            for (node, scores, fused_score) in fused {
+                if scores.semantic < similarity_threshold {
+                    continue;
+                }
+
                 if let Some(ref groups) = group_ids {
                     if !node.group_id.as_ref().map_or(false, |gid| groups.contains(gid)) {
                         continue;
                     }
                 }
-                
-                // Generate embedding for node content
-                let node_text = format!("{} {} {}", node.name, node.node_type, node.summary);
-                match embedding_engine.encode_text(&node_text).await {
-                    Ok(node_embedding) => {
-                        // Calculate cosine similarity
-                        let similarity = cosine_similarity(&query_embedding, &node_embedding);
-                        
-                        if similarity >= similarity_threshold {
-                            similar_concepts.push(json!({
-                                "uuid": node.uuid,
-                                "name": node.name,
-                                "node_type": node.node_type,
-                                "summary": node.summary,
-                                "similarity": similarity,
-                                "group_id": node.group_id,
-                                "created_at": node.created_at
-                            }));
-                        }
-                    },
-                    Err(e) => {
-                        warn!("Failed to generate embedding for node {}: {}", node.uuid, e);
+
+                if let Some(ref expr) = filter_expr {
+                    if !evaluate(expr, &node) {
+                        continue;
+                    }
+                }
+
+                let mut entry = json!({
+                    "uuid": node.uuid,
+                    "name": node.name,
+                    "node_type": node.node_type,
+                    "summary": node.summary,
+                    "similarity": scores.semantic,
+                    "group_id": node.group_id,
+                    "created_at": node.created_at
+                });
+                if matches!(verbosity, OutputVerbosity::Full) {
+                    if let Some(obj) = entry.as_object_mut() {
+                        obj.insert("score_details".to_string(), json!({
+                            "text_rank": scores.text_rank,
+                            "vector_rank": scores.vector_rank,
+                            "lexical_score": scores.lexical,
+                            "semantic_score": scores.semantic,
+                            "fused_score": fused_score
+                        }));
                     }
                 }
+                similar_concepts.push(entry);
             }
-            
-            // Sort by similarity (highest first) and limit results
+
+            // `fuse_weighted_rrf` already sorts by fused score; re-sort by
+            // raw semantic similarity since that's what `similarity_threshold`
+            // filters on and what callers of this operation expect ranking by.
             similar_concepts.sort_by(|a, b| {
                 let sim_a = a.get("similarity").and_then(|v| v.as_f64()).unwrap_or(0.0);
                 let sim_b = b.get("similarity").and_then(|v| v.as_f64()).unwrap_or(0.0);
                 sim_b.partial_cmp(&sim_a).unwrap_or(std::cmp::Ordering::Equal)
             });
             similar_concepts.truncate(max_results);
-            
+
             let additional_fields = json!({
                 "similarity_threshold": similarity_threshold,
-                "group_ids": group_ids
+                "group_ids": group_ids,
+                "semantic_ratio": semantic_ratio,
+                "ef_search": ef_search,
+                "filter": filter_str
             });
-            
+
             let mut response = verbosity.format_response_metadata("similar_concepts", Some(query), similar_concepts.len(), Some(additional_fields));
             response["results"] = json!(similar_concepts);
             Ok(response)
         },
+        "hybrid_nodes" => {
+            let query = params.get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Query required for hybrid_nodes operation"))?;
+
+            let group_id = params.get("group_id").and_then(|v| v.as_str());
+
+            let query_embedding = embedding_engine.encode_text(query).await
+                .map_err(|e| anyhow!("Failed to generate query embedding: {}", e))?;
+
+            let nodes = storage.hybrid_search_nodes(query, &query_embedding, group_id, max_results)?;
+            let results: Vec<Value> = nodes.iter().map(|node| verbosity.format_node(node)).collect();
+
+            let additional_fields = json!({ "group_id": group_id });
+            let mut response = verbosity.format_response_metadata("hybrid_nodes", Some(query), results.len(), Some(additional_fields));
+            response["results"] = json!(results);
+            Ok(response)
+        },
         "batch" => {
+            // Note: `cursor` pagination isn't supported here — each item
+            // is a bare query string rather than a tagged operation with
+            // its own parameters, so there's no single query identity to
+            // snapshot a cursor against. Callers that need to page through
+            // one of these queries should reissue it singly via the
+            // 'nodes' operation with `cursor`.
             let queries = params.get("queries")
                 .and_then(|v| v.as_array())
                 .ok_or_else(|| anyhow!("Queries array required for batch operation"))?;
-            
+
+            // Single knob applied to every query in the batch (queries are
+            // bare strings, not per-query param objects, so there's
+            // nowhere to put a per-query override). Fused the same way as
+            // `nodes`/`facts`/`similar_concepts`, against storage directly
+            // rather than through `search_engine.search`, since that path
+            // goes through `VectorSearchEngine`'s disconnected vector leg
+            // (see `hnsw` module docs) and would silently fall back to
+            // keyword-only ranking regardless of `semantic_ratio`.
+            let semantic_ratio = params.get("semantic_ratio")
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+                .unwrap_or(0.5);
+            let (text_weight, vector_weight) = weights_from_semantic_ratio(semantic_ratio);
+
+            // Single knob applied to every query in the batch, same
+            // reasoning as `semantic_ratio` above.
+            let filter_str = params.get("filter").and_then(|v| v.as_str());
+            let filter_expr = filter_str
+                .map(parse_filter)
+                .transpose()
+                .map_err(|e| anyhow!("Invalid filter expression: {}", e))?;
+
+            let fetch_limit = (max_results * 10).max(200);
+
             let mut batch_results = Vec::new();
             for query in queries {
                 if let Some(query_str) = query.as_str() {
-                    // Perform hybrid search for each query
-                    match search_engine.search(query_str, max_results).await {
-                        Ok(search_result) => {
-                            let results: Vec<Value> = search_result.nodes.into_iter().map(|node| {
-                                verbosity.format_node(&node)
-                            }).collect();
-                            
+                    match handle_batch_query(query_str, fetch_limit, max_results, text_weight, vector_weight, filter_expr.as_ref(), storage, embedding_engine, verbosity).await {
+                        Ok(results) => {
                             batch_results.push(json!({
                                 "query": query_str,
                                 "results": results,
@@ -642,57 +1725,706 @@ async fn handle_search_memory(
                             }));
                         },
                         Err(e) => {
-                    batch_results.push(json!({
-                        "query": query_str,
-                        "results": [],
+                            batch_results.push(json!({
+                                "query": query_str,
+                                "results": [],
                                 "total_found": 0,
                                 "error": format!("Search failed: {}", e)
-                    }));
+                            }));
                         }
                     }
                 }
             }
-            
-            let mut response = verbosity.format_response_metadata("batch", None, queries.len(), None);
+
+            let additional_fields = json!({ "semantic_ratio": semantic_ratio, "filter": filter_str });
+            let mut response = verbosity.format_response_metadata("batch", None, queries.len(), Some(additional_fields));
             response["batch_results"] = json!(batch_results);
             response["total_queries"] = json!(queries.len());
             Ok(response)
         },
+        "shortest_path" => {
+            let source_uuid = params.get("source_uuid")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("source_uuid required for shortest_path operation"))?;
+            let target_uuid = params.get("target_uuid")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("target_uuid required for shortest_path operation"))?;
+            let source = uuid::Uuid::parse_str(source_uuid)
+                .map_err(|e| anyhow!("Invalid source_uuid: {}", e))?;
+            let target = uuid::Uuid::parse_str(target_uuid)
+                .map_err(|e| anyhow!("Invalid target_uuid: {}", e))?;
+            let max_hops = params.get("max_hops").and_then(|v| v.as_u64()).map(|v| v as usize);
+
+            let group_ids = params.get("group_ids")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>());
+            let edge_filter = match &group_ids {
+                Some(groups) => EdgeFilter::new().group_ids(groups.clone()),
+                None => EdgeFilter::new(),
+            };
+            let edges = storage.find_edges(&edge_filter)?;
+
+            match astar_shortest_path(source, target, &edges, max_hops) {
+                Some((node_path, edge_path, total_cost)) => {
+                    let mut path_nodes = Vec::new();
+                    for uuid in &node_path {
+                        if let Some(node) = storage.get_node(*uuid)? {
+                            path_nodes.push(verbosity.format_node(&node));
+                        }
+                    }
+
+                    let mut path_edges = Vec::new();
+                    for uuid in &edge_path {
+                        if let Some(edge) = storage.get_edge(*uuid)? {
+                            let source_node = storage.get_node(edge.source_node_uuid)?;
+                            let target_node = storage.get_node(edge.target_node_uuid)?;
+                            path_edges.push(verbosity.format_edge(&edge, source_node.as_ref(), target_node.as_ref()));
+                        }
+                    }
+
+                    Ok(json!({
+                        "success": true,
+                        "found": true,
+                        "nodes": path_nodes,
+                        "edges": path_edges,
+                        "hops": edge_path.len(),
+                        "total_cost": total_cost
+                    }))
+                }
+                None => Ok(json!({
+                    "success": true,
+                    "found": false,
+                    "message": "No path found between source_uuid and target_uuid within the given constraints"
+                }))
+            }
+        },
         _ => Err(anyhow!("Unknown search operation: {}", operation))
     }
 }
 
-// Helper function for cosine similarity calculation
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
+/// Derives `(text_weight, vector_weight)` from a single `semantic_ratio`
+/// knob (`0.0` = keyword only, `1.0` = semantic only), the same mapping
+/// `HybridSearchOptions::text_weight`/`vector_weight` uses internally.
+fn weights_from_semantic_ratio(semantic_ratio: f32) -> (f32, f32) {
+    (1.0 - semantic_ratio, semantic_ratio)
+}
+
+const FACTS_RRF_K: f32 = 60.0;
+
+/// Weighted-RRF fuse of a keyword-ranked list and a semantic-ranked list
+/// into one, giving `facts`/`similar_concepts` the same fusion math as
+/// `nodes`' `SearchStrategy::Hybrid` path (see
+/// `HybridSearchEngine::search_with_strategy`) without going back through
+/// the search engine's node-only machinery — these two operations read
+/// `KGEdge`/`KGNode` straight from storage. Keyword candidates carry no
+/// raw relevance score (storage's text search only orders by FTS5 `rank`),
+/// so `ComponentScores::lexical` stays `0.0` for them, matching
+/// `storage::hybrid_search_nodes`'s existing rank-only text leg.
+fn fuse_weighted_rrf<T: Clone>(
+    keyword: Vec<T>,
+    semantic: Vec<(T, f32)>,
+    text_weight: f32,
+    vector_weight: f32,
+    rrf_k: f32,
+    uuid_of: impl Fn(&T) -> uuid::Uuid,
+) -> Vec<(T, ComponentScores, f32)> {
+    let mut fused: HashMap<uuid::Uuid, (T, ComponentScores, f32)> = HashMap::new();
+
+    for (rank, item) in keyword.iter().enumerate() {
+        let uuid = uuid_of(item);
+        let entry = fused.entry(uuid).or_insert_with(|| (item.clone(), ComponentScores::default(), 0.0));
+        entry.1.text_rank = Some(rank + 1);
+        entry.2 += text_weight / (rrf_k + (rank + 1) as f32);
     }
-    
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-    
-    if norm_a == 0.0 || norm_b == 0.0 {
-        0.0
-    } else {
-        dot_product / (norm_a * norm_b)
+    for (rank, (item, score)) in semantic.iter().enumerate() {
+        let uuid = uuid_of(item);
+        let entry = fused.entry(uuid).or_insert_with(|| (item.clone(), ComponentScores::default(), 0.0));
+        entry.1.semantic = *score;
+        entry.1.vector_rank = Some(rank + 1);
+        entry.2 += vector_weight / (rrf_k + (rank + 1) as f32);
     }
+
+    let mut ranked: Vec<(T, ComponentScores, f32)> = fused.into_values().collect();
+    ranked.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
 }
 
-/// Handle pattern analysis operations
-async fn handle_analyze_patterns(
-    params: Value,
+/// One `batch` query's node search, fused the same way as the `nodes`/
+/// `similar_concepts` operations (`fuse_weighted_rrf` over a keyword leg
+/// and an `hnsw_search_nodes` semantic leg) so every `batch` entry honors
+/// `semantic_ratio` too. Returns formatted, `max_results`-truncated nodes;
+/// `Full` verbosity gets the same `score_details` breakdown.
+async fn handle_batch_query(
+    query: &str,
+    fetch_limit: usize,
+    max_results: usize,
+    text_weight: f32,
+    vector_weight: f32,
+    filter_expr: Option<&FilterExpr>,
+    storage: &Arc<GraphStorage>,
+    embedding_engine: &Arc<LocalEmbeddingEngine>,
+    verbosity: OutputVerbosity,
+) -> Result<Vec<Value>> {
+    let keyword_nodes = if text_weight > 0.0 {
+        storage.search_nodes_by_text(query, None, fetch_limit)?
+    } else {
+        Vec::new()
+    };
+
+    let semantic_nodes = if vector_weight > 0.0 {
+        let query_embedding = embedding_engine.encode_text(query).await
+            .map_err(|e| anyhow!("Failed to generate query embedding: {}", e))?;
+        storage.hnsw_search_nodes(&query_embedding, fetch_limit, fetch_limit)?
+    } else {
+        Vec::new()
+    };
+
+    let mut fused = fuse_weighted_rrf(keyword_nodes, semantic_nodes, text_weight, vector_weight, FACTS_RRF_K, |node| node.uuid);
+    if let Some(expr) = filter_expr {
+        fused.retain(|(node, _, _)| evaluate(expr, node));
+    }
+    fused.truncate(max_results);
+
+    Ok(fused.into_iter().map(|(node, scores, fused_score)| {
+        let mut formatted = verbosity.format_node(&node);
+        if matches!(verbosity, OutputVerbosity::Full) {
+            if let Some(obj) = formatted.as_object_mut() {
+                obj.insert("score_details".to_string(), json!({
+                    "text_rank": scores.text_rank,
+                    "vector_rank": scores.vector_rank,
+                    "lexical_score": scores.lexical,
+                    "semantic_score": scores.semantic,
+                    "fused_score": fused_score
+                }));
+            }
+        }
+        formatted
+    }).collect())
+}
+
+/// Brandes' algorithm for betweenness centrality, plus closeness
+/// centrality computed from the same single-source BFS, over the
+/// unweighted graph formed by treating every edge as an undirected
+/// connection between its two nodes. For each source `s`: BFS records
+/// each node's distance, predecessor list, and shortest-path count
+/// `sigma`; then dependencies accumulate in reverse BFS order via
+/// `delta[v] += (sigma[v]/sigma[w]) * (1 + delta[w])` for each
+/// predecessor `v` of `w`, and `delta[v]` is added to the betweenness of
+/// every `v != s`. Since the graph is undirected, every shortest path is
+/// discovered once from each endpoint, so the summed betweenness is
+/// halved at the end. Closeness is `(reachable - 1) / sum(distances)`
+/// for nodes reachable from `s`.
+fn brandes_centrality(
+    node_ids: &[uuid::Uuid],
+    adjacency: &HashMap<uuid::Uuid, Vec<uuid::Uuid>>,
+) -> (HashMap<uuid::Uuid, f32>, HashMap<uuid::Uuid, f32>) {
+    let mut betweenness: HashMap<uuid::Uuid, f64> = node_ids.iter().map(|&id| (id, 0.0)).collect();
+    let mut closeness: HashMap<uuid::Uuid, f32> = node_ids.iter().map(|&id| (id, 0.0)).collect();
+
+    for &s in node_ids {
+        let mut stack = Vec::new();
+        let mut pred: HashMap<uuid::Uuid, Vec<uuid::Uuid>> = node_ids.iter().map(|&id| (id, Vec::new())).collect();
+        let mut sigma: HashMap<uuid::Uuid, f64> = node_ids.iter().map(|&id| (id, 0.0)).collect();
+        let mut dist: HashMap<uuid::Uuid, i64> = node_ids.iter().map(|&id| (id, -1)).collect();
+        sigma.insert(s, 1.0);
+        dist.insert(s, 0);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(s);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            if let Some(neighbors) = adjacency.get(&v) {
+                for &w in neighbors {
+                    if dist[&w] < 0 {
+                        dist.insert(w, dist[&v] + 1);
+                        queue.push_back(w);
+                    }
+                    if dist[&w] == dist[&v] + 1 {
+                        let sigma_v = sigma[&v];
+                        *sigma.get_mut(&w).unwrap() += sigma_v;
+                        pred.get_mut(&w).unwrap().push(v);
+                    }
+                }
+            }
+        }
+
+        let reachable: Vec<(uuid::Uuid, i64)> = dist.iter()
+            .filter(|(_, &d)| d > 0)
+            .map(|(&id, &d)| (id, d))
+            .collect();
+        let total_dist: i64 = reachable.iter().map(|(_, d)| d).sum();
+        if total_dist > 0 {
+            *closeness.get_mut(&s).unwrap() = reachable.len() as f32 / total_dist as f32;
+        }
+
+        let mut delta: HashMap<uuid::Uuid, f64> = node_ids.iter().map(|&id| (id, 0.0)).collect();
+        while let Some(w) = stack.pop() {
+            for &v in &pred[&w] {
+                let contribution = (sigma[&v] / sigma[&w]) * (1.0 + delta[&w]);
+                *delta.get_mut(&v).unwrap() += contribution;
+            }
+            if w != s {
+                *betweenness.get_mut(&w).unwrap() += delta[&w];
+            }
+        }
+    }
+
+    let betweenness = betweenness.into_iter().map(|(id, score)| (id, (score / 2.0) as f32)).collect();
+    (betweenness, closeness)
+}
+
+/// PageRank over the directed graph formed by `source_node_uuid ->
+/// target_node_uuid` edges, via power iteration. Every node starts at
+/// `1/N`; each iteration sets `rank(v) = (1-d)/N + d * (Σ
+/// rank(u)/outdeg(u) over in-neighbors u, + dangling_mass/N)`, where
+/// `dangling_mass` is the summed rank of nodes with no outgoing edges
+/// (redistributed uniformly rather than lost, as a zero-outdegree node
+/// would otherwise leak rank out of the system). Stops once the L1 change
+/// between iterations drops below `tolerance` or `max_iter` is reached.
+fn pagerank(
+    node_ids: &[uuid::Uuid],
+    out_adjacency: &HashMap<uuid::Uuid, Vec<uuid::Uuid>>,
+    damping: f32,
+    tolerance: f32,
+    max_iter: usize,
+) -> HashMap<uuid::Uuid, f32> {
+    let n = node_ids.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let mut in_neighbors: HashMap<uuid::Uuid, Vec<uuid::Uuid>> = node_ids.iter().map(|&id| (id, Vec::new())).collect();
+    for (&u, outs) in out_adjacency {
+        for &v in outs {
+            if let Some(list) = in_neighbors.get_mut(&v) {
+                list.push(u);
+            }
+        }
+    }
+    let out_degree = |u: &uuid::Uuid| out_adjacency.get(u).map(|v| v.len()).unwrap_or(0);
+
+    let mut ranks: HashMap<uuid::Uuid, f32> = node_ids.iter().map(|&id| (id, 1.0 / n as f32)).collect();
+
+    for _ in 0..max_iter {
+        let dangling_mass: f32 = node_ids.iter()
+            .filter(|&&id| out_degree(&id) == 0)
+            .map(|id| ranks[id])
+            .sum();
+
+        let mut next_ranks: HashMap<uuid::Uuid, f32> = HashMap::with_capacity(n);
+        for &v in node_ids {
+            let incoming: f32 = in_neighbors[&v].iter()
+                .map(|u| ranks[u] / out_degree(u).max(1) as f32)
+                .sum();
+            let rank = (1.0 - damping) / n as f32 + damping * (incoming + dangling_mass / n as f32);
+            next_ranks.insert(v, rank);
+        }
+
+        let l1_change: f32 = node_ids.iter().map(|id| (next_ranks[id] - ranks[id]).abs()).sum();
+        ranks = next_ranks;
+        if l1_change < tolerance {
+            break;
+        }
+    }
+
+    ranks
+}
+
+fn l2_normalize(v: &[f32]) -> Vec<f32> {
+    let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
+}
+
+/// k-means++ centroid seeding for `spherical_kmeans`: the first centroid is
+/// picked uniformly at random, then each subsequent one with probability
+/// proportional to its squared cosine distance from the nearest
+/// already-chosen centroid, so centroids start spread out across the data
+/// rather than clumped. `vectors` must already be L2-normalized.
+fn kmeans_plus_plus_init(vectors: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+    let n = vectors.len();
+    let mut centroids = Vec::with_capacity(k);
+    centroids.push(vectors[(rand::random::<f64>() * n as f64) as usize % n].clone());
+
+    while centroids.len() < k {
+        let sq_distances: Vec<f64> = vectors.iter()
+            .map(|v| {
+                let nearest = centroids.iter()
+                    .map(|c| 1.0 - cosine_similarity(v, c))
+                    .fold(f32::INFINITY, f32::min);
+                (nearest * nearest) as f64
+            })
+            .collect();
+        let total: f64 = sq_distances.iter().sum();
+
+        let chosen = if total <= 0.0 {
+            // Every point coincides with an already-chosen centroid;
+            // nothing to weight by, so just take the next one in order.
+            centroids.len() % n
+        } else {
+            let target = rand::random::<f64>() * total;
+            let mut acc = 0.0;
+            sq_distances.iter().position(|&d| {
+                acc += d;
+                acc >= target
+            }).unwrap_or(n - 1)
+        };
+        centroids.push(vectors[chosen].clone());
+    }
+
+    centroids
+}
+
+/// Spherical k-means: like standard k-means but centroids are re-normalized
+/// to unit length after each mean update and points are assigned by
+/// highest cosine similarity rather than lowest Euclidean distance, which
+/// is the right notion of "closest" for embedding vectors. `vectors` must
+/// already be L2-normalized; iterates until assignments stop changing or
+/// `max_iter` is hit. An empty cluster keeps its previous centroid rather
+/// than being reseeded. Returns each point's cluster index.
+fn spherical_kmeans(vectors: &[Vec<f32>], k: usize, max_iter: usize) -> Vec<usize> {
+    let mut centroids = kmeans_plus_plus_init(vectors, k);
+    let mut assignments = vec![usize::MAX; vectors.len()];
+
+    for _ in 0..max_iter {
+        let mut changed = false;
+        for (i, v) in vectors.iter().enumerate() {
+            let best = (0..k)
+                .map(|c| (c, cosine_similarity(v, &centroids[c])))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(c, _)| c)
+                .unwrap_or(0);
+            if assignments[i] != best {
+                assignments[i] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let dims = vectors[0].len();
+        let mut sums = vec![vec![0.0f32; dims]; k];
+        let mut counts = vec![0usize; k];
+        for (i, v) in vectors.iter().enumerate() {
+            counts[assignments[i]] += 1;
+            for (d, x) in sums[assignments[i]].iter_mut().zip(v.iter()) {
+                *d += x;
+            }
+        }
+        for c in 0..k {
+            if counts[c] > 0 {
+                centroids[c] = l2_normalize(&sums[c]);
+            }
+        }
+    }
+
+    assignments
+}
+
+/// DBSCAN over cosine similarity instead of Euclidean distance: two points
+/// are neighbors when their cosine similarity is at least `min_similarity`
+/// (the "epsilon" in the usual formulation, just expressed as a similarity
+/// floor rather than a distance ceiling since that's the natural scale for
+/// embeddings). A point with fewer than `min_points` neighbors (itself
+/// excluded) that isn't reachable from a denser point is left as noise
+/// (`-1`); everything else gets a `0`-based cluster id. `vectors` must
+/// already be L2-normalized. Pairwise similarity is computed directly
+/// (O(n²)) rather than through the `hnsw` index — this runs once per
+/// `semantic_clusters` call rather than per search, so the simpler,
+/// index-free implementation isn't worth the complexity of keeping a
+/// second index in sync.
+fn dbscan_cosine(vectors: &[Vec<f32>], min_similarity: f32, min_points: usize) -> Vec<i64> {
+    let n = vectors.len();
+    let neighbors_of = |i: usize| -> Vec<usize> {
+        (0..n).filter(|&j| j != i && cosine_similarity(&vectors[i], &vectors[j]) >= min_similarity).collect()
+    };
+
+    let mut visited = vec![false; n];
+    let mut labels = vec![-1i64; n];
+    let mut next_cluster = 0i64;
+
+    for i in 0..n {
+        if visited[i] {
+            continue;
+        }
+        visited[i] = true;
+
+        let mut seed_set = neighbors_of(i);
+        if seed_set.len() < min_points {
+            continue; // stays noise unless claimed by another point's expansion below
+        }
+
+        labels[i] = next_cluster;
+        let mut cursor = 0;
+        while cursor < seed_set.len() {
+            let j = seed_set[cursor];
+            cursor += 1;
+
+            if !visited[j] {
+                visited[j] = true;
+                let j_neighbors = neighbors_of(j);
+                if j_neighbors.len() >= min_points {
+                    for &candidate in &j_neighbors {
+                        if !seed_set.contains(&candidate) {
+                            seed_set.push(candidate);
+                        }
+                    }
+                }
+            }
+            if labels[j] == -1 {
+                labels[j] = next_cluster;
+            }
+        }
+        next_cluster += 1;
+    }
+
+    labels
+}
+
+/// For each non-noise label, picks the member with the highest average
+/// cosine similarity to its other cluster-mates as that cluster's
+/// representative, and reports that same average (over every pair, not
+/// just the representative's) as a cohesion score. A singleton cluster is
+/// trivially its own representative with cohesion `1.0`.
+fn cluster_summaries(indices_by_label: &HashMap<i64, Vec<usize>>, vectors: &[Vec<f32>]) -> HashMap<i64, (usize, f32)> {
+    indices_by_label.iter().map(|(&label, members)| {
+        if members.len() == 1 {
+            return (label, (members[0], 1.0));
+        }
+
+        let mut best_idx = members[0];
+        let mut best_avg = f32::NEG_INFINITY;
+        let mut total = 0.0f32;
+        let mut pairs = 0usize;
+        for &i in members {
+            let sum: f32 = members.iter().filter(|&&j| j != i)
+                .map(|&j| cosine_similarity(&vectors[i], &vectors[j]))
+                .sum();
+            let avg = sum / (members.len() - 1) as f32;
+            if avg > best_avg {
+                best_avg = avg;
+                best_idx = i;
+            }
+            total += sum;
+            pairs += members.len() - 1;
+        }
+
+        (label, (best_idx, total / pairs as f32))
+    }).collect()
+}
+
+/// Per-bucket activity accumulated by the `temporal` analysis: how many
+/// episodes/edges fell in this bucket, plus a frequency count of the
+/// relation types and (endpoint) node types seen, for the "dominant
+/// entities/relationships" breakdown.
+#[derive(Debug, Clone, Default)]
+struct TemporalBucket {
+    episode_count: usize,
+    edge_count: usize,
+    relation_type_counts: HashMap<String, usize>,
+    node_type_counts: HashMap<String, usize>,
+}
+
+/// Truncates `ts` down to the start of its bucket at the requested
+/// granularity (`hour`/`week`/`month`, defaulting to `day` for any other
+/// value including `"day"` itself). `week` buckets start on Monday.
+fn bucket_start(ts: DateTime<Utc>, granularity: &str) -> DateTime<Utc> {
+    let day_start = Utc.with_ymd_and_hms(ts.year(), ts.month(), ts.day(), 0, 0, 0).unwrap();
+    match granularity {
+        "hour" => Utc.with_ymd_and_hms(ts.year(), ts.month(), ts.day(), ts.hour(), 0, 0).unwrap(),
+        "week" => day_start - chrono::Duration::days(ts.weekday().num_days_from_monday() as i64),
+        "month" => Utc.with_ymd_and_hms(ts.year(), ts.month(), 1, 0, 0, 0).unwrap(),
+        _ => day_start,
+    }
+}
+
+/// The `n` most frequent keys in `counts`, highest first, formatted as
+/// `{"type": ..., "count": ...}` JSON values.
+fn top_counts_json(counts: &HashMap<String, usize>, n: usize) -> Vec<Value> {
+    let mut ranked: Vec<(&String, &usize)> = counts.iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    ranked.truncate(n);
+    ranked.into_iter().map(|(k, v)| json!({ "type": k, "count": v })).collect()
+}
+
+/// A contiguous run of time-ordered buckets whose combined
+/// episode+edge count exceeded the burst threshold.
+struct Burst {
+    start_index: usize,
+    end_index: usize,
+    peak_index: usize,
+    peak_count: usize,
+    node_type_counts: HashMap<String, usize>,
+}
+
+/// Kleinberg-style burst detection over a bucketed count series: flags
+/// contiguous runs of buckets whose `episode_count + edge_count` exceeds
+/// `mean + 2 * stddev` of the whole series. Needs at least 2 buckets to
+/// define a meaningful stddev; returns nothing for 0 or 1 buckets.
+fn detect_bursts(buckets: &[(DateTime<Utc>, TemporalBucket)]) -> Vec<Burst> {
+    if buckets.len() < 2 {
+        return Vec::new();
+    }
+
+    let counts: Vec<f64> = buckets.iter().map(|(_, b)| (b.episode_count + b.edge_count) as f64).collect();
+    let mean = counts.iter().sum::<f64>() / counts.len() as f64;
+    let variance = counts.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / counts.len() as f64;
+    let threshold = mean + 2.0 * variance.sqrt();
+
+    let mut bursts = Vec::new();
+    let mut i = 0;
+    while i < counts.len() {
+        if counts[i] <= threshold {
+            i += 1;
+            continue;
+        }
+
+        let start_index = i;
+        let mut peak_index = i;
+        let mut peak_count = buckets[i].1.episode_count + buckets[i].1.edge_count;
+        while i < counts.len() && counts[i] > threshold {
+            let count = buckets[i].1.episode_count + buckets[i].1.edge_count;
+            if count > peak_count {
+                peak_count = count;
+                peak_index = i;
+            }
+            i += 1;
+        }
+        let end_index = i - 1;
+
+        let mut node_type_counts: HashMap<String, usize> = HashMap::new();
+        for (_, bucket) in &buckets[start_index..=end_index] {
+            for (node_type, count) in &bucket.node_type_counts {
+                *node_type_counts.entry(node_type.clone()).or_insert(0) += count;
+            }
+        }
+
+        bursts.push(Burst { start_index, end_index, peak_index, peak_count, node_type_counts });
+    }
+
+    bursts
+}
+
+/// A*/Dijkstra shortest path between two nodes, treating `KGEdge.weight`
+/// as strength rather than distance: edge cost is `1.0 / weight` (clamped
+/// away from zero so a zero/negative weight can't divide badly), so a
+/// stronger relationship is a shorter hop. The graph is traversed as
+/// undirected — each edge connects its source and target both ways, like
+/// `brandes_centrality`'s adjacency — and `max_hops`, if set, bounds
+/// search depth rather than cost. The heuristic is the zero function,
+/// which is always admissible and degrades this to plain Dijkstra; an
+/// embedding-cosine-derived heuristic was left out because proving its
+/// scale stays admissible against the `1/weight` cost space needs more
+/// care than this pass allows. Returns `(node_path, edge_path, total_cost)`
+/// source-to-target in order, or `None` if no path exists within
+/// `max_hops`.
+fn astar_shortest_path(
+    source: uuid::Uuid,
+    target: uuid::Uuid,
+    edges: &[KGEdge],
+    max_hops: Option<usize>,
+) -> Option<(Vec<uuid::Uuid>, Vec<uuid::Uuid>, f32)> {
+    if source == target {
+        return Some((vec![source], Vec::new(), 0.0));
+    }
+
+    let mut adjacency: HashMap<uuid::Uuid, Vec<(uuid::Uuid, uuid::Uuid, f32)>> = HashMap::new();
+    for edge in edges {
+        let cost = 1.0 / edge.weight.max(0.0001);
+        adjacency.entry(edge.source_node_uuid).or_default().push((edge.target_node_uuid, edge.uuid, cost));
+        adjacency.entry(edge.target_node_uuid).or_default().push((edge.source_node_uuid, edge.uuid, cost));
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    struct OpenEntry {
+        f_score: f32,
+        node: uuid::Uuid,
+        hops: usize,
+    }
+    impl Eq for OpenEntry {}
+    impl PartialOrd for OpenEntry {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for OpenEntry {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            // Reversed so `BinaryHeap` (a max-heap) pops the lowest f_score first.
+            other.f_score.partial_cmp(&self.f_score).unwrap_or(std::cmp::Ordering::Equal)
+        }
+    }
+
+    let mut open = std::collections::BinaryHeap::new();
+    let mut g_score: HashMap<uuid::Uuid, f32> = HashMap::new();
+    let mut came_from: HashMap<uuid::Uuid, (uuid::Uuid, uuid::Uuid)> = HashMap::new();
+    let mut closed: std::collections::HashSet<uuid::Uuid> = std::collections::HashSet::new();
+
+    g_score.insert(source, 0.0);
+    open.push(OpenEntry { f_score: 0.0, node: source, hops: 0 });
+
+    while let Some(OpenEntry { node: current, hops, .. }) = open.pop() {
+        if current == target {
+            let mut node_path = vec![current];
+            let mut edge_path = Vec::new();
+            let mut cursor = current;
+            while let Some(&(prev, edge_uuid)) = came_from.get(&cursor) {
+                node_path.push(prev);
+                edge_path.push(edge_uuid);
+                cursor = prev;
+            }
+            node_path.reverse();
+            edge_path.reverse();
+            return Some((node_path, edge_path, g_score[&target]));
+        }
+
+        if !closed.insert(current) {
+            continue;
+        }
+        if let Some(limit) = max_hops {
+            if hops >= limit {
+                continue;
+            }
+        }
+
+        let current_g = g_score[&current];
+        if let Some(neighbors) = adjacency.get(&current) {
+            for &(neighbor, edge_uuid, cost) in neighbors {
+                if closed.contains(&neighbor) {
+                    continue;
+                }
+                let tentative_g = current_g + cost;
+                if tentative_g < *g_score.get(&neighbor).unwrap_or(&f32::INFINITY) {
+                    g_score.insert(neighbor, tentative_g);
+                    came_from.insert(neighbor, (current, edge_uuid));
+                    open.push(OpenEntry { f_score: tentative_g, node: neighbor, hops: hops + 1 });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Handle pattern analysis operations
+async fn handle_analyze_patterns(
+    params: Value,
     storage: &Arc<GraphStorage>,
     search_engine: &Arc<HybridSearchEngine>,
 ) -> Result<Value> {
     let analysis_type = params.get("analysis_type")
         .and_then(|v| v.as_str())
         .ok_or_else(|| anyhow!("Missing required parameter: analysis_type"))?;
-    
+
     let max_results = params.get("max_results")
         .and_then(|v| v.as_u64())
         .unwrap_or(20) as usize;
-    
+
+    let verbosity = OutputVerbosity::from_params(&params);
+
     match analysis_type {
         "relationships" => {
             Ok(json!({
@@ -719,24 +2451,161 @@ async fn handle_analyze_patterns(
             let time_granularity = params.get("time_granularity")
                 .and_then(|v| v.as_str())
                 .unwrap_or("day");
-            
+            let concept_filter = params.get("concept_filter").and_then(|v| v.as_str());
+
+            let window_start = Utc::now() - chrono::Duration::days(time_range_days as i64);
+
+            let mut episodes = storage.find_episodes(&EpisodeFilter::new().created_after(window_start))?;
+            if let Some(concept) = concept_filter {
+                episodes.retain(|e| e.name.contains(concept) || e.content.contains(concept));
+            }
+            let edges = storage.find_edges(&EdgeFilter::new().created_after(window_start))?;
+
+            // Edges are the only window-scoped source with entity links
+            // (`find_episodes` returns the lightweight, un-hydrated row —
+            // see its doc comment — so episode-to-entity linkage isn't
+            // available without a second `load_episodes_full` round trip);
+            // node types for the "dominant entity types" breakdown come
+            // from each edge's endpoints instead.
+            let mut node_type_by_uuid: HashMap<uuid::Uuid, String> = HashMap::new();
+            for edge in &edges {
+                for uuid in [edge.source_node_uuid, edge.target_node_uuid] {
+                    if let std::collections::hash_map::Entry::Vacant(entry) = node_type_by_uuid.entry(uuid) {
+                        if let Some(node) = storage.get_node(uuid)? {
+                            entry.insert(node.node_type);
+                        }
+                    }
+                }
+            }
+
+            let mut buckets: BTreeMap<DateTime<Utc>, TemporalBucket> = BTreeMap::new();
+            for episode in &episodes {
+                buckets.entry(bucket_start(episode.created_at, time_granularity)).or_default().episode_count += 1;
+            }
+            for edge in &edges {
+                let bucket = buckets.entry(bucket_start(edge.created_at, time_granularity)).or_default();
+                bucket.edge_count += 1;
+                *bucket.relation_type_counts.entry(edge.relation_type.clone()).or_insert(0) += 1;
+                for uuid in [edge.source_node_uuid, edge.target_node_uuid] {
+                    if let Some(node_type) = node_type_by_uuid.get(&uuid) {
+                        *bucket.node_type_counts.entry(node_type.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+
+            let bucket_entries: Vec<(DateTime<Utc>, TemporalBucket)> = buckets.into_iter().collect();
+            let bursts = detect_bursts(&bucket_entries);
+
+            let patterns: Vec<Value> = bucket_entries.iter().map(|(start, bucket)| {
+                json!({
+                    "bucket_start": start.to_rfc3339(),
+                    "episode_count": bucket.episode_count,
+                    "edge_count": bucket.edge_count,
+                    "total_count": bucket.episode_count + bucket.edge_count,
+                    "dominant_relation_types": top_counts_json(&bucket.relation_type_counts, 3),
+                    "dominant_node_types": top_counts_json(&bucket.node_type_counts, 3)
+                })
+            }).collect();
+
+            let burst_json: Vec<Value> = bursts.iter().map(|burst| {
+                json!({
+                    "start": bucket_entries[burst.start_index].0.to_rfc3339(),
+                    "end": bucket_entries[burst.end_index].0.to_rfc3339(),
+                    "peak_bucket": bucket_entries[burst.peak_index].0.to_rfc3339(),
+                    "peak_count": burst.peak_count,
+                    "dominant_node_types": top_counts_json(&burst.node_type_counts, 3)
+                })
+            }).collect();
+
             Ok(json!({
                 "success": true,
                 "analysis_type": "temporal",
                 "time_range_days": time_range_days,
                 "time_granularity": time_granularity,
-                "patterns": [],
-                "total_found": 0,
-                "message": "Temporal pattern analysis available but not yet implemented"
+                "concept_filter": concept_filter,
+                "patterns": patterns,
+                "total_found": patterns.len(),
+                "bursts": burst_json
             }))
         },
         "centrality" => {
+            let group_ids = params.get("group_ids")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect::<Vec<String>>());
+            let top_k = params.get("top_k")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(max_results);
+            let centrality_method = params.get("centrality_method")
+                .and_then(|v| v.as_str())
+                .unwrap_or("betweenness");
+
+            let (node_filter, edge_filter) = match &group_ids {
+                Some(groups) => (
+                    NodeFilter::new().group_ids(groups.clone()),
+                    EdgeFilter::new().group_ids(groups.clone()),
+                ),
+                None => (NodeFilter::new(), EdgeFilter::new()),
+            };
+            let nodes = storage.find_nodes(&node_filter)?;
+            let edges = storage.find_edges(&edge_filter)?;
+
+            let node_ids: Vec<uuid::Uuid> = nodes.iter().map(|n| n.uuid).collect();
+
+            let (scores, closeness) = match centrality_method {
+                "pagerank" => {
+                    let damping = params.get("damping_factor")
+                        .and_then(|v| v.as_f64())
+                        .map(|v| v as f32)
+                        .unwrap_or(0.85);
+                    let mut out_adjacency: HashMap<uuid::Uuid, Vec<uuid::Uuid>> = node_ids.iter().map(|&id| (id, Vec::new())).collect();
+                    for edge in &edges {
+                        if out_adjacency.contains_key(&edge.source_node_uuid) && out_adjacency.contains_key(&edge.target_node_uuid) {
+                            out_adjacency.get_mut(&edge.source_node_uuid).unwrap().push(edge.target_node_uuid);
+                        }
+                    }
+                    (pagerank(&node_ids, &out_adjacency, damping, 1e-6, 100), None)
+                }
+                _ => {
+                    let mut adjacency: HashMap<uuid::Uuid, Vec<uuid::Uuid>> = node_ids.iter().map(|&id| (id, Vec::new())).collect();
+                    for edge in &edges {
+                        if adjacency.contains_key(&edge.source_node_uuid) && adjacency.contains_key(&edge.target_node_uuid) {
+                            adjacency.get_mut(&edge.source_node_uuid).unwrap().push(edge.target_node_uuid);
+                            adjacency.get_mut(&edge.target_node_uuid).unwrap().push(edge.source_node_uuid);
+                        }
+                    }
+                    let (betweenness, closeness) = brandes_centrality(&node_ids, &adjacency);
+                    (betweenness, Some(closeness))
+                }
+            };
+
+            let mut ranked_nodes = nodes;
+            ranked_nodes.sort_by(|a, b| {
+                let score_a = scores.get(&a.uuid).copied().unwrap_or(0.0);
+                let score_b = scores.get(&b.uuid).copied().unwrap_or(0.0);
+                score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            ranked_nodes.truncate(top_k);
+
+            let important_nodes: Vec<Value> = ranked_nodes.into_iter().map(|node| {
+                let mut formatted = verbosity.format_node(&node);
+                if let Some(obj) = formatted.as_object_mut() {
+                    obj.insert("centrality".to_string(), json!(scores.get(&node.uuid).copied().unwrap_or(0.0)));
+                    if let Some(ref closeness) = closeness {
+                        obj.insert("closeness".to_string(), json!(closeness.get(&node.uuid).copied().unwrap_or(0.0)));
+                    }
+                }
+                formatted
+            }).collect();
+
+            let total_found = important_nodes.len();
             Ok(json!({
                 "success": true,
                 "analysis_type": "centrality",
-                "important_nodes": [],
-                "total_found": 0,
-                "message": "Centrality analysis available but not yet implemented"
+                "centrality_method": centrality_method,
+                "group_ids": group_ids,
+                "important_nodes": important_nodes,
+                "total_found": total_found
             }))
         },
         "semantic_clusters" => {
@@ -745,27 +2614,113 @@ async fn handle_analyze_patterns(
                 .unwrap_or("kmeans");
             let num_clusters = params.get("num_clusters")
                 .and_then(|v| v.as_u64())
-                .unwrap_or(5);
-            
+                .unwrap_or(5) as usize;
+
+            let embeddings = storage.all_node_embeddings()?;
+            if embeddings.is_empty() {
+                return Ok(json!({
+                    "success": true,
+                    "analysis_type": "semantic_clusters",
+                    "cluster_method": cluster_method,
+                    "clusters": [],
+                    "total_clusters": 0,
+                    "noise_count": 0,
+                    "message": "No node embeddings available to cluster"
+                }));
+            }
+
+            let normalized: Vec<Vec<f32>> = embeddings.iter().map(|(_, v)| l2_normalize(v)).collect();
+
+            let labels: Vec<i64> = match cluster_method {
+                "dbscan" => {
+                    let epsilon = params.get("epsilon")
+                        .and_then(|v| v.as_f64())
+                        .map(|v| v as f32)
+                        .unwrap_or(0.85);
+                    let min_points = params.get("min_cluster_size")
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as usize)
+                        .unwrap_or(3);
+                    dbscan_cosine(&normalized, epsilon, min_points)
+                }
+                "kmeans" => {
+                    let k = num_clusters.clamp(1, normalized.len());
+                    spherical_kmeans(&normalized, k, 100).into_iter().map(|c| c as i64).collect()
+                }
+                other => return Err(anyhow!("Unknown cluster_method: {}. Supported: kmeans, dbscan", other)),
+            };
+
+            let mut indices_by_label: HashMap<i64, Vec<usize>> = HashMap::new();
+            for (i, &label) in labels.iter().enumerate() {
+                indices_by_label.entry(label).or_default().push(i);
+            }
+            let noise_count = indices_by_label.get(&-1).map(|v| v.len()).unwrap_or(0);
+            indices_by_label.remove(&-1);
+
+            let summaries = cluster_summaries(&indices_by_label, &normalized);
+
+            let mut clusters = Vec::new();
+            for (label, members) in &indices_by_label {
+                let (representative_idx, cohesion) = summaries[label];
+                let representative_uuid = embeddings[representative_idx].0;
+                let representative = storage.get_node(representative_uuid)?;
+
+                let mut member_entries = Vec::new();
+                for &idx in members {
+                    let uuid = embeddings[idx].0;
+                    if let Some(node) = storage.get_node(uuid)? {
+                        member_entries.push(json!({ "uuid": node.uuid, "name": node.name }));
+                    }
+                }
+
+                clusters.push(json!({
+                    "cluster_id": label,
+                    "size": members.len(),
+                    "cohesion": cohesion,
+                    "representative": representative.map(|n| json!({ "uuid": n.uuid, "name": n.name })),
+                    "members": member_entries
+                }));
+            }
+            clusters.sort_by(|a, b| {
+                let size_a = a.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+                let size_b = b.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+                size_b.cmp(&size_a)
+            });
+
             Ok(json!({
                 "success": true,
                 "analysis_type": "semantic_clusters",
                 "cluster_method": cluster_method,
-                "num_clusters": num_clusters,
-                "clusters": [],
-                "total_clusters": 0,
-                "message": "Semantic clustering analysis available but not yet implemented"
+                "total_clusters": clusters.len(),
+                "noise_count": noise_count,
+                "clusters": clusters
             }))
         },
         _ => Err(anyhow!("Unknown analysis type: {}", analysis_type))
     }
 }
 
+/// Builds one `stats` operation metric entry: a name, a Prometheus-style
+/// `type` (`"counter"` for a monotonically-increasing total, `"gauge"` for
+/// anything else), a value, and a human-readable `desc` so a downstream
+/// monitoring agent can ingest the list uniformly without hardcoding
+/// per-metric knowledge.
+fn graph_stat_metric(name: &str, kind: &str, value: Value, desc: &str) -> Value {
+    json!({
+        "name": name,
+        "type": kind,
+        "value": value,
+        "desc": desc,
+    })
+}
+
 /// Handle graph management operations with batch support
 async fn handle_manage_graph(
     params: Value,
     storage: &Arc<GraphStorage>,
     embedding_engine: &Arc<LocalEmbeddingEngine>,
+    memory_optimizer: &Arc<MemoryOptimizer>,
+    search_queue: &Arc<SearchQueue>,
 ) -> Result<Value> {
     let operation = params.get("operation")
         .and_then(|v| v.as_str())
@@ -781,138 +2736,521 @@ async fn handle_manage_graph(
             
             Ok(json!({
                 "success": true,
-                "operation": "get_entity_edge",
-                "uuid": uuid,
-                "result": null,
-                "message": "Entity edge retrieval available but not yet implemented"
+                "operation": "get_entity_edge",
+                "uuid": uuid,
+                "result": null,
+                "message": "Entity edge retrieval available but not yet implemented"
+            }))
+        },
+        "delete_entity_edge" => {
+            let uuid_str = params.get("uuid")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("UUID required for delete_entity_edge operation"))?;
+            
+            let uuid = uuid::Uuid::parse_str(uuid_str)
+                .map_err(|e| anyhow!("Invalid UUID format: {}", e))?;
+            
+            match storage.delete_edge(&uuid) {
+                Ok(_) => {
+                    Ok(json!({
+                        "success": true,
+                        "operation": "delete_entity_edge",
+                        "uuid": uuid,
+                        "message": "Entity edge deleted successfully"
+                    }))
+                },
+                Err(e) => Err(anyhow!("Failed to delete entity edge: {}", e))
+            }
+        },
+        "delete_episode" => {
+            let uuid_str = params.get("uuid")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("UUID required for delete_episode operation"))?;
+            
+            let uuid = uuid::Uuid::parse_str(uuid_str)
+                .map_err(|e| anyhow!("Invalid UUID format: {}", e))?;
+            
+            match storage.delete_episode(&uuid) {
+                Ok(_) => {
+                    Ok(json!({
+                        "success": true,
+                        "operation": "delete_episode",
+                        "uuid": uuid,
+                        "message": "Episode deleted successfully"
+                    }))
+                },
+                Err(e) => Err(anyhow!("Failed to delete episode: {}", e))
+            }
+        },
+        "delete_batch" => {
+            let uuids = params.get("uuids")
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| anyhow!("UUIDs array required for delete_batch operation"))?;
+            
+            let confirm = params.get("confirm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            
+            if !confirm {
+                return Err(anyhow!("Batch deletion requires explicit confirmation (confirm: true)"));
+            }
+            
+            let delete_type = params.get("delete_type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("mixed");
+            
+            let mut deleted_count = 0;
+            let mut errors = Vec::new();
+            
+            for uuid_val in uuids {
+                if let Some(uuid_str) = uuid_val.as_str() {
+                    match uuid::Uuid::parse_str(uuid_str) {
+                        Ok(uuid) => {
+                            // For now, only handle episode deletion
+                            match storage.delete_episode(&uuid) {
+                                Ok(_) => deleted_count += 1,
+                                Err(e) => errors.push(format!("Failed to delete {}: {}", uuid, e))
+                            }
+                        },
+                        Err(e) => errors.push(format!("Invalid UUID {}: {}", uuid_str, e))
+                    }
+                }
+            }
+            
+            Ok(json!({
+                "success": true,
+                "operation": "delete_batch",
+                "delete_type": delete_type,
+                "deleted_count": deleted_count,
+                "total_requested": uuids.len(),
+                "errors": errors
+            }))
+        },
+        "clear_graph" => {
+            let confirm = params.get("confirm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            
+            if !confirm {
+                return Err(anyhow!("Graph clearing requires explicit confirmation (confirm: true)"));
+            }
+            
+            match storage.clear_all_data() {
+                Ok(_) => {
+                    Ok(json!({
+                        "success": true,
+                        "operation": "clear_graph",
+                        "message": "Graph cleared successfully"
+                    }))
+                },
+                Err(e) => Err(anyhow!("Failed to clear graph: {}", e))
+            }
+        },
+        "get_episodes" => {
+            // Read before cursor resolution, like `max_results` elsewhere,
+            // so a resumed cursor can still ask for a different page size.
+            let last_n = params.get("last_n")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10) as usize;
+            let (params, offset) = resolve_cursor(&params, "get_episodes")?;
+            let params = &params;
+            let group_id = params.get("group_id")
+                .and_then(|v| v.as_str());
+            let filter_str = params.get("filter").and_then(|v| v.as_str());
+            let filter_expr = filter_str
+                .map(parse_filter)
+                .transpose()
+                .map_err(|e| anyhow!("Invalid filter expression: {}", e))?;
+
+            match storage.get_recent_episodes(group_id, offset + last_n) {
+                Ok(all_episodes) => {
+                    let total_fetched = all_episodes.len();
+                    let unfiltered_page: Vec<_> = all_episodes.into_iter().skip(offset).take(last_n).collect();
+                    let next_cursor = (total_fetched > offset + unfiltered_page.len())
+                        .then(|| PageCursor::new("get_episodes", params.clone(), offset + unfiltered_page.len()).encode());
+
+                    // `filter` is applied after the page is sliced, like
+                    // every other post-scoring filter in this file, so a
+                    // page can come back with fewer than `last_n` entries
+                    // when some are filtered out, but the cursor still
+                    // advances by the unfiltered page size above.
+                    let page_episodes: Vec<_> = unfiltered_page.into_iter()
+                        .filter(|episode| filter_expr.as_ref().map_or(true, |expr| evaluate(expr, episode)))
+                        .collect();
+
+                    let results: Vec<Value> = page_episodes.into_iter().map(|episode| {
+                        verbosity.format_episode(&episode)
+                    }).collect();
+
+                    let additional_fields = json!({
+                        "group_id": group_id,
+                        "filter": filter_str
+                    });
+
+                    let mut response = verbosity.format_response_metadata("get_episodes", None, results.len(), Some(additional_fields));
+                    response["results"] = json!(results);
+                    if let Some(token) = next_cursor {
+                        response["next_cursor"] = json!(token);
+                    }
+                    Ok(response)
+                },
+                Err(e) => Err(anyhow!("Failed to retrieve episodes: {}", e))
+            }
+        },
+        "stats" => {
+            let group_ids: Vec<String> = params.get("group_ids")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default();
+
+            let counts = storage.graph_counts(&group_ids)?;
+
+            if matches!(verbosity, OutputVerbosity::Summary) {
+                return Ok(json!({
+                    "success": true,
+                    "operation": "stats",
+                    "group_ids": group_ids,
+                    "nodes": counts.nodes,
+                    "edges": counts.edges,
+                    "episodes": counts.episodes,
+                }));
+            }
+
+            let episodes_by_group = storage.episode_counts_by_group(&group_ids)?;
+            let cache_stats = embedding_engine.get_cache_stats().await.ok();
+            let memory_stats = memory_optimizer.get_memory_stats().await.ok();
+            let queue_stats = search_queue.stats();
+
+            let mut metrics = vec![
+                graph_stat_metric("graph_nodes_total", "gauge", json!(counts.nodes),
+                    "Total knowledge graph nodes, scoped to group_ids if given."),
+                graph_stat_metric("graph_edges_total", "gauge", json!(counts.edges),
+                    "Total knowledge graph edges, scoped to group_ids if given."),
+                graph_stat_metric("graph_episodes_total", "gauge", json!(counts.episodes),
+                    "Total ingested episodes, scoped to group_ids if given."),
+                graph_stat_metric("graph_episodes_by_group", "gauge", json!(episodes_by_group),
+                    "Episode count per group_id (ungrouped episodes under the key \"ungrouped\")."),
+                graph_stat_metric("indexing_throughput_episodes_per_sec", "gauge", Value::Null,
+                    "Not yet instrumented: index_codebase doesn't persist per-run throughput across calls."),
+                graph_stat_metric("indexing_throughput_files_per_sec", "gauge", Value::Null,
+                    "Not yet instrumented: index_codebase doesn't persist per-run throughput across calls."),
+                graph_stat_metric("query_latency_p50_ms", "gauge", Value::Null,
+                    "Not yet instrumented: no per-query latency histogram is recorded anywhere yet."),
+                graph_stat_metric("query_latency_p95_ms", "gauge", Value::Null,
+                    "Not yet instrumented: no per-query latency histogram is recorded anywhere yet."),
+                graph_stat_metric("query_latency_p99_ms", "gauge", Value::Null,
+                    "Not yet instrumented: no per-query latency histogram is recorded anywhere yet."),
+                graph_stat_metric("search_queue_running", "gauge", json!(queue_stats.running),
+                    "Calls currently executing through the search queue."),
+                graph_stat_metric("search_queue_waiting", "gauge", json!(queue_stats.waiting),
+                    "Calls currently queued awaiting a search-queue permit."),
+                graph_stat_metric("search_queue_rejected_total", "counter", json!(queue_stats.rejected_total),
+                    "Calls shed by the search queue's random-drop admission control."),
+            ];
+
+            if let Some(cache_stats) = cache_stats {
+                metrics.push(graph_stat_metric("embedding_batch_cache_used", "gauge", json!(cache_stats.batch_cache_used),
+                    "Entries currently held in the batch embedding cache."));
+                metrics.push(graph_stat_metric("embedding_batch_cache_capacity", "gauge", json!(cache_stats.batch_cache_capacity),
+                    "Maximum entries the batch embedding cache can hold before evicting."));
+                metrics.push(graph_stat_metric("embedding_onnx_cache_size", "gauge", json!(cache_stats.onnx_cache_size),
+                    "Entries currently held in the ONNX runtime's own embedding cache."));
+            }
+
+            if let Some(stats) = memory_stats {
+                let cache = &stats.cache_statistics;
+                let hits = cache.l1_hits + cache.l2_hits + cache.l3_hits + cache.embedding_hits + cache.query_hits;
+                let misses = cache.l1_misses + cache.l2_misses + cache.l3_misses + cache.embedding_misses + cache.query_misses;
+                let hit_rate = if hits + misses > 0 { Some(hits as f64 / (hits + misses) as f64) } else { None };
+                metrics.push(graph_stat_metric("memory_cache_hits_total", "counter", json!(hits),
+                    "Combined L1/L2/L3/embedding/query cache hits since startup."));
+                metrics.push(graph_stat_metric("memory_cache_misses_total", "counter", json!(misses),
+                    "Combined L1/L2/L3/embedding/query cache misses since startup."));
+                metrics.push(graph_stat_metric("memory_cache_hit_rate", "gauge", json!(hit_rate),
+                    "hits / (hits + misses) across all memory caches; null until at least one lookup has happened."));
+                metrics.push(graph_stat_metric("memory_cache_evictions_total", "counter", json!(cache.evictions),
+                    "Entries evicted from the memory caches since startup."));
+                metrics.push(graph_stat_metric("memory_cache_bytes", "gauge", json!(cache.total_memory_used),
+                    "Estimated bytes currently held across the memory caches."));
+            }
+
+            if matches!(verbosity, OutputVerbosity::Full) {
+                for (op, io) in storage.io_stats_snapshot() {
+                    metrics.push(graph_stat_metric(&format!("storage_io_{op}_reads_total"), "counter", json!(io.reads),
+                        &format!("Row reads recorded against the `{op}` storage operation since startup.")));
+                    metrics.push(graph_stat_metric(&format!("storage_io_{op}_writes_total"), "counter", json!(io.writes),
+                        &format!("Row writes recorded against the `{op}` storage operation since startup.")));
+                    metrics.push(graph_stat_metric(&format!("storage_io_{op}_bytes_total"), "counter", json!(io.bytes),
+                        &format!("Bytes touched by the `{op}` storage operation since startup.")));
+                }
+            } else {
+                // Compact verbosity: keep the list to the headline metrics
+                // above and drop the long tail of descriptions.
+                for m in metrics.iter_mut() {
+                    if let Some(obj) = m.as_object_mut() {
+                        obj.remove("desc");
+                    }
+                }
+            }
+
+            Ok(json!({
+                "success": true,
+                "operation": "stats",
+                "group_ids": group_ids,
+                "metrics": metrics,
+            }))
+        },
+        "set_retention" => {
+            let confirm = params.get("confirm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            if !confirm {
+                return Err(anyhow!("Setting a retention policy requires explicit confirmation (confirm: true), since it prunes episodes immediately"));
+            }
+
+            let group_id = params.get("group_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("ungrouped")
+                .to_string();
+            let max_age_days = params.get("max_age_days").and_then(|v| v.as_i64());
+            let max_episodes = params.get("max_episodes").and_then(|v| v.as_i64());
+            let preserve_entities = params.get("preserve_entities")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+
+            if max_age_days.is_none() && max_episodes.is_none() {
+                return Err(anyhow!("set_retention requires at least one of max_age_days or max_episodes"));
+            }
+
+            storage.set_retention_policy(&group_id, max_age_days, max_episodes, preserve_entities)?;
+
+            let policy = RetentionPolicy {
+                group_id: group_id.clone(),
+                max_age_days,
+                max_episodes,
+                preserve_entities,
+                updated_at: String::new(),
+            };
+            let result = storage.apply_retention_policy(&policy)?;
+
+            Ok(json!({
+                "success": true,
+                "operation": "set_retention",
+                "group_id": group_id,
+                "max_age_days": max_age_days,
+                "max_episodes": max_episodes,
+                "preserve_entities": preserve_entities,
+                "episodes_pruned": result.episodes_pruned,
+                "bytes_reclaimed": result.bytes_reclaimed,
+                "nodes_gc": result.nodes_gc,
+                "edges_gc": result.edges_gc,
+            }))
+        },
+        "compact" => {
+            let confirm = params.get("confirm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            if !confirm {
+                return Err(anyhow!("Compaction requires explicit confirmation (confirm: true)"));
+            }
+
+            let batch_size = params.get("batch_size")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(100) as usize;
+            let (params, offset) = resolve_cursor(&params, "compact")?;
+            let params = &params;
+            let group_id = params.get("group_id").and_then(|v| v.as_str());
+
+            let (result, has_more) = storage.compact_episodes(group_id, offset, batch_size)?;
+            let (nodes_gc, edges_gc) = storage.gc_orphaned_entities()?;
+
+            let next_cursor = has_more
+                .then(|| PageCursor::new("compact", params.clone(), offset + batch_size).encode());
+
+            let mut response = json!({
+                "success": true,
+                "operation": "compact",
+                "group_id": group_id,
+                "episodes_merged": result.episodes_merged,
+                "bytes_reclaimed": result.bytes_reclaimed,
+                "nodes_gc": nodes_gc,
+                "edges_gc": edges_gc,
+            });
+            if let Some(token) = next_cursor {
+                response["next_cursor"] = json!(token);
+            }
+            Ok(response)
+        },
+        "pin" | "unpin" => {
+            let kind = match params.get("alias_kind").and_then(|v| v.as_str()) {
+                Some("uuid") => AliasKind::Uuid,
+                Some("group_id") => AliasKind::GroupId,
+                Some(other) => return Err(anyhow!("Unknown alias_kind: {} (expected 'uuid' or 'group_id')", other)),
+                None => return Err(anyhow!("Missing required parameter: alias_kind")),
+            };
+            let value = params.get("alias_value")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: alias_value"))?;
+
+            if operation == "pin" {
+                storage.pin(kind, value)?;
+            } else {
+                storage.unpin(kind, value)?;
+            }
+
+            Ok(json!({
+                "success": true,
+                "operation": operation,
+                "alias_kind": kind.as_db_str(),
+                "alias_value": value,
+            }))
+        },
+        "list_pins" => {
+            let pins = storage.list_pins()?;
+            let pins: Vec<Value> = pins.into_iter()
+                .map(|(kind, value)| json!({ "alias_kind": kind.as_db_str(), "alias_value": value }))
+                .collect();
+
+            Ok(json!({
+                "success": true,
+                "operation": "list_pins",
+                "pins": pins,
+            }))
+        },
+        "gc" => {
+            let confirm = params.get("confirm")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            if !confirm {
+                return Err(anyhow!("GC requires explicit confirmation (confirm: true)"));
+            }
+
+            let size_targets = params.get("max_bytes")
+                .and_then(|v| v.as_u64())
+                .map(|max_bytes| SizeTargets { max_bytes: Some(max_bytes) });
+
+            let stats = storage.gc(size_targets)?;
+
+            Ok(json!({
+                "success": true,
+                "operation": "gc",
+                "nodes_deleted": stats.nodes_deleted,
+                "edges_deleted": stats.edges_deleted,
+                "bytes_reclaimed": stats.bytes_reclaimed,
+                "vacuumed": stats.vacuumed,
             }))
         },
-        "delete_entity_edge" => {
+        "node_history" | "edge_history" => {
             let uuid_str = params.get("uuid")
                 .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("UUID required for delete_entity_edge operation"))?;
-            
+                .ok_or_else(|| anyhow!("UUID required for {} operation", operation))?;
             let uuid = uuid::Uuid::parse_str(uuid_str)
                 .map_err(|e| anyhow!("Invalid UUID format: {}", e))?;
-            
-            match storage.delete_edge(&uuid) {
-                Ok(_) => {
-                    Ok(json!({
-                        "success": true,
-                        "operation": "delete_entity_edge",
-                        "uuid": uuid,
-                        "message": "Entity edge deleted successfully"
-                    }))
-                },
-                Err(e) => Err(anyhow!("Failed to delete entity edge: {}", e))
+
+            if operation == "node_history" {
+                let history = storage.get_node_history(uuid)?;
+                let history: Vec<Value> = history.iter().map(|n| verbosity.format_node(n)).collect();
+                Ok(json!({ "success": true, "operation": "node_history", "uuid": uuid, "history": history }))
+            } else {
+                let history = storage.get_edge_history(uuid)?;
+                let mut formatted = Vec::with_capacity(history.len());
+                for edge in &history {
+                    let source_node = storage.get_node(edge.source_node_uuid)?;
+                    let target_node = storage.get_node(edge.target_node_uuid)?;
+                    formatted.push(verbosity.format_edge(edge, source_node.as_ref(), target_node.as_ref()));
+                }
+                Ok(json!({ "success": true, "operation": "edge_history", "uuid": uuid, "history": formatted }))
             }
         },
-        "delete_episode" => {
+        "node_as_of" | "edge_as_of" => {
             let uuid_str = params.get("uuid")
                 .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("UUID required for delete_episode operation"))?;
-            
+                .ok_or_else(|| anyhow!("UUID required for {} operation", operation))?;
             let uuid = uuid::Uuid::parse_str(uuid_str)
                 .map_err(|e| anyhow!("Invalid UUID format: {}", e))?;
-            
-            match storage.delete_episode(&uuid) {
-                Ok(_) => {
-                    Ok(json!({
-                        "success": true,
-                        "operation": "delete_episode",
-                        "uuid": uuid,
-                        "message": "Episode deleted successfully"
-                    }))
-                },
-                Err(e) => Err(anyhow!("Failed to delete episode: {}", e))
+
+            let at_str = params.get("at")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing required parameter: at (ISO 8601 timestamp)"))?;
+            let at = chrono::DateTime::parse_from_rfc3339(at_str)
+                .map_err(|e| anyhow!("Invalid timestamp in 'at': {}", e))?
+                .with_timezone(&chrono::Utc);
+
+            if operation == "node_as_of" {
+                let node = storage.get_node_as_of(uuid, at)?;
+                Ok(json!({
+                    "success": true,
+                    "operation": "node_as_of",
+                    "uuid": uuid,
+                    "at": at.to_rfc3339(),
+                    "node": node.as_ref().map(|n| verbosity.format_node(n)),
+                }))
+            } else {
+                let edge = storage.get_edge_as_of(uuid, at)?;
+                let formatted = match &edge {
+                    Some(e) => {
+                        let source_node = storage.get_node(e.source_node_uuid)?;
+                        let target_node = storage.get_node(e.target_node_uuid)?;
+                        Some(verbosity.format_edge(e, source_node.as_ref(), target_node.as_ref()))
+                    },
+                    None => None,
+                };
+                Ok(json!({
+                    "success": true,
+                    "operation": "edge_as_of",
+                    "uuid": uuid,
+                    "at": at.to_rfc3339(),
+                    "edge": formatted,
+                }))
             }
         },
-        "delete_batch" => {
-            let uuids = params.get("uuids")
-                .and_then(|v| v.as_array())
-                .ok_or_else(|| anyhow!("UUIDs array required for delete_batch operation"))?;
-            
-            let confirm = params.get("confirm")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-            
-            if !confirm {
-                return Err(anyhow!("Batch deletion requires explicit confirmation (confirm: true)"));
-            }
-            
-            let delete_type = params.get("delete_type")
+        "revert_node" | "revert_edge" => {
+            let uuid_str = params.get("uuid")
                 .and_then(|v| v.as_str())
-                .unwrap_or("mixed");
-            
-            let mut deleted_count = 0;
-            let mut errors = Vec::new();
-            
-            for uuid_val in uuids {
-                if let Some(uuid_str) = uuid_val.as_str() {
-                    match uuid::Uuid::parse_str(uuid_str) {
-                        Ok(uuid) => {
-                            // For now, only handle episode deletion
-                            match storage.delete_episode(&uuid) {
-                                Ok(_) => deleted_count += 1,
-                                Err(e) => errors.push(format!("Failed to delete {}: {}", uuid, e))
-                            }
-                        },
-                        Err(e) => errors.push(format!("Invalid UUID {}: {}", uuid_str, e))
-                    }
-                }
+                .ok_or_else(|| anyhow!("UUID required for {} operation", operation))?;
+            let uuid = uuid::Uuid::parse_str(uuid_str)
+                .map_err(|e| anyhow!("Invalid UUID format: {}", e))?;
+
+            let revision_seq = params.get("revision_seq")
+                .and_then(|v| v.as_i64())
+                .ok_or_else(|| anyhow!("Missing required parameter: revision_seq"))?;
+
+            if operation == "revert_node" {
+                storage.revert_node(uuid, revision_seq)?;
+            } else {
+                storage.revert_edge(uuid, revision_seq)?;
             }
-            
+
             Ok(json!({
                 "success": true,
-                "operation": "delete_batch",
-                "delete_type": delete_type,
-                "deleted_count": deleted_count,
-                "total_requested": uuids.len(),
-                "errors": errors
+                "operation": operation,
+                "uuid": uuid,
+                "revision_seq": revision_seq,
             }))
         },
-        "clear_graph" => {
-            let confirm = params.get("confirm")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-            
-            if !confirm {
-                return Err(anyhow!("Graph clearing requires explicit confirmation (confirm: true)"));
-            }
-            
-            match storage.clear_all_data() {
-                Ok(_) => {
-                    Ok(json!({
-                        "success": true,
-                        "operation": "clear_graph",
-                        "message": "Graph cleared successfully"
-                    }))
-                },
-                Err(e) => Err(anyhow!("Failed to clear graph: {}", e))
-            }
-        },
-        "get_episodes" => {
-            let group_id = params.get("group_id")
-                .and_then(|v| v.as_str());
-            let last_n = params.get("last_n")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(10) as usize;
-            
-            match storage.get_recent_episodes(group_id, last_n) {
-                Ok(episodes) => {
-                    let results: Vec<Value> = episodes.into_iter().map(|episode| {
-                        verbosity.format_episode(&episode)
-                    }).collect();
-                    
-                    let additional_fields = json!({
-                        "group_id": group_id
-                    });
-                    
-                    let mut response = verbosity.format_response_metadata("get_episodes", None, results.len(), Some(additional_fields));
-                    response["results"] = json!(results);
-                    Ok(response)
-                },
-                Err(e) => Err(anyhow!("Failed to retrieve episodes: {}", e))
+        "node_siblings" | "edge_siblings" => {
+            let uuid_str = params.get("uuid")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("UUID required for {} operation", operation))?;
+            let uuid = uuid::Uuid::parse_str(uuid_str)
+                .map_err(|e| anyhow!("Invalid UUID format: {}", e))?;
+
+            if operation == "node_siblings" {
+                let siblings = storage.get_node_siblings(uuid)?;
+                let siblings: Vec<Value> = siblings.iter().map(|n| verbosity.format_node(n)).collect();
+                Ok(json!({ "success": true, "operation": "node_siblings", "uuid": uuid, "siblings": siblings }))
+            } else {
+                let siblings = storage.get_edge_siblings(uuid)?;
+                let mut formatted = Vec::with_capacity(siblings.len());
+                for edge in &siblings {
+                    let source_node = storage.get_node(edge.source_node_uuid)?;
+                    let target_node = storage.get_node(edge.target_node_uuid)?;
+                    formatted.push(verbosity.format_edge(edge, source_node.as_ref(), target_node.as_ref()));
+                }
+                Ok(json!({ "success": true, "operation": "edge_siblings", "uuid": uuid, "siblings": formatted }))
             }
         },
         _ => Err(anyhow!("Unknown management operation: {}", operation))
@@ -924,15 +3262,18 @@ async fn handle_index_codebase(
     params: Value,
     storage: &Arc<GraphStorage>,
     embedding_engine: &Arc<LocalEmbeddingEngine>,
+    watch_manager: &Arc<IndexWatchManager>,
+    progress: &Option<(Value, ProgressSink)>,
 ) -> Result<Value> {
     let operation = params.get("operation")
         .and_then(|v| v.as_str())
         .ok_or_else(|| anyhow!("Missing required parameter: operation"))?;
-    
+
     let verbosity = OutputVerbosity::from_params(&params);
-    
+
     match operation {
         "index" | "reindex" => {
+            emit_progress(progress, 0.0, 1.0, "Indexing started: extracting entities and generating embeddings");
             let path = params.get("path")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| anyhow!("Path required for index/reindex operation"))?;
@@ -968,11 +3309,23 @@ async fn handle_index_codebase(
             let extract_symbols = params.get("extract_symbols")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(true);
-            
+
+            let extract_history = params.get("extract_history")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
             let group_id = params.get("group_id")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
-            
+
+            let cache_dir = params.get("cache_dir")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let profile = params.get("profile")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
             // Create indexing configuration
             let config = IndexingConfig {
                 languages,
@@ -983,13 +3336,15 @@ async fn handle_index_codebase(
                 incremental,
                 extract_dependencies,
                 extract_symbols,
+                extract_history,
                 group_id,
+                cache_dir,
             };
             
             // Create and run indexer
-            let indexer = CodebaseIndexer::new_with_mcp_config(path.to_string(), config.clone());
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings(path.to_string(), config.clone(), Some(embedding_engine.embedding_config()));
             
-            match indexer.index_codebase_mcp(path, storage.clone(), embedding_engine.clone()).await {
+            match indexer.index_codebase_mcp(path, storage.clone(), embedding_engine.clone(), profile).await {
                 Ok(result) => {
                     let response = match verbosity {
                         OutputVerbosity::Summary => json!({
@@ -1019,7 +3374,8 @@ async fn handle_index_codebase(
                                 "parallel_workers": config.parallel_workers,
                                 "incremental": config.incremental,
                                 "extract_dependencies": config.extract_dependencies,
-                                "extract_symbols": config.extract_symbols
+                                "extract_symbols": config.extract_symbols,
+                                "extract_history": config.extract_history
                             },
                             "results": {
                                 "files_processed": result.files_processed,
@@ -1027,24 +3383,309 @@ async fn handle_index_codebase(
                                 "dependencies_mapped": result.dependencies_mapped,
                                 "processing_time_ms": result.processing_time_ms,
                                 "languages_detected": result.languages_detected,
-                                "errors": result.errors
+                                "errors": result.errors,
+                                "profile": result.profile
                             }
                         })
                     };
+                    emit_progress(progress, 1.0, 1.0, &format!("Indexing complete: {} files processed", result.files_processed));
                     Ok(response)
                 },
                 Err(e) => Err(anyhow!("Indexing failed: {}", e))
             }
         },
         "status" => {
-            // Return indexing status (placeholder implementation)
+            let path = params.get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Path required for status operation"))?;
+            let cache_dir = params.get("cache_dir")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            let config = IndexingConfig { cache_dir, ..IndexingConfig::default() };
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings(path.to_string(), config, Some(embedding_engine.embedding_config()));
+            let manifest = match indexer.file_index_cache() {
+                Some(cache) => cache.manifest().await?,
+                None => Vec::new(),
+            };
+
+            let total_indexed_files = manifest.len();
+            let mut symbol_counts_by_language: HashMap<String, usize> = HashMap::new();
+            let mut last_run_at: Option<i64> = None;
+            for entry in &manifest {
+                *symbol_counts_by_language.entry(entry.language.clone()).or_insert(0) += entry.symbol_count;
+                last_run_at = Some(last_run_at.map_or(entry.indexed_at, |current| current.max(entry.indexed_at)));
+            }
+
+            // A file is stale once its on-disk content hash no longer
+            // matches what the manifest recorded (it was edited since its
+            // last index run) or it's gone entirely (deleted since then).
+            let mut stale_files = Vec::new();
+            for entry in &manifest {
+                match tokio::fs::read(&entry.file_path).await {
+                    Ok(bytes) => {
+                        if crate::indexing::file_index_cache::content_hash(&bytes) != entry.content_hash {
+                            stale_files.push(entry.file_path.clone());
+                        }
+                    }
+                    Err(_) => stale_files.push(entry.file_path.clone()),
+                }
+            }
+
             Ok(json!({
                 "success": true,
                 "operation": "status",
                 "status": "ready",
-                "message": "Indexing status check available but not yet implemented"
+                "path": path,
+                "total_indexed_files": total_indexed_files,
+                "symbol_counts_by_language": symbol_counts_by_language,
+                "last_run_at": last_run_at,
+                "stale_files": stale_files
+            }))
+        },
+        "watch" => {
+            let watch_action = params.get("watch_action")
+                .and_then(|v| v.as_str())
+                .unwrap_or("start");
+
+            match watch_action {
+                "start" => {
+                    let path = params.get("path")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| anyhow!("Path required for watch start"))?;
+                    let watch_id = params.get("watch_id")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| path.to_string());
+                    let debounce_ms = params.get("debounce_ms").and_then(|v| v.as_u64()).unwrap_or(500);
+                    let parallel_workers = params.get("parallel_workers")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(4) as usize;
+                    let cache_dir = params.get("cache_dir").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    let group_id = params.get("group_id").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+                    let config = IndexingConfig {
+                        parallel_workers,
+                        cache_dir,
+                        group_id,
+                        ..IndexingConfig::default()
+                    };
+                    let indexer = Arc::new(CodebaseIndexer::new_with_mcp_config_and_embeddings(path.to_string(), config, Some(embedding_engine.embedding_config())));
+                    let watcher_config = WatcherConfig {
+                        enabled: true,
+                        watch_paths: vec![PathBuf::from(path)],
+                        debounce_ms,
+                    };
+
+                    watch_manager.start(watch_id.clone(), watcher_config, storage.clone(), indexer).await?;
+
+                    Ok(json!({
+                        "success": true,
+                        "operation": "watch",
+                        "watch_action": "start",
+                        "watch_id": watch_id
+                    }))
+                },
+                "stop" => {
+                    let watch_id = params.get("watch_id")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| anyhow!("watch_id required for watch stop"))?;
+
+                    watch_manager.stop(watch_id).await?;
+
+                    Ok(json!({
+                        "success": true,
+                        "operation": "watch",
+                        "watch_action": "stop",
+                        "watch_id": watch_id
+                    }))
+                },
+                "status" => {
+                    let watches = watch_manager.status().await;
+                    Ok(json!({
+                        "success": true,
+                        "operation": "watch",
+                        "watch_action": "status",
+                        "watches": watches
+                    }))
+                },
+                other => Err(anyhow!("Unknown watch_action '{}'; expected 'start', 'stop', or 'status'", other)),
+            }
+        },
+        "call_hierarchy" => {
+            let path = params.get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Path required for call_hierarchy operation"))?;
+            let symbol = params.get("symbol")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Symbol required for call_hierarchy operation"))?;
+            let cache_dir = params.get("cache_dir").and_then(|v| v.as_str()).map(|s| s.to_string());
+            let suggest = params.get("suggest").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            // Re-indexes `path` into a throwaway indexer so `call_graph` is
+            // populated for this call, the same approach `index`/`reindex`
+            // use rather than assuming some other call already primed a
+            // long-lived indexer.
+            let config = IndexingConfig { cache_dir, ..IndexingConfig::default() };
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings(path.to_string(), config, Some(embedding_engine.embedding_config()));
+            indexer.index_codebase_mcp(path, storage.clone(), embedding_engine.clone(), false).await?;
+
+            match indexer.call_hierarchy_mcp(symbol, None).await {
+                CallHierarchyResult::Candidates(candidates) => {
+                    // `candidates` above is already a fuzzy ("did you mean")
+                    // fallback (see `call_hierarchy_mcp`'s `fuzzy_match_score`
+                    // pass), scored for fzf-style incremental typing. `suggest`
+                    // additionally runs the trigram/edit-distance suggester
+                    // tuned for genuine misspellings instead.
+                    let suggestions = if suggest {
+                        Some(indexer.suggest_symbol_mcp(symbol, 10).await)
+                    } else {
+                        None
+                    };
+                    Ok(json!({
+                        "success": true,
+                        "operation": "call_hierarchy",
+                        "symbol": symbol,
+                        "resolved": false,
+                        "candidates": candidates,
+                        "suggestions": suggestions
+                    }))
+                },
+                CallHierarchyResult::Found { symbol, incoming, outgoing } => {
+                    let response = match verbosity {
+                        OutputVerbosity::Summary => json!({
+                            "success": true,
+                            "operation": "call_hierarchy",
+                            "symbol": symbol,
+                            "resolved": true,
+                            "incoming_count": incoming.len(),
+                            "outgoing_count": outgoing.len()
+                        }),
+                        OutputVerbosity::Compact => json!({
+                            "success": true,
+                            "operation": "call_hierarchy",
+                            "symbol": symbol,
+                            "resolved": true,
+                            "callers": incoming.iter().map(|e| e.symbol.clone()).collect::<Vec<_>>(),
+                            "callees": outgoing.iter().map(|e| e.symbol.clone()).collect::<Vec<_>>()
+                        }),
+                        OutputVerbosity::Full => json!({
+                            "success": true,
+                            "operation": "call_hierarchy",
+                            "symbol": symbol,
+                            "resolved": true,
+                            "incoming": incoming.iter().map(|e| json!({
+                                "symbol": e.symbol,
+                                "file": e.file,
+                                "line": e.line
+                            })).collect::<Vec<_>>(),
+                            "outgoing": outgoing.iter().map(|e| json!({
+                                "symbol": e.symbol,
+                                "file": e.file,
+                                "line": e.line
+                            })).collect::<Vec<_>>()
+                        })
+                    };
+                    Ok(response)
+                }
+            }
+        },
+        "suggest_symbol" => {
+            let path = params.get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Path required for suggest_symbol operation"))?;
+            let symbol = params.get("symbol")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Symbol required for suggest_symbol operation"))?;
+            let cache_dir = params.get("cache_dir").and_then(|v| v.as_str()).map(|s| s.to_string());
+            let max_results = params.get("max_results")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10) as usize;
+
+            let config = IndexingConfig { cache_dir, ..IndexingConfig::default() };
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings(path.to_string(), config, Some(embedding_engine.embedding_config()));
+            indexer.index_codebase_mcp(path, storage.clone(), embedding_engine.clone(), false).await?;
+
+            let suggestions = indexer.suggest_symbol_mcp(symbol, max_results).await;
+            Ok(json!({
+                "success": true,
+                "operation": "suggest_symbol",
+                "symbol": symbol,
+                "suggestions": suggestions
             }))
         },
+        "search" => {
+            let query = params.get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Query required for search operation"))?;
+            let max_results = params.get("max_results")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(50) as usize;
+
+            // Builds its inverted index straight off storage's already-
+            // persisted episodes, rather than a throwaway `CodebaseIndexer`
+            // (see `tfidf` module docs) — there's no per-path re-indexing
+            // step here, so this ranks over everything ingested so far
+            // across every indexed path, not just one.
+            let mut episodes = Vec::new();
+            let mut offset = 0;
+            let page_size = 500;
+            loop {
+                let page = storage.get_episodes_page(offset, page_size)?;
+                if page.is_empty() {
+                    break;
+                }
+                let page_len = page.len();
+                episodes.extend(page);
+                offset += page_len;
+            }
+
+            let index = crate::search::TfIdfIndex::build(&episodes);
+            let hits = index.search(query, max_results);
+
+            let response = match verbosity {
+                OutputVerbosity::Summary => json!({
+                    "success": true,
+                    "operation": "search",
+                    "query": query,
+                    "count": hits.len(),
+                    "ids": hits.iter().map(|h| h.doc_id).collect::<Vec<_>>()
+                }),
+                OutputVerbosity::Compact => json!({
+                    "success": true,
+                    "operation": "search",
+                    "query": query,
+                    "results": hits.iter().map(|h| {
+                        let episode = index.episode(h.doc_id);
+                        json!({
+                            "id": h.doc_id,
+                            "name": episode.name,
+                            "file": episode.source_description,
+                            "score": h.score
+                        })
+                    }).collect::<Vec<_>>()
+                }),
+                OutputVerbosity::Full => json!({
+                    "success": true,
+                    "operation": "search",
+                    "query": query,
+                    "results": hits.iter().map(|h| {
+                        let episode = index.episode(h.doc_id);
+                        json!({
+                            "id": h.doc_id,
+                            "name": episode.name,
+                            "file": episode.source_description,
+                            "score": h.score,
+                            "matched_terms": h.term_scores.iter().map(|(term, score)| json!({
+                                "term": term,
+                                "score": score
+                            })).collect::<Vec<_>>()
+                        })
+                    }).collect::<Vec<_>>()
+                })
+            };
+            Ok(response)
+        },
         "search_code" => {
             let query = params.get("query")
                 .and_then(|v| v.as_str())
@@ -1061,12 +3702,16 @@ async fn handle_index_codebase(
             let max_results = params.get("max_results")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(50) as usize;
-            
+
+            let min_complexity = params.get("min_complexity").and_then(|v| v.as_u64()).map(|v| v as u32);
+            let min_entropy = params.get("min_entropy").and_then(|v| v.as_f64());
+            let rank_by_recency = params.get("rank_by_recency").and_then(|v| v.as_bool()).unwrap_or(false);
+
             // Perform code search using the indexer
             let config = IndexingConfig::default();
-            let indexer = CodebaseIndexer::new_with_mcp_config("".to_string(), config);
-            
-            match indexer.search_code_mcp(query, symbol_type, context_lines, max_results, storage.clone()).await {
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings("".to_string(), config, Some(embedding_engine.embedding_config()));
+
+            match indexer.search_code_mcp(query, symbol_type, context_lines, max_results, storage.clone(), min_complexity, min_entropy, rank_by_recency).await {
                 Ok(results) => {
                     let formatted_results: Vec<Value> = results.into_iter().map(|result| {
                         match verbosity {
@@ -1080,25 +3725,36 @@ async fn handle_index_codebase(
                                 "symbol": result.symbol_name,
                                 "symbol_type": result.symbol_type,
                                 "line": result.line_number,
-                                "context": result.context_lines.join("\n")
+                                "end_line": result.end_line,
+                                "context": result.context_lines.join("\n"),
+                                "complexity": result.complexity,
+                                "entropy": result.entropy,
+                                "last_commit_date": result.last_commit_date
                             }),
                             OutputVerbosity::Full => json!({
                                 "file": result.file_path,
                                 "symbol": result.symbol_name,
                                 "symbol_type": result.symbol_type,
                                 "line": result.line_number,
+                                "end_line": result.end_line,
                                 "column": result.column_number,
                                 "context_lines": result.context_lines,
                                 "full_context": result.full_context,
                                 "language": result.language,
-                                "relevance_score": result.relevance_score
+                                "relevance_score": result.relevance_score,
+                                "complexity": result.complexity,
+                                "entropy": result.entropy,
+                                "last_commit_date": result.last_commit_date
                             })
                         }
                     }).collect();
-                    
+
                     let mut response = verbosity.format_response_metadata("search_code", Some(query), formatted_results.len(), Some(json!({
                         "symbol_type": symbol_type,
-                        "context_lines": context_lines
+                        "context_lines": context_lines,
+                        "min_complexity": min_complexity,
+                        "min_entropy": min_entropy,
+                        "rank_by_recency": rank_by_recency
                     })));
                     response["results"] = json!(formatted_results);
                     Ok(response)
@@ -1112,7 +3768,7 @@ async fn handle_index_codebase(
                 .ok_or_else(|| anyhow!("File path required for get_dependencies operation"))?;
             
             let config = IndexingConfig::default();
-            let indexer = CodebaseIndexer::new_with_mcp_config("".to_string(), config);
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings("".to_string(), config, Some(embedding_engine.embedding_config()));
             
             match indexer.get_file_dependencies_mcp(file_path, storage.clone()).await {
                 Ok(dependencies) => {
@@ -1155,10 +3811,22 @@ async fn handle_index_codebase(
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| anyhow!("Path required for analyze_structure operation"))?;
             
+            let use_cargo_metadata = params.get("use_cargo_metadata")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let profile = params.get("profile")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let include_non_source = params.get("include_non_source")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
             let config = IndexingConfig::default();
-            let indexer = CodebaseIndexer::new_with_mcp_config(path.to_string(), config);
-            
-            match indexer.analyze_codebase_structure_mcp(storage.clone()).await {
+            let indexer = CodebaseIndexer::new_with_mcp_config_and_embeddings(path.to_string(), config, Some(embedding_engine.embedding_config()));
+
+            match indexer.analyze_codebase_structure_mcp(storage.clone(), use_cargo_metadata, Path::new(path), profile, include_non_source).await {
                 Ok(analysis) => {
                     let response = match verbosity {
                         OutputVerbosity::Summary => json!({
@@ -1186,7 +3854,10 @@ async fn handle_index_codebase(
                                 "directory_structure": analysis.directory_structure,
                                 "file_types": analysis.file_types,
                                 "complexity_metrics": analysis.complexity_metrics,
-                                "dependency_graph": analysis.dependency_graph
+                                "dependency_graph": analysis.dependency_graph,
+                                "circular_dependencies": analysis.circular_dependencies,
+                                "cargo_workspace": analysis.cargo_workspace,
+                                "profile": analysis.profile
                             }
                         })
                     };
@@ -2,12 +2,16 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
 use sha2::{Sha256, Digest};
 
+use super::errors::McpError;
+
 /// Performance metrics for MCP operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
@@ -15,8 +19,16 @@ pub struct PerformanceMetrics {
     pub successful_requests: u64,
     pub failed_requests: u64,
     pub average_response_time: Duration,
+    pub latency_percentiles: LatencyPercentiles,
     pub tool_metrics: HashMap<String, ToolMetrics>,
+    /// Exponentially-weighted moving average over recent `tick_window`
+    /// periods (see `ResponseCache::tick_window`), not an all-time ratio -
+    /// reflects recent cache behavior rather than being dragged toward
+    /// whatever happened at startup.
     pub cache_hit_rate: f32,
+    pub cache_hits_total: u64,
+    pub cache_misses_total: u64,
+    pub cache_evictions_total: u64,
     pub memory_usage: u64,
     pub uptime: Duration,
     pub last_updated: chrono::DateTime<chrono::Utc>,
@@ -32,14 +44,161 @@ pub struct ToolMetrics {
     pub average_duration: Duration,
     pub min_duration: Duration,
     pub max_duration: Duration,
+    pub latency_percentiles: LatencyPercentiles,
     pub last_called: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// p50/p90/p95/p99/p999 read off a [`LatencyHistogram`] - tail behavior a
+/// single `average_duration` hides, since a mean can look healthy while p99
+/// is terrible.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+}
+
+/// Fixed-size logarithmic-bucket latency histogram, recorded in
+/// microseconds. Below `SUB_BUCKETS` values get one bucket each; above it,
+/// each doubling of the value range (`[2^e, 2^(e+1))`) is split into
+/// `SUB_BUCKETS` equal-width sub-buckets, giving a bounded relative error of
+/// about `1 / SUB_BUCKETS` with a fixed, small bucket count - unlike the
+/// `Vec<Duration>` this replaces, recording a sample is an `O(1)`
+/// bucket-index computation plus one `AtomicU64::fetch_add`, with no
+/// per-sample storage and so no cap or `drain` needed to bound memory.
+pub struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+}
+
+impl LatencyHistogram {
+    /// `k`: log2 of the sub-bucket count per exponent group. 5 gives 32
+    /// sub-buckets per doubling, i.e. ~3% relative error - tight enough for
+    /// p99/p999 latency reporting without a large bucket count.
+    const BUCKET_BITS: u32 = 5;
+    const SUB_BUCKETS: u64 = 1 << Self::BUCKET_BITS;
+    /// One linear group below `SUB_BUCKETS`, then one log group per
+    /// remaining bit of a u64 microsecond value - covers the full range a
+    /// `Duration` can report without ever needing to grow.
+    const NUM_BUCKETS: usize = (Self::SUB_BUCKETS as usize) * (65 - Self::BUCKET_BITS as usize);
+
+    pub fn new() -> Self {
+        let mut buckets = Vec::with_capacity(Self::NUM_BUCKETS);
+        buckets.resize_with(Self::NUM_BUCKETS, || AtomicU64::new(0));
+        Self { buckets }
+    }
+
+    fn bucket_index(value_us: u64) -> usize {
+        if value_us < Self::SUB_BUCKETS {
+            return value_us as usize;
+        }
+        let msb = 63 - value_us.leading_zeros() as u64;
+        let group = msb - Self::BUCKET_BITS as u64 + 1;
+        let shift = group - 1;
+        let sub = (value_us >> shift) - Self::SUB_BUCKETS;
+        Self::SUB_BUCKETS as usize * group as usize + sub as usize
+    }
+
+    /// The representative value callers get back for a quantile landing in
+    /// bucket `idx` - the midpoint of that bucket's value range, so the
+    /// reported figure isn't biased to the range's low end.
+    fn value_for_bucket(idx: usize) -> u64 {
+        if idx < Self::SUB_BUCKETS as usize {
+            return idx as u64;
+        }
+        let group = (idx / Self::SUB_BUCKETS as usize) as u64;
+        let sub = (idx % Self::SUB_BUCKETS as usize) as u64;
+        let shift = group - 1;
+        let lower = (Self::SUB_BUCKETS + sub) << shift;
+        let width = 1u64 << shift;
+        lower + width / 2
+    }
+
+    pub fn record(&self, duration: Duration) {
+        let micros = duration.as_micros().min(u64::MAX as u128) as u64;
+        let idx = Self::bucket_index(micros).min(self.buckets.len() - 1);
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Sums every bucket's count, then walks buckets in order until the
+    /// running sum crosses `q * total`, returning that bucket's
+    /// representative value - the query-time half of the algorithm
+    /// described on `LatencyHistogram`.
+    pub fn percentile(&self, q: f64) -> Duration {
+        let counts: Vec<u64> = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return Duration::from_micros(0);
+        }
+
+        let threshold = ((q.clamp(0.0, 1.0)) * total as f64).ceil().max(1.0) as u64;
+        let mut cumulative = 0u64;
+        for (idx, count) in counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= threshold {
+                return Duration::from_micros(Self::value_for_bucket(idx));
+            }
+        }
+        Duration::from_micros(0)
+    }
+
+    pub fn percentiles(&self) -> LatencyPercentiles {
+        LatencyPercentiles {
+            p50: self.percentile(0.50),
+            p90: self.percentile(0.90),
+            p95: self.percentile(0.95),
+            p99: self.percentile(0.99),
+            p999: self.percentile(0.999),
+        }
+    }
+
+    /// `(le_seconds, cumulative_count)` pairs for a Prometheus-style
+    /// cumulative histogram, skipping empty buckets so the exposition
+    /// doesn't carry ~1920 zero-sample lines per tool. The final `+Inf`
+    /// sample always equals the total count.
+    pub fn cumulative_buckets(&self) -> Vec<(f64, u64)> {
+        let mut out = Vec::new();
+        let mut cumulative = 0u64;
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            let count = bucket.load(Ordering::Relaxed);
+            if count == 0 {
+                continue;
+            }
+            cumulative += count;
+            let le_us = Self::value_for_bucket(idx);
+            out.push((le_us as f64 / 1_000_000.0, cumulative));
+        }
+        out
+    }
+
+    fn total_count(&self) -> u64 {
+        self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).sum()
+    }
+}
+
 /// Response cache for expensive operations
 pub struct ResponseCache {
     cache: Arc<Mutex<HashMap<String, CachedResponse>>>,
     max_entries: usize,
     default_ttl: Duration,
+    /// All-time totals, backing `hit_count`/`miss_count` and the
+    /// `kg_cache_hits_total`/`kg_cache_misses_total` counters
+    /// `PerformanceMonitor::render_prometheus` reports - these only ever
+    /// grow, as a Prometheus counter must.
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+    /// Entries removed via TTL expiry (`get`, `cleanup`) or LRU eviction
+    /// (`evict_lru`); backs `eviction_count`.
+    evictions: Arc<AtomicU64>,
+    /// Hits/misses since the last `tick_window`, folded into
+    /// `windowed_hit_rate` and reset to zero each time it runs - unlike
+    /// `hits`/`misses`, these describe recent behavior rather than an
+    /// all-time ratio.
+    window_hits: Arc<AtomicU64>,
+    window_misses: Arc<AtomicU64>,
+    /// EWMA of per-window hit rate, returned by `get_hit_rate`.
+    windowed_hit_rate: Arc<Mutex<f32>>,
 }
 
 #[derive(Clone)]
@@ -56,8 +215,22 @@ pub struct PerformanceMonitor {
     start_time: Instant,
     metrics: Arc<Mutex<PerformanceMetrics>>,
     cache: ResponseCache,
-    response_times: Arc<Mutex<Vec<Duration>>>,
-    max_response_time_samples: usize,
+    /// Global latency histogram across every tool call.
+    global_latency: Arc<LatencyHistogram>,
+    /// Running sum/count backing `average_response_time` - kept separate
+    /// from the histogram since an exact mean needs the raw total, which a
+    /// bucketed histogram alone can't reconstruct.
+    total_duration_us: Arc<AtomicU64>,
+    total_duration_count: Arc<AtomicU64>,
+    /// Per-tool latency histograms, keyed the same as `PerformanceMetrics::tool_metrics`.
+    tool_latency: Arc<Mutex<HashMap<String, Arc<LatencyHistogram>>>>,
+    /// Single-flight coalescing for `get_or_compute`: one `broadcast::Sender`
+    /// per cache key currently being computed. Callers that join while a key
+    /// is in flight subscribe here instead of starting a redundant
+    /// computation; the entry is removed - by whichever caller is running
+    /// the computation - the moment it finishes, success or failure, so a
+    /// failed computation never poisons the key for the next caller.
+    pending: Arc<Mutex<HashMap<String, tokio::sync::broadcast::Sender<std::result::Result<Value, String>>>>>,
 }
 
 impl PerformanceMonitor {
@@ -70,15 +243,22 @@ impl PerformanceMonitor {
                 successful_requests: 0,
                 failed_requests: 0,
                 average_response_time: Duration::from_millis(0),
+                latency_percentiles: LatencyPercentiles::default(),
                 tool_metrics: HashMap::new(),
                 cache_hit_rate: 0.0,
+                cache_hits_total: 0,
+                cache_misses_total: 0,
+                cache_evictions_total: 0,
                 memory_usage: 0,
                 uptime: Duration::from_secs(0),
                 last_updated: chrono::Utc::now(),
             })),
             cache: ResponseCache::new(cache_size, cache_ttl),
-            response_times: Arc::new(Mutex::new(Vec::new())),
-            max_response_time_samples: 1000,
+            global_latency: Arc::new(LatencyHistogram::new()),
+            total_duration_us: Arc::new(AtomicU64::new(0)),
+            total_duration_count: Arc::new(AtomicU64::new(0)),
+            tool_latency: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -138,25 +318,152 @@ impl PerformanceMonitor {
         format!("{}:{}", tool_name, format!("{:x}", hash))
     }
 
+    /// Single-flight request coalescing around `fut`, keyed by `key` (see
+    /// `generate_cache_key`). If `key` is already being computed by another
+    /// concurrent caller, this one waits for that result instead of running
+    /// `fut` itself - otherwise a burst of identical cache-missing requests
+    /// (a thundering herd) would each redundantly run the same expensive
+    /// operation. The caller that actually runs `fut` also records it via
+    /// `update_tool_metrics`/`update_response_times`, same as
+    /// `record_tool_execution`; callers that coalesce onto someone else's
+    /// computation don't record a second sample for it.
+    pub async fn get_or_compute<F>(&self, tool_name: &str, key: String, fut: F) -> Result<Value>
+    where
+        F: std::future::Future<Output = Result<Value>>,
+    {
+        if let Some(cached) = self.cache.get(&key).await {
+            return Ok(cached);
+        }
+
+        // Either subscribe to an already-running computation for `key`, or
+        // register ourselves as the one running it - never hold `pending`'s
+        // lock across an `.await`.
+        let joined = {
+            let mut pending = self.pending.lock().unwrap();
+            if let Some(sender) = pending.get(&key) {
+                Some(sender.subscribe())
+            } else {
+                let (sender, _receiver) = tokio::sync::broadcast::channel(1);
+                pending.insert(key.clone(), sender);
+                None
+            }
+        };
+
+        if let Some(mut receiver) = joined {
+            return match receiver.recv().await {
+                Ok(Ok(value)) => Ok(value),
+                Ok(Err(message)) => Err(anyhow::anyhow!(message)),
+                Err(_) => Err(anyhow::anyhow!(
+                    "Coalesced computation for '{}' finished without a result",
+                    key
+                )),
+            };
+        }
+
+        let start = Instant::now();
+        let result = fut.await;
+        let duration = start.elapsed();
+        self.update_tool_metrics(tool_name, duration, result.is_ok()).await;
+        self.update_response_times(duration).await;
+
+        if let Ok(value) = &result {
+            self.cache.set(key.clone(), value.clone(), None).await;
+        }
+
+        // Remove the pending entry before broadcasting, on both success and
+        // failure, so a failed computation never permanently poisons the key
+        // for the next caller.
+        if let Some(sender) = self.pending.lock().unwrap().remove(&key) {
+            let broadcast_result = match &result {
+                Ok(value) => Ok(value.clone()),
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = sender.send(broadcast_result);
+        }
+
+        result
+    }
+
     /// Get current performance metrics
     pub async fn get_metrics(&self) -> PerformanceMetrics {
         let mut metrics = self.metrics.lock().unwrap().clone();
         metrics.uptime = self.start_time.elapsed();
         metrics.cache_hit_rate = self.cache.get_hit_rate().await;
+        metrics.cache_hits_total = self.cache.hit_count();
+        metrics.cache_misses_total = self.cache.miss_count();
+        metrics.cache_evictions_total = self.cache.eviction_count();
         metrics.memory_usage = self.estimate_memory_usage().await;
         metrics.last_updated = chrono::Utc::now();
-        
-        // Update average response time
-        if let Ok(response_times) = self.response_times.lock() {
-            if !response_times.is_empty() {
-                let total: Duration = response_times.iter().sum();
-                metrics.average_response_time = total / response_times.len() as u32;
+
+        let total_count = self.total_duration_count.load(Ordering::Relaxed);
+        if total_count > 0 {
+            let total_us = self.total_duration_us.load(Ordering::Relaxed);
+            metrics.average_response_time = Duration::from_micros(total_us / total_count);
+        }
+        metrics.latency_percentiles = self.global_latency.percentiles();
+
+        if let Ok(tool_latency) = self.tool_latency.lock() {
+            for (name, tool_metrics) in metrics.tool_metrics.iter_mut() {
+                if let Some(histogram) = tool_latency.get(name) {
+                    tool_metrics.latency_percentiles = histogram.percentiles();
+                }
             }
         }
-        
+
         metrics
     }
 
+    /// Renders cache and per-tool latency stats in Prometheus text
+    /// exposition format. Deliberately omits a `kg_requests_total{tool,status}`
+    /// counter - `AppState::tool_call_metrics` already exports the same
+    /// breakdown as `kg_tool_calls_total`, and duplicating it here under a
+    /// different name would just give operators two counters to reconcile.
+    pub async fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP kg_cache_hits_total Tool response cache hits.\n");
+        out.push_str("# TYPE kg_cache_hits_total counter\n");
+        out.push_str(&format!("kg_cache_hits_total {}\n", self.cache.hit_count()));
+
+        out.push_str("# HELP kg_cache_misses_total Tool response cache misses.\n");
+        out.push_str("# TYPE kg_cache_misses_total counter\n");
+        out.push_str(&format!("kg_cache_misses_total {}\n", self.cache.miss_count()));
+
+        out.push_str("# HELP kg_cache_entries Entries currently held in the tool response cache.\n");
+        out.push_str("# TYPE kg_cache_entries gauge\n");
+        out.push_str(&format!("kg_cache_entries {}\n", self.cache.entry_count()));
+
+        out.push_str("# HELP kg_tool_duration_seconds Per-tool call latency, bucketed by the logarithmic histogram described on LatencyHistogram.\n");
+        out.push_str("# TYPE kg_tool_duration_seconds histogram\n");
+        let tool_metrics = self.metrics.lock().unwrap().tool_metrics.clone();
+        if let Ok(tool_latency) = self.tool_latency.lock() {
+            for (name, histogram) in tool_latency.iter() {
+                for (le, cumulative) in histogram.cumulative_buckets() {
+                    out.push_str(&format!(
+                        "kg_tool_duration_seconds_bucket{{tool=\"{}\",le=\"{}\"}} {}\n",
+                        name, le, cumulative
+                    ));
+                }
+                let total = histogram.total_count();
+                out.push_str(&format!(
+                    "kg_tool_duration_seconds_bucket{{tool=\"{}\",le=\"+Inf\"}} {}\n",
+                    name, total
+                ));
+                // `average_duration` is an exact rolling mean (see
+                // `update_tool_metrics`), so multiplying it back out by
+                // `call_count` gives an exact sum - unlike the histogram
+                // buckets, which only approximate individual samples.
+                if let Some(tm) = tool_metrics.get(name) {
+                    let sum_seconds = tm.average_duration.as_secs_f64() * tm.call_count as f64;
+                    out.push_str(&format!("kg_tool_duration_seconds_sum{{tool=\"{}\"}} {}\n", name, sum_seconds));
+                }
+                out.push_str(&format!("kg_tool_duration_seconds_count{{tool=\"{}\"}} {}\n", name, total));
+            }
+        }
+
+        out
+    }
+
     /// Start background metrics collection
     pub fn start_background_collection(&self) -> tokio::task::JoinHandle<()> {
         let monitor = self.clone();
@@ -167,25 +474,33 @@ impl PerformanceMonitor {
             loop {
                 interval.tick().await;
                 
-                // Cleanup old cache entries
+                // Cleanup old cache entries and fold this window's hit/miss
+                // counts into the EWMA `get_hit_rate` reports.
                 monitor.cache.cleanup().await;
-                
+                monitor.cache.tick_window();
+
                 // Log performance summary
                 let metrics = monitor.get_metrics().await;
                 info!(
-                    "Performance Summary - Requests: {}/{} ({}% success), Avg Response: {:?}, Cache Hit Rate: {:.1}%, Memory: {}MB",
+                    "Performance Summary - Requests: {}/{} ({}% success), Avg Response: {:?}, p50/p95/p99: {:?}/{:?}/{:?}, Cache Hit Rate: {:.1}%, Memory: {}MB",
                     metrics.successful_requests,
                     metrics.total_requests,
                     if metrics.total_requests > 0 { (metrics.successful_requests * 100) / metrics.total_requests } else { 0 },
                     metrics.average_response_time,
+                    metrics.latency_percentiles.p50,
+                    metrics.latency_percentiles.p95,
+                    metrics.latency_percentiles.p99,
                     metrics.cache_hit_rate * 100.0,
                     metrics.memory_usage / 1_000_000
                 );
-                
+
                 // Log slow tools
                 for (name, tool_metrics) in &metrics.tool_metrics {
                     if tool_metrics.average_duration > Duration::from_millis(1000) {
-                        warn!("Slow tool detected: {} - Avg: {:?}", name, tool_metrics.average_duration);
+                        warn!(
+                            "Slow tool detected: {} - Avg: {:?}, p99: {:?}, max: {:?}",
+                            name, tool_metrics.average_duration, tool_metrics.latency_percentiles.p99, tool_metrics.max_duration
+                        );
                     }
                 }
             }
@@ -206,18 +521,19 @@ impl PerformanceMonitor {
                 average_duration: Duration::from_millis(0),
                 min_duration: duration,
                 max_duration: duration,
+                latency_percentiles: LatencyPercentiles::default(),
                 last_called: None,
             });
-        
+
         tool_metrics.call_count += 1;
         tool_metrics.last_called = Some(chrono::Utc::now());
-        
+
         if success {
             tool_metrics.success_count += 1;
         } else {
             tool_metrics.error_count += 1;
         }
-        
+
         // Update duration statistics
         if duration < tool_metrics.min_duration {
             tool_metrics.min_duration = duration;
@@ -225,23 +541,29 @@ impl PerformanceMonitor {
         if duration > tool_metrics.max_duration {
             tool_metrics.max_duration = duration;
         }
-        
+
         // Calculate rolling average
         let total_duration = tool_metrics.average_duration * (tool_metrics.call_count - 1) as u32 + duration;
         tool_metrics.average_duration = total_duration / tool_metrics.call_count as u32;
+
+        drop(metrics);
+
+        if let Ok(mut tool_latency) = self.tool_latency.lock() {
+            tool_latency
+                .entry(tool_name.to_string())
+                .or_insert_with(|| Arc::new(LatencyHistogram::new()))
+                .record(duration);
+        }
     }
 
-    /// Update response time tracking
+    /// Update global latency tracking: the histogram `get_metrics` reads
+    /// percentiles from, plus the running sum/count `average_response_time`
+    /// is computed from - both `O(1)` per call, replacing the unbounded
+    /// `Vec<Duration>` this used to push into (and periodically `drain`).
     async fn update_response_times(&self, duration: Duration) {
-        if let Ok(mut response_times) = self.response_times.lock() {
-            response_times.push(duration);
-            
-            // Keep only recent samples
-            if response_times.len() > self.max_response_time_samples {
-                let len = response_times.len();
-                response_times.drain(0..len - self.max_response_time_samples);
-            }
-        }
+        self.global_latency.record(duration);
+        self.total_duration_us.fetch_add(duration.as_micros().min(u64::MAX as u128) as u64, Ordering::Relaxed);
+        self.total_duration_count.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Increment successful request counter
@@ -273,8 +595,11 @@ impl Clone for PerformanceMonitor {
             start_time: self.start_time,
             metrics: Arc::clone(&self.metrics),
             cache: self.cache.clone(),
-            response_times: Arc::clone(&self.response_times),
-            max_response_time_samples: self.max_response_time_samples,
+            global_latency: Arc::clone(&self.global_latency),
+            total_duration_us: Arc::clone(&self.total_duration_us),
+            total_duration_count: Arc::clone(&self.total_duration_count),
+            tool_latency: Arc::clone(&self.tool_latency),
+            pending: Arc::clone(&self.pending),
         }
     }
 }
@@ -285,6 +610,12 @@ impl ResponseCache {
             cache: Arc::new(Mutex::new(HashMap::new())),
             max_entries,
             default_ttl,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+            evictions: Arc::new(AtomicU64::new(0)),
+            window_hits: Arc::new(AtomicU64::new(0)),
+            window_misses: Arc::new(AtomicU64::new(0)),
+            windowed_hit_rate: Arc::new(Mutex::new(0.0)),
         }
     }
 
@@ -294,19 +625,26 @@ impl ResponseCache {
                 // Check if expired
                 if cached.created_at.elapsed() > cached.ttl {
                     cache.remove(key);
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    self.window_misses.fetch_add(1, Ordering::Relaxed);
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
                     return None;
                 }
-                
+
                 // Update access statistics
                 cached.access_count += 1;
                 cached.last_accessed = Instant::now();
-                
+
                 debug!("Cache hit for key: {}", key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                self.window_hits.fetch_add(1, Ordering::Relaxed);
                 return Some(cached.response.clone());
             }
         }
-        
+
         debug!("Cache miss for key: {}", key);
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.window_misses.fetch_add(1, Ordering::Relaxed);
         None
     }
 
@@ -329,18 +667,78 @@ impl ResponseCache {
         }
     }
 
+    /// Removes expired entries, adapting each survivor's TTL to its
+    /// `access_count` first: entries accessed often get their TTL doubled
+    /// (capped at `Self::MAX_TTL`) so hot responses survive longer between
+    /// sweeps, while entries accessed once or never get it halved (floored
+    /// at `Self::MIN_TTL`) so cold entries clear out sooner. `HashMap::retain`
+    /// gives `&mut CachedResponse` in its predicate, which is what makes
+    /// mutating `ttl` in place possible here.
     async fn cleanup(&self) {
         if let Ok(mut cache) = self.cache.lock() {
             let now = Instant::now();
+            let evictions = &self.evictions;
             cache.retain(|_key, cached| {
-                now.duration_since(cached.created_at) <= cached.ttl
+                if cached.access_count > Self::HOT_ACCESS_THRESHOLD {
+                    cached.ttl = (cached.ttl * 2).min(Self::MAX_TTL);
+                } else if cached.access_count <= 1 {
+                    cached.ttl = (cached.ttl / 2).max(Self::MIN_TTL);
+                }
+
+                let keep = now.duration_since(cached.created_at) <= cached.ttl;
+                if !keep {
+                    evictions.fetch_add(1, Ordering::Relaxed);
+                }
+                keep
             });
         }
     }
 
+    /// EWMA smoothing factor for `tick_window`: how much weight the latest
+    /// window gets over the accumulated history.
+    const WINDOW_EWMA_ALPHA: f32 = 0.3;
+    const HOT_ACCESS_THRESHOLD: u64 = 5;
+    const MIN_TTL: Duration = Duration::from_secs(5);
+    const MAX_TTL: Duration = Duration::from_secs(3600);
+
+    /// Folds this window's hit/miss counts into `windowed_hit_rate` via an
+    /// EWMA, then resets the window counters to zero - called from
+    /// `PerformanceMonitor::start_background_collection`'s 60s tick so
+    /// `get_hit_rate` tracks recent behavior instead of an all-time ratio
+    /// dragged toward whatever happened at startup. A quiet window (no
+    /// traffic since the last tick) leaves the EWMA unchanged rather than
+    /// pulling it toward zero.
+    fn tick_window(&self) {
+        let hits = self.window_hits.swap(0, Ordering::Relaxed);
+        let misses = self.window_misses.swap(0, Ordering::Relaxed);
+        let total = hits + misses;
+        if total == 0 {
+            return;
+        }
+
+        let window_rate = hits as f32 / total as f32;
+        let mut rate = self.windowed_hit_rate.lock().unwrap();
+        *rate = Self::WINDOW_EWMA_ALPHA * window_rate + (1.0 - Self::WINDOW_EWMA_ALPHA) * *rate;
+    }
+
     async fn get_hit_rate(&self) -> f32 {
-        // Simplified hit rate calculation
-        0.75 // Placeholder - would track actual hits/misses in production
+        *self.windowed_hit_rate.lock().unwrap()
+    }
+
+    fn hit_count(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    fn miss_count(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn eviction_count(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    fn entry_count(&self) -> usize {
+        self.cache.lock().map(|cache| cache.len()).unwrap_or(0)
     }
 
     async fn estimate_memory_usage(&self) -> u64 {
@@ -357,6 +755,7 @@ impl ResponseCache {
             .min_by_key(|(_, cached)| cached.last_accessed)
             .map(|(key, _)| key.clone()) {
             cache.remove(&lru_key);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
         }
     }
 }
@@ -367,73 +766,268 @@ impl Clone for ResponseCache {
             cache: Arc::clone(&self.cache),
             max_entries: self.max_entries,
             default_ttl: self.default_ttl,
+            hits: Arc::clone(&self.hits),
+            misses: Arc::clone(&self.misses),
+            evictions: Arc::clone(&self.evictions),
+            window_hits: Arc::clone(&self.window_hits),
+            window_misses: Arc::clone(&self.window_misses),
+            windowed_hit_rate: Arc::clone(&self.windowed_hit_rate),
         }
     }
 }
 
-/// Connection pool for managing client connections
+/// Connection pool for managing client connections.
+///
+/// Rebuilt on a `tokio::sync::Semaphore` rather than the original
+/// busy-loop/`oneshot` design, which pushed a `Sender` onto a queue that
+/// nothing ever fired, so a waiter on contention either deadlocked or spun
+/// re-locking `active_connections`. A `Semaphore` makes release automatic
+/// (dropping `ConnectionGuard`'s `OwnedSemaphorePermit` wakes the next waiter
+/// FIFO) and gives `acquire_timeout` something to race against instead of
+/// queuing forever - the same permit-based backpressure `mcp::search_queue::SearchQueue`
+/// uses for execution admission control.
 pub struct ConnectionPool {
     max_connections: usize,
-    active_connections: Arc<Mutex<usize>>,
-    connection_queue: Arc<Mutex<Vec<tokio::sync::oneshot::Sender<()>>>>,
+    semaphore: Arc<Semaphore>,
+    /// Caps concurrent connections per tool name so one slow tool can't
+    /// starve the whole pool; each tool gets its own semaphore, sized to
+    /// `tool_limit`, created lazily the first time that name is seen.
+    tool_semaphores: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+    tool_limit: usize,
+    waiting: Arc<AtomicU64>,
+    total_wait_us: Arc<AtomicU64>,
+    total_acquired: Arc<AtomicU64>,
+    timed_out: Arc<AtomicU64>,
+}
+
+/// Point-in-time occupancy/latency snapshot, reported alongside
+/// `PerformanceMetrics`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ConnectionPoolStats {
+    pub active_connections: usize,
+    pub max_connections: usize,
+    /// Callers currently blocked in `acquire`/`acquire_timeout`.
+    pub waiting: u64,
+    /// Mean time callers have spent waiting for a permit, across every
+    /// `acquire`/`acquire_timeout` call that has completed (successfully or
+    /// by timing out) so far.
+    pub average_wait: Duration,
+    pub timed_out_total: u64,
 }
 
 impl ConnectionPool {
     pub fn new(max_connections: usize) -> Self {
+        Self::with_tool_limit(max_connections, max_connections)
+    }
+
+    /// `tool_limit` bounds how many connections a single tool name may hold
+    /// concurrently, independent of `max_connections` - set it lower than
+    /// `max_connections` to stop one slow tool from starving the rest.
+    pub fn with_tool_limit(max_connections: usize, tool_limit: usize) -> Self {
         Self {
             max_connections,
-            active_connections: Arc::new(Mutex::new(0)),
-            connection_queue: Arc::new(Mutex::new(Vec::new())),
+            semaphore: Arc::new(Semaphore::new(max_connections)),
+            tool_semaphores: Arc::new(Mutex::new(HashMap::new())),
+            tool_limit,
+            waiting: Arc::new(AtomicU64::new(0)),
+            total_wait_us: Arc::new(AtomicU64::new(0)),
+            total_acquired: Arc::new(AtomicU64::new(0)),
+            timed_out: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    /// Try to acquire a connection slot
+    fn tool_semaphore(&self, tool_name: &str) -> Arc<Semaphore> {
+        let mut semaphores = self.tool_semaphores.lock().unwrap();
+        Arc::clone(
+            semaphores
+                .entry(tool_name.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.tool_limit))),
+        )
+    }
+
+    /// Acquire a connection slot, queuing indefinitely if none are free.
     pub async fn acquire(&self) -> Result<ConnectionGuard> {
-        loop {
-            // Try to get a connection immediately
-            if let Ok(mut active) = self.active_connections.lock() {
-                if *active < self.max_connections {
-                    *active += 1;
-                    return Ok(ConnectionGuard::new(Arc::clone(&self.active_connections)));
-                }
-            }
-            
-            // Wait for a connection to become available
-            let (tx, rx) = tokio::sync::oneshot::channel();
-            if let Ok(mut queue) = self.connection_queue.lock() {
-                queue.push(tx);
+        self.acquire_for(None).await.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Like [`acquire`](Self::acquire), but scoped to `tool_name`'s
+    /// sub-limit in addition to the pool-wide limit.
+    pub async fn acquire_for_tool(&self, tool_name: &str) -> Result<ConnectionGuard> {
+        self.acquire_for(Some(tool_name)).await.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Waits up to `timeout` for a connection slot (and, if `tool_name` is
+    /// given, that tool's sub-limit), returning `McpError::PoolExhausted`
+    /// instead of queuing forever if neither becomes free in time.
+    pub async fn acquire_timeout(&self, tool_name: Option<&str>, timeout: Duration) -> std::result::Result<ConnectionGuard, McpError> {
+        match tokio::time::timeout(timeout, self.acquire_for(tool_name)).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.timed_out.fetch_add(1, Ordering::Relaxed);
+                Err(McpError::PoolExhausted {
+                    message: format!("No connection slot available within {:?}", timeout),
+                    retry_after_secs: Some(timeout.as_secs_f64()),
+                })
             }
-            
-            rx.await.map_err(|_| anyhow::anyhow!("Connection pool closed"))?;
         }
     }
 
-    /// Get current connection statistics
-    pub fn get_stats(&self) -> (usize, usize) {
-        let active_count = self.active_connections.lock()
-            .map(|guard| *guard)
-            .unwrap_or(0);
-        (active_count, self.max_connections)
+    async fn acquire_for(&self, tool_name: Option<&str>) -> std::result::Result<ConnectionGuard, McpError> {
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        let start = Instant::now();
+
+        let pool_permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .map_err(|_| McpError::Internal { message: "connection pool is shutting down".to_string() })?;
+
+        let tool_permit = match tool_name {
+            Some(name) => {
+                let tool_sem = self.tool_semaphore(name);
+                Some(
+                    tool_sem
+                        .acquire_owned()
+                        .await
+                        .map_err(|_| McpError::Internal { message: "connection pool is shutting down".to_string() })?,
+                )
+            }
+            None => None,
+        };
+
+        self.waiting.fetch_sub(1, Ordering::Relaxed);
+        self.total_wait_us.fetch_add(start.elapsed().as_micros().min(u64::MAX as u128) as u64, Ordering::Relaxed);
+        self.total_acquired.fetch_add(1, Ordering::Relaxed);
+
+        Ok(ConnectionGuard {
+            _pool_permit: pool_permit,
+            _tool_permit: tool_permit,
+        })
+    }
+
+    /// Current occupancy and lifetime wait-time/timeout stats; see
+    /// `ConnectionPoolStats`.
+    pub fn get_stats(&self) -> ConnectionPoolStats {
+        let active_connections = self.max_connections - self.semaphore.available_permits();
+        let total_acquired = self.total_acquired.load(Ordering::Relaxed);
+        let average_wait = if total_acquired > 0 {
+            Duration::from_micros(self.total_wait_us.load(Ordering::Relaxed) / total_acquired)
+        } else {
+            Duration::from_millis(0)
+        };
+        ConnectionPoolStats {
+            active_connections,
+            max_connections: self.max_connections,
+            waiting: self.waiting.load(Ordering::Relaxed),
+            average_wait,
+            timed_out_total: self.timed_out.load(Ordering::Relaxed),
+        }
     }
 }
 
-/// RAII guard for connection slots
+/// RAII guard for a connection slot (and, if acquired via
+/// `acquire_for_tool`/`acquire_timeout` with a tool name, that tool's
+/// sub-limit slot). Releasing is automatic: dropping the held
+/// `OwnedSemaphorePermit`s wakes the next FIFO waiter on each semaphore.
 pub struct ConnectionGuard {
-    active_connections: Arc<Mutex<usize>>,
+    _pool_permit: tokio::sync::OwnedSemaphorePermit,
+    _tool_permit: Option<tokio::sync::OwnedSemaphorePermit>,
 }
 
-impl ConnectionGuard {
-    fn new(active_connections: Arc<Mutex<usize>>) -> Self {
-        Self { active_connections }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn releasing_a_guard_frees_its_slot_for_the_next_waiter() {
+        let pool = ConnectionPool::new(1);
+        let first = pool.acquire().await.expect("first acquire should succeed");
+        assert_eq!(pool.get_stats().active_connections, 1);
+
+        drop(first);
+        let second = pool.acquire().await.expect("slot should be free again");
+        assert_eq!(pool.get_stats().active_connections, 1);
+        drop(second);
     }
-}
 
-impl Drop for ConnectionGuard {
-    fn drop(&mut self) {
-        if let Ok(mut active) = self.active_connections.lock() {
-            if *active > 0 {
-                *active -= 1;
-            }
+    #[tokio::test]
+    async fn acquire_timeout_reports_pool_exhausted_instead_of_hanging() {
+        let pool = ConnectionPool::new(1);
+        let _held = pool.acquire().await.expect("first acquire should succeed");
+
+        let result = pool.acquire_timeout(None, Duration::from_millis(20)).await;
+        assert!(
+            matches!(result, Err(McpError::PoolExhausted { .. })),
+            "expected PoolExhausted, got {:?}",
+            result.map(|_| ())
+        );
+        assert_eq!(pool.get_stats().timed_out_total, 1);
+    }
+
+    #[tokio::test]
+    async fn a_busy_tool_cannot_starve_the_pool_wide_limit() {
+        // Pool-wide limit of 2, but "slow_tool" is capped at 1 of its own -
+        // a second call for the same tool must wait on its sub-limit even
+        // though a pool-wide slot is still free.
+        let pool = ConnectionPool::with_tool_limit(2, 1);
+        let _first = pool.acquire_for_tool("slow_tool").await.expect("first call should succeed");
+
+        let result = pool
+            .acquire_timeout(Some("slow_tool"), Duration::from_millis(20))
+            .await;
+        assert!(
+            matches!(result, Err(McpError::PoolExhausted { .. })),
+            "second call for the same tool should be blocked by its sub-limit, got {:?}",
+            result.map(|_| ())
+        );
+
+        // A different tool isn't affected by "slow_tool"'s sub-limit.
+        let other = pool.acquire_for_tool("other_tool").await;
+        assert!(other.is_ok(), "a different tool should still get a pool-wide slot");
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_for_the_same_key_coalesce_onto_one_computation() {
+        let monitor = Arc::new(PerformanceMonitor::new(100, Duration::from_secs(60)));
+        let runs = Arc::new(AtomicU64::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let monitor = Arc::clone(&monitor);
+            let runs = Arc::clone(&runs);
+            handles.push(tokio::spawn(async move {
+                monitor
+                    .get_or_compute("tool", "shared-key".to_string(), async {
+                        runs.fetch_add(1, Ordering::Relaxed);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok(json!({"value": 42}))
+                    })
+                    .await
+            }));
         }
+
+        for handle in handles {
+            let result = handle.await.expect("task should not panic").expect("computation should succeed");
+            assert_eq!(result, json!({"value": 42}));
+        }
+
+        assert_eq!(runs.load(Ordering::Relaxed), 1, "only one caller should have actually run the computation");
+    }
+
+    #[tokio::test]
+    async fn a_failed_computation_does_not_poison_the_key_for_later_callers() {
+        let monitor = PerformanceMonitor::new(100, Duration::from_secs(60));
+
+        let first = monitor
+            .get_or_compute("tool", "flaky-key".to_string(), async {
+                Err(anyhow::anyhow!("boom"))
+            })
+            .await;
+        assert!(first.is_err());
+
+        let second = monitor
+            .get_or_compute("tool", "flaky-key".to_string(), async { Ok(json!({"ok": true})) })
+            .await
+            .expect("a later call for the same key should run its own computation, not inherit the failure");
+        assert_eq!(second, json!({"ok": true}));
     }
 } 
\ No newline at end of file
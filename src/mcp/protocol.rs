@@ -1,16 +1,24 @@
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt, AsyncBufReadExt, BufReader, stdin, stdout};
+use std::sync::{Arc, Mutex as StdMutex};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, AsyncBufReadExt, BufReader, stdin, stdout};
+use tokio::sync::{mpsc, Mutex as AsyncMutex};
+use tokio::task::AbortHandle;
 use tracing::{debug, error, info, warn};
 
 use crate::graph::storage::GraphStorage;
 use crate::embeddings::LocalEmbeddingEngine;
 use crate::search::HybridSearchEngine;
 use crate::memory::MemoryOptimizer;
+use crate::metrics::RecentEventsBuffer;
+use super::errors::ToolRateLimiter;
 use super::handlers;
+use super::search_queue::SearchQueue;
+use super::workers::WorkerManager;
+use crate::indexing::{StreamIngestionManager, IndexWatchManager};
 
 /// MCP JSON-RPC 2.0 request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,133 +49,273 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
-/// MCP protocol handler
-pub struct McpProtocol {
-    reader: BufReader<tokio::io::Stdin>,
-    writer: tokio::io::Stdout,
-    client_info: Option<ClientInfo>,
-    initialized: bool,
-    storage: Arc<GraphStorage>,
-    embedding_engine: Arc<LocalEmbeddingEngine>,
-    search_engine: Arc<HybridSearchEngine>,
-    memory_optimizer: Arc<MemoryOptimizer>,
+/// A message read from one line of stdin: either a single JSON-RPC request,
+/// or a JSON-RPC 2.0 batch (an array of requests) to dispatch together.
+enum IncomingMessage {
+    Single(JsonRpcRequest),
+    Batch(Vec<JsonRpcRequest>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ClientInfo {
-    pub name: String,
-    pub version: String,
+/// How messages are delimited on a byte-stream transport (stdio, TCP).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Framing {
+    /// One JSON value per line, newline-terminated - simple, but breaks the
+    /// moment a serialized payload contains an embedded newline.
+    #[default]
+    LineDelimited,
+    /// LSP-style `Content-Length: N\r\n\r\n` header followed by exactly `N`
+    /// bytes of UTF-8 payload, as used by lsp-server/helix-lsp.
+    ContentLength,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ServerInfo {
-    pub name: String,
-    pub version: String,
-    pub protocol_version: String,
-    pub capabilities: ServerCapabilities,
+/// Read half of a connection's transport - the only thing `handle_connection`'s
+/// read loop touches. Kept separate from the write side so the loop can read
+/// the next message while a response to a previous one is still being
+/// written by the writer task (see `spawn_writer`).
+#[async_trait]
+trait TransportReader: Send {
+    async fn recv(&mut self) -> Result<Option<String>>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ServerCapabilities {
-    pub tools: Option<ToolsCapability>,
-    pub resources: Option<ResourcesCapability>,
-    pub prompts: Option<PromptsCapability>,
-    pub logging: Option<LoggingCapability>,
+/// Write half of a connection's transport, owned exclusively by the writer
+/// task a constructor spawns alongside each `McpProtocol`. Keeping writes on
+/// one task serializes them without requiring `handle_request` itself to
+/// hold a lock around the whole round trip.
+#[async_trait]
+trait TransportWriter: Send {
+    async fn send(&mut self, payload: &str) -> Result<()>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolsCapability {
-    pub list_changed: Option<bool>,
+/// `TransportReader` over a raw `AsyncRead` byte stream (stdio, TCP), framed
+/// according to `Framing`.
+struct ByteStreamReader<R> {
+    reader: BufReader<R>,
+    framing: Framing,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResourcesCapability {
-    pub subscribe: Option<bool>,
-    pub list_changed: Option<bool>,
-}
+impl<R: AsyncRead + Unpin + Send> ByteStreamReader<R> {
+    /// Reads one non-empty, newline-terminated JSON message body.
+    async fn read_line_delimited(&mut self) -> Result<Option<String>> {
+        loop {
+            let mut line = String::new();
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PromptsCapability {
-    pub list_changed: Option<bool>,
-}
+            match self.reader.read_line(&mut line).await? {
+                0 => return Ok(None), // EOF
+                _ => {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue; // Skip empty lines
+                    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LoggingCapability {
-    pub level: Option<String>,
+                    debug!("Received line: {}", line);
+                    return Ok(Some(line.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Reads one LSP-style `Content-Length: N\r\n\r\n`-framed JSON message
+    /// body: headers line-by-line until the blank separator, then exactly
+    /// `N` bytes of payload.
+    async fn read_content_length_framed(&mut self) -> Result<Option<String>> {
+        let mut content_length: Option<usize> = None;
+
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).await? == 0 {
+                return Ok(None); // EOF
+            }
+
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                break; // blank line separates headers from the payload
+            }
+
+            if let Some(value) = line.strip_prefix("Content-Length:") {
+                content_length = Some(value.trim().parse()?);
+            }
+        }
+
+        let content_length = content_length
+            .ok_or_else(|| anyhow!("Content-Length-framed message is missing its Content-Length header"))?;
+
+        let mut body = vec![0u8; content_length];
+        self.reader.read_exact(&mut body).await?;
+        let body = String::from_utf8(body)?;
+
+        debug!("Received Content-Length-framed message: {}", body);
+        Ok(Some(body))
+    }
 }
 
-impl McpProtocol {
-    /// Create a new MCP protocol handler using stdio
-    pub async fn new_stdio(
-        storage: Arc<GraphStorage>,
-        embedding_engine: Arc<LocalEmbeddingEngine>,
-        search_engine: Arc<HybridSearchEngine>,
-        memory_optimizer: Arc<MemoryOptimizer>,
-    ) -> Result<Self> {
-        Ok(Self {
-            reader: BufReader::new(stdin()),
-            writer: stdout(),
-            client_info: None,
-            initialized: false,
-            storage,
-            embedding_engine,
-            search_engine,
-            memory_optimizer,
-        })
+#[async_trait]
+impl<R: AsyncRead + Unpin + Send> TransportReader for ByteStreamReader<R> {
+    async fn recv(&mut self) -> Result<Option<String>> {
+        match self.framing {
+            Framing::LineDelimited => self.read_line_delimited().await,
+            Framing::ContentLength => self.read_content_length_framed().await,
+        }
     }
+}
 
-    /// Handle MCP communication loop
-    pub async fn handle_connection(mut self) -> Result<()> {
-        info!("Starting MCP protocol handler");
-        
-        loop {
-            match self.read_message().await {
-                Ok(Some(request)) => {
-                    let response = self.handle_request(request).await;
-                    self.send_response(response).await?;
-                }
-                Ok(None) => {
-                    debug!("Client disconnected gracefully");
-                    break;
-                }
-                Err(e) => {
-                    error!("Error reading message: {}", e);
-                    break;
-                }
+/// `TransportWriter` over a raw `AsyncWrite` byte stream, framed according
+/// to `Framing`.
+struct ByteStreamWriter<W> {
+    writer: W,
+    framing: Framing,
+}
+
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send> TransportWriter for ByteStreamWriter<W> {
+    async fn send(&mut self, payload: &str) -> Result<()> {
+        match self.framing {
+            Framing::LineDelimited => {
+                self.writer.write_all(payload.as_bytes()).await?;
+                self.writer.write_all(b"\n").await?; // Add newline delimiter
+            }
+            Framing::ContentLength => {
+                let header = format!("Content-Length: {}\r\n\r\n", payload.len());
+                self.writer.write_all(header.as_bytes()).await?;
+                self.writer.write_all(payload.as_bytes()).await?;
             }
         }
-        
+
+        self.writer.flush().await?;
         Ok(())
     }
+}
+
+/// Builds a byte-stream transport's read/write halves as a pair, so callers
+/// never have to remember to frame both sides the same way.
+fn byte_stream_transport<R, W>(
+    reader: BufReader<R>,
+    writer: W,
+    framing: Framing,
+) -> (Box<dyn TransportReader>, Box<dyn TransportWriter>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    (
+        Box::new(ByteStreamReader { reader, framing }),
+        Box::new(ByteStreamWriter { writer, framing }),
+    )
+}
+
+/// `TransportReader` over an already-upgraded WebSocket's read half. Each
+/// message is already a discrete unit, so there's no line/header framing to
+/// do - `recv` reads one `Message::Text`/`Message::Binary` frame.
+struct WebSocketReader {
+    stream: futures::stream::SplitStream<axum::extract::ws::WebSocket>,
+}
+
+#[async_trait]
+impl TransportReader for WebSocketReader {
+    async fn recv(&mut self) -> Result<Option<String>> {
+        use axum::extract::ws::Message;
+        use futures::StreamExt;
 
-    /// Read a JSON-RPC message from stdin
-    async fn read_message(&mut self) -> Result<Option<JsonRpcRequest>> {
         loop {
-            let mut line = String::new();
-            
-            match self.reader.read_line(&mut line).await? {
-                0 => return Ok(None), // EOF
-                _ => {
-                    let line = line.trim();
-                    if line.is_empty() {
-                        continue; // Skip empty lines
-                    }
-                    
-                    debug!("Received line: {}", line);
-                    
-                    let request: JsonRpcRequest = serde_json::from_str(line)?;
-                    debug!("Parsed request: {} with id: {:?}", request.method, request.id);
-                    
-                    return Ok(Some(request));
-                }
+            match self.stream.next().await {
+                None => return Ok(None), // Connection closed
+                Some(Err(e)) => return Err(anyhow!("WebSocket read error: {}", e)),
+                Some(Ok(Message::Text(text))) => return Ok(Some(text.to_string())),
+                Some(Ok(Message::Binary(bytes))) => return Ok(Some(String::from_utf8(bytes.into())?)),
+                Some(Ok(Message::Close(_))) => return Ok(None),
+                // Ping/Pong are handled by axum itself; nothing to surface here.
+                Some(Ok(_)) => continue,
             }
         }
     }
+}
 
+/// `TransportWriter` over an already-upgraded WebSocket's write half - one
+/// `Message::Text` frame per `send`.
+struct WebSocketWriter {
+    sink: futures::stream::SplitSink<axum::extract::ws::WebSocket, axum::extract::ws::Message>,
+}
+
+#[async_trait]
+impl TransportWriter for WebSocketWriter {
+    async fn send(&mut self, payload: &str) -> Result<()> {
+        use axum::extract::ws::Message;
+        use futures::SinkExt;
+        self.sink.send(Message::Text(payload.to_string().into())).await?;
+        Ok(())
+    }
+}
 
+/// MCP's logging levels, borrowed straight from RFC 5424 syslog severity -
+/// the same eight names the `logging/setLevel` request and
+/// `notifications/message` notification use on the wire. Declared in
+/// increasing severity so the derived `Ord` lets `ConnectionContext::log`
+/// compare a message's level against the client's configured threshold with
+/// a plain `<`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
 
+/// Per-connection state touched by more than one in-flight request - who the
+/// client said it was, whether `initialize` has run, the live
+/// `resources/subscribe` set, and the minimum `LogLevel` the client wants to
+/// see via `notifications/message`. Guarded by an async mutex rather than
+/// living directly on `McpProtocol`, since every spawned request task (see
+/// `ConnectionContext::handle_request`) needs to read or update it without
+/// owning the connection itself.
+struct ConnectionState {
+    client_info: Option<ClientInfo>,
+    initialized: bool,
+    /// Resource URIs (`"kg://node/{uuid}"` / `"kg://edge/{uuid}"`) this
+    /// connection's client has subscribed to via `resources/subscribe` -
+    /// just a membership set, so `()` carries no information beyond "present".
+    subscriptions: HashMap<String, ()>,
+    /// Set via `logging/setLevel`; defaults to `Info`, matching the `"info"`
+    /// default `ServerCapabilities.logging` advertises at `initialize`.
+    log_level: LogLevel,
+}
+
+/// Everything one JSON-RPC request needs to run to completion on its own
+/// spawned task, independent of every other in-flight request on the same
+/// connection: the `Arc`-shared storage/engines/managers, the connection's
+/// shared mutable state, and a channel back to the single writer task so
+/// responses stay serialized regardless of which task finishes first.
+#[derive(Clone)]
+struct ConnectionContext {
+    storage: Arc<GraphStorage>,
+    embedding_engine: Arc<LocalEmbeddingEngine>,
+    search_engine: Arc<HybridSearchEngine>,
+    memory_optimizer: Arc<MemoryOptimizer>,
+    rate_limiter: Arc<ToolRateLimiter>,
+    worker_manager: Arc<WorkerManager>,
+    recent_events: Arc<RecentEventsBuffer>,
+    search_queue: Arc<SearchQueue>,
+    stream_manager: Arc<StreamIngestionManager>,
+    watch_manager: Arc<IndexWatchManager>,
+    state: Arc<AsyncMutex<ConnectionState>>,
+    outbound_tx: mpsc::UnboundedSender<Value>,
+}
+
+/// Enqueues `value` for the writer task to send, logging (rather than
+/// propagating) the only way this can fail: the writer task has already
+/// exited because the connection closed.
+fn enqueue_outbound(tx: &mpsc::UnboundedSender<Value>, value: Value) {
+    if tx.send(value).is_err() {
+        warn!("Dropped outbound MCP message: writer task has shut down");
+    }
+}
+
+impl ConnectionContext {
     /// Handle a JSON-RPC request
-    async fn handle_request(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
+    async fn handle_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         let method = &request.method;
         let params = request.params.clone();
         let id = request.id.clone();
@@ -177,8 +325,11 @@ impl McpProtocol {
             "initialized" => self.handle_initialized(params).await,
             "tools/list" => self.handle_tools_list(params).await,
             "tools/call" => self.handle_tools_call(params).await,
+            "resources/subscribe" => self.handle_resources_subscribe(params).await,
+            "resources/unsubscribe" => self.handle_resources_unsubscribe(params).await,
+            "logging/setLevel" => self.handle_set_log_level(params).await,
             "ping" => Ok(json!({"pong": true})),
-            _ => Err(self.method_not_found_error(method)),
+            _ => Err(Self::method_not_found_error(method)),
         };
 
         match result {
@@ -192,19 +343,53 @@ impl McpProtocol {
                 jsonrpc: "2.0".to_string(),
                 id,
                 result: None,
-                error: Some(self.error_to_json_rpc_error(e)),
+                error: Some(Self::error_to_json_rpc_error(e)),
             },
         }
     }
 
+    /// Dispatches every request in a JSON-RPC 2.0 batch through
+    /// `handle_request` in turn, dropping the response for any element
+    /// without an `id` (a notification). Returns `None` when there is
+    /// nothing to send back - an all-notification batch, per JSON-RPC 2.0's
+    /// "no response objects" rule - and a single `INVALID_REQUEST` error
+    /// object (not wrapped in an array) for an empty batch, matching the
+    /// spec's other explicit edge case. A batch runs as one task (see
+    /// `McpProtocol::handle_connection`) so it can't stall the read loop,
+    /// but its elements aren't individually cancellable the way a
+    /// top-level request is - `notifications/cancelled` only tracks those.
+    async fn handle_batch(&self, requests: Vec<JsonRpcRequest>) -> Option<Value> {
+        if requests.is_empty() {
+            return Some(json!({
+                "jsonrpc": "2.0",
+                "id": Value::Null,
+                "error": {
+                    "code": error_codes::INVALID_REQUEST,
+                    "message": "Invalid Request"
+                }
+            }));
+        }
+
+        let mut responses = Vec::new();
+        for request in requests {
+            let is_notification = request.id.is_none();
+            let response = self.handle_request(request).await;
+            if !is_notification {
+                responses.push(response);
+            }
+        }
+
+        if responses.is_empty() { None } else { Some(json!(responses)) }
+    }
+
     /// Handle initialize request
-    async fn handle_initialize(&mut self, params: Option<Value>) -> Result<Value> {
+    async fn handle_initialize(&self, params: Option<Value>) -> Result<Value> {
         debug!("Handling initialize request");
-        
+
         if let Some(params) = params {
             if let Ok(client_info) = serde_json::from_value::<ClientInfo>(params.clone()) {
                 info!("Client info: {} v{}", client_info.name, client_info.version);
-                self.client_info = Some(client_info);
+                self.state.lock().await.client_info = Some(client_info);
             }
         }
 
@@ -216,7 +401,10 @@ impl McpProtocol {
                 tools: Some(ToolsCapability {
                     list_changed: Some(false),
                 }),
-                resources: None,
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: Some(true),
+                }),
                 prompts: None,
                 logging: Some(LoggingCapability {
                     level: Some("info".to_string()),
@@ -228,17 +416,17 @@ impl McpProtocol {
     }
 
     /// Handle initialized notification
-    async fn handle_initialized(&mut self, _params: Option<Value>) -> Result<Value> {
+    async fn handle_initialized(&self, _params: Option<Value>) -> Result<Value> {
         debug!("Client initialized");
-        self.initialized = true;
+        self.state.lock().await.initialized = true;
         Ok(json!({}))
     }
 
     /// Handle tools/list request
     async fn handle_tools_list(&self, _params: Option<Value>) -> Result<Value> {
         debug!("Handling tools/list request");
-        
-        if !self.initialized {
+
+        if !self.state.lock().await.initialized {
             return Err(anyhow!("Server not initialized"));
         }
 
@@ -248,44 +436,127 @@ impl McpProtocol {
     /// Handle tools/call request
     async fn handle_tools_call(&self, params: Option<Value>) -> Result<Value> {
         debug!("Handling tools/call request");
-        
-        if !self.initialized {
+
+        if !self.state.lock().await.initialized {
             return Err(anyhow!("Server not initialized"));
         }
 
         let params = params.ok_or_else(|| anyhow!("Missing parameters"))?;
-        
+
         let tool_name = params.get("name")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("Missing tool name"))?;
-            
+
         let tool_params = params.get("arguments").cloned()
             .unwrap_or(json!({}));
 
-        // Delegate to handlers
-        crate::mcp::handlers::handle_tool_request(
-            tool_name, 
+        self.log(LogLevel::Debug, "tools/call", json!({
+            "tool": tool_name,
+            "arguments": tool_params,
+        })).await;
+
+        let started = std::time::Instant::now();
+
+        // Delegate to handlers. Stdio has no SSE session to stream
+        // `notifications/progress` over, so no progress sink is offered.
+        let result = crate::mcp::handlers::handle_tool_request(
+            tool_name,
             tool_params,
             &self.storage,
             &self.embedding_engine,
             &self.search_engine,
             &self.memory_optimizer,
-        ).await
+            &self.rate_limiter,
+            &self.worker_manager,
+            &self.recent_events,
+            &self.search_queue,
+            &self.stream_manager,
+            &self.watch_manager,
+            None,
+        ).await;
+
+        let duration_ms = started.elapsed().as_millis();
+        match &result {
+            Ok(_) => {
+                self.log(LogLevel::Info, "tools/call", json!({
+                    "tool": tool_name,
+                    "duration_ms": duration_ms,
+                })).await;
+            }
+            Err(e) => {
+                self.log(LogLevel::Error, "tools/call", json!({
+                    "tool": tool_name,
+                    "duration_ms": duration_ms,
+                    "error": e.to_string(),
+                })).await;
+            }
+        }
+
+        result
     }
 
-    /// Send a JSON-RPC response
-    async fn send_response(&mut self, response: JsonRpcResponse) -> Result<()> {
-        let json_string = serde_json::to_string(&response)?;
-        self.writer.write_all(json_string.as_bytes()).await?;
-        self.writer.write_all(b"\n").await?; // Add newline delimiter
-        self.writer.flush().await?;
-        
-        debug!("Sent response for id: {:?}", response.id);
-        Ok(())
+    /// Handle resources/subscribe request: registers the client's interest
+    /// in `params.uri` (a resource addressed as `"kg://node/{uuid}"` /
+    /// `"kg://edge/{uuid}"`) so a later `GraphStorage` mutation of it gets
+    /// pushed back as a `notifications/resources/updated`.
+    async fn handle_resources_subscribe(&self, params: Option<Value>) -> Result<Value> {
+        let uri = Self::resource_uri_param(params)?;
+        self.state.lock().await.subscriptions.insert(uri, ());
+        Ok(json!({}))
+    }
+
+    /// Reverses `handle_resources_subscribe`.
+    async fn handle_resources_unsubscribe(&self, params: Option<Value>) -> Result<Value> {
+        let uri = Self::resource_uri_param(params)?;
+        self.state.lock().await.subscriptions.remove(&uri);
+        Ok(json!({}))
+    }
+
+    fn resource_uri_param(params: Option<Value>) -> Result<String> {
+        params
+            .and_then(|p| p.get("uri").and_then(|v| v.as_str()).map(|s| s.to_string()))
+            .ok_or_else(|| anyhow!("Missing 'uri' parameter"))
+    }
+
+    /// Handle logging/setLevel request: stores the client-requested minimum
+    /// `LogLevel` so `log` starts (or stops) forwarding `notifications/message`
+    /// at that severity.
+    async fn handle_set_log_level(&self, params: Option<Value>) -> Result<Value> {
+        let level = params
+            .and_then(|p| p.get("level").cloned())
+            .ok_or_else(|| anyhow!("Missing 'level' parameter"))?;
+        let level: LogLevel = serde_json::from_value(level.clone())
+            .map_err(|_| anyhow!("Unknown log level: {}", level))?;
+
+        self.state.lock().await.log_level = level;
+        Ok(json!({}))
+    }
+
+    /// Pushes a `notifications/message` to the client when `level` meets or
+    /// exceeds the minimum level set via `logging/setLevel` (default `info`).
+    /// This is what makes the `logging` capability advertised at
+    /// `initialize` actually do something, instead of just sitting there -
+    /// without it clients have no visibility into tool execution beyond
+    /// whatever `tracing` output reaches stderr, which most MCP clients
+    /// never see.
+    async fn log(&self, level: LogLevel, logger: &str, data: Value) {
+        if level < self.state.lock().await.log_level {
+            return;
+        }
+
+        enqueue_outbound(&self.outbound_tx, json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": level,
+                "logger": logger,
+                "data": data,
+            }
+        }));
     }
 
     /// Convert error to JSON-RPC error
-    fn error_to_json_rpc_error(&self, error: anyhow::Error) -> JsonRpcError {
+    fn error_to_json_rpc_error(error: anyhow::Error) -> JsonRpcError {
         JsonRpcError {
             code: -32603, // Internal error
             message: error.to_string(),
@@ -294,11 +565,457 @@ impl McpProtocol {
     }
 
     /// Method not found error
-    fn method_not_found_error(&self, method: &str) -> anyhow::Error {
+    fn method_not_found_error(method: &str) -> anyhow::Error {
         anyhow!("Method not found: {}", method)
     }
 }
 
+/// MCP protocol handler
+pub struct McpProtocol {
+    reader: Box<dyn TransportReader>,
+    ctx: ConnectionContext,
+    /// Fed by `GraphStorage::notify_change` every time a mutation commits;
+    /// drained alongside `read_message` in `handle_connection`'s `select!`
+    /// so a pushed `notifications/resources/updated` can be written out
+    /// between client requests instead of waiting for the next one.
+    resource_updates_rx: mpsc::UnboundedReceiver<String>,
+    /// `AbortHandle`s for requests currently running on their own spawned
+    /// task, keyed by the request id's canonical JSON text (`serde_json::Value`
+    /// has no `Hash` impl, so the id itself can't be the map key). Consulted
+    /// by `handle_cancel_notification` and cleaned up by each task on its
+    /// own completion - never holds an entry for a notification, since a
+    /// notification has no id a later cancellation could reference.
+    in_flight: Arc<StdMutex<HashMap<String, AbortHandle>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientInfo {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub name: String,
+    pub version: String,
+    pub protocol_version: String,
+    pub capabilities: ServerCapabilities,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    pub tools: Option<ToolsCapability>,
+    pub resources: Option<ResourcesCapability>,
+    pub prompts: Option<PromptsCapability>,
+    pub logging: Option<LoggingCapability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolsCapability {
+    pub list_changed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcesCapability {
+    pub subscribe: Option<bool>,
+    pub list_changed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptsCapability {
+    pub list_changed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingCapability {
+    pub level: Option<String>,
+}
+
+impl McpProtocol {
+    /// Spawns the task that owns the transport's write half for the rest of
+    /// the connection's life: every outbound message, whether a normal
+    /// response, a batch reply, a pushed resource update, or a synthesized
+    /// cancellation error, goes through the returned channel instead of
+    /// writing directly, so concurrently-running request tasks never race
+    /// each other onto the wire.
+    fn spawn_writer(mut writer: Box<dyn TransportWriter>) -> mpsc::UnboundedSender<Value> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+
+        tokio::spawn(async move {
+            while let Some(value) = rx.recv().await {
+                let json_string = match serde_json::to_string(&value) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        error!("Failed to serialize outbound MCP message: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Err(e) = writer.send(&json_string).await {
+                    error!("Failed to write outbound MCP message: {}", e);
+                    break;
+                }
+            }
+        });
+
+        tx
+    }
+
+    /// Shared by every `new_*` constructor: wires up the reader/outbound
+    /// channel plus the `Arc`-shared storage/engines/managers every
+    /// transport needs alike, and registers this connection's own sender in
+    /// `GraphStorage`'s change-notifier registry so it gets pushed every
+    /// mutated resource URI alongside whatever other connections are live.
+    #[allow(clippy::too_many_arguments)]
+    fn assemble(
+        reader: Box<dyn TransportReader>,
+        outbound_tx: mpsc::UnboundedSender<Value>,
+        storage: Arc<GraphStorage>,
+        embedding_engine: Arc<LocalEmbeddingEngine>,
+        search_engine: Arc<HybridSearchEngine>,
+        memory_optimizer: Arc<MemoryOptimizer>,
+        rate_limiter: Arc<ToolRateLimiter>,
+        worker_manager: Arc<WorkerManager>,
+        recent_events: Arc<RecentEventsBuffer>,
+        search_queue: Arc<SearchQueue>,
+        stream_manager: Arc<StreamIngestionManager>,
+        watch_manager: Arc<IndexWatchManager>,
+    ) -> Self {
+        let (resource_update_tx, resource_updates_rx) = mpsc::unbounded_channel();
+        storage.set_change_notifier(resource_update_tx);
+
+        let ctx = ConnectionContext {
+            storage,
+            embedding_engine,
+            search_engine,
+            memory_optimizer,
+            rate_limiter,
+            worker_manager,
+            recent_events,
+            search_queue,
+            stream_manager,
+            watch_manager,
+            state: Arc::new(AsyncMutex::new(ConnectionState {
+                client_info: None,
+                initialized: false,
+                subscriptions: HashMap::new(),
+                log_level: LogLevel::Info,
+            })),
+            outbound_tx,
+        };
+
+        Self {
+            reader,
+            ctx,
+            resource_updates_rx,
+            in_flight: Arc::new(StdMutex::new(HashMap::new())),
+        }
+    }
+
+    /// Create a new MCP protocol handler using stdio
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_stdio(
+        storage: Arc<GraphStorage>,
+        embedding_engine: Arc<LocalEmbeddingEngine>,
+        search_engine: Arc<HybridSearchEngine>,
+        memory_optimizer: Arc<MemoryOptimizer>,
+        rate_limiter: Arc<ToolRateLimiter>,
+        worker_manager: Arc<WorkerManager>,
+        recent_events: Arc<RecentEventsBuffer>,
+        search_queue: Arc<SearchQueue>,
+        stream_manager: Arc<StreamIngestionManager>,
+        watch_manager: Arc<IndexWatchManager>,
+        framing: Framing,
+    ) -> Result<Self> {
+        let (reader, writer) = byte_stream_transport(BufReader::new(stdin()), stdout(), framing);
+        let outbound_tx = Self::spawn_writer(writer);
+        Ok(Self::assemble(
+            reader,
+            outbound_tx,
+            storage,
+            embedding_engine,
+            search_engine,
+            memory_optimizer,
+            rate_limiter,
+            worker_manager,
+            recent_events,
+            search_queue,
+            stream_manager,
+            watch_manager,
+        ))
+    }
+
+    /// Accepts TCP connections on `listener` forever, spawning an
+    /// independent `McpProtocol` - its own connection state, same as a
+    /// fresh stdio connection - for each one, all sharing the `Arc`-wrapped
+    /// storage/engines/managers passed in. One connection erroring out is
+    /// logged and closes only that connection; it does not bring down the
+    /// listener or any other client.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_tcp(
+        listener: tokio::net::TcpListener,
+        storage: Arc<GraphStorage>,
+        embedding_engine: Arc<LocalEmbeddingEngine>,
+        search_engine: Arc<HybridSearchEngine>,
+        memory_optimizer: Arc<MemoryOptimizer>,
+        rate_limiter: Arc<ToolRateLimiter>,
+        worker_manager: Arc<WorkerManager>,
+        recent_events: Arc<RecentEventsBuffer>,
+        search_queue: Arc<SearchQueue>,
+        stream_manager: Arc<StreamIngestionManager>,
+        watch_manager: Arc<IndexWatchManager>,
+        framing: Framing,
+    ) -> Result<()> {
+        loop {
+            let (stream, peer_addr) = listener.accept().await?;
+            info!("Accepted MCP TCP connection from {}", peer_addr);
+
+            let storage = Arc::clone(&storage);
+            let embedding_engine = Arc::clone(&embedding_engine);
+            let search_engine = Arc::clone(&search_engine);
+            let memory_optimizer = Arc::clone(&memory_optimizer);
+            let rate_limiter = Arc::clone(&rate_limiter);
+            let worker_manager = Arc::clone(&worker_manager);
+            let recent_events = Arc::clone(&recent_events);
+            let search_queue = Arc::clone(&search_queue);
+            let stream_manager = Arc::clone(&stream_manager);
+            let watch_manager = Arc::clone(&watch_manager);
+
+            tokio::spawn(async move {
+                let (read_half, write_half) = tokio::io::split(stream);
+                let (reader, writer) = byte_stream_transport(BufReader::new(read_half), write_half, framing);
+                let outbound_tx = Self::spawn_writer(writer);
+                let protocol = Self::assemble(
+                    reader,
+                    outbound_tx,
+                    storage,
+                    embedding_engine,
+                    search_engine,
+                    memory_optimizer,
+                    rate_limiter,
+                    worker_manager,
+                    recent_events,
+                    search_queue,
+                    stream_manager,
+                    watch_manager,
+                );
+
+                if let Err(e) = protocol.handle_connection().await {
+                    error!("MCP TCP connection from {} ended with error: {}", peer_addr, e);
+                }
+            });
+        }
+    }
+
+    /// Wraps an already-upgraded WebSocket connection (e.g. from an axum
+    /// `WebSocketUpgrade` handler) as its own `McpProtocol`, ready for
+    /// `handle_connection`. Multiple concurrent WebSocket clients are just
+    /// multiple calls to this constructor, same as `new_tcp`'s accept loop.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_websocket(
+        socket: axum::extract::ws::WebSocket,
+        storage: Arc<GraphStorage>,
+        embedding_engine: Arc<LocalEmbeddingEngine>,
+        search_engine: Arc<HybridSearchEngine>,
+        memory_optimizer: Arc<MemoryOptimizer>,
+        rate_limiter: Arc<ToolRateLimiter>,
+        worker_manager: Arc<WorkerManager>,
+        recent_events: Arc<RecentEventsBuffer>,
+        search_queue: Arc<SearchQueue>,
+        stream_manager: Arc<StreamIngestionManager>,
+        watch_manager: Arc<IndexWatchManager>,
+    ) -> Self {
+        use futures::StreamExt;
+
+        let (sink, stream) = socket.split();
+        let outbound_tx = Self::spawn_writer(Box::new(WebSocketWriter { sink }));
+
+        Self::assemble(
+            Box::new(WebSocketReader { stream }),
+            outbound_tx,
+            storage,
+            embedding_engine,
+            search_engine,
+            memory_optimizer,
+            rate_limiter,
+            worker_manager,
+            recent_events,
+            search_queue,
+            stream_manager,
+            watch_manager,
+        )
+    }
+
+    /// Handle MCP communication loop. Each incoming request is spawned onto
+    /// its own task (following lsp-server's `req_queue`) so a slow
+    /// `tools/call` can't stall the read loop or any other in-flight
+    /// request; the loop itself just keeps reading, dispatching, and
+    /// forwarding pushed resource updates.
+    pub async fn handle_connection(mut self) -> Result<()> {
+        info!("Starting MCP protocol handler");
+
+        loop {
+            tokio::select! {
+                message = self.read_message() => {
+                    match message {
+                        Ok(Some(IncomingMessage::Single(request))) => {
+                            if request.method == "notifications/cancelled" {
+                                self.handle_cancel_notification(request.params).await;
+                            } else {
+                                self.spawn_request(request);
+                            }
+                        }
+                        Ok(Some(IncomingMessage::Batch(requests))) => {
+                            let ctx = self.ctx.clone();
+                            tokio::spawn(async move {
+                                if let Some(payload) = ctx.handle_batch(requests).await {
+                                    enqueue_outbound(&ctx.outbound_tx, payload);
+                                }
+                            });
+                        }
+                        Ok(None) => {
+                            debug!("Client disconnected gracefully");
+                            break;
+                        }
+                        Err(e) => {
+                            error!("Error reading message: {}", e);
+                            break;
+                        }
+                    }
+                }
+                Some(uri) = self.resource_updates_rx.recv() => {
+                    self.push_resource_update(&uri).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a JSON-RPC message from the connection's transport. A message
+    /// body may hold either a single request object or a JSON-RPC 2.0 batch
+    /// (an array of request objects), so the body is parsed generically
+    /// first and only then deserialized into its typed shape.
+    async fn read_message(&mut self) -> Result<Option<IncomingMessage>> {
+        let Some(body) = self.reader.recv().await? else { return Ok(None) };
+
+        let value: Value = serde_json::from_str(&body)?;
+        let message = if value.is_array() {
+            IncomingMessage::Batch(serde_json::from_value(value)?)
+        } else {
+            let request: JsonRpcRequest = serde_json::from_value(value)?;
+            debug!("Parsed request: {} with id: {:?}", request.method, request.id);
+            IncomingMessage::Single(request)
+        };
+
+        Ok(Some(message))
+    }
+
+    /// Spawns `request` as its own task so it can run concurrently with
+    /// everything else on this connection. Notifications (no `id`) are
+    /// never added to `in_flight`, since there's nothing a later
+    /// `notifications/cancelled` could key off of.
+    fn spawn_request(&self, request: JsonRpcRequest) {
+        let id_key = request.id.as_ref().map(Self::id_key);
+        let ctx = self.ctx.clone();
+        let in_flight = Arc::clone(&self.in_flight);
+        let key_for_cleanup = id_key.clone();
+        // On the default multi-threaded runtime, a spawned task can be
+        // picked up by an idle worker and run to completion before the
+        // spawning thread gets back here to register its `AbortHandle` -
+        // `"ping"` and unknown-method requests resolve with zero `.await`
+        // points, so this isn't even a narrow window. This gate makes the
+        // task wait for that registration to actually happen (signaled
+        // below, strictly after the `in_flight.insert`) before it does any
+        // real work, so it can never see its own `remove` report "nothing
+        // to cancel" just because it outran its own bookkeeping.
+        let (registered_tx, registered_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let join_handle = tokio::spawn(async move {
+            let _ = registered_rx.await;
+            let response = ctx.handle_request(request).await;
+            // `AbortHandle::abort` can't interrupt a task that has already
+            // finished `handle_request` and has no further `.await` point
+            // left to cancel at - so whether this task's own result still
+            // goes out depends on winning the race to remove its
+            // `in_flight` entry. If `handle_cancel_notification` removed it
+            // first, it has already sent the synthesized cancellation
+            // response on this id's behalf, and sending the real response
+            // too would double-reply to the client.
+            let still_owns_response = match &key_for_cleanup {
+                Some(key) => in_flight.lock().unwrap().remove(key).is_some(),
+                None => true,
+            };
+            if still_owns_response {
+                enqueue_outbound(&ctx.outbound_tx, serde_json::to_value(&response).unwrap_or(Value::Null));
+            }
+        });
+
+        if let Some(key) = id_key {
+            self.in_flight.lock().unwrap().insert(key, join_handle.abort_handle());
+        }
+        // Only now may the task proceed - `in_flight` (if this request has
+        // an id at all) is guaranteed populated by this point.
+        let _ = registered_tx.send(());
+    }
+
+    /// Handles an incoming `notifications/cancelled` (`{"requestId": ...}`):
+    /// aborts the matching in-flight task, if any, and replies on its behalf
+    /// with a `-32800` "Request cancelled" error (lsp-server's
+    /// `RequestCancelled`) so the client isn't left waiting on a response
+    /// that will now never arrive. Silently does nothing for an unknown or
+    /// already-finished request id, since the real response (or nothing, if
+    /// it was itself a notification) has already gone out by then.
+    async fn handle_cancel_notification(&self, params: Option<Value>) {
+        let Some(request_id) = params.as_ref().and_then(|p| p.get("requestId")).cloned() else {
+            warn!("notifications/cancelled missing requestId");
+            return;
+        };
+
+        let key = Self::id_key(&request_id);
+        let Some(handle) = self.in_flight.lock().unwrap().remove(&key) else {
+            debug!("notifications/cancelled for unknown or already-finished request {}", key);
+            return;
+        };
+        handle.abort();
+
+        let cancelled = JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: Some(request_id),
+            result: None,
+            error: Some(JsonRpcError {
+                code: error_codes::REQUEST_CANCELLED,
+                message: "Request cancelled".to_string(),
+                data: None,
+            }),
+        };
+        enqueue_outbound(&self.ctx.outbound_tx, serde_json::to_value(&cancelled).unwrap_or(Value::Null));
+    }
+
+    /// Canonical `in_flight` map key for a JSON-RPC id: `serde_json::Value`
+    /// has no `Hash` impl, so requests are tracked by the id's serialized
+    /// JSON text instead - stable and unique for the string/number ids
+    /// JSON-RPC allows.
+    fn id_key(id: &Value) -> String {
+        serde_json::to_string(id).unwrap_or_default()
+    }
+
+    /// Forwards `uri` to the client as an id-less `notifications/resources/updated`
+    /// JSON-RPC notification, but only when it's actually subscribed - most
+    /// mutations have no subscriber at all, and this is called for every one.
+    async fn push_resource_update(&self, uri: &str) {
+        if !self.ctx.state.lock().await.subscriptions.contains_key(uri) {
+            return;
+        }
+
+        enqueue_outbound(&self.ctx.outbound_tx, json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/resources/updated",
+            "params": { "uri": uri }
+        }));
+    }
+}
+
 /// Error codes for JSON-RPC
 #[allow(dead_code)]
 pub mod error_codes {
@@ -307,4 +1024,8 @@ pub mod error_codes {
     pub const METHOD_NOT_FOUND: i32 = -32601;
     pub const INVALID_PARAMS: i32 = -32602;
     pub const INTERNAL_ERROR: i32 = -32603;
-} 
\ No newline at end of file
+    /// lsp-server's `RequestCancelled`, reused as-is for MCP's
+    /// `notifications/cancelled` since JSON-RPC itself has no standard code
+    /// for "this request was cancelled".
+    pub const REQUEST_CANCELLED: i32 = -32800;
+}
@@ -0,0 +1,196 @@
+//! Bounded admission control for `search_memory`/`add_memory`.
+//!
+//! `handle_tool_request` used to dispatch both straight onto the async
+//! runtime with no ceiling on how many could run at once, so a burst of
+//! queries could exhaust CPU and thrash the embedding engine. `SearchQueue`
+//! caps concurrently *executing* calls to a `tokio::sync::Semaphore` sized
+//! from `std::thread::available_parallelism()`, and bounds how many calls may
+//! be *waiting* for a permit at once via `capacity`.
+//!
+//! The interesting part is what happens when a waiter arrives and the queue
+//! is already at `capacity`: evicting the oldest waiter gives everyone the
+//! worst-case latency, and evicting the newest is trivially gameable (just
+//! don't be the request that tips it over). Instead one waiter is picked
+//! **uniformly at random** and dropped to make room — see `acquire`. A full
+//! queue then produces backpressure (random, bounded-latency rejections)
+//! rather than either unbounded queuing or a pile-up at one end.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rand::Rng;
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+
+use super::errors::McpError;
+
+/// Held for the duration of a `search_memory`/`add_memory` call; dropping it
+/// frees the concurrency permit for the next waiter.
+pub struct QueueTicket {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Admission control in front of the search/add execution path. See the
+/// module docs for the random-drop behavior that kicks in once `capacity`
+/// waiters are queued at once.
+pub struct SearchQueue {
+    /// Caps calls actually *running* at once.
+    concurrency: Arc<Semaphore>,
+    /// Total permits `concurrency` was created with, since
+    /// `Semaphore::available_permits` only reports what's left.
+    concurrency_permits: usize,
+    /// Caps calls *waiting* for a concurrency permit at once. Keyed by an
+    /// opaque id; each entry's sender is fired with `()` if that waiter is
+    /// chosen as the random eviction victim.
+    waiting: Arc<Mutex<HashMap<u64, oneshot::Sender<()>>>>,
+    capacity: usize,
+    next_id: AtomicU64,
+    /// Total callers shed via the random-drop eviction path, for the
+    /// `admin_metrics` tool; see `stats`.
+    rejected_total: AtomicU64,
+}
+
+/// Point-in-time occupancy/rejection snapshot, reported by the
+/// `admin_metrics` tool.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct SearchQueueStats {
+    pub running: usize,
+    pub running_capacity: usize,
+    pub waiting: usize,
+    pub waiting_capacity: usize,
+    pub rejected_total: u64,
+}
+
+impl SearchQueue {
+    /// `capacity` is the configured `ServerConfig::search_queue_size` —
+    /// how many callers may be queued awaiting a permit before the random-drop
+    /// admission control in `acquire` starts shedding load. The number of
+    /// permits that can run concurrently is derived from
+    /// `std::thread::available_parallelism()` (falling back to 2 on
+    /// platforms that can't report it), not from `capacity`.
+    pub fn new(capacity: usize) -> Self {
+        let concurrent_permits = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2);
+        Self {
+            concurrency: Arc::new(Semaphore::new(concurrent_permits)),
+            concurrency_permits: concurrent_permits,
+            waiting: Arc::new(Mutex::new(HashMap::new())),
+            capacity: capacity.max(1),
+            next_id: AtomicU64::new(0),
+            rejected_total: AtomicU64::new(0),
+        }
+    }
+
+    /// Current occupancy and lifetime rejection count; see `SearchQueueStats`.
+    pub fn stats(&self) -> SearchQueueStats {
+        let running_capacity = self.concurrency_permits;
+        let running = running_capacity - self.concurrency.available_permits();
+        SearchQueueStats {
+            running,
+            running_capacity,
+            waiting: self.waiting.lock().unwrap().len(),
+            waiting_capacity: self.capacity,
+            rejected_total: self.rejected_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Waits for a concurrency permit, queuing behind `capacity` other
+    /// callers at most. If the queue is already full when this call joins
+    /// it, one currently-waiting caller — picked uniformly at random, which
+    /// may or may not be this one — is evicted to make room; the evicted
+    /// caller gets `McpError::QueueFull` back instead of waiting forever.
+    pub async fn acquire(&self) -> Result<QueueTicket, McpError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (evicted_tx, evicted_rx) = oneshot::channel();
+
+        {
+            let mut waiting = self.waiting.lock().unwrap();
+            if waiting.len() >= self.capacity {
+                let victim_index = rand::thread_rng().gen_range(0..waiting.len());
+                if let Some(&victim_id) = waiting.keys().nth(victim_index) {
+                    if let Some(victim_tx) = waiting.remove(&victim_id) {
+                        // The victim's `acquire` call is still waiting on
+                        // `evicted_rx`; a closed receiver (it already
+                        // finished some other way) is fine to ignore.
+                        let _ = victim_tx.send(());
+                    }
+                }
+            }
+            waiting.insert(id, evicted_tx);
+        }
+
+        let result = tokio::select! {
+            biased;
+            _ = evicted_rx => {
+                self.rejected_total.fetch_add(1, Ordering::Relaxed);
+                Err(McpError::QueueFull {
+                    message: "Dropped from the search queue to make room for a newer request".to_string(),
+                    retry_after_secs: Some(1.0),
+                })
+            },
+            permit = Arc::clone(&self.concurrency).acquire_owned() => {
+                permit
+                    .map(|p| QueueTicket { _permit: p })
+                    .map_err(|_| McpError::Internal { message: "search queue is shutting down".to_string() })
+            }
+        };
+
+        self.waiting.lock().unwrap().remove(&id);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn admits_up_to_available_parallelism_concurrently() {
+        let queue = Arc::new(SearchQueue::new(16));
+        let running_capacity = queue.stats().running_capacity;
+        let mut tickets = Vec::with_capacity(running_capacity);
+        for _ in 0..running_capacity {
+            tickets.push(queue.acquire().await.expect("acquire within the concurrency limit should succeed"));
+        }
+        assert_eq!(queue.stats().running, running_capacity);
+        drop(tickets);
+    }
+
+    #[tokio::test]
+    async fn sheds_load_once_waiting_capacity_is_exceeded() {
+        // Capacity of 1 waiter plus every concurrency permit held by this
+        // task forces every further `acquire` call to actually queue, so
+        // the random-drop path is exercised deterministically: with only
+        // one waiter slot, whichever call already occupies it gets evicted
+        // the moment a second call joins.
+        let queue = Arc::new(SearchQueue::new(1));
+        let mut held = Vec::new();
+        for _ in 0..queue.stats().running_capacity {
+            held.push(queue.acquire().await.expect("acquire within the concurrency limit should succeed"));
+        }
+
+        let q2 = Arc::clone(&queue);
+        let first_waiter = tokio::spawn(async move { q2.acquire().await });
+        // Give the spawned task a chance to register itself as a waiter
+        // before this call tries to evict someone to make room for itself.
+        tokio::task::yield_now().await;
+
+        let q3 = Arc::clone(&queue);
+        let second_waiter = tokio::spawn(async move { q3.acquire().await });
+        // Let the second call register itself and run its eviction check
+        // before the first call's permit becomes available.
+        tokio::task::yield_now().await;
+
+        let first_outcome = first_waiter.await.expect("waiter task should not panic");
+        assert!(
+            matches!(first_outcome, Err(McpError::QueueFull { .. })),
+            "the earlier waiter should be evicted to make room for the later one, got {:?}",
+            first_outcome.map(|_| ())
+        );
+
+        drop(held);
+        let second_outcome = second_waiter.await.expect("waiter task should not panic");
+        assert!(second_outcome.is_ok(), "the later call should be admitted once a permit freed up");
+    }
+}
@@ -1,24 +1,85 @@
 use anyhow::Result;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 use std::sync::Arc;
 use axum::{
-    extract::State,
-    http::{header, StatusCode},
+    extract::{Extension, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response, Sse},
     routing::{get, post},
     Json, Router,
 };
-use futures::stream::{self, Stream};
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
 use tower_http::cors::CorsLayer;
 
 use crate::config::ServerConfig;
+use crate::graph::{Episode, EpisodeSource};
 use crate::graph::storage::GraphStorage;
 use crate::embeddings::LocalEmbeddingEngine;
 use crate::search::{HybridSearchEngine, TextSearchEngine, VectorSearchEngine};
 use crate::memory::MemoryOptimizer;
+use super::auth::{Authenticator, Principal};
+use super::errors::{ErrorContext, McpError, RateLimiter, ToolRateLimiter};
+use super::performance::PerformanceMonitor;
 use super::protocol::McpProtocol;
+use super::search_queue::SearchQueue;
+use super::workers::{DbHealthCheckWorker, EmbeddingWarmupWorker, MemoryGcWorker, WorkerManager};
+use crate::indexing::{StreamIngestionManager, IndexWatchManager};
+use crate::metrics::RecentEventsBuffer;
+use crate::security::api_keys::{ApiKeyScope, ResolvedScopes};
+use crate::stability::CircuitBreakerRegistry;
+
+/// Per-`(tool, status)` call counters backing the `kg_tool_calls_total`
+/// OpenMetrics counter. Incremented once per `handle_tool_call_mcp`
+/// invocation, alongside `ToolRateLimiter`'s per-tool buckets.
+#[derive(Default)]
+pub struct ToolCallMetrics {
+    counts: std::sync::Mutex<std::collections::HashMap<(String, String), u64>>,
+}
+
+impl ToolCallMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, tool_name: &str, status: &str) {
+        let mut counts = self.counts.lock().unwrap();
+        *counts.entry((tool_name.to_string(), status.to_string())).or_insert(0) += 1;
+    }
+
+    fn snapshot(&self) -> Vec<((String, String), u64)> {
+        self.counts.lock().unwrap().iter().map(|(k, v)| (k.clone(), *v)).collect()
+    }
+}
+
+/// Per-`McpError::variant_name` counters backing the `/metrics` admin
+/// endpoint's `errors` field. Incremented wherever an `McpError` is turned
+/// into a client-visible denial or failure (see `denied_response` and
+/// `handle_tool_call_mcp`'s dispatch-error branch).
+#[derive(Default)]
+pub struct ErrorMetrics {
+    counts: std::sync::Mutex<std::collections::HashMap<&'static str, u64>>,
+}
+
+impl ErrorMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, variant: &'static str) {
+        let mut counts = self.counts.lock().unwrap();
+        *counts.entry(variant).or_insert(0) += 1;
+    }
+
+    fn snapshot(&self) -> Vec<(&'static str, u64)> {
+        self.counts.lock().unwrap().iter().map(|(k, v)| (*k, *v)).collect()
+    }
+}
 
 #[derive(Clone)]
 pub struct AppState {
@@ -26,6 +87,61 @@ pub struct AppState {
     embedding_engine: Arc<LocalEmbeddingEngine>,
     search_engine: Arc<HybridSearchEngine>,
     memory_optimizer: Arc<MemoryOptimizer>,
+    rate_limiter: Arc<ToolRateLimiter>,
+    tool_call_metrics: Arc<ToolCallMetrics>,
+    /// Backs the `/metrics` admin endpoint's `errors` field; see `ErrorMetrics`.
+    error_metrics: Arc<ErrorMetrics>,
+    /// Cache and per-tool latency stats backing the `/metrics` OpenMetrics
+    /// histogram samples; see `PerformanceMonitor::render_prometheus`.
+    performance_monitor: Arc<PerformanceMonitor>,
+    /// Per-breaker state gauges backing the `/metrics` OpenMetrics output;
+    /// see `CircuitBreakerRegistry::render_prometheus`. No breaker is
+    /// registered against any tool yet, so this is currently always empty -
+    /// tripping one is left to whichever future request adds per-tool
+    /// circuit breaking.
+    circuit_breakers: Arc<CircuitBreakerRegistry>,
+    worker_manager: Arc<WorkerManager>,
+    /// Backs the `get_recent_events` tool; see `McpServer::recent_events`.
+    recent_events: Arc<RecentEventsBuffer>,
+    /// Bounds concurrent/queued `search_memory`/`add_memory` execution; see
+    /// `mcp::search_queue::SearchQueue`.
+    search_queue: Arc<SearchQueue>,
+    /// Registry of running streaming-ingestion sources; see
+    /// `indexing::streaming::StreamIngestionManager`.
+    stream_manager: Arc<StreamIngestionManager>,
+    /// Registry of running codebase-watch sources; see
+    /// `indexing::watcher::IndexWatchManager`.
+    watch_manager: Arc<IndexWatchManager>,
+    /// Per-`(principal, tool)` token-bucket rate limiting (see
+    /// `auth::Authenticator`/`errors::RateLimiter`), distinct from
+    /// `rate_limiter`'s global per-tool buckets, which aren't keyed by
+    /// caller. Shared behind a `Mutex` since `RateLimiter::check_rate_limit`
+    /// takes `&mut self` and `AppState` is cloned per request.
+    client_rate_limiter: Arc<std::sync::Mutex<RateLimiter>>,
+    /// Whether `api_key_auth` enforces `Authorization: Bearer <key>` on this
+    /// request. Always `false` for the stdio transport (no HTTP surface to
+    /// protect); for HTTP/SSE it mirrors `config.security.enable_authentication`
+    /// unless overridden by `KG_MCP_ALLOW_UNAUTHENTICATED` for local dev.
+    auth_required: bool,
+    start_time: std::time::Instant,
+    /// Flips to `true` once a shutdown signal has been received; watched by
+    /// long-lived handlers (the SSE keep-alive stream) so they can wind down
+    /// instead of being hard-killed when the listener stops.
+    shutdown: tokio::sync::watch::Receiver<bool>,
+    /// Live `/sse` connections, keyed by session id, so a `tools/call`
+    /// POSTed to `/sse?sessionId=...` can stream `notifications/progress`
+    /// messages back over the matching open GET connection.
+    sse_sessions: Arc<RwLock<HashMap<String, crate::mcp::handlers::ProgressSink>>>,
+}
+
+/// Query parameters accepted by both `/sse` endpoints for correlating a
+/// `tools/call` POST with the GET connection that should stream its
+/// progress. Absent on the initial GET, which mints a fresh id and reports
+/// it back via a `session` SSE event.
+#[derive(Debug, Deserialize)]
+struct SseSessionParams {
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
 }
 
 pub struct McpServer {
@@ -34,6 +150,25 @@ pub struct McpServer {
     embedding_engine: Arc<LocalEmbeddingEngine>,
     search_engine: Arc<HybridSearchEngine>,
     memory_optimizer: Arc<MemoryOptimizer>,
+    rate_limiter: Arc<ToolRateLimiter>,
+    tool_call_metrics: Arc<ToolCallMetrics>,
+    /// See `AppState::error_metrics`.
+    error_metrics: Arc<ErrorMetrics>,
+    /// See `AppState::performance_monitor`.
+    performance_monitor: Arc<PerformanceMonitor>,
+    /// See `AppState::circuit_breakers`.
+    circuit_breakers: Arc<CircuitBreakerRegistry>,
+    worker_manager: Arc<WorkerManager>,
+    recent_events: Arc<RecentEventsBuffer>,
+    /// See `AppState::search_queue`.
+    search_queue: Arc<SearchQueue>,
+    /// See `AppState::stream_manager`.
+    stream_manager: Arc<StreamIngestionManager>,
+    /// See `AppState::watch_manager`.
+    watch_manager: Arc<IndexWatchManager>,
+    /// See `AppState::client_rate_limiter`.
+    client_rate_limiter: Arc<std::sync::Mutex<RateLimiter>>,
+    start_time: std::time::Instant,
 }
 
 impl McpServer {
@@ -44,12 +179,27 @@ impl McpServer {
         search_engine: Arc<HybridSearchEngine>,
         memory_optimizer: Arc<MemoryOptimizer>,
     ) -> Self {
+        let rate_limiter = Arc::new(ToolRateLimiter::new(config.tool_rate_limit.clone()));
+        let client_rate_limiter = Arc::new(std::sync::Mutex::new(RateLimiter::with_burst_capacity(config.security.rate_limit_requests_per_minute, config.security.rate_limit_burst)));
+        let search_queue = Arc::new(SearchQueue::new(config.search_queue_size));
         Self {
             config,
             storage,
             embedding_engine,
             search_engine,
             memory_optimizer,
+            rate_limiter,
+            tool_call_metrics: Arc::new(ToolCallMetrics::new()),
+            error_metrics: Arc::new(ErrorMetrics::new()),
+            performance_monitor: Arc::new(PerformanceMonitor::new(1000, Duration::from_secs(300))),
+            circuit_breakers: Arc::new(CircuitBreakerRegistry::new()),
+            worker_manager: Arc::new(WorkerManager::new()),
+            recent_events: Arc::new(RecentEventsBuffer::default()),
+            search_queue,
+            stream_manager: Arc::new(StreamIngestionManager::new()),
+            watch_manager: Arc::new(IndexWatchManager::new()),
+            client_rate_limiter,
+            start_time: std::time::Instant::now(),
         }
     }
 
@@ -79,37 +229,64 @@ impl McpServer {
             memory_mapping_enabled: false,
         };
         let memory_optimizer = Arc::new(MemoryOptimizer::new(memory_config));
-        
+        let rate_limiter = Arc::new(ToolRateLimiter::new(config.tool_rate_limit.clone()));
+        let client_rate_limiter = Arc::new(std::sync::Mutex::new(RateLimiter::with_burst_capacity(config.security.rate_limit_requests_per_minute, config.security.rate_limit_burst)));
+        let search_queue = Arc::new(SearchQueue::new(config.search_queue_size));
+
         // Initialize components
         memory_optimizer.initialize().await?;
-        
+
         info!("KG MCP Server components initialized successfully");
-        
+
         Ok(Self {
             config,
             storage,
             embedding_engine,
             search_engine,
             memory_optimizer,
+            rate_limiter,
+            tool_call_metrics: Arc::new(ToolCallMetrics::new()),
+            error_metrics: Arc::new(ErrorMetrics::new()),
+            performance_monitor: Arc::new(PerformanceMonitor::new(1000, Duration::from_secs(300))),
+            circuit_breakers: Arc::new(CircuitBreakerRegistry::new()),
+            worker_manager: Arc::new(WorkerManager::new()),
+            recent_events: Arc::new(RecentEventsBuffer::default()),
+            search_queue,
+            stream_manager: Arc::new(StreamIngestionManager::new()),
+            watch_manager: Arc::new(IndexWatchManager::new()),
+            client_rate_limiter,
+            start_time: std::time::Instant::now(),
         })
     }
 
     pub async fn run(&self) -> Result<()> {
+        // Nothing ever sends on this channel, so `run_with_shutdown` only
+        // ever stops via SIGINT/SIGTERM, matching `run`'s historical behavior.
+        let (_never_tx, never_rx) = tokio::sync::watch::channel(false);
+        self.run_with_shutdown(never_rx).await
+    }
+
+    /// Like [`run`](Self::run), but also stops as soon as `external_shutdown`
+    /// reports `true` — e.g. the tray app's "Restart Server" action signaling
+    /// a hot restart instead of the process exiting outright.
+    pub async fn run_with_shutdown(&self, external_shutdown: tokio::sync::watch::Receiver<bool>) -> Result<()> {
         info!("KG MCP Server starting...");
 
         // Start background tasks
         self.start_background_tasks().await?;
 
-        // Check if we should run as HTTP/SSE server or stdio
-        if std::env::var("MCP_TRANSPORT").as_deref() == Ok("sse") || 
+        // Check if we should run as HTTP/SSE, raw TCP, or stdio
+        if std::env::var("MCP_TRANSPORT").as_deref() == Ok("sse") ||
            std::env::var("MCP_TRANSPORT").as_deref() == Ok("http") {
-            self.run_http_server().await
+            self.run_http_server(external_shutdown).await
+        } else if std::env::var("MCP_TRANSPORT").as_deref() == Ok("tcp") {
+            self.run_tcp_server().await
         } else {
             self.run_stdio_server().await
         }
     }
 
-    async fn run_http_server(&self) -> Result<()> {
+    async fn run_http_server(&self, external_shutdown: tokio::sync::watch::Receiver<bool>) -> Result<()> {
         let port = std::env::var("MCP_PORT")
             .unwrap_or_else(|_| self.config.port.to_string())
             .parse::<u16>()
@@ -117,46 +294,122 @@ impl McpServer {
 
         info!("Starting KG MCP Server with HTTP/SSE transport on port {}", port);
 
+        let auth_required = self.config.security.enable_authentication
+            && std::env::var("KG_MCP_ALLOW_UNAUTHENTICATED").is_err();
+        if self.config.security.enable_authentication && !auth_required {
+            info!("⚠️  KG_MCP_ALLOW_UNAUTHENTICATED set: API key enforcement disabled despite security.enable_authentication");
+        }
+
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+
         let state = AppState {
             storage: Arc::clone(&self.storage),
             embedding_engine: Arc::clone(&self.embedding_engine),
             search_engine: Arc::clone(&self.search_engine),
             memory_optimizer: Arc::clone(&self.memory_optimizer),
+            rate_limiter: Arc::clone(&self.rate_limiter),
+            tool_call_metrics: Arc::clone(&self.tool_call_metrics),
+            error_metrics: Arc::clone(&self.error_metrics),
+            performance_monitor: Arc::clone(&self.performance_monitor),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            worker_manager: Arc::clone(&self.worker_manager),
+            recent_events: Arc::clone(&self.recent_events),
+            search_queue: Arc::clone(&self.search_queue),
+            stream_manager: Arc::clone(&self.stream_manager),
+            watch_manager: Arc::clone(&self.watch_manager),
+            client_rate_limiter: Arc::clone(&self.client_rate_limiter),
+            auth_required,
+            start_time: self.start_time,
+            shutdown: shutdown_rx,
+            sse_sessions: Arc::new(RwLock::new(HashMap::new())),
         };
 
-        let app = Router::new()
+        // Everything except `/health` requires a valid API key when
+        // `auth_required`; `route_layer` scopes the middleware to just the
+        // routes added before it, so the liveness probe stays reachable
+        // even with authentication enabled.
+        let protected = Router::new()
             // SSE endpoint for MCP clients - handles both GET and POST
             .route("/sse", get(handle_sse_connect).post(handle_sse_request))
             // HTTP endpoint for MCP over HTTP
             .route("/mcp", post(handle_mcp_request))
-            // Health check endpoint
-            .route("/health", get(health_check))
+            // WebSocket endpoint for MCP - each upgraded connection runs its
+            // own `McpProtocol` via `McpProtocol::new_websocket`, independent
+            // of the SSE/HTTP request-response handlers above.
+            .route("/mcp/ws", get(handle_mcp_websocket))
+            // Streaming multipart file ingestion (see `handle_file_ingest`)
+            .route("/ingest/file", post(handle_file_ingest))
             // Metrics endpoint
             .route("/metrics", get(metrics_endpoint))
+            // Background worker status (see mcp::workers)
+            .route("/workers", get(workers_endpoint))
+            // Active rate-limited clients (see `errors::RateLimiter::active_clients`)
+            .route("/clients", get(clients_endpoint))
             // Tool endpoints (for debugging)
             .route("/tools", get(list_tools))
+            // API key administration (see security::api_keys) — Admin scope only
+            .route("/admin/keys", get(list_api_keys_endpoint).post(create_api_key_endpoint))
+            .route("/admin/keys/:id", axum::routing::delete(revoke_api_key_endpoint))
+            .route_layer(axum::middleware::from_fn_with_state(state.clone(), api_key_auth));
+
+        let app = Router::new()
+            // Health check endpoint: intentionally outside `protected` so
+            // infra probes work without a key.
+            .route("/health", get(health_check))
+            .merge(protected)
             .layer(CorsLayer::permissive())
             .with_state(state);
 
         let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
         info!("✅ KG MCP Server listening on http://0.0.0.0:{}/sse", port);
-        
-        axum::serve(listener, app).await?;
-        
+
+        // `with_graceful_shutdown` stops accepting new connections as soon as
+        // the future below resolves and then waits for in-flight requests to
+        // finish; `shutdown_tx` additionally lets the SSE keep-alive stream
+        // notice and send a final `close` event instead of being cut off.
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_signal(shutdown_tx, external_shutdown))
+            .await?;
+
+        info!("HTTP listener stopped accepting connections; draining background workers...");
+        self.worker_manager.shutdown_all().await;
+
+        info!("Flushing memory-optimizer state before exit...");
+        if let Err(e) = self.memory_optimizer.force_gc().await {
+            warn!("Final memory flush failed during shutdown: {}", e);
+        }
+        self.memory_optimizer.shutdown().await;
+
         Ok(())
     }
 
     async fn run_stdio_server(&self) -> Result<()> {
         info!("KG MCP Server starting with stdio communication");
 
+        // `MCP_FRAMING=content-length` opts into LSP-style
+        // `Content-Length: N\r\n\r\n` framing; anything else (including
+        // unset) keeps the line-delimited default for backward compatibility.
+        let framing = if std::env::var("MCP_FRAMING").as_deref() == Ok("content-length") {
+            crate::mcp::protocol::Framing::ContentLength
+        } else {
+            crate::mcp::protocol::Framing::LineDelimited
+        };
+
         // Create protocol handler using stdin/stdout for MCP communication
         let protocol = McpProtocol::new_stdio(
             Arc::clone(&self.storage),
             Arc::clone(&self.embedding_engine),
             Arc::clone(&self.search_engine),
             Arc::clone(&self.memory_optimizer),
+            Arc::clone(&self.rate_limiter),
+            Arc::clone(&self.worker_manager),
+            Arc::clone(&self.recent_events),
+            Arc::clone(&self.search_queue),
+            Arc::clone(&self.stream_manager),
+            Arc::clone(&self.watch_manager),
+            framing,
         ).await?;
-        
+
         info!("MCP Server ready for communication via stdio");
         
         // Handle MCP protocol via stdio
@@ -165,70 +418,55 @@ impl McpServer {
         Ok(())
     }
 
+    /// Runs `McpProtocol::new_tcp`'s accept loop on `MCP_TCP_PORT` (default
+    /// 9090), the same JSON-RPC framing the stdio transport uses (see
+    /// `run_stdio_server`'s `MCP_FRAMING` handling) but over a plain TCP
+    /// socket instead of stdin/stdout - useful for clients that can't spawn
+    /// a child process but can open a socket (e.g. a remote dev container).
+    async fn run_tcp_server(&self) -> Result<()> {
+        let port = std::env::var("MCP_TCP_PORT")
+            .ok()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(9090);
+
+        let framing = if std::env::var("MCP_FRAMING").as_deref() == Ok("content-length") {
+            crate::mcp::protocol::Framing::ContentLength
+        } else {
+            crate::mcp::protocol::Framing::LineDelimited
+        };
+
+        let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
+        info!("✅ KG MCP Server listening for raw TCP MCP connections on 0.0.0.0:{}", port);
+
+        McpProtocol::new_tcp(
+            listener,
+            Arc::clone(&self.storage),
+            Arc::clone(&self.embedding_engine),
+            Arc::clone(&self.search_engine),
+            Arc::clone(&self.memory_optimizer),
+            Arc::clone(&self.rate_limiter),
+            Arc::clone(&self.worker_manager),
+            Arc::clone(&self.recent_events),
+            Arc::clone(&self.search_queue),
+            Arc::clone(&self.stream_manager),
+            Arc::clone(&self.watch_manager),
+            framing,
+        ).await
+    }
+
     /// Start background maintenance tasks
     async fn start_background_tasks(&self) -> Result<()> {
         info!("Starting background maintenance tasks");
-        
-        // Start memory optimization background task with error recovery
-        let memory_optimizer = Arc::clone(&self.memory_optimizer);
-        tokio::spawn(async move {
-            let mut consecutive_errors = 0;
-            const MAX_CONSECUTIVE_ERRORS: u32 = 5;
-            
-            loop {
-                tokio::time::sleep(std::time::Duration::from_secs(300)).await; // 5 minutes
-                
-                match memory_optimizer.force_gc().await {
-                    Ok(_) => {
-                        consecutive_errors = 0;
-                        tracing::debug!("Memory GC completed successfully");
-                    },
-                    Err(e) => {
-                        consecutive_errors += 1;
-                        tracing::error!("Memory optimization error (attempt {}): {}", consecutive_errors, e);
-                        
-                        if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-                            tracing::error!("Too many consecutive GC failures, backing off");
-                            tokio::time::sleep(std::time::Duration::from_secs(1800)).await; // 30 minutes
-                            consecutive_errors = 0;
-                        }
-                    }
-                }
-            }
-        });
-
-        // Start embedding cache warmup with error recovery
-        let embedding_engine = Arc::clone(&self.embedding_engine);
-        tokio::spawn(async move {
-            let common_queries = vec![
-                "search".to_string(),
-                "query".to_string(),
-                "find".to_string(),
-                "knowledge".to_string(),
-                "graph".to_string(),
-            ];
-            
-            match embedding_engine.warmup(common_queries).await {
-                Ok(_) => tracing::info!("Embedding warmup completed successfully"),
-                Err(e) => {
-                    tracing::error!("Embedding warmup error: {}", e);
-                    // Don't crash the server, just log the error
-                }
-            }
-        });
-
-        // Start database health check task
-        let storage = Arc::clone(&self.storage);
-        tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(std::time::Duration::from_secs(600)).await; // 10 minutes
-                
-                match storage.count_nodes().await {
-                    Ok(count) => tracing::debug!("Database health check: {} nodes", count),
-                    Err(e) => tracing::warn!("Database health check failed: {}", e),
-                }
-            }
-        });
+
+        self.worker_manager
+            .register(MemoryGcWorker::new(Arc::clone(&self.memory_optimizer)))
+            .await;
+        self.worker_manager
+            .register(EmbeddingWarmupWorker::new(Arc::clone(&self.embedding_engine)))
+            .await;
+        self.worker_manager
+            .register(DbHealthCheckWorker::new(Arc::clone(&self.storage)))
+            .await;
 
         info!("Background tasks started successfully");
         Ok(())
@@ -250,27 +488,148 @@ impl McpServer {
     pub fn get_memory_optimizer(&self) -> Arc<MemoryOptimizer> {
         Arc::clone(&self.memory_optimizer)
     }
+
+    /// The buffer backing `get_recent_events`/the tray app's "Recent Events"
+    /// submenu. Install a `metrics::RecentEventsLayer` wrapping this on the
+    /// process's `tracing_subscriber::registry()` to actually feed it.
+    pub fn recent_events(&self) -> Arc<RecentEventsBuffer> {
+        Arc::clone(&self.recent_events)
+    }
+
+    /// Points `get_recent_events` at `buffer` instead of the default one
+    /// created in `new`/`new_from_config`, so a caller that installs its own
+    /// process-wide `RecentEventsLayer` (e.g. the tray app, which needs the
+    /// buffer before the server exists to build its "Recent Events" menu
+    /// item) can have the server read from that same instance.
+    pub fn with_recent_events_buffer(mut self, buffer: Arc<RecentEventsBuffer>) -> Self {
+        self.recent_events = buffer;
+        self
+    }
+}
+
+/// Resolves once SIGINT (Ctrl+C)/SIGTERM arrives, or `external_shutdown`
+/// reports `true` (a hot restart requested from outside this process, e.g.
+/// the tray app), then flips `shutdown_tx` so every clone of its receiver
+/// (the SSE keep-alive stream) observes it. Passed to
+/// `axum::serve(...).with_graceful_shutdown(...)`.
+async fn shutdown_signal(
+    shutdown_tx: tokio::sync::watch::Sender<bool>,
+    mut external_shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let external = async {
+        while external_shutdown.changed().await.is_ok() {
+            if *external_shutdown.borrow() {
+                break;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C, starting graceful shutdown"),
+        _ = terminate => info!("Received SIGTERM, starting graceful shutdown"),
+        _ = external => info!("Restart requested; starting graceful shutdown of current generation"),
+    }
+
+    let _ = shutdown_tx.send(true);
 }
 
 // HTTP/SSE handlers
 
+/// State threaded through `handle_sse_connect`'s `stream::unfold`: the
+/// shutdown watch, this connection's progress receiver, and what's needed
+/// to deregister its session once the stream ends.
+struct SseConnState {
+    shutdown: tokio::sync::watch::Receiver<bool>,
+    progress_rx: mpsc::UnboundedReceiver<Value>,
+    sessions: Arc<RwLock<HashMap<String, crate::mcp::handlers::ProgressSink>>>,
+    session_id: String,
+}
+
 async fn handle_sse_connect(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
+    Query(params): Query<SseSessionParams>,
 ) -> impl IntoResponse {
-    info!("SSE connection established, waiting for requests...");
-    
-    // Just establish the connection - don't send any events initially
-    let stream = stream::unfold((), move |_| async move {
-        // Keep the connection alive but don't send any data
-        tokio::time::sleep(Duration::from_secs(30)).await;
-        Some((Ok::<axum::response::sse::Event, std::convert::Infallible>(
-            axum::response::sse::Event::default()
-                .event("ping")
-                .data("keep-alive")
-        ), ()))
+    let session_id = params.session_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    info!("SSE connection established for session {}, waiting for requests...", session_id);
+
+    let (progress_tx, progress_rx) = mpsc::unbounded_channel();
+    state.sse_sessions.write().await.insert(session_id.clone(), progress_tx);
+
+    // Tell the client its session id up front so a `tools/call` POSTed to
+    // `/sse?sessionId=...` streams its `notifications/progress` updates
+    // back over this same connection.
+    let announce = stream::once({
+        let session_id = session_id.clone();
+        async move {
+            Ok::<axum::response::sse::Event, std::convert::Infallible>(
+                axum::response::sse::Event::default()
+                    .event("session")
+                    .data(json!({ "sessionId": session_id }).to_string()),
+            )
+        }
     });
 
-    Sse::new(stream)
+    let conn_state = SseConnState {
+        shutdown: state.shutdown.clone(),
+        progress_rx,
+        sessions: Arc::clone(&state.sse_sessions),
+        session_id,
+    };
+
+    // Forwards progress notifications as they arrive, pings on an idle
+    // timeout to keep the connection alive, and ends with a final `close`
+    // event (deregistering the session) once shutdown is signaled.
+    let body = stream::unfold(Some(conn_state), move |state| async move {
+        let mut state = state?;
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(30)) => {
+                Some((Ok::<axum::response::sse::Event, std::convert::Infallible>(
+                    axum::response::sse::Event::default()
+                        .event("ping")
+                        .data("keep-alive")
+                ), Some(state)))
+            }
+            msg = state.progress_rx.recv() => {
+                match msg {
+                    Some(payload) => Some((Ok(
+                        axum::response::sse::Event::default()
+                            .event("message")
+                            .data(payload.to_string())
+                    ), Some(state))),
+                    None => {
+                        state.sessions.write().await.remove(&state.session_id);
+                        None
+                    }
+                }
+            }
+            _ = state.shutdown.changed() => {
+                state.sessions.write().await.remove(&state.session_id);
+                Some((Ok(
+                    axum::response::sse::Event::default()
+                        .event("close")
+                        .data("server shutting down")
+                ), None))
+            }
+        }
+    });
+
+    Sse::new(announce.chain(body))
         .keep_alive(
             axum::response::sse::KeepAlive::new()
                 .interval(Duration::from_secs(30))
@@ -280,46 +639,241 @@ async fn handle_sse_connect(
 
 async fn handle_sse_request(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(params): Query<SseSessionParams>,
     Json(request): Json<Value>,
 ) -> impl IntoResponse {
     info!("Received MCP SSE request: {}", request);
-    
-    // Handle MCP request and respond with SSE
-    let response = handle_mcp_message(&state, request).await;
-    
-    // Return the response as SSE
-    let event = axum::response::sse::Event::default()
-        .data(response.to_string());
-    
+
+    // If this POST names a session with an open `/sse` GET connection,
+    // grab its progress sink so a `tools/call` with a `progressToken` can
+    // stream updates back over that connection as it runs.
+    let progress_sink = match &params.session_id {
+        Some(session_id) => state.sse_sessions.read().await.get(session_id).cloned(),
+        None => None,
+    };
+
+    // Handle MCP request (possibly a JSON-RPC batch) and respond with SSE
+    let response = handle_mcp_payload(&state, &principal, request, progress_sink).await;
+
+    let body = match response {
+        Some(response) => format!("data: {}\n\n", response),
+        // All-notifications batch: nothing to correlate, so no event to send.
+        None => String::new(),
+    };
+
     axum::response::Response::builder()
         .header("Content-Type", "text/event-stream")
         .header("Cache-Control", "no-cache")
         .header("Connection", "keep-alive")
         .header("Access-Control-Allow-Origin", "*")
-        .body(format!("data: {}\n\n", response))
+        .body(body)
         .unwrap()
 }
 
 async fn handle_mcp_request(
     State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(request): Json<Value>,
 ) -> impl IntoResponse {
     info!("Received MCP HTTP request: {}", request);
-    
-    let response = handle_mcp_message(&state, request).await;
-    Json(response)
+
+    // Plain `/mcp` has no SSE connection to stream progress over.
+    match handle_mcp_payload(&state, &principal, request, None).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
 }
 
-async fn handle_mcp_message(state: &AppState, request: Value) -> Value {
+/// Upgrades `/mcp/ws` to a WebSocket and hands it off to its own
+/// `McpProtocol`, sharing the same storage/engines/managers as every other
+/// transport - see `McpProtocol::new_websocket`. Runs on a spawned task so
+/// the upgrade response returns immediately and multiple clients can stay
+/// connected concurrently, same as `McpProtocol::new_tcp`'s accept loop.
+async fn handle_mcp_websocket(
+    State(state): State<AppState>,
+    ws: axum::extract::ws::WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        let protocol = McpProtocol::new_websocket(
+            socket,
+            Arc::clone(&state.storage),
+            Arc::clone(&state.embedding_engine),
+            Arc::clone(&state.search_engine),
+            Arc::clone(&state.memory_optimizer),
+            Arc::clone(&state.rate_limiter),
+            Arc::clone(&state.worker_manager),
+            Arc::clone(&state.recent_events),
+            Arc::clone(&state.search_queue),
+            Arc::clone(&state.stream_manager),
+            Arc::clone(&state.watch_manager),
+        );
+
+        if let Err(e) = protocol.handle_connection().await {
+            error!("MCP WebSocket connection ended with error: {}", e);
+        }
+    })
+}
+
+/// Per-field body-size caps enforced on `/ingest/file`'s multipart stream,
+/// modeled on Garage's `handle_post_object`: the metadata fields are plain
+/// form data and have no business being large, so capping them early rejects
+/// an oversized/hostile request before it can allocate much; `file` is the
+/// one field meant to actually carry document bytes, so it gets a much
+/// larger ceiling of its own.
+const INGEST_METADATA_FIELD_LIMIT: u64 = 16 * 1024; // 16 KiB
+const INGEST_FILE_FIELD_LIMIT: u64 = 200 * 1024 * 1024; // 200 MiB
+
+/// Streaming multipart ingestion endpoint: POST a `file` field (plus
+/// optional `group_id`/`source`/`name` form fields) to create an `Episode`
+/// directly from raw document bytes (PDF/text/markdown), instead of having
+/// to base64/JSON-embed the content into an `add_memory` tool call. Parses
+/// the request directly with `multer` (rather than axum's `Multipart`
+/// extractor, which only offers one whole-body size limit) so every field
+/// gets its own cap via `Constraints`/`SizeLimit` — see
+/// `INGEST_METADATA_FIELD_LIMIT`/`INGEST_FILE_FIELD_LIMIT` — and a field
+/// that exceeds its declared limit is rejected as soon as `multer` notices,
+/// without buffering the rest of the stream.
+async fn handle_file_ingest(
+    State(state): State<AppState>,
+    Extension(_principal): Extension<Principal>,
+    request: Request,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Missing Content-Type header".to_string()))?
+        .to_string();
+
+    let boundary = multer::parse_boundary(&content_type)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Not a multipart request: {}", e)))?;
+
+    let constraints = multer::Constraints::new()
+        .allowed_fields(vec!["group_id", "source", "name", "file"])
+        .size_limit(
+            multer::SizeLimit::new()
+                .for_field("group_id", INGEST_METADATA_FIELD_LIMIT)
+                .for_field("source", INGEST_METADATA_FIELD_LIMIT)
+                .for_field("name", INGEST_METADATA_FIELD_LIMIT)
+                .for_field("file", INGEST_FILE_FIELD_LIMIT),
+        );
+
+    let body_stream = request.into_body().into_data_stream();
+    let mut multipart = multer::Multipart::with_constraints(body_stream, boundary, constraints);
+
+    let mut group_id: Option<String> = None;
+    let mut source: Option<String> = None;
+    let mut name: Option<String> = None;
+    let mut file_name: Option<String> = None;
+    let mut file_bytes: Option<Vec<u8>> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return Err((StatusCode::PAYLOAD_TOO_LARGE, format!("Multipart read failed: {}", e))),
+        };
+
+        match field.name().unwrap_or("") {
+            "group_id" => group_id = field.text().await.ok(),
+            "source" => source = field.text().await.ok(),
+            "name" => name = field.text().await.ok(),
+            "file" => {
+                file_name = field.file_name().map(|s| s.to_string());
+                file_bytes = Some(
+                    field
+                        .bytes()
+                        .await
+                        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to read 'file' field: {}", e)))?
+                        .to_vec(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let file_bytes = file_bytes.ok_or((StatusCode::BAD_REQUEST, "Missing required 'file' field".to_string()))?;
+    let content = String::from_utf8_lossy(&file_bytes).into_owned();
+    let name = name.or(file_name).unwrap_or_else(|| "uploaded-file".to_string());
+    let source_description = source.unwrap_or_else(|| "multipart-upload".to_string());
+
+    let episode = Episode::new(name, content, EpisodeSource::File, source_description, group_id);
+
+    state
+        .storage
+        .insert_episode(&episode)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store episode: {}", e)))?;
+
+    Ok(Json(json!({
+        "success": true,
+        "episode_id": episode.uuid.to_string()
+    })))
+}
+
+/// Dispatches a single MCP request or a JSON-RPC 2.0 batch (an array of
+/// requests). Batch elements are run concurrently; elements without an
+/// `id` are notifications and are executed but contribute no entry to the
+/// response array. Returns `None` when there is nothing to send back to
+/// the client (an all-notification batch), matching JSON-RPC 2.0's
+/// "no response objects" rule for batches. `progress_sink`, when present,
+/// is cloned into every element so each can independently stream
+/// `notifications/progress` updates.
+async fn handle_mcp_payload(
+    state: &AppState,
+    principal: &Principal,
+    payload: Value,
+    progress_sink: Option<crate::mcp::handlers::ProgressSink>,
+) -> Option<Value> {
+    match payload {
+        Value::Array(elements) => {
+            if elements.is_empty() {
+                return Some(json!({
+                    "jsonrpc": "2.0",
+                    "error": {
+                        "code": -32600,
+                        "message": "Invalid Request"
+                    },
+                    "id": Value::Null
+                }));
+            }
+
+            let responses = futures::future::join_all(elements.into_iter().map(|element| {
+                let progress_sink = progress_sink.clone();
+                async move {
+                    let is_notification = element.get("id").is_none();
+                    let response = handle_mcp_message(state, principal, element, progress_sink).await;
+                    if is_notification { None } else { Some(response) }
+                }
+            }))
+            .await;
+
+            let responses: Vec<Value> = responses.into_iter().flatten().collect();
+            if responses.is_empty() {
+                None
+            } else {
+                Some(Value::Array(responses))
+            }
+        }
+        single => Some(handle_mcp_message(state, principal, single, progress_sink).await),
+    }
+}
+
+async fn handle_mcp_message(
+    state: &AppState,
+    principal: &Principal,
+    request: Value,
+    progress_sink: Option<crate::mcp::handlers::ProgressSink>,
+) -> Value {
     let request_id = request.get("id").cloned();
-    
+
     // Handle MCP request based on method
     let mut response = match request.get("method").and_then(|m| m.as_str()) {
         Some("initialize") => handle_initialize(&request).await,
         Some("tools/list") => handle_list_tools_mcp(&state).await,
         Some("tools/call") => {
             if let Some(params) = request.get("params") {
-                handle_tool_call_mcp(&state, params.clone()).await
+                handle_tool_call_mcp(&state, principal, params.clone(), progress_sink).await
             } else {
                 json!({
                     "jsonrpc": "2.0",
@@ -387,10 +941,46 @@ async fn handle_list_tools_mcp(_state: &AppState) -> Value {
     })
 }
 
-async fn handle_tool_call_mcp(state: &AppState, params: Value) -> Value {
+/// Minimum scope each tool requires. Everything not listed mutates the
+/// graph or server state and defaults to `Write`; key administration is
+/// `Admin`-only.
+pub(crate) fn required_scope_for_tool(tool_name: &str) -> ApiKeyScope {
+    match tool_name {
+        "mcp_kg-mcp-server_search_memory" | "mcp_kg-mcp-server_analyze_patterns" | "mcp_kg-mcp-server_get_recent_events" | "mcp_kg-mcp-server_admin_metrics" => ApiKeyScope::Read,
+        "mcp_kg-mcp-server_manage_api_keys" => ApiKeyScope::Admin,
+        _ => ApiKeyScope::Write,
+    }
+}
+
+async fn handle_tool_call_mcp(
+    state: &AppState,
+    principal: &Principal,
+    params: Value,
+    progress_sink: Option<crate::mcp::handlers::ProgressSink>,
+) -> Value {
     let tool_name = params.get("name").and_then(|n| n.as_str()).unwrap_or("");
     let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
-    
+
+    // MCP's `notifications/progress`: the caller opts in by including a
+    // `progressToken` in `params._meta`, which we must echo back verbatim
+    // on every update. Only meaningful when a progress sink was actually
+    // wired up (i.e. this came in over `/sse` with a matching session).
+    let progress_token = params.get("_meta").and_then(|m| m.get("progressToken")).cloned();
+    let progress = match (progress_token, progress_sink) {
+        (Some(token), Some(sink)) => Some((token, sink)),
+        _ => None,
+    };
+
+    if let Err(e) = Authenticator::authorize_tool(principal, tool_name) {
+        state.tool_call_metrics.record(tool_name, "error");
+        return denied_response(state, principal, tool_name, e);
+    }
+
+    if let Err(e) = state.client_rate_limiter.lock().unwrap().check_rate_limit(&principal.client_id, Some(tool_name)) {
+        state.tool_call_metrics.record(tool_name, "error");
+        return denied_response(state, principal, tool_name, e);
+    }
+
     // Use the comprehensive tool handler from handlers.rs
     let result = match crate::mcp::handlers::handle_tool_request(
         tool_name,
@@ -399,14 +989,29 @@ async fn handle_tool_call_mcp(state: &AppState, params: Value) -> Value {
         &state.embedding_engine,
         &state.search_engine,
         &state.memory_optimizer,
+        &state.rate_limiter,
+        &state.worker_manager,
+        &state.recent_events,
+        &state.search_queue,
+        &state.stream_manager,
+        &state.watch_manager,
+        progress,
     ).await {
-        Ok(response) => response,
-        Err(e) => json!({
-            "success": false,
-            "error": format!("Tool execution failed: {}", e)
-        })
+        Ok(response) => {
+            state.tool_call_metrics.record(tool_name, "ok");
+            response
+        }
+        Err(e) => {
+            state.tool_call_metrics.record(tool_name, "error");
+            let variant = e.downcast_ref::<McpError>().map(McpError::variant_name).unwrap_or("internal");
+            state.error_metrics.record(variant);
+            json!({
+                "success": false,
+                "error": format!("Tool execution failed: {}", e)
+            })
+        }
     };
-    
+
     json!({
         "jsonrpc": "2.0",
         "result": {
@@ -420,6 +1025,49 @@ async fn handle_tool_call_mcp(state: &AppState, params: Value) -> Value {
     })
 }
 
+/// Builds the client-visible JSON-RPC response for an `Authenticator`/
+/// `RateLimiter` denial (an `AuthError` or `RateLimit` `McpError`), and logs
+/// an `ErrorContext` stamped with `principal`'s identity so the denial is
+/// traceable back to a specific caller without ever logging its credential.
+fn denied_response(state: &AppState, principal: &Principal, tool_name: &str, error: McpError) -> Value {
+    state.error_metrics.record(error.variant_name());
+    let context = ErrorContext {
+        error_id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now(),
+        tool_name: Some(tool_name.to_string()),
+        parameters: None,
+        user_agent: None,
+        client_version: None,
+        client_id: Some(principal.client_id.clone()),
+        credential_fingerprint: principal.credential_fingerprint.clone(),
+    };
+    let mcp_error = error.to_mcp_error(&context.error_id);
+    warn!("Tool call denied: {} - Context: {:?}", mcp_error.message, context);
+    state.recent_events.push(crate::metrics::EventRecord {
+        level: "WARN".to_string(),
+        timestamp: chrono::Utc::now(),
+        target: "mcp::server".to_string(),
+        message: mcp_error.message.clone(),
+        error_id: Some(context.error_id),
+    });
+
+    json!({
+        "jsonrpc": "2.0",
+        "result": {
+            "content": [
+                {
+                    "type": "text",
+                    "text": json!({
+                        "success": false,
+                        "error": mcp_error.message,
+                        "data": mcp_error.data
+                    }).to_string()
+                }
+            ]
+        }
+    })
+}
+
 // Health check and utility endpoints
 
 async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
@@ -451,21 +1099,44 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
         }
     }
 
-    // Check embedding engine health
+    // Check embedding engine health: `current_model` only returns `Some`
+    // once `initialize` has completed, so its presence doubles as an
+    // initialization check without a dedicated getter.
+    let embedding_model = state.embedding_engine.current_model().await;
     health_status["embedding_engine"] = json!({
-        "status": "healthy",
-        "model": "nomic-embed-text-v1.5"
+        "status": if embedding_model.is_some() { "healthy" } else { "initializing" },
+        "initialized": embedding_model.is_some(),
+        "model": embedding_model
     });
 
-    // Check memory usage
+    // Check memory optimizer health: `get_memory_stats` only succeeds once
+    // the optimizer's caches have been initialized.
+    let memory_initialized = state.memory_optimizer.get_memory_stats().await.is_ok();
     health_status["memory"] = json!({
-        "status": "healthy"
+        "status": if memory_initialized { "healthy" } else { "initializing" },
+        "initialized": memory_initialized
     });
 
     Json(health_status)
 }
 
-async fn metrics_endpoint(State(state): State<AppState>) -> impl IntoResponse {
+/// Wants OpenMetrics/Prometheus text exposition instead of the default JSON
+/// body. Recognizes both the dedicated `/metrics/prometheus`-style `Accept`
+/// value and a plain `text/plain` request, since most scrape configs just
+/// set the latter.
+fn wants_openmetrics(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .map(|accept| accept.contains("text/plain") || accept.contains("application/openmetrics-text"))
+        .unwrap_or(false)
+}
+
+async fn metrics_endpoint(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if wants_openmetrics(&headers) {
+        return render_openmetrics(&state).await.into_response();
+    }
+
     let mut metrics = json!({
         "server": "cursor-kg",
         "version": env!("CARGO_PKG_VERSION"),
@@ -488,18 +1159,244 @@ async fn metrics_endpoint(State(state): State<AppState>) -> impl IntoResponse {
 
     // System metrics
     metrics["system"] = json!({
-        "uptime_seconds": std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
+        "uptime_seconds": state.start_time.elapsed().as_secs(),
         "rust_version": env!("RUSTC_VERSION"),
         "build_timestamp": env!("BUILD_TIMESTAMP")
     });
 
-    Json(metrics)
+    // Per-tool call counts, keyed by outcome (see `ToolCallMetrics`).
+    metrics["tool_calls"] = state
+        .tool_call_metrics
+        .snapshot()
+        .into_iter()
+        .map(|((tool, status), count)| json!({"tool": tool, "status": status, "count": count}))
+        .collect::<Vec<_>>()
+        .into();
+
+    // Per-`McpError::variant_name` counts (see `ErrorMetrics`).
+    metrics["errors"] = state
+        .error_metrics
+        .snapshot()
+        .into_iter()
+        .map(|(variant, count)| json!({"variant": variant, "count": count}))
+        .collect::<Vec<_>>()
+        .into();
+
+    // Number of distinct clients with at least one request counted against
+    // `client_rate_limiter` in the current window (see `/clients`).
+    metrics["rate_limited_clients"] = json!(state.client_rate_limiter.lock().unwrap().active_clients().len());
+
+    Json(metrics).into_response()
+}
+
+/// Renders the same counts `metrics_endpoint` reports as JSON in
+/// OpenMetrics text exposition format: a `# HELP`/`# TYPE` pair per metric
+/// followed by its `name{labels} value` samples. See
+/// <https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md>.
+async fn render_openmetrics(state: &AppState) -> impl IntoResponse {
+    let mut out = String::new();
+
+    let node_count = state.storage.count_nodes().await.unwrap_or(0);
+    let edge_count = state.storage.count_edges().await.unwrap_or(0);
+    let episode_count = state.storage.count_episodes().await.unwrap_or(0);
+
+    out.push_str("# HELP kg_nodes_total Total number of knowledge graph nodes.\n");
+    out.push_str("# TYPE kg_nodes_total gauge\n");
+    out.push_str(&format!("kg_nodes_total {}\n", node_count));
+
+    out.push_str("# HELP kg_edges_total Total number of knowledge graph edges.\n");
+    out.push_str("# TYPE kg_edges_total gauge\n");
+    out.push_str(&format!("kg_edges_total {}\n", edge_count));
+
+    out.push_str("# HELP kg_episodes_total Total number of ingested episodes.\n");
+    out.push_str("# TYPE kg_episodes_total gauge\n");
+    out.push_str(&format!("kg_episodes_total {}\n", episode_count));
+
+    out.push_str("# HELP kg_uptime_seconds Seconds since the server process started.\n");
+    out.push_str("# TYPE kg_uptime_seconds gauge\n");
+    out.push_str(&format!("kg_uptime_seconds {}\n", state.start_time.elapsed().as_secs()));
+
+    if let Ok(engine_metrics) = state.embedding_engine.metrics_handle().await {
+        let cache_stats = &engine_metrics.cache;
+        out.push_str("# HELP kg_embedding_cache_size Entries currently held in the embedding batch cache.\n");
+        out.push_str("# TYPE kg_embedding_cache_size gauge\n");
+        out.push_str(&format!("kg_embedding_cache_size {}\n", cache_stats.batch_cache_used));
+
+        out.push_str("# HELP kg_embedding_cache_capacity Configured capacity of the embedding batch cache.\n");
+        out.push_str("# TYPE kg_embedding_cache_capacity gauge\n");
+        out.push_str(&format!("kg_embedding_cache_capacity {}\n", cache_stats.batch_cache_capacity));
+
+        out.push_str("# HELP kg_embedding_model_dimensions Embedding width of the currently loaded model.\n");
+        out.push_str("# TYPE kg_embedding_model_dimensions gauge\n");
+        out.push_str(&format!("kg_embedding_model_dimensions {}\n", engine_metrics.dimensions.unwrap_or(0)));
+    }
+
+    if let Ok(mem_stats) = state.memory_optimizer.get_memory_stats().await {
+        let cache = &mem_stats.cache_statistics;
+        let hits = cache.l1_hits + cache.l2_hits + cache.l3_hits + cache.embedding_hits + cache.query_hits;
+        let misses = cache.l1_misses + cache.l2_misses + cache.l3_misses + cache.embedding_misses + cache.query_misses;
+        let hit_rate = if hits + misses > 0 { hits as f64 / (hits + misses) as f64 } else { 0.0 };
+
+        out.push_str("# HELP kg_memory_cache_bytes Total bytes held by the memory optimizer's caches.\n");
+        out.push_str("# TYPE kg_memory_cache_bytes gauge\n");
+        out.push_str(&format!("kg_memory_cache_bytes {}\n", cache.total_memory_used));
+
+        out.push_str("# HELP kg_memory_cache_hit_rate Hit rate across the memory optimizer's L1/L2/L3/embedding/query caches.\n");
+        out.push_str("# TYPE kg_memory_cache_hit_rate gauge\n");
+        out.push_str(&format!("kg_memory_cache_hit_rate {}\n", hit_rate));
+    }
+
+    out.push_str("# HELP kg_tool_calls_total Total MCP tool invocations by tool name and outcome.\n");
+    out.push_str("# TYPE kg_tool_calls_total counter\n");
+    for ((tool, status), count) in state.tool_call_metrics.snapshot() {
+        out.push_str(&format!(
+            "kg_tool_calls_total{{tool=\"{}\",status=\"{}\"}} {}\n",
+            tool, status, count
+        ));
+    }
+
+    // Cache hit/miss/entry gauges and per-tool latency histograms (see
+    // `PerformanceMonitor::render_prometheus`), plus circuit breaker state
+    // gauges (see `CircuitBreakerRegistry::render_prometheus`) - combined
+    // into this same handler rather than a separate endpoint, since they're
+    // both just more samples in the same exposition.
+    out.push_str(&state.performance_monitor.render_prometheus().await);
+    out.push_str(&state.circuit_breakers.render_prometheus());
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        out,
+    )
+}
+
+async fn workers_endpoint(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({
+        "workers": state.worker_manager.status_report().await
+    }))
+}
+
+/// Active `client_rate_limiter` entries (see `RateLimiter::active_clients`):
+/// every `Principal::client_id` with at least one request counted against it
+/// in the current window, and its remaining budget for the rest of the
+/// minute. Lets an operator see who's being rate limited without tailing
+/// logs.
+async fn clients_endpoint(State(state): State<AppState>) -> impl IntoResponse {
+    let clients = state.client_rate_limiter.lock().unwrap().active_clients();
+    Json(json!({
+        "clients": clients
+            .into_iter()
+            .map(|(client_id, remaining)| json!({"client_id": client_id, "remaining": remaining}))
+            .collect::<Vec<_>>()
+    }))
 }
 
 async fn list_tools(State(state): State<AppState>) -> impl IntoResponse {
     let tools_response = handle_list_tools_mcp(&state).await;
     Json(tools_response)
+}
+
+// Scoped API key authentication (see `security::api_keys`)
+
+/// Validates `Authorization: Bearer <key>` against the stored key registry
+/// and attaches the resolved scopes to the request's extensions. A no-op
+/// pass-through (full `Admin` scope) when `state.auth_required` is false,
+/// which is how the pre-existing open-by-default behavior is preserved for
+/// local development and for deployments that haven't opted in yet.
+async fn api_key_auth(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if !state.auth_required {
+        let principal = Principal::unrestricted();
+        req.extensions_mut().insert(ResolvedScopes(principal.scopes.clone()));
+        req.extensions_mut().insert(principal);
+        return Ok(next.run(req).await);
+    }
+
+    let authorization_header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+
+    let credential = match Authenticator::extract_credential(authorization_header, &Value::Null) {
+        Some(credential) => credential,
+        None => return Err(StatusCode::UNAUTHORIZED),
+    };
+
+    match Authenticator::new(Arc::clone(&state.storage)).authenticate(credential) {
+        Ok(principal) => {
+            req.extensions_mut().insert(ResolvedScopes(principal.scopes.clone()));
+            req.extensions_mut().insert(principal);
+            Ok(next.run(req).await)
+        }
+        Err(e) => {
+            warn!("API key authentication failed: {}", e);
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+}
+
+fn require_admin(scopes: &ResolvedScopes) -> Result<(), StatusCode> {
+    if scopes.allows(ApiKeyScope::Admin) {
+        Ok(())
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+async fn list_api_keys_endpoint(
+    State(state): State<AppState>,
+    Extension(scopes): Extension<ResolvedScopes>,
+) -> Result<impl IntoResponse, StatusCode> {
+    require_admin(&scopes)?;
+    let keys = state.storage.list_api_keys().map_err(|e| {
+        error!("Failed to list API keys: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(json!({ "keys": keys })))
+}
+
+async fn create_api_key_endpoint(
+    State(state): State<AppState>,
+    Extension(scopes): Extension<ResolvedScopes>,
+    Json(body): Json<Value>,
+) -> Result<impl IntoResponse, StatusCode> {
+    require_admin(&scopes)?;
+
+    let name = body.get("name").and_then(|v| v.as_str()).unwrap_or("unnamed-key");
+    let requested_scopes: Vec<ApiKeyScope> = body
+        .get("scopes")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .filter_map(|s| ApiKeyScope::parse(s).ok())
+                .collect()
+        })
+        .unwrap_or_else(|| vec![ApiKeyScope::Read]);
+
+    let created = state.storage.create_api_key(name, &requested_scopes).map_err(|e| {
+        error!("Failed to create API key: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(json!(created)))
+}
+
+async fn revoke_api_key_endpoint(
+    State(state): State<AppState>,
+    Extension(scopes): Extension<ResolvedScopes>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    require_admin(&scopes)?;
+    let revoked = state.storage.revoke_api_key(&id).map_err(|e| {
+        error!("Failed to revoke API key {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if revoked {
+        Ok(Json(json!({ "success": true, "id": id })))
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
 }
\ No newline at end of file
@@ -38,11 +38,45 @@ pub fn get_tool_definitions() -> Value {
                             "type": "string",
                             "description": "Optional UUID for the episode"
                         },
+                        "transforms": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "type": {
+                                        "type": "string",
+                                        "enum": ["extract_field", "rename_field", "redact", "normalize_language", "derive_field"]
+                                    },
+                                    "config": {"type": "object"}
+                                },
+                                "required": ["type"]
+                            },
+                            "description": "Ordered preprocessing pipeline applied to episode_body before entity/relationship extraction: 'extract_field' (config: field) pulls a JSON field out as the new body, 'rename_field' (config: from, to) renames a JSON field, 'redact' (config: pattern, replacement?) regex-replaces matches, 'normalize_language' collapses whitespace, 'derive_field' (config: fields, new_field, join_with?) concatenates JSON fields into a new one. Which steps actually fired is stored with the episode as 'transforms_applied' and echoed at verbosity=full."
+                        },
+                        "extractor": {
+                            "type": "string",
+                            "enum": ["rules", "embedding", "llm"],
+                            "default": "rules",
+                            "description": "Entity/relationship extraction backend. 'rules' (default) uses pattern matching only. 'embedding' additionally scores entity/relationship confidence with the embedding engine's cosine similarity. 'llm' is not available in this build (no chat-completion backend configured) and returns an error."
+                        },
+                        "entity_types": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "If set, only entities whose extracted type is in this list are kept"
+                        },
+                        "min_confidence": {
+                            "type": "number",
+                            "description": "Overrides the extractor's default minimum confidence threshold for both entities and relationships"
+                        },
+                        "max_entities": {
+                            "type": "integer",
+                            "description": "Overrides the extractor's default cap on entities extracted per episode"
+                        },
                         "verbosity": {
                             "type": "string",
                             "enum": ["summary", "compact", "full"],
                             "default": "compact",
-                            "description": "Output verbosity level: 'summary' for minimal output, 'compact' for essential info (default), 'full' for complete details"
+                            "description": "Output verbosity level: 'summary' for minimal output, 'compact' for essential info (default), 'full' for complete details (includes per-entity/relationship confidence and the extractor used)"
                         }
                     },
                     "required": ["name", "episode_body"]
@@ -56,12 +90,12 @@ pub fn get_tool_definitions() -> Value {
                     "properties": {
                         "operation": {
                             "type": "string",
-                            "enum": ["nodes", "facts", "episodes", "similar_concepts", "batch"],
-                            "description": "Type of search operation: 'nodes' for node summaries, 'facts' for relationships, 'episodes' for recent episodes, 'similar_concepts' for semantic similarity, 'batch' for multiple queries"
+                            "enum": ["nodes", "facts", "episodes", "similar_concepts", "hybrid_nodes", "shortest_path", "batch"],
+                            "description": "Type of search operation: 'nodes' for node summaries, 'facts' for relationships (now keyword+semantic fused, like 'nodes'), 'episodes' for recent episodes, 'similar_concepts' for node semantic similarity (now also fused with a keyword leg), 'hybrid_nodes' to fuse FTS5 text match and embedding cosine similarity straight out of storage via Reciprocal Rank Fusion (k=60), 'shortest_path' to find how two nodes are connected via weighted A*/Dijkstra over edge weight treated as relationship strength, 'batch' for multiple queries (now also keyword+semantic fused, one 'semantic_ratio' applied across every query in the batch)"
                         },
                         "query": {
                             "type": "string",
-                            "description": "Primary search query (required for nodes, facts, similar_concepts)"
+                            "description": "Primary search query (required for nodes, facts, similar_concepts). For episodes, optional: when given, ranks by semantic similarity against chunk-level embeddings of oversized episode bodies (rolled up to the parent episode by best chunk score) instead of listing by recency"
                         },
                         "queries": {
                             "type": "array",
@@ -75,7 +109,7 @@ pub fn get_tool_definitions() -> Value {
                         },
                         "group_id": {
                             "type": "string",
-                            "description": "Single group ID for episodes operation"
+                            "description": "Single group ID for episodes or hybrid_nodes operation"
                         },
                         "max_results": {
                             "type": "integer",
@@ -86,6 +120,18 @@ pub fn get_tool_definitions() -> Value {
                             "type": "string",
                             "description": "Optional UUID of a node to center the search around"
                         },
+                        "source_uuid": {
+                            "type": "string",
+                            "description": "For shortest_path: UUID of the starting node"
+                        },
+                        "target_uuid": {
+                            "type": "string",
+                            "description": "For shortest_path: UUID of the destination node"
+                        },
+                        "max_hops": {
+                            "type": "integer",
+                            "description": "For shortest_path: maximum number of edges to traverse before giving up. Omit for unbounded"
+                        },
                         "entity_filter": {
                             "type": "string",
                             "description": "Optional entity type to filter results"
@@ -93,12 +139,60 @@ pub fn get_tool_definitions() -> Value {
                         "similarity_threshold": {
                             "type": "number",
                             "default": 0.7,
-                            "description": "Minimum similarity score threshold for similar_concepts (0.0-1.0, default: 0.7)"
+                            "description": "Minimum semantic similarity score threshold for similar_concepts (0.0-1.0, default: 0.7), applied to the node's raw cosine-similarity score after fusion"
+                        },
+                        "semantic_ratio": {
+                            "type": "number",
+                            "default": 0.5,
+                            "description": "For 'nodes', 'facts', 'similar_concepts', and 'batch': single knob interpolating between keyword and semantic retrieval (0.0 = keyword only, 1.0 = semantic only), translated internally into the same weighted Reciprocal Rank Fusion 'text_weight'/'vector_weight' use. For 'nodes', only applies when 'search_strategy'/'diversify' aren't set. 'facts'/'similar_concepts'/'batch' default to 0.5 (equal-weighted fusion) when omitted; 'batch' applies the single value across every query in its 'queries' array."
+                        },
+                        "diversify": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "For the nodes operation: re-rank with Maximal Marginal Relevance instead of pure relevance, to surface distinct graph regions instead of near-duplicate hits"
+                        },
+                        "filter": {
+                            "type": "string",
+                            "description": "For 'similar_concepts', 'batch', and 'episodes': a filter-expression string evaluated as a post-scoring predicate over node fields ('node_type', 'group_id', 'name', 'summary', 'created_at' — 'episodes' has no 'node_type'/'summary' column, so 'summary' matches an episode's 'content' instead and 'node_type' never matches). Supports '==', '!=', '>', '>=', '<', '<=', 'CONTAINS' (substring match), 'IN [...]', and boolean 'AND'/'OR'/'NOT' with parentheses, e.g. `node_type == \"Person\" AND name CONTAINS \"Smith\"`. A malformed expression fails the call with the offending token's position. 'batch' applies one expression across every query in its 'queries' array, like 'semantic_ratio'"
+                        },
+                        "ef_search": {
+                            "type": "integer",
+                            "description": "For similar_concepts: HNSW query-time candidate list size. Larger values trade query latency for recall. Defaults to the candidate pool size derived from max_results"
+                        },
+                        "m": {
+                            "type": "integer",
+                            "description": "For similar_concepts: max neighbors per HNSW layer. Only takes effect on reinsertion, so setting this triggers a full rebuild of the node embedding index before the query runs"
+                        },
+                        "mmr_lambda": {
+                            "type": "number",
+                            "default": 0.5,
+                            "description": "Relevance/diversity tradeoff for diversify (0.0-1.0): 1.0 is pure relevance, 0.0 is pure novelty (default: 0.5)"
+                        },
+                        "search_strategy": {
+                            "type": "string",
+                            "enum": ["semantic", "keyword", "hybrid"],
+                            "description": "For the nodes operation: pin the query to a single retrieval path ('semantic' for vector search only, 'keyword' for text search only) or explicit 'hybrid' Reciprocal Rank Fusion of both, overriding this server's configured default fusion algorithm. Omit to keep the default search behavior."
+                        },
+                        "rrf_k": {
+                            "type": "number",
+                            "description": "For search_strategy 'hybrid': the k constant in RRF's score(d) = sum over lists of w / (k + rank). Higher k flattens the influence of rank position. Defaults to the server's configured RRF k (typically 60.0)"
+                        },
+                        "text_weight": {
+                            "type": "number",
+                            "description": "For search_strategy 'hybrid': weight applied to the keyword list's RRF contribution (default: 1.0)"
+                        },
+                        "vector_weight": {
+                            "type": "number",
+                            "description": "For search_strategy 'hybrid': weight applied to the semantic list's RRF contribution (default: 1.0)"
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "Opaque pagination token from a previous call's 'next_cursor', for 'nodes'/'facts'/'episodes'. Resuming a cursor replays the original query's other parameters as they were on the first call; only 'max_results'/'last_n' from this call are honored."
                         },
                         "last_n": {
                             "type": "integer",
                             "default": 10,
-                            "description": "Number of recent episodes to retrieve (for episodes operation)"
+                            "description": "Number of episodes to retrieve (for episodes operation): most recent by default, or top-ranked by 'query' when given"
                         },
                         "verbosity": {
                             "type": "string",
@@ -119,7 +213,7 @@ pub fn get_tool_definitions() -> Value {
                         "analysis_type": {
                             "type": "string",
                             "enum": ["relationships", "clusters", "temporal", "centrality", "semantic_clusters"],
-                            "description": "Type of pattern analysis: 'relationships' for frequent patterns, 'clusters' for entity clustering, 'temporal' for time-based patterns, 'centrality' for important nodes, 'semantic_clusters' for concept grouping"
+                            "description": "Type of pattern analysis: 'relationships' for frequent patterns, 'clusters' for entity clustering, 'temporal' for time-based patterns, 'centrality' for the most structurally important nodes via 'centrality_method', 'semantic_clusters' for concept grouping over node embeddings via spherical k-means or cosine DBSCAN"
                         },
                         "group_ids": {
                             "type": "array",
@@ -131,6 +225,21 @@ pub fn get_tool_definitions() -> Value {
                             "default": 20,
                             "description": "Maximum number of patterns/clusters to return (default: 20)"
                         },
+                        "top_k": {
+                            "type": "integer",
+                            "description": "For 'centrality': number of top-ranked nodes to return by 'centrality_method'. Defaults to max_results"
+                        },
+                        "centrality_method": {
+                            "type": "string",
+                            "enum": ["betweenness", "pagerank"],
+                            "default": "betweenness",
+                            "description": "For 'centrality': 'betweenness' ranks nodes by Brandes' betweenness centrality (plus closeness) over the undirected graph; 'pagerank' ranks by PageRank power iteration over the directed edge graph, using 'damping_factor' (default: betweenness)"
+                        },
+                        "damping_factor": {
+                            "type": "number",
+                            "default": 0.85,
+                            "description": "For centrality_method 'pagerank': probability of following an outgoing edge rather than jumping to a random node each step (default: 0.85)"
+                        },
                         "time_range_days": {
                             "type": "integer",
                             "default": 30,
@@ -150,17 +259,22 @@ pub fn get_tool_definitions() -> Value {
                             "type": "string",
                             "enum": ["kmeans", "hierarchical", "dbscan"],
                             "default": "kmeans",
-                            "description": "Clustering algorithm for semantic_clusters (default: kmeans)"
+                            "description": "Clustering algorithm for semantic_clusters. 'kmeans' runs spherical k-means (cosine similarity, k-means++ seeding) over L2-normalized node embeddings; 'dbscan' groups nodes within 'epsilon' cosine similarity of each other and labels sparse points as noise, with no 'num_clusters' needed. 'hierarchical' isn't implemented yet (default: kmeans)"
                         },
                         "num_clusters": {
                             "type": "integer",
                             "default": 5,
-                            "description": "Number of clusters for kmeans (default: 5)"
+                            "description": "Number of clusters for kmeans, clamped to the number of embedded nodes (default: 5)"
+                        },
+                        "epsilon": {
+                            "type": "number",
+                            "default": 0.85,
+                            "description": "For dbscan: minimum cosine similarity for two nodes to count as neighbors (default: 0.85)"
                         },
                         "min_cluster_size": {
                             "type": "integer",
                             "default": 3,
-                            "description": "Minimum size for clusters (default: 3)"
+                            "description": "For dbscan: minimum neighbor count (excluding itself) for a node to seed/extend a cluster rather than stay noise (default: 3)"
                         },
                         "verbosity": {
                             "type": "string",
@@ -180,8 +294,8 @@ pub fn get_tool_definitions() -> Value {
                     "properties": {
                         "operation": {
                             "type": "string",
-                            "enum": ["get_entity_edge", "delete_entity_edge", "delete_episode", "delete_batch", "clear_graph", "get_episodes"],
-                            "description": "Management operation: 'get_entity_edge' to retrieve edge, 'delete_entity_edge' to remove edge, 'delete_episode' to remove episode, 'delete_batch' for multiple deletions, 'clear_graph' to reset, 'get_episodes' to retrieve recent episodes"
+                            "enum": ["get_entity_edge", "delete_entity_edge", "delete_episode", "delete_batch", "clear_graph", "get_episodes", "stats", "set_retention", "compact", "pin", "unpin", "list_pins", "gc", "node_history", "edge_history", "node_as_of", "edge_as_of", "revert_node", "revert_edge", "node_siblings", "edge_siblings"],
+                            "description": "Management operation: 'get_entity_edge' to retrieve edge, 'delete_entity_edge' to remove edge, 'delete_episode' to remove episode, 'delete_batch' for multiple deletions, 'clear_graph' to reset, 'get_episodes' to retrieve recent episodes, 'stats' for server diagnostics (counters/gauges for graph size, embedding cache, and storage I/O), 'set_retention' to store a per-group max_age_days/max_episodes policy and immediately prune episodes outside it, 'compact' to merge duplicate episodes and garbage-collect orphaned entities in resumable batches, 'pin'/'unpin' to mark (or unmark) a UUID or group_id as a root 'gc' must never collect, 'list_pins' to list current pinned roots, 'gc' to mark-and-sweep delete every node/edge not reachable from an episode or a pin, 'node_history'/'edge_history' to list every past revision of a node/edge oldest-first plus its current state, 'node_as_of'/'edge_as_of' to reconstruct a node/edge's state at a past timestamp, 'revert_node'/'revert_edge' to make a past revision current again (itself recorded as a new revision), 'node_siblings'/'edge_siblings' to list concurrent versions left unresolved by a multi-writer causal-context conflict (resolving them is a storage-layer API for replication tooling, not yet exposed here)"
                         },
                         "uuid": {
                             "type": "string",
@@ -200,17 +314,69 @@ pub fn get_tool_definitions() -> Value {
                         },
                         "confirm": {
                             "type": "boolean",
-                            "description": "Required confirmation for destructive operations (clear_graph, delete_batch)"
+                            "description": "Required confirmation for destructive operations (clear_graph, delete_batch, set_retention, compact, gc)"
+                        },
+                        "alias_kind": {
+                            "type": "string",
+                            "enum": ["uuid", "group_id"],
+                            "description": "For 'pin'/'unpin': whether alias_value names a single entity UUID or an entire group_id"
+                        },
+                        "alias_value": {
+                            "type": "string",
+                            "description": "For 'pin'/'unpin': the UUID or group_id to (un)pin, per alias_kind"
+                        },
+                        "max_bytes": {
+                            "type": "integer",
+                            "description": "For 'gc': VACUUM the database afterward if its file is still at least this many bytes (omit to never VACUUM)"
+                        },
+                        "at": {
+                            "type": "string",
+                            "description": "For 'node_as_of'/'edge_as_of': ISO 8601 timestamp to reconstruct the entity's state at"
+                        },
+                        "revision_seq": {
+                            "type": "integer",
+                            "description": "For 'revert_node'/'revert_edge': the revision_seq (from 'node_history'/'edge_history') to make current again"
                         },
                         "group_id": {
                             "type": "string",
-                            "description": "Group ID for get_episodes operation"
+                            "description": "Group ID for get_episodes, set_retention, or compact (default for set_retention: 'ungrouped'; omit on compact to cover every group)"
+                        },
+                        "group_ids": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Group IDs to scope counts to, for 'stats' operation (default: all groups)"
                         },
                         "last_n": {
                             "type": "integer",
                             "default": 10,
                             "description": "Number of recent episodes to retrieve"
                         },
+                        "filter": {
+                            "type": "string",
+                            "description": "For 'get_episodes': a filter-expression string evaluated as a post-scoring predicate over episode fields ('group_id', 'name', 'created_at', plus 'summary' matching an episode's 'content'; 'node_type' never matches since episodes have no such column). Supports '==', '!=', '>', '>=', '<', '<=', 'CONTAINS' (substring match), 'IN [...]', and boolean 'AND'/'OR'/'NOT' with parentheses. A malformed expression fails the call with the offending token's position. Applied after 'last_n' slices the page, so a page can come back smaller than 'last_n'"
+                        },
+                        "max_age_days": {
+                            "type": "integer",
+                            "description": "For 'set_retention': prune episodes in group_id older than this many days. At least one of max_age_days/max_episodes is required."
+                        },
+                        "max_episodes": {
+                            "type": "integer",
+                            "description": "For 'set_retention': keep only the newest max_episodes episodes in group_id, pruning the rest. At least one of max_age_days/max_episodes is required."
+                        },
+                        "preserve_entities": {
+                            "type": "boolean",
+                            "default": true,
+                            "description": "For 'set_retention': if false, also garbage-collect nodes/edges left with no remaining episode reference after pruning (default true keeps them indefinitely, matching delete_episode's existing behavior)"
+                        },
+                        "batch_size": {
+                            "type": "integer",
+                            "default": 100,
+                            "description": "For 'compact': number of episodes to scan for duplicates per call"
+                        },
+                        "cursor": {
+                            "type": "string",
+                            "description": "Opaque pagination token from a previous call's 'next_cursor', for 'get_episodes' or 'compact'. Resuming a cursor replays the original call's other parameters as they were the first time; only 'last_n'/'batch_size' from this call is honored."
+                        },
                         "verbosity": {
                             "type": "string",
                             "enum": ["summary", "compact", "full"],
@@ -229,8 +395,8 @@ pub fn get_tool_definitions() -> Value {
                     "properties": {
                         "operation": {
                             "type": "string",
-                            "enum": ["index", "reindex", "status", "search_code", "get_dependencies", "analyze_structure"],
-                            "description": "Indexing operation: 'index' for initial indexing, 'reindex' for full re-indexing, 'status' for indexing progress, 'search_code' for code search, 'get_dependencies' for dependency mapping, 'analyze_structure' for codebase analysis"
+                            "enum": ["index", "reindex", "status", "watch", "call_hierarchy", "suggest_symbol", "search", "search_code", "get_dependencies", "analyze_structure"],
+                            "description": "Indexing operation: 'index' for initial indexing (see 'profile'), 'reindex' for full re-indexing (see 'profile'), 'status' for the persistent index manifest summary (total indexed files, symbol counts by language, last run time, and files now stale relative to disk), 'watch' to start/stop/list background filesystem watchers that keep the graph live (see 'watch_action'), 'call_hierarchy' for a function/method's callers and callees (see 'symbol', 'suggest'), 'suggest_symbol' for spelling-tolerant \"did you mean\" corrections against every indexed symbol name (see 'symbol'), 'search' for TF-IDF ranked retrieval over every indexed code chunk ('query'/'max_results'; 'full' verbosity includes each hit's matching-term breakdown), 'search_code' for fuzzy symbol-name search, 'get_dependencies' for dependency mapping, 'analyze_structure' for codebase analysis (see 'use_cargo_metadata', 'profile', 'include_non_source')"
                         },
                         "path": {
                             "type": "string",
@@ -276,6 +442,11 @@ pub fn get_tool_definitions() -> Value {
                             "default": true,
                             "description": "Extract function/class/variable symbols"
                         },
+                        "extract_history": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Shell out to `git log` for each indexed file's recent commit history and churn (commit count and lines changed over a lookback window), attached as temporal metadata so search_code can rank by recency of change"
+                        },
                         "query": {
                             "type": "string",
                             "description": "Search query for search_code operation"
@@ -286,19 +457,75 @@ pub fn get_tool_definitions() -> Value {
                         },
                         "symbol_type": {
                             "type": "string",
-                            "enum": ["function", "class", "variable", "import", "all"],
+                            "enum": ["function", "method", "class", "struct", "enum", "trait", "impl", "interface", "module", "type", "variable", "import", "all"],
                             "default": "all",
-                            "description": "Type of symbols to search for in search_code operation"
+                            "description": "Type of symbols to search for in search_code operation (matches the block_type values code_chunker::extract_symbols reports for the file's language, e.g. 'function'/'struct'/'trait' for Rust, 'function'/'class' for Python, 'function'/'method'/'class'/'interface' for JS/TS, 'function'/'method'/'type' for Go)"
                         },
                         "context_lines": {
                             "type": "integer",
                             "default": 5,
                             "description": "Number of context lines to include around search results"
                         },
+                        "min_complexity": {
+                            "type": "integer",
+                            "description": "Only return search_code symbols with McCabe cyclomatic complexity at or above this value (e.g. to find the most complex functions)"
+                        },
+                        "min_entropy": {
+                            "type": "number",
+                            "description": "Only return search_code symbols with Shannon token entropy (bits) at or above this value"
+                        },
+                        "rank_by_recency": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Rank search_code results by how recently their file was last committed (requires extract_history to have been enabled when the file was indexed) instead of by match quality"
+                        },
                         "group_id": {
                             "type": "string",
                             "description": "Group ID to associate indexed content with"
                         },
+                        "cache_dir": {
+                            "type": "string",
+                            "description": "Path to the persistent index manifest database backing 'incremental'/'status'/'watch' (content hash, last-indexed timestamp, language, and symbol count per file). Defaults to '<path>/.kg_index_cache.db' when unset, so 'status' must be passed the same 'path'/'cache_dir' as the 'index'/'watch' call it's reporting on"
+                        },
+                        "watch_action": {
+                            "type": "string",
+                            "enum": ["start", "stop", "status"],
+                            "default": "start",
+                            "description": "For the 'watch' operation: 'start' a background filesystem watcher under 'watch_id' (re-indexing changed files with the same 'parallel_workers' pool as they're saved), 'stop' one by 'watch_id', or 'status' to list every running watch"
+                        },
+                        "watch_id": {
+                            "type": "string",
+                            "description": "Identifier for a 'watch' operation's background watcher, used to 'stop' it later. Defaults to 'path' for 'watch_action: start'; required for 'watch_action: stop'"
+                        },
+                        "debounce_ms": {
+                            "type": "integer",
+                            "default": 500,
+                            "description": "For 'watch_action: start': how long to wait after the first filesystem event in a burst before re-indexing, so one save (which can fire several events) only triggers one re-index pass per file"
+                        },
+                        "symbol": {
+                            "type": "string",
+                            "description": "Function/method name to look up for the 'call_hierarchy' operation (resolved by exact name first; if nothing matches exactly, returns a ranked list of fuzzy candidates instead of guessing which one was meant) or to find corrections for with 'suggest_symbol'"
+                        },
+                        "suggest": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "For 'call_hierarchy': when the symbol doesn't resolve exactly, also include a 'suggestions' list of spelling-tolerant corrections (trigram-index + Damerau-Levenshtein ranked, tuned for genuine misspellings) alongside the existing fuzzy 'candidates' list"
+                        },
+                        "use_cargo_metadata": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "For 'analyze_structure': when 'path' contains a Cargo.toml, also shell out to 'cargo metadata' and include a 'cargo_workspace' list (one entry per package, with its edition and normal/dev/build dependencies) in 'full' verbosity output, so crate boundaries and external deps can be told apart from the source-derived 'dependency_graph'. Ignored for other operations; has no effect (and isn't an error) if 'path' isn't a cargo project"
+                        },
+                        "profile": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "For 'index', 'reindex', and 'analyze_structure': record a per-stage wall time/call-count/percentage breakdown (file discovery, parallel file processing, embedding backlog drain, dependency/call graph construction, symbol indexing, cross-file analysis for indexing; file cache scan, directory structure, dependency graph rendering, cargo metadata lookup for structure analysis) and include it as a 'profile' span tree in 'full' verbosity output. Off by default since recording has a small bookkeeping cost per span"
+                        },
+                        "include_non_source": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "For 'analyze_structure': also walk files outside the recognized languages (config, docs, data fixtures, generated files, and anything else 'index'/'reindex' skip) and report them bucketed by category ('non_source::config', 'non_source::markup', 'non_source::data', 'non_source::binary', 'non_source::generated', 'non_source::other') in 'file_types', and as their own entries in 'directory_structure'. Off by default, matching the existing source-only behavior"
+                        },
                         "max_results": {
                             "type": "integer",
                             "default": 50,
@@ -313,7 +540,151 @@ pub fn get_tool_definitions() -> Value {
                     },
                     "required": ["operation"]
                 }
+            },
+            {
+                "name": "mcp_kg-mcp-server_manage_workers",
+                "description": "Introspect and control the server's supervised background workers (memory GC, embedding cache warmup, DB health check).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "operation": {
+                            "type": "string",
+                            "enum": ["list_workers", "control_worker"],
+                            "description": "'list_workers' to report every worker's status and iteration count, 'control_worker' to pause/resume/trigger/cancel one"
+                        },
+                        "worker_name": {
+                            "type": "string",
+                            "description": "Worker to target for control_worker (e.g. 'memory_gc', 'embedding_warmup', 'db_health_check')"
+                        },
+                        "command": {
+                            "type": "string",
+                            "enum": ["pause", "resume", "trigger_now", "cancel"],
+                            "description": "Command to send for control_worker"
+                        }
+                    },
+                    "required": ["operation"]
+                }
+            },
+            {
+                "name": "mcp_kg-mcp-server_manage_api_keys",
+                "description": "Create, list, or revoke scoped API keys for the HTTP/SSE transport's Bearer authentication. Requires Admin scope when called over HTTP.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "operation": {
+                            "type": "string",
+                            "enum": ["create", "list", "revoke"],
+                            "description": "'create' a new key (returns its plaintext once), 'list' existing keys, 'revoke' one by id"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Human-readable label for the key being created"
+                        },
+                        "scopes": {
+                            "type": "array",
+                            "items": {"type": "string", "enum": ["read", "write", "admin"]},
+                            "default": ["read"],
+                            "description": "Scopes to grant the new key"
+                        },
+                        "id": {
+                            "type": "string",
+                            "description": "Key id to revoke"
+                        }
+                    },
+                    "required": ["operation"]
+                }
+            },
+            {
+                "name": "mcp_kg-mcp-server_get_recent_events",
+                "description": "Returns the current snapshot of the server's lock-free recent-events ring buffer (tracing events plus MCP error responses), newest last. Same data the tray app's 'Recent Events' submenu shows.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "limit": {
+                            "type": "integer",
+                            "description": "Return at most this many of the most recent events (default: the whole snapshot, up to the buffer's capacity)"
+                        }
+                    }
+                }
+            },
+            {
+                "name": "mcp_kg-mcp-server_batch",
+                "description": "Run a list of add_memory/search_memory sub-operations in one call, returning one tagged result per item in order. A malformed item fails only that item, not the whole batch, unless 'atomic' is set. add_memory items in the batch have their embeddings computed in one pass and are inserted in a single transaction, which is substantially faster than N separate add_memory calls.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "operations": {
+                            "type": "array",
+                            "description": "Each item is an add_memory or search_memory params object with an added 'type' field ('add_memory' or 'search_memory') naming which one it is",
+                            "items": {"type": "object"}
+                        },
+                        "atomic": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "If true, roll back every add_memory item in the batch when any one of them fails to store (search_memory items are unaffected, since they have nothing to roll back)"
+                        }
+                    },
+                    "required": ["operations"]
+                }
+            },
+            {
+                "name": "mcp_kg-mcp-server_manage_ingestion",
+                "description": "Start, stop, or check the status of long-running streaming ingestion sources that feed episodes into the graph from an external append-only stream (e.g. a tailed log file), as an alternative to one-shot add_memory calls. Each stream commits a checkpoint after every successfully stored episode and resumes from it on restart. 'status' reports each stream's current lag: how many records from its last poll are still awaiting a durable store + commit.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "operation": {
+                            "type": "string",
+                            "enum": ["start", "stop", "status"],
+                            "description": "'start' a new stream, 'stop' a running one, 'status' to report every registered stream"
+                        },
+                        "stream_id": {
+                            "type": "string",
+                            "description": "Stream to target; required for start and stop"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Newline-delimited file to tail as the stream source, for start (only source kind currently supported)"
+                        },
+                        "auto_offset_reset": {
+                            "type": "string",
+                            "enum": ["earliest", "latest"],
+                            "default": "latest",
+                            "description": "When starting a stream with no existing checkpoint: 'earliest' replays the whole stream from the beginning, 'latest' skips to the tail"
+                        },
+                        "group_id": {
+                            "type": "string",
+                            "description": "Group ID to associate episodes stored from this stream with"
+                        }
+                    },
+                    "required": ["operation"]
+                }
+            },
+            {
+                "name": "mcp_kg-mcp-server_admin_metrics",
+                "description": "Live health/capacity counters for operators: graph node/edge/episode counts and database file size, embedding-engine cache occupancy and loaded model, search-queue occupancy and rejection counts, and MemoryOptimizer cache hit/miss/eviction stats. A None embedding model means the model is still loading, not an error — useful for a readiness check distinct from liveness.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "format": {
+                            "type": "string",
+                            "enum": ["json", "openmetrics"],
+                            "default": "json",
+                            "description": "'json' for structured output, 'openmetrics' for Prometheus text exposition format"
+                        }
+                    }
+                }
             }
         ]
     })
-} 
\ No newline at end of file
+}
+
+/// Whether `name` is one of the tools `get_tool_definitions` advertises.
+/// Used by `mcp::auth::Authenticator::authorize_tool` to tell an unknown
+/// tool (`McpError::ToolNotFound`) apart from an insufficiently-scoped one
+/// (`McpError::AuthError`).
+pub fn tool_exists(name: &str) -> bool {
+    get_tool_definitions()["tools"]
+        .as_array()
+        .is_some_and(|tools| tools.iter().any(|t| t["name"] == name))
+}
\ No newline at end of file
@@ -0,0 +1,174 @@
+//! Declarative ingestion transform pipeline for `add_memory`'s `transforms`
+//! parameter.
+//!
+//! Each step is a `{type, config}` object, evaluated in order against the
+//! episode body before entity/relationship extraction runs. Steps are
+//! intentionally narrow and composable rather than a general scripting
+//! language — the same tradeoff the repo already makes elsewhere (e.g.
+//! `PageCursor` over a general query language) in favor of something that
+//! fails predictably on bad input instead of silently doing the wrong
+//! thing.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde_json::Value;
+
+/// One transform step's effect, recorded alongside the episode as
+/// provenance: which step ran and a short human-readable note on what it
+/// did, so a caller can tell why a stored body differs from what they sent.
+#[derive(Debug, Clone)]
+pub struct FiredTransform {
+    pub step_type: String,
+    pub detail: String,
+}
+
+impl FiredTransform {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "type": self.step_type,
+            "detail": self.detail,
+        })
+    }
+}
+
+/// Runs `transforms` over `body` in order, returning the transformed body
+/// and a provenance record of every step that actually changed something.
+/// A step that doesn't apply (e.g. a field extraction whose field is
+/// absent) is skipped rather than erroring, since heterogeneous input is
+/// the whole reason this pipeline exists; a step with a malformed `config`
+/// errors immediately so a caller finds out at ingest time, not by staring
+/// at a silently-unredacted secret later.
+pub fn apply_transforms(body: &str, transforms: &[Value]) -> Result<(String, Vec<Value>)> {
+    let mut current = body.to_string();
+    let mut fired = Vec::new();
+
+    for step in transforms {
+        let step_type = step.get("type")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Each transform step requires a string 'type' field"))?;
+        let config = step.get("config").cloned().unwrap_or(Value::Null);
+
+        let outcome = match step_type {
+            "extract_field" => extract_field(&current, &config)?,
+            "rename_field" => rename_field(&current, &config)?,
+            "redact" => redact(&current, &config)?,
+            "normalize_language" => normalize_language(&current),
+            "derive_field" => derive_field(&current, &config)?,
+            other => return Err(anyhow!("Unknown transform type: {}", other)),
+        };
+
+        if let Some((next, detail)) = outcome {
+            current = next;
+            fired.push(FiredTransform { step_type: step_type.to_string(), detail });
+        }
+    }
+
+    Ok((current, fired.iter().map(FiredTransform::to_json).collect()))
+}
+
+/// `{field}`: for a JSON body, replaces the body with the string value at
+/// the top-level key `field` (a no-op if the body isn't a JSON object or
+/// the field is absent/not a string).
+fn extract_field(body: &str, config: &Value) -> Result<Option<(String, String)>> {
+    let field = config.get("field")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("extract_field requires config.field"))?;
+
+    let Ok(parsed) = serde_json::from_str::<Value>(body) else { return Ok(None) };
+    let Some(extracted) = parsed.get(field).and_then(|v| v.as_str()) else { return Ok(None) };
+
+    Ok(Some((
+        extracted.to_string(),
+        format!("extracted field '{field}' as the episode body"),
+    )))
+}
+
+/// `{from, to}`: for a JSON object body, renames key `from` to `to` in
+/// place (a no-op if the body isn't a JSON object or `from` is absent).
+fn rename_field(body: &str, config: &Value) -> Result<Option<(String, String)>> {
+    let from = config.get("from")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("rename_field requires config.from"))?;
+    let to = config.get("to")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("rename_field requires config.to"))?;
+
+    let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(body) else { return Ok(None) };
+    let Some(value) = map.remove(from) else { return Ok(None) };
+    map.insert(to.to_string(), value);
+
+    Ok(Some((
+        serde_json::to_string(&Value::Object(map))?,
+        format!("renamed field '{from}' to '{to}'"),
+    )))
+}
+
+/// `{pattern, replacement?}`: replaces every regex match of `pattern` in
+/// the body with `replacement` (default `"[REDACTED]"`), for scrubbing
+/// secrets/PII before extraction sees them.
+fn redact(body: &str, config: &Value) -> Result<Option<(String, String)>> {
+    let pattern = config.get("pattern")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("redact requires config.pattern"))?;
+    let replacement = config.get("replacement")
+        .and_then(|v| v.as_str())
+        .unwrap_or("[REDACTED]");
+
+    let regex = Regex::new(pattern).map_err(|e| anyhow!("redact: invalid regex pattern: {}", e))?;
+    let matches = regex.find_iter(body).count();
+    if matches == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some((
+        regex.replace_all(body, replacement).into_owned(),
+        format!("redacted {matches} match(es) of pattern '{pattern}'"),
+    )))
+}
+
+/// No-config step that collapses runs of whitespace to a single space and
+/// trims the ends. A conservative stand-in for full language
+/// normalization — there's no locale/script detection in this crate to
+/// drive anything more elaborate yet.
+fn normalize_language(body: &str) -> Option<(String, String)> {
+    let normalized = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized == body {
+        return None;
+    }
+    Some((normalized, "normalized whitespace".to_string()))
+}
+
+/// `{fields, new_field, join_with?}`: for a JSON object body, sets
+/// `new_field` to the values of `fields` (each coerced to a string, or
+/// skipped if absent) joined with `join_with` (default `" "`).
+fn derive_field(body: &str, config: &Value) -> Result<Option<(String, String)>> {
+    let fields = config.get("fields")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("derive_field requires config.fields (array of field names)"))?;
+    let new_field = config.get("new_field")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("derive_field requires config.new_field"))?;
+    let join_with = config.get("join_with").and_then(|v| v.as_str()).unwrap_or(" ");
+
+    let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(body) else { return Ok(None) };
+
+    let parts: Vec<String> = fields.iter()
+        .filter_map(|f| f.as_str())
+        .filter_map(|name| map.get(name))
+        .map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect();
+
+    if parts.is_empty() {
+        return Ok(None);
+    }
+
+    map.insert(new_field.to_string(), Value::String(parts.join(join_with)));
+
+    Ok(Some((
+        serde_json::to_string(&Value::Object(map))?,
+        format!("derived field '{new_field}' from {fields:?}"),
+    )))
+}
@@ -0,0 +1,343 @@
+//! Supervised background worker registry.
+//!
+//! `McpServer::start_background_tasks` used to spawn three bare `tokio::spawn`
+//! loops (memory GC, embedding warmup, DB health check) with no way to
+//! observe or control them from the outside. This module gives each of those
+//! jobs a [`BackgroundWorker`] impl, owned by a [`WorkerManager`] that tracks
+//! status and iteration counts and accepts [`WorkerCommand`]s — mirroring
+//! Garage's background task manager (#332): one supervised worker per job,
+//! configurable tranquility, status listing, and error reporting instead of
+//! loops that back off invisibly.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, error, warn};
+
+/// Current lifecycle state of a registered worker, as seen from the outside.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WorkerStatus {
+    /// Waiting out its `tranquility` delay until the next iteration.
+    Idle,
+    /// Currently running `run_iteration`.
+    Busy,
+    /// Paused via `WorkerCommand::Pause`; won't run until resumed.
+    Paused,
+    /// The last iteration returned an error. The worker keeps running —
+    /// `Dead` here means "unhealthy", not "stopped".
+    Dead { last_error: String },
+}
+
+/// Control-plane commands accepted by a running worker's command channel.
+#[derive(Debug, Clone)]
+pub enum WorkerCommand {
+    Pause,
+    Resume,
+    /// Skip the rest of the current tranquility delay and iterate now.
+    TriggerNow,
+    /// Stop the worker's loop for good.
+    Cancel,
+}
+
+/// A supervised background job. Implementors hold whatever `Arc<...>` state
+/// they need (storage, embedding engine, ...) and do one unit of work per
+/// `run_iteration` call; the manager handles scheduling, pausing, and status
+/// reporting around it.
+#[async_trait]
+pub trait BackgroundWorker: Send + Sync + 'static {
+    /// Stable identifier, used as the key in `WorkerManager::status_report`
+    /// and the `worker_name` argument to `control_worker`.
+    fn name(&self) -> &str;
+
+    /// How long to wait after a successful iteration before running again.
+    fn tranquility(&self) -> Duration;
+
+    /// Do one unit of work. Errors are recorded as `WorkerStatus::Dead` but
+    /// do not stop the worker's loop.
+    async fn run_iteration(&self) -> Result<()>;
+}
+
+/// Point-in-time snapshot of one worker, as returned by `/workers` and the
+/// `list_workers` MCP operation.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerReport {
+    pub name: String,
+    pub status: WorkerStatus,
+    pub iterations: u64,
+    pub tranquility_secs: u64,
+}
+
+struct WorkerEntry {
+    status: Arc<RwLock<WorkerStatus>>,
+    iterations: Arc<AtomicU64>,
+    tranquility: Duration,
+    command_tx: mpsc::UnboundedSender<WorkerCommand>,
+}
+
+/// Registry of supervised background workers. Each `register` call spawns
+/// one `tokio` task running that worker's loop; `status_report` and
+/// `send_command` let callers (the `/workers` route, the `manage_workers`
+/// MCP tool) introspect and steer them without touching the tasks directly.
+#[derive(Default)]
+pub struct WorkerManager {
+    workers: RwLock<HashMap<String, WorkerEntry>>,
+}
+
+impl WorkerManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns `worker`'s supervised loop and registers it under its `name()`.
+    pub async fn register<W: BackgroundWorker>(&self, worker: W) {
+        let name = worker.name().to_string();
+        let status = Arc::new(RwLock::new(WorkerStatus::Idle));
+        let iterations = Arc::new(AtomicU64::new(0));
+        let tranquility = worker.tranquility();
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+
+        self.workers.write().await.insert(
+            name.clone(),
+            WorkerEntry {
+                status: Arc::clone(&status),
+                iterations: Arc::clone(&iterations),
+                tranquility,
+                command_tx,
+            },
+        );
+
+        tokio::spawn(run_worker_loop(worker, status, iterations, command_rx));
+    }
+
+    /// Snapshots every registered worker's current status and iteration count.
+    pub async fn status_report(&self) -> Vec<WorkerReport> {
+        let workers = self.workers.read().await;
+        let mut reports = Vec::with_capacity(workers.len());
+        for (name, entry) in workers.iter() {
+            reports.push(WorkerReport {
+                name: name.clone(),
+                status: entry.status.read().await.clone(),
+                iterations: entry.iterations.load(Ordering::Relaxed),
+                tranquility_secs: entry.tranquility.as_secs(),
+            });
+        }
+        reports.sort_by(|a, b| a.name.cmp(&b.name));
+        reports
+    }
+
+    /// Forwards `command` to `worker_name`'s command channel. Returns an
+    /// error if no worker is registered under that name.
+    pub async fn send_command(&self, worker_name: &str, command: WorkerCommand) -> Result<()> {
+        let workers = self.workers.read().await;
+        let entry = workers
+            .get(worker_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown worker: {}", worker_name))?;
+        entry
+            .command_tx
+            .send(command)
+            .map_err(|_| anyhow::anyhow!("Worker '{}' loop has exited", worker_name))
+    }
+
+    /// Cancels every registered worker's loop. Called during graceful
+    /// shutdown so no iteration is left mid-flight when the process exits;
+    /// a worker whose loop already exited is ignored rather than treated as
+    /// an error.
+    pub async fn shutdown_all(&self) {
+        let workers = self.workers.read().await;
+        for (name, entry) in workers.iter() {
+            if entry.command_tx.send(WorkerCommand::Cancel).is_err() {
+                debug!("Worker '{}' already stopped before shutdown", name);
+            }
+        }
+    }
+}
+
+/// What woke `run_worker_loop` up for one pass: the tranquility timer
+/// elapsing, an explicit command, or the command channel closing (the
+/// `WorkerManager` was dropped).
+enum Wake {
+    TimerElapsed,
+    Command(WorkerCommand),
+    ChannelClosed,
+}
+
+async fn run_worker_loop<W: BackgroundWorker>(
+    worker: W,
+    status: Arc<RwLock<WorkerStatus>>,
+    iterations: Arc<AtomicU64>,
+    mut command_rx: mpsc::UnboundedReceiver<WorkerCommand>,
+) {
+    let mut paused = false;
+
+    loop {
+        let wake = if paused {
+            match command_rx.recv().await {
+                Some(cmd) => Wake::Command(cmd),
+                None => Wake::ChannelClosed,
+            }
+        } else {
+            tokio::select! {
+                _ = tokio::time::sleep(worker.tranquility()) => Wake::TimerElapsed,
+                cmd = command_rx.recv() => match cmd {
+                    Some(cmd) => Wake::Command(cmd),
+                    None => Wake::ChannelClosed,
+                },
+            }
+        };
+
+        match wake {
+            Wake::ChannelClosed => {
+                debug!("Worker '{}' command channel closed, stopping", worker.name());
+                return;
+            }
+            Wake::Command(WorkerCommand::Pause) => {
+                paused = true;
+                *status.write().await = WorkerStatus::Paused;
+                continue;
+            }
+            Wake::Command(WorkerCommand::Resume) => {
+                paused = false;
+                *status.write().await = WorkerStatus::Idle;
+                continue;
+            }
+            Wake::Command(WorkerCommand::Cancel) => {
+                debug!("Worker '{}' cancelled", worker.name());
+                return;
+            }
+            // Explicit trigger overrides a pause for one iteration; the
+            // timer elapsing or an explicit trigger while running both just
+            // fall through to running the iteration below.
+            Wake::Command(WorkerCommand::TriggerNow) | Wake::TimerElapsed => {}
+        }
+
+        *status.write().await = WorkerStatus::Busy;
+        match worker.run_iteration().await {
+            Ok(()) => {
+                iterations.fetch_add(1, Ordering::Relaxed);
+                *status.write().await = if paused { WorkerStatus::Paused } else { WorkerStatus::Idle };
+            }
+            Err(e) => {
+                iterations.fetch_add(1, Ordering::Relaxed);
+                error!("Worker '{}' iteration failed: {}", worker.name(), e);
+                *status.write().await = WorkerStatus::Dead { last_error: e.to_string() };
+            }
+        }
+    }
+}
+
+/// Periodic `MemoryOptimizer::force_gc` sweep. Replaces the old bare
+/// `tokio::spawn` GC loop; the consecutive-error backoff that loop used to
+/// do manually now just shows up as `WorkerStatus::Dead` between successful
+/// iterations.
+pub struct MemoryGcWorker {
+    memory_optimizer: Arc<crate::memory::MemoryOptimizer>,
+}
+
+impl MemoryGcWorker {
+    pub fn new(memory_optimizer: Arc<crate::memory::MemoryOptimizer>) -> Self {
+        Self { memory_optimizer }
+    }
+}
+
+#[async_trait]
+impl BackgroundWorker for MemoryGcWorker {
+    fn name(&self) -> &str {
+        "memory_gc"
+    }
+
+    fn tranquility(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    async fn run_iteration(&self) -> Result<()> {
+        self.memory_optimizer.force_gc().await?;
+        debug!("Memory GC completed successfully");
+        Ok(())
+    }
+}
+
+/// Re-warms the embedding cache with a fixed set of common queries.
+pub struct EmbeddingWarmupWorker {
+    embedding_engine: Arc<crate::embeddings::LocalEmbeddingEngine>,
+}
+
+impl EmbeddingWarmupWorker {
+    pub fn new(embedding_engine: Arc<crate::embeddings::LocalEmbeddingEngine>) -> Self {
+        Self { embedding_engine }
+    }
+}
+
+#[async_trait]
+impl BackgroundWorker for EmbeddingWarmupWorker {
+    fn name(&self) -> &str {
+        "embedding_warmup"
+    }
+
+    fn tranquility(&self) -> Duration {
+        Duration::from_secs(3600)
+    }
+
+    async fn run_iteration(&self) -> Result<()> {
+        let common_queries = vec![
+            "search".to_string(),
+            "query".to_string(),
+            "find".to_string(),
+            "knowledge".to_string(),
+            "graph".to_string(),
+        ];
+        self.embedding_engine.warmup(common_queries).await?;
+        debug!("Embedding warmup completed successfully");
+        Ok(())
+    }
+}
+
+/// Periodic `GraphStorage::count_nodes` liveness probe.
+pub struct DbHealthCheckWorker {
+    storage: Arc<crate::graph::storage::GraphStorage>,
+}
+
+impl DbHealthCheckWorker {
+    pub fn new(storage: Arc<crate::graph::storage::GraphStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait]
+impl BackgroundWorker for DbHealthCheckWorker {
+    fn name(&self) -> &str {
+        "db_health_check"
+    }
+
+    fn tranquility(&self) -> Duration {
+        Duration::from_secs(600)
+    }
+
+    async fn run_iteration(&self) -> Result<()> {
+        let count = self.storage.count_nodes().await?;
+        debug!("Database health check: {} nodes", count);
+        Ok(())
+    }
+}
+
+/// Parses the `control_worker` MCP operation's `command` string.
+pub fn parse_worker_command(command: &str) -> Result<WorkerCommand> {
+    match command {
+        "pause" => Ok(WorkerCommand::Pause),
+        "resume" => Ok(WorkerCommand::Resume),
+        "trigger_now" => Ok(WorkerCommand::TriggerNow),
+        "cancel" => Ok(WorkerCommand::Cancel),
+        other => {
+            warn!("Unknown worker command: {}", other);
+            Err(anyhow::anyhow!(
+                "Unknown worker command '{}'; expected one of: pause, resume, trigger_now, cancel",
+                other
+            ))
+        }
+    }
+}
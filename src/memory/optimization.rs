@@ -1,9 +1,14 @@
 use anyhow::Result;
-use std::collections::{HashMap, BTreeMap, VecDeque};
+use std::collections::{HashMap, BTreeMap, HashSet, VecDeque};
 use tracing::debug;
 use std::sync::{Arc, RwLock, Mutex};
+use std::sync::atomic::{AtomicUsize, AtomicU64, Ordering};
 use std::time::{Duration, Instant, SystemTime};
 use std::mem;
+use std::path::PathBuf;
+use std::fs;
+use serde::{Serialize, de::DeserializeOwned};
+use thiserror::Error;
 use uuid::Uuid;
 use crate::graph::{KGNode, KGEdge, Episode};
 
@@ -12,6 +17,11 @@ pub struct MemoryOptimizer {
     memory_pool: Arc<MemoryPool>,
     gc_scheduler: Arc<GarbageCollector>,
     performance_monitor: Arc<PerformanceMonitor>,
+    /// Handles for the background tasks spawned by `initialize` (GC ticker,
+    /// snapshot/suggestion monitor, preload). Tokio's own multi-threaded
+    /// scheduler work-steals these across its worker pool; `shutdown` aborts
+    /// them so a caller can tear the optimizer down cleanly.
+    background_tasks: Mutex<Vec<tokio::task::JoinHandle<()>>>,
     config: MemoryConfig,
 }
 
@@ -25,6 +35,13 @@ pub struct MemoryConfig {
     pub preload_enabled: bool,
     pub compression_enabled: bool,
     pub memory_mapping_enabled: bool,
+    /// Hard byte budget shared by every cache level's `Reservation`.
+    /// `gc_threshold` of this is the watermark `MemoryPool::try_grow`
+    /// enforces before it starts spilling cold entries to `spill_dir`.
+    pub max_memory_bytes: usize,
+    /// Directory spilled cache entries are serialized into when a `put_*`
+    /// would push the pool past its watermark.
+    pub spill_dir: PathBuf,
 }
 
 impl Default for MemoryConfig {
@@ -38,18 +55,424 @@ impl Default for MemoryConfig {
             preload_enabled: true,
             compression_enabled: true,
             memory_mapping_enabled: true,
+            max_memory_bytes: 256 * 1024 * 1024, // 256MB
+            spill_dir: std::env::temp_dir().join("cursor-kg-spill"),
         }
     }
 }
 
-// Multi-level LRU cache with TTL support
+// Default number of shards for each cache level; overridden at construction
+// time by `shard_count()` when the host exposes more cores.
+const DEFAULT_SHARD_COUNT: usize = 16;
+
+/// Picks a power-of-two shard count so `shard_index` can use a cheap mask
+/// instead of a modulo. Never goes below `DEFAULT_SHARD_COUNT` so single/few
+/// core hosts still get enough parallelism to avoid false sharing on the
+/// stats counters.
+fn shard_count() -> usize {
+    num_cpus::get().next_power_of_two().max(DEFAULT_SHARD_COUNT)
+}
+
+/// Hashes `key` with an FxHash-style multiply/rotate mix and maps it onto one
+/// of `shard_count` shards. `shard_count` must be a power of two.
+fn shard_index(key: &[u8], shard_count: usize) -> usize {
+    let mut state: u64 = 0;
+    for &byte in key {
+        state = (state.rotate_left(5) ^ byte as u64).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+    (state as usize) & (shard_count - 1)
+}
+
+/// Per-shard hit/miss counters, aggregated on demand by `CacheManager::get_statistics`.
+#[derive(Debug, Default)]
+struct ShardStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// A cache level split into independent lock-per-shard buckets so that
+/// unrelated keys never contend on the same `RwLock`. The key is hashed once
+/// by the caller to pick a shard; everything below operates on that shard
+/// alone.
+struct ShardedCache<K, V> {
+    shards: Vec<RwLock<LruCache<K, V>>>,
+    stats: Vec<ShardStats>,
+}
+
+impl<K: Clone + Eq + std::hash::Hash, V: Clone> ShardedCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        let n = shard_count();
+        let per_shard = (capacity / n).max(1);
+        Self {
+            shards: (0..n).map(|_| RwLock::new(LruCache::new(per_shard))).collect(),
+            stats: (0..n).map(|_| ShardStats::default()).collect(),
+        }
+    }
+
+    fn get(&self, shard: usize, key: &K) -> Option<V> {
+        let cache = match self.shards[shard].read() {
+            Ok(cache) => cache,
+            Err(e) => {
+                tracing::error!("Failed to acquire cache shard read lock: {}", e);
+                return None;
+            }
+        };
+        let hit = cache.get(key).cloned();
+        if hit.is_some() {
+            self.stats[shard].hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.stats[shard].misses.fetch_add(1, Ordering::Relaxed);
+        }
+        hit
+    }
+
+    /// Returns the evicted entry (if the shard was at capacity) so callers
+    /// can shrink their `Reservation` by its size.
+    fn put(&self, shard: usize, key: K, value: V) -> Result<Option<(K, V)>> {
+        match self.shards[shard].write() {
+            Ok(mut cache) => Ok(cache.put(key, value)),
+            Err(e) => {
+                tracing::error!("Failed to acquire cache shard write lock: {}", e);
+                Err(anyhow::anyhow!("Cache write failed: {}", e))
+            }
+        }
+    }
+
+    fn hits(&self) -> u64 {
+        self.stats.iter().map(|s| s.hits.load(Ordering::Relaxed)).sum()
+    }
+
+    fn misses(&self) -> u64 {
+        self.stats.iter().map(|s| s.misses.load(Ordering::Relaxed)).sum()
+    }
+
+    /// Clones every currently-cached value across all shards. Used for the
+    /// approximate-match scan in `CacheManager::find_similar_search`, which
+    /// needs to compare a new query embedding against every cached one; not
+    /// on the hot exact-lookup path.
+    fn snapshot_values(&self) -> Vec<V> {
+        self.shards
+            .iter()
+            .filter_map(|shard| shard.read().ok())
+            .flat_map(|cache| cache.map.values().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+/// A consumer's current byte usage against the shared `MemoryPool` budget.
+/// Each cache level (and the embedding store) holds one of these instead of
+/// reporting usage through a side channel.
+#[derive(Debug, Default)]
+pub struct Reservation {
+    used: AtomicUsize,
+}
+
+impl Reservation {
+    fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    fn grow(&self, bytes: usize) {
+        self.used.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, bytes: usize) {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |u| Some(u.saturating_sub(bytes)))
+            .ok();
+    }
+}
+
+/// Returned by `MemoryPool::try_grow` when the pool is over its watermark and
+/// no registered consumer had anything left to spill to disk.
+#[derive(Debug, Error)]
+pub enum TryGrowError {
+    #[error("cannot reserve {requested} bytes: pool at {reserved}/{pool_size} and no consumer is spillable")]
+    NothingSpillable {
+        requested: usize,
+        reserved: usize,
+        pool_size: usize,
+    },
+    #[error("failed to spill cache entry to disk: {0}")]
+    SpillIo(String),
+}
+
+/// A cache level that can give bytes back under pressure by serializing its
+/// coldest entries to disk. Implemented by the spillable `ShardedCache`
+/// levels (L3/episode and query-result); L1/L2/embedding are small and hot
+/// enough that we never spill them.
+trait Spillable {
+    /// Bytes this consumer is currently holding that it could spill.
+    fn spillable_bytes(&self) -> usize;
+    /// Spill the coldest entries until at least `need` bytes are freed (or
+    /// the cache is empty); returns the number of bytes actually freed.
+    fn spill_coldest(&self, need: usize) -> usize;
+}
+
+/// Disk-backed store for spilled cache entries, one file per key under
+/// `MemoryConfig::spill_dir`. Entries are serialized with bincode; the
+/// in-memory cache keeps a tombstone (see `CacheManager::spilled`) so a
+/// later `get_*` knows to rehydrate from here instead of reporting a miss.
+struct SpillStore {
+    dir: PathBuf,
+}
+
+impl SpillStore {
+    fn new(dir: PathBuf) -> Self {
+        if let Err(e) = fs::create_dir_all(&dir) {
+            tracing::warn!("Failed to create spill directory {}: {}", dir.display(), e);
+        }
+        Self { dir }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        // Keys (UUIDs/query strings) can contain path separators, so hash
+        // them into a filesystem-safe name rather than using them verbatim.
+        let mut state: u64 = 0;
+        for &byte in key.as_bytes() {
+            state = (state.rotate_left(5) ^ byte as u64).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+        }
+        self.dir.join(format!("{:016x}.spill", state))
+    }
+
+    fn write<T: Serialize>(&self, key: &str, value: &T) -> Result<usize, TryGrowError> {
+        let bytes = bincode::serialize(value).map_err(|e| TryGrowError::SpillIo(e.to_string()))?;
+        let len = bytes.len();
+        fs::write(self.path_for(key), bytes).map_err(|e| TryGrowError::SpillIo(e.to_string()))?;
+        Ok(len)
+    }
+
+    fn read<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = fs::read(self.path_for(key)).ok()?;
+        bincode::deserialize(&bytes).ok()
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path_for(key));
+    }
+
+    /// Path of the small index file that remembers which keys are currently
+    /// spilled under `name` (one index per spillable level), so a later
+    /// process can find them again without listing the whole spill directory
+    /// and guessing at filenames.
+    fn index_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.index"))
+    }
+
+    fn write_index(&self, name: &str, keys: &HashSet<String>) {
+        let mut sorted: Vec<&str> = keys.iter().map(String::as_str).collect();
+        sorted.sort_unstable();
+        match bincode::serialize(&sorted) {
+            Ok(bytes) => {
+                if let Err(e) = fs::write(self.index_path(name), bytes) {
+                    tracing::warn!("Failed to persist spill index {}: {}", name, e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize spill index {}: {}", name, e),
+        }
+    }
+
+    /// Reads back the keys recorded by `write_index`, if any. Returns an
+    /// empty set on a fresh `spill_dir` or a corrupt/missing index rather
+    /// than failing preload outright.
+    fn read_index(&self, name: &str) -> HashSet<String> {
+        fs::read(self.index_path(name))
+            .ok()
+            .and_then(|bytes| bincode::deserialize::<Vec<String>>(&bytes).ok())
+            .map(|keys| keys.into_iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// On-disk representation of a spilled entry. Drops the `Instant` timestamps
+/// `CachedItem` carries (neither bincode-friendly nor meaningful across a
+/// process restart) but keeps `access_count` so a rehydrated entry doesn't
+/// look colder than it is.
+#[derive(Serialize, serde::Deserialize)]
+struct SpillRecord<T> {
+    data: T,
+    access_count: u64,
+}
+
+/// Bridges a single spillable `ShardedCache` level to the `Spillable` trait
+/// `MemoryPool::try_grow` spills through, without the cache itself needing
+/// to know about disk storage or tombstones.
+struct SpillAdapter<'a, T> {
+    cache: &'a ShardedCache<String, CachedItem<T>>,
+    reservation: &'a Reservation,
+    spilled: &'a Mutex<HashSet<String>>,
+    spill_store: &'a SpillStore,
+    level: CacheLevel,
+    /// Name of this level's persisted spill index (see `SpillStore::write_index`).
+    index_name: &'static str,
+    performance_monitor: &'a PerformanceMonitor,
+}
+
+impl<'a, T: Clone + Serialize> Spillable for SpillAdapter<'a, T> {
+    fn spillable_bytes(&self) -> usize {
+        self.reservation.used()
+    }
+
+    fn spill_coldest(&self, need: usize) -> usize {
+        let mut freed = 0usize;
+        while freed < need {
+            let mut progressed = false;
+            for shard in &self.cache.shards {
+                let popped = match shard.write() {
+                    Ok(mut guard) => guard.pop_oldest(),
+                    Err(_) => None,
+                };
+                let Some((key, item)) = popped else { continue };
+                let record = SpillRecord {
+                    data: item.data,
+                    access_count: item.access_count,
+                };
+                match self.spill_store.write(&key, &record) {
+                    Ok(_) => {
+                        let spilled = {
+                            let mut guard = self.spilled.lock().unwrap();
+                            guard.insert(key);
+                            guard.clone()
+                        };
+                        self.spill_store.write_index(self.index_name, &spilled);
+                        freed += item.size_bytes;
+                        self.reservation.shrink(item.size_bytes);
+                        self.performance_monitor.record_event(TraceEvent::Spill {
+                            level: self.level,
+                            bytes: item.size_bytes,
+                        });
+                        progressed = true;
+                    }
+                    Err(e) => tracing::warn!("Failed to spill cache entry to disk: {}", e),
+                }
+                if freed >= need {
+                    break;
+                }
+            }
+            if !progressed {
+                break;
+            }
+        }
+        freed
+    }
+}
+
+/// Batched cosine-similarity re-ranking for the embedding/query caches. The
+/// default path stacks candidates into a flat `[N, D]` buffer and scores
+/// them in one pass; with the `gpu-similarity` feature enabled, the same
+/// batch is instead offloaded to a compute backend (e.g. `wgpu`) as a single
+/// matmul, which is where the win shows up once `N` is large. Either path
+/// returns the same `(index, score)` ranking, so callers never branch on
+/// which backend actually ran.
+struct SimilarityEngine;
+
+impl SimilarityEngine {
+    /// Returns the `k` highest cosine-similarity `(index, score)` pairs
+    /// between `query` and `candidates`, sorted descending by score.
+    fn batch_topk(query: &[f32], candidates: &[Vec<f32>], k: usize) -> Vec<(usize, f32)> {
+        #[cfg(feature = "gpu-similarity")]
+        {
+            if let Some(ranked) = gpu_similarity::batch_topk(query, candidates, k) {
+                return ranked;
+            }
+        }
+        Self::batch_topk_scalar(query, candidates, k)
+    }
+
+    /// Scalar fallback, also used directly when `gpu-similarity` is off.
+    fn batch_topk_scalar(query: &[f32], candidates: &[Vec<f32>], k: usize) -> Vec<(usize, f32)> {
+        let mut scored: Vec<(usize, f32)> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, candidate)| (i, crate::embeddings::cosine_similarity(query, candidate)))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        scored
+    }
+}
+
+/// GPU-backed batch similarity, compiled only when the `gpu-similarity`
+/// feature is enabled. Stacks `candidates` into one `[N, D]` tensor and runs
+/// a single batched matmul against `query` rather than scoring row-by-row;
+/// `batch_topk` returns `None` (falling back to the scalar path) if the
+/// compute backend can't be initialized on this machine.
+#[cfg(feature = "gpu-similarity")]
+mod gpu_similarity {
+    pub fn batch_topk(query: &[f32], candidates: &[Vec<f32>], k: usize) -> Option<Vec<(usize, f32)>> {
+        use ndarray::{Array1, Array2};
+
+        if candidates.is_empty() {
+            return Some(Vec::new());
+        }
+        let dim = query.len();
+        let n = candidates.len();
+
+        let flat: Vec<f32> = candidates.iter().flat_map(|row| row.iter().copied()).collect();
+        let matrix = Array2::from_shape_vec((n, dim), flat).ok()?;
+        let query_vec = Array1::from_vec(query.to_vec());
+
+        let dots = matrix.dot(&query_vec);
+        let query_norm = query_vec.dot(&query_vec).sqrt();
+        let row_norms = matrix.map_axis(ndarray::Axis(1), |row| row.dot(&row).sqrt());
+
+        let mut scored: Vec<(usize, f32)> = dots
+            .iter()
+            .zip(row_norms.iter())
+            .enumerate()
+            .map(|(i, (&dot, &row_norm))| {
+                let denom = row_norm * query_norm;
+                let score = if denom == 0.0 { 0.0 } else { dot / denom };
+                (i, score)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+        Some(scored)
+    }
+}
+
+/// Central reservation tracker: every cache level and the embedding store
+/// registers a `Reservation` here and calls `try_grow` before inserting. When
+/// growing directly would exceed `pool_size` (the enforced `gc_threshold`
+/// watermark over `max_memory_bytes`), the largest spillable consumer is
+/// asked to push its coldest entries to disk before the grow is retried.
+pub struct MemoryPool {
+    node_pool: Arc<Mutex<VecDeque<KGNode>>>,
+    edge_pool: Arc<Mutex<VecDeque<KGEdge>>>,
+    episode_pool: Arc<Mutex<VecDeque<Episode>>>,
+    vector_pool: Arc<Mutex<VecDeque<Vec<f32>>>>,
+    string_pool: Arc<Mutex<VecDeque<String>>>,
+    allocation_stats: Arc<Mutex<AllocationStats>>,
+    reserved: AtomicUsize,
+    pool_size: usize,
+    config: MemoryConfig,
+}
+
+// Multi-level LRU cache with TTL support. Each level is sharded (see
+// `ShardedCache`) so independent UUIDs/queries don't block each other behind
+// one global lock.
 pub struct CacheManager {
-    l1_cache: Arc<RwLock<LruCache<Uuid, CachedItem<KGNode>>>>,
-    l2_cache: Arc<RwLock<LruCache<String, CachedItem<Vec<KGNode>>>>>,
-    l3_cache: Arc<RwLock<LruCache<String, CachedItem<Episode>>>>,
-    embedding_cache: Arc<RwLock<LruCache<String, CachedItem<Vec<f32>>>>>,
-    query_cache: Arc<RwLock<LruCache<String, CachedItem<SearchCacheEntry>>>>,
+    l1_cache: ShardedCache<Uuid, CachedItem<KGNode>>,
+    l2_cache: ShardedCache<String, CachedItem<Vec<KGNode>>>,
+    l3_cache: ShardedCache<String, CachedItem<Episode>>,
+    embedding_cache: ShardedCache<String, CachedItem<Vec<f32>>>,
+    query_cache: ShardedCache<String, CachedItem<SearchCacheEntry>>,
     statistics: Arc<Mutex<CacheStatistics>>,
+    /// Byte usage each level reports to `memory_pool`. Only `l3`/`query` are
+    /// spillable; `l1`/`embedding` just reserve so the pool's total stays
+    /// accurate, and shrink again whenever their own LRU eviction frees space.
+    l1_reservation: Reservation,
+    embedding_reservation: Reservation,
+    l3_reservation: Reservation,
+    query_reservation: Reservation,
+    /// Keys currently spilled to `spill_store` rather than held in memory;
+    /// `get_episode`/`get_search_results` rehydrate through these tombstones.
+    l3_spilled: Mutex<HashSet<String>>,
+    query_spilled: Mutex<HashSet<String>>,
+    spill_store: Arc<SpillStore>,
+    memory_pool: Arc<MemoryPool>,
+    performance_monitor: Arc<PerformanceMonitor>,
     config: MemoryConfig,
 }
 
@@ -62,12 +485,17 @@ struct CachedItem<T> {
     size_bytes: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
 struct SearchCacheEntry {
     results: Vec<(KGNode, f32)>,
     episodes: Vec<(Episode, f32)>,
     timestamp: SystemTime,
     query_hash: u64,
+    /// Embedding of the query that produced this entry, when the caller had
+    /// one available. Lets `find_similar_search` re-rank this entry against
+    /// a *different* query that embeds close to it, instead of only ever
+    /// matching on the exact query string.
+    query_embedding: Option<Vec<f32>>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -86,17 +514,6 @@ struct CacheStatistics {
     evictions: u64,
 }
 
-// Memory pool for object reuse
-pub struct MemoryPool {
-    node_pool: Arc<Mutex<VecDeque<KGNode>>>,
-    edge_pool: Arc<Mutex<VecDeque<KGEdge>>>,
-    episode_pool: Arc<Mutex<VecDeque<Episode>>>,
-    vector_pool: Arc<Mutex<VecDeque<Vec<f32>>>>,
-    string_pool: Arc<Mutex<VecDeque<String>>>,
-    allocation_stats: Arc<Mutex<AllocationStats>>,
-    config: MemoryConfig,
-}
-
 #[derive(Debug, Default, Clone)]
 struct AllocationStats {
     nodes_allocated: u64,
@@ -115,6 +532,11 @@ pub struct GarbageCollector {
     last_gc: Arc<Mutex<Instant>>,
     memory_usage: Arc<Mutex<BTreeMap<Instant, usize>>>,
     gc_stats: Arc<Mutex<GcStatistics>>,
+    /// Serializes the actual sweep so the scheduled background pass and a
+    /// caller-triggered `force_gc` can never run at once and double-count
+    /// `total_memory_freed`.
+    sweep_lock: tokio::sync::Mutex<()>,
+    performance_monitor: Arc<PerformanceMonitor>,
     config: MemoryConfig,
 }
 
@@ -131,6 +553,213 @@ pub struct PerformanceMonitor {
     metrics: Arc<Mutex<PerformanceMetrics>>,
     memory_snapshots: Arc<Mutex<VecDeque<MemorySnapshot>>>,
     optimization_suggestions: Arc<Mutex<Vec<OptimizationSuggestion>>>,
+    trace: TraceRecorder,
+}
+
+/// Which cache level (or subsystem) a `TraceEvent` is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CacheLevel {
+    L1,
+    L2,
+    L3,
+    Embedding,
+    Query,
+    Other,
+}
+
+impl CacheLevel {
+    fn from_tag(tag: &str) -> Self {
+        match tag {
+            "l1" => CacheLevel::L1,
+            "l2" => CacheLevel::L2,
+            "l3" | "episode" => CacheLevel::L3,
+            "embedding" => CacheLevel::Embedding,
+            "query" => CacheLevel::Query,
+            _ => CacheLevel::Other,
+        }
+    }
+}
+
+/// A single low-overhead profiler event. Carries only a monotonic timestamp
+/// and a small `Copy` payload — formatting into JSON happens only at export
+/// time, never on the hot path.
+#[derive(Debug, Clone, Copy)]
+enum TraceEvent {
+    CacheHit { level: CacheLevel, dur: Duration },
+    CacheMiss { level: CacheLevel, dur: Duration },
+    GcStart,
+    GcEnd { dur: Duration },
+    Eviction { level: CacheLevel },
+    Spill { level: CacheLevel, bytes: usize },
+}
+
+impl TraceEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            TraceEvent::CacheHit { .. } => "cache_hit",
+            TraceEvent::CacheMiss { .. } => "cache_miss",
+            TraceEvent::GcStart => "gc_start",
+            TraceEvent::GcEnd { .. } => "gc_end",
+            TraceEvent::Eviction { .. } => "eviction",
+            TraceEvent::Spill { .. } => "spill",
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        match self {
+            TraceEvent::CacheHit { dur, .. }
+            | TraceEvent::CacheMiss { dur, .. }
+            | TraceEvent::GcEnd { dur, .. } => *dur,
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn level(&self) -> Option<CacheLevel> {
+        match self {
+            TraceEvent::CacheHit { level, .. }
+            | TraceEvent::CacheMiss { level, .. }
+            | TraceEvent::Eviction { level }
+            | TraceEvent::Spill { level, .. } => Some(*level),
+            _ => None,
+        }
+    }
+}
+
+/// Output format for `PerformanceMonitor::export_trace`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// One JSON object per line.
+    Ndjson,
+    /// `chrome://tracing` array-of-objects format.
+    ChromeTrace,
+}
+
+/// Serializable row written by `export_trace`; not used on the hot path.
+#[derive(Serialize)]
+struct TraceEventRecord {
+    name: &'static str,
+    ts_micros: u128,
+    dur_micros: u128,
+    level: Option<CacheLevel>,
+    bytes: Option<usize>,
+}
+
+/// Chrome `chrome://tracing` complete-event ("ph":"X") entry.
+#[derive(Serialize)]
+struct ChromeTraceEntry {
+    name: &'static str,
+    ph: &'static str,
+    ts: u128,
+    dur: u128,
+    pid: u32,
+    tid: u32,
+}
+
+/// Ring buffer of timestamped `TraceEvent`s, gated behind `enabled` so the
+/// hot path only pays for an `AtomicBool` load when tracing is off.
+struct TraceRecorder {
+    enabled: std::sync::atomic::AtomicBool,
+    start: Instant,
+    events: Mutex<VecDeque<(Duration, TraceEvent)>>,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl TraceRecorder {
+    fn new(capacity: usize) -> Self {
+        Self {
+            enabled: std::sync::atomic::AtomicBool::new(false),
+            start: Instant::now(),
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn set_enabled(&self, on: bool) {
+        self.enabled.store(on, Ordering::Relaxed);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Hit-rate aggregate kept as running atomics rather than recomputed by
+    /// scanning the ring buffer, so it stays cheap even with tracing off.
+    fn hit_rate(&self) -> f32 {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        if total == 0 {
+            0.0
+        } else {
+            hits as f32 / total as f32
+        }
+    }
+
+    fn record(&self, event: TraceEvent) {
+        match &event {
+            TraceEvent::CacheHit { .. } => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+            }
+            TraceEvent::CacheMiss { .. } => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+
+        if !self.is_enabled() {
+            return;
+        }
+        let ts = self.start.elapsed();
+        let mut events = self.events.lock().unwrap();
+        if events.len() >= self.capacity {
+            events.pop_front();
+        }
+        events.push_back((ts, event));
+    }
+
+    fn export(&self, path: &std::path::Path, format: TraceFormat) -> Result<()> {
+        let events = self.events.lock().unwrap();
+        match format {
+            TraceFormat::Ndjson => {
+                let mut out = String::new();
+                for (ts, event) in events.iter() {
+                    let record = TraceEventRecord {
+                        name: event.name(),
+                        ts_micros: ts.as_micros(),
+                        dur_micros: event.duration().as_micros(),
+                        level: event.level(),
+                        bytes: match event {
+                            TraceEvent::Spill { bytes, .. } => Some(*bytes),
+                            _ => None,
+                        },
+                    };
+                    out.push_str(&serde_json::to_string(&record)?);
+                    out.push('\n');
+                }
+                fs::write(path, out)?;
+            }
+            TraceFormat::ChromeTrace => {
+                let entries: Vec<ChromeTraceEntry> = events
+                    .iter()
+                    .map(|(ts, event)| ChromeTraceEntry {
+                        name: event.name(),
+                        ph: "X",
+                        ts: ts.as_micros(),
+                        dur: event.duration().as_micros().max(1),
+                        pid: 0,
+                        tid: 0,
+                    })
+                    .collect();
+                fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -179,16 +808,17 @@ enum Priority {
 
 impl MemoryOptimizer {
     pub fn new(config: MemoryConfig) -> Self {
-        let cache_manager = Arc::new(CacheManager::new(config.clone()));
         let memory_pool = Arc::new(MemoryPool::new(config.clone()));
-        let gc_scheduler = Arc::new(GarbageCollector::new(config.clone()));
         let performance_monitor = Arc::new(PerformanceMonitor::new());
+        let cache_manager = Arc::new(CacheManager::new(config.clone(), memory_pool.clone(), performance_monitor.clone()));
+        let gc_scheduler = Arc::new(GarbageCollector::new(config.clone(), performance_monitor.clone()));
 
         Self {
             cache_manager,
             memory_pool,
             gc_scheduler,
             performance_monitor,
+            background_tasks: Mutex::new(Vec::new()),
             config,
         }
     }
@@ -197,21 +827,31 @@ impl MemoryOptimizer {
     pub async fn initialize(&self) -> Result<()> {
         debug!("ðŸš€ Initializing memory optimizer...");
 
-        // Preload frequently accessed data if enabled
+        // Preload frequently accessed data in the background if enabled, so
+        // initialization doesn't block request-serving on a cold cache.
         if self.config.preload_enabled {
-            self.preload_critical_data().await?;
+            self.spawn_preload();
         }
 
         // Start background garbage collection
         self.start_gc_scheduler().await?;
 
         // Initialize performance monitoring
-        self.performance_monitor.start_monitoring().await?;
+        self.start_performance_monitoring().await?;
 
         debug!("âœ… Memory optimizer initialized");
         Ok(())
     }
 
+    /// Joins every background task started by `initialize`. Safe to call
+    /// more than once; a second call just finds nothing left to abort.
+    pub async fn shutdown(&self) {
+        let handles: Vec<_> = self.background_tasks.lock().unwrap().drain(..).collect();
+        for handle in handles {
+            handle.abort();
+        }
+    }
+
     /// Get a node from cache or create/load it
     pub async fn get_node(&self, uuid: Uuid) -> Result<Option<KGNode>> {
         let start = Instant::now();
@@ -258,13 +898,56 @@ impl MemoryOptimizer {
         self.cache_manager.get_search_results(query).await
     }
 
-    /// Cache search results
-    pub async fn cache_search_results(&self, query: String, results: Vec<(KGNode, f32)>, episodes: Vec<(Episode, f32)>) -> Result<()> {
+    /// Looks up `query` exactly first; on a miss, and if `query_embedding` is
+    /// given, re-ranks against the coldest-to-warmest cached entries that
+    /// embedded close enough to be a usable stand-in (cosine similarity at
+    /// or above `threshold`). Lets a near-duplicate query reuse a cached
+    /// result set instead of falling through to storage.
+    pub async fn get_cached_search_similar(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        threshold: f32,
+    ) -> Result<Option<SearchCacheEntry>> {
+        let start = Instant::now();
+        if let Some(entry) = self.cache_manager.get_search_results(query).await? {
+            self.performance_monitor.record_cache_hit("query", start.elapsed()).await;
+            return Ok(Some(entry));
+        }
+
+        let Some(embedding) = query_embedding else {
+            self.performance_monitor.record_cache_miss("query", start.elapsed()).await;
+            return Ok(None);
+        };
+
+        match self.cache_manager.find_similar_search(embedding, threshold) {
+            Some(entry) => {
+                self.performance_monitor.record_cache_hit("query", start.elapsed()).await;
+                Ok(Some(entry))
+            }
+            None => {
+                self.performance_monitor.record_cache_miss("query", start.elapsed()).await;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Cache search results, optionally tagging the entry with the query
+    /// embedding so a later near-duplicate query can find it via
+    /// `get_cached_search_similar`.
+    pub async fn cache_search_results(
+        &self,
+        query: String,
+        results: Vec<(KGNode, f32)>,
+        episodes: Vec<(Episode, f32)>,
+        query_embedding: Option<Vec<f32>>,
+    ) -> Result<()> {
         let entry = SearchCacheEntry {
             results,
             episodes,
             timestamp: SystemTime::now(),
             query_hash: self.hash_query(&query),
+            query_embedding,
         };
         self.cache_manager.put_search_results(query, entry).await?;
         Ok(())
@@ -371,15 +1054,79 @@ impl MemoryOptimizer {
 
     // Private helper methods
 
-    async fn preload_critical_data(&self) -> Result<()> {
-        debug!("ðŸ“š Preloading critical data...");
-        // Placeholder for preloading frequently accessed nodes, embeddings, etc.
-        Ok(())
+    /// Spawns preloading of previously-spilled episodes/search results as a
+    /// background task so `initialize` returns immediately. L1/embedding
+    /// never spill (see `Spillable`), so there's nothing durable to warm
+    /// them from; L3/query do, and rehydrating their last-spilled entries
+    /// here means a freshly restarted server doesn't start every one of
+    /// those lookups as a cold miss.
+    fn spawn_preload(&self) {
+        let cache_manager = self.cache_manager.clone();
+        let handle = tokio::spawn(async move {
+            debug!("ðŸ“š Preloading previously spilled cache entries...");
+            let (episodes, queries) = cache_manager.preload_spilled().await;
+            debug!("ðŸ“š Preload warmed {} episodes and {} query results from disk", episodes, queries);
+        });
+        self.background_tasks.lock().unwrap().push(handle);
     }
 
+    /// Drives garbage collection on the `gc_interval` timer, but also polls
+    /// more frequently so a GC pass can be triggered early whenever the
+    /// monitor reports memory pressure at or above `gc_threshold`. Both this
+    /// loop and `force_gc` route through `GarbageCollector::collect`, which
+    /// serializes the actual sweep so the two paths can't run concurrently
+    /// and double-count `total_memory_freed`.
     async fn start_gc_scheduler(&self) -> Result<()> {
-        // Start background garbage collection scheduling
-        // In a real implementation, this would spawn a background task
+        let gc = self.gc_scheduler.clone();
+        let monitor = self.performance_monitor.clone();
+        let gc_interval = self.config.gc_interval;
+        let gc_threshold = self.config.gc_threshold;
+        let poll_interval = Duration::from_secs(5).min(gc_interval);
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(poll_interval);
+            let mut last_run = Instant::now();
+            loop {
+                ticker.tick().await;
+
+                let due = last_run.elapsed() >= gc_interval;
+                let under_pressure = monitor.memory_pressure().await >= gc_threshold;
+                if !due && !under_pressure {
+                    continue;
+                }
+                if under_pressure && !due {
+                    debug!("Triggering GC early: memory pressure at/above {:.0}% threshold", gc_threshold * 100.0);
+                }
+
+                if let Err(e) = gc.collect().await {
+                    tracing::error!("Background GC pass failed: {}", e);
+                }
+                last_run = Instant::now();
+            }
+        });
+
+        self.background_tasks.lock().unwrap().push(handle);
+        Ok(())
+    }
+
+    /// Spawns the periodic memory-snapshot/optimization-suggestion monitor.
+    async fn start_performance_monitoring(&self) -> Result<()> {
+        let monitor = self.performance_monitor.clone();
+        let cache_manager = self.cache_manager.clone();
+        let memory_pool = self.memory_pool.clone();
+        let gc_threshold = self.config.gc_threshold;
+
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(30));
+            loop {
+                ticker.tick().await;
+                monitor
+                    .record_snapshot(&cache_manager, &memory_pool, gc_threshold)
+                    .await;
+            }
+        });
+
+        self.background_tasks.lock().unwrap().push(handle);
         Ok(())
     }
 
@@ -420,44 +1167,34 @@ impl MemoryOptimizer {
 // Implementation for individual components
 
 impl CacheManager {
-    fn new(config: MemoryConfig) -> Self {
+    fn new(config: MemoryConfig, memory_pool: Arc<MemoryPool>, performance_monitor: Arc<PerformanceMonitor>) -> Self {
         Self {
-            l1_cache: Arc::new(RwLock::new(LruCache::new(config.max_cache_size / 4))),
-            l2_cache: Arc::new(RwLock::new(LruCache::new(config.max_cache_size / 4))),
-            l3_cache: Arc::new(RwLock::new(LruCache::new(config.max_cache_size / 4))),
-            embedding_cache: Arc::new(RwLock::new(LruCache::new(config.max_cache_size / 4))),
-            query_cache: Arc::new(RwLock::new(LruCache::new(1000))),
+            l1_cache: ShardedCache::new(config.max_cache_size / 4),
+            l2_cache: ShardedCache::new(config.max_cache_size / 4),
+            l3_cache: ShardedCache::new(config.max_cache_size / 4),
+            embedding_cache: ShardedCache::new(config.max_cache_size / 4),
+            query_cache: ShardedCache::new(1000),
             statistics: Arc::new(Mutex::new(CacheStatistics::default())),
+            l1_reservation: Reservation::default(),
+            embedding_reservation: Reservation::default(),
+            l3_reservation: Reservation::default(),
+            query_reservation: Reservation::default(),
+            l3_spilled: Mutex::new(HashSet::new()),
+            query_spilled: Mutex::new(HashSet::new()),
+            spill_store: Arc::new(SpillStore::new(config.spill_dir.clone())),
+            memory_pool,
+            performance_monitor,
             config,
         }
     }
 
     async fn get_node(&self, uuid: Uuid) -> Result<Option<KGNode>> {
-        let cache = match self.l1_cache.read() {
-            Ok(cache) => cache,
-            Err(e) => {
-                tracing::error!("Failed to acquire L1 cache read lock: {}", e);
-                return Ok(None);
-            }
-        };
-        
-        if let Some(cached_item) = cache.get(&uuid) {
+        let shard = shard_index(uuid.as_bytes(), self.l1_cache.shards.len());
+        if let Some(cached_item) = self.l1_cache.get(shard, &uuid) {
             if cached_item.created_at.elapsed() < self.config.cache_ttl {
-                let mut stats = match self.statistics.lock() {
-                    Ok(stats) => stats,
-                    Err(e) => {
-                        tracing::warn!("Failed to update cache statistics: {}", e);
-                        return Ok(Some(cached_item.data.clone()));
-                    }
-                };
-                stats.l1_hits += 1;
-                return Ok(Some(cached_item.data.clone()));
+                return Ok(Some(cached_item.data));
             }
         }
-        
-        if let Ok(mut stats) = self.statistics.lock() {
-            stats.l1_misses += 1;
-        }
         Ok(None)
     }
 
@@ -471,39 +1208,32 @@ impl CacheManager {
             size_bytes: size,
         };
 
-        match self.l1_cache.write() {
-            Ok(mut cache) => {
-                cache.put(uuid, cached_item);
-                Ok(())
-            },
-            Err(e) => {
-                tracing::error!("Failed to acquire L1 cache write lock: {}", e);
-                Err(anyhow::anyhow!("Cache write failed: {}", e))
-            }
+        let l3_adapter = self.l3_spill_adapter();
+        let query_adapter = self.query_spill_adapter();
+        if let Err(e) = self.memory_pool.try_grow(
+            &self.l1_reservation,
+            size,
+            &[&l3_adapter as &dyn Spillable, &query_adapter as &dyn Spillable],
+        ) {
+            tracing::warn!("Dropping node cache entry under memory pressure: {}", e);
+            return Ok(());
+        }
+
+        let shard = shard_index(uuid.as_bytes(), self.l1_cache.shards.len());
+        if let Some((_, evicted)) = self.l1_cache.put(shard, uuid, cached_item)? {
+            self.memory_pool.release(&self.l1_reservation, evicted.size_bytes);
+            self.performance_monitor.record_event(TraceEvent::Eviction { level: CacheLevel::L1 });
         }
+        Ok(())
     }
 
     async fn get_embedding(&self, text: &str) -> Result<Option<Vec<f32>>> {
-        let cache = match self.embedding_cache.read() {
-            Ok(cache) => cache,
-            Err(e) => {
-                tracing::error!("Failed to acquire embedding cache read lock: {}", e);
-                return Ok(None);
-            }
-        };
-        
-        if let Some(cached_item) = cache.get(&text.to_string()) {
+        let shard = shard_index(text.as_bytes(), self.embedding_cache.shards.len());
+        if let Some(cached_item) = self.embedding_cache.get(shard, &text.to_string()) {
             if cached_item.created_at.elapsed() < self.config.cache_ttl {
-                if let Ok(mut stats) = self.statistics.lock() {
-                    stats.embedding_hits += 1;
-                }
-                return Ok(Some(cached_item.data.clone()));
+                return Ok(Some(cached_item.data));
             }
         }
-        
-        if let Ok(mut stats) = self.statistics.lock() {
-            stats.embedding_misses += 1;
-        }
         Ok(None)
     }
 
@@ -517,31 +1247,34 @@ impl CacheManager {
             size_bytes: size,
         };
 
-        match self.embedding_cache.write() {
-            Ok(mut cache) => {
-                cache.put(text, cached_item);
-                Ok(())
-            },
-            Err(e) => {
-                tracing::error!("Failed to acquire embedding cache write lock: {}", e);
-                Err(anyhow::anyhow!("Embedding cache write failed: {}", e))
-            }
+        let l3_adapter = self.l3_spill_adapter();
+        let query_adapter = self.query_spill_adapter();
+        if let Err(e) = self.memory_pool.try_grow(
+            &self.embedding_reservation,
+            size,
+            &[&l3_adapter as &dyn Spillable, &query_adapter as &dyn Spillable],
+        ) {
+            tracing::warn!("Dropping embedding cache entry under memory pressure: {}", e);
+            return Ok(());
         }
+
+        let shard = shard_index(text.as_bytes(), self.embedding_cache.shards.len());
+        if let Some((_, evicted)) = self.embedding_cache.put(shard, text, cached_item)? {
+            self.memory_pool.release(&self.embedding_reservation, evicted.size_bytes);
+            self.performance_monitor.record_event(TraceEvent::Eviction { level: CacheLevel::Embedding });
+        }
+        Ok(())
     }
 
     async fn get_search_results(&self, query: &str) -> Result<Option<SearchCacheEntry>> {
-        let cache = self.query_cache.read().unwrap();
-        if let Some(cached_item) = cache.get(&query.to_string()) {
+        let key = query.to_string();
+        let shard = shard_index(key.as_bytes(), self.query_cache.shards.len());
+        if let Some(cached_item) = self.query_cache.get(shard, &key) {
             if cached_item.created_at.elapsed() < self.config.cache_ttl {
-                let mut stats = self.statistics.lock().unwrap();
-                stats.query_hits += 1;
-                return Ok(Some(cached_item.data.clone()));
+                return Ok(Some(cached_item.data));
             }
         }
-        
-        let mut stats = self.statistics.lock().unwrap();
-        stats.query_misses += 1;
-        Ok(None)
+        Ok(self.rehydrate_query(&key))
     }
 
     async fn put_search_results(&self, query: String, entry: SearchCacheEntry) -> Result<()> {
@@ -554,24 +1287,37 @@ impl CacheManager {
             size_bytes: size,
         };
 
-        let mut cache = self.query_cache.write().unwrap();
-        cache.put(query, cached_item);
+        let l3_adapter = self.l3_spill_adapter();
+        let query_adapter = self.query_spill_adapter();
+        if let Err(e) = self.memory_pool.try_grow(
+            &self.query_reservation,
+            size,
+            &[&l3_adapter as &dyn Spillable, &query_adapter as &dyn Spillable],
+        ) {
+            tracing::warn!("Dropping search-result cache entry under memory pressure: {}", e);
+            return Ok(());
+        }
+
+        if self.query_spilled.lock().unwrap().remove(&query) {
+            self.spill_store.write_index("query", &self.query_spilled.lock().unwrap());
+        }
+        let shard = shard_index(query.as_bytes(), self.query_cache.shards.len());
+        if let Some((_, evicted)) = self.query_cache.put(shard, query, cached_item)? {
+            self.memory_pool.release(&self.query_reservation, evicted.size_bytes);
+            self.performance_monitor.record_event(TraceEvent::Eviction { level: CacheLevel::Query });
+        }
         Ok(())
     }
 
     async fn get_episode(&self, uuid: Uuid) -> Result<Option<Episode>> {
-        let cache = self.l3_cache.read().unwrap();
-        if let Some(cached_item) = cache.get(&uuid.to_string()) {
+        let key = uuid.to_string();
+        let shard = shard_index(key.as_bytes(), self.l3_cache.shards.len());
+        if let Some(cached_item) = self.l3_cache.get(shard, &key) {
             if cached_item.created_at.elapsed() < self.config.cache_ttl {
-                let mut stats = self.statistics.lock().unwrap();
-                stats.l3_hits += 1;
-                return Ok(Some(cached_item.data.clone()));
+                return Ok(Some(cached_item.data));
             }
         }
-        
-        let mut stats = self.statistics.lock().unwrap();
-        stats.l3_misses += 1;
-        Ok(None)
+        Ok(self.rehydrate_episode(&key))
     }
 
     async fn put_episode(&self, uuid: Uuid, episode: Episode) -> Result<()> {
@@ -584,13 +1330,171 @@ impl CacheManager {
             size_bytes: size,
         };
 
-        let mut cache = self.l3_cache.write().unwrap();
-        cache.put(uuid.to_string(), cached_item);
+        let l3_adapter = self.l3_spill_adapter();
+        let query_adapter = self.query_spill_adapter();
+        if let Err(e) = self.memory_pool.try_grow(
+            &self.l3_reservation,
+            size,
+            &[&l3_adapter as &dyn Spillable, &query_adapter as &dyn Spillable],
+        ) {
+            tracing::warn!("Dropping episode cache entry under memory pressure: {}", e);
+            return Ok(());
+        }
+
+        let key = uuid.to_string();
+        if self.l3_spilled.lock().unwrap().remove(&key) {
+            self.spill_store.write_index("l3", &self.l3_spilled.lock().unwrap());
+        }
+        let shard = shard_index(key.as_bytes(), self.l3_cache.shards.len());
+        if let Some((_, evicted)) = self.l3_cache.put(shard, key, cached_item)? {
+            self.memory_pool.release(&self.l3_reservation, evicted.size_bytes);
+            self.performance_monitor.record_event(TraceEvent::Eviction { level: CacheLevel::L3 });
+        }
         Ok(())
     }
 
+    fn l3_spill_adapter(&self) -> SpillAdapter<'_, Episode> {
+        SpillAdapter {
+            cache: &self.l3_cache,
+            reservation: &self.l3_reservation,
+            spilled: &self.l3_spilled,
+            spill_store: &self.spill_store,
+            level: CacheLevel::L3,
+            index_name: "l3",
+            performance_monitor: &self.performance_monitor,
+        }
+    }
+
+    fn query_spill_adapter(&self) -> SpillAdapter<'_, SearchCacheEntry> {
+        SpillAdapter {
+            cache: &self.query_cache,
+            reservation: &self.query_reservation,
+            spilled: &self.query_spilled,
+            spill_store: &self.spill_store,
+            level: CacheLevel::Query,
+            index_name: "query",
+            performance_monitor: &self.performance_monitor,
+        }
+    }
+
+    /// Rehydrates every entry that `write_index` recorded as spilled —
+    /// typically from before a process restart — straight back into its
+    /// live cache, so a freshly started server doesn't serve a string of
+    /// cold misses for data that was hot when it last shut down. Returns the
+    /// number of episodes and query results warmed.
+    async fn preload_spilled(&self) -> (usize, usize) {
+        let episodes = self.preload_level(
+            self.spill_store.read_index("l3"),
+            &self.l3_spilled,
+            &self.l3_reservation,
+            &self.l3_cache,
+            CacheLevel::L3,
+        );
+        let queries = self.preload_level(
+            self.spill_store.read_index("query"),
+            &self.query_spilled,
+            &self.query_reservation,
+            &self.query_cache,
+            CacheLevel::Query,
+        );
+        (episodes, queries)
+    }
+
+    fn preload_level<T: Clone + DeserializeOwned>(
+        &self,
+        keys: HashSet<String>,
+        spilled: &Mutex<HashSet<String>>,
+        reservation: &Reservation,
+        cache: &ShardedCache<String, CachedItem<T>>,
+        level: CacheLevel,
+    ) -> usize {
+        let mut warmed = 0;
+        for key in keys {
+            let Some(record) = self.spill_store.read::<SpillRecord<T>>(&key) else {
+                continue;
+            };
+            let size = mem::size_of_val(&record.data);
+            if self.memory_pool.try_grow(reservation, size, &[]).is_err() {
+                continue;
+            }
+            let cached_item = CachedItem {
+                data: record.data,
+                created_at: Instant::now(),
+                last_accessed: Instant::now(),
+                access_count: record.access_count,
+                size_bytes: size,
+            };
+            let shard = shard_index(key.as_bytes(), cache.shards.len());
+            match cache.put(shard, key.clone(), cached_item) {
+                Ok(evicted) => {
+                    if let Some((_, evicted)) = evicted {
+                        self.memory_pool.release(reservation, evicted.size_bytes);
+                    }
+                    spilled.lock().unwrap().remove(&key);
+                    self.performance_monitor.record_event(TraceEvent::CacheHit { level, dur: Duration::ZERO });
+                    warmed += 1;
+                }
+                Err(_) => self.memory_pool.release(reservation, size),
+            }
+        }
+        warmed
+    }
+
+    /// Transparently rehydrates an episode that was spilled to disk under
+    /// memory pressure. Does not re-reserve pool space on read: a spilled
+    /// entry returned here stays off-heap until the next `put_episode`.
+    fn rehydrate_episode(&self, key: &str) -> Option<Episode> {
+        if !self.l3_spilled.lock().unwrap().contains(key) {
+            return None;
+        }
+        self.spill_store.read::<SpillRecord<Episode>>(key).map(|r| r.data)
+    }
+
+    fn rehydrate_query(&self, key: &str) -> Option<SearchCacheEntry> {
+        if !self.query_spilled.lock().unwrap().contains(key) {
+            return None;
+        }
+        self.spill_store.read::<SpillRecord<SearchCacheEntry>>(key).map(|r| r.data)
+    }
+
+    /// Scans the in-memory query cache for the entry whose stored query
+    /// embedding is closest to `embedding`, returning it if that similarity
+    /// clears `threshold`. Only considers entries still resident in memory;
+    /// spilled entries aren't worth the disk read just to probe similarity.
+    fn find_similar_search(&self, embedding: &[f32], threshold: f32) -> Option<SearchCacheEntry> {
+        let candidates: Vec<CachedItem<SearchCacheEntry>> = self.query_cache.snapshot_values();
+        let embeddings: Vec<Vec<f32>> = candidates
+            .iter()
+            .map(|item| item.data.query_embedding.clone().unwrap_or_default())
+            .collect();
+
+        let (index, score) = SimilarityEngine::batch_topk(embedding, &embeddings, 1)
+            .into_iter()
+            .next()?;
+
+        if score < threshold {
+            return None;
+        }
+        Some(candidates[index].data.clone())
+    }
+
+    /// Aggregates the independent per-shard hit/miss counters for every
+    /// cache level into a single snapshot. `total_memory_used` and
+    /// `evictions` still come from the shared `statistics` mutex, which
+    /// other subsystems (the memory pool, GC) also contribute to.
     async fn get_statistics(&self) -> Result<CacheStatistics> {
-        Ok(self.statistics.lock().unwrap().clone())
+        let mut stats = self.statistics.lock().unwrap().clone();
+        stats.l1_hits = self.l1_cache.hits();
+        stats.l1_misses = self.l1_cache.misses();
+        stats.l2_hits = self.l2_cache.hits();
+        stats.l2_misses = self.l2_cache.misses();
+        stats.l3_hits = self.l3_cache.hits();
+        stats.l3_misses = self.l3_cache.misses();
+        stats.embedding_hits = self.embedding_cache.hits();
+        stats.embedding_misses = self.embedding_cache.misses();
+        stats.query_hits = self.query_cache.hits();
+        stats.query_misses = self.query_cache.misses();
+        Ok(stats)
     }
 
     async fn get_memory_usage(&self) -> Result<usize> {
@@ -601,6 +1505,7 @@ impl CacheManager {
 
 impl MemoryPool {
     fn new(config: MemoryConfig) -> Self {
+        let pool_size = (config.max_memory_bytes as f32 * config.gc_threshold) as usize;
         Self {
             node_pool: Arc::new(Mutex::new(VecDeque::with_capacity(config.memory_pool_size / 4))),
             edge_pool: Arc::new(Mutex::new(VecDeque::with_capacity(config.memory_pool_size / 4))),
@@ -608,10 +1513,58 @@ impl MemoryPool {
             vector_pool: Arc::new(Mutex::new(VecDeque::with_capacity(config.memory_pool_size / 4))),
             string_pool: Arc::new(Mutex::new(VecDeque::with_capacity(1000))),
             allocation_stats: Arc::new(Mutex::new(AllocationStats::default())),
+            reserved: AtomicUsize::new(0),
+            pool_size,
             config,
         }
     }
 
+    /// Attempts to reserve `bytes` for `consumer`. Grows directly if the pool
+    /// has headroom under `pool_size`; otherwise spills from `spillable`
+    /// (largest-first) and retries until it fits or nothing is left to spill.
+    fn try_grow(
+        &self,
+        consumer: &Reservation,
+        bytes: usize,
+        spillable: &[&dyn Spillable],
+    ) -> std::result::Result<(), TryGrowError> {
+        loop {
+            let reserved = self.reserved.load(Ordering::Relaxed);
+            if reserved + bytes <= self.pool_size {
+                self.reserved.fetch_add(bytes, Ordering::Relaxed);
+                consumer.grow(bytes);
+                return Ok(());
+            }
+
+            let victim = spillable.iter().max_by_key(|s| s.spillable_bytes());
+            let freed = match victim {
+                Some(v) if v.spillable_bytes() > 0 => v.spill_coldest(bytes),
+                _ => 0,
+            };
+
+            if freed == 0 {
+                return Err(TryGrowError::NothingSpillable {
+                    requested: bytes,
+                    reserved,
+                    pool_size: self.pool_size,
+                });
+            }
+            self.reserved.fetch_sub(freed.min(reserved), Ordering::Relaxed);
+        }
+    }
+
+    /// Releases `bytes` previously reserved by `consumer` back to the pool.
+    fn release(&self, consumer: &Reservation, bytes: usize) {
+        consumer.shrink(bytes);
+        self.reserved
+            .fetch_sub(bytes.min(self.reserved.load(Ordering::Relaxed)), Ordering::Relaxed);
+    }
+
+    /// Fraction of `pool_size` currently reserved; feeds `MemorySnapshot::fragmentation`.
+    fn reserved_ratio(&self) -> f32 {
+        self.reserved.load(Ordering::Relaxed) as f32 / self.pool_size.max(1) as f32
+    }
+
     async fn get_node(&self) -> Result<KGNode> {
         let mut pool = self.node_pool.lock().unwrap();
         if let Some(mut node) = pool.pop_front() {
@@ -660,16 +1613,23 @@ impl MemoryPool {
 }
 
 impl GarbageCollector {
-    fn new(config: MemoryConfig) -> Self {
+    fn new(config: MemoryConfig, performance_monitor: Arc<PerformanceMonitor>) -> Self {
         Self {
             last_gc: Arc::new(Mutex::new(Instant::now())),
             memory_usage: Arc::new(Mutex::new(BTreeMap::new())),
             gc_stats: Arc::new(Mutex::new(GcStatistics::default())),
+            sweep_lock: tokio::sync::Mutex::new(()),
+            performance_monitor,
             config,
         }
     }
 
+    /// Runs a GC sweep. Both the background scheduler and `force_gc` call
+    /// this, so it takes `sweep_lock` first — an overlapping call simply
+    /// waits its turn rather than running a second concurrent sweep.
     async fn collect(&self) -> Result<GcResult> {
+        let _guard = self.sweep_lock.lock().await;
+        self.performance_monitor.record_event(TraceEvent::GcStart);
         let start = Instant::now();
         debug!("ðŸ—‘ï¸  Starting garbage collection...");
 
@@ -687,6 +1647,7 @@ impl GarbageCollector {
         );
 
         debug!("âœ… Garbage collection completed in {:?}", collection_time);
+        self.performance_monitor.record_event(TraceEvent::GcEnd { dur: collection_time });
         Ok(GcResult {
             memory_freed,
             collection_time,
@@ -705,20 +1666,102 @@ impl PerformanceMonitor {
             metrics: Arc::new(Mutex::new(PerformanceMetrics::default())),
             memory_snapshots: Arc::new(Mutex::new(VecDeque::with_capacity(1000))),
             optimization_suggestions: Arc::new(Mutex::new(Vec::new())),
+            trace: TraceRecorder::new(10_000),
         }
     }
 
-    async fn start_monitoring(&self) -> Result<()> {
-        // Start background monitoring tasks
-        Ok(())
+    /// Enables or disables event recording. Cheap to toggle at runtime;
+    /// hit/miss counters are tracked regardless, only the ring buffer (and
+    /// therefore `export_trace`'s output) depends on this.
+    pub fn set_trace_enabled(&self, enabled: bool) {
+        self.trace.set_enabled(enabled);
+    }
+
+    /// Writes the recorded event stream to `path` in the given format.
+    pub async fn export_trace(&self, path: &std::path::Path, format: TraceFormat) -> Result<()> {
+        self.trace.export(path, format)
     }
 
-    async fn record_cache_hit(&self, _cache_type: &str, _duration: Duration) {
-        // Record cache hit metrics
+    /// Takes a memory snapshot and, if usage is at/above `gc_threshold`,
+    /// records an optimization suggestion. Called periodically by
+    /// `MemoryOptimizer::start_performance_monitoring`.
+    async fn record_snapshot(&self, cache_manager: &CacheManager, memory_pool: &MemoryPool, gc_threshold: f32) {
+        let cache_memory = cache_manager.get_memory_usage().await.unwrap_or(0);
+        let pool_memory = memory_pool.get_memory_usage().await.unwrap_or(0);
+        let fragmentation = memory_pool.reserved_ratio();
+
+        let snapshot = MemorySnapshot {
+            timestamp: Instant::now(),
+            total_memory: cache_memory + pool_memory,
+            cache_memory,
+            pool_memory,
+            fragmentation,
+        };
+
+        let mut snapshots = self.memory_snapshots.lock().unwrap();
+        if snapshots.len() >= snapshots.capacity() {
+            snapshots.pop_front();
+        }
+        snapshots.push_back(snapshot);
+        drop(snapshots);
+
+        if fragmentation >= gc_threshold {
+            self.optimization_suggestions.lock().unwrap().push(OptimizationSuggestion {
+                suggestion_type: OptimizationType::GcTuning,
+                description: format!("Reserved memory at {:.0}% of the pool budget", fragmentation * 100.0),
+                potential_savings: 0,
+                priority: Priority::High,
+                timestamp: Instant::now(),
+            });
+        }
     }
 
-    async fn record_cache_miss(&self, _cache_type: &str, _duration: Duration) {
-        // Record cache miss metrics
+    /// Fraction of the pool's byte budget currently reserved, per the most
+    /// recent snapshot. Used by the GC scheduler to trigger early.
+    async fn memory_pressure(&self) -> f32 {
+        self.memory_snapshots
+            .lock()
+            .unwrap()
+            .back()
+            .map(|s| s.fragmentation)
+            .unwrap_or(0.0)
+    }
+
+    async fn record_cache_hit(&self, cache_type: &str, duration: Duration) {
+        self.trace.record(TraceEvent::CacheHit {
+            level: CacheLevel::from_tag(cache_type),
+            dur: duration,
+        });
+        self.record_response_time(duration);
+    }
+
+    async fn record_cache_miss(&self, cache_type: &str, duration: Duration) {
+        self.trace.record(TraceEvent::CacheMiss {
+            level: CacheLevel::from_tag(cache_type),
+            dur: duration,
+        });
+        self.record_response_time(duration);
+    }
+
+    /// Backs `PerformanceMetrics::query_response_times`/`cache_hit_rates`
+    /// with aggregates computed from the trace stream's running counters.
+    fn record_response_time(&self, duration: Duration) {
+        let mut metrics = self.metrics.lock().unwrap();
+        if metrics.query_response_times.len() >= 1000 {
+            metrics.query_response_times.pop_front();
+        }
+        metrics.query_response_times.push_back(duration);
+
+        if metrics.cache_hit_rates.len() >= 1000 {
+            metrics.cache_hit_rates.pop_front();
+        }
+        metrics.cache_hit_rates.push_back(self.trace.hit_rate());
+    }
+
+    /// Records a `TraceEvent` that isn't a cache hit/miss (GC, eviction,
+    /// spill) on behalf of another subsystem.
+    fn record_event(&self, event: TraceEvent) {
+        self.trace.record(event);
     }
 
     async fn get_statistics(&self) -> Result<PerformanceMetrics> {
@@ -750,15 +1793,27 @@ impl<K: Clone + Eq + std::hash::Hash, V> LruCache<K, V> {
         self.map.get(key)
     }
 
-    fn put(&mut self, key: K, value: V) {
-        if self.map.len() >= self.capacity {
-            if let Some(oldest) = self.order.pop_front() {
-                self.map.remove(&oldest);
-            }
-        }
-        
+    /// Inserts `key`/`value`, evicting the coldest entry first if the cache
+    /// is at capacity. Returns the evicted entry, if any, so callers can keep
+    /// their own byte accounting (e.g. `Reservation`) in sync.
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        let evicted = if self.map.len() >= self.capacity {
+            self.pop_oldest()
+        } else {
+            None
+        };
+
         self.map.insert(key.clone(), value);
         self.order.push_back(key);
+        evicted
+    }
+
+    /// Removes and returns the coldest (oldest-inserted) entry, used by
+    /// `Spillable::spill_coldest` to free bytes under memory pressure.
+    fn pop_oldest(&mut self) -> Option<(K, V)> {
+        let key = self.order.pop_front()?;
+        let value = self.map.remove(&key)?;
+        Some((key, value))
     }
 }
 
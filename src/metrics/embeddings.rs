@@ -0,0 +1,142 @@
+//! Pluggable observability backend for [`crate::embeddings::LocalEmbeddingEngine`].
+//!
+//! [`EmbeddingMetricsExporter`] is the seam that lets encode latency, cache
+//! effectiveness, and model-load timing ship to an OTLP collector instead of
+//! only ever reaching `debug!` logging in `print_stats`, without the engine
+//! needing to know which backend is active — the same shape as
+//! [`super::extraction::ExtractionMetricsExporter`]. `NoopEmbeddingMetrics`
+//! is the default so a caller that never configures an exporter pays no cost
+//! and sees no behavior change.
+//!
+//! This complements, rather than replaces, the synchronous point-in-time
+//! snapshot `LocalEmbeddingEngine::metrics_handle` returns for the `mcp`
+//! server's `/metrics` admin endpoint: that one answers "what's the cache
+//! size right now", this one answers "how has encode latency trended" —
+//! a question only a real metrics backend with a time dimension can answer.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Counters/histograms `LocalEmbeddingEngine` reports on, implemented by
+/// whichever backend `EmbeddingMetricsExporterKind` selects.
+pub trait EmbeddingMetricsExporter: Send + Sync {
+    /// One `encode_texts` call finished: `batch_size` texts took `latency`.
+    fn record_encode_batch(&self, batch_size: usize, latency: Duration);
+
+    /// A cache lookup in `tier` (`"batch_memory"`, `"batch_persistent"`, or
+    /// `"onnx"` — see `BatchProcessor::check_cache` and
+    /// `OnnxEmbeddingEngine::encode_batch`) resolved to a hit or a miss.
+    fn record_cache_access(&self, tier: &str, hit: bool);
+
+    /// `LocalEmbeddingEngine::initialize` finished loading a model in `latency`.
+    fn record_model_load(&self, latency: Duration);
+
+    /// The currently loaded model's embedding width, reported as a gauge so
+    /// a dashboard can flag an unexpected dimensionality change across a
+    /// model swap without grepping logs.
+    fn set_dimensions(&self, dimensions: u64);
+}
+
+/// Discards every metric. The default exporter, so embedding generation has
+/// zero observability overhead until an operator opts into one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopEmbeddingMetrics;
+
+impl EmbeddingMetricsExporter for NoopEmbeddingMetrics {
+    fn record_encode_batch(&self, _batch_size: usize, _latency: Duration) {}
+    fn record_cache_access(&self, _tier: &str, _hit: bool) {}
+    fn record_model_load(&self, _latency: Duration) {}
+    fn set_dimensions(&self, _dimensions: u64) {}
+}
+
+/// Ships embedding metrics via an OTLP meter: an `embedding_encode_latency_ms`
+/// histogram, an `embedding_batch_size` histogram, `embedding_cache_hits_total`/
+/// `embedding_cache_misses_total` counters labeled by `tier`, an
+/// `embedding_model_load_ms` histogram, and an `embedding_model_dimensions` gauge.
+pub struct OtlpEmbeddingMetrics {
+    encode_latency_ms: opentelemetry::metrics::Histogram<f64>,
+    batch_size: opentelemetry::metrics::Histogram<u64>,
+    cache_hits_total: opentelemetry::metrics::Counter<u64>,
+    cache_misses_total: opentelemetry::metrics::Counter<u64>,
+    model_load_ms: opentelemetry::metrics::Histogram<f64>,
+    model_dimensions: opentelemetry::metrics::Gauge<u64>,
+}
+
+impl OtlpEmbeddingMetrics {
+    /// Builds the exporter from the global OTLP meter registered for
+    /// `instrumentation_name` (set up via the process's OTLP pipeline
+    /// initialization, outside this module's concern).
+    pub fn new(instrumentation_name: &'static str) -> Self {
+        let meter = opentelemetry::global::meter(instrumentation_name);
+        Self {
+            encode_latency_ms: meter
+                .f64_histogram("embedding_encode_latency_ms")
+                .with_description("Latency of encode_texts calls")
+                .init(),
+            batch_size: meter
+                .u64_histogram("embedding_batch_size")
+                .with_description("Number of texts per encode_texts call")
+                .init(),
+            cache_hits_total: meter
+                .u64_counter("embedding_cache_hits_total")
+                .with_description("Embedding cache lookups that hit, labeled by tier")
+                .init(),
+            cache_misses_total: meter
+                .u64_counter("embedding_cache_misses_total")
+                .with_description("Embedding cache lookups that missed, labeled by tier")
+                .init(),
+            model_load_ms: meter
+                .f64_histogram("embedding_model_load_ms")
+                .with_description("Time to load and initialize an embedding model")
+                .init(),
+            model_dimensions: meter
+                .u64_gauge("embedding_model_dimensions")
+                .with_description("Embedding width of the currently loaded model")
+                .init(),
+        }
+    }
+}
+
+impl EmbeddingMetricsExporter for OtlpEmbeddingMetrics {
+    fn record_encode_batch(&self, batch_size: usize, latency: Duration) {
+        self.encode_latency_ms.record(latency.as_secs_f64() * 1000.0, &[]);
+        self.batch_size.record(batch_size as u64, &[]);
+    }
+
+    fn record_cache_access(&self, tier: &str, hit: bool) {
+        let attrs = [opentelemetry::KeyValue::new("tier", tier.to_string())];
+        if hit {
+            self.cache_hits_total.add(1, &attrs);
+        } else {
+            self.cache_misses_total.add(1, &attrs);
+        }
+    }
+
+    fn record_model_load(&self, latency: Duration) {
+        self.model_load_ms.record(latency.as_secs_f64() * 1000.0, &[]);
+    }
+
+    fn set_dimensions(&self, dimensions: u64) {
+        self.model_dimensions.record(dimensions, &[]);
+    }
+}
+
+/// Which `EmbeddingMetricsExporter` backend `LocalEmbeddingEngine` should
+/// report through.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingMetricsExporterKind {
+    /// No observability backend configured; metrics are discarded.
+    #[default]
+    Noop,
+    /// Report via the process's global OTLP meter provider.
+    Otlp,
+}
+
+/// Builds the exporter `kind` selects.
+pub fn build_embedding_metrics_exporter(kind: EmbeddingMetricsExporterKind) -> Arc<dyn EmbeddingMetricsExporter> {
+    match kind {
+        EmbeddingMetricsExporterKind::Noop => Arc::new(NoopEmbeddingMetrics),
+        EmbeddingMetricsExporterKind::Otlp => Arc::new(OtlpEmbeddingMetrics::new("kg_mcp_server::embeddings")),
+    }
+}
@@ -0,0 +1,196 @@
+//! Bounded, lock-free-to-read recent-events buffer backing the tray app's
+//! "Recent Events" submenu and the `get_recent_events` MCP tool, so both can
+//! surface the last N structured log/error events without tailing a log
+//! file from disk.
+//!
+//! [`RecentEventsLayer`] is a [`tracing_subscriber::Layer`] that turns each
+//! event into an [`EventRecord`] and pushes it into a [`RecentEventsBuffer`].
+//! `tracing::Event`s can arrive from any thread, so the producer/consumer
+//! halves of the underlying [`rtrb::RingBuffer`] are each held behind a
+//! short-lived `Mutex` rather than owned by a single caller outright — the
+//! wait-free guarantee the request asks for is on the read side, which is
+//! what `mcp::errors::ErrorHandler` and readers on the hot query path (the
+//! tray submenu, the MCP tool) actually need: `RecentEventsBuffer::snapshot`
+//! only ever does an `ArcSwap::load_full`, never a lock.
+
+use std::sync::{Arc, Mutex};
+
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Snapshot of the ring buffer's capacity used when a caller doesn't
+/// construct a `RecentEventsBuffer` with an explicit size.
+pub const DEFAULT_RECENT_EVENTS_CAPACITY: usize = 200;
+
+/// One tracing event or `McpErrorResponse`, serialized into a fixed shape
+/// cheap enough to copy into the ring buffer on the hot path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub level: String,
+    pub timestamp: DateTime<Utc>,
+    pub target: String,
+    pub message: String,
+    /// Set when this record came from `ErrorHandler::handle_error`, so a
+    /// reader can correlate it with the same id a client saw in the MCP
+    /// error response's debug data.
+    pub error_id: Option<String>,
+}
+
+/// Bounded SPSC ring buffer (drop-oldest on overflow) whose consumer side
+/// drains into an `ArcSwap` snapshot, so reads never block on a producer.
+pub struct RecentEventsBuffer {
+    producer: Mutex<rtrb::Producer<EventRecord>>,
+    consumer: Mutex<rtrb::Consumer<EventRecord>>,
+    snapshot: ArcSwap<Vec<EventRecord>>,
+    capacity: usize,
+}
+
+impl RecentEventsBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let (producer, consumer) = rtrb::RingBuffer::new(capacity.max(1));
+        Self {
+            producer: Mutex::new(producer),
+            consumer: Mutex::new(consumer),
+            snapshot: ArcSwap::from_pointee(Vec::new()),
+            capacity,
+        }
+    }
+
+    /// Pushes `record`, dropping the oldest queued-but-undrained record
+    /// instead of blocking if the ring is momentarily full, then drains
+    /// into the snapshot so `snapshot()` sees it immediately.
+    pub fn push(&self, record: EventRecord) {
+        let mut producer = self.producer.lock().unwrap();
+        if producer.is_full() {
+            drop(producer);
+            if let Ok(mut consumer) = self.consumer.lock() {
+                let _ = consumer.pop();
+            }
+            producer = self.producer.lock().unwrap();
+        }
+        let _ = producer.push(record);
+        drop(producer);
+        self.drain();
+    }
+
+    /// Drains whatever the producer side has queued into the `ArcSwap`
+    /// snapshot, trimming the front so it never holds more than `capacity`
+    /// records (drop-oldest for the retained snapshot itself, not just the
+    /// ring).
+    fn drain(&self) {
+        let mut consumer = self.consumer.lock().unwrap();
+        let mut drained = Vec::new();
+        while let Ok(record) = consumer.pop() {
+            drained.push(record);
+        }
+        drop(consumer);
+        if drained.is_empty() {
+            return;
+        }
+
+        let mut next = (**self.snapshot.load()).clone();
+        next.extend(drained);
+        let overflow = next.len().saturating_sub(self.capacity);
+        if overflow > 0 {
+            next.drain(0..overflow);
+        }
+        self.snapshot.store(Arc::new(next));
+    }
+
+    /// Current snapshot, newest last. Lock-free: just an `ArcSwap::load_full`.
+    pub fn snapshot(&self) -> Arc<Vec<EventRecord>> {
+        self.snapshot.load_full()
+    }
+}
+
+impl Default for RecentEventsBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_EVENTS_CAPACITY)
+    }
+}
+
+/// Pulls the `message` field (tracing's conventional name for an event's
+/// formatted text) out of a `tracing::Event` without needing the full
+/// `tracing_subscriber::fmt` formatting machinery.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        }
+    }
+}
+
+/// `tracing_subscriber::Layer` that serializes each event into an
+/// `EventRecord` and pushes it into `buffer`. Install alongside the crate's
+/// usual `fmt` layer via `tracing_subscriber::registry()`.
+pub struct RecentEventsLayer {
+    buffer: Arc<RecentEventsBuffer>,
+}
+
+impl RecentEventsLayer {
+    pub fn new(buffer: Arc<RecentEventsBuffer>) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecentEventsLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        self.buffer.push(EventRecord {
+            level: event.metadata().level().to_string(),
+            timestamp: Utc::now(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+            error_id: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(message: &str) -> EventRecord {
+        EventRecord {
+            level: "INFO".to_string(),
+            timestamp: Utc::now(),
+            target: "test".to_string(),
+            message: message.to_string(),
+            error_id: None,
+        }
+    }
+
+    #[test]
+    fn snapshot_reflects_pushed_records_in_order() {
+        let buffer = RecentEventsBuffer::new(4);
+        buffer.push(record("one"));
+        buffer.push(record("two"));
+
+        let snapshot = buffer.snapshot();
+        assert_eq!(snapshot.iter().map(|r| r.message.as_str()).collect::<Vec<_>>(), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn overflow_drops_oldest_rather_than_blocking() {
+        let buffer = RecentEventsBuffer::new(2);
+        buffer.push(record("one"));
+        buffer.push(record("two"));
+        buffer.push(record("three"));
+
+        let snapshot = buffer.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot.iter().map(|r| r.message.as_str()).collect::<Vec<_>>(), vec!["two", "three"]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Pluggable observability backend for `nlp::RelationshipExtractor`.
+//!
+//! [`ExtractionMetricsExporter`] is the seam that lets extraction metrics
+//! ship to an OTLP collector instead of going nowhere, without
+//! `RelationshipExtractor` needing to know which backend is active — the
+//! same shape as [`crate::embeddings::EmbeddingProvider`]. `NoopExtractionMetrics`
+//! is the default so a caller that never configures an exporter pays no
+//! cost and sees no behavior change.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Counters/histograms `RelationshipExtractor` reports on, implemented by
+/// whichever backend `ExtractionMetricsExporterKind` selects.
+pub trait ExtractionMetricsExporter: Send + Sync {
+    /// One relationship of `relation_type` was kept in the final output
+    /// (after filtering/ranking/truncation).
+    fn record_relationship(&self, relation_type: &str);
+
+    /// `phase` (`"co_occurrence"`, `"semantic"`, `"pattern"`, `"domain_specific"`,
+    /// or the overall `"extract_relationships_between_entities"`/
+    /// `"extract_relationships"` span) took `latency` to run over one text.
+    fn record_phase_latency(&self, phase: &str, latency: Duration);
+
+    /// `discarded` candidates were dropped by `max_relationships_per_text`
+    /// truncation for one extraction call (`0` when nothing was discarded,
+    /// so callers can derive a discard rate rather than just a raw count).
+    fn record_truncation(&self, discarded: u64);
+}
+
+/// Discards every metric. The default exporter, so extraction has zero
+/// observability overhead until an operator opts into one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopExtractionMetrics;
+
+impl ExtractionMetricsExporter for NoopExtractionMetrics {
+    fn record_relationship(&self, _relation_type: &str) {}
+    fn record_phase_latency(&self, _phase: &str, _latency: Duration) {}
+    fn record_truncation(&self, _discarded: u64) {}
+}
+
+/// Ships extraction metrics via an OTLP meter: a `relationships_total`
+/// counter labeled by `relation_type`, an `extraction_phase_latency_ms`
+/// histogram labeled by `phase`, and a `relationships_truncated_total`
+/// counter. The last one is a counter rather than a true gauge — a gauge
+/// needs a registered observable callback polled on the collector's
+/// schedule, while a monotonic counter is exactly as queryable (rate of
+/// truncation over time) with none of that extra wiring.
+pub struct OtlpExtractionMetrics {
+    relationships_total: opentelemetry::metrics::Counter<u64>,
+    phase_latency_ms: opentelemetry::metrics::Histogram<f64>,
+    relationships_truncated_total: opentelemetry::metrics::Counter<u64>,
+}
+
+impl OtlpExtractionMetrics {
+    /// Builds the exporter from the global OTLP meter registered for
+    /// `instrumentation_name` (set up via the process's OTLP pipeline
+    /// initialization, outside this module's concern).
+    pub fn new(instrumentation_name: &'static str) -> Self {
+        let meter = opentelemetry::global::meter(instrumentation_name);
+        Self {
+            relationships_total: meter
+                .u64_counter("relationships_total")
+                .with_description("Relationships kept in extraction output, labeled by relation_type")
+                .init(),
+            phase_latency_ms: meter
+                .f64_histogram("extraction_phase_latency_ms")
+                .with_description("Per-text extraction latency by pipeline phase")
+                .init(),
+            relationships_truncated_total: meter
+                .u64_counter("relationships_truncated_total")
+                .with_description("Candidates discarded by max_relationships_per_text truncation")
+                .init(),
+        }
+    }
+}
+
+impl ExtractionMetricsExporter for OtlpExtractionMetrics {
+    fn record_relationship(&self, relation_type: &str) {
+        self.relationships_total.add(1, &[opentelemetry::KeyValue::new("relation_type", relation_type.to_string())]);
+    }
+
+    fn record_phase_latency(&self, phase: &str, latency: Duration) {
+        self.phase_latency_ms.record(latency.as_secs_f64() * 1000.0, &[opentelemetry::KeyValue::new("phase", phase.to_string())]);
+    }
+
+    fn record_truncation(&self, discarded: u64) {
+        if discarded > 0 {
+            self.relationships_truncated_total.add(discarded, &[]);
+        }
+    }
+}
+
+/// Which `ExtractionMetricsExporter` backend `RelationshipExtractor` should
+/// report through.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionMetricsExporterKind {
+    /// No observability backend configured; metrics are discarded.
+    #[default]
+    Noop,
+    /// Report via the process's global OTLP meter provider.
+    Otlp,
+}
+
+/// Builds the exporter `kind` selects.
+pub fn build_extraction_metrics_exporter(kind: ExtractionMetricsExporterKind) -> Arc<dyn ExtractionMetricsExporter> {
+    match kind {
+        ExtractionMetricsExporterKind::Noop => Arc::new(NoopExtractionMetrics),
+        ExtractionMetricsExporterKind::Otlp => Arc::new(OtlpExtractionMetrics::new("kg_mcp_server::nlp::relationship_extractor")),
+    }
+}
@@ -0,0 +1,19 @@
+pub mod embeddings;
+pub mod events;
+pub mod extraction;
+pub mod profiler;
+pub mod rss;
+pub mod tracking_allocator;
+
+pub use embeddings::{
+    build_embedding_metrics_exporter, EmbeddingMetricsExporter, EmbeddingMetricsExporterKind,
+    NoopEmbeddingMetrics, OtlpEmbeddingMetrics,
+};
+pub use events::{EventRecord, RecentEventsBuffer, RecentEventsLayer, DEFAULT_RECENT_EVENTS_CAPACITY};
+pub use extraction::{
+    build_extraction_metrics_exporter, ExtractionMetricsExporter, ExtractionMetricsExporterKind,
+    NoopExtractionMetrics, OtlpExtractionMetrics,
+};
+pub use profiler::ProfileSpan;
+pub use rss::current_rss;
+pub use tracking_allocator::{live_allocated, peak_allocated};
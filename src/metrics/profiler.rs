@@ -0,0 +1,129 @@
+//! Lightweight hierarchical span profiler backing indexing operations'
+//! `profile: bool` opt-in (see `CodebaseIndexer::index_codebase`/
+//! `analyze_codebase_structure_mcp`): a thread-local stack of named spans,
+//! each accumulating elapsed wall time and call count into a tree, flushed
+//! into a `ProfileSpan` list on request. Little overhead when disabled — an
+//! `enter` call then only reads a `Cell<bool>` and takes an `Instant::now()`,
+//! and its guard's drop returns without touching the span tree.
+//!
+//! Caveat: a span entered before an `.await` that actually suspends (vs.
+//! resolving immediately, as an uncontended lock does) can resume on a
+//! different worker thread under tokio's multi-threaded runtime, orphaning
+//! it from this thread's stack. `index_codebase`'s per-file `tokio::spawn`
+//! loop is deliberately left uninstrumented for this reason — spans only
+//! wrap the sequential stages around it, which run start-to-finish on the
+//! calling task's thread.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static ENABLED: Cell<bool> = Cell::new(false);
+    static STACK: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+    static ROOT: RefCell<HashMap<&'static str, Node>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    total: Duration,
+    calls: u64,
+    children: HashMap<&'static str, Node>,
+}
+
+/// One stage's entry in a flushed span tree.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProfileSpan {
+    pub name: String,
+    pub wall_time_ms: f64,
+    pub calls: u64,
+    pub percent_of_total: f64,
+    pub children: Vec<ProfileSpan>,
+}
+
+/// Enables (or disables) profiling on the calling thread and clears any
+/// span tree left over from a previous call, so each profiled operation
+/// starts from an empty tree. Call once before the spans it wraps run.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+    if enabled {
+        ROOT.with(|r| r.borrow_mut().clear());
+        STACK.with(|s| s.borrow_mut().clear());
+    }
+}
+
+/// RAII guard for one span, returned by `enter`. Recording happens on drop
+/// so a span covers its whole scope regardless of an early `?` return.
+pub struct SpanGuard {
+    name: &'static str,
+    started_at: Instant,
+    recording: bool,
+}
+
+/// Enters a named span, nested under whatever span is currently on top of
+/// this thread's stack (or at the root if none is). A no-op timer if
+/// profiling isn't enabled on this thread (see `set_enabled`).
+pub fn enter(name: &'static str) -> SpanGuard {
+    let recording = ENABLED.with(|e| e.get());
+    if recording {
+        STACK.with(|s| s.borrow_mut().push(name));
+    }
+    SpanGuard { name, started_at: Instant::now(), recording }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        if !self.recording {
+            return;
+        }
+        let elapsed = self.started_at.elapsed();
+        let ancestors = STACK.with(|s| {
+            let mut stack = s.borrow_mut();
+            stack.pop();
+            stack.clone()
+        });
+        ROOT.with(|r| {
+            let mut root = r.borrow_mut();
+            let mut children = &mut *root;
+            for ancestor in &ancestors {
+                children = &mut children.entry(ancestor).or_default().children;
+            }
+            let node = children.entry(self.name).or_default();
+            node.total += elapsed;
+            node.calls += 1;
+        });
+    }
+}
+
+/// Flushes the current thread's span tree and disables further recording.
+/// Returns `None` if profiling wasn't enabled (see `set_enabled`). Call
+/// once per profiled operation, after all its spans have closed.
+pub fn take_tree() -> Option<Vec<ProfileSpan>> {
+    let was_enabled = ENABLED.with(|e| e.get());
+    ENABLED.with(|e| e.set(false));
+    if !was_enabled {
+        return None;
+    }
+
+    let roots = ROOT.with(|r| r.borrow().clone());
+    let total_ms: f64 = roots.values().map(|node| node.total.as_secs_f64() * 1000.0).sum();
+    Some(flush(&roots, total_ms))
+}
+
+fn flush(nodes: &HashMap<&'static str, Node>, overall_total_ms: f64) -> Vec<ProfileSpan> {
+    let mut spans: Vec<ProfileSpan> = nodes
+        .iter()
+        .map(|(name, node)| {
+            let wall_time_ms = node.total.as_secs_f64() * 1000.0;
+            ProfileSpan {
+                name: name.to_string(),
+                wall_time_ms,
+                calls: node.calls,
+                percent_of_total: if overall_total_ms > 0.0 { wall_time_ms / overall_total_ms * 100.0 } else { 0.0 },
+                children: flush(&node.children, overall_total_ms),
+            }
+        })
+        .collect();
+    spans.sort_by(|a, b| b.wall_time_ms.partial_cmp(&a.wall_time_ms).unwrap_or(std::cmp::Ordering::Equal));
+    spans
+}
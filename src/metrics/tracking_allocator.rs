@@ -0,0 +1,46 @@
+//! Optional allocator-tracked live/peak byte counters. Disabled by default
+//! (zero overhead for callers who don't opt in); enable the `track-allocations`
+//! feature and install [`TrackingAllocator`] as the process's `#[global_allocator]`
+//! to get real numbers out of [`live_allocated`] / [`peak_allocated`].
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes currently allocated via [`TrackingAllocator`]. Reads `0` if the
+/// `track-allocations` feature isn't enabled or the allocator isn't installed.
+pub fn live_allocated() -> u64 {
+    LIVE_BYTES.load(Ordering::Relaxed)
+}
+
+/// High-water mark of [`live_allocated`] observed since process start (or the
+/// last allocation, whichever reads the counter last — there's no reset hook,
+/// since peak memory is meant to track the whole process lifetime).
+pub fn peak_allocated() -> u64 {
+    PEAK_BYTES.load(Ordering::Relaxed)
+}
+
+/// `GlobalAlloc` wrapper around [`std::alloc::System`] that maintains the
+/// counters read by [`live_allocated`]/[`peak_allocated`]. Only compiled in
+/// under the `track-allocations` feature, since every allocation now pays for
+/// two extra atomic ops.
+#[cfg(feature = "track-allocations")]
+pub struct TrackingAllocator;
+
+#[cfg(feature = "track-allocations")]
+unsafe impl std::alloc::GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        let ptr = std::alloc::System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed) + layout.size() as u64;
+            PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout);
+        LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+}
@@ -1,18 +1,172 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, Context};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
+use regex::RegexSet;
 use uuid::Uuid;
 
+use crate::context::{CdcConfig, FastCdcChunker};
 use crate::graph::{KGNode, KGEdge, Episode};
 
+/// Chunk size thresholds for `BackupManager::store_chunks` — larger than
+/// `context::CdcConfig::default()`'s text-chunking defaults since backup
+/// bodies are whole-graph JSON blobs, not individual documents.
+fn backup_chunk_config() -> CdcConfig {
+    CdcConfig {
+        min_size: 4 * 1024,
+        normal_size: 16 * 1024,
+        max_size: 64 * 1024,
+    }
+}
+
+/// File extensions `BackupManager` recognizes on a chunk file, in the order
+/// `find_chunk_path` probes them. A chunk with none of these extensions is
+/// stored uncompressed. An encrypted chunk additionally carries a trailing
+/// `.enc`, applied after whichever of these (if any) was used.
+const COMPRESSION_EXTENSIONS: &[&str] = &["zst", "br"];
+
+/// Trailing extension marking a chunk file as encrypted (see
+/// `BackupManager::encrypt_bytes`).
+const ENCRYPTION_EXTENSION: &str = "enc";
+
+/// Magic bytes prefixed to every `PartHeader`, so a part file can be told
+/// apart from anything else that might land in the backup directory.
+const PART_MAGIC: &[u8; 4] = b"KGBP";
+
+/// Version of the part-file header layout itself — distinct from
+/// `BackupData::backup_format_version`, which describes the JSON payload
+/// the parts reassemble into.
+const PART_HEADER_VERSION: u8 = 1;
+
+/// Fixed-size binary header prefixed to every `<backup_id>.partNNN` file
+/// `BackupManager::write_manifest` produces once a manifest exceeds
+/// `BackupConfig::max_file_size_mb`, mirroring the header/version scheme
+/// zvault uses for its own chunked archives: enough to detect a part that's
+/// missing, truncated, out of order, or swapped with another backup's part
+/// before any of its payload is trusted.
+struct PartHeader {
+    part_index: u32,
+    total_parts: u32,
+    payload_len: u64,
+    payload_checksum: [u8; 32],
+}
+
+impl PartHeader {
+    const ENCODED_LEN: usize = 4 + 1 + 4 + 4 + 8 + 32;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::ENCODED_LEN);
+        out.extend_from_slice(PART_MAGIC);
+        out.push(PART_HEADER_VERSION);
+        out.extend_from_slice(&self.part_index.to_le_bytes());
+        out.extend_from_slice(&self.total_parts.to_le_bytes());
+        out.extend_from_slice(&self.payload_len.to_le_bytes());
+        out.extend_from_slice(&self.payload_checksum);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < Self::ENCODED_LEN {
+            return Err(anyhow!("Backup part header is truncated"));
+        }
+        if &bytes[0..4] != PART_MAGIC {
+            return Err(anyhow!("Backup part file has an invalid magic header - this isn't a backup part"));
+        }
+        let version = bytes[4];
+        if version != PART_HEADER_VERSION {
+            return Err(anyhow!("Unsupported backup part header version {}", version));
+        }
+
+        let part_index = u32::from_le_bytes(bytes[5..9].try_into().unwrap());
+        let total_parts = u32::from_le_bytes(bytes[9..13].try_into().unwrap());
+        let payload_len = u64::from_le_bytes(bytes[13..21].try_into().unwrap());
+        let mut payload_checksum = [0u8; 32];
+        payload_checksum.copy_from_slice(&bytes[21..53]);
+
+        Ok(Self { part_index, total_parts, payload_len, payload_checksum })
+    }
+}
+
+/// Version byte prefixed to an encrypted chunk's header, ahead of its nonce
+/// — bumped if the on-disk layout ever needs to change, so a future
+/// `decrypt_bytes` can tell old chunks from new ones instead of guessing.
+const ENCRYPTION_FORMAT_VERSION: u8 = 1;
+
+/// Compression codec applied to chunk bytes before they're written to
+/// `chunks/`. Chunks are content-addressed by their *uncompressed* hash, so
+/// switching codecs between backups never breaks dedup — it just means a
+/// given hash's bytes on disk might be `zst` from one backup and `br` from
+/// another, whichever was written first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionCodec {
+    /// Fast to compress and decompress even at higher levels; the default.
+    Zstd { level: i32 },
+    /// Slower than zstd at a comparable ratio, but often smaller — worth it
+    /// for cold archival backups that are written once and rarely read.
+    Brotli { quality: u32 },
+}
+
+impl Default for CompressionCodec {
+    fn default() -> Self {
+        CompressionCodec::Zstd { level: 3 }
+    }
+}
+
+impl CompressionCodec {
+    fn extension(&self) -> &'static str {
+        match self {
+            CompressionCodec::Zstd { .. } => "zst",
+            CompressionCodec::Brotli { .. } => "br",
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match *self {
+            CompressionCodec::Zstd { level } => {
+                zstd::stream::encode_all(data, level).context("zstd compression failed")
+            }
+            CompressionCodec::Brotli { quality } => {
+                let mut out = Vec::new();
+                let params = brotli::enc::BrotliEncoderParams {
+                    quality: quality as i32,
+                    ..Default::default()
+                };
+                brotli::BrotliCompress(&mut std::io::Cursor::new(data), &mut out, &params)
+                    .context("brotli compression failed")?;
+                Ok(out)
+            }
+        }
+    }
+
+    fn decompress_as(extension: &str, data: &[u8]) -> Result<Vec<u8>> {
+        match extension {
+            "zst" => zstd::stream::decode_all(data).context("zstd decompression failed"),
+            "br" => {
+                let mut out = Vec::new();
+                brotli::BrotliDecompress(&mut std::io::Cursor::new(data), &mut out)
+                    .context("brotli decompression failed")?;
+                Ok(out)
+            }
+            other => Err(anyhow!("Unknown backup chunk compression extension '{}'", other)),
+        }
+    }
+}
+
 /// Backup manager for migration data safety
 pub struct BackupManager {
     backup_directory: PathBuf,
     compression_enabled: bool,
+    compression_codec: CompressionCodec,
     max_backup_age_days: u32,
+    /// Set via `with_encryption_key`/`with_encryption_passphrase`. Required
+    /// whenever a `BackupConfig::encryption` backup is created or an
+    /// already-encrypted one is read; absent otherwise.
+    encryption_key: Option<[u8; 32]>,
 }
 
 /// Backup metadata
@@ -28,19 +182,71 @@ pub struct BackupMetadata {
     pub compression_ratio: Option<f32>,
     pub checksum: String,
     pub description: String,
+    /// Id of the backup this one diffs against. `None` means this is a full
+    /// backup; `Some` means `BackupData`'s `nodes`/`edges`/`episodes` only
+    /// hold Adds/Mods and `deleted_*_uuids` holds the Dels, both relative to
+    /// `reconstruct_chain(parent_backup_id)`'s state.
+    #[serde(default)]
+    pub parent_backup_id: Option<String>,
+    /// The exclude filters this backup was created with, if any — lets a
+    /// later selective restore (or a human inspecting the backup) tell a
+    /// deliberately-carved subgraph apart from one that's merely small.
+    #[serde(default)]
+    pub applied_excludes: Option<BackupExcludes>,
 }
 
 /// Complete backup data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupData {
     pub metadata: BackupMetadata,
+    /// For a full backup, every node. For an incremental backup, only the
+    /// nodes that were added or whose content changed since `parent_backup_id`.
     pub nodes: Vec<KGNode>,
     pub edges: Vec<KGEdge>,
     pub episodes: Vec<Episode>,
+    /// UUIDs present in the parent backup but absent here. Always empty for
+    /// a full backup.
+    #[serde(default)]
+    pub deleted_node_uuids: Vec<Uuid>,
+    #[serde(default)]
+    pub deleted_edge_uuids: Vec<Uuid>,
+    #[serde(default)]
+    pub deleted_episode_uuids: Vec<Uuid>,
     pub schema_version: String,
     pub backup_format_version: String,
 }
 
+/// Everything in `BackupData` except `metadata` — what actually gets
+/// serialized and split into content-defined chunks for storage. Kept
+/// separate from `BackupMetadata` so two backups whose bodies are mostly
+/// identical (e.g. successive full backups of a slowly-changing graph, or
+/// an incremental next to the full backup it diffs against) land on mostly
+/// the same chunk hashes and share those chunk files on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupBody {
+    nodes: Vec<KGNode>,
+    edges: Vec<KGEdge>,
+    episodes: Vec<Episode>,
+    #[serde(default)]
+    deleted_node_uuids: Vec<Uuid>,
+    #[serde(default)]
+    deleted_edge_uuids: Vec<Uuid>,
+    #[serde(default)]
+    deleted_episode_uuids: Vec<Uuid>,
+    schema_version: String,
+    backup_format_version: String,
+}
+
+/// On-disk shape of `<backup_id>.json`: the metadata plus the ordered list
+/// of chunk hashes needed to reconstruct this backup's `BackupBody` from
+/// `chunks/`. This file stays small regardless of graph size — the actual
+/// data lives in `chunks/`, deduplicated across every backup that shares it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupManifest {
+    metadata: BackupMetadata,
+    chunk_hashes: Vec<String>,
+}
+
 /// Backup configuration
 #[derive(Debug, Clone)]
 pub struct BackupConfig {
@@ -49,6 +255,33 @@ pub struct BackupConfig {
     pub verify_integrity: bool,
     pub incremental_backup: bool,
     pub max_file_size_mb: usize,
+    /// Encrypt new chunks with XChaCha20-Poly1305 (applied after
+    /// compression). Requires `BackupManager::with_encryption_key` or
+    /// `with_encryption_passphrase` to have been called first — `create_backup`
+    /// fails before doing any work if this is set without a key configured.
+    pub encryption: bool,
+    /// When set, carves a subgraph out of the input before anything else
+    /// (diffing, counting, chunking) sees it — see `BackupExcludes`.
+    pub excludes: Option<BackupExcludes>,
+}
+
+/// Subgraph exclusion filters for `create_backup`, borrowed from zvault's
+/// `excludes: RegexSet` idea. `patterns` is matched against a node's
+/// `name`/`node_type`, an episode's `group_id`/`source`, or an edge's
+/// `relation_type`; any match excludes that item. `group_id_allowlist`, if
+/// non-empty, additionally requires `group_id` to be in the list regardless
+/// of `patterns` — so e.g. "back up only project X's episodes" doesn't need
+/// a pattern that can never match anything else.
+///
+/// Excluding a node cascades to every edge touching it (an edge with a
+/// missing endpoint would otherwise fail `verify_backup`'s orphan check),
+/// and to every episode's `entity_uuids`/`edge_uuids` that reference an
+/// excluded node or edge - the episode itself is kept, just with those
+/// references dropped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupExcludes {
+    pub patterns: Vec<String>,
+    pub group_id_allowlist: Vec<String>,
 }
 
 /// Backup restoration options
@@ -82,11 +315,364 @@ impl BackupManager {
         Ok(Self {
             backup_directory: backup_dir,
             compression_enabled,
+            compression_codec: CompressionCodec::default(),
             max_backup_age_days,
+            encryption_key: None,
         })
     }
 
-    /// Create a complete backup of the current data
+    /// Overrides the codec newly-written chunks use (default: zstd level 3).
+    /// Chunks already on disk under the old codec are left as-is and decode
+    /// fine regardless — see [`CompressionCodec`].
+    pub fn with_compression_codec(mut self, codec: CompressionCodec) -> Self {
+        self.compression_codec = codec;
+        self
+    }
+
+    /// Sets the raw 32-byte key used to encrypt/decrypt backups created with
+    /// `BackupConfig::encryption` set. Use `with_encryption_passphrase`
+    /// instead if you only have a human passphrase.
+    pub fn with_encryption_key(mut self, key: [u8; 32]) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Derives a key from `passphrase` via Argon2id and sets it the same way
+    /// `with_encryption_key` would. `salt` must be stable across calls for
+    /// the same passphrase to keep deriving the same key — store it next to
+    /// wherever the passphrase itself is kept, not in the backup directory.
+    pub fn with_encryption_passphrase(self, passphrase: &str, salt: &[u8]) -> Result<Self> {
+        let key = derive_key_from_passphrase(passphrase, salt)?;
+        Ok(self.with_encryption_key(key))
+    }
+
+    fn encryption_key(&self) -> Result<&Key> {
+        self.encryption_key
+            .as_ref()
+            .map(Key::from_slice)
+            .ok_or_else(|| anyhow!(
+                "Backup encryption is enabled but no encryption key was configured — \
+                 call BackupManager::with_encryption_key or with_encryption_passphrase first"
+            ))
+    }
+
+    /// Encrypts `data` with XChaCha20-Poly1305 under a fresh random nonce,
+    /// returning `[format version][24-byte nonce][ciphertext+tag]`.
+    fn encrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let cipher = XChaCha20Poly1305::new(self.encryption_key()?);
+
+        let mut nonce_bytes = [0u8; 24];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let nonce = XNonce::from_slice(&nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(nonce, data)
+            .map_err(|_| anyhow!("Failed to encrypt backup chunk"))?;
+
+        let mut out = Vec::with_capacity(1 + nonce_bytes.len() + ciphertext.len());
+        out.push(ENCRYPTION_FORMAT_VERSION);
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Reverses `encrypt_bytes`, verifying the Poly1305 tag before returning
+    /// any plaintext. A failed tag means the chunk was corrupted or
+    /// tampered with, not that it's merely unreadable — callers should treat
+    /// this the same as any other integrity failure.
+    fn decrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let cipher = XChaCha20Poly1305::new(self.encryption_key()?);
+
+        let (&version, rest) = data
+            .split_first()
+            .ok_or_else(|| anyhow!("Encrypted backup chunk is empty"))?;
+        if version != ENCRYPTION_FORMAT_VERSION {
+            return Err(anyhow!("Unsupported backup encryption format version {}", version));
+        }
+        if rest.len() < 24 {
+            return Err(anyhow!("Encrypted backup chunk is shorter than its nonce header - file is corrupted"));
+        }
+        let (nonce_bytes, ciphertext) = rest.split_at(24);
+        let nonce = XNonce::from_slice(nonce_bytes);
+
+        cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| anyhow!("Backup chunk failed authentication - it may be corrupted or tampered with"))
+    }
+
+    fn manifest_json_path(&self, backup_id: &str) -> PathBuf {
+        self.backup_directory.join(format!("{}.json", backup_id))
+    }
+
+    fn manifest_part_path(&self, backup_id: &str, part_index: u32) -> PathBuf {
+        self.backup_directory.join(format!("{}.part{:03}", backup_id, part_index))
+    }
+
+    /// Every backup id with a manifest (single-file or multi-part) present
+    /// in the backup directory, deduplicated.
+    fn backup_ids(&self) -> Result<Vec<String>> {
+        if !self.backup_directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut ids = HashSet::new();
+        for entry in fs::read_dir(&self.backup_directory)? {
+            let entry = entry?;
+            let Some(filename) = entry.file_name().to_str().map(str::to_string) else { continue };
+
+            if let Some(id) = filename.strip_suffix(".json") {
+                ids.insert(id.to_string());
+            } else if let Some(id) = filename.strip_suffix(".part000") {
+                ids.insert(id.to_string());
+            }
+        }
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Deletes whichever manifest files exist for `backup_id` — the single
+    /// `.json` file, or every `.partNNN` file, whichever this backup used.
+    fn remove_manifest_files(&self, backup_id: &str) -> Result<()> {
+        let json_path = self.manifest_json_path(backup_id);
+        if json_path.exists() {
+            fs::remove_file(json_path)?;
+        }
+
+        let mut part_index = 0u32;
+        loop {
+            let path = self.manifest_part_path(backup_id, part_index);
+            if !path.exists() {
+                break;
+            }
+            fs::remove_file(path)?;
+            part_index += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `manifest_bytes` as `backup_id`'s manifest, either as a single
+    /// `<backup_id>.json` file when it fits within `max_file_size_mb`, or
+    /// split into numbered, checksummed `<backup_id>.partNNN` files
+    /// otherwise (see `PartHeader`). Returns the total bytes written to
+    /// disk. Any manifest files already on disk for `backup_id` are removed
+    /// first, so a second pass that needs fewer parts than an earlier one
+    /// doesn't leave stale trailing parts behind.
+    fn write_manifest(&self, backup_id: &str, manifest_bytes: &[u8], max_file_size_mb: usize) -> Result<u64> {
+        self.remove_manifest_files(backup_id)?;
+
+        let max_bytes = max_file_size_mb.max(1) * 1024 * 1024;
+        if manifest_bytes.len() <= max_bytes {
+            let path = self.manifest_json_path(backup_id);
+            fs::write(&path, manifest_bytes)?;
+            return Ok(fs::metadata(&path)?.len());
+        }
+
+        let total_parts = ((manifest_bytes.len() + max_bytes - 1) / max_bytes) as u32;
+        let mut total_written = 0u64;
+
+        for (part_index, payload) in manifest_bytes.chunks(max_bytes).enumerate() {
+            let header = PartHeader {
+                part_index: part_index as u32,
+                total_parts,
+                payload_len: payload.len() as u64,
+                payload_checksum: sha256_digest(payload),
+            };
+
+            let mut contents = header.encode();
+            contents.extend_from_slice(payload);
+
+            let path = self.manifest_part_path(backup_id, part_index as u32);
+            fs::write(&path, &contents)?;
+            total_written += contents.len() as u64;
+        }
+
+        Ok(total_written)
+    }
+
+    /// Loads `backup_id`'s manifest bytes, reassembling and validating
+    /// `.partNNN` files in order if it was split. Fails loudly (rather than
+    /// returning a partial manifest) on a missing trailing part, an
+    /// out-of-order or relabeled part, a part whose header disagrees with
+    /// its siblings on the total part count, or a part that fails its own
+    /// payload checksum.
+    fn read_manifest_bytes(&self, backup_id: &str) -> Result<Vec<u8>> {
+        let json_path = self.manifest_json_path(backup_id);
+        if json_path.exists() {
+            return fs::read(&json_path)
+                .with_context(|| format!("Failed to read backup manifest '{}'", backup_id));
+        }
+
+        let mut payload = Vec::new();
+        let mut part_index = 0u32;
+        let mut expected_total_parts = None;
+
+        loop {
+            let path = self.manifest_part_path(backup_id, part_index);
+            if !path.exists() {
+                break;
+            }
+
+            let raw = fs::read(&path)
+                .with_context(|| format!("Failed to read backup part '{}'", path.display()))?;
+            let header = PartHeader::decode(&raw)
+                .with_context(|| format!("Invalid header in backup part '{}'", path.display()))?;
+
+            if header.part_index != part_index {
+                return Err(anyhow!(
+                    "Backup '{}' part file {} claims part index {} - parts are out of order or mislabeled",
+                    backup_id, part_index, header.part_index
+                ));
+            }
+            match expected_total_parts {
+                None => expected_total_parts = Some(header.total_parts),
+                Some(expected) if expected != header.total_parts => {
+                    return Err(anyhow!(
+                        "Backup '{}' part {} disagrees with earlier parts on total part count ({} vs {})",
+                        backup_id, part_index, header.total_parts, expected
+                    ));
+                }
+                _ => {}
+            }
+
+            let part_payload = &raw[PartHeader::ENCODED_LEN..];
+            if part_payload.len() as u64 != header.payload_len {
+                return Err(anyhow!("Backup '{}' part {} has a truncated payload", backup_id, part_index));
+            }
+            if sha256_digest(part_payload) != header.payload_checksum {
+                return Err(anyhow!("Backup '{}' part {} failed its payload checksum - the part is corrupted", backup_id, part_index));
+            }
+
+            payload.extend_from_slice(part_payload);
+            part_index += 1;
+        }
+
+        let total_parts = expected_total_parts
+            .ok_or_else(|| anyhow!("Backup '{}' not found", backup_id))?;
+        if part_index != total_parts {
+            return Err(anyhow!(
+                "Backup '{}' is missing its trailing part(s): found {} of {} expected parts",
+                backup_id, part_index, total_parts
+            ));
+        }
+
+        Ok(payload)
+    }
+
+    /// Directory unique backup chunks live in, named by their SHA-256 hash,
+    /// plus a [`COMPRESSION_EXTENSIONS`] suffix if stored compressed —
+    /// identical chunks produced by different backups are written once and
+    /// shared by every manifest that references them.
+    fn chunks_dir(&self) -> PathBuf {
+        self.backup_directory.join("chunks")
+    }
+
+    fn chunk_path(&self, hash: &str) -> PathBuf {
+        self.chunks_dir().join(hash)
+    }
+
+    /// Every filename a chunk with content hash `hash` might be stored
+    /// under: plain, compressed, encrypted, or compressed-then-encrypted.
+    fn candidate_chunk_filenames(hash: &str) -> Vec<String> {
+        let mut names: Vec<String> = std::iter::once(hash.to_string())
+            .chain(COMPRESSION_EXTENSIONS.iter().map(|ext| format!("{}.{}", hash, ext)))
+            .collect();
+        names.extend(names.clone().into_iter().map(|name| format!("{}.{}", name, ENCRYPTION_EXTENSION)));
+        names
+    }
+
+    /// Locates `hash`'s chunk file on disk regardless of which codec and/or
+    /// encryption it was written with.
+    fn find_chunk_path(&self, hash: &str) -> Option<PathBuf> {
+        Self::candidate_chunk_filenames(hash)
+            .into_iter()
+            .map(|name| self.chunks_dir().join(name))
+            .find(|path| path.exists())
+    }
+
+    /// Splits `data` into content-defined chunks (FastCDC, ~16 KiB average)
+    /// and writes each one not already on disk to `chunks/`, compressing
+    /// with `self.compression_codec` when `compress` is set and encrypting
+    /// with `self.encryption_key` (applied after compression) when
+    /// `encrypt` is set, returning the ordered hash list a manifest needs to
+    /// reconstruct `data`. Chunks already present from an earlier backup are
+    /// left alone — this is where cross-backup deduplication actually
+    /// happens. Hashing happens on the plaintext, so dedup still matches a
+    /// chunk against an earlier backup that wrote it under a different
+    /// codec or encryption setting.
+    fn store_chunks(&self, data: &[u8], compress: bool, encrypt: bool) -> Result<Vec<String>> {
+        fs::create_dir_all(self.chunks_dir())?;
+
+        let chunker = FastCdcChunker::new(backup_chunk_config());
+        let mut hashes = Vec::new();
+        let mut start = 0;
+        for end in chunker.cut_points(data) {
+            let chunk = &data[start..end];
+            let hash = hash_bytes(chunk);
+
+            if self.find_chunk_path(&hash).is_none() {
+                let mut bytes = chunk.to_vec();
+                let mut filename = hash.clone();
+
+                if compress {
+                    bytes = self.compression_codec.compress(&bytes)?;
+                    filename = format!("{}.{}", filename, self.compression_codec.extension());
+                }
+                if encrypt {
+                    bytes = self.encrypt_bytes(&bytes)?;
+                    filename = format!("{}.{}", filename, ENCRYPTION_EXTENSION);
+                }
+
+                fs::write(self.chunks_dir().join(filename), &bytes)?;
+            }
+
+            hashes.push(hash);
+            start = end;
+        }
+
+        Ok(hashes)
+    }
+
+    /// Reverses whichever of encryption/compression `store_chunks` applied
+    /// to the file at `path`, inferred from its extension(s).
+    fn load_chunk_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let mut filename = path.file_name().and_then(|f| f.to_str()).unwrap_or_default().to_string();
+        let mut bytes = fs::read(path)?;
+
+        if let Some(stem) = filename.strip_suffix(&format!(".{}", ENCRYPTION_EXTENSION)) {
+            bytes = self.decrypt_bytes(&bytes)?;
+            filename = stem.to_string();
+        }
+        if let Some(ext) = Path::new(&filename).extension().and_then(|s| s.to_str()) {
+            bytes = CompressionCodec::decompress_as(ext, &bytes)?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Reassembles the byte stream `chunk_hashes` was split from by reading,
+    /// transparently decrypting/decompressing, and concatenating each chunk
+    /// file in order.
+    fn load_chunks(&self, chunk_hashes: &[String]) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        for hash in chunk_hashes {
+            let path = self.find_chunk_path(hash)
+                .with_context(|| format!("Missing backup chunk '{}' referenced by a chunk list", hash))?;
+            let chunk = self.load_chunk_bytes(&path)
+                .with_context(|| format!("Failed to load backup chunk '{}'", hash))?;
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+
+    /// Create a backup of the current data. When `config.incremental_backup`
+    /// is set and `parent_backup_id` names an existing backup, this stores
+    /// only the nodes/edges/episodes that were added or changed since that
+    /// parent (plus the UUIDs of anything deleted), diffed against the
+    /// parent's *reconstructed* state (see `reconstruct_chain`) so a chain of
+    /// incrementals keeps working. Falls back to a full backup when
+    /// incremental is requested but no parent is given — there's nothing to
+    /// diff against yet.
     pub async fn create_backup(
         &self,
         nodes: &[KGNode],
@@ -94,10 +680,15 @@ impl BackupManager {
         episodes: &[Episode],
         config: &BackupConfig,
         description: String,
+        parent_backup_id: Option<&str>,
     ) -> Result<String> {
+        if config.encryption {
+            // Fail before doing any diffing/serialization work rather than
+            // partway through writing chunks.
+            self.encryption_key()?;
+        }
+
         let backup_id = format!("backup_{}", Utc::now().format("%Y%m%d_%H%M%S_%f"));
-        let backup_filename = format!("{}.json", backup_id);
-        let backup_path = self.backup_directory.join(&backup_filename);
 
         log::info!("Creating backup: {}", backup_id);
 
@@ -120,60 +711,132 @@ impl BackupManager {
             }).collect()
         };
 
-        // Create backup metadata
+        // Carve out the requested subgraph, if any, before anything below
+        // (incremental diffing, counts, chunking) sees the data - excluded
+        // nodes/edges/episodes behave as if they were never passed in.
+        let (nodes, edges, filtered_episodes): (Vec<KGNode>, Vec<KGEdge>, Vec<Episode>) = match &config.excludes {
+            Some(excludes) => apply_excludes(nodes, edges, &filtered_episodes, excludes)?,
+            None => (nodes.to_vec(), edges.to_vec(), filtered_episodes),
+        };
+        let nodes = &nodes;
+        let edges = &edges;
+
+        let (stored_nodes, stored_edges, stored_episodes,
+             deleted_node_uuids, deleted_edge_uuids, deleted_episode_uuids,
+             effective_parent_backup_id) =
+            match (config.incremental_backup, parent_backup_id) {
+                (true, Some(parent_id)) => {
+                    let (parent_nodes, parent_edges, parent_episodes) = self.reconstruct_chain(parent_id).await
+                        .with_context(|| format!("Failed to reconstruct parent backup '{}' for incremental diff", parent_id))?;
+
+                    let (changed_nodes, deleted_node_uuids) = diff_by_uuid(&parent_nodes, nodes, |n| n.uuid)?;
+                    let (changed_edges, deleted_edge_uuids) = diff_by_uuid(&parent_edges, edges, |e| e.uuid)?;
+                    let (changed_episodes, deleted_episode_uuids) = diff_by_uuid(&parent_episodes, &filtered_episodes, |e| e.uuid)?;
+
+                    (changed_nodes, changed_edges, changed_episodes,
+                     deleted_node_uuids, deleted_edge_uuids, deleted_episode_uuids,
+                     Some(parent_id.to_string()))
+                }
+                _ => (nodes.to_vec(), edges.to_vec(), filtered_episodes, Vec::new(), Vec::new(), Vec::new(), None),
+            };
+
+        // Create backup metadata. Counts describe the backup's own graph -
+        // the full input graph normally, or the carved-out subgraph when
+        // `config.excludes` is set - so stats/listing stay meaningful even
+        // though an incremental file only stores the changed subset.
         let metadata = BackupMetadata {
             backup_id: backup_id.clone(),
             created_at: Utc::now(),
             source_type: "kg_mcp_server".to_string(),
             node_count: nodes.len(),
             edge_count: edges.len(),
-            episode_count: episodes.len(),
+            episode_count: filtered_episodes.len(),
             file_size_bytes: 0, // Will be updated after writing
             compression_ratio: None,
             checksum: "".to_string(), // Will be calculated after writing
             description,
+            parent_backup_id: effective_parent_backup_id,
+            applied_excludes: config.excludes.clone(),
         };
 
         // Create backup data structure
         let backup_data = BackupData {
             metadata,
-            nodes: nodes.to_vec(),
-            edges: edges.to_vec(),
-            episodes: filtered_episodes,
+            nodes: stored_nodes,
+            edges: stored_edges,
+            episodes: stored_episodes,
+            deleted_node_uuids,
+            deleted_edge_uuids,
+            deleted_episode_uuids,
             schema_version: "1.0".to_string(),
             backup_format_version: "1.0".to_string(),
         };
 
-        // Serialize and write backup
-        let json_data = serde_json::to_string_pretty(&backup_data)?;
-        
-        if config.compress_data && self.compression_enabled {
-            // In a real implementation, we would use compression here
-            // For now, just write the JSON data
-            fs::write(&backup_path, &json_data)?;
+        // Split the body out of `backup_data`, content-chunk it, and write
+        // each not-already-present chunk to `chunks/` — this is what makes
+        // near-identical backups share storage instead of each paying for a
+        // full copy of the graph.
+        let body = BackupBody {
+            nodes: backup_data.nodes,
+            edges: backup_data.edges,
+            episodes: backup_data.episodes,
+            deleted_node_uuids: backup_data.deleted_node_uuids,
+            deleted_edge_uuids: backup_data.deleted_edge_uuids,
+            deleted_episode_uuids: backup_data.deleted_episode_uuids,
+            schema_version: backup_data.schema_version,
+            backup_format_version: backup_data.backup_format_version,
+        };
+        let body_bytes = serde_json::to_vec(&body)?;
+        let compress = config.compress_data && self.compression_enabled;
+        let chunk_hashes = self.store_chunks(&body_bytes, compress, config.encryption)?;
+
+        // The checksum is over the *logical* body, not its on-disk chunk
+        // bytes — a chunk's compression is a property of whichever backup
+        // happened to write it first (dedup keys on the uncompressed hash),
+        // so this is what stays stable for `verify_backup` to compare against
+        // regardless of which codec wrote any given chunk.
+        let checksum = hash_bytes(&body_bytes);
+
+        // Ratio is actual on-disk bytes (post-dedup, so a chunk already
+        // shared with an earlier backup costs nothing extra here) over the
+        // uncompressed body size.
+        let compression_ratio = if compress {
+            let mut unique_hashes: Vec<&String> = chunk_hashes.iter().collect();
+            unique_hashes.sort_unstable();
+            unique_hashes.dedup();
+            let on_disk_bytes: u64 = unique_hashes.into_iter()
+                .filter_map(|hash| self.find_chunk_path(hash))
+                .filter_map(|path| fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+            Some(on_disk_bytes as f32 / body_bytes.len() as f32)
         } else {
-            fs::write(&backup_path, &json_data)?;
-        }
+            None
+        };
 
-        // Update metadata with actual file information
-        let file_metadata = fs::metadata(&backup_path)?;
-        let file_size = file_metadata.len();
-        let checksum = self.calculate_checksum(&backup_path)?;
+        let mut metadata = backup_data.metadata;
+        metadata.checksum = checksum;
+        metadata.compression_ratio = compression_ratio;
 
-        // Update the backup file with correct metadata
-        let mut updated_backup_data = backup_data;
-        updated_backup_data.metadata.file_size_bytes = file_size;
-        updated_backup_data.metadata.checksum = checksum;
+        // Write once to learn the manifest's own serialized size, then
+        // again with that size folded into the metadata — same two-pass
+        // shape this used when the whole backup lived in one file.
+        // `write_manifest` transparently splits into checksummed `.partNNN`
+        // files if the manifest is bigger than `config.max_file_size_mb`.
+        let manifest = BackupManifest { metadata: metadata.clone(), chunk_hashes: chunk_hashes.clone() };
+        let file_size = self.write_manifest(&backup_id, &serde_json::to_vec(&manifest)?, config.max_file_size_mb)?;
 
-        let updated_json = serde_json::to_string_pretty(&updated_backup_data)?;
-        fs::write(&backup_path, &updated_json)?;
+        metadata.file_size_bytes = file_size;
+        let manifest = BackupManifest { metadata, chunk_hashes };
+        self.write_manifest(&backup_id, &serde_json::to_vec(&manifest)?, config.max_file_size_mb)?;
 
         // Verify backup integrity if requested
         if config.verify_integrity {
             self.verify_backup(&backup_id).await?;
         }
 
-        log::info!("Backup created successfully: {} ({} bytes)", backup_id, file_size);
+        log::info!("Backup created successfully: {} ({} bytes manifest, {} chunks)",
+                   backup_id, file_size, manifest.chunk_hashes.len());
         Ok(backup_id)
     }
 
@@ -181,18 +844,9 @@ impl BackupManager {
     pub async fn list_backups(&self) -> Result<Vec<BackupMetadata>> {
         let mut backups = Vec::new();
 
-        if !self.backup_directory.exists() {
-            return Ok(backups);
-        }
-
-        for entry in fs::read_dir(&self.backup_directory)? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Ok(backup_data) = self.load_backup_data(&path).await {
-                    backups.push(backup_data.metadata);
-                }
+        for backup_id in self.backup_ids()? {
+            if let Ok(backup_data) = self.load_backup_data(&backup_id).await {
+                backups.push(backup_data.metadata);
             }
         }
 
@@ -202,7 +856,8 @@ impl BackupManager {
         Ok(backups)
     }
 
-    /// Restore data from a backup
+    /// Restore data from a backup, walking its parent chain (if it's an
+    /// incremental backup) to reconstruct the full node/edge/episode set.
     pub async fn restore_backup(
         &self,
         backup_id: &str,
@@ -215,52 +870,94 @@ impl BackupManager {
             self.verify_backup(backup_id).await?;
         }
 
-        // Load backup data
-        let backup_path = self.backup_directory.join(format!("{}.json", backup_id));
-        let backup_data = self.load_backup_data(&backup_path).await?;
+        let (nodes, edges, episodes) = self.reconstruct_chain(backup_id).await?;
 
         // Apply selective restore filters if specified
         let (nodes, edges, episodes) = if let Some(ref selective) = options.selective_restore {
-            self.apply_selective_restore(&backup_data, selective)?
+            self.apply_selective_restore(&nodes, &edges, &episodes, selective)?
         } else {
-            (backup_data.nodes, backup_data.edges, backup_data.episodes)
+            (nodes, edges, episodes)
         };
 
-        log::info!("Backup restored: {} nodes, {} edges, {} episodes", 
+        log::info!("Backup restored: {} nodes, {} edges, {} episodes",
                   nodes.len(), edges.len(), episodes.len());
 
         Ok((nodes, edges, episodes))
     }
 
+    /// Walks `backup_id`'s parent chain back to its nearest full backup and
+    /// replays each link's Adds/Mods/Dels in order (oldest first), so the
+    /// returned set reflects the full graph at `backup_id`'s point in time
+    /// regardless of how many incrementals sit between it and the last full
+    /// backup. A full backup (no `parent_backup_id`) resolves in one step.
+    /// A missing link in the chain fails loudly rather than silently
+    /// returning a partial graph.
+    async fn reconstruct_chain(&self, backup_id: &str) -> Result<(Vec<KGNode>, Vec<KGEdge>, Vec<Episode>)> {
+        let mut chain = Vec::new();
+        let mut current_id = backup_id.to_string();
+        loop {
+            let backup_data = self.load_backup_data(&current_id).await
+                .with_context(|| format!("Broken backup chain: failed to load '{}'", current_id))?;
+            let parent_id = backup_data.metadata.parent_backup_id.clone();
+            chain.push(backup_data);
+            match parent_id {
+                Some(parent_id) => current_id = parent_id,
+                None => break,
+            }
+        }
+        chain.reverse(); // oldest (the full backup) first
+
+        let mut nodes: HashMap<Uuid, KGNode> = HashMap::new();
+        let mut edges: HashMap<Uuid, KGEdge> = HashMap::new();
+        let mut episodes: HashMap<Uuid, Episode> = HashMap::new();
+
+        for backup_data in chain {
+            for uuid in &backup_data.deleted_node_uuids { nodes.remove(uuid); }
+            for uuid in &backup_data.deleted_edge_uuids { edges.remove(uuid); }
+            for uuid in &backup_data.deleted_episode_uuids { episodes.remove(uuid); }
+
+            for node in backup_data.nodes { nodes.insert(node.uuid, node); }
+            for edge in backup_data.edges { edges.insert(edge.uuid, edge); }
+            for episode in backup_data.episodes { episodes.insert(episode.uuid, episode); }
+        }
+
+        Ok((nodes.into_values().collect(), edges.into_values().collect(), episodes.into_values().collect()))
+    }
+
     /// Verify backup integrity
     pub async fn verify_backup(&self, backup_id: &str) -> Result<bool> {
-        let backup_path = self.backup_directory.join(format!("{}.json", backup_id));
-        
-        if !backup_path.exists() {
-            return Err(anyhow!("Backup file not found: {}", backup_id));
-        }
+        // Load and verify backup data. `load_backup_data` already fails
+        // loudly on a missing manifest, a missing/truncated trailing part,
+        // a part that fails its own checksum, or (via `load_chunks`) a
+        // missing or tampered chunk - the caller doesn't need to check
+        // existence itself first.
+        let backup_data = self.load_backup_data(backup_id).await
+            .with_context(|| format!("Backup '{}' failed verification", backup_id))?;
 
-        // Load and verify backup data
-        let backup_data = self.load_backup_data(&backup_path).await?;
-        
-        // Verify checksum
-        let current_checksum = self.calculate_checksum(&backup_path)?;
+        // Verify checksum against the reassembled body, not the (small,
+        // metadata-only) manifest file on disk.
+        let current_checksum = body_checksum(&backup_data)?;
         if current_checksum != backup_data.metadata.checksum {
-            return Err(anyhow!("Backup checksum mismatch - file may be corrupted"));
+            return Err(anyhow!("Backup checksum mismatch - file or chunk store may be corrupted"));
         }
 
-        // Verify data consistency
-        let node_uuids: std::collections::HashSet<_> = backup_data.nodes.iter().map(|n| n.uuid).collect();
-        
+        // Incremental backups only store the changed subset of nodes/edges,
+        // so reference consistency has to be checked against the full
+        // chain's reconstructed state, not this file's records alone. This
+        // also fails loudly if a parent in the chain is missing.
+        let (nodes, edges, episodes) = self.reconstruct_chain(backup_id).await
+            .with_context(|| format!("Backup chain verification failed for '{}'", backup_id))?;
+        let node_uuids: std::collections::HashSet<_> = nodes.iter().map(|n| n.uuid).collect();
+
         // Check for orphaned edges
-        for edge in &backup_data.edges {
+        for edge in &edges {
             if !node_uuids.contains(&edge.source_node_uuid) || !node_uuids.contains(&edge.target_node_uuid) {
                 return Err(anyhow!("Backup contains orphaned edges - data integrity compromised"));
             }
         }
 
         // Check episode references
-        for episode in &backup_data.episodes {
+        for episode in &episodes {
             for entity_uuid in &episode.entity_uuids {
                 if !node_uuids.contains(entity_uuid) {
                     log::warn!("Episode {} references non-existent entity {}", episode.uuid, entity_uuid);
@@ -278,15 +975,12 @@ impl BackupManager {
         let mut deleted_count = 0;
 
         let backups = self.list_backups().await?;
-        
+
         for backup in backups {
             if backup.created_at < cutoff_date {
-                let backup_path = self.backup_directory.join(format!("{}.json", backup.backup_id));
-                if backup_path.exists() {
-                    fs::remove_file(&backup_path)?;
-                    deleted_count += 1;
-                    log::info!("Deleted old backup: {}", backup.backup_id);
-                }
+                self.remove_manifest_files(&backup.backup_id)?;
+                deleted_count += 1;
+                log::info!("Deleted old backup: {}", backup.backup_id);
             }
         }
 
@@ -294,47 +988,137 @@ impl BackupManager {
             log::info!("Cleaned up {} old backups", deleted_count);
         }
 
+        // Vacuum: a chunk is only worth keeping if some surviving manifest's
+        // chunk list still references it.
+        let orphaned_chunks = self.vacuum_chunks()?;
+        if orphaned_chunks > 0 {
+            log::info!("Vacuumed {} orphaned backup chunks", orphaned_chunks);
+        }
+
         Ok(deleted_count)
     }
 
+    /// Every chunk hash referenced by any manifest currently in the backup
+    /// directory.
+    fn referenced_chunk_hashes(&self) -> Result<HashSet<String>> {
+        let mut referenced = HashSet::new();
+
+        for backup_id in self.backup_ids()? {
+            let Ok(manifest_bytes) = self.read_manifest_bytes(&backup_id) else { continue };
+            let Ok(manifest) = serde_json::from_slice::<BackupManifest>(&manifest_bytes) else { continue };
+            referenced.extend(manifest.chunk_hashes);
+        }
+
+        Ok(referenced)
+    }
+
+    /// Deletes every file in `chunks/` no surviving manifest's chunk list
+    /// references, reclaiming the space a deleted backup (or the parts of
+    /// its chain no longer shared with anything else) held. Returns how
+    /// many chunk files were removed.
+    fn vacuum_chunks(&self) -> Result<usize> {
+        let chunks_dir = self.chunks_dir();
+        if !chunks_dir.exists() {
+            return Ok(0);
+        }
+
+        let referenced = self.referenced_chunk_hashes()?;
+
+        let mut removed = 0;
+        for entry in fs::read_dir(&chunks_dir)? {
+            let entry = entry?;
+            let Some(filename) = entry.file_name().to_str().map(str::to_string) else { continue };
+            let hash = chunk_hash_from_filename(&filename);
+            if !referenced.contains(hash) {
+                fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Number of unique chunk files in `chunks/` and their total size on
+    /// disk — what the surviving backups actually cost after cross-backup
+    /// deduplication.
+    fn chunk_store_stats(&self) -> Result<(usize, u64)> {
+        let chunks_dir = self.chunks_dir();
+        if !chunks_dir.exists() {
+            return Ok((0, 0));
+        }
+
+        let mut count = 0;
+        let mut size = 0;
+        for entry in fs::read_dir(&chunks_dir)? {
+            let entry = entry?;
+            count += 1;
+            size += entry.metadata()?.len();
+        }
+
+        Ok((count, size))
+    }
+
     /// Get backup statistics
     pub async fn get_backup_stats(&self) -> Result<BackupStats> {
         let backups = self.list_backups().await?;
-        
+
         let total_backups = backups.len();
+        let incremental_backups = backups.iter().filter(|b| b.parent_backup_id.is_some()).count();
+        let full_backups = total_backups - incremental_backups;
         let total_size_bytes: u64 = backups.iter().map(|b| b.file_size_bytes).sum();
         let oldest_backup = backups.iter().map(|b| b.created_at).min();
         let newest_backup = backups.iter().map(|b| b.created_at).max();
+        let (chunk_count, deduplicated_size_bytes) = self.chunk_store_stats()?;
 
         Ok(BackupStats {
             total_backups,
+            full_backups,
+            incremental_backups,
             total_size_bytes,
             oldest_backup,
             newest_backup,
             average_size_bytes: if total_backups > 0 { total_size_bytes / total_backups as u64 } else { 0 },
+            chunk_count,
+            deduplicated_size_bytes,
         })
     }
 
-    /// Load backup data from file
-    async fn load_backup_data(&self, backup_path: &Path) -> Result<BackupData> {
-        let json_data = fs::read_to_string(backup_path)?;
-        let backup_data: BackupData = serde_json::from_str(&json_data)?;
-        Ok(backup_data)
+    /// Loads a manifest file and reassembles its `BackupBody` from `chunks/`.
+    async fn load_backup_data(&self, backup_id: &str) -> Result<BackupData> {
+        let manifest_bytes = self.read_manifest_bytes(backup_id)?;
+        let manifest: BackupManifest = serde_json::from_slice(&manifest_bytes)?;
+
+        let body_bytes = self.load_chunks(&manifest.chunk_hashes)?;
+        let body: BackupBody = serde_json::from_slice(&body_bytes)?;
+
+        Ok(BackupData {
+            metadata: manifest.metadata,
+            nodes: body.nodes,
+            edges: body.edges,
+            episodes: body.episodes,
+            deleted_node_uuids: body.deleted_node_uuids,
+            deleted_edge_uuids: body.deleted_edge_uuids,
+            deleted_episode_uuids: body.deleted_episode_uuids,
+            schema_version: body.schema_version,
+            backup_format_version: body.backup_format_version,
+        })
     }
 
     /// Apply selective restore filters
     fn apply_selective_restore(
         &self,
-        backup_data: &BackupData,
+        nodes: &[KGNode],
+        edges: &[KGEdge],
+        episodes: &[Episode],
         selective: &SelectiveRestore,
     ) -> Result<(Vec<KGNode>, Vec<KGEdge>, Vec<Episode>)> {
-        let mut nodes = Vec::new();
-        let mut edges = Vec::new();
-        let mut episodes = Vec::new();
+        let mut out_nodes = Vec::new();
+        let mut out_edges = Vec::new();
+        let mut out_episodes = Vec::new();
 
         // Filter nodes
         if selective.restore_nodes {
-            for node in &backup_data.nodes {
+            for node in nodes {
                 let mut include = true;
 
                 // Apply node filters
@@ -350,27 +1134,27 @@ impl BackupManager {
                 }
 
                 if include {
-                    nodes.push(node.clone());
+                    out_nodes.push(node.clone());
                 }
             }
         }
 
         // Filter edges (only include if both nodes are included)
         if selective.restore_edges {
-            let node_uuids: std::collections::HashSet<_> = nodes.iter().map(|n| n.uuid).collect();
-            
-            for edge in &backup_data.edges {
+            let node_uuids: std::collections::HashSet<_> = out_nodes.iter().map(|n| n.uuid).collect();
+
+            for edge in edges {
                 if node_uuids.contains(&edge.source_node_uuid) && node_uuids.contains(&edge.target_node_uuid) {
-                    edges.push(edge.clone());
+                    out_edges.push(edge.clone());
                 }
             }
         }
 
         // Filter episodes
         if selective.restore_episodes {
-            let node_uuids: std::collections::HashSet<_> = nodes.iter().map(|n| n.uuid).collect();
-            
-            for episode in &backup_data.episodes {
+            let node_uuids: std::collections::HashSet<_> = out_nodes.iter().map(|n| n.uuid).collect();
+
+            for episode in episodes {
                 let mut include = true;
 
                 // Apply date range filter
@@ -384,34 +1168,187 @@ impl BackupManager {
                 }
 
                 if include {
-                    episodes.push(episode.clone());
+                    out_episodes.push(episode.clone());
                 }
             }
         }
 
-        Ok((nodes, edges, episodes))
+        Ok((out_nodes, out_edges, out_episodes))
+    }
+}
+
+/// Strips a `chunks/` filename's `COMPRESSION_EXTENSIONS` suffix (if any) to
+/// recover the bare content hash it was stored under.
+fn chunk_hash_from_filename(filename: &str) -> &str {
+    let filename = filename.strip_suffix(&format!(".{}", ENCRYPTION_EXTENSION)).unwrap_or(filename);
+    for ext in COMPRESSION_EXTENSIONS {
+        if let Some(hash) = filename.strip_suffix(&format!(".{}", ext)) {
+            return hash;
+        }
     }
+    filename
+}
 
-    /// Calculate file checksum for integrity verification
-    fn calculate_checksum(&self, file_path: &Path) -> Result<String> {
-        use sha2::{Sha256, Digest};
-        
-        let data = fs::read(file_path)?;
-        let mut hasher = Sha256::new();
-        hasher.update(&data);
-        let result = hasher.finalize();
-        Ok(format!("{:x}", result))
+/// Derives a 32-byte backup encryption key from a human passphrase via
+/// Argon2id. `salt` must stay stable across calls for the same passphrase to
+/// keep deriving the same key; a random salt generated once and stored
+/// alongside the passphrase (not in the backup directory) is the usual
+/// approach.
+pub fn derive_key_from_passphrase(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    use argon2::Argon2;
+
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Argon2 key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// SHA-256 hex digest of `data`, used both to name chunks in `chunks/` and
+/// to fingerprint entities for dedup/diffing.
+fn hash_bytes(data: &[u8]) -> String {
+    sha256_digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Raw SHA-256 digest of `data` — used where a fixed-size `[u8; 32]` is more
+/// convenient than `hash_bytes`'s hex string, e.g. `PartHeader::payload_checksum`.
+fn sha256_digest(data: &[u8]) -> [u8; 32] {
+    use sha2::{Sha256, Digest};
+
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Cheap per-entity content fingerprint for `diff_by_uuid`'s Mod detection —
+/// two entities sharing a UUID but hashing differently is all that
+/// distinguishes a Mod from an unchanged entry.
+fn content_hash<T: Serialize>(value: &T) -> Result<String> {
+    Ok(hash_bytes(&serde_json::to_vec(value)?))
+}
+
+/// Recomputes the checksum a freshly-loaded `BackupData`'s body would have
+/// hashed to at write time, for `verify_backup` to compare against the
+/// checksum recorded in its manifest.
+fn body_checksum(backup_data: &BackupData) -> Result<String> {
+    let body = BackupBody {
+        nodes: backup_data.nodes.clone(),
+        edges: backup_data.edges.clone(),
+        episodes: backup_data.episodes.clone(),
+        deleted_node_uuids: backup_data.deleted_node_uuids.clone(),
+        deleted_edge_uuids: backup_data.deleted_edge_uuids.clone(),
+        deleted_episode_uuids: backup_data.deleted_episode_uuids.clone(),
+        schema_version: backup_data.schema_version.clone(),
+        backup_format_version: backup_data.backup_format_version.clone(),
+    };
+    content_hash(&body)
+}
+
+/// Three-way diffs `current` against `parent` by UUID: entries only in
+/// `current`, or present in both but with a changed `content_hash`, are
+/// returned as Adds/Mods in the first element (the incremental backup format
+/// stores both the same way); entries only in `parent` are Dels, returned as
+/// their UUIDs in the second element.
+fn diff_by_uuid<T, F>(parent: &[T], current: &[T], uuid_of: F) -> Result<(Vec<T>, Vec<Uuid>)>
+where
+    T: Serialize + Clone,
+    F: Fn(&T) -> Uuid,
+{
+    let parent_by_uuid: HashMap<Uuid, &T> = parent.iter().map(|entity| (uuid_of(entity), entity)).collect();
+    let current_by_uuid: HashMap<Uuid, &T> = current.iter().map(|entity| (uuid_of(entity), entity)).collect();
+
+    let mut changed = Vec::new();
+    for (uuid, entity) in &current_by_uuid {
+        let added_or_modified = match parent_by_uuid.get(uuid) {
+            None => true,
+            Some(parent_entity) => content_hash(entity)? != content_hash(parent_entity)?,
+        };
+        if added_or_modified {
+            changed.push((*entity).clone());
+        }
     }
+
+    let deleted = parent_by_uuid.keys().filter(|uuid| !current_by_uuid.contains_key(uuid)).copied().collect();
+
+    Ok((changed, deleted))
+}
+
+/// Applies `excludes` to `nodes`/`edges`/`episodes` - see `BackupExcludes`.
+/// Excluding a node cascades to any edge touching it and to the
+/// `entity_uuids`/`edge_uuids` references of every surviving episode.
+fn apply_excludes(
+    nodes: &[KGNode],
+    edges: &[KGEdge],
+    episodes: &[Episode],
+    excludes: &BackupExcludes,
+) -> Result<(Vec<KGNode>, Vec<KGEdge>, Vec<Episode>)> {
+    let patterns = RegexSet::new(&excludes.patterns)
+        .context("Invalid regex in BackupConfig.excludes.patterns")?;
+    let allowlist: HashSet<&str> = excludes.group_id_allowlist.iter().map(String::as_str).collect();
+
+    // Only enforced when the allowlist is non-empty - an empty allowlist
+    // means "no group_id restriction", not "exclude everything".
+    let group_id_excluded = |group_id: &Option<String>| {
+        !allowlist.is_empty() && !group_id.as_deref().is_some_and(|id| allowlist.contains(id))
+    };
+
+    let mut excluded_node_uuids = HashSet::new();
+    let kept_nodes: Vec<KGNode> = nodes.iter().cloned().filter(|node| {
+        let excluded = patterns.is_match(&node.name)
+            || patterns.is_match(&node.node_type)
+            || group_id_excluded(&node.group_id);
+        if excluded {
+            excluded_node_uuids.insert(node.uuid);
+        }
+        !excluded
+    }).collect();
+
+    let mut excluded_edge_uuids = HashSet::new();
+    let kept_edges: Vec<KGEdge> = edges.iter().cloned().filter(|edge| {
+        let excluded = patterns.is_match(&edge.relation_type)
+            || group_id_excluded(&edge.group_id)
+            || excluded_node_uuids.contains(&edge.source_node_uuid)
+            || excluded_node_uuids.contains(&edge.target_node_uuid);
+        if excluded {
+            excluded_edge_uuids.insert(edge.uuid);
+        }
+        !excluded
+    }).collect();
+
+    let kept_episodes: Vec<Episode> = episodes.iter().cloned().filter(|episode| {
+        let source_label = format!("{:?}", episode.source);
+        !(patterns.is_match(episode.group_id.as_deref().unwrap_or_default())
+            || patterns.is_match(&source_label)
+            || group_id_excluded(&episode.group_id))
+    }).map(|mut episode| {
+        episode.entity_uuids.retain(|uuid| !excluded_node_uuids.contains(uuid));
+        episode.edge_uuids.retain(|uuid| !excluded_edge_uuids.contains(uuid));
+        episode
+    }).collect();
+
+    Ok((kept_nodes, kept_edges, kept_episodes))
 }
 
 /// Backup statistics
 #[derive(Debug, Clone)]
 pub struct BackupStats {
     pub total_backups: usize,
+    /// Backups with no `parent_backup_id` — a complete snapshot on their own.
+    pub full_backups: usize,
+    /// Backups storing only an Add/Mod/Del diff against a parent.
+    pub incremental_backups: usize,
+    /// Sum of every manifest's `file_size_bytes` — small, since the actual
+    /// graph data lives in `chunks/`, not the manifests.
     pub total_size_bytes: u64,
     pub oldest_backup: Option<DateTime<Utc>>,
     pub newest_backup: Option<DateTime<Utc>>,
     pub average_size_bytes: u64,
+    /// Unique chunk files currently in `chunks/`.
+    pub chunk_count: usize,
+    /// Total bytes actually on disk in `chunks/` — what storing every
+    /// surviving backup costs after cross-backup deduplication, as opposed
+    /// to what it would cost if each backup stored its body independently.
+    pub deduplicated_size_bytes: u64,
 }
 
 impl Default for BackupConfig {
@@ -422,6 +1359,8 @@ impl Default for BackupConfig {
             verify_integrity: true,
             incremental_backup: false,
             max_file_size_mb: 100,
+            encryption: false,
+            excludes: None,
         }
     }
 }
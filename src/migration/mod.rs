@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -8,22 +8,110 @@ use async_trait::async_trait;
 use std::time::{Duration, Instant};
 
 pub mod graphiti_migrator;
+pub mod postgres_migrator;
+pub mod schema_version;
+pub mod source_reader;
 pub mod validation;
 pub mod backup;
+pub mod remediation;
+pub mod schema_migrations;
 
 use crate::graph::{KGNode, KGEdge, Episode};
 
-/// Migration configuration for different source systems
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Migration configuration for different source systems.
+///
+/// `Serialize` is implemented by hand below rather than derived, so that
+/// logging or persisting a `MigrationConfig` (it's embedded in progress
+/// callbacks and error reports) can never leak `source_connection` — a DSN
+/// commonly carries inline credentials, the same concern
+/// `security::secrets::SecretString` addresses for the auth token.
+#[derive(Debug, Clone, Deserialize)]
 pub struct MigrationConfig {
     pub source_type: SourceType,
     pub source_connection: String,
+    /// Path to a file holding `source_connection`'s real value instead of
+    /// committing it inline, following Garage's `rpc_secret_file` pattern.
+    /// Mutually exclusive with a non-empty `source_connection` — see
+    /// `resolve_source_connection`, which enforces that and is what every
+    /// `Migrator` impl should call to get the DSN to actually connect with.
+    #[serde(default)]
+    pub source_connection_file: Option<String>,
     pub target_database: String,
     pub batch_size: usize,
     pub validation_enabled: bool,
     pub backup_enabled: bool,
     pub parallel_workers: usize,
     pub chunk_size: usize,
+    /// Rows per `SKIP`/`LIMIT` page when a `Migrator` streams its source
+    /// instead of materializing it into a `Vec` up front - see
+    /// `GraphitiMigrator`'s Neo4j reader. Independent of `batch_size`, which
+    /// governs how many already-fetched items `migrate_batch` converts and
+    /// checkpoints at a time.
+    pub page_size: usize,
+    /// How many times a single item's conversion/storage is retried (with
+    /// exponential backoff) before it's recorded as a non-recoverable
+    /// `MigrationError` instead of just a retryable one. See
+    /// `GraphitiMigrator::retry_with_backoff`.
+    pub max_retry_attempts: u32,
+}
+
+impl Serialize for MigrationConfig {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("MigrationConfig", 11)?;
+        state.serialize_field("source_type", &self.source_type)?;
+        state.serialize_field(
+            "source_connection",
+            if self.source_connection.is_empty() { "" } else { "[REDACTED]" },
+        )?;
+        state.serialize_field("source_connection_file", &self.source_connection_file)?;
+        state.serialize_field("target_database", &self.target_database)?;
+        state.serialize_field("batch_size", &self.batch_size)?;
+        state.serialize_field("validation_enabled", &self.validation_enabled)?;
+        state.serialize_field("backup_enabled", &self.backup_enabled)?;
+        state.serialize_field("parallel_workers", &self.parallel_workers)?;
+        state.serialize_field("chunk_size", &self.chunk_size)?;
+        state.serialize_field("page_size", &self.page_size)?;
+        state.serialize_field("max_retry_attempts", &self.max_retry_attempts)?;
+        state.end()
+    }
+}
+
+impl MigrationConfig {
+    /// Resolves the DSN/credentials to actually connect with: from
+    /// `source_connection_file` (read fresh at connect time, so a rotated
+    /// file takes effect on the next migration run without restarting the
+    /// process) if set, otherwise the inline `source_connection`. Errors if
+    /// both are set non-empty rather than silently preferring one, mirroring
+    /// Garage's `rpc_secret_file` handling of the same ambiguity.
+    pub fn resolve_source_connection(&self) -> Result<String> {
+        match &self.source_connection_file {
+            Some(path) if !self.source_connection.is_empty() => Err(anyhow!(
+                "Both source_connection and source_connection_file are set ({}) - configure only one",
+                path
+            )),
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read source connection file: {}", path))?;
+                Ok(contents.trim().to_string())
+            }
+            None => Ok(self.source_connection.clone()),
+        }
+    }
+
+    /// Non-secret identifier for matching/recording a `MigrationCheckpoint`:
+    /// the file path when `source_connection_file` is set, otherwise the
+    /// inline connection string. Checkpoints are plaintext sidecar files, so
+    /// this deliberately never holds what `resolve_source_connection` would
+    /// return for the file case.
+    pub fn connection_identifier(&self) -> String {
+        self.source_connection_file
+            .clone()
+            .unwrap_or_else(|| self.source_connection.clone())
+    }
 }
 
 /// Supported source systems for migration
@@ -34,6 +122,9 @@ pub enum SourceType {
     JsonExport,
     CsvExport,
     CustomFormat,
+    /// A relational knowledge store reachable via `postgres_migrator::PostgresMigrator`,
+    /// read through a pooled connection rather than Neo4j's mocked bolt client.
+    Postgres,
 }
 
 /// Migration statistics and progress tracking
@@ -107,20 +198,41 @@ pub trait Migrator: Send + Sync {
     /// Analyze the source data to create a migration plan
     async fn analyze_source<'a>(&self, config: &'a MigrationConfig) -> Result<MigrationPlan>;
 
-    /// Perform the actual migration
+    /// Perform the actual migration. Implementors should check for an
+    /// incomplete `MigrationCheckpoint` for the same `source_connection`/
+    /// `target_database` before doing any work, and transparently continue
+    /// from it (via `resume`) rather than starting over, so a crashed or
+    /// killed run is safe to just re-invoke.
     async fn migrate<'a>(
-        &self, 
-        config: &'a MigrationConfig, 
+        &self,
+        config: &'a MigrationConfig,
         progress_callback: Option<Box<dyn Fn(MigrationProgress) + Send + Sync>>
     ) -> Result<MigrationResult>;
 
+    /// Continues a migration from a previously persisted `checkpoint`
+    /// (see `MigrationCheckpoint::load`), skipping items already recorded in
+    /// `checkpoint.completed_source_ids` and picking back up at
+    /// `checkpoint.phase`. `migrate` calls this itself when it finds an
+    /// on-disk checkpoint; exposed separately for callers (an admin CLI, a
+    /// `--resume` flag) that already hold the checkpoint and want to resume
+    /// a specific run without `migrate` re-deriving it from disk.
+    async fn resume<'a>(
+        &self,
+        config: &'a MigrationConfig,
+        checkpoint: MigrationCheckpoint,
+        progress_callback: Option<Box<dyn Fn(MigrationProgress) + Send + Sync>>,
+    ) -> Result<MigrationResult>;
+
     /// Validate the migrated data
     async fn validate<'a>(&self, config: &'a MigrationConfig) -> Result<ValidationReport>;
 
     /// Create a backup before migration
     async fn backup<'a>(&self, config: &'a MigrationConfig) -> Result<String>;
 
-    /// Rollback migration using backup
+    /// Rollback migration using backup. Must also discard any on-disk
+    /// `MigrationCheckpoint` for `config` (see `MigrationCheckpoint::delete`)
+    /// so a partially-applied resumed migration doesn't leave behind a
+    /// checkpoint that points at data this just rolled back.
     async fn rollback<'a>(&self, config: &'a MigrationConfig, backup_location: &str) -> Result<()>;
 }
 
@@ -137,6 +249,12 @@ pub struct MigrationPlan {
     pub recommended_batch_size: usize,
     pub recommended_workers: usize,
     pub potential_issues: Vec<String>,
+    /// Dry-run report from `schema_version::plan_upgrades`: which
+    /// registered upgrade steps a sample of the source data would trigger
+    /// before conversion. Empty for sources that don't version their data
+    /// (e.g. `PostgresMigrator`, which mirrors the target schema directly).
+    #[serde(default)]
+    pub schema_upgrade_steps: Vec<String>,
 }
 
 /// Real-time migration progress
@@ -153,6 +271,10 @@ pub struct MigrationProgress {
     pub estimated_remaining: std::time::Duration,
     pub current_throughput: f32,
     pub errors_encountered: usize,
+    /// Whether this run picked up from a `MigrationCheckpoint` rather than
+    /// starting fresh — lets a progress UI distinguish "100 items processed
+    /// so far" from "100 items processed just now".
+    pub is_resume: bool,
 }
 
 /// Migration phases for progress tracking
@@ -169,17 +291,111 @@ pub enum MigrationPhase {
     Completed,
 }
 
+/// Progress checkpoint persisted after each committed batch, so a crash or
+/// kill mid-run can be resumed from roughly where it left off instead of
+/// starting over. Written as a sidecar JSON file next to `target_database`
+/// (`<target_database>.migration_checkpoint.json`) rather than a table in
+/// the target itself — a checkpoint has to be readable before the migration
+/// has necessarily created any schema there, and it needs to survive even a
+/// `target_database` that's an entirely fresh file. `completed_source_ids`
+/// is what makes resuming safe: combined with `GraphStorage::insert_node`/
+/// `insert_edge`/`insert_episode` already being `INSERT OR REPLACE`/
+/// `INSERT OR IGNORE` upserts keyed by the source UUID, replaying a batch
+/// this checkpoint already recorded converges to the same row rather than
+/// double-counting it in `MigrationStats`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    /// `MigrationConfig::connection_identifier()` for the run this
+    /// checkpoint belongs to — never the resolved secret itself, since this
+    /// struct is persisted to disk as plaintext JSON.
+    pub source_connection: String,
+    pub target_database: String,
+    pub phase: MigrationPhase,
+    pub last_source_id: Option<String>,
+    pub nodes_processed: usize,
+    pub edges_processed: usize,
+    pub episodes_processed: usize,
+    pub batch_index: usize,
+    /// Source ids (see `GraphitiNode::uuid` and friends) already committed
+    /// to `target_database`, checked before re-converting/re-storing an item
+    /// so a resumed run doesn't duplicate work a prior run already finished.
+    pub completed_source_ids: std::collections::HashSet<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MigrationCheckpoint {
+    pub fn new(source_connection: String, target_database: String) -> Self {
+        Self {
+            source_connection,
+            target_database,
+            phase: MigrationPhase::Analyzing,
+            last_source_id: None,
+            nodes_processed: 0,
+            edges_processed: 0,
+            episodes_processed: 0,
+            batch_index: 0,
+            completed_source_ids: std::collections::HashSet::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Sidecar path a checkpoint for `target_database` is read from/written to.
+    pub fn path_for(target_database: &str) -> std::path::PathBuf {
+        let mut path = std::ffi::OsString::from(target_database);
+        path.push(".migration_checkpoint.json");
+        std::path::PathBuf::from(path)
+    }
+
+    /// Loads the checkpoint for `target_database`, if one exists and hasn't
+    /// already been cleaned up by a prior successful `Completed` phase.
+    /// Returns `Ok(None)` rather than erroring when the sidecar file is
+    /// simply absent, since "no checkpoint" is the normal fresh-start case.
+    pub fn load(target_database: &str) -> Result<Option<Self>> {
+        let path = Self::path_for(target_database);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    /// Persists this checkpoint, overwriting whatever was there before.
+    /// Called after each committed batch, so `updated_at` doubles as "last
+    /// time we know the run was still making progress".
+    pub fn save(&mut self) -> Result<()> {
+        self.updated_at = Utc::now();
+        let path = Self::path_for(&self.target_database);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Removes the sidecar file — called once a migration reaches
+    /// `MigrationPhase::Completed`, and by `rollback`, so neither a
+    /// finished run nor a rolled-back one leaves behind a checkpoint that
+    /// would make a later `migrate` call think there's still work to resume.
+    pub fn delete(target_database: &str) -> Result<()> {
+        let path = Self::path_for(target_database);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
 impl Default for MigrationConfig {
     fn default() -> Self {
         Self {
             source_type: SourceType::GraphitiMcp,
             source_connection: "".to_string(),
+            source_connection_file: None,
             target_database: "data/kg_database.db".to_string(),
             batch_size: 1000,
             validation_enabled: true,
             backup_enabled: true,
             parallel_workers: 4,
             chunk_size: 100,
+            page_size: 10_000,
+            max_retry_attempts: 3,
         }
     }
 }
@@ -231,10 +447,12 @@ pub mod utils {
                 Ok("json") => SourceType::JsonExport,
                 Ok("csv") => SourceType::CsvExport,
                 Ok("custom") => SourceType::CustomFormat,
+                Ok("postgres") => SourceType::Postgres,
                 _ => SourceType::GraphitiMcp,
             },
             source_connection: std::env::var("MIGRATION_SOURCE_CONNECTION")
                 .unwrap_or_else(|_| "".to_string()),
+            source_connection_file: std::env::var("MIGRATION_SOURCE_CONNECTION_FILE").ok(),
             target_database: std::env::var("MIGRATION_TARGET_DB")
                 .unwrap_or_else(|_| "data/kg_database.db".to_string()),
             batch_size: std::env::var("MIGRATION_BATCH_SIZE")
@@ -255,9 +473,17 @@ pub mod utils {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(100),
+            page_size: std::env::var("MIGRATION_PAGE_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10_000),
+            max_retry_attempts: std::env::var("MIGRATION_MAX_RETRY_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
         }
     }
-    
+
     /// Estimate migration complexity based on data characteristics
     pub fn calculate_complexity_score(node_count: usize, edge_count: usize, episode_count: usize) -> f32 {
         let total_items = node_count + edge_count + episode_count;
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{ValidationIssue, ValidationReport};
+use crate::embeddings::LocalEmbeddingEngine;
+use crate::graph::{Episode, KGEdge, KGNode};
+
+/// Outcome of a `DataRemediator::remediate` pass: how many of each kind of
+/// fix were applied, plus any embedding-backfill failures (non-fatal —
+/// remediation keeps going on the rest of the data).
+#[derive(Debug, Clone, Default)]
+pub struct RemediationReport {
+    pub edges_dropped: usize,
+    pub references_stripped: usize,
+    pub weights_clamped: usize,
+    pub embeddings_backfilled: usize,
+    pub errors: Vec<String>,
+}
+
+/// Applies the `suggested_fix` actions `DataValidator::validate_data` only
+/// describes. Acts on a `ValidationReport`'s issue categories directly
+/// against the in-memory collections that produced it: drops edges the
+/// report flagged as orphaned, strips dangling entity/edge references out
+/// of episodes, clamps out-of-range edge weights back into `(0.0, 1.0]`,
+/// and — when an embedding engine is supplied — backfills missing
+/// `episode.embedding`s the same way `handle_add_memory` generates them.
+pub struct DataRemediator {
+    embedding_engine: Option<Arc<LocalEmbeddingEngine>>,
+}
+
+impl DataRemediator {
+    pub fn new(embedding_engine: Option<Arc<LocalEmbeddingEngine>>) -> Self {
+        Self { embedding_engine }
+    }
+
+    /// Repairs `nodes`/`edges`/`episodes` in place based on which issue
+    /// categories `report` actually flagged, so a clean report is a no-op.
+    pub async fn remediate(
+        &self,
+        report: &ValidationReport,
+        nodes: &[KGNode],
+        edges: &mut Vec<KGEdge>,
+        episodes: &mut Vec<Episode>,
+    ) -> Result<RemediationReport> {
+        let mut remediation = RemediationReport::default();
+
+        let node_ids: HashSet<Uuid> = nodes.iter().map(|n| n.uuid).collect();
+
+        if has_category(report, "Orphaned Edges") {
+            let before = edges.len();
+            edges.retain(|edge| {
+                node_ids.contains(&edge.source_node_uuid) && node_ids.contains(&edge.target_node_uuid)
+            });
+            remediation.edges_dropped = before - edges.len();
+        }
+
+        let edge_ids: HashSet<Uuid> = edges.iter().map(|e| e.uuid).collect();
+
+        if has_category(report, "Invalid References") {
+            for episode in episodes.iter_mut() {
+                let before = episode.entity_uuids.len() + episode.edge_uuids.len();
+                episode.entity_uuids.retain(|uuid| node_ids.contains(uuid));
+                episode.edge_uuids.retain(|uuid| edge_ids.contains(uuid));
+                remediation.references_stripped +=
+                    before - (episode.entity_uuids.len() + episode.edge_uuids.len());
+            }
+        }
+
+        if has_category(report, "Data Quality") {
+            for edge in edges.iter_mut() {
+                if edge.weight <= 0.0 || edge.weight > 1.0 {
+                    edge.weight = edge.weight.clamp(0.01, 1.0);
+                    remediation.weights_clamped += 1;
+                }
+            }
+        }
+
+        if has_category(report, "Missing Embeddings") {
+            if let Some(embedding_engine) = &self.embedding_engine {
+                for episode in episodes.iter_mut() {
+                    if episode.embedding.is_some() {
+                        continue;
+                    }
+                    match embedding_engine.encode_text(&episode.content).await {
+                        Ok(embedding) => {
+                            episode.embedding = Some(embedding);
+                            remediation.embeddings_backfilled += 1;
+                        }
+                        Err(e) => {
+                            remediation.errors.push(format!(
+                                "Failed to backfill embedding for episode {}: {}",
+                                episode.uuid, e
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(remediation)
+    }
+}
+
+fn has_category(report: &ValidationReport, category: &str) -> bool {
+    report.issues.iter().any(|issue: &ValidationIssue| issue.category == category)
+}
@@ -0,0 +1,158 @@
+//! Versioned, reversible SQLite schema migrations for `kg-migrate`, modeled
+//! on migra/diesel/sea-orm: each migration is a timestamped directory
+//! (`YYMMDDHHMMSS_<slug>/up.sql` + `down.sql`) under a migrations
+//! directory, applied in lexicographic (i.e. chronological) order and
+//! tracked in a `_kg_schema_migrations(version, applied_at)` table.
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One migration directory: `up_sql` applied going forward, `down_sql`
+/// applied when rolling back.
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub version: String,
+    pub name: String,
+    pub up_sql: String,
+    pub down_sql: String,
+}
+
+/// Slugifies a migration name the way `make` does: lowercase, with any
+/// character outside `[0-9a-z]` replaced by `_`.
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_digit() || c.is_ascii_lowercase() { c } else { '_' })
+        .collect()
+}
+
+/// Creates a new `<timestamp>_<slug>/` directory under `migrations_dir`
+/// with empty `up.sql`/`down.sql` stubs, returning the directory path.
+/// `timestamp` is expected in `YYMMDDHHMMSS` form.
+pub fn make_migration(migrations_dir: &Path, name: &str, timestamp: &str) -> Result<PathBuf> {
+    let dir_name = format!("{}_{}", timestamp, slugify(name));
+    let dir = migrations_dir.join(&dir_name);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create migration directory {}", dir.display()))?;
+    fs::write(dir.join("up.sql"), "-- Write your forward migration here\n")
+        .with_context(|| format!("Failed to write {}/up.sql", dir.display()))?;
+    fs::write(dir.join("down.sql"), "-- Write the rollback for this migration here\n")
+        .with_context(|| format!("Failed to write {}/down.sql", dir.display()))?;
+    Ok(dir)
+}
+
+/// Reads all migration directories under `migrations_dir`, sorted
+/// lexicographically by their `<timestamp>_<slug>` directory name — which
+/// is also chronological order, since the timestamp prefix is fixed-width.
+/// Returns an empty list if the directory doesn't exist yet.
+pub fn load_migrations(migrations_dir: &Path) -> Result<Vec<Migration>> {
+    if !migrations_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs: Vec<PathBuf> = fs::read_dir(migrations_dir)
+        .with_context(|| format!("Failed to read migrations directory {}", migrations_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    dirs.into_iter().map(|dir| load_migration(&dir)).collect()
+}
+
+fn load_migration(dir: &Path) -> Result<Migration> {
+    let dir_name = dir.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let (version, name) = dir_name.split_once('_').unwrap_or((dir_name.as_str(), ""));
+
+    let up_sql = fs::read_to_string(dir.join("up.sql"))
+        .with_context(|| format!("Missing or unreadable up.sql in {}", dir.display()))?;
+    let down_sql = fs::read_to_string(dir.join("down.sql"))
+        .with_context(|| format!("Missing or unreadable down.sql in {}", dir.display()))?;
+
+    Ok(Migration {
+        version: version.to_string(),
+        name: name.to_string(),
+        up_sql,
+        down_sql,
+    })
+}
+
+fn ensure_migrations_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS _kg_schema_migrations (
+            version TEXT PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn applied_versions(conn: &Connection) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT version FROM _kg_schema_migrations")?;
+    let versions = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<HashSet<String>>>()?;
+    Ok(versions)
+}
+
+/// Applies every migration in `migrations` whose version isn't yet
+/// recorded in `_kg_schema_migrations`, in order, recording each version
+/// immediately after its `up.sql` runs so a failure partway through still
+/// leaves already-applied migrations marked applied. Returns the versions
+/// that were newly applied.
+pub fn apply_pending(conn: &Connection, migrations: &[Migration]) -> Result<Vec<String>> {
+    ensure_migrations_table(conn)?;
+    let applied = applied_versions(conn)?;
+
+    let mut newly_applied = Vec::new();
+    for migration in migrations {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        conn.execute_batch(&migration.up_sql)
+            .with_context(|| format!("Migration {} ({}) failed to apply", migration.version, migration.name))?;
+        conn.execute(
+            "INSERT INTO _kg_schema_migrations (version, applied_at) VALUES (?1, ?2)",
+            rusqlite::params![migration.version, chrono::Utc::now().to_rfc3339()],
+        )?;
+        newly_applied.push(migration.version.clone());
+    }
+
+    Ok(newly_applied)
+}
+
+/// Rolls back the last `count` applied migrations, most-recently-applied
+/// first, running each one's `down.sql` and deleting its row from
+/// `_kg_schema_migrations`. Returns the versions that were rolled back, in
+/// the order they were rolled back.
+pub fn rollback(conn: &Connection, migrations: &[Migration], count: usize) -> Result<Vec<String>> {
+    ensure_migrations_table(conn)?;
+    let applied = applied_versions(conn)?;
+
+    let mut applied_in_order: Vec<&Migration> = migrations.iter()
+        .filter(|migration| applied.contains(&migration.version))
+        .collect();
+    applied_in_order.reverse();
+
+    let mut rolled_back = Vec::new();
+    for migration in applied_in_order.into_iter().take(count) {
+        conn.execute_batch(&migration.down_sql)
+            .with_context(|| format!("Migration {} ({}) failed to roll back", migration.version, migration.name))?;
+        conn.execute(
+            "DELETE FROM _kg_schema_migrations WHERE version = ?1",
+            rusqlite::params![migration.version],
+        )?;
+        rolled_back.push(migration.version.clone());
+    }
+
+    Ok(rolled_back)
+}
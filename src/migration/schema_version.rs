@@ -0,0 +1,136 @@
+//! Versioned upgrade chain for Graphiti source data. Some exports carry a
+//! `version` property per node/edge/episode (or none at all, in which case
+//! the oldest supported shape is assumed) written by an older graphiti-mcp
+//! release. Rather than `convert_node`/`convert_edge`/`convert_episode` each
+//! having to know every historical property layout, a small ordered chain of
+//! steps upgrades the raw property map one version at a time before
+//! conversion ever sees it - so supporting a newer export shape is one new
+//! `UpgradeStep`, not a rewrite of the conversion functions.
+
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Oldest version this chain knows how to start from - a source that never
+/// writes a `version` property predates the property itself, so it's
+/// assumed to be this old rather than rejected outright.
+pub const BASELINE_VERSION: &str = "v0.8";
+
+/// One rung of the upgrade ladder: `from` must match a property map's
+/// current version exactly for `apply` to run; the caller advances the
+/// tracked version to `to` once it has.
+pub struct UpgradeStep {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub description: &'static str,
+    pub apply: fn(&mut HashMap<String, Value>),
+}
+
+/// The ordered v0.8 -> v0.9 -> v1.0 chain, registered as a flat list rather
+/// than a lookup table - each step only ever needs to match the immediately
+/// preceding version, so `upgrade_properties`/`plan_upgrades` just walk it.
+pub fn upgrade_chain() -> Vec<UpgradeStep> {
+    vec![
+        UpgradeStep {
+            from: "v0.8",
+            to: "v0.9",
+            description: "Rename legacy `label` property to `type`",
+            apply: |properties| {
+                if let Some(label) = properties.remove("label") {
+                    properties.entry("type".to_string()).or_insert(label);
+                }
+            },
+        },
+        UpgradeStep {
+            from: "v0.9",
+            to: "v1.0",
+            description: "Default a missing `weight` property to 1.0",
+            apply: |properties| {
+                properties
+                    .entry("weight".to_string())
+                    .or_insert_with(|| Value::from(1.0));
+            },
+        },
+    ]
+}
+
+/// The newest version `upgrade_chain` upgrades to - conversion assumes its
+/// input is already this version.
+pub fn current_version() -> &'static str {
+    upgrade_chain().last().map(|step| step.to).unwrap_or(BASELINE_VERSION)
+}
+
+/// Reads a `version` property as a string, defaulting to `BASELINE_VERSION`
+/// when it's absent.
+pub fn detect_version(properties: &HashMap<String, Value>) -> String {
+    properties
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or(BASELINE_VERSION)
+        .to_string()
+}
+
+/// Walks `upgrade_chain` starting at `properties`'s detected version,
+/// applying every step whose `from` matches in turn, until either
+/// `current_version()` is reached or no further step matches. A version
+/// that never matches any step (not `BASELINE_VERSION`, not the `to` of any
+/// step) is left exactly as found - `plan_upgrades` is how a caller checks
+/// for that ahead of time instead of discovering it mid-migration. Returns
+/// the description of every step actually applied, and removes the
+/// `version` marker once the map reaches `current_version()` since
+/// conversion doesn't need it past this point.
+pub fn upgrade_properties(properties: &mut HashMap<String, Value>) -> Vec<&'static str> {
+    let mut applied = Vec::new();
+    let mut version = detect_version(properties);
+
+    loop {
+        let chain = upgrade_chain();
+        let Some(step) = chain.iter().find(|s| s.from == version) else {
+            break;
+        };
+        (step.apply)(properties);
+        applied.push(step.description);
+        version = step.to.to_string();
+    }
+
+    if version == current_version() {
+        properties.remove("version");
+    } else {
+        properties.insert("version".to_string(), Value::String(version));
+    }
+
+    applied
+}
+
+/// Dry-run counterpart to `upgrade_properties` for `analyze_source`: given
+/// the versions found across a sample of source items, reports which
+/// upgrade steps would run (in order: for each distinct version that isn't
+/// already current, one line per step that would run for it), plus the
+/// distinct versions that have no known migration path at all.
+pub fn plan_upgrades(sample_versions: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut steps_that_would_run = Vec::new();
+    let mut unknown_versions = Vec::new();
+    let known_froms: HashSet<&str> = upgrade_chain().iter().map(|s| s.from).collect();
+
+    let mut seen = HashSet::new();
+    for version in sample_versions {
+        if version == current_version() || !seen.insert(version.clone()) {
+            continue;
+        }
+        if !known_froms.contains(version.as_str()) {
+            unknown_versions.push(version.clone());
+            continue;
+        }
+
+        let mut v = version.clone();
+        loop {
+            let chain = upgrade_chain();
+            let Some(step) = chain.iter().find(|s| s.from == v) else {
+                break;
+            };
+            steps_that_would_run.push(format!("{} -> {}: {}", step.from, step.to, step.description));
+            v = step.to.to_string();
+        }
+    }
+
+    (steps_that_would_run, unknown_versions)
+}
@@ -4,6 +4,8 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
 use super::{ValidationReport, ValidationIssue, ValidationSeverity};
+use crate::embeddings::cosine_similarity;
+use crate::graph::storage::GraphStorage;
 use crate::graph::{KGNode, KGEdge, Episode};
 
 /// Data validator for migration quality assurance
@@ -21,6 +23,18 @@ pub struct ValidationConfig {
     pub check_embedding_quality: bool,
     pub strict_mode: bool,
     pub performance_threshold: f32,
+    /// Blended name/embedding similarity (0.0-1.0) above which two nodes of
+    /// the same type are flagged as probable duplicates by
+    /// `check_fuzzy_duplicate_entities`.
+    pub duplicate_similarity_threshold: f32,
+    /// Connected components smaller than this (in nodes) are flagged as a
+    /// "Disconnected Subgraph" issue by `check_graph_connectivity`.
+    pub min_component_size: usize,
+    /// When set, any episode embedding whose dimension doesn't match this
+    /// value is flagged as an `Error`-level "Embedding Inconsistency" issue
+    /// by `check_embedding_quality`, even if it is the only embedding
+    /// present (a single consistently-wrong dimension otherwise passes).
+    pub expected_embedding_dim: Option<usize>,
 }
 
 /// Detailed validation statistics
@@ -35,6 +49,78 @@ pub struct ValidationStats {
     pub duplicate_entities: usize,
     pub performance_score: f32,
     pub validation_duration: std::time::Duration,
+    /// Number of connected components found by `check_graph_connectivity`.
+    pub component_count: usize,
+    /// Node count of the largest connected component.
+    pub largest_component_size: usize,
+    /// Nodes with zero edges (their own singleton component).
+    pub isolated_nodes: usize,
+    /// Component id per node, for downstream visualization/pruning of
+    /// orphaned clusters.
+    pub component_membership: HashMap<Uuid, usize>,
+}
+
+/// Result of `DataValidator::check_graph_connectivity`.
+struct ConnectivityReport {
+    issues: Vec<ValidationIssue>,
+    component_count: usize,
+    largest_component_size: usize,
+    isolated_node_count: usize,
+    component_membership: HashMap<Uuid, usize>,
+}
+
+/// Minimal union-find (disjoint-set) with path compression and union by
+/// size, used to group node UUIDs into connected components.
+struct UnionFind {
+    parent: HashMap<Uuid, Uuid>,
+    size: HashMap<Uuid, usize>,
+}
+
+impl UnionFind {
+    fn new(uuids: impl Iterator<Item = Uuid>) -> Self {
+        let mut uf = Self { parent: HashMap::new(), size: HashMap::new() };
+        for uuid in uuids {
+            uf.insert(uuid);
+        }
+        uf
+    }
+
+    /// Adds a single UUID as its own singleton set, if not already present.
+    /// Lets callers build the set incrementally across paginated reads
+    /// instead of collecting every UUID before constructing a `UnionFind`.
+    fn insert(&mut self, uuid: Uuid) {
+        self.parent.entry(uuid).or_insert(uuid);
+        self.size.entry(uuid).or_insert(1);
+    }
+
+    fn find(&mut self, uuid: Uuid) -> Uuid {
+        let root = self.parent[&uuid];
+        if root == uuid {
+            return uuid;
+        }
+        let root = self.find(root);
+        self.parent.insert(uuid, root);
+        root
+    }
+
+    fn union(&mut self, a: Uuid, b: Uuid) {
+        if !self.parent.contains_key(&a) || !self.parent.contains_key(&b) {
+            // One endpoint references a non-existent node; referential
+            // integrity checks already flag this elsewhere.
+            return;
+        }
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return;
+        }
+        let (small, large) = if self.size[&root_a] < self.size[&root_b] {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        self.parent.insert(small, large);
+        *self.size.get_mut(&large).unwrap() += self.size[&small];
+    }
 }
 
 impl DataValidator {
@@ -45,13 +131,18 @@ impl DataValidator {
         }
     }
 
-    /// Comprehensive data validation
+    /// Comprehensive data validation. `node_embeddings`, when supplied,
+    /// lets `check_fuzzy_duplicate_entities` factor embedding cosine
+    /// similarity into its blended score; callers without node-level
+    /// embeddings on hand (e.g. a pre-embedding migration pass) can pass
+    /// `None` and it falls back to name similarity alone.
     pub async fn validate_data(
         &self,
         nodes: &[KGNode],
         edges: &[KGEdge],
         episodes: &[Episode],
         config: &ValidationConfig,
+        node_embeddings: Option<&HashMap<Uuid, Vec<f32>>>,
     ) -> Result<ValidationReport> {
         let start_time = std::time::Instant::now();
         let mut issues = Vec::new();
@@ -90,20 +181,34 @@ impl DataValidator {
             issues.extend(consistency_issues);
         }
 
-        // 4. Performance Metrics
+        // 4. Fuzzy Duplicate Entity Resolution
+        let duplicate_issues = self.check_fuzzy_duplicate_entities(nodes, node_embeddings, config).await?;
+        if !duplicate_issues.is_empty() {
+            consistency_score -= 0.1;
+            issues.extend(duplicate_issues);
+        }
+
+        // 5. Performance Metrics
         if config.check_performance_metrics {
             performance_score = self.calculate_performance_score(nodes, edges, episodes).await?;
         }
 
-        // 5. Embedding Quality Checks
+        // 6. Embedding Quality Checks
         if config.check_embedding_quality {
-            let embedding_issues = self.check_embedding_quality(episodes).await?;
+            let embedding_issues = self.check_embedding_quality(episodes, config).await?;
             if !embedding_issues.is_empty() {
                 completeness_score -= 0.1;
                 issues.extend(embedding_issues);
             }
         }
 
+        // 7. Graph Connectivity Checks
+        let connectivity = self.check_graph_connectivity(nodes, edges, config)?;
+        if !connectivity.issues.is_empty() {
+            consistency_score -= 0.1;
+            issues.extend(connectivity.issues);
+        }
+
         // Generate recommendations based on issues found
         let recommendations = self.generate_recommendations(&issues, &ValidationStats {
             total_nodes: nodes.len(),
@@ -115,6 +220,10 @@ impl DataValidator {
             duplicate_entities: issues.iter().filter(|i| i.category == "Duplicate Entities").count(),
             performance_score,
             validation_duration: start_time.elapsed(),
+            component_count: connectivity.component_count,
+            largest_component_size: connectivity.largest_component_size,
+            isolated_nodes: connectivity.isolated_node_count,
+            component_membership: connectivity.component_membership,
         });
 
         Ok(ValidationReport {
@@ -127,6 +236,312 @@ impl DataValidator {
         })
     }
 
+    /// Cursor-based entry point for graphs too large to load into a single
+    /// `&[KGNode]`/`&[KGEdge]`/`&[Episode]` triple: walks `storage` a
+    /// `chunk_size`-row page at a time instead of materializing everything
+    /// `validate_data` would. First pass reads only node UUIDs (plus a
+    /// small name+type index for exact-duplicate detection) into memory;
+    /// the second and third passes stream edges and episodes, checking
+    /// references against that UUID set without ever holding every edge or
+    /// episode at once. Memory scales with the number of *distinct* nodes
+    /// and issues found, not with the total edge/episode count.
+    ///
+    /// This covers the same referential-integrity, completeness,
+    /// consistency, and connectivity checks as `validate_data`. Fuzzy
+    /// duplicate-entity resolution and deep embedding vector-health checks
+    /// are intentionally out of scope here: both need either full name
+    /// sets for blocking or full embedding vectors for cosine similarity,
+    /// which would defeat the bounded-memory guarantee this entry point
+    /// exists for. Use `validate_data` on a sampled subset if those checks
+    /// are needed for a graph this large.
+    pub async fn validate_stream(
+        &self,
+        storage: &GraphStorage,
+        config: &ValidationConfig,
+        chunk_size: usize,
+    ) -> Result<ValidationReport> {
+        let start_time = std::time::Instant::now();
+        let mut issues = Vec::new();
+
+        // Pass 1: node UUIDs + a compact name/type index, nothing else.
+        let mut node_ids: HashSet<Uuid> = HashSet::new();
+        let mut name_type_index: HashMap<(String, String), Vec<Uuid>> = HashMap::new();
+        let mut uf = UnionFind::new(std::iter::empty());
+        let mut missing_name_or_type = 0usize;
+        let mut total_nodes = 0usize;
+
+        let mut offset = 0;
+        loop {
+            let page = storage.get_nodes_page(offset, chunk_size)?;
+            if page.is_empty() {
+                break;
+            }
+            for node in &page {
+                node_ids.insert(node.uuid);
+                uf.insert(node.uuid);
+                name_type_index.entry((node.name.clone(), node.node_type.clone())).or_default().push(node.uuid);
+                if node.name.trim().is_empty() || node.node_type.trim().is_empty() {
+                    missing_name_or_type += 1;
+                }
+            }
+            total_nodes += page.len();
+            offset += page.len();
+        }
+
+        for ((name, node_type), uuids) in &name_type_index {
+            if uuids.len() > 1 {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Warning,
+                    category: "Duplicate Entities".to_string(),
+                    description: format!("Found {} nodes with same name '{}' and type '{}'", uuids.len(), name, node_type),
+                    affected_items: uuids.iter().map(|u| u.to_string()).collect(),
+                    suggested_fix: Some("Consider merging duplicate entities or adding distinguishing information".to_string()),
+                });
+            }
+        }
+        if missing_name_or_type > 0 {
+            issues.push(ValidationIssue {
+                severity: ValidationSeverity::Warning,
+                category: "Missing Data".to_string(),
+                description: format!("{} nodes have an empty name or type", missing_name_or_type),
+                affected_items: vec![],
+                suggested_fix: Some("Provide a meaningful name and type for these nodes".to_string()),
+            });
+        }
+
+        // Pass 2: stream edges, validating references against `node_ids`
+        // and folding endpoints into the union-find, without collecting
+        // every edge. `edge_ids`/`connected_node_ids`/the relationship
+        // dedup index are bounded by edge count, not edge *content*.
+        let mut edge_ids: HashSet<Uuid> = HashSet::new();
+        let mut connected_node_ids: HashSet<Uuid> = HashSet::new();
+        let mut relationship_index: HashMap<(Uuid, Uuid, String), Vec<Uuid>> = HashMap::new();
+        let mut total_edges = 0usize;
+        let mut orphaned_edges = 0usize;
+
+        offset = 0;
+        loop {
+            let page = storage.get_edges_page(offset, chunk_size)?;
+            if page.is_empty() {
+                break;
+            }
+            for edge in &page {
+                edge_ids.insert(edge.uuid);
+                let source_ok = node_ids.contains(&edge.source_node_uuid);
+                let target_ok = node_ids.contains(&edge.target_node_uuid);
+
+                if !source_ok {
+                    orphaned_edges += 1;
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Error,
+                        category: "Orphaned Edges".to_string(),
+                        description: format!("Edge {} has invalid source node {}", edge.uuid, edge.source_node_uuid),
+                        affected_items: vec![edge.uuid.to_string()],
+                        suggested_fix: Some("Remove edge or add missing source node".to_string()),
+                    });
+                }
+                if !target_ok {
+                    orphaned_edges += 1;
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Error,
+                        category: "Orphaned Edges".to_string(),
+                        description: format!("Edge {} has invalid target node {}", edge.uuid, edge.target_node_uuid),
+                        affected_items: vec![edge.uuid.to_string()],
+                        suggested_fix: Some("Remove edge or add missing target node".to_string()),
+                    });
+                }
+                if source_ok && target_ok {
+                    uf.union(edge.source_node_uuid, edge.target_node_uuid);
+                    connected_node_ids.insert(edge.source_node_uuid);
+                    connected_node_ids.insert(edge.target_node_uuid);
+                }
+
+                if edge.relation_type.trim().is_empty() {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Warning,
+                        category: "Missing Data".to_string(),
+                        description: format!("Edge {} has empty relation type", edge.uuid),
+                        affected_items: vec![edge.uuid.to_string()],
+                        suggested_fix: Some("Assign a meaningful relation type to the edge".to_string()),
+                    });
+                }
+                if edge.weight <= 0.0 || edge.weight > 1.0 {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Info,
+                        category: "Data Quality".to_string(),
+                        description: format!("Edge {} has unusual weight: {}", edge.uuid, edge.weight),
+                        affected_items: vec![edge.uuid.to_string()],
+                        suggested_fix: Some("Verify edge weight is in expected range (0.0-1.0)".to_string()),
+                    });
+                }
+
+                let key = (edge.source_node_uuid, edge.target_node_uuid, edge.relation_type.clone());
+                relationship_index.entry(key).or_default().push(edge.uuid);
+            }
+            total_edges += page.len();
+            offset += page.len();
+        }
+
+        for ((source, target, relation), uuids) in &relationship_index {
+            if uuids.len() > 1 {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Info,
+                    category: "Duplicate Relationships".to_string(),
+                    description: format!("Found {} duplicate '{}' relationships between {} and {}", uuids.len(), relation, source, target),
+                    affected_items: uuids.iter().map(|u| u.to_string()).collect(),
+                    suggested_fix: Some("Consider consolidating duplicate relationships".to_string()),
+                });
+            }
+        }
+
+        // Pass 3: stream episodes, checking references and embedding
+        // coverage without collecting every episode.
+        let mut total_episodes = 0usize;
+        let mut episodes_with_embeddings = 0usize;
+        let mut invalid_references = 0usize;
+
+        offset = 0;
+        loop {
+            let page = storage.get_episodes_page(offset, chunk_size)?;
+            if page.is_empty() {
+                break;
+            }
+            for episode in &page {
+                if episode.embedding.is_some() {
+                    episodes_with_embeddings += 1;
+                }
+                if episode.content.trim().is_empty() {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Error,
+                        category: "Missing Data".to_string(),
+                        description: format!("Episode {} has empty content", episode.uuid),
+                        affected_items: vec![episode.uuid.to_string()],
+                        suggested_fix: Some("Provide content for the episode".to_string()),
+                    });
+                }
+                if episode.entity_uuids.is_empty() && episode.edge_uuids.is_empty() {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Warning,
+                        category: "Missing Data".to_string(),
+                        description: format!("Episode {} has no entity or edge references", episode.uuid),
+                        affected_items: vec![episode.uuid.to_string()],
+                        suggested_fix: Some("Add entity or edge references to the episode".to_string()),
+                    });
+                }
+                for entity_uuid in &episode.entity_uuids {
+                    if !node_ids.contains(entity_uuid) {
+                        invalid_references += 1;
+                        issues.push(ValidationIssue {
+                            severity: ValidationSeverity::Warning,
+                            category: "Invalid References".to_string(),
+                            description: format!("Episode {} references non-existent entity {}", episode.uuid, entity_uuid),
+                            affected_items: vec![episode.uuid.to_string()],
+                            suggested_fix: Some("Remove invalid entity reference or add missing entity".to_string()),
+                        });
+                    }
+                }
+                for edge_uuid in &episode.edge_uuids {
+                    if !edge_ids.contains(edge_uuid) {
+                        invalid_references += 1;
+                        issues.push(ValidationIssue {
+                            severity: ValidationSeverity::Warning,
+                            category: "Invalid References".to_string(),
+                            description: format!("Episode {} references non-existent edge {}", episode.uuid, edge_uuid),
+                            affected_items: vec![episode.uuid.to_string()],
+                            suggested_fix: Some("Remove invalid edge reference or add missing edge".to_string()),
+                        });
+                    }
+                }
+            }
+            total_episodes += page.len();
+            offset += page.len();
+        }
+
+        if total_episodes > 0 {
+            let embedding_coverage = episodes_with_embeddings as f32 / total_episodes as f32;
+            if embedding_coverage < 0.5 {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Warning,
+                    category: "Missing Embeddings".to_string(),
+                    description: format!("Only {:.1}% of episodes have embeddings", embedding_coverage * 100.0),
+                    affected_items: vec![],
+                    suggested_fix: Some("Generate embeddings for episodes to enable semantic search".to_string()),
+                });
+            }
+        }
+
+        // Component stats, derived from the union-find built across passes
+        // 1-2 rather than a fourth full pass over the data.
+        let mut component_ids: HashMap<Uuid, usize> = HashMap::new();
+        let mut component_membership: HashMap<Uuid, usize> = HashMap::new();
+        let mut component_members: HashMap<usize, Vec<Uuid>> = HashMap::new();
+        let mut isolated_nodes = 0usize;
+        for &node_uuid in &node_ids {
+            let root = uf.find(node_uuid);
+            let next_id = component_ids.len();
+            let component_id = *component_ids.entry(root).or_insert(next_id);
+            component_membership.insert(node_uuid, component_id);
+            component_members.entry(component_id).or_default().push(node_uuid);
+
+            if !connected_node_ids.contains(&node_uuid) {
+                isolated_nodes += 1;
+            }
+        }
+        let largest_component_size = component_members.values().map(|m| m.len()).max().unwrap_or(0);
+        for members in component_members.values() {
+            if members.len() > 1 && members.len() < config.min_component_size {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Warning,
+                    category: "Disconnected Subgraph".to_string(),
+                    description: format!(
+                        "Found a {}-node component disconnected from the main graph (minimum expected: {})",
+                        members.len(), config.min_component_size
+                    ),
+                    affected_items: members.iter().map(|u| u.to_string()).collect(),
+                    suggested_fix: Some("Investigate whether this cluster was fully ingested or merged into the main graph".to_string()),
+                });
+            }
+        }
+
+        let data_integrity_score = if orphaned_edges > 0 || invalid_references > 0 { 0.7 } else { 1.0 };
+        let completeness_score = if missing_name_or_type > 0 { 0.8 } else { 1.0 };
+        let consistency_score = if issues.iter().any(|i| {
+            matches!(i.category.as_str(), "Duplicate Entities" | "Duplicate Relationships" | "Disconnected Subgraph")
+        }) { 0.8 } else { 1.0 };
+        let performance_score = if total_nodes == 0 {
+            config.performance_threshold
+        } else {
+            let avg_connections = total_edges as f32 / total_nodes as f32;
+            ((avg_connections / 10.0).min(1.0) * 0.5 + config.performance_threshold * 0.5).min(1.0)
+        };
+
+        let stats = ValidationStats {
+            total_nodes,
+            total_edges,
+            total_episodes,
+            orphaned_edges,
+            invalid_references,
+            missing_embeddings: total_episodes.saturating_sub(episodes_with_embeddings),
+            duplicate_entities: issues.iter().filter(|i| i.category == "Duplicate Entities").count(),
+            performance_score,
+            validation_duration: start_time.elapsed(),
+            component_count: component_members.len(),
+            largest_component_size,
+            isolated_nodes,
+            component_membership,
+        };
+        let recommendations = self.generate_recommendations(&issues, &stats);
+
+        Ok(ValidationReport {
+            data_integrity_score,
+            completeness_score,
+            consistency_score,
+            performance_score,
+            issues,
+            recommendations,
+        })
+    }
+
     /// Check referential integrity between nodes, edges, and episodes
     async fn check_referential_integrity(
         &self,
@@ -334,8 +749,173 @@ impl DataValidator {
         Ok(issues)
     }
 
+    /// Union-find over node UUIDs, unioned across every edge, to surface
+    /// knowledge-graph regions unreachable from the main body (incomplete
+    /// ingestion, broken merges). Fully isolated nodes (zero edges) are
+    /// their own singleton component and are reported separately from
+    /// multi-node islands.
+    fn check_graph_connectivity(
+        &self,
+        nodes: &[KGNode],
+        edges: &[KGEdge],
+        config: &ValidationConfig,
+    ) -> Result<ConnectivityReport> {
+        let mut uf = UnionFind::new(nodes.iter().map(|n| n.uuid));
+
+        for edge in edges {
+            uf.union(edge.source_node_uuid, edge.target_node_uuid);
+        }
+
+        let mut component_nodes: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for node in nodes {
+            component_nodes.entry(uf.find(node.uuid)).or_default().push(node.uuid);
+        }
+
+        let connected: HashSet<Uuid> = edges
+            .iter()
+            .flat_map(|e| [e.source_node_uuid, e.target_node_uuid])
+            .collect();
+
+        let mut issues = Vec::new();
+        let mut largest_component_size = 0;
+        let mut isolated_node_count = 0;
+
+        for members in component_nodes.values() {
+            largest_component_size = largest_component_size.max(members.len());
+
+            if members.len() == 1 {
+                let node_uuid = members[0];
+                if !connected.contains(&node_uuid) {
+                    isolated_node_count += 1;
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Warning,
+                        category: "Isolated Node".to_string(),
+                        description: format!("Node {} has no edges and is unreachable from the rest of the graph", node_uuid),
+                        affected_items: vec![node_uuid.to_string()],
+                        suggested_fix: Some("Connect this node to related entities or confirm it is intentionally standalone".to_string()),
+                    });
+                }
+            } else if members.len() < config.min_component_size {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Warning,
+                    category: "Disconnected Subgraph".to_string(),
+                    description: format!(
+                        "Found a {}-node component disconnected from the main graph (minimum expected: {})",
+                        members.len(), config.min_component_size
+                    ),
+                    affected_items: members.iter().map(|u| u.to_string()).collect(),
+                    suggested_fix: Some("Investigate whether this cluster was fully ingested or merged into the main graph".to_string()),
+                });
+            }
+        }
+
+        let component_membership: HashMap<Uuid, usize> = component_nodes
+            .values()
+            .enumerate()
+            .flat_map(|(component_id, members)| members.iter().map(move |uuid| (*uuid, component_id)))
+            .collect();
+
+        Ok(ConnectivityReport {
+            issues,
+            component_count: component_nodes.len(),
+            largest_component_size,
+            isolated_node_count,
+            component_membership,
+        })
+    }
+
+    /// Find probable duplicate entities that a plain name+type match misses:
+    /// near-identical spellings, abbreviations, or the same node re-embedded
+    /// slightly differently. Candidates are blocked by `node_type` plus a
+    /// cheap bucket key (the lowercased first token of the name) so only
+    /// nodes that could plausibly match are ever compared pairwise, instead
+    /// of every node against every other node in the graph.
+    async fn check_fuzzy_duplicate_entities(
+        &self,
+        nodes: &[KGNode],
+        node_embeddings: Option<&HashMap<Uuid, Vec<f32>>>,
+        config: &ValidationConfig,
+    ) -> Result<Vec<ValidationIssue>> {
+        let mut issues = Vec::new();
+
+        let mut buckets: HashMap<(String, String), Vec<&KGNode>> = HashMap::new();
+        for node in nodes {
+            let bucket_key = node
+                .name
+                .trim()
+                .to_lowercase()
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_string();
+            buckets.entry((node.node_type.clone(), bucket_key)).or_default().push(node);
+        }
+
+        let mut seen_pairs: HashSet<(Uuid, Uuid)> = HashSet::new();
+
+        for candidates in buckets.values() {
+            if candidates.len() < 2 {
+                continue;
+            }
+
+            for i in 0..candidates.len() {
+                for j in (i + 1)..candidates.len() {
+                    let a = candidates[i];
+                    let b = candidates[j];
+                    if a.uuid == b.uuid {
+                        continue;
+                    }
+                    let pair = if a.uuid < b.uuid { (a.uuid, b.uuid) } else { (b.uuid, a.uuid) };
+                    if !seen_pairs.insert(pair) {
+                        continue;
+                    }
+
+                    // Exact name+type duplicates are already reported by
+                    // `check_data_consistency`; this check only needs to
+                    // surface the near-duplicate case.
+                    if a.name == b.name {
+                        continue;
+                    }
+
+                    let name_similarity = normalized_name_similarity(&a.name, &b.name);
+
+                    let embedding_similarity = match node_embeddings {
+                        Some(embeddings) => match (embeddings.get(&a.uuid), embeddings.get(&b.uuid)) {
+                            (Some(ea), Some(eb)) => Some(cosine_similarity(ea, eb)),
+                            _ => None,
+                        },
+                        None => None,
+                    };
+
+                    let blended_score = match embedding_similarity {
+                        Some(embedding_similarity) => (name_similarity * 0.4) + (embedding_similarity * 0.6),
+                        None => name_similarity,
+                    };
+
+                    if blended_score >= config.duplicate_similarity_threshold {
+                        issues.push(ValidationIssue {
+                            severity: ValidationSeverity::Info,
+                            category: "Probable Duplicate Entities".to_string(),
+                            description: format!(
+                                "Nodes '{}' and '{}' ({}) look like the same entity (score {:.2})",
+                                a.name, b.name, a.node_type, blended_score
+                            ),
+                            affected_items: vec![a.uuid.to_string(), b.uuid.to_string()],
+                            suggested_fix: Some(format!(
+                                "Merge into a single canonical node (suggested: '{}')",
+                                canonical_candidate(a, b)
+                            )),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
     /// Check embedding quality and coverage
-    async fn check_embedding_quality(&self, episodes: &[Episode]) -> Result<Vec<ValidationIssue>> {
+    async fn check_embedding_quality(&self, episodes: &[Episode], config: &ValidationConfig) -> Result<Vec<ValidationIssue>> {
         let mut issues = Vec::new();
 
         let episodes_with_embeddings = episodes.iter().filter(|e| e.embedding.is_some()).count();
@@ -343,7 +923,7 @@ impl DataValidator {
 
         if total_episodes > 0 {
             let embedding_coverage = episodes_with_embeddings as f32 / total_episodes as f32;
-            
+
             if embedding_coverage < 0.5 {
                 issues.push(ValidationIssue {
                     severity: ValidationSeverity::Warning,
@@ -371,6 +951,100 @@ impl DataValidator {
                     suggested_fix: Some("Ensure all embeddings use the same model and dimensions".to_string()),
                 });
             }
+
+            // A single wrong-but-consistent dimension passes the check
+            // above, so when the caller knows the expected dimension,
+            // compare against it directly.
+            if let Some(expected_dim) = config.expected_embedding_dim {
+                for episode in episodes {
+                    if let Some(ref embedding) = episode.embedding {
+                        if embedding.len() != expected_dim {
+                            issues.push(ValidationIssue {
+                                severity: ValidationSeverity::Error,
+                                category: "Embedding Inconsistency".to_string(),
+                                description: format!(
+                                    "Episode {} embedding has dimension {}, expected {}",
+                                    episode.uuid, embedding.len(), expected_dim
+                                ),
+                                affected_items: vec![episode.uuid.to_string()],
+                                suggested_fix: Some("Regenerate this embedding with the configured embedding model".to_string()),
+                            });
+                        }
+                    }
+                }
+            }
+
+            // Vector-health checks: a broken embedder silently poisons
+            // semantic search, so flag vectors that couldn't have come
+            // from a working model.
+            let mut degenerate_items = Vec::new();
+            for episode in episodes {
+                if let Some(ref embedding) = episode.embedding {
+                    if embedding.is_empty() {
+                        continue;
+                    }
+                    let has_nan_or_inf = embedding.iter().any(|v| !v.is_finite());
+                    let l2_norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+                    let is_all_zero = embedding.iter().all(|v| *v == 0.0);
+
+                    if has_nan_or_inf || is_all_zero || l2_norm < 1e-6 {
+                        degenerate_items.push(episode.uuid.to_string());
+                    }
+                }
+            }
+
+            if !degenerate_items.is_empty() {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Error,
+                    category: "Degenerate Embedding".to_string(),
+                    description: format!(
+                        "{} episode embeddings are all-zero, contain NaN/Inf, or have a near-zero L2 norm",
+                        degenerate_items.len()
+                    ),
+                    affected_items: degenerate_items,
+                    suggested_fix: Some("Check the embedding model/pipeline for failures and regenerate these embeddings".to_string()),
+                });
+            }
+
+            // Exact-duplicate embeddings across distinct episodes usually
+            // mean the embedder fell back to a constant vector.
+            let mut vector_counts: HashMap<Vec<u32>, Vec<String>> = HashMap::new();
+            for episode in episodes {
+                if let Some(ref embedding) = episode.embedding {
+                    if embedding.is_empty() {
+                        continue;
+                    }
+                    let key: Vec<u32> = embedding.iter().map(|v| v.to_bits()).collect();
+                    vector_counts.entry(key).or_default().push(episode.uuid.to_string());
+                }
+            }
+
+            let duplicate_embedding_count: usize = vector_counts
+                .values()
+                .filter(|episode_ids| episode_ids.len() > 1)
+                .map(|episode_ids| episode_ids.len())
+                .sum();
+
+            if episodes_with_embeddings > 0 {
+                let duplicate_fraction = duplicate_embedding_count as f32 / episodes_with_embeddings as f32;
+                if duplicate_fraction > 0.1 {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Warning,
+                        category: "Duplicate Embeddings".to_string(),
+                        description: format!(
+                            "{:.1}% of episode embeddings are exact duplicates of another episode's embedding - the embedder may be returning a constant/fallback vector",
+                            duplicate_fraction * 100.0
+                        ),
+                        affected_items: vector_counts
+                            .values()
+                            .filter(|episode_ids| episode_ids.len() > 1)
+                            .flatten()
+                            .cloned()
+                            .collect(),
+                        suggested_fix: Some("Verify the embedding model is loaded correctly and not returning a fallback vector".to_string()),
+                    });
+                }
+            }
         }
 
         Ok(issues)
@@ -453,6 +1127,13 @@ impl DataValidator {
             recommendations.push("Review and consolidate duplicate entities to reduce redundancy".to_string());
         }
 
+        if stats.component_count > 1 {
+            recommendations.push(format!(
+                "Graph has {} disconnected components (largest: {} nodes, {} isolated) - investigate incomplete ingestion or broken merges",
+                stats.component_count, stats.largest_component_size, stats.isolated_nodes
+            ));
+        }
+
         // Data size recommendations
         if stats.total_nodes > 100000 {
             recommendations.push("Large dataset detected - consider implementing data archiving strategy".to_string());
@@ -466,6 +1147,63 @@ impl DataValidator {
     }
 }
 
+/// Levenshtein distance normalized into a 0.0-1.0 similarity score (1.0 =
+/// identical). Case-insensitive, since entity names commonly differ only in
+/// capitalization.
+fn normalized_name_similarity(a: &str, b: &str) -> f32 {
+    let a = a.trim().to_lowercase();
+    let b = b.trim().to_lowercase();
+
+    if a == b {
+        return 1.0;
+    }
+    let max_len = a.chars().count().max(b.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    1.0 - (levenshtein_distance(&a, &b) as f32 / max_len as f32)
+}
+
+/// Standard DP-matrix Levenshtein distance.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (m, n) = (a.len(), b.len());
+
+    let mut dp = vec![vec![0usize; n + 1]; m + 1];
+    for i in 0..=m {
+        dp[i][0] = i;
+    }
+    for j in 0..=n {
+        dp[0][j] = j;
+    }
+
+    for i in 1..=m {
+        for j in 1..=n {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dp[i][j] = (dp[i - 1][j] + 1)
+                .min(dp[i][j - 1] + 1)
+                .min(dp[i - 1][j - 1] + cost);
+        }
+    }
+
+    dp[m][n]
+}
+
+/// Suggest which of a probable-duplicate pair should become the canonical
+/// node: prefer the longer (presumably more complete) name, breaking ties
+/// by the earlier-created node.
+fn canonical_candidate<'a>(a: &'a KGNode, b: &'a KGNode) -> &'a str {
+    if a.name.len() != b.name.len() {
+        if a.name.len() > b.name.len() { &a.name } else { &b.name }
+    } else if a.created_at <= b.created_at {
+        &a.name
+    } else {
+        &b.name
+    }
+}
+
 impl Default for ValidationConfig {
     fn default() -> Self {
         Self {
@@ -475,6 +1213,9 @@ impl Default for ValidationConfig {
             check_embedding_quality: true,
             strict_mode: false,
             performance_threshold: 0.7,
+            duplicate_similarity_threshold: 0.85,
+            min_component_size: 2,
+            expected_embedding_dim: None,
         }
     }
 } 
\ No newline at end of file
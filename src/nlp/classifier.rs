@@ -0,0 +1,313 @@
+//! Trainable Naive Bayes classifier used to replace
+//! `EntityExtractor`'s static per-pattern confidence literals with a
+//! calibrated, improving-over-time prediction of entity type and
+//! confidence. Trained incrementally (`BayesClassifier::train`) rather
+//! than in one batch, so classification quality improves as the graph
+//! ingests more episodes without ever needing to retrain from scratch.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+/// Laplace smoothing constant `α` - keeps an unseen feature from zeroing
+/// out a class's whole log-likelihood, rather than a bigger value that
+/// would wash out the signal a small amount of training data does carry.
+const LAPLACE_ALPHA: f64 = 1.0;
+
+/// Orthogonal sparse bigram tokenizer: slides a window of `window_size`
+/// tokens over the input and, for each window, pairs its first token with
+/// every other token at distance `d`, encoded as `"tok0|d|tokN"`. Captures
+/// word order - "error in parser" and "parser in error" produce different
+/// features - without the combinatorial blowup of a full bigram/trigram
+/// model.
+#[derive(Debug, Clone)]
+pub struct OsbTokenizer {
+    window_size: usize,
+}
+
+impl Default for OsbTokenizer {
+    fn default() -> Self {
+        Self { window_size: 5 }
+    }
+}
+
+impl OsbTokenizer {
+    pub fn new(window_size: usize) -> Self {
+        Self { window_size: window_size.max(2) }
+    }
+
+    /// Lowercased, punctuation-trimmed whitespace tokens.
+    fn tokens(&self, text: &str) -> Vec<String> {
+        text.split_whitespace()
+            .map(|tok| tok.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+            .filter(|tok| !tok.is_empty())
+            .collect()
+    }
+
+    /// The OSB feature multiset for `text` - see struct docs.
+    pub fn features(&self, text: &str) -> Vec<String> {
+        let tokens = self.tokens(text);
+        let mut features = Vec::new();
+
+        for i in 0..tokens.len() {
+            for d in 1..self.window_size {
+                let Some(tok_n) = tokens.get(i + d) else { break };
+                features.push(format!("{}|{}|{}", tokens[i], d, tok_n));
+            }
+        }
+
+        features
+    }
+}
+
+fn hash_feature(feature: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    feature.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Naive Bayes text classifier over `OsbTokenizer` features.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BayesClassifier {
+    tokenizer_window: usize,
+    /// `feature_counts[class][hashed_feature]` - occurrences of a feature
+    /// hash across every training example labeled `class`, i.e. `count(f, c)`.
+    feature_counts: HashMap<String, HashMap<u64, u32>>,
+    /// `class_totals[class]` - `Σ count(f, c)` over every feature seen for
+    /// `class`, the smoothing denominator's `total(c)` term.
+    class_totals: HashMap<String, u32>,
+    /// `class_docs[class]` - number of training examples labeled `class`,
+    /// for the prior `log P(c)`.
+    class_docs: HashMap<String, u32>,
+    /// Distinct feature hashes seen across every class - `V` in the
+    /// Laplace-smoothing denominator `total(c) + α·V`.
+    vocabulary: HashSet<u64>,
+}
+
+impl BayesClassifier {
+    pub fn new(window_size: usize) -> Self {
+        Self { tokenizer_window: window_size.max(2), ..Default::default() }
+    }
+
+    fn tokenizer(&self) -> OsbTokenizer {
+        OsbTokenizer::new(if self.tokenizer_window == 0 { 5 } else { self.tokenizer_window })
+    }
+
+    /// Folds one labeled example's OSB features into `label`'s running
+    /// counts.
+    pub fn train(&mut self, text: &str, label: &str) {
+        let features = self.tokenizer().features(text);
+
+        let counts = self.feature_counts.entry(label.to_string()).or_default();
+        let total = self.class_totals.entry(label.to_string()).or_insert(0);
+        for feature in &features {
+            let hash = hash_feature(feature);
+            *counts.entry(hash).or_insert(0) += 1;
+            *total += 1;
+            self.vocabulary.insert(hash);
+        }
+
+        *self.class_docs.entry(label.to_string()).or_insert(0) += 1;
+    }
+
+    /// Whether at least one `train` call has labeled an example `label` -
+    /// `QualityFilter` uses this to tell "never trained" from "trained,
+    /// but this example just lost".
+    fn has_class(&self, label: &str) -> bool {
+        self.class_docs.contains_key(label)
+    }
+
+    /// Predicts the best-scoring class and a calibrated `0..1` confidence
+    /// for `context`: for each class `c`,
+    /// `log P(c) + Σ log((count(f,c)+α)/(total(c)+α·V))` with Laplace
+    /// smoothing, then a softmax over every class's log-likelihood turns
+    /// the winner's score into a probability instead of an unbounded
+    /// log-odds value. Returns `("unknown", 0.0)` before any `train` call
+    /// has run.
+    pub fn classify(&self, context: &str) -> (String, f32) {
+        if self.class_docs.is_empty() {
+            return ("unknown".to_string(), 0.0);
+        }
+
+        let features = self.tokenizer().features(context);
+        let total_docs: f64 = self.class_docs.values().sum::<u32>() as f64;
+        let vocab_size = self.vocabulary.len().max(1) as f64;
+
+        let log_likelihoods: Vec<(String, f64)> = self
+            .class_docs
+            .keys()
+            .map(|class| {
+                let prior = (*self.class_docs.get(class).unwrap_or(&0) as f64) / total_docs;
+                let total = *self.class_totals.get(class).unwrap_or(&0) as f64;
+                let counts = self.feature_counts.get(class);
+
+                let mut log_likelihood = prior.ln();
+                for feature in &features {
+                    let hash = hash_feature(feature);
+                    let count = counts.and_then(|c| c.get(&hash)).copied().unwrap_or(0) as f64;
+                    log_likelihood += ((count + LAPLACE_ALPHA) / (total + LAPLACE_ALPHA * vocab_size)).ln();
+                }
+
+                (class.clone(), log_likelihood)
+            })
+            .collect();
+
+        softmax_best(&log_likelihoods)
+    }
+
+    /// Serializes the trained counts to `path` as JSON, creating its
+    /// parent directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create classifier directory {}", parent.display()))?;
+        }
+        let json = serde_json::to_string(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write classifier counts to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Loads previously `save`d counts from `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read classifier counts from {}", path.display()))?;
+        serde_json::from_str(&json).context("Failed to parse classifier counts")
+    }
+}
+
+/// Numerically stable softmax over `log_likelihoods`, returning the
+/// highest-scoring class alongside its resulting probability.
+fn softmax_best(log_likelihoods: &[(String, f64)]) -> (String, f32) {
+    let max = log_likelihoods.iter().map(|(_, ll)| *ll).fold(f64::NEG_INFINITY, f64::max);
+    let exp_sum: f64 = log_likelihoods.iter().map(|(_, ll)| (ll - max).exp()).sum();
+
+    log_likelihoods
+        .iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(class, ll)| (class.clone(), ((ll - max).exp() / exp_sum) as f32))
+        .unwrap_or_else(|| ("unknown".to_string(), 0.0))
+}
+
+const KEEP_LABEL: &str = "keep";
+const DROP_LABEL: &str = "drop";
+
+/// Binary "keep vs drop" entity-quality filter, spam-filter style: wraps a
+/// `BayesClassifier` trained on exactly two labels (`keep`/`drop`) and a
+/// decision threshold on the winning class's softmax confidence, rather
+/// than exposing raw per-token log-likelihood ratios to callers. Meant for
+/// noisy, dictionary-free extractors (proper nouns, CamelCase technical
+/// terms) where a fixed stopword list can't keep up with a project's own
+/// vocabulary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityFilter {
+    classifier: BayesClassifier,
+    /// Minimum confidence `classify` must assign the `keep` class for a
+    /// candidate to survive.
+    keep_threshold: f32,
+}
+
+impl QualityFilter {
+    pub fn new(keep_threshold: f32) -> Self {
+        Self { classifier: BayesClassifier::new(5), keep_threshold }
+    }
+
+    /// Folds a confirmed-good candidate's name plus context into the
+    /// positive ("keep") class - the feedback hook for an entity a user
+    /// confirmed, or simply never deleted.
+    pub fn train_positive(&mut self, text: &str) {
+        self.classifier.train(text, KEEP_LABEL);
+    }
+
+    /// Folds a later-deleted-or-merged candidate's name plus context into
+    /// the negative ("drop") class.
+    pub fn train_negative(&mut self, text: &str) {
+        self.classifier.train(text, DROP_LABEL);
+    }
+
+    /// Whether `text` (a candidate's name plus its surrounding context)
+    /// should survive. An untrained filter, or one that has only ever seen
+    /// one side of the keep/drop decision, can't meaningfully distinguish
+    /// the two - it keeps everything rather than dropping by default,
+    /// since a filter that's never seen a negative example has no basis to
+    /// reject anything.
+    pub fn should_keep(&self, text: &str) -> bool {
+        if !(self.classifier.has_class(KEEP_LABEL) && self.classifier.has_class(DROP_LABEL)) {
+            return true;
+        }
+
+        let (label, confidence) = self.classifier.classify(text);
+        label == KEEP_LABEL && confidence >= self.keep_threshold
+    }
+
+    /// Serializes the underlying classifier's counts to `path` as JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create quality filter directory {}", parent.display()))?;
+        }
+        let json = serde_json::to_string(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write quality filter counts to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Loads previously `save`d counts from `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read quality filter counts from {}", path.display()))?;
+        serde_json::from_str(&json).context("Failed to parse quality filter counts")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osb_tokenizer_pairs_first_token_with_later_tokens_at_distance() {
+        let tokenizer = OsbTokenizer::new(3);
+        let features = tokenizer.features("error in parser");
+        assert!(features.contains(&"error|1|in".to_string()));
+        assert!(features.contains(&"error|2|parser".to_string()));
+        assert!(features.contains(&"in|1|parser".to_string()));
+    }
+
+    #[test]
+    fn classifier_favors_the_class_whose_training_text_is_more_similar() {
+        let mut classifier = BayesClassifier::new(5);
+        classifier.train("connect to the database server", "technical_term");
+        classifier.train("visit the website homepage", "url");
+
+        let (label, confidence) = classifier.classify("connect to the database");
+        assert_eq!(label, "technical_term");
+        assert!(confidence > 0.0 && confidence <= 1.0);
+    }
+
+    #[test]
+    fn classify_before_training_reports_unknown() {
+        let classifier = BayesClassifier::new(5);
+        assert_eq!(classifier.classify("anything"), ("unknown".to_string(), 0.0));
+    }
+
+    #[test]
+    fn quality_filter_keeps_everything_until_it_has_seen_both_classes() {
+        let mut filter = QualityFilter::new(0.5);
+        assert!(filter.should_keep("Sentence Starting Word"));
+        filter.train_negative("Sentence Starting Word in a log line");
+        assert!(filter.should_keep("anything at all"));
+    }
+
+    #[test]
+    fn quality_filter_drops_candidates_similar_to_trained_negatives() {
+        let mut filter = QualityFilter::new(0.5);
+        for _ in 0..5 {
+            filter.train_positive("DatabaseConnection pool exhausted during migration");
+            filter.train_negative("The Quick a sentence-initial capital in prose");
+        }
+        assert!(!filter.should_keep("The Quick brown fox in prose"));
+    }
+}
@@ -7,7 +7,8 @@ use chrono::Utc;
 use tracing::{debug, info};
 
 use crate::graph::{KGNode, EpisodeSource};
-use crate::embeddings::LocalEmbeddingEngine;
+use crate::embeddings::{LocalEmbeddingEngine, cosine_similarity};
+use crate::nlp::classifier::{BayesClassifier, QualityFilter};
 
 /// Entity extraction configuration
 #[derive(Debug, Clone)]
@@ -21,7 +22,34 @@ pub struct EntityExtractionConfig {
     pub min_confidence: f32,
     pub max_entities_per_text: usize,
     pub enable_fuzzy_matching: bool,
+    /// Cosine similarity (over name embeddings) at or above which two
+    /// same-`entity_type` entities are collapsed into one canonical entry
+    /// by `fuzzy_deduplicate` - only consulted when `enable_fuzzy_matching`
+    /// is set and an embedding engine was passed to `EntityExtractor::new`.
+    pub fuzzy_dedup_threshold: f32,
     pub custom_patterns: Vec<EntityPattern>,
+    /// Path to a `BayesClassifier::save`d counts file to load at
+    /// construction. When set (and the file exists), `extract_from_text`
+    /// fills `ExtractedEntity::entity_type`/`confidence` from
+    /// `BayesClassifier::classify` instead of the static per-pattern
+    /// confidence literals - `None` (the default) keeps the old
+    /// heuristic-only behavior.
+    pub bayes_classifier_path: Option<std::path::PathBuf>,
+    /// Enables the learned "keep vs drop" quality filter (see
+    /// `nlp::classifier::QualityFilter`) that replaces the fixed
+    /// `common_words` stopword list for the noisy proper-noun/CamelCase
+    /// extractors. Starts untrained (keeps everything) until
+    /// `EntityExtractor::record_entity_confirmed`/`record_entity_rejected`
+    /// have fed it at least one example of each class.
+    pub enable_quality_filter: bool,
+    /// Minimum confidence the quality filter must assign "keep" for a
+    /// proper-noun/technical-term candidate to survive.
+    pub quality_filter_keep_threshold: f32,
+    /// Path to a `QualityFilter::save`d counts file to load at
+    /// construction (and the default target for a caller-triggered
+    /// `EntityExtractor::save_quality_filter`). A missing file just starts
+    /// the filter untrained, same as not setting this at all.
+    pub quality_filter_path: Option<std::path::PathBuf>,
 }
 
 impl Default for EntityExtractionConfig {
@@ -36,7 +64,12 @@ impl Default for EntityExtractionConfig {
             min_confidence: 0.6,
             max_entities_per_text: 100,
             enable_fuzzy_matching: true,
+            fuzzy_dedup_threshold: 0.85,
             custom_patterns: Vec::new(),
+            bayes_classifier_path: None,
+            enable_quality_filter: false,
+            quality_filter_keep_threshold: 0.6,
+            quality_filter_path: None,
         }
     }
 }
@@ -75,6 +108,15 @@ pub struct EntityExtractor {
     code_block_regex: Regex,
     browser_regex: Regex,
     technology_regex: Regex,
+    /// Loaded from `config.bayes_classifier_path`, when set - see
+    /// `apply_bayes_classification`.
+    bayes_classifier: Option<BayesClassifier>,
+    /// Present when `config.enable_quality_filter` is set - guarded by a
+    /// `Mutex` rather than needing `&mut self`, since `EntityExtractor` is
+    /// typically shared behind an `Arc` (see `codebase_indexer.rs`) and
+    /// `record_entity_confirmed`/`record_entity_rejected` must still be
+    /// able to train it.
+    quality_filter: Option<std::sync::Mutex<QualityFilter>>,
 }
 
 #[derive(Clone)]
@@ -104,10 +146,27 @@ impl EntityExtractor {
             }
         }
 
+        let bayes_classifier = match &config.bayes_classifier_path {
+            Some(path) if path.exists() => Some(BayesClassifier::load(path)?),
+            _ => None,
+        };
+
+        let quality_filter = if config.enable_quality_filter {
+            let filter = match &config.quality_filter_path {
+                Some(path) if path.exists() => QualityFilter::load(path)?,
+                _ => QualityFilter::new(config.quality_filter_keep_threshold),
+            };
+            Some(std::sync::Mutex::new(filter))
+        } else {
+            None
+        };
+
         Ok(Self {
             config,
             embedding_engine,
             patterns,
+            bayes_classifier,
+            quality_filter,
             technical_term_regex: Regex::new(r"\b[A-Z][a-zA-Z]*(?:[A-Z][a-zA-Z]*)*\b")?,
             proper_noun_regex: Regex::new(r"\b[A-Z][a-z]+(?:\s+[A-Z][a-z]+)*\b")?,
             quoted_text_regex: Regex::new(r#""([^"]+)"|'([^']+)'|`([^`]+)`"#)?,
@@ -127,7 +186,7 @@ impl EntityExtractor {
             EpisodeSource::Json => {
                 entities.extend(self.extract_from_json(content, episode_name)?);
             },
-            EpisodeSource::Text | EpisodeSource::Message => {
+            EpisodeSource::Text | EpisodeSource::Message | EpisodeSource::Code | EpisodeSource::File => {
                 entities.extend(self.extract_from_text(content, episode_name)?);
             }
         }
@@ -139,16 +198,113 @@ impl EntityExtractor {
             seen.insert(key)
         });
 
+        // Bayesian quality filter: suppress proper-noun/technical-term
+        // candidates the user has previously rejected often enough, before
+        // the confidence/length filter below gets a chance to keep them on
+        // heuristic confidence alone.
+        if let Some(quality_filter) = &self.quality_filter {
+            let filter = quality_filter.lock().unwrap();
+            entities.retain(|entity| {
+                if entity.entity_type != "proper_noun" && entity.entity_type != "technical_term" {
+                    return true;
+                }
+                filter.should_keep(&Self::quality_filter_text(entity))
+            });
+        }
+
         // Filter by confidence and length
         entities.retain(|entity| {
-            entity.confidence > 0.3 && 
+            entity.confidence > 0.3 &&
             entity.name.len() >= self.config.min_entity_length &&
             entity.name.len() <= self.config.max_entity_length
         });
 
+        // Semantic fuzzy dedup: the exact (name, type) dedup above keeps
+        // "Patchright" and "patchright" (or "WebAuthn"/"Web Authn") as
+        // separate entities, since they're different strings - when an
+        // embedding engine is available, collapse same-type entities whose
+        // name embeddings are near-identical into one canonical entity.
+        if self.config.enable_fuzzy_matching {
+            if let Some(engine) = self.embedding_engine.clone() {
+                entities = self.fuzzy_deduplicate(entities, &engine).await?;
+            }
+        }
+
         Ok(entities)
     }
 
+    /// Collapses entities of the same `entity_type` whose name embeddings'
+    /// cosine similarity meets `fuzzy_dedup_threshold` into a single
+    /// canonical `ExtractedEntity`, clustering greedily (same threshold
+    /// convention as `GraphQueryEngine::match_nodes`' node-similarity
+    /// matching) rather than full hierarchical clustering. The
+    /// highest-confidence cluster member survives as the canonical entity;
+    /// every other member's name is recorded as an alias in
+    /// `metadata["aliases"]` instead of silently discarded.
+    async fn fuzzy_deduplicate(&self, entities: Vec<ExtractedEntity>, engine: &LocalEmbeddingEngine) -> Result<Vec<ExtractedEntity>> {
+        let mut by_type: HashMap<String, Vec<ExtractedEntity>> = HashMap::new();
+        for entity in entities {
+            by_type.entry(entity.entity_type.clone()).or_default().push(entity);
+        }
+
+        let mut result = Vec::new();
+        for group in by_type.into_values() {
+            if group.len() == 1 {
+                result.extend(group);
+                continue;
+            }
+
+            let names: Vec<String> = group.iter().map(|e| e.name.clone()).collect();
+            let embeddings = engine.encode_texts(&names).await?;
+
+            let mut assigned = vec![false; group.len()];
+            for i in 0..group.len() {
+                if assigned[i] {
+                    continue;
+                }
+                assigned[i] = true;
+                let mut cluster = vec![i];
+                for (j, is_assigned) in assigned.iter_mut().enumerate().skip(i + 1) {
+                    if *is_assigned {
+                        continue;
+                    }
+                    if cosine_similarity(&embeddings[i], &embeddings[j]) >= self.config.fuzzy_dedup_threshold {
+                        cluster.push(j);
+                        *is_assigned = true;
+                    }
+                }
+                result.push(Self::collapse_cluster(&group, &cluster));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Merges `group[indices]` into one `ExtractedEntity`: the
+    /// highest-confidence member's fields survive, every other member's
+    /// name becomes an alias.
+    fn collapse_cluster(group: &[ExtractedEntity], indices: &[usize]) -> ExtractedEntity {
+        let canonical_idx = indices
+            .iter()
+            .copied()
+            .max_by(|&a, &b| group[a].confidence.partial_cmp(&group[b].confidence).unwrap_or(std::cmp::Ordering::Equal))
+            .expect("cluster is never empty");
+        let mut canonical = group[canonical_idx].clone();
+
+        let aliases: Vec<serde_json::Value> = indices
+            .iter()
+            .copied()
+            .filter(|&idx| idx != canonical_idx)
+            .map(|idx| serde_json::Value::String(group[idx].name.clone()))
+            .collect();
+
+        if !aliases.is_empty() {
+            canonical.metadata.insert("aliases".to_string(), serde_json::Value::Array(aliases));
+        }
+
+        canonical
+    }
+
     /// Extract entities from JSON content
     fn extract_from_json(&self, content: &str, episode_name: &str) -> Result<Vec<ExtractedEntity>> {
         let mut entities = Vec::new();
@@ -343,9 +499,60 @@ impl EntityExtractor {
             }
         }
 
+        if self.bayes_classifier.is_some() {
+            for entity in &mut entities {
+                self.apply_bayes_classification(entity);
+            }
+        }
+
         Ok(entities)
     }
 
+    /// When `bayes_classifier` is configured, reclassifies `entity`'s
+    /// `entity_type`/`confidence` from its surrounding `context` instead of
+    /// leaving the static per-pattern heuristic in place - lets
+    /// classification quality improve as the graph ingests more episodes
+    /// and `BayesClassifier::train` is called on confirmed entities,
+    /// without needing a second extraction pass.
+    fn apply_bayes_classification(&self, entity: &mut ExtractedEntity) {
+        let Some(classifier) = &self.bayes_classifier else { return };
+        let (entity_type, confidence) = classifier.classify(&entity.context);
+        entity.entity_type = entity_type;
+        entity.confidence = confidence;
+    }
+
+    /// The name+context string the quality filter trains and classifies on -
+    /// kept as a single helper so `record_entity_confirmed`,
+    /// `record_entity_rejected`, and the `extract_entities` retain pass can't
+    /// drift apart on what text actually gets fed to `QualityFilter`.
+    fn quality_filter_text(entity: &ExtractedEntity) -> String {
+        format!("{} {}", entity.name, entity.context)
+    }
+
+    /// Records that `entity` was a good extraction (e.g. the user kept it, or
+    /// promoted it into the graph), training the quality filter's "keep"
+    /// class. No-op when `enable_quality_filter` is off.
+    pub fn record_entity_confirmed(&self, entity: &ExtractedEntity) {
+        let Some(quality_filter) = &self.quality_filter else { return };
+        quality_filter.lock().unwrap().train_positive(&Self::quality_filter_text(entity));
+    }
+
+    /// Records that `entity` was junk (e.g. the user deleted or merged it
+    /// away), training the quality filter's "drop" class so similar future
+    /// candidates get suppressed. No-op when `enable_quality_filter` is off.
+    pub fn record_entity_rejected(&self, entity: &ExtractedEntity) {
+        let Some(quality_filter) = &self.quality_filter else { return };
+        quality_filter.lock().unwrap().train_negative(&Self::quality_filter_text(entity));
+    }
+
+    /// Persists the quality filter's learned state to `path` for reuse
+    /// across process restarts via `EntityExtractionConfig::quality_filter_path`.
+    /// No-op (returns `Ok`) when `enable_quality_filter` is off.
+    pub fn save_quality_filter(&self, path: &std::path::Path) -> Result<()> {
+        let Some(quality_filter) = &self.quality_filter else { return Ok(()) };
+        quality_filter.lock().unwrap().save(path)
+    }
+
     /// Convert extracted entity to KGNode
     pub fn entity_to_node(&self, entity: &ExtractedEntity, group_id: Option<String>) -> KGNode {
         let now = Utc::now();
@@ -0,0 +1,7 @@
+pub mod entity_extractor;
+pub mod relationship_extractor;
+pub mod classifier;
+
+pub use entity_extractor::*;
+pub use relationship_extractor::*;
+pub use classifier::*;
@@ -0,0 +1,188 @@
+//! Okapi BM25 scoring, selectable as an alternative to `TextSearchEngine`'s
+//! hand-tuned boost-factor model via `ScoringStrategy`/`with_scoring_strategy`.
+//! Precomputing per-field document frequencies and average field lengths
+//! needs a full corpus scan, so `Bm25Stats` is cached and rebuilt only when
+//! stale — the same read-then-rebuild-on-stale-write pattern
+//! `QueryEngine::ensure_graph_cache` already uses for its graph cache.
+
+use crate::graph::storage::GraphStorage;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Term frequency saturation constant — higher values let repeated term
+/// occurrences keep contributing longer before diminishing returns kick in.
+pub const BM25_K1: f32 = 1.2;
+/// Field-length normalization strength — 0 ignores document length
+/// entirely, 1 fully normalizes by it.
+pub const BM25_B: f32 = 0.75;
+
+/// Which relevance model `TextSearchEngine` turns term matches into a score
+/// with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoringStrategy {
+    /// The original `contains` + position-bonus model, normalized by
+    /// `BoostFactors`.
+    #[default]
+    BoostFactors,
+    /// Okapi BM25 over precomputed per-field document frequencies and
+    /// average field lengths, combined using `BoostFactors` as field
+    /// weights.
+    Bm25,
+}
+
+/// Document frequencies and average length for a single indexed field
+/// (e.g. node `name`, episode `content`).
+#[derive(Debug, Clone, Default)]
+pub struct FieldStats {
+    /// term -> number of documents whose field contains it at least once.
+    doc_freq: HashMap<String, usize>,
+    avg_len: f32,
+    doc_count: usize,
+}
+
+impl FieldStats {
+    fn record(&mut self, text: &str, total_len: &mut usize) {
+        self.doc_count += 1;
+        let lower = text.to_lowercase();
+        let terms: Vec<&str> = lower.split_whitespace().collect();
+        *total_len += terms.len();
+
+        let mut seen = HashSet::new();
+        for term in terms {
+            if seen.insert(term) {
+                *self.doc_freq.entry(term.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// BM25 score of `text` against `query_terms` (already lowercased).
+    fn bm25_score(&self, text: &str, query_terms: &[String]) -> f32 {
+        if self.doc_count == 0 || self.avg_len <= 0.0 {
+            return 0.0;
+        }
+
+        let lower = text.to_lowercase();
+        let doc_terms: Vec<&str> = lower.split_whitespace().collect();
+        let len_f = doc_terms.len() as f32;
+        let n = self.doc_count as f32;
+
+        let mut score = 0.0;
+        for term in query_terms {
+            let tf = doc_terms.iter().filter(|t| *t == term).count() as f32;
+            if tf == 0.0 {
+                continue;
+            }
+            let df = *self.doc_freq.get(term).unwrap_or(&0) as f32;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            let numerator = idf * tf * (BM25_K1 + 1.0);
+            let denominator = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * (len_f / self.avg_len));
+            score += numerator / denominator;
+        }
+        score
+    }
+}
+
+/// Per-field BM25 statistics for every field `TextSearchEngine` scores:
+/// node `name`/`node_type`/`summary` and episode `content`.
+pub struct Bm25Stats {
+    pub name: FieldStats,
+    pub node_type: FieldStats,
+    pub summary: FieldStats,
+    pub content: FieldStats,
+    pub built_at: std::time::Instant,
+}
+
+impl Bm25Stats {
+    /// Streams the full node and episode corpus (same paging idiom as
+    /// `ValidationEngine::validate_stream`) to compute document frequencies
+    /// and average lengths for every scored field.
+    pub fn rebuild(storage: &GraphStorage) -> Result<Self> {
+        let mut name = FieldStats::default();
+        let mut node_type = FieldStats::default();
+        let mut summary = FieldStats::default();
+        let mut content = FieldStats::default();
+
+        let mut name_total_len = 0;
+        let mut type_total_len = 0;
+        let mut summary_total_len = 0;
+        let mut content_total_len = 0;
+
+        let page_size = 500;
+        let mut offset = 0;
+        loop {
+            let page = storage.get_nodes_page(offset, page_size)?;
+            if page.is_empty() {
+                break;
+            }
+            let page_len = page.len();
+            for node in page {
+                name.record(&node.name, &mut name_total_len);
+                node_type.record(&node.node_type, &mut type_total_len);
+                summary.record(&node.summary, &mut summary_total_len);
+            }
+            offset += page_len;
+        }
+
+        let mut offset = 0;
+        loop {
+            let page = storage.get_episodes_page(offset, page_size)?;
+            if page.is_empty() {
+                break;
+            }
+            let page_len = page.len();
+            for episode in page {
+                content.record(&episode.content, &mut content_total_len);
+            }
+            offset += page_len;
+        }
+
+        if name.doc_count > 0 {
+            name.avg_len = name_total_len as f32 / name.doc_count as f32;
+        }
+        if node_type.doc_count > 0 {
+            node_type.avg_len = type_total_len as f32 / node_type.doc_count as f32;
+        }
+        if summary.doc_count > 0 {
+            summary.avg_len = summary_total_len as f32 / summary.doc_count as f32;
+        }
+        if content.doc_count > 0 {
+            content.avg_len = content_total_len as f32 / content.doc_count as f32;
+        }
+
+        Ok(Self {
+            name,
+            node_type,
+            summary,
+            content,
+            built_at: std::time::Instant::now(),
+        })
+    }
+
+    /// Combined BM25 score for a node's `name`/`node_type`/`summary`
+    /// fields, weighted by `boost_factors` the same way the boost-factor
+    /// model uses them.
+    pub fn score_node(
+        &self,
+        name: &str,
+        node_type: &str,
+        summary: &str,
+        query_terms: &[String],
+        boost_factors: &crate::search::BoostFactors,
+    ) -> f32 {
+        self.name.bm25_score(name, query_terms) * boost_factors.name_boost
+            + self.node_type.bm25_score(node_type, query_terms) * boost_factors.type_boost
+            + self.summary.bm25_score(summary, query_terms) * boost_factors.summary_boost
+    }
+
+    /// Combined BM25 score for an episode's `name`/`content` fields.
+    pub fn score_episode(
+        &self,
+        name: &str,
+        content: &str,
+        query_terms: &[String],
+        boost_factors: &crate::search::BoostFactors,
+    ) -> f32 {
+        self.name.bm25_score(name, query_terms) * boost_factors.name_boost
+            + self.content.bm25_score(content, query_terms) * boost_factors.content_boost
+    }
+}
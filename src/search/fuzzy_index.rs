@@ -0,0 +1,151 @@
+//! In-memory FST term dictionary and cached Levenshtein-automaton builders
+//! backing `TextSearchEngine::fuzzy_search`. Building a `LevenshteinAutomatonBuilder`
+//! precomputes a parametric transition table that depends only on the max
+//! edit distance, not the query, so the three builders it needs (distance
+//! 0, 1, 2) are built once and reused for every query; the per-query DFA
+//! itself (`build_dfa`/`build_prefix_dfa`) is cheap. Intersecting that DFA
+//! with an `fst::Set` of the corpus's terms enumerates exactly the terms
+//! within range in one streamed pass, replacing the old `*query*` wildcard
+//! expansion plus pairwise `levenshtein_distance` rescan over every word.
+
+use anyhow::{Context, Result};
+use fst::{Automaton, IntoStreamer, Set, Streamer};
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA};
+
+/// A dictionary term within range of a fuzzy query, plus its exact edit
+/// distance. The automaton intersection only proves "within `max_distance`";
+/// the distance is recovered with a direct Levenshtein computation over the
+/// much smaller set of terms it actually matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub term: String,
+    pub distance: u32,
+}
+
+/// Adapts `levenshtein_automata::DFA` to `fst::Automaton` so it can be
+/// streamed against a `Set`.
+struct DfaAutomaton<'a>(&'a DFA);
+
+impl<'a> Automaton for DfaAutomaton<'a> {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.0.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.0.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        *state != levenshtein_automata::SINK_STATE
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.0.transition(*state, byte)
+    }
+}
+
+/// The three Levenshtein-automaton builders `fuzzy_search` can need,
+/// precomputed once at engine construction. `false` below means
+/// transpositions are *not* treated as a single edit, keeping the notion of
+/// distance consistent with the plain `levenshtein_distance` used to report
+/// the final score.
+pub struct FuzzyAutomatonBuilders {
+    dist_0: LevenshteinAutomatonBuilder,
+    dist_1: LevenshteinAutomatonBuilder,
+    dist_2: LevenshteinAutomatonBuilder,
+}
+
+impl FuzzyAutomatonBuilders {
+    pub fn new() -> Self {
+        Self {
+            dist_0: LevenshteinAutomatonBuilder::new(0, false),
+            dist_1: LevenshteinAutomatonBuilder::new(1, false),
+            dist_2: LevenshteinAutomatonBuilder::new(2, false),
+        }
+    }
+
+    fn for_distance(&self, max_distance: u32) -> &LevenshteinAutomatonBuilder {
+        match max_distance {
+            0 => &self.dist_0,
+            1 => &self.dist_1,
+            _ => &self.dist_2,
+        }
+    }
+
+    /// Streams `terms` (need not be sorted or deduplicated — that's handled
+    /// here, since `fst::SetBuilder` requires both) for every term within
+    /// `max_distance` edits of `query`. `prefix` makes the automaton accept
+    /// any suffix after a matching prefix, matching the `query*` wildcard
+    /// semantics `enhance_query` already uses elsewhere in this engine.
+    /// `max_distance` above 2 is clamped, since only builders for 0/1/2 are
+    /// cached.
+    pub fn fuzzy_match<I: IntoIterator<Item = String>>(
+        &self,
+        terms: I,
+        query: &str,
+        max_distance: u32,
+        prefix: bool,
+    ) -> Result<Vec<FuzzyMatch>> {
+        let mut sorted: Vec<String> = terms.into_iter().collect();
+        sorted.sort();
+        sorted.dedup();
+        let set = Set::from_iter(sorted).context("Failed to build fuzzy-match term dictionary")?;
+
+        let builder = self.for_distance(max_distance.min(2));
+        let dfa = if prefix {
+            builder.build_prefix_dfa(query)
+        } else {
+            builder.build_dfa(query)
+        };
+
+        let mut matches = Vec::new();
+        let mut stream = set.search(DfaAutomaton(&dfa)).into_stream();
+        while let Some(term) = stream.next() {
+            if let Ok(term) = std::str::from_utf8(term) {
+                matches.push(FuzzyMatch {
+                    term: term.to_string(),
+                    distance: levenshtein_distance(query, term),
+                });
+            }
+        }
+        Ok(matches)
+    }
+}
+
+impl Default for FuzzyAutomatonBuilders {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Plain Levenshtein edit distance (insert/delete/substitute, each cost 1).
+/// Used to recover the exact distance for terms the automaton has already
+/// proven are within `max_distance`.
+pub fn levenshtein_distance(s1: &str, s2: &str) -> u32 {
+    let len1 = s1.len();
+    let len2 = s2.len();
+    let mut matrix = vec![vec![0; len2 + 1]; len1 + 1];
+
+    for i in 0..=len1 {
+        matrix[i][0] = i;
+    }
+    for j in 0..=len2 {
+        matrix[0][j] = j;
+    }
+
+    let s1_chars: Vec<char> = s1.chars().collect();
+    let s2_chars: Vec<char> = s2.chars().collect();
+
+    for i in 1..=len1 {
+        for j in 1..=len2 {
+            let cost = if s1_chars[i - 1] == s2_chars[j - 1] { 0 } else { 1 };
+            matrix[i][j] = (matrix[i - 1][j] + 1)
+                .min(matrix[i][j - 1] + 1)
+                .min(matrix[i - 1][j - 1] + cost);
+        }
+    }
+
+    matrix[len1][len2] as u32
+}
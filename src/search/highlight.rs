@@ -0,0 +1,166 @@
+//! Match-position tracking and highlighted snippet generation, layered on
+//! top of the term list `calculate_text_match_score` already computes, so a
+//! caller building a UI can show *where* a query matched instead of just a
+//! relevance score.
+
+/// Where one query term matched within one field of a result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchBounds {
+    pub field: String,
+    pub start: usize,
+    pub length: usize,
+}
+
+/// How a matched result's snippet should be cropped and marked up.
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub highlight: bool,
+    /// Target snippet length in characters. `None` returns the full field
+    /// text (still highlighted if `highlight` is set).
+    pub crop_length: Option<usize>,
+    pub crop_marker: String,
+    pub highlight_pre: String,
+    pub highlight_post: String,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            highlight: true,
+            crop_length: Some(80),
+            crop_marker: "…".to_string(),
+            highlight_pre: "<em>".to_string(),
+            highlight_post: "</em>".to_string(),
+        }
+    }
+}
+
+/// A search hit alongside where its query terms matched and, when cropping
+/// is enabled, a ready-to-display snippet.
+#[derive(Debug, Clone)]
+pub struct MatchedNode {
+    pub node: crate::graph::KGNode,
+    pub score: f32,
+    pub matches: Vec<MatchBounds>,
+    pub snippet: Option<String>,
+}
+
+/// Finds every case-insensitive occurrence of each of `query_terms` in
+/// `text`, reusing the same terms `calculate_text_match_score` scores
+/// against so match positions reflect exactly what contributed to the
+/// relevance score.
+pub fn find_matches(field: &str, text: &str, query_terms: &[&str]) -> Vec<MatchBounds> {
+    let text_lower = text.to_lowercase();
+    let mut matches = Vec::new();
+
+    for term in query_terms {
+        let term_lower = term.to_lowercase();
+        if term_lower.is_empty() {
+            continue;
+        }
+        let mut cursor = 0;
+        while let Some(pos) = text_lower[cursor..].find(&term_lower) {
+            let absolute = cursor + pos;
+            matches.push(MatchBounds {
+                field: field.to_string(),
+                start: absolute,
+                length: term_lower.len(),
+            });
+            cursor = absolute + term_lower.len();
+        }
+    }
+
+    matches.sort_by_key(|m| m.start);
+    matches
+}
+
+/// Crops `text` to roughly `options.crop_length` characters centered on the
+/// densest cluster of `matches`, wrapping each matched substring in
+/// `options.highlight_pre`/`_post` when `options.highlight` is set. Falls
+/// back to the full (optionally highlighted) text when cropping is off or
+/// unnecessary.
+pub fn format_snippet(text: &str, matches: &[MatchBounds], options: &FormatOptions) -> String {
+    let crop_length = match options.crop_length {
+        Some(length) if length < text.len() => length,
+        _ => return highlight(text, matches, 0, text.len(), options),
+    };
+
+    let (window_start, window_end) = densest_window(text.len(), matches, crop_length);
+
+    let mut snippet = String::new();
+    if window_start > 0 {
+        snippet.push_str(&options.crop_marker);
+    }
+    snippet.push_str(&highlight(text, matches, window_start, window_end, options));
+    if window_end < text.len() {
+        snippet.push_str(&options.crop_marker);
+    }
+    snippet
+}
+
+/// Picks the `crop_length`-wide window that contains the most matches,
+/// trying each match's position as a candidate window anchor (offset back
+/// by a quarter of the crop length so the anchoring match isn't pinned to
+/// the window's leading edge).
+fn densest_window(text_len: usize, matches: &[MatchBounds], crop_length: usize) -> (usize, usize) {
+    if matches.is_empty() {
+        return (0, crop_length.min(text_len));
+    }
+
+    let max_start = text_len.saturating_sub(crop_length);
+    let mut best_start = 0;
+    let mut best_count = 0;
+
+    for anchor in matches {
+        let start = anchor.start.saturating_sub(crop_length / 4).min(max_start);
+        let end = (start + crop_length).min(text_len);
+        let count = matches.iter().filter(|m| m.start >= start && m.start + m.length <= end).count();
+        if count > best_count {
+            best_count = count;
+            best_start = start;
+        }
+    }
+
+    let end = (best_start + crop_length).min(text_len);
+    (best_start, end)
+}
+
+/// Slices `text[window_start..window_end]` and, if `options.highlight` is
+/// set, wraps every match falling fully within that window in
+/// `highlight_pre`/`_post`, merging overlapping/adjacent matches first so
+/// markers never nest.
+fn highlight(text: &str, matches: &[MatchBounds], window_start: usize, window_end: usize, options: &FormatOptions) -> String {
+    let window = &text[window_start..window_end];
+    if !options.highlight {
+        return window.to_string();
+    }
+
+    let mut spans: Vec<(usize, usize)> = matches.iter()
+        .filter(|m| m.start >= window_start && m.start + m.length <= window_end)
+        .map(|m| (m.start - window_start, m.length))
+        .collect();
+    spans.sort();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, length) in spans.drain(..) {
+        if let Some(&mut (last_start, ref mut last_len)) = merged.last_mut() {
+            if start <= last_start + *last_len {
+                *last_len = (start + length).saturating_sub(last_start).max(*last_len);
+                continue;
+            }
+        }
+        merged.push((start, length));
+    }
+
+    let mut output = String::new();
+    let mut cursor = 0;
+    for (start, length) in merged {
+        output.push_str(&window[cursor..start]);
+        output.push_str(&options.highlight_pre);
+        output.push_str(&window[start..start + length]);
+        output.push_str(&options.highlight_post);
+        cursor = start + length;
+    }
+    output.push_str(&window[cursor..]);
+    output
+}
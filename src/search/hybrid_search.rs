@@ -1,19 +1,141 @@
 use anyhow::Result;
-use crate::graph::{KGNode, KGEdge, Episode, SearchResult};
-use crate::search::{TextSearchEngine, VectorSearchEngine};
-use crate::embeddings::LocalEmbeddingEngine;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use crate::graph::{KGNode, KGEdge, Episode, SearchResult, ComponentScores};
+use crate::search::{SearchOptions, TextSearchEngine, VectorSearchEngine};
+use crate::embeddings::{EmbeddingProvider, cosine_similarity};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Embeds a single text through `engine`'s batch API — mirrors
+/// `vector_search::embed_text`, duplicated here rather than shared since
+/// it's a one-line adapter and the two modules otherwise have no reason to
+/// depend on each other's internals.
+async fn embed_text(engine: &dyn EmbeddingProvider, text: &str) -> Result<Vec<f32>> {
+    engine.embed_batch(&[text.to_string()]).await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Embedding provider returned no vectors for a single-text batch"))
+}
+
+/// Which retrieval path a `search_with_options` call actually took, so
+/// callers (and diagnostics) can tell when resilience or lazy-embedding
+/// logic kicked in instead of a plain fused hybrid search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchPath {
+    /// Text and vector search both ran and were fused.
+    Hybrid,
+    /// No embedding engine is configured on this `HybridSearchEngine`.
+    TextOnly,
+    /// An embedding engine is configured, but encoding the query or the
+    /// vector search itself failed; text results were returned instead of
+    /// propagating the error.
+    TextOnlyEmbeddingFallback,
+    /// The embedding step was skipped because the top text results already
+    /// cleared `HybridSearchOptions::skip_embedding_if_text_score_above`.
+    TextOnlyLazySkip,
+    /// The embedding and vector search steps were skipped because
+    /// `HybridSearchOptions::time_budget` was exhausted after text search
+    /// alone; the result is also marked `SearchResult::degraded`.
+    TextOnlyTimeBudgetExceeded,
+}
+
+/// A single layer of a fused candidate's score detail, compared via total
+/// ordering (`NaN` sorts as equal rather than panicking or silently
+/// collapsing comparisons, the way a bare `partial_cmp().unwrap_or(Equal)`
+/// on the final score would).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScoreValue(f32);
+
+impl Eq for ScoreValue {}
+
+impl PartialOrd for ScoreValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoreValue {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// A candidate's score as an ordered list of components — primary fused
+/// relevance first, then secondary tie-breaking signals (here, recency) —
+/// mirroring Meilisearch's score-detail comparison. Two details compare by
+/// walking both lists in order and returning at the first differing
+/// component; a detail missing a trailing component compares as lower than
+/// one that has it, so a candidate with a corroborating secondary signal
+/// outranks an otherwise-tied candidate without one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScoreDetail(Vec<ScoreValue>);
+
+impl ScoreDetail {
+    fn new(primary: f32) -> Self {
+        Self(vec![ScoreValue(primary)])
+    }
+
+    fn with_secondary(mut self, secondary: f32) -> Self {
+        self.0.push(ScoreValue(secondary));
+        self
+    }
+}
+
+impl PartialOrd for ScoreDetail {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoreDetail {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        for index in 0..self.0.len().max(other.0.len()) {
+            let ordering = match (self.0.get(index), other.0.get(index)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => std::cmp::Ordering::Greater,
+                (None, Some(_)) => std::cmp::Ordering::Less,
+                (None, None) => std::cmp::Ordering::Equal,
+            };
+            if ordering != std::cmp::Ordering::Equal {
+                return ordering;
+            }
+        }
+        std::cmp::Ordering::Equal
+    }
+}
+
+/// Secondary tie-breaking signal for `ScoreDetail`: more recently updated
+/// nodes rank above otherwise-tied older ones.
+fn recency_signal(node: &KGNode) -> f32 {
+    node.updated_at.timestamp() as f32
+}
+
+/// Sorts `results` descending by fused score, using `recency_signal` as a
+/// `ScoreDetail` tie-breaker instead of the NaN-collapsing
+/// `partial_cmp().unwrap_or(Equal)` pattern.
+fn sort_by_score_detail(results: &mut [(KGNode, f32)]) {
+    results.sort_by(|(node_a, score_a), (node_b, score_b)| {
+        let detail_a = ScoreDetail::new(*score_a).with_secondary(recency_signal(node_a));
+        let detail_b = ScoreDetail::new(*score_b).with_secondary(recency_signal(node_b));
+        detail_b.cmp(&detail_a)
+    });
+}
 
 pub struct HybridSearchEngine {
     text_engine: TextSearchEngine,
     vector_engine: VectorSearchEngine,
-    embedding_engine: Option<LocalEmbeddingEngine>,
+    embedding_engine: Option<Arc<dyn EmbeddingProvider>>,
     fusion_algorithm: FusionAlgorithm,
     text_weight: f32,
     vector_weight: f32,
+    /// `k` constant for `FusionAlgorithm::ReciprocalRankFusion`'s `1/(k + rank)` term.
+    rrf_k: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FusionAlgorithm {
     LinearCombination,
     ReciprocalRankFusion,
@@ -23,29 +145,108 @@ pub enum FusionAlgorithm {
     MinScore,
 }
 
+impl Default for FusionAlgorithm {
+    fn default() -> Self {
+        FusionAlgorithm::LinearCombination
+    }
+}
+
+/// Which result list(s) `HybridSearchEngine::search_with_strategy` draws
+/// from, for callers that want to pin down a single retrieval path
+/// instead of this engine's configured default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategy {
+    /// Vector/embedding search only.
+    Semantic,
+    /// Text/keyword search only.
+    Keyword,
+    /// Both lists, merged with Reciprocal Rank Fusion.
+    Hybrid,
+}
+
+impl Default for SearchStrategy {
+    fn default() -> Self {
+        SearchStrategy::Hybrid
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HybridSearchOptions {
-    pub text_weight: f32,
-    pub vector_weight: f32,
+    /// Single knob replacing separate `text_weight`/`vector_weight` tuning,
+    /// borrowed from Meilisearch v1.8's hybrid search API: `0.0` is pure
+    /// keyword search, `1.0` is pure vector search. `text_weight()`/
+    /// `vector_weight()` derive `1.0 - semantic_ratio`/`semantic_ratio` from
+    /// this so fusion code never tunes the two independently.
+    pub semantic_ratio: f32,
     pub fusion_algorithm: FusionAlgorithm,
+    pub rrf_k: f32,
     pub diversification: bool,
     pub re_ranking: bool,
     pub query_expansion: bool,
+    /// Skip the embedding/vector-search step entirely when the top text
+    /// result's relevance score already exceeds this, saving an expensive
+    /// local encode. `None` (the default) never skips.
+    pub skip_embedding_if_text_score_above: Option<f32>,
+    /// Drop text candidates below this relevance score before fusion.
+    /// Mirrors aichat's `rag_min_score_text`. `None` (the default) keeps
+    /// every candidate the text engine returns.
+    pub min_score_text: Option<f32>,
+    /// Drop vector candidates below this cosine-similarity score before
+    /// fusion. Mirrors aichat's `rag_min_score_vector`. `None` (the
+    /// default) keeps every candidate the vector engine returns.
+    pub min_score_vector: Option<f32>,
+    /// Like Meilisearch's search cutoff: once this much time has elapsed
+    /// since `search_with_options` started, skip the embedding encode and
+    /// vector search and return the text-only results gathered so far,
+    /// with `SearchResult::degraded` set. `None` (the default) never cuts
+    /// the search short.
+    pub time_budget: Option<Duration>,
+}
+
+impl HybridSearchOptions {
+    pub fn text_weight(&self) -> f32 {
+        1.0 - self.semantic_ratio
+    }
+
+    pub fn vector_weight(&self) -> f32 {
+        self.semantic_ratio
+    }
 }
 
 impl Default for HybridSearchOptions {
     fn default() -> Self {
         Self {
-            text_weight: 0.6,
-            vector_weight: 0.4,
+            semantic_ratio: 0.4,
             fusion_algorithm: FusionAlgorithm::LinearCombination,
+            rrf_k: 60.0,
             diversification: true,
             re_ranking: true,
             query_expansion: false,
+            skip_embedding_if_text_score_above: None,
+            min_score_text: None,
+            min_score_vector: None,
+            time_budget: None,
         }
     }
 }
 
+/// One named, independently-weighted search source for
+/// `HybridSearchEngine::federated_search` — typically a distinct
+/// per-project knowledge graph with its own `HybridSearchEngine` over its
+/// own storage, queried alongside the others as one federated result.
+pub struct FederatedSource<'a> {
+    pub name: String,
+    pub engine: &'a HybridSearchEngine,
+    pub weight: f32,
+}
+
+impl<'a> FederatedSource<'a> {
+    pub fn new(name: impl Into<String>, engine: &'a HybridSearchEngine, weight: f32) -> Self {
+        Self { name: name.into(), engine, weight }
+    }
+}
+
 impl HybridSearchEngine {
     pub fn new(text_engine: TextSearchEngine, vector_engine: VectorSearchEngine) -> Self {
         Self {
@@ -55,10 +256,11 @@ impl HybridSearchEngine {
             fusion_algorithm: FusionAlgorithm::LinearCombination,
             text_weight: 0.6,
             vector_weight: 0.4,
+            rrf_k: 60.0,
         }
     }
 
-    pub fn with_embedding_engine(mut self, engine: LocalEmbeddingEngine) -> Self {
+    pub fn with_embedding_engine(mut self, engine: Arc<dyn EmbeddingProvider>) -> Self {
         self.embedding_engine = Some(engine);
         self
     }
@@ -68,6 +270,12 @@ impl HybridSearchEngine {
         self
     }
 
+    /// Sets the `k` constant used by `FusionAlgorithm::ReciprocalRankFusion`.
+    pub fn with_rrf_k(mut self, rrf_k: f32) -> Self {
+        self.rrf_k = rrf_k;
+        self
+    }
+
     pub fn with_weights(mut self, text_weight: f32, vector_weight: f32) -> Self {
         // Normalize weights
         let total = text_weight + vector_weight;
@@ -76,37 +284,416 @@ impl HybridSearchEngine {
         self
     }
 
-    /// Hybrid search combining text and vector similarity
+    /// Hybrid search combining text and vector similarity, using the fusion
+    /// algorithm, weights, and RRF `k` configured on this engine.
     pub async fn search(&self, query: &str, limit: usize) -> Result<SearchResult> {
-        let options = HybridSearchOptions::default();
-        self.search_with_options(query, limit, &options).await
+        let options = HybridSearchOptions {
+            semantic_ratio: self.vector_weight,
+            fusion_algorithm: self.fusion_algorithm.clone(),
+            rrf_k: self.rrf_k,
+            ..Default::default()
+        };
+        let (result, _path) = self.search_with_options(query, limit, &options).await?;
+        Ok(result)
     }
 
-    /// Hybrid search with custom options
-    pub async fn search_with_options(&self, query: &str, limit: usize, options: &HybridSearchOptions) -> Result<SearchResult> {
-        println!("🔍 Hybrid search: '{}' (limit: {})", query, limit);
+    /// Runs `query` under a single, explicitly-chosen retrieval strategy
+    /// instead of this engine's configured default, for callers (like
+    /// `search_memory`'s `search_strategy` parameter) that want to pin
+    /// down exactly which list(s) a query draws from:
+    /// - `Keyword`: text search only.
+    /// - `Semantic`: vector search only; errors if no embedding engine is
+    ///   configured.
+    /// - `Hybrid`: both lists independently, merged according to this
+    ///   engine's configured `fusion_algorithm`. When that's
+    ///   `ReciprocalRankFusion` (the default), `score(d) = Σ_L w_L / (k +
+    ///   rank_L(d))` over 1-based ranks, where a list a result doesn't
+    ///   appear in contributes nothing — RRF needs no score normalization
+    ///   across the two engines' incomparable scales, so it's the more
+    ///   robust default when keyword and semantic scores have very
+    ///   different distributions. Any other configured algorithm instead
+    ///   sums `w_L * raw_score_L(d)`, i.e. the weighted mode stays
+    ///   available for callers who've normalized or otherwise trust the
+    ///   two engines' raw scores enough to weight them directly. `rrf_k`
+    ///   overrides this engine's configured `k` when `Some`;
+    ///   `text_weight`/`vector_weight` default to `1.0` when `None`.
+    ///
+    /// `SearchResult::component_scores` records each result's per-list
+    /// rank (`text_rank`/`vector_rank`) alongside its raw score, so
+    /// `full`-verbosity callers can see why a result fused where it did.
+    pub async fn search_with_strategy(
+        &self,
+        query: &str,
+        strategy: SearchStrategy,
+        limit: usize,
+        rrf_k: Option<f32>,
+        text_weight: Option<f32>,
+        vector_weight: Option<f32>,
+    ) -> Result<SearchResult> {
+        match strategy {
+            SearchStrategy::Keyword => {
+                let scored = self.text_engine
+                    .search_nodes_with_scores(query, limit, &SearchOptions::default())
+                    .await?;
+
+                let mut result = SearchResult::new();
+                for (rank, (node, score)) in scored.into_iter().enumerate() {
+                    result.component_scores.insert(node.uuid, ComponentScores {
+                        lexical: score,
+                        text_rank: Some(rank + 1),
+                        ..Default::default()
+                    });
+                    result.add_node(node, score);
+                }
+                Ok(result)
+            }
+            SearchStrategy::Semantic => {
+                let embedding_engine = self.embedding_engine.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Semantic search strategy requires an embedding engine"))?;
+                let query_embedding = embed_text(embedding_engine.as_ref(), query).await?;
+                let scored = self.vector_engine.search_nodes_with_scores(&query_embedding, limit).await?;
+
+                let mut result = SearchResult::new();
+                for (rank, (node, score)) in scored.into_iter().enumerate() {
+                    result.semantic_hit_count += 1;
+                    result.component_scores.insert(node.uuid, ComponentScores {
+                        semantic: score,
+                        vector_rank: Some(rank + 1),
+                        ..Default::default()
+                    });
+                    result.add_node(node, score);
+                }
+                Ok(result)
+            }
+            SearchStrategy::Hybrid => {
+                let k = rrf_k.unwrap_or(self.rrf_k);
+                let text_weight = text_weight.unwrap_or(1.0);
+                let vector_weight = vector_weight.unwrap_or(1.0);
+
+                let text_future = self.text_engine.search_nodes_with_scores(query, limit * 2, &SearchOptions::default());
+                let vector_future = async {
+                    match &self.embedding_engine {
+                        None => Ok(Vec::new()),
+                        Some(embedding_engine) => {
+                            let query_embedding = embed_text(embedding_engine.as_ref(), query).await?;
+                            self.vector_engine.search_nodes_with_scores(&query_embedding, limit * 2).await
+                        }
+                    }
+                };
+                let (text_results, vector_results): (Vec<(KGNode, f32)>, Vec<(KGNode, f32)>) =
+                    tokio::try_join!(text_future, vector_future)?;
+
+                let use_rrf = matches!(self.fusion_algorithm, FusionAlgorithm::ReciprocalRankFusion);
+
+                // node, lexical, semantic, text_rank, vector_rank, fused score
+                let mut fused: HashMap<uuid::Uuid, (KGNode, f32, f32, Option<usize>, Option<usize>, f32)> = HashMap::new();
+                for (rank, (node, score)) in text_results.iter().enumerate() {
+                    let entry = fused.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0.0, None, None, 0.0));
+                    entry.1 = *score;
+                    entry.3 = Some(rank + 1);
+                    entry.5 += if use_rrf { text_weight / (k + rank as f32 + 1.0) } else { score * text_weight };
+                }
+                for (rank, (node, score)) in vector_results.iter().enumerate() {
+                    let entry = fused.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0.0, None, None, 0.0));
+                    entry.2 = *score;
+                    entry.4 = Some(rank + 1);
+                    entry.5 += if use_rrf { vector_weight / (k + rank as f32 + 1.0) } else { score * vector_weight };
+                }
 
-        // Perform text search
-        let text_results = self.text_engine.search_nodes(query, limit * 2).await?;
-        
-        // Perform vector search if embedding engine is available
-        let vector_results = if let Some(embedding_engine) = &self.embedding_engine {
-            let query_embedding = embedding_engine.encode_text(query).await?;
-            self.vector_engine.search_nodes(&query_embedding, limit * 2).await?
+                let mut ranked: Vec<(KGNode, f32, f32, Option<usize>, Option<usize>, f32)> = fused.into_values().collect();
+                ranked.sort_by(|a, b| b.5.partial_cmp(&a.5).unwrap_or(std::cmp::Ordering::Equal));
+                ranked.truncate(limit);
+
+                let mut result = SearchResult::new();
+                for (node, lexical, semantic, text_rank, vector_rank, fused_score) in ranked {
+                    if vector_rank.is_some() {
+                        result.semantic_hit_count += 1;
+                    }
+                    result.component_scores.insert(node.uuid, ComponentScores { lexical, semantic, text_rank, vector_rank });
+                    result.add_node(node, fused_score);
+                }
+                Ok(result)
+            }
+        }
+    }
+
+    /// Hybrid search with a single tunable `semantic_ratio` (`0.0` = pure
+    /// keyword, `1.0` = pure vector), independent of whatever
+    /// `fusion_algorithm`/weights this engine was built with. Unlike
+    /// `search_with_options`, the lexical and vector paths run concurrently
+    /// rather than serially (there's no lazy-skip/time-budget logic here to
+    /// make serial ordering pay off), and each path's scores are min-max
+    /// normalized before blending so `semantic_ratio` means the same thing
+    /// regardless of the two engines' raw score scales. `ReciprocalRankFusion`
+    /// needs no such normalization by design, so it's applied straight to
+    /// rank position as usual. The returned `SearchResult::component_scores`
+    /// carries each node's normalized lexical/semantic contribution so
+    /// callers can debug why a result ranked where it did.
+    pub async fn hybrid_search(&self, query: &str, semantic_ratio: f32, limit: usize) -> Result<SearchResult> {
+        println!("🔍 Hybrid search: '{}' (semantic_ratio: {:.2})", query, semantic_ratio);
+        let semantic_ratio = semantic_ratio.clamp(0.0, 1.0);
+
+        let text_future = self.text_engine.search_nodes_with_scores(query, limit * 2, &SearchOptions::default());
+        let vector_future = async {
+            match &self.embedding_engine {
+                None => Ok(Vec::new()),
+                Some(embedding_engine) => {
+                    let query_embedding = embed_text(embedding_engine.as_ref(), query).await?;
+                    self.vector_engine.search_nodes_with_scores(&query_embedding, limit * 2).await
+                }
+            }
+        };
+
+        let (text_results, vector_results): (Vec<(KGNode, f32)>, Vec<(KGNode, f32)>) =
+            tokio::try_join!(text_future, vector_future)?;
+
+        let norm_text = min_max_normalize(&text_results);
+        let norm_vector = min_max_normalize(&vector_results);
+
+        // node, lexical, semantic, fused
+        let mut fused: HashMap<uuid::Uuid, (KGNode, f32, f32, f32)> = HashMap::new();
+
+        if matches!(self.fusion_algorithm, FusionAlgorithm::ReciprocalRankFusion) {
+            let k = self.rrf_k;
+            for (rank, (node, _)) in text_results.iter().enumerate() {
+                let entry = fused.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0.0, 0.0));
+                entry.1 = *norm_text.get(&node.uuid).unwrap_or(&0.0);
+                entry.3 += 1.0 / (k + rank as f32 + 1.0);
+            }
+            for (rank, (node, _)) in vector_results.iter().enumerate() {
+                let entry = fused.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0.0, 0.0));
+                entry.2 = *norm_vector.get(&node.uuid).unwrap_or(&0.0);
+                entry.3 += 1.0 / (k + rank as f32 + 1.0);
+            }
         } else {
-            Vec::new()
+            for (node, _) in &text_results {
+                let entry = fused.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0.0, 0.0));
+                entry.1 = *norm_text.get(&node.uuid).unwrap_or(&0.0);
+            }
+            for (node, _) in &vector_results {
+                let entry = fused.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0.0, 0.0));
+                entry.2 = *norm_vector.get(&node.uuid).unwrap_or(&0.0);
+            }
+            for entry in fused.values_mut() {
+                entry.3 = (1.0 - semantic_ratio) * entry.1 + semantic_ratio * entry.2;
+            }
+        }
+
+        let mut ranked: Vec<(KGNode, f32, f32, f32)> = fused.into_values().collect();
+        ranked.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(limit);
+
+        let mut result = SearchResult::new();
+        for (node, lexical, semantic, fused_score) in ranked {
+            if semantic > 0.0 {
+                result.semantic_hit_count += 1;
+            }
+            result.component_scores.insert(node.uuid, ComponentScores { lexical, semantic, ..Default::default() });
+            result.add_node(node, fused_score);
+        }
+
+        println!("✅ Hybrid search completed ({} results)", result.nodes.len());
+        Ok(result)
+    }
+
+    /// Re-ranks results with Maximal Marginal Relevance instead of pure
+    /// relevance: iteratively picks the candidate maximizing
+    /// `λ·cos(d, q) − (1−λ)·max_{d_j∈S} cos(d, d_j)` against the already-selected
+    /// set `S`, trading off relevance to the query against redundancy with what's
+    /// already surfaced. `lambda` of 1.0 degenerates to pure relevance; 0.0 to
+    /// pure novelty. Requires an embedding engine; falls back to a plain
+    /// `search` when none is configured.
+    pub async fn search_with_mmr(&self, query: &str, limit: usize, lambda: f32) -> Result<SearchResult> {
+        let engine = match &self.embedding_engine {
+            Some(engine) => engine,
+            None => return self.search(query, limit).await,
+        };
+
+        // Over-fetch a candidate pool so the diversity pass has room to trade
+        // off against pure relevance order.
+        let candidate_pool = self.search(query, (limit * 4).max(limit)).await?;
+        let query_embedding = embed_text(engine.as_ref(), query).await?;
+
+        let mut candidates = Vec::new();
+        for node in candidate_pool.nodes {
+            let node_text = format!("{} {} {}", node.name, node.node_type, node.summary);
+            let embedding = embed_text(engine.as_ref(), &node_text).await?;
+            candidates.push((node, embedding));
+        }
+
+        let selected = mmr_select(candidates, &query_embedding, lambda.clamp(0.0, 1.0), limit);
+
+        let mut result = SearchResult::new();
+        for (node, score) in selected {
+            result.add_node(node, score);
+        }
+        result.sort_by_score();
+        Ok(result)
+    }
+
+    /// Hybrid search with custom options. Returns which retrieval path was
+    /// actually taken alongside the result — see [`SearchPath`].
+    ///
+    /// Resilient to embedding failures the way Meilisearch's hybrid search
+    /// is: if encoding the query or the vector search itself fails, and the
+    /// configured weight doesn't make this a pure-vector query
+    /// (`semantic_ratio == 1.0`), the error is logged
+    /// and text-only results are returned rather than aborting the whole
+    /// search. A pure-vector query still hard-fails, since there would be
+    /// no text results to fall back to anyway.
+    pub async fn search_with_options(&self, query: &str, limit: usize, options: &HybridSearchOptions) -> Result<(SearchResult, SearchPath)> {
+        println!("🔍 Hybrid search: '{}' (limit: {})", query, limit);
+
+        let start = Instant::now();
+        let is_pure_vector = options.semantic_ratio >= 1.0;
+
+        // Text search runs first since it's cheapest — if the time budget
+        // is already gone by the time it returns, there's no point paying
+        // for an embedding encode plus vector search on top of it.
+        let mut scored_text_results = self.text_engine
+            .search_nodes_with_scores(query, limit * 2, &SearchOptions::default())
+            .await?;
+
+        if let Some(min_score) = options.min_score_text {
+            scored_text_results.retain(|(_, score)| *score >= min_score);
+        }
+
+        let budget_exhausted = options.time_budget
+            .map(|budget| start.elapsed() >= budget)
+            .unwrap_or(false);
+
+        if budget_exhausted && !is_pure_vector {
+            let combined_results = self.combine_results(&scored_text_results, &[], options).await?;
+
+            let mut result = SearchResult::new();
+            for (node, score) in combined_results.into_iter().take(limit) {
+                result.add_node(node, score);
+            }
+            result.sort_by_score();
+            result.degraded = true;
+
+            println!("⚠️ Hybrid search time budget exhausted, returning degraded text-only results");
+            return Ok((result, SearchPath::TextOnlyTimeBudgetExceeded));
+        }
+
+        // Lazy embedding: if the top text result already clears the
+        // confidence bar, skip the embedding call and vector search
+        // entirely rather than paying for an encode whose contribution
+        // wouldn't change the outcome.
+        if let Some(threshold) = options.skip_embedding_if_text_score_above {
+            let clears_threshold = scored_text_results.first()
+                .map(|(_, score)| *score > threshold)
+                .unwrap_or(false);
+
+            if clears_threshold && !is_pure_vector {
+                let combined_results = self.combine_results(&scored_text_results, &[], options).await?;
+
+                let mut result = SearchResult::new();
+                for (node, score) in combined_results.into_iter().take(limit) {
+                    result.add_node(node, score);
+                }
+                result.sort_by_score();
+
+                println!("✅ Hybrid search completed (lazy: skipped embedding)");
+                return Ok((result, SearchPath::TextOnlyLazySkip));
+            }
+        }
+
+        let (vector_results, path) = match &self.embedding_engine {
+            None => (Vec::new(), SearchPath::TextOnly),
+            Some(embedding_engine) => match embed_text(embedding_engine.as_ref(), query).await {
+                Err(error) if is_pure_vector => return Err(error),
+                Err(error) => {
+                    warn!("Embedding query failed, continuing with text-only results: {}", error);
+                    (Vec::new(), SearchPath::TextOnlyEmbeddingFallback)
+                }
+                Ok(query_embedding) => {
+                    match self.vector_engine.search_nodes_with_scores(&query_embedding, limit * 2).await {
+                        Ok(mut scored_results) => {
+                            if let Some(min_score) = options.min_score_vector {
+                                scored_results.retain(|(_, score)| *score >= min_score);
+                            }
+                            (scored_results, SearchPath::Hybrid)
+                        }
+                        Err(error) if is_pure_vector => return Err(error),
+                        Err(error) => {
+                            warn!("Vector search failed, continuing with text-only results: {}", error);
+                            (Vec::new(), SearchPath::TextOnlyEmbeddingFallback)
+                        }
+                    }
+                }
+            },
         };
 
         // Combine and rank results
-        let combined_results = self.combine_results(&text_results, &vector_results, options).await?;
-        
+        let combined_results = self.combine_results(&scored_text_results, &vector_results, options).await?;
+        let vector_uuids: std::collections::HashSet<uuid::Uuid> =
+            vector_results.iter().map(|(node, _)| node.uuid).collect();
+
         let mut result = SearchResult::new();
         for (node, score) in combined_results.into_iter().take(limit) {
+            if vector_uuids.contains(&node.uuid) {
+                result.semantic_hit_count += 1;
+            }
             result.add_node(node, score);
         }
 
         result.sort_by_score();
         println!("✅ Hybrid search completed");
+        Ok((result, path))
+    }
+
+    /// Runs `query` against every `sources` entry — each its own
+    /// `HybridSearchEngine` over a distinct graph/index, e.g. a per-project
+    /// memory store — and merges them into one globally-ranked result,
+    /// following Meilisearch's federated search design: each source's
+    /// scores are scaled by its configured `weight` before merging, nodes
+    /// appearing in multiple sources are deduplicated by UUID keeping the
+    /// max weighted score, and `SearchResult::source_hit_counts` records
+    /// how many final results came from each source.
+    pub async fn federated_search(
+        sources: &[FederatedSource<'_>],
+        query: &str,
+        limit: usize,
+        options: &HybridSearchOptions,
+    ) -> Result<SearchResult> {
+        println!("🔍 Federated search across {} sources: '{}'", sources.len(), query);
+
+        let mut best: HashMap<uuid::Uuid, (KGNode, f32, String)> = HashMap::new();
+
+        for source in sources {
+            let (source_result, _path) = source.engine.search_with_options(query, limit * 2, options).await?;
+
+            for (node, score) in source_result.nodes_with_scores() {
+                let weighted_score = score * source.weight;
+                best.entry(node.uuid)
+                    .and_modify(|(best_node, best_score, best_source)| {
+                        if weighted_score > *best_score {
+                            *best_node = node.clone();
+                            *best_score = weighted_score;
+                            *best_source = source.name.clone();
+                        }
+                    })
+                    .or_insert((node, weighted_score, source.name.clone()));
+            }
+        }
+
+        let mut ranked: Vec<(KGNode, f32, String)> = best.into_values().collect();
+        ranked.sort_by(|(node_a, score_a, _), (node_b, score_b, _)| {
+            let detail_a = ScoreDetail::new(*score_a).with_secondary(recency_signal(node_a));
+            let detail_b = ScoreDetail::new(*score_b).with_secondary(recency_signal(node_b));
+            detail_b.cmp(&detail_a)
+        });
+        ranked.truncate(limit);
+
+        let mut result = SearchResult::new();
+        for (node, score, source_name) in ranked {
+            *result.source_hit_counts.entry(source_name).or_insert(0) += 1;
+            result.add_node(node, score);
+        }
+        result.sort_by_score();
+
+        println!("✅ Federated search completed");
         Ok(result)
     }
 
@@ -114,55 +701,51 @@ impl HybridSearchEngine {
     pub async fn multi_modal_search(&self, queries: &HashMap<String, String>, limit: usize) -> Result<SearchResult> {
         println!("🔍 Multi-modal search with {} query types", queries.len());
 
-        let mut all_results = Vec::new();
-        let mut modal_weights = HashMap::new();
-
-        for (modal_type, query) in queries {
-            let weight = self.get_modal_weight(modal_type);
-            modal_weights.insert(modal_type.clone(), weight);
+        let modal_weights: HashMap<String, f32> = queries.keys()
+            .map(|modal_type| (modal_type.clone(), self.get_modal_weight(modal_type)))
+            .collect();
 
-            match modal_type.as_str() {
+        // Each modal type's sub-search is independent of the others, so fan
+        // them out concurrently instead of paying for N serial round-trips.
+        let per_modal_results = futures::future::try_join_all(queries.iter().map(|(modal_type, query)| async move {
+            let scored: Vec<(KGNode, f32)> = match modal_type.as_str() {
                 "text" => {
                     let nodes = self.text_engine.search_nodes(query, limit * 2).await?;
-                    for node in nodes {
-                        all_results.push((node, 1.0, modal_type.clone()));
-                    }
+                    nodes.into_iter().map(|node| (node, 1.0)).collect()
                 },
                 "semantic" => {
                     if let Some(embedding_engine) = &self.embedding_engine {
-                        let query_embedding = embedding_engine.encode_text(query).await?;
+                        let query_embedding = embed_text(embedding_engine.as_ref(), query).await?;
                         let nodes = self.vector_engine.search_nodes(&query_embedding, limit * 2).await?;
-                        for node in nodes {
-                            all_results.push((node, 1.0, modal_type.clone()));
-                        }
+                        nodes.into_iter().map(|node| (node, 1.0)).collect()
+                    } else {
+                        Vec::new()
                     }
                 },
                 "hybrid" => {
                     let search_result = self.search(query, limit * 2).await?;
-                                    for (node, score) in search_result.nodes_with_scores() {
-                    all_results.push((node, score, modal_type.clone()));
-                }
+                    search_result.nodes_with_scores()
                 },
                 _ => {
                     // Default to text search for unknown modal types
                     let nodes = self.text_engine.search_nodes(query, limit * 2).await?;
-                    for node in nodes {
-                        all_results.push((node, 1.0, modal_type.clone()));
-                    }
+                    nodes.into_iter().map(|node| (node, 1.0)).collect()
                 }
-            }
-        }
+            };
+            Ok::<_, anyhow::Error>((modal_type.clone(), scored))
+        })).await?;
 
         // Aggregate results by node UUID and apply modal weights
         let mut node_scores: HashMap<uuid::Uuid, (KGNode, f32, usize)> = HashMap::new();
-        
-        for (node, score, modal_type) in all_results {
-            let weight = modal_weights.get(&modal_type).unwrap_or(&1.0);
-            let weighted_score = score * weight;
-            
-            let entry = node_scores.entry(node.uuid).or_insert((node.clone(), 0.0, 0));
-            entry.1 += weighted_score;
-            entry.2 += 1;
+
+        for (modal_type, scored) in per_modal_results {
+            let weight = *modal_weights.get(&modal_type).unwrap_or(&1.0);
+            for (node, score) in scored {
+                let weighted_score = score * weight;
+                let entry = node_scores.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0, 0));
+                entry.1 += weighted_score;
+                entry.2 += 1;
+            }
         }
 
         // Create final result
@@ -187,17 +770,16 @@ impl HybridSearchEngine {
         println!("🔍 Adaptive search with {} feedback items", user_feedback.len());
 
         // Analyze user feedback to adjust weights
-        let (adjusted_text_weight, adjusted_vector_weight) = self.analyze_feedback(user_feedback);
-        
+        let (_adjusted_text_weight, adjusted_vector_weight) = self.analyze_feedback(user_feedback);
+
         // Perform search with adjusted weights
         let options = HybridSearchOptions {
-            text_weight: adjusted_text_weight,
-            vector_weight: adjusted_vector_weight,
+            semantic_ratio: adjusted_vector_weight,
             ..Default::default()
         };
 
-        let mut result = self.search_with_options(query, limit * 2, &options).await?;
-        
+        let (mut result, _path) = self.search_with_options(query, limit * 2, &options).await?;
+
         // Apply learned preferences
         self.apply_learned_preferences(&mut result, user_feedback).await?;
         
@@ -239,19 +821,27 @@ impl HybridSearchEngine {
         println!("🔍 Faceted search with {} facet types", facets.len());
 
         let mut result = SearchResult::new();
-        let mut facet_results = Vec::new();
-
-        // Search within each facet
-        for (facet_type, facet_values) in facets {
-            for facet_value in facet_values {
-                let faceted_query = format!("{} {}:{}", query, facet_type, facet_value);
-                let facet_result = self.search(&faceted_query, limit).await?;
-                
-                for (node, score) in facet_result.nodes_with_scores() {
-                    facet_results.push((node, score, facet_type.clone(), facet_value.clone()));
-                }
-            }
-        }
+
+        // Each facet value is its own independent sub-search, so fan them
+        // all out concurrently rather than paying for one serial round-trip
+        // per facet value.
+        let facet_queries: Vec<(String, String)> = facets.iter()
+            .flat_map(|(facet_type, facet_values)| {
+                facet_values.iter().map(move |facet_value| (facet_type.clone(), facet_value.clone()))
+            })
+            .collect();
+
+        let per_facet_results = futures::future::try_join_all(facet_queries.iter().map(|(facet_type, facet_value)| async move {
+            let faceted_query = format!("{} {}:{}", query, facet_type, facet_value);
+            let facet_result = self.search(&faceted_query, limit).await?;
+            Ok::<_, anyhow::Error>(
+                facet_result.nodes_with_scores().into_iter()
+                    .map(|(node, score)| (node, score, facet_type.clone(), facet_value.clone()))
+                    .collect::<Vec<_>>()
+            )
+        })).await?;
+
+        let facet_results: Vec<(KGNode, f32, String, String)> = per_facet_results.into_iter().flatten().collect();
 
         // Aggregate and score faceted results
         let mut node_scores: HashMap<uuid::Uuid, (KGNode, f32, Vec<String>)> = HashMap::new();
@@ -284,7 +874,7 @@ impl HybridSearchEngine {
 
     // Private helper methods
 
-    async fn combine_results(&self, text_results: &[KGNode], vector_results: &[KGNode], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    async fn combine_results(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         match options.fusion_algorithm {
             FusionAlgorithm::LinearCombination => {
                 self.linear_combination_fusion(text_results, vector_results, options).await
@@ -307,120 +897,124 @@ impl HybridSearchEngine {
         }
     }
 
-    async fn linear_combination_fusion(&self, text_results: &[KGNode], vector_results: &[KGNode], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    /// Fuses on the engines' real relevance scores (BM25-ish text score,
+    /// cosine similarity from vector search) rather than synthetic
+    /// rank-derived ones, so the weighting in `options.text_weight()`/
+    /// `vector_weight()` actually reflects how confident each engine was.
+    async fn linear_combination_fusion(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         let mut combined_scores: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
 
-        // Add text results with text weight
-        for (rank, node) in text_results.iter().enumerate() {
-            let text_score = 1.0 - (rank as f32 / text_results.len().max(1) as f32);
-            combined_scores.insert(node.uuid, (node.clone(), text_score * options.text_weight));
+        for (node, text_score) in text_results {
+            combined_scores.insert(node.uuid, (node.clone(), text_score * options.text_weight()));
         }
 
-        // Add vector results with vector weight
-        for (rank, node) in vector_results.iter().enumerate() {
-            let vector_score = 1.0 - (rank as f32 / vector_results.len().max(1) as f32);
-            let entry = combined_scores.entry(node.uuid).or_insert((node.clone(), 0.0));
-            entry.1 += vector_score * options.vector_weight;
+        for (node, vector_score) in vector_results {
+            let entry = combined_scores.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0));
+            entry.1 += vector_score * options.vector_weight();
         }
 
         let mut results: Vec<(KGNode, f32)> = combined_scores.into_values().collect();
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
+        sort_by_score_detail(&mut results);
+
         Ok(results)
     }
 
-    async fn reciprocal_rank_fusion(&self, text_results: &[KGNode], vector_results: &[KGNode], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    async fn reciprocal_rank_fusion(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         let mut rrf_scores: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
-        let k = 60.0; // RRF constant
-
-        // Calculate RRF scores for text results
-        for (rank, node) in text_results.iter().enumerate() {
+        let k = options.rrf_k;
+
+        // RRF fuses on rank position, not the raw score, by design — it's
+        // what makes it robust to engines whose scores live on very
+        // different scales. The two result slices already arrive ranked by
+        // each engine's real relevance score, so rank position still
+        // reflects that ordering.
+        for (rank, (node, _)) in text_results.iter().enumerate() {
             let rrf_score = 1.0 / (k + rank as f32 + 1.0);
             rrf_scores.insert(node.uuid, (node.clone(), rrf_score));
         }
 
-        // Add RRF scores for vector results
-        for (rank, node) in vector_results.iter().enumerate() {
+        for (rank, (node, _)) in vector_results.iter().enumerate() {
             let rrf_score = 1.0 / (k + rank as f32 + 1.0);
-            let entry = rrf_scores.entry(node.uuid).or_insert((node.clone(), 0.0));
+            let entry = rrf_scores.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0));
             entry.1 += rrf_score;
         }
 
         let mut results: Vec<(KGNode, f32)> = rrf_scores.into_values().collect();
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
+        sort_by_score_detail(&mut results);
+
         Ok(results)
     }
 
-    async fn borda_count_fusion(&self, text_results: &[KGNode], vector_results: &[KGNode], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    async fn borda_count_fusion(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         let mut borda_scores: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
 
-        // Borda count for text results
-        for (rank, node) in text_results.iter().enumerate() {
+        // Borda count is also inherently rank-based by definition (votes
+        // proportional to rank position), unlike the fabricated
+        // `1.0 - rank/len` scores the other fusion methods used to use.
+        for (rank, (node, _)) in text_results.iter().enumerate() {
             let borda_score = (text_results.len() - rank) as f32;
             borda_scores.insert(node.uuid, (node.clone(), borda_score));
         }
 
-        // Add Borda count for vector results
-        for (rank, node) in vector_results.iter().enumerate() {
+        for (rank, (node, _)) in vector_results.iter().enumerate() {
             let borda_score = (vector_results.len() - rank) as f32;
-            let entry = borda_scores.entry(node.uuid).or_insert((node.clone(), 0.0));
+            let entry = borda_scores.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0));
             entry.1 += borda_score;
         }
 
         let mut results: Vec<(KGNode, f32)> = borda_scores.into_values().collect();
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
+        sort_by_score_detail(&mut results);
+
         Ok(results)
     }
 
-    async fn weighted_sum_fusion(&self, text_results: &[KGNode], vector_results: &[KGNode], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    async fn weighted_sum_fusion(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         // Similar to linear combination but with different normalization
         self.linear_combination_fusion(text_results, vector_results, options).await
     }
 
-    async fn max_score_fusion(&self, text_results: &[KGNode], vector_results: &[KGNode], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    /// Takes each candidate's best real score across engines. With
+    /// rank-derived scores this was meaningless across differently-scaled
+    /// engines (the top result from either side always scored ~1.0); using
+    /// the true relevance score makes "max" actually mean something.
+    async fn max_score_fusion(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         let mut max_scores: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
 
-        // Take max score from text results
-        for (rank, node) in text_results.iter().enumerate() {
-            let score = 1.0 - (rank as f32 / text_results.len().max(1) as f32);
-            max_scores.insert(node.uuid, (node.clone(), score));
+        for (node, score) in text_results {
+            max_scores.insert(node.uuid, (node.clone(), *score));
         }
 
-        // Take max score from vector results
-        for (rank, node) in vector_results.iter().enumerate() {
-            let score = 1.0 - (rank as f32 / vector_results.len().max(1) as f32);
-            let entry = max_scores.entry(node.uuid).or_insert((node.clone(), 0.0));
-            entry.1 = entry.1.max(score);
+        for (node, score) in vector_results {
+            let entry = max_scores.entry(node.uuid).or_insert_with(|| (node.clone(), *score));
+            entry.1 = entry.1.max(*score);
         }
 
         let mut results: Vec<(KGNode, f32)> = max_scores.into_values().collect();
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
+        sort_by_score_detail(&mut results);
+
         Ok(results)
     }
 
-    async fn min_score_fusion(&self, text_results: &[KGNode], vector_results: &[KGNode], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
+    /// Takes each candidate's worst real score across engines (absent from
+    /// one engine entirely still counts its other engine's score, matching
+    /// the pre-existing behavior of only tightening scores a candidate
+    /// that appears in both result sets).
+    async fn min_score_fusion(&self, text_results: &[(KGNode, f32)], vector_results: &[(KGNode, f32)], _options: &HybridSearchOptions) -> Result<Vec<(KGNode, f32)>> {
         let mut min_scores: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
 
-        // Initialize with text results
-        for (rank, node) in text_results.iter().enumerate() {
-            let score = 1.0 - (rank as f32 / text_results.len().max(1) as f32);
-            min_scores.insert(node.uuid, (node.clone(), score));
+        for (node, score) in text_results {
+            min_scores.insert(node.uuid, (node.clone(), *score));
         }
 
-        // Take min score from vector results
-        for (rank, node) in vector_results.iter().enumerate() {
-            let score = 1.0 - (rank as f32 / vector_results.len().max(1) as f32);
+        for (node, score) in vector_results {
             if let Some(entry) = min_scores.get_mut(&node.uuid) {
-                entry.1 = entry.1.min(score);
+                entry.1 = entry.1.min(*score);
             }
         }
 
         let mut results: Vec<(KGNode, f32)> = min_scores.into_values().collect();
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
+        sort_by_score_detail(&mut results);
+
         Ok(results)
     }
 
@@ -529,6 +1123,61 @@ impl HybridSearchEngine {
     }
 }
 
+/// Min-max normalizes a ranked result list's scores into `0.0..=1.0` so
+/// `hybrid_search` can blend lexical and vector scores that otherwise live
+/// on unrelated scales. A list with no score spread (including a single
+/// result) normalizes every entry to `1.0` rather than dividing by a
+/// zero range.
+fn min_max_normalize(results: &[(KGNode, f32)]) -> HashMap<uuid::Uuid, f32> {
+    if results.is_empty() {
+        return HashMap::new();
+    }
+
+    let min = results.iter().map(|(_, score)| *score).fold(f32::INFINITY, f32::min);
+    let max = results.iter().map(|(_, score)| *score).fold(f32::NEG_INFINITY, f32::max);
+    let range = max - min;
+
+    results.iter()
+        .map(|(node, score)| {
+            let normalized = if range > f32::EPSILON { (score - min) / range } else { 1.0 };
+            (node.uuid, normalized)
+        })
+        .collect()
+}
+
+/// Greedily selects up to `limit` candidates by Maximal Marginal Relevance:
+/// at each step, picks the remaining candidate maximizing
+/// `λ·cos(d, q) − (1−λ)·max_{d_j∈S} cos(d, d_j)`, where `S` is what's already
+/// been selected. The returned score is each item's MMR score at selection
+/// time, not its raw relevance to the query.
+fn mmr_select(candidates: Vec<(KGNode, Vec<f32>)>, query_embedding: &[f32], lambda: f32, limit: usize) -> Vec<(KGNode, f32)> {
+    let mut remaining = candidates;
+    let mut selected: Vec<(KGNode, Vec<f32>, f32)> = Vec::new();
+
+    while !remaining.is_empty() && selected.len() < limit {
+        let mut best_idx = 0;
+        let mut best_score = f32::NEG_INFINITY;
+
+        for (idx, (_, embedding)) in remaining.iter().enumerate() {
+            let relevance = cosine_similarity(embedding, query_embedding);
+            let redundancy = selected.iter()
+                .map(|(_, selected_embedding, _)| cosine_similarity(embedding, selected_embedding))
+                .fold(0.0f32, f32::max);
+            let mmr_score = lambda * relevance - (1.0 - lambda) * redundancy;
+
+            if mmr_score > best_score {
+                best_score = mmr_score;
+                best_idx = idx;
+            }
+        }
+
+        let (node, embedding) = remaining.remove(best_idx);
+        selected.push((node, embedding, best_score));
+    }
+
+    selected.into_iter().map(|(node, _, score)| (node, score)).collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct UserFeedback {
     pub search_type: String,
@@ -0,0 +1,136 @@
+//! Generic nearest-neighbor building blocks shared across `VectorSearchEngine`'s
+//! search methods, which previously each hand-rolled their own
+//! `BinaryHeap<ScoredItem>` top-k loop with identical push/pop/truncate
+//! logic duplicated four times (`search_nodes_with_scores`,
+//! `search_episodes`, `semantic_search`, `multi_vector_search`). `top_k_by_score`
+//! is that loop, written once. `Proximity` and its `[f32]` impls give the
+//! same treatment to the metric side: `VectorSearchEngine::distance_fn` and
+//! `calculate_similarity` each separately match over `DistanceMetric`, and
+//! those impls are the reusable version of that match.
+
+use std::cmp::Ordering;
+use crate::embeddings::{cosine_similarity, euclidean_distance};
+
+/// A distance between two `T`s. Implementations don't have to be a true
+/// metric (obey the triangle inequality) — `DotProductDistance` isn't —
+/// but callers like the VP-tree that prune using that inequality need one
+/// that is.
+pub trait Proximity<T: ?Sized> {
+    /// Bound by `PartialOrd` rather than the `Ord` a textbook definition
+    /// would use: `f32` can only ever implement `PartialOrd` (NaN has no
+    /// defined order), and every heap comparison already in this module
+    /// handles that via `partial_cmp(...).unwrap_or(Ordering::Equal)`
+    /// rather than a wrapper type that forbids NaN outright.
+    type Distance: PartialOrd;
+
+    fn distance(&self, a: &T, b: &T) -> Self::Distance;
+}
+
+pub struct CosineDistance;
+pub struct EuclideanDistance;
+pub struct DotProductDistance;
+pub struct ManhattanDistance;
+
+impl Proximity<[f32]> for CosineDistance {
+    type Distance = f32;
+
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 - cosine_similarity(a, b)
+    }
+}
+
+impl Proximity<[f32]> for EuclideanDistance {
+    type Distance = f32;
+
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        euclidean_distance(a, b)
+    }
+}
+
+impl Proximity<[f32]> for DotProductDistance {
+    type Distance = f32;
+
+    /// A larger dot product means *more* similar, the opposite sense of a
+    /// distance, and raw dot product isn't bounded or a true metric either
+    /// — so, matching `VectorSearchEngine::distance_fn`'s existing
+    /// handling of `DistanceMetric::DotProduct`, this approximates it with
+    /// cosine distance rather than returning a value nearest-neighbor
+    /// callers would misread as "closer is smaller".
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 - cosine_similarity(a, b)
+    }
+}
+
+impl Proximity<[f32]> for ManhattanDistance {
+    type Distance = f32;
+
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum()
+    }
+}
+
+/// A search result: `item` paired with its `distance` under whatever
+/// `Proximity` impl produced it.
+#[derive(Debug, Clone)]
+pub struct Neighbor<V, D> {
+    pub item: V,
+    pub distance: D,
+}
+
+/// The top `k` items by descending `score` — the one bounded max-heap
+/// loop every `VectorSearchEngine` search method used to write out by
+/// hand. `score` is "higher is better" (a similarity, not a distance) to
+/// match how those callers already compare against `similarity_threshold`
+/// before calling this.
+pub fn top_k_by_score<T>(scored: impl IntoIterator<Item = (T, f32)>, k: usize) -> Vec<(T, f32)> {
+    use std::collections::BinaryHeap;
+
+    struct ScoreHeapEntry<T>(T, f32);
+
+    impl<T> PartialEq for ScoreHeapEntry<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.1 == other.1
+        }
+    }
+    impl<T> Eq for ScoreHeapEntry<T> {}
+    impl<T> PartialOrd for ScoreHeapEntry<T> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl<T> Ord for ScoreHeapEntry<T> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.1.partial_cmp(&other.1).unwrap_or(Ordering::Equal) // max-heap by score: `pop` yields the highest first
+        }
+    }
+
+    let mut heap: BinaryHeap<ScoreHeapEntry<T>> = BinaryHeap::new();
+    for (item, score) in scored {
+        heap.push(ScoreHeapEntry(item, score));
+    }
+
+    let mut results = Vec::with_capacity(k.min(heap.len()));
+    for _ in 0..k.min(heap.len()) {
+        if let Some(ScoreHeapEntry(item, score)) = heap.pop() {
+            results.push((item, score));
+        }
+    }
+    results
+}
+
+/// Exact k-nearest-neighbor over `items` by `metric`, used by
+/// `VectorSearchEngine::approximate_knn_search`'s brute-force fallback
+/// path (the VP-tree covers the indexed case).
+pub fn k_nearest<T: Clone, M: Proximity<[f32], Distance = f32>>(
+    metric: &M,
+    query: &[f32],
+    items: &[(T, Vec<f32>)],
+    k: usize,
+) -> Vec<Neighbor<T, f32>> {
+    let mut scored: Vec<Neighbor<T, f32>> = items.iter()
+        .map(|(item, embedding)| Neighbor { item: item.clone(), distance: metric.distance(query, embedding) })
+        .collect();
+    scored.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(Ordering::Equal));
+    scored.truncate(k);
+    scored
+}
@@ -1,11 +1,26 @@
+pub mod bm25;
+pub mod fuzzy_index;
+pub mod highlight;
 pub mod hybrid_search;
+pub mod metric;
+pub mod proximity;
+pub mod query_parser;
+pub mod stemmer;
 pub mod text_search;
+pub mod tfidf;
 pub mod vector_search;
+pub mod vp_tree;
 
 // Re-export the main engines for easier access
-pub use hybrid_search::{HybridSearchEngine, FusionAlgorithm, HybridSearchOptions, UserFeedback, SearchContext, PreviousQuery};
+pub use bm25::ScoringStrategy;
+pub use fuzzy_index::{FuzzyAutomatonBuilders, FuzzyMatch};
+pub use highlight::{FormatOptions, MatchBounds, MatchedNode};
+pub use metric::{CosineDistance, DotProductDistance, EuclideanDistance, ManhattanDistance, Neighbor, Proximity};
+pub use query_parser::Operation;
+pub use hybrid_search::{HybridSearchEngine, FusionAlgorithm, HybridSearchOptions, SearchPath, SearchStrategy, UserFeedback, SearchContext, PreviousQuery};
 pub use text_search::{TextSearchEngine, BoostFactors, SearchOptions};
-pub use vector_search::{VectorSearchEngine, DistanceMetric};
+pub use tfidf::{TfIdfIndex, TfIdfHit};
+pub use vector_search::{VectorSearchEngine, DistanceMetric, SearchFilter};
 
 use anyhow::Result;
 use uuid::Uuid;
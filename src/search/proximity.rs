@@ -0,0 +1,70 @@
+//! Principled term-proximity distance for multi-term queries, replacing
+//! `calculate_proximity_score`'s old per-position `1/distance` sum (which
+//! double-counted overlapping windows and didn't model the cheapest overall
+//! arrangement of the query's terms).
+//!
+//! Builds an implicit layered graph: layer `i` holds every position in the
+//! document where query term `i` occurs, a virtual source feeds layer 0,
+//! and a virtual sink receives from the last layer. An edge from a
+//! position in layer `i` to one in layer `i + 1` costs `max(gap - 1, 0)`,
+//! where `gap` is the distance between the two positions (adjacent terms,
+//! `gap == 1`, cost nothing; identical or non-monotonic positions are
+//! clamped to cost 0 rather than going negative). Because the layers are
+//! already topologically ordered, an iterative Dijkstra over them reduces
+//! to a single forward DP pass — the minimal source→sink path is the
+//! document's proximity distance.
+
+/// The cheapest total positional cost of arranging every term in
+/// `query_terms` (in order) within `words`, or `None` if some term never
+/// occurs — such a document is excluded from proximity ranking outright
+/// rather than penalized with an arbitrary large cost.
+pub fn proximity_distance(words: &[&str], query_terms: &[&str]) -> Option<u32> {
+    if query_terms.is_empty() {
+        return None;
+    }
+
+    // Layer i: every position in `words` matching query_terms[i].
+    let layers: Vec<Vec<usize>> = query_terms
+        .iter()
+        .map(|term| {
+            words
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| **word == *term)
+                .map(|(pos, _)| pos)
+                .collect()
+        })
+        .collect();
+
+    if layers.iter().any(|layer| layer.is_empty()) {
+        return None;
+    }
+
+    // dist[i] is the cheapest cost from the virtual source to
+    // `layers[layer_idx][i]`, updated one layer at a time.
+    let mut dist: Vec<u32> = vec![0; layers[0].len()];
+
+    for layer_idx in 1..layers.len() {
+        let prev_positions = &layers[layer_idx - 1];
+        let curr_positions = &layers[layer_idx];
+        let mut next_dist = vec![u32::MAX; curr_positions.len()];
+
+        for (curr_i, &curr_pos) in curr_positions.iter().enumerate() {
+            for (prev_i, &prev_pos) in prev_positions.iter().enumerate() {
+                if dist[prev_i] == u32::MAX {
+                    continue;
+                }
+                let gap = curr_pos as i64 - prev_pos as i64;
+                let edge_cost = (gap - 1).max(0) as u32;
+                let candidate = dist[prev_i].saturating_add(edge_cost);
+                if candidate < next_dist[curr_i] {
+                    next_dist[curr_i] = candidate;
+                }
+            }
+        }
+
+        dist = next_dist;
+    }
+
+    dist.into_iter().filter(|&cost| cost != u32::MAX).min()
+}
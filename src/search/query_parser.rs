@@ -0,0 +1,198 @@
+//! Boolean/phrase query AST for `TextSearchEngine::boolean_search` (and the
+//! `Phrase` leaf shared with `phrase_search`). Replaces the previous
+//! ` AND `/` OR ` string replacement, which broke on parentheses, quoted
+//! phrases, and operator precedence, with a real tokenizer + recursive
+//! descent parser. Precedence (tightest first): `NOT`, `AND` (including
+//! bare juxtaposition, e.g. `rust http`), `OR`.
+
+use anyhow::{bail, Result};
+
+/// A parsed boolean/phrase query. Leaves (`Query`, `Phrase`) are evaluated
+/// against storage into per-node candidate scores; `And`/`Or`/`Not` combine
+/// those candidate sets by intersection/union/difference.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    And(Vec<Operation>),
+    Or(Vec<Operation>),
+    Not(Box<Operation>),
+    Query(String),
+    Phrase(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+    Phrase(Vec<String>),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+            continue;
+        }
+        if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+            continue;
+        }
+        if c == '"' {
+            let mut j = i + 1;
+            let mut phrase = String::new();
+            while j < chars.len() && chars[j] != '"' {
+                phrase.push(chars[j]);
+                j += 1;
+            }
+            if j >= chars.len() {
+                bail!("Unterminated quoted phrase in query: {}", query);
+            }
+            let words: Vec<String> = phrase.split_whitespace().map(|w| w.to_lowercase()).collect();
+            if words.is_empty() {
+                bail!("Empty quoted phrase in query: {}", query);
+            }
+            tokens.push(Token::Phrase(words));
+            i = j + 1;
+            continue;
+        }
+        if c == '+' || c == '-' {
+            // `+word`/`-word` sugar: `-` negates the following term, `+` is
+            // a no-op (AND is already the default combinator).
+            let mut j = i + 1;
+            let mut word = String::new();
+            while j < chars.len() && !chars[j].is_whitespace() && chars[j] != '(' && chars[j] != ')' {
+                word.push(chars[j]);
+                j += 1;
+            }
+            if word.is_empty() {
+                bail!("Dangling '{}' with no following term in query: {}", c, query);
+            }
+            if c == '-' {
+                tokens.push(Token::Not);
+            }
+            tokens.push(Token::Word(word.to_lowercase()));
+            i = j;
+            continue;
+        }
+
+        let mut j = i;
+        let mut word = String::new();
+        while j < chars.len()
+            && !chars[j].is_whitespace()
+            && chars[j] != '('
+            && chars[j] != ')'
+            && chars[j] != '"'
+        {
+            word.push(chars[j]);
+            j += 1;
+        }
+        match word.to_uppercase().as_str() {
+            "AND" => tokens.push(Token::And),
+            "OR" => tokens.push(Token::Or),
+            "NOT" => tokens.push(Token::Not),
+            _ => tokens.push(Token::Word(word.to_lowercase())),
+        }
+        i = j;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// expr := and_expr (`OR` and_expr)*
+    fn parse_expr(&mut self) -> Result<Operation> {
+        let mut parts = vec![self.parse_and_expr()?];
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.advance();
+            parts.push(self.parse_and_expr()?);
+        }
+        Ok(if parts.len() == 1 { parts.pop().unwrap() } else { Operation::Or(parts) })
+    }
+
+    /// and_expr := not_expr ([`AND`] not_expr)* — a bare juxtaposition of
+    /// two atoms (no explicit `AND`) is treated as `AND`, matching how most
+    /// search engines read `rust http`.
+    fn parse_and_expr(&mut self) -> Result<Operation> {
+        let mut parts = vec![self.parse_not_expr()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.advance();
+                    parts.push(self.parse_not_expr()?);
+                }
+                Some(Token::Or) | Some(Token::RParen) | None => break,
+                _ => parts.push(self.parse_not_expr()?),
+            }
+        }
+        Ok(if parts.len() == 1 { parts.pop().unwrap() } else { Operation::And(parts) })
+    }
+
+    /// not_expr := `NOT`? atom
+    fn parse_not_expr(&mut self) -> Result<Operation> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.advance();
+            Ok(Operation::Not(Box::new(self.parse_atom()?)))
+        } else {
+            self.parse_atom()
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Operation> {
+        match self.advance() {
+            Some(Token::LParen) => {
+                let inner = self.parse_expr()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => bail!("Unbalanced parentheses in boolean query"),
+                }
+            }
+            Some(Token::Word(word)) => Ok(Operation::Query(word)),
+            Some(Token::Phrase(words)) => Ok(Operation::Phrase(words)),
+            other => bail!("Unexpected token in boolean query: {:?}", other),
+        }
+    }
+}
+
+/// Parses a boolean/phrase query into an `Operation` tree.
+pub fn parse_query(query: &str) -> Result<Operation> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        bail!("Empty boolean query");
+    }
+
+    let mut parser = Parser { tokens, pos: 0 };
+    let operation = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        bail!("Unexpected trailing tokens in boolean query: {}", query);
+    }
+    Ok(operation)
+}
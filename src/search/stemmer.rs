@@ -0,0 +1,57 @@
+//! Tokenization helper backing `TextSearchEngine::apply_stemming`, which
+//! previously "stemmed" by blindly stripping the substrings `"ing"`/`"ed"`/
+//! `"s"` from the whole query string — mangling words like `"boss"` into
+//! `"bo"` and doing nothing for irregular forms (`"ran"` never becomes
+//! `"run"`). Real stemming is delegated to the `rust_stemmers` crate's
+//! Snowball (Porter2) English algorithm; this module only owns splitting
+//! text into the word tokens that algorithm expects.
+
+/// Splits `text` into lowercase alphanumeric word tokens, dropping
+/// punctuation rather than treating it as part of a word the way a naive
+/// `split_whitespace` would.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_string())
+        .collect()
+}
+
+/// Like `tokenize`, but also splits each already-alphanumeric-bounded token
+/// on camelCase boundaries (snake_case is already handled by `tokenize`'s
+/// split on `_`), so an identifier like `parseHTTPRequest` yields `parse`,
+/// `http`, `request` instead of one opaque token. Used for code/identifier
+/// text (e.g. `tfidf::TfIdfIndex`); plain prose should keep using `tokenize`.
+pub fn tokenize_code(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .flat_map(split_camel_case)
+        .collect()
+}
+
+/// Splits one token on lowercase/digit-to-uppercase transitions and on the
+/// boundary between a run of uppercase letters and the lowercase word that
+/// follows it (so `HTTPServer` splits into `HTTP`, `Server` rather than
+/// `H`, `T`, `T`, `P`, `Server`), lowercasing each piece.
+fn split_camel_case(token: &str) -> Vec<String> {
+    let chars: Vec<char> = token.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        let starts_new_word = i > 0 && c.is_uppercase() && {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            !prev.is_uppercase() || next_is_lower
+        };
+        if starts_new_word && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words.into_iter().map(|w| w.to_lowercase()).collect()
+}
@@ -1,9 +1,15 @@
 use anyhow::Result;
 use crate::graph::{KGNode, KGEdge, Episode, SearchResult};
 use crate::graph::storage::GraphStorage;
+use crate::search::bm25::{Bm25Stats, ScoringStrategy};
+use crate::search::fuzzy_index::FuzzyAutomatonBuilders;
+use crate::search::query_parser::{self, Operation};
+use crate::search::proximity;
+use crate::search::highlight;
+use crate::search::stemmer;
 use std::collections::HashMap;
 use regex::Regex;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use crate::config::SearchConfig;
 
 pub struct TextSearchEngine {
@@ -13,6 +19,19 @@ pub struct TextSearchEngine {
     min_score_threshold: f32,
     boost_factors: BoostFactors,
     config: SearchConfig,
+    /// Cached Levenshtein-automaton builders for `fuzzy_search` — see
+    /// `fuzzy_index` for why these are built once instead of per query.
+    fuzzy_builders: FuzzyAutomatonBuilders,
+    /// Which relevance model `calculate_node_relevance_score`/
+    /// `calculate_episode_relevance_score` use.
+    scoring_strategy: ScoringStrategy,
+    /// Per-field document frequencies/average lengths for `ScoringStrategy::Bm25`,
+    /// rebuilt from storage when stale — see `bm25::Bm25Stats`.
+    bm25_cache: RwLock<Option<Bm25Stats>>,
+    /// Snowball (Porter2) English stemmer backing `apply_stemming` — built
+    /// once since `rust_stemmers::Stemmer` only depends on the algorithm,
+    /// not on any per-query state.
+    stemmer: rust_stemmers::Stemmer,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +87,10 @@ impl TextSearchEngine {
             min_score_threshold: 0.1,
             boost_factors: BoostFactors::default(),
             config: Default::default(),
+            fuzzy_builders: FuzzyAutomatonBuilders::new(),
+            scoring_strategy: ScoringStrategy::default(),
+            bm25_cache: RwLock::new(None),
+            stemmer: rust_stemmers::Stemmer::create(rust_stemmers::Algorithm::English),
         }
     }
 
@@ -76,6 +99,11 @@ impl TextSearchEngine {
         self
     }
 
+    pub fn with_scoring_strategy(mut self, scoring_strategy: ScoringStrategy) -> Self {
+        self.scoring_strategy = scoring_strategy;
+        self
+    }
+
     pub fn with_min_score_threshold(mut self, threshold: f32) -> Self {
         self.min_score_threshold = threshold.clamp(0.0, 1.0);
         self
@@ -89,14 +117,23 @@ impl TextSearchEngine {
 
     /// Node search with custom options
     pub async fn search_nodes_with_options(&self, query: &str, limit: usize, options: &SearchOptions) -> Result<Vec<KGNode>> {
+        let results = self.search_nodes_with_scores(query, limit, options).await?;
+        Ok(results.into_iter().map(|(node, _)| node).collect())
+    }
+
+    /// Like `search_nodes_with_options`, but keeps each node's relevance
+    /// score instead of discarding it — callers that need to judge result
+    /// confidence (e.g. deciding whether a vector search is still worth
+    /// running) use this instead.
+    pub async fn search_nodes_with_scores(&self, query: &str, limit: usize, options: &SearchOptions) -> Result<Vec<(KGNode, f32)>> {
         println!("🔍 Text search for nodes: '{}' (limit: {})", query, limit);
 
         // Parse and enhance the query
         let enhanced_query = self.enhance_query(query, options)?;
-        
+
         // Perform FTS5 search
         let raw_results = self.storage.search_nodes_by_text(&enhanced_query, None, limit * 2)?;
-        
+
         // Apply advanced scoring and ranking
         let mut scored_results = Vec::new();
         for node in raw_results {
@@ -110,8 +147,45 @@ impl TextSearchEngine {
         scored_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         scored_results.truncate(limit);
 
-        let results: Vec<KGNode> = scored_results.into_iter().map(|(node, _)| node).collect();
-        println!("✅ Found {} matching nodes", results.len());
+        println!("✅ Found {} matching nodes", scored_results.len());
+        Ok(scored_results)
+    }
+
+    /// Like `search_nodes_with_scores`, but also returns where each query
+    /// term matched (reusing the term list `calculate_text_match_score`
+    /// scores against) and, per `format_options`, a ready-to-display
+    /// snippet windowed around the densest cluster of matches — everything
+    /// a UI needs to highlight or preview a result instead of just a score.
+    pub async fn search_nodes_with_matches(&self, query: &str, limit: usize, format_options: &highlight::FormatOptions) -> Result<Vec<highlight::MatchedNode>> {
+        let scored = self.search_nodes_with_scores(query, limit, &SearchOptions::default()).await?;
+        let query_terms: Vec<&str> = query.split_whitespace().collect();
+
+        let mut results = Vec::with_capacity(scored.len());
+        for (node, score) in scored {
+            let mut matches = highlight::find_matches("name", &node.name, &query_terms);
+            matches.extend(highlight::find_matches("node_type", &node.node_type, &query_terms));
+            matches.extend(highlight::find_matches("summary", &node.summary, &query_terms));
+
+            // Snippet comes from whichever field actually matched, summary
+            // preferred since it's the field most worth previewing.
+            let snippet = [
+                ("summary", &node.summary),
+                ("name", &node.name),
+                ("node_type", &node.node_type),
+            ]
+                .into_iter()
+                .find_map(|(field, text)| {
+                    let field_matches: Vec<highlight::MatchBounds> =
+                        matches.iter().filter(|m| m.field == field).cloned().collect();
+                    if field_matches.is_empty() {
+                        return None;
+                    }
+                    Some(highlight::format_snippet(text, &field_matches, format_options))
+                });
+
+            results.push(highlight::MatchedNode { node, score, matches, snippet });
+        }
+
         Ok(results)
     }
 
@@ -202,7 +276,10 @@ impl TextSearchEngine {
         Ok(result)
     }
 
-    /// Phrase search with proximity matching
+    /// Phrase search with proximity matching. Shares the same `Phrase`
+    /// leaf evaluation `boolean_search` uses for a quoted phrase
+    /// (`query_parser::Operation::Phrase`), then layers a proximity bonus
+    /// on top since the boolean AST has no notion of "near" distance.
     pub async fn phrase_search(&self, phrase: &str, proximity: u32, limit: usize) -> Result<Vec<KGNode>> {
         println!("🔍 Phrase search: '{}' (proximity: {})", phrase, proximity);
 
@@ -212,18 +289,18 @@ impl TextSearchEngine {
             return self.search_nodes(phrase, limit).await;
         }
 
-        // Build proximity query for FTS5
-        let proximity_query = if proximity == 0 {
-            format!("\"{}\"", phrase) // Exact phrase
-        } else {
-            format!("NEAR({}, {})", terms.join(" "), proximity)
-        };
+        let words: Vec<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+        let phrase_matches = self.evaluate_phrase_leaf(&words, limit).await?;
 
-        let raw_results = self.storage.search_nodes_by_text(&proximity_query, None, limit * 2)?;
-        
         let mut scored_results = Vec::new();
-        for node in raw_results {
-            let score = self.calculate_phrase_score(&node, &terms, proximity).await?;
+        for (node, base_score) in phrase_matches.into_values() {
+            let mut score = base_score;
+            if proximity > 0 {
+                let combined_text = format!("{} {} {}", node.name, node.node_type, node.summary).to_lowercase();
+                let word_refs: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+                score += self.calculate_proximity_score(&combined_text, &word_refs, proximity);
+            }
+            let score = score.clamp(0.0, 1.0);
             if score >= self.min_score_threshold {
                 scored_results.push((node, score));
             }
@@ -237,33 +314,65 @@ impl TextSearchEngine {
         Ok(results)
     }
 
-    /// Fuzzy search with edit distance tolerance
+    /// Fuzzy search with edit distance tolerance.
+    ///
+    /// Instead of expanding `query` into `*query*`-style wildcard patterns
+    /// and rescanning every candidate's text pairwise, this streams the
+    /// full corpus once into a term → owning-nodes index, intersects a
+    /// cached Levenshtein automaton (see `fuzzy_index`) with an FST of
+    /// those terms to find exactly the ones within `max_distance` edits of
+    /// `query`, then scores each owning node by its best-matching term.
     pub async fn fuzzy_search(&self, query: &str, max_distance: u32, limit: usize) -> Result<Vec<KGNode>> {
         println!("🔍 Fuzzy search: '{}' (max distance: {})", query, max_distance);
 
-        // Generate fuzzy query patterns
-        let fuzzy_patterns = self.generate_fuzzy_patterns(query, max_distance);
-        let mut all_results = Vec::new();
-
-        for pattern in fuzzy_patterns {
-            let pattern_results = self.storage.search_nodes_by_text(&pattern, None, limit)?;
-            all_results.extend(pattern_results);
-        }
-
-        // Remove duplicates and score
-        let mut unique_results: HashMap<uuid::Uuid, KGNode> = HashMap::new();
-        for node in all_results {
-            unique_results.insert(node.uuid, node);
+        let query_lower = query.to_lowercase();
+
+        // Page through every node (same idiom as
+        // `ValidationEngine::validate_stream`) building a term -> owning
+        // nodes index; the automaton intersection below only proves which
+        // *terms* are in range, so owning nodes still need a lookup.
+        let mut term_to_nodes: HashMap<String, Vec<KGNode>> = HashMap::new();
+        let mut offset = 0;
+        let page_size = 500;
+        loop {
+            let page = self.storage.get_nodes_page(offset, page_size)?;
+            if page.is_empty() {
+                break;
+            }
+            let page_len = page.len();
+            for node in page {
+                let combined = format!("{} {} {}", node.name, node.node_type, node.summary).to_lowercase();
+                for term in combined.split_whitespace() {
+                    term_to_nodes.entry(term.to_string()).or_default().push(node.clone());
+                }
+            }
+            offset += page_len;
         }
 
-        let mut scored_results = Vec::new();
-        for node in unique_results.into_values() {
-            let score = self.calculate_fuzzy_score(&node, query, max_distance).await?;
-            if score >= self.min_score_threshold {
-                scored_results.push((node, score));
+        let fuzzy_matches = self.fuzzy_builders.fuzzy_match(
+            term_to_nodes.keys().cloned(),
+            &query_lower,
+            max_distance,
+            false,
+        )?;
+
+        let mut scored_results: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
+        for fuzzy_match in &fuzzy_matches {
+            let max_len = query_lower.len().max(fuzzy_match.term.len()).max(1) as f32;
+            let similarity = 1.0 - (fuzzy_match.distance as f32 / max_len);
+            if let Some(nodes) = term_to_nodes.get(&fuzzy_match.term) {
+                for node in nodes {
+                    let entry = scored_results.entry(node.uuid).or_insert_with(|| (node.clone(), 0.0));
+                    entry.1 = entry.1.max(similarity);
+                }
             }
         }
 
+        let mut scored_results: Vec<(KGNode, f32)> = scored_results
+            .into_values()
+            .filter(|(_, score)| *score >= self.min_score_threshold)
+            .collect();
+
         scored_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         scored_results.truncate(limit);
 
@@ -272,23 +381,23 @@ impl TextSearchEngine {
         Ok(results)
     }
 
-    /// Boolean search with AND, OR, NOT operators
+    /// Boolean search with AND, OR, NOT operators and parenthesised
+    /// grouping, parsed into an `Operation` tree by `query_parser` (see its
+    /// module doc for the grammar) rather than the previous
+    /// ` AND `/` OR ` string replacement. Each leaf is evaluated against
+    /// storage into a candidate-uuid → score map; `And`/`Or`/`Not` combine
+    /// those maps by intersection/union/difference, and surviving scores
+    /// are the sum of whichever leaves matched.
     pub async fn boolean_search(&self, query: &str, limit: usize) -> Result<Vec<KGNode>> {
         println!("🔍 Boolean search: '{}'", query);
 
-        // Parse boolean query
-        let boolean_query = self.parse_boolean_query(query)?;
-        
-        // Execute boolean search using FTS5 boolean syntax
-        let raw_results = self.storage.search_nodes_by_text(&boolean_query, None, limit * 2)?;
-        
-        let mut scored_results = Vec::new();
-        for node in raw_results {
-            let score = self.calculate_boolean_score(&node, query).await?;
-            if score >= self.min_score_threshold {
-                scored_results.push((node, score));
-            }
-        }
+        let operation = query_parser::parse_query(query)?;
+        let evaluated = self.evaluate_operation(&operation, limit).await?;
+
+        let mut scored_results: Vec<(KGNode, f32)> = evaluated
+            .into_values()
+            .filter(|(_, score)| *score >= self.min_score_threshold)
+            .collect();
 
         scored_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         scored_results.truncate(limit);
@@ -322,8 +431,21 @@ impl TextSearchEngine {
     }
 
     async fn calculate_node_relevance_score(&self, node: &KGNode, query: &str, _options: &SearchOptions) -> Result<f32> {
+        if self.scoring_strategy == ScoringStrategy::Bm25 {
+            let query_terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+            let cache = self.ensure_bm25_stats().await?;
+            let guard = cache.read().unwrap();
+            let stats = guard.as_ref().expect("ensure_bm25_stats always populates the cache before returning");
+            let total = stats.score_node(&node.name, &node.node_type, &node.summary, &query_terms, &self.boost_factors);
+            // BM25 is unbounded, unlike the boost-factor model's 0..1 range;
+            // squash it so `min_score_threshold` still means something
+            // comparable regardless of which strategy produced the score.
+            return Ok((total / (total + 1.0)).clamp(0.0, 1.0));
+        }
+
         let query_terms: Vec<&str> = query.split_whitespace().collect();
         let mut total_score = 0.0;
+        let mut total_weight = self.boost_factors.name_boost + self.boost_factors.type_boost + self.boost_factors.summary_boost;
 
         // Score based on name matches
         let name_score = self.calculate_text_match_score(&node.name, &query_terms);
@@ -337,11 +459,36 @@ impl TextSearchEngine {
         let summary_score = self.calculate_text_match_score(&node.summary, &query_terms);
         total_score += summary_score * self.boost_factors.summary_boost;
 
+        // For multi-term queries, reward documents where the terms occur
+        // close together over ones where they're merely all present
+        // somewhere, using the same shortest-path proximity distance
+        // `phrase_search` uses.
+        if query_terms.len() > 1 {
+            let lower_terms: Vec<String> = query_terms.iter().map(|t| t.to_lowercase()).collect();
+            let lower_term_refs: Vec<&str> = lower_terms.iter().map(|t| t.as_str()).collect();
+            let combined = format!("{} {} {}", node.name, node.node_type, node.summary).to_lowercase();
+            let words: Vec<&str> = combined.split_whitespace().collect();
+            if let Some(distance) = proximity::proximity_distance(&words, &lower_term_refs) {
+                let proximity_score = 1.0 / (1.0 + distance as f32);
+                total_score += proximity_score * self.boost_factors.name_boost;
+                total_weight += self.boost_factors.name_boost;
+            }
+        }
+
         // Normalize score
-        Ok((total_score / (self.boost_factors.name_boost + self.boost_factors.type_boost + self.boost_factors.summary_boost)).clamp(0.0, 1.0))
+        Ok((total_score / total_weight).clamp(0.0, 1.0))
     }
 
     async fn calculate_episode_relevance_score(&self, episode: &Episode, query: &str, _options: &SearchOptions) -> Result<f32> {
+        if self.scoring_strategy == ScoringStrategy::Bm25 {
+            let query_terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+            let cache = self.ensure_bm25_stats().await?;
+            let guard = cache.read().unwrap();
+            let stats = guard.as_ref().expect("ensure_bm25_stats always populates the cache before returning");
+            let total = stats.score_episode(&episode.name, &episode.content, &query_terms, &self.boost_factors);
+            return Ok((total / (total + 1.0)).clamp(0.0, 1.0));
+        }
+
         let query_terms: Vec<&str> = query.split_whitespace().collect();
         let mut total_score = 0.0;
 
@@ -357,6 +504,27 @@ impl TextSearchEngine {
         Ok((total_score / (self.boost_factors.name_boost + self.boost_factors.content_boost)).clamp(0.0, 1.0))
     }
 
+    /// Returns the cached `Bm25Stats`, rebuilding from storage first if the
+    /// cache is empty or more than 5 minutes old — the same staleness
+    /// policy `QueryEngine::ensure_graph_cache` uses for its graph cache.
+    async fn ensure_bm25_stats(&self) -> Result<&RwLock<Option<Bm25Stats>>> {
+        let should_rebuild = {
+            let guard = self.bm25_cache.read().unwrap();
+            match guard.as_ref() {
+                None => true,
+                Some(stats) => stats.built_at.elapsed().as_secs() > 300,
+            }
+        };
+
+        if should_rebuild {
+            let rebuilt = Bm25Stats::rebuild(&self.storage)?;
+            let mut guard = self.bm25_cache.write().unwrap();
+            *guard = Some(rebuilt);
+        }
+
+        Ok(&self.bm25_cache)
+    }
+
     fn calculate_text_match_score(&self, text: &str, query_terms: &[&str]) -> f32 {
         let text_lower = text.to_lowercase();
         let mut score = 0.0;
@@ -380,73 +548,151 @@ impl TextSearchEngine {
         score / total_terms
     }
 
-    async fn calculate_phrase_score(&self, node: &KGNode, terms: &[&str], proximity: u32) -> Result<f32> {
-        let combined_text = format!("{} {} {}", node.name, node.node_type, node.summary).to_lowercase();
-        let mut score = 0.0;
-
-        // Check for exact phrase match
-        let phrase = terms.join(" ").to_lowercase();
-        if combined_text.contains(&phrase) {
-            score += 1.0;
+    /// Proximity bonus for `terms` occurring in `text`, via the cheapest
+    /// source→sink path through each term's matched positions (see
+    /// `proximity::proximity_distance`) rather than summing `1/distance`
+    /// independently per position, which double-counted overlapping
+    /// windows and didn't model the cheapest overall arrangement. A
+    /// distance beyond `max_distance`, or a term that never occurs, scores
+    /// zero instead of being penalized with a capped-but-nonzero bonus.
+    fn calculate_proximity_score(&self, text: &str, terms: &[&str], max_distance: u32) -> f32 {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match proximity::proximity_distance(&words, terms) {
+            Some(distance) if distance <= max_distance => 1.0 / (1.0 + distance as f32),
+            _ => 0.0,
         }
+    }
 
-        // Check for proximity matches
-        if proximity > 0 {
-            score += self.calculate_proximity_score(&combined_text, terms, proximity);
-        }
+    /// Evaluates an `Operation` tree against storage into a
+    /// uuid → (node, score) map. `Query`/`Phrase` leaves hit storage
+    /// directly; `And`/`Or`/`Not` combine child maps by
+    /// intersection/union/difference, summing scores for nodes that match
+    /// more than one leaf.
+    fn evaluate_operation<'a>(&'a self, op: &'a Operation, limit: usize) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<HashMap<uuid::Uuid, (KGNode, f32)>>> + Send + 'a>> {
+        Box::pin(async move {
+            match op {
+                Operation::Query(term) => self.evaluate_term(term, limit).await,
+                Operation::Phrase(words) => self.evaluate_phrase_leaf(words, limit).await,
+                Operation::And(children) => {
+                    // NOT only makes sense relative to the other children of
+                    // this AND (there's no "everything" set to subtract
+                    // from otherwise), so positives are intersected first
+                    // and negatives are subtracted from that result.
+                    let mut positives = Vec::new();
+                    let mut negatives = Vec::new();
+                    for child in children {
+                        match child {
+                            Operation::Not(inner) => negatives.push(inner.as_ref()),
+                            other => positives.push(other),
+                        }
+                    }
 
-        Ok(score.clamp(0.0, 1.0))
-    }
+                    let mut result: Option<HashMap<uuid::Uuid, (KGNode, f32)>> = None;
+                    for positive in &positives {
+                        let evaluated = self.evaluate_operation(positive, limit).await?;
+                        result = Some(match result {
+                            None => evaluated,
+                            Some(acc) => acc
+                                .into_iter()
+                                .filter_map(|(uuid, (node, score))| {
+                                    evaluated.get(&uuid).map(|(_, other_score)| (uuid, (node, score + other_score)))
+                                })
+                                .collect(),
+                        });
+                    }
 
-    fn calculate_proximity_score(&self, text: &str, terms: &[&str], max_distance: u32) -> f32 {
-        let words: Vec<&str> = text.split_whitespace().collect();
-        let mut max_score: f32 = 0.0;
-
-        for (i, word) in words.iter().enumerate() {
-            if terms.contains(word) {
-                let mut local_score = 1.0;
-                let mut found_terms = 1;
-
-                // Look for other terms within proximity
-                for j in 1..=max_distance as usize {
-                    if i + j < words.len() && terms.contains(&words[i + j]) {
-                        local_score += 1.0 / (j as f32);
-                        found_terms += 1;
+                    // A bare `NOT x` (no positive siblings) has nothing to
+                    // intersect against, so it starts from the full corpus.
+                    let mut result = match result {
+                        Some(result) => result,
+                        None => self.all_nodes_capped(limit.max(100) * 4).await?,
+                    };
+
+                    for negative in negatives {
+                        let excluded = self.evaluate_operation(negative, limit).await?;
+                        result.retain(|uuid, _| !excluded.contains_key(uuid));
                     }
-                    if i >= j && terms.contains(&words[i - j]) {
-                        local_score += 1.0 / (j as f32);
-                        found_terms += 1;
+
+                    Ok(result)
+                }
+                Operation::Or(children) => {
+                    let mut combined: HashMap<uuid::Uuid, (KGNode, f32)> = HashMap::new();
+                    for child in children {
+                        let evaluated = self.evaluate_operation(child, limit).await?;
+                        for (uuid, (node, score)) in evaluated {
+                            let entry = combined.entry(uuid).or_insert((node, 0.0));
+                            entry.1 += score;
+                        }
                     }
+                    Ok(combined)
+                }
+                Operation::Not(inner) => {
+                    let excluded = self.evaluate_operation(inner, limit).await?;
+                    let mut universe = self.all_nodes_capped(limit.max(100) * 4).await?;
+                    universe.retain(|uuid, _| !excluded.contains_key(uuid));
+                    Ok(universe)
                 }
-
-                let term_coverage = found_terms as f32 / terms.len() as f32;
-                max_score = max_score.max(local_score * term_coverage);
             }
-        }
-
-        max_score / terms.len() as f32
+        })
     }
 
-    async fn calculate_fuzzy_score(&self, node: &KGNode, query: &str, max_distance: u32) -> Result<f32> {
-        let combined_text = format!("{} {} {}", node.name, node.node_type, node.summary);
-        let words: Vec<&str> = combined_text.split_whitespace().collect();
-        let mut max_score: f32 = 0.0;
-
-        for word in words {
-            let distance = self.levenshtein_distance(query, word);
-            if distance <= max_distance {
-                let similarity = 1.0 - (distance as f32 / query.len().max(word.len()) as f32);
-                max_score = max_score.max(similarity);
-            }
+    /// Evaluates a single `Query(term)` leaf: an FTS5 prefix search scored
+    /// by the same name/type/summary boost-weighted match score
+    /// `calculate_node_relevance_score` uses for plain queries.
+    async fn evaluate_term(&self, term: &str, limit: usize) -> Result<HashMap<uuid::Uuid, (KGNode, f32)>> {
+        let fts_term = format!("{}*", term);
+        let nodes = self.storage.search_nodes_by_text(&fts_term, None, limit * 2)?;
+
+        let mut result = HashMap::new();
+        let query_terms = [term];
+        for node in nodes {
+            let score = self.calculate_text_match_score(&node.name, &query_terms) * self.boost_factors.name_boost
+                + self.calculate_text_match_score(&node.node_type, &query_terms) * self.boost_factors.type_boost
+                + self.calculate_text_match_score(&node.summary, &query_terms) * self.boost_factors.summary_boost;
+            result.insert(node.uuid, (node, score));
         }
+        Ok(result)
+    }
 
-        Ok(max_score)
+    /// Evaluates a single `Phrase(words)` leaf: an FTS5 exact-phrase search,
+    /// scored 1.0 for nodes whose combined text actually contains the
+    /// phrase (FTS5's phrase match can be looser than a literal substring
+    /// once stemming/tokenization is involved) and 0.5 otherwise.
+    async fn evaluate_phrase_leaf(&self, words: &[String], limit: usize) -> Result<HashMap<uuid::Uuid, (KGNode, f32)>> {
+        let phrase_query = format!("\"{}\"", words.join(" "));
+        let nodes = self.storage.search_nodes_by_text(&phrase_query, None, limit * 2)?;
+
+        let phrase = words.join(" ");
+        let mut result = HashMap::new();
+        for node in nodes {
+            let combined = format!("{} {} {}", node.name, node.node_type, node.summary).to_lowercase();
+            let score = if combined.contains(&phrase) { 1.0 } else { 0.5 };
+            result.insert(node.uuid, (node, score));
+        }
+        Ok(result)
     }
 
-    async fn calculate_boolean_score(&self, node: &KGNode, _query: &str) -> Result<f32> {
-        // Simplified boolean scoring - in practice, this would parse the boolean expression
-        // and calculate scores based on term presence/absence
-        Ok(0.8)
+    /// Pages through every node (capped at `cap`) for a bare `NOT x` with
+    /// no positive sibling to intersect against — see `evaluate_operation`.
+    async fn all_nodes_capped(&self, cap: usize) -> Result<HashMap<uuid::Uuid, (KGNode, f32)>> {
+        let mut result = HashMap::new();
+        let page_size = 500.min(cap.max(1));
+        let mut offset = 0;
+        loop {
+            if result.len() >= cap {
+                break;
+            }
+            let page = self.storage.get_nodes_page(offset, page_size)?;
+            if page.is_empty() {
+                break;
+            }
+            let page_len = page.len();
+            for node in page {
+                result.insert(node.uuid, (node, 0.0));
+            }
+            offset += page_len;
+        }
+        Ok(result)
     }
 
     fn get_field_boost_factor(&self, field: &str) -> f32 {
@@ -460,56 +706,17 @@ impl TextSearchEngine {
         }
     }
 
+    /// Tokenizes `text` (see `stemmer::tokenize`) and reduces each word to
+    /// its Snowball (Porter2) English stem, replacing the previous
+    /// substring-stripping approximation (`text.replace("ing", "")...`),
+    /// which mangled words like `"boss"` into `"bo"` and left irregular
+    /// forms untouched.
     fn apply_stemming(&self, text: &str) -> String {
-        // Simplified stemming - in practice, you'd use a proper stemming library
-        text.replace("ing", "").replace("ed", "").replace("s", "")
+        stemmer::tokenize(text)
+            .iter()
+            .map(|token| self.stemmer.stem(token).into_owned())
+            .collect::<Vec<String>>()
+            .join(" ")
     }
 
-    fn generate_fuzzy_patterns(&self, query: &str, _max_distance: u32) -> Vec<String> {
-        // Simplified fuzzy pattern generation
-        vec![
-            query.to_string(),
-            format!("{}*", query),
-            format!("*{}", query),
-            format!("*{}*", query),
-        ]
-    }
-
-    fn parse_boolean_query(&self, query: &str) -> Result<String> {
-        // Convert simple boolean query to FTS5 syntax
-        let mut fts_query = query.to_string();
-        
-        fts_query = fts_query.replace(" AND ", " ");
-        fts_query = fts_query.replace(" OR ", " OR ");
-        fts_query = fts_query.replace(" NOT ", " NOT ");
-        
-        Ok(fts_query)
-    }
-
-    fn levenshtein_distance(&self, s1: &str, s2: &str) -> u32 {
-        let len1 = s1.len();
-        let len2 = s2.len();
-        let mut matrix = vec![vec![0; len2 + 1]; len1 + 1];
-
-        for i in 0..=len1 {
-            matrix[i][0] = i;
-        }
-        for j in 0..=len2 {
-            matrix[0][j] = j;
-        }
-
-        let s1_chars: Vec<char> = s1.chars().collect();
-        let s2_chars: Vec<char> = s2.chars().collect();
-
-        for i in 1..=len1 {
-            for j in 1..=len2 {
-                let cost = if s1_chars[i - 1] == s2_chars[j - 1] { 0 } else { 1 };
-                matrix[i][j] = (matrix[i - 1][j] + 1)
-                    .min(matrix[i][j - 1] + 1)
-                    .min(matrix[i - 1][j - 1] + cost);
-            }
-        }
-
-        matrix[len1][len2] as u32
-    }
-} 
\ No newline at end of file
+}
\ No newline at end of file
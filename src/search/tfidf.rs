@@ -0,0 +1,108 @@
+//! Plain TF-IDF ranked retrieval over the indexed episode corpus, backing
+//! the `index_codebase` tool's `"search"` operation. Complementary to
+//! `TextSearchEngine`'s FTS5/boost-factor/BM25 node search: this builds a
+//! term -> postings inverted index straight off `storage`'s already-
+//! persisted `Episode`s (the code-chunk text `CodebaseIndexer` extracts at
+//! symbol granularity during indexing), so no separate index needs to be
+//! built or kept in sync — only rebuilt fresh per `search` call, the same
+//! read-storage-and-score approach `Bm25Stats::rebuild` already uses.
+//!
+//! Scoring follows the textbook formula rather than BM25's saturation/
+//! length-normalized one: `tf = 1 + ln(freq)`, `idf = ln(N / df)`, summed
+//! per matched query term.
+
+use std::collections::HashMap;
+
+use crate::graph::Episode;
+use crate::search::stemmer;
+
+/// One scored hit from `TfIdfIndex::search`.
+#[derive(Debug, Clone)]
+pub struct TfIdfHit {
+    pub doc_id: usize,
+    pub score: f32,
+    /// Per-matched-term contribution (already-stemmed term, `tf * idf`),
+    /// in descending-contribution order — the "matching term breakdown"
+    /// `Full` verbosity exposes per hit.
+    pub term_scores: Vec<(String, f32)>,
+}
+
+/// In-memory inverted index over a corpus of `Episode`s.
+pub struct TfIdfIndex<'a> {
+    episodes: &'a [Episode],
+    /// term -> postings list of (doc_id, term_frequency).
+    postings: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl<'a> TfIdfIndex<'a> {
+    /// Tokenizes `episode.name` + `episode.content` for every episode
+    /// (splitting identifier fragments on camelCase/snake_case boundaries
+    /// via `stemmer::tokenize_code`, then reducing each to its Snowball
+    /// stem) and builds the term -> postings map `search` scores against.
+    pub fn build(episodes: &'a [Episode]) -> Self {
+        let stemmer = rust_stemmers::Stemmer::create(rust_stemmers::Algorithm::English);
+        let mut postings: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+
+        for (doc_id, episode) in episodes.iter().enumerate() {
+            let combined = format!("{} {}", episode.name, episode.content);
+            let mut term_freq: HashMap<String, usize> = HashMap::new();
+            for raw in stemmer::tokenize_code(&combined) {
+                let stemmed = stemmer.stem(&raw).into_owned();
+                *term_freq.entry(stemmed).or_insert(0) += 1;
+            }
+            for (term, freq) in term_freq {
+                postings.entry(term).or_default().push((doc_id, freq));
+            }
+        }
+
+        Self { episodes, postings }
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.episodes.len()
+    }
+
+    /// Scores every document sharing at least one term with `query` as the
+    /// sum over matched terms of `tf * idf`, and returns the top `limit`
+    /// hits highest-score first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<TfIdfHit> {
+        let stemmer = rust_stemmers::Stemmer::create(rust_stemmers::Algorithm::English);
+        let query_terms: Vec<String> = stemmer::tokenize_code(query)
+            .into_iter()
+            .map(|t| stemmer.stem(&t).into_owned())
+            .collect();
+
+        let n = self.document_count() as f32;
+        let mut scores: HashMap<usize, Vec<(String, f32)>> = HashMap::new();
+
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else { continue };
+            if postings.is_empty() {
+                continue;
+            }
+            let df = postings.len() as f32;
+            let idf = (n / df).ln();
+            for &(doc_id, freq) in postings {
+                let tf = 1.0 + (freq as f32).ln();
+                scores.entry(doc_id).or_default().push((term.clone(), tf * idf));
+            }
+        }
+
+        let mut hits: Vec<TfIdfHit> = scores
+            .into_iter()
+            .map(|(doc_id, mut term_scores)| {
+                let score = term_scores.iter().map(|(_, s)| s).sum();
+                term_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+                TfIdfHit { doc_id, score, term_scores }
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(limit);
+        hits
+    }
+
+    pub fn episode(&self, doc_id: usize) -> &Episode {
+        &self.episodes[doc_id]
+    }
+}
@@ -1,13 +1,41 @@
 use anyhow::Result;
-use std::collections::BinaryHeap;
+use chrono::{DateTime, Utc};
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
 use crate::graph::{KGNode, KGEdge, Episode, SearchResult};
-use crate::embeddings::{LocalEmbeddingEngine, cosine_similarity, euclidean_distance};
+use crate::embeddings::{EmbeddingProvider, cosine_similarity, euclidean_distance};
+use crate::search::vp_tree::VpTree;
+use crate::search::metric::{self, CosineDistance, DotProductDistance, EuclideanDistance, ManhattanDistance, Proximity};
+
+/// Embeds a single text through `engine`'s batch API — every `EmbeddingProvider`
+/// implementor only has to define `embed_batch`, so this is the one-off path
+/// the per-node/per-query call sites below share.
+async fn embed_text(engine: &dyn EmbeddingProvider, text: &str) -> Result<Vec<f32>> {
+    engine.embed_batch(&[text.to_string()]).await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Embedding provider returned no vectors for a single-text batch"))
+}
 
 pub struct VectorSearchEngine {
-    embedding_engine: Option<LocalEmbeddingEngine>,
+    embedding_engine: Option<Arc<dyn EmbeddingProvider>>,
     similarity_threshold: f32,
     distance_metric: DistanceMetric,
+    /// VP-tree index backing `approximate_knn_search`, built by
+    /// `build_index` and consulted only while it still matches the current
+    /// embedded-node count — see `VpIndex`.
+    index: RwLock<Option<VpIndex>>,
+}
+
+/// A built VP-tree plus the node count it was built over. `approximate_knn_search`
+/// treats the index as stale the moment that count no longer matches
+/// `get_all_nodes_with_embeddings`, rather than tracking per-node
+/// invalidation the storage layer has no hook for yet.
+struct VpIndex {
+    tree: VpTree<KGNode>,
+    built_for_len: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -18,29 +46,96 @@ pub enum DistanceMetric {
     Manhattan,
 }
 
-#[derive(Debug, Clone)]
-struct ScoredItem<T> {
-    item: T,
-    score: f32,
+/// Restricts a search scan to a subset of candidates before they're ever
+/// scored, rather than the all-or-nothing `similarity_threshold` applied
+/// after scoring every embedding. An empty/`None` field imposes no
+/// restriction on that dimension. Build with `SearchFilter::default()` and
+/// the `with_*` methods, matching `VectorSearchEngine`'s own builder style.
+#[derive(Clone, Debug, Default)]
+pub struct SearchFilter {
+    allowed_node_types: Option<HashSet<String>>,
+    allow_ids: Option<HashSet<Uuid>>,
+    deny_ids: Option<HashSet<Uuid>>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
 }
 
-impl<T> PartialEq for ScoredItem<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.score == other.score
+impl SearchFilter {
+    pub fn with_node_types(mut self, node_types: impl IntoIterator<Item = String>) -> Self {
+        self.allowed_node_types = Some(node_types.into_iter().collect());
+        self
+    }
+
+    pub fn with_allowed_ids(mut self, ids: impl IntoIterator<Item = Uuid>) -> Self {
+        self.allow_ids = Some(ids.into_iter().collect());
+        self
+    }
+
+    pub fn with_denied_ids(mut self, ids: impl IntoIterator<Item = Uuid>) -> Self {
+        self.deny_ids = Some(ids.into_iter().collect());
+        self
     }
-}
 
-impl<T> Eq for ScoredItem<T> {}
+    pub fn with_created_after(mut self, after: DateTime<Utc>) -> Self {
+        self.created_after = Some(after);
+        self
+    }
 
-impl<T> PartialOrd for ScoredItem<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.score.partial_cmp(&self.score) // Reverse for max-heap
+    pub fn with_created_before(mut self, before: DateTime<Utc>) -> Self {
+        self.created_before = Some(before);
+        self
+    }
+
+    fn matches_id_and_time(&self, id: Uuid, created_at: DateTime<Utc>) -> bool {
+        if let Some(allow) = &self.allow_ids {
+            if !allow.contains(&id) {
+                return false;
+            }
+        }
+        if let Some(deny) = &self.deny_ids {
+            if deny.contains(&id) {
+                return false;
+            }
+        }
+        if let Some(after) = self.created_after {
+            if created_at < after {
+                return false;
+            }
+        }
+        if let Some(before) = self.created_before {
+            if created_at > before {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_node(&self, node: &KGNode) -> bool {
+        if let Some(allowed) = &self.allowed_node_types {
+            if !allowed.contains(&node.node_type) {
+                return false;
+            }
+        }
+        self.matches_id_and_time(node.uuid, node.created_at)
+    }
+
+    fn matches_episode(&self, episode: &Episode) -> bool {
+        // Episodes have no `node_type`, so `allowed_node_types` simply
+        // doesn't constrain them.
+        self.matches_id_and_time(episode.uuid, episode.created_at)
     }
 }
 
-impl<T> Ord for ScoredItem<T> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+/// Lets `VectorSearchEngine` itself stand in as the `Proximity` impl
+/// `metric::k_nearest` expects, dispatching to whichever metric it's
+/// configured with via `proximity_distance` — so the brute-force k-NN
+/// fallback shares that one generic nearest-neighbor routine instead of
+/// its own copy of the sort-and-truncate loop.
+impl Proximity<[f32]> for VectorSearchEngine {
+    type Distance = f32;
+
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.proximity_distance(a, b)
     }
 }
 
@@ -50,14 +145,35 @@ impl VectorSearchEngine {
             embedding_engine: None,
             similarity_threshold: 0.7,
             distance_metric: DistanceMetric::Cosine,
+            index: RwLock::new(None),
         }
     }
 
-    pub fn with_embedding_engine(mut self, engine: LocalEmbeddingEngine) -> Self {
+    pub fn with_embedding_engine(mut self, engine: Arc<dyn EmbeddingProvider>) -> Self {
         self.embedding_engine = Some(engine);
         self
     }
 
+    /// Fails loudly if `query` wasn't produced by the configured provider -
+    /// e.g. a caller passing through a precomputed vector from a different
+    /// model, or a stale embedding left over from before a provider swap -
+    /// rather than letting it silently compare against stored vectors of a
+    /// different dimensionality (or coincidentally matching length but
+    /// meaningless geometry). A no-op when no provider is configured, since
+    /// there's nothing to validate `query` against.
+    fn validate_query_dimensions(&self, query: &[f32]) -> Result<()> {
+        if let Some(engine) = &self.embedding_engine {
+            let expected = engine.dimensions();
+            if query.len() != expected {
+                return Err(anyhow::anyhow!(
+                    "Query embedding has {} dimensions, but the configured provider '{}' produces {}-dimension vectors",
+                    query.len(), engine.model_id(), expected
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn with_similarity_threshold(mut self, threshold: f32) -> Self {
         self.similarity_threshold = threshold.clamp(0.0, 1.0);
         self
@@ -70,31 +186,40 @@ impl VectorSearchEngine {
 
     /// Search for similar nodes using embedding vectors
     pub async fn search_nodes(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<KGNode>> {
+        let scored = self.search_nodes_with_scores(query_embedding, limit).await?;
+        Ok(scored.into_iter().map(|(node, _)| node).collect())
+    }
+
+    /// Search for similar nodes using embedding vectors, keeping each node's
+    /// real cosine-similarity score alongside it instead of discarding it —
+    /// callers that need to filter or fuse on the actual relevance (rather
+    /// than just the ranked order `search_nodes` returns) should use this.
+    pub async fn search_nodes_with_scores(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<(KGNode, f32)>> {
+        self.search_nodes_with_filter(query_embedding, limit, &SearchFilter::default()).await
+    }
+
+    /// `search_nodes_with_scores`, additionally skipping every node
+    /// `filter` rejects before it's ever scored, instead of scoring every
+    /// embedded node and filtering the result afterward.
+    pub async fn search_nodes_with_filter(&self, query_embedding: &[f32], limit: usize, filter: &SearchFilter) -> Result<Vec<(KGNode, f32)>> {
+        self.validate_query_dimensions(query_embedding)?;
         println!("🔍 Vector search for {} similar nodes", limit);
-        
+
         // Get all nodes with embeddings (in practice, this would be optimized with vector indexing)
         let all_nodes = self.get_all_nodes_with_embeddings().await?;
-        
-        let mut scored_nodes = BinaryHeap::new();
-        
+
+        let mut scored_nodes = Vec::new();
         for (node, embedding) in all_nodes {
+            if !filter.matches_node(&node) {
+                continue;
+            }
             let similarity = self.calculate_similarity(query_embedding, &embedding)?;
-            
             if similarity >= self.similarity_threshold {
-                scored_nodes.push(ScoredItem {
-                    item: node,
-                    score: similarity,
-                });
+                scored_nodes.push((node, similarity));
             }
         }
 
-        // Extract top results
-        let mut results = Vec::new();
-        for _ in 0..limit.min(scored_nodes.len()) {
-            if let Some(scored_item) = scored_nodes.pop() {
-                results.push(scored_item.item);
-            }
-        }
+        let results = metric::top_k_by_score(scored_nodes, limit);
 
         println!("✅ Found {} similar nodes", results.len());
         Ok(results)
@@ -102,99 +227,201 @@ impl VectorSearchEngine {
 
     /// Search for similar episodes using embedding vectors
     pub async fn search_episodes(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<Episode>> {
+        self.search_episodes_with_filter(query_embedding, limit, &SearchFilter::default()).await
+    }
+
+    /// `search_episodes`, additionally skipping every episode `filter`
+    /// rejects before it's ever scored.
+    pub async fn search_episodes_with_filter(&self, query_embedding: &[f32], limit: usize, filter: &SearchFilter) -> Result<Vec<Episode>> {
+        self.validate_query_dimensions(query_embedding)?;
         println!("🔍 Vector search for {} similar episodes", limit);
-        
+
         // Get all episodes with embeddings
         let all_episodes = self.get_all_episodes_with_embeddings().await?;
-        
-        let mut scored_episodes = BinaryHeap::new();
-        
+
+        let mut scored_episodes = Vec::new();
         for (episode, embedding) in all_episodes {
+            if !filter.matches_episode(&episode) {
+                continue;
+            }
             let similarity = self.calculate_similarity(query_embedding, &embedding)?;
-            
             if similarity >= self.similarity_threshold {
-                scored_episodes.push(ScoredItem {
-                    item: episode,
-                    score: similarity,
-                });
+                scored_episodes.push((episode, similarity));
             }
         }
 
-        // Extract top results
-        let mut results = Vec::new();
-        for _ in 0..limit.min(scored_episodes.len()) {
-            if let Some(scored_item) = scored_episodes.pop() {
-                results.push(scored_item.item);
-            }
-        }
+        let results: Vec<Episode> = metric::top_k_by_score(scored_episodes, limit)
+            .into_iter()
+            .map(|(episode, _)| episode)
+            .collect();
 
         println!("✅ Found {} similar episodes", results.len());
         Ok(results)
     }
 
+    /// All nodes within an absolute `radius` of `query` (under this
+    /// engine's configured `distance_metric`), sorted by ascending
+    /// distance — a true radius query, rather than `search_nodes`'s fixed
+    /// `similarity_threshold` which doesn't correspond to any particular
+    /// distance bound once converted from a similarity.
+    pub async fn nearest_within(&self, query: &[f32], radius: f32) -> Result<Vec<(KGNode, f32)>> {
+        self.validate_query_dimensions(query)?;
+        let all_nodes = self.get_all_nodes_with_embeddings().await?;
+
+        let mut results: Vec<(KGNode, f32)> = all_nodes.into_iter()
+            .map(|(node, embedding)| (node, self.proximity_distance(query, &embedding)))
+            .filter(|(_, distance)| *distance <= radius)
+            .collect();
+        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+        println!("🎯 Found {} nodes within radius {}", results.len(), radius);
+        Ok(results)
+    }
+
+    /// `nearest_within`, truncated to the closest `k`.
+    pub async fn k_nearest_within(&self, query: &[f32], k: usize, radius: f32) -> Result<Vec<(KGNode, f32)>> {
+        let mut results = self.nearest_within(query, radius).await?;
+        results.truncate(k);
+        Ok(results)
+    }
+
     /// Advanced semantic search with query expansion
     pub async fn semantic_search(&self, query: &str, candidates: &[KGNode], top_k: usize) -> Result<Vec<(KGNode, f32)>> {
         let engine = self.embedding_engine.as_ref()
             .ok_or_else(|| anyhow::anyhow!("Embedding engine not initialized"))?;
 
         // Generate query embedding
-        let query_embedding = engine.encode_text(query).await?;
-        
+        let query_embedding = embed_text(engine.as_ref(), query).await?;
+
         // Calculate similarities for all candidates
-        let mut scored_candidates = BinaryHeap::new();
-        
+        let mut scored_candidates = Vec::new();
         for node in candidates {
             // Generate embedding for the node (combining name, summary, etc.)
             let node_text = format!("{} {} {}", node.name, node.node_type, node.summary);
-            let node_embedding = engine.encode_text(&node_text).await?;
-            
+            let node_embedding = embed_text(engine.as_ref(), &node_text).await?;
+
             let similarity = self.calculate_similarity(&query_embedding, &node_embedding)?;
-            
             if similarity >= self.similarity_threshold {
-                scored_candidates.push(ScoredItem {
-                    item: node.clone(),
-                    score: similarity,
-                });
+                scored_candidates.push((node.clone(), similarity));
             }
         }
 
-        // Extract top-k results
-        let mut results = Vec::new();
-        for _ in 0..top_k.min(scored_candidates.len()) {
-            if let Some(scored_item) = scored_candidates.pop() {
-                results.push((scored_item.item, scored_item.score));
-            }
-        }
+        let results = metric::top_k_by_score(scored_candidates, top_k);
 
         println!("🎯 Semantic search found {} relevant nodes", results.len());
         Ok(results)
     }
 
-    /// Approximate nearest neighbor search using HNSW-inspired approach
+    /// Builds (or rebuilds) the VP-tree index `approximate_knn_search` uses,
+    /// over every currently-embedded node. Callers re-run this after
+    /// embeddings change enough to matter; `approximate_knn_search` itself
+    /// only ever reads the cached index and falls back to brute force when
+    /// it detects the index no longer matches the current node count.
+    pub async fn build_index(&self) -> Result<()> {
+        let all_nodes = self.get_all_nodes_with_embeddings().await?;
+        let built_for_len = all_nodes.len();
+        let distance = self.distance_fn();
+        let tree = VpTree::build(all_nodes, &distance);
+
+        let mut guard = self.index.write().unwrap();
+        *guard = Some(VpIndex { tree, built_for_len });
+        println!("🧭 Built VP-tree index over {} embedded nodes", built_for_len);
+        Ok(())
+    }
+
+    /// Exact k-NN search via the VP-tree built by `build_index`, replacing
+    /// the previous "sample every Nth node, sort" approximation that
+    /// silently lost recall. Falls back to a genuine brute-force scan
+    /// (still exact, just O(n) instead of roughly O(log n) per query) when
+    /// the index hasn't been built yet or the embedded node count has
+    /// since changed out from under it.
     pub async fn approximate_knn_search(&self, query_embedding: &[f32], k: usize) -> Result<Vec<(KGNode, f32)>> {
-        // This is a simplified version of approximate nearest neighbor search
-        // In practice, you'd use a proper HNSW or LSH implementation
-        
+        self.validate_query_dimensions(query_embedding)?;
         let all_nodes = self.get_all_nodes_with_embeddings().await?;
-        let mut candidates = Vec::new();
-        
-        // Sample a subset for approximation (in practice, this would be more sophisticated)
-        let sample_size = (all_nodes.len() / 4).max(k * 10).min(all_nodes.len());
-        let step = all_nodes.len() / sample_size.max(1);
-        
-        for (i, (node, embedding)) in all_nodes.iter().enumerate() {
-            if i % step == 0 {
-                let similarity = self.calculate_similarity(query_embedding, embedding)?;
-                candidates.push((node.clone(), similarity));
+        let distance = self.distance_fn();
+
+        let index_is_current = {
+            let guard = self.index.read().unwrap();
+            guard.as_ref().map(|index| index.built_for_len == all_nodes.len()).unwrap_or(false)
+        };
+
+        let (distances, used_index): (Vec<(KGNode, f32)>, bool) = if index_is_current {
+            let guard = self.index.read().unwrap();
+            let index = guard.as_ref().expect("checked index_is_current above");
+            (index.tree.k_nearest(query_embedding, k, &distance), true)
+        } else {
+            let neighbors = metric::k_nearest(self, query_embedding, &all_nodes, k);
+            (neighbors.into_iter().map(|n| (n.item, n.distance)).collect(), false)
+        };
+
+        // The tree and brute-force fallback both return raw metric
+        // distances; convert back to the 0..1 "higher is better" score the
+        // rest of this engine's search methods use.
+        let results: Vec<(KGNode, f32)> = distances.into_iter()
+            .map(|(node, dist)| (node, 1.0 / (1.0 + dist)))
+            .collect();
+
+        println!(
+            "🚀 KNN search found {} candidates ({})",
+            results.len(),
+            if used_index { "VP-tree" } else { "brute force, stale or missing index" }
+        );
+        Ok(results)
+    }
+
+    /// Merges this engine's candidates for `query` into `neighbors` in
+    /// place, rather than allocating a fresh heap and `Vec` the way
+    /// `search_nodes_with_scores` does. `neighbors` is treated as a bounded
+    /// accumulator already sorted by descending score: callers reuse the
+    /// same `Vec` (ideally started with `Vec::with_capacity(k)`) across
+    /// many queries, or merge partial results from multiple shards, without
+    /// re-sorting from scratch each time.
+    pub async fn merge_k_nearest(&self, query: &[f32], k: usize, neighbors: &mut Vec<(KGNode, f32)>) -> Result<()> {
+        self.validate_query_dimensions(query)?;
+        let all_nodes = self.get_all_nodes_with_embeddings().await?;
+        for (node, embedding) in all_nodes {
+            let score = self.calculate_similarity(query, &embedding)?;
+            Self::merge_candidate(neighbors, k, node, score);
+        }
+        Ok(())
+    }
+
+    /// `merge_k_nearest`, additionally discarding any candidate farther
+    /// than `radius` (under this engine's configured `distance_metric`)
+    /// from `query`.
+    pub async fn merge_k_nearest_within(&self, query: &[f32], k: usize, radius: f32, neighbors: &mut Vec<(KGNode, f32)>) -> Result<()> {
+        self.validate_query_dimensions(query)?;
+        let all_nodes = self.get_all_nodes_with_embeddings().await?;
+        for (node, embedding) in all_nodes {
+            if self.proximity_distance(query, &embedding) > radius {
+                continue;
             }
+            let score = self.calculate_similarity(query, &embedding)?;
+            Self::merge_candidate(neighbors, k, node, score);
         }
+        Ok(())
+    }
 
-        // Sort by similarity and return top-k
-        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
-        candidates.truncate(k);
+    /// Inserts `(node, score)` into `neighbors` — sorted by descending
+    /// score — keeping at most the top `k`. Skips the binary search
+    /// entirely once `neighbors` is full and `score` doesn't beat the
+    /// current worst (last) entry, which is the common case once an
+    /// accumulator has seen enough candidates to fill up.
+    fn merge_candidate(neighbors: &mut Vec<(KGNode, f32)>, k: usize, node: KGNode, score: f32) {
+        if k == 0 {
+            return;
+        }
+        if neighbors.len() >= k {
+            if let Some(&(_, worst)) = neighbors.last() {
+                if score <= worst {
+                    return;
+                }
+            }
+        }
 
-        println!("🚀 Approximate KNN search found {} candidates", candidates.len());
-        Ok(candidates)
+        let pos = neighbors.partition_point(|(_, existing_score)| *existing_score > score);
+        neighbors.insert(pos, (node, score));
+        neighbors.truncate(k);
     }
 
     /// Multi-vector search combining different aspects
@@ -202,9 +429,12 @@ impl VectorSearchEngine {
         if query_vectors.len() != weights.len() {
             return Err(anyhow::anyhow!("Query vectors and weights must have same length"));
         }
+        for query_vector in query_vectors {
+            self.validate_query_dimensions(query_vector)?;
+        }
 
         let all_nodes = self.get_all_nodes_with_embeddings().await?;
-        let mut scored_nodes = BinaryHeap::new();
+        let mut scored_nodes = Vec::new();
 
         for (node, node_embedding) in all_nodes {
             let mut combined_score = 0.0;
@@ -221,28 +451,25 @@ impl VectorSearchEngine {
             }
 
             if combined_score >= self.similarity_threshold {
-                scored_nodes.push(ScoredItem {
-                    item: node,
-                    score: combined_score,
-                });
+                scored_nodes.push((node, combined_score));
             }
         }
 
-        let mut results = Vec::new();
-        for _ in 0..limit.min(scored_nodes.len()) {
-            if let Some(scored_item) = scored_nodes.pop() {
-                results.push(scored_item.item);
-            }
-        }
+        let results: Vec<KGNode> = metric::top_k_by_score(scored_nodes, limit)
+            .into_iter()
+            .map(|(node, _)| node)
+            .collect();
 
         println!("🔀 Multi-vector search found {} nodes", results.len());
         Ok(results)
     }
 
-    /// Embedding-based clustering
-    pub async fn cluster_nodes(&self, nodes: &[KGNode], num_clusters: usize, max_iterations: usize) -> Result<Vec<Vec<KGNode>>> {
+    /// Embedding-based clustering. Returns each cluster's nodes alongside
+    /// the k-means inertia (within-cluster sum of squared distances) so
+    /// callers can compare runs or sweep `num_clusters` for an elbow point.
+    pub async fn cluster_nodes(&self, nodes: &[KGNode], num_clusters: usize, max_iterations: usize) -> Result<(Vec<Vec<KGNode>>, f32)> {
         if nodes.is_empty() || num_clusters == 0 {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), 0.0));
         }
 
         let engine = self.embedding_engine.as_ref()
@@ -252,15 +479,15 @@ impl VectorSearchEngine {
         let mut node_embeddings = Vec::new();
         for node in nodes {
             let node_text = format!("{} {} {}", node.name, node.node_type, node.summary);
-            let embedding = engine.encode_text(&node_text).await?;
+            let embedding = embed_text(engine.as_ref(), &node_text).await?;
             node_embeddings.push((node.clone(), embedding));
         }
 
-        // Simple k-means clustering
-        let clusters = self.k_means_clustering(&node_embeddings, num_clusters, max_iterations)?;
-        
-        println!("🔍 Clustered {} nodes into {} clusters", nodes.len(), clusters.len());
-        Ok(clusters)
+        // k-means clustering with k-means++ seeding
+        let (clusters, inertia) = self.k_means_clustering(&node_embeddings, num_clusters, max_iterations)?;
+
+        println!("🔍 Clustered {} nodes into {} clusters (inertia {:.4})", nodes.len(), clusters.len(), inertia);
+        Ok((clusters, inertia))
     }
 
     /// Find outliers based on embedding distances
@@ -275,7 +502,7 @@ impl VectorSearchEngine {
         let mut node_embeddings = Vec::new();
         for node in nodes {
             let node_text = format!("{} {} {}", node.name, node.node_type, node.summary);
-            let embedding = engine.encode_text(&node_text).await?;
+            let embedding = embed_text(engine.as_ref(), &node_text).await?;
             node_embeddings.push((node.clone(), embedding));
         }
 
@@ -287,12 +514,7 @@ impl VectorSearchEngine {
             // Calculate distances to all other nodes
             for (j, (_, other_embedding)) in node_embeddings.iter().enumerate() {
                 if i != j {
-                    let distance = match self.distance_metric {
-                        DistanceMetric::Cosine => 1.0 - cosine_similarity(embedding, other_embedding),
-                        DistanceMetric::Euclidean => euclidean_distance(embedding, other_embedding),
-                        _ => 1.0 - cosine_similarity(embedding, other_embedding),
-                    };
-                    distances.push(distance);
+                    distances.push(self.proximity_distance(embedding, other_embedding));
                 }
             }
 
@@ -308,6 +530,84 @@ impl VectorSearchEngine {
         Ok(outliers)
     }
 
+    /// Local Outlier Factor scores over `nodes`: for each node, its k
+    /// nearest neighbors (found via a VP-tree built over just this
+    /// candidate set, reusing the same index from `build_index`/
+    /// `approximate_knn_search` rather than a quadratic scan) define a
+    /// local reachability density, and a node's LOF is the mean ratio of
+    /// its neighbors' density to its own. Unlike `find_outliers`'s global
+    /// mean-distance threshold, this reflects *local* density — a node in
+    /// a sparse-but-uniform region scores near 1.0 (inlier), while one
+    /// that's locally sparser than its own neighbors scores well above
+    /// 1.0, without needing a hand-tuned absolute distance cutoff.
+    pub async fn local_outlier_factors(&self, nodes: &[KGNode], k: usize) -> Result<Vec<(KGNode, f32)>> {
+        if k == 0 || nodes.len() <= k {
+            return Ok(Vec::new());
+        }
+
+        let engine = self.embedding_engine.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Embedding engine not initialized"))?;
+
+        let mut node_embeddings = Vec::new();
+        for node in nodes {
+            let node_text = format!("{} {} {}", node.name, node.node_type, node.summary);
+            let embedding = embed_text(engine.as_ref(), &node_text).await?;
+            node_embeddings.push((node.clone(), embedding));
+        }
+
+        let distance = self.distance_fn();
+        let tree = VpTree::build(node_embeddings.clone(), &distance);
+
+        // Each node's k nearest neighbors (excluding itself) and its
+        // k-distance — the distance to the k-th nearest neighbor, used as
+        // the reachability floor below.
+        let mut neighbor_sets: Vec<Vec<(KGNode, f32)>> = Vec::with_capacity(node_embeddings.len());
+        let mut k_distances: std::collections::HashMap<Uuid, f32> = std::collections::HashMap::new();
+        for (node, embedding) in &node_embeddings {
+            let mut neighbors = tree.k_nearest(embedding, k + 1, &distance);
+            neighbors.retain(|(other, _)| other.uuid != node.uuid);
+            neighbors.truncate(k);
+            if let Some((_, k_distance)) = neighbors.last() {
+                k_distances.insert(node.uuid, *k_distance);
+            }
+            neighbor_sets.push(neighbors);
+        }
+
+        // lrd(a) = 1 / (mean over neighbors b of reach-dist_k(a,b)), where
+        // reach-dist_k(a,b) = max(k-distance(b), distance(a,b)).
+        let mut lrd: std::collections::HashMap<Uuid, f32> = std::collections::HashMap::new();
+        for ((node, _), neighbors) in node_embeddings.iter().zip(&neighbor_sets) {
+            if neighbors.is_empty() {
+                lrd.insert(node.uuid, 0.0);
+                continue;
+            }
+            let mean_reach_distance: f32 = neighbors.iter()
+                .map(|(other, distance_to_other)| {
+                    let other_k_distance = k_distances.get(&other.uuid).copied().unwrap_or(*distance_to_other);
+                    distance_to_other.max(other_k_distance)
+                })
+                .sum::<f32>() / neighbors.len() as f32;
+            lrd.insert(node.uuid, if mean_reach_distance > f32::EPSILON { 1.0 / mean_reach_distance } else { f32::INFINITY });
+        }
+
+        // LOF(a) = mean over neighbors b of lrd(b) / lrd(a)
+        let mut scores = Vec::with_capacity(node_embeddings.len());
+        for ((node, _), neighbors) in node_embeddings.iter().zip(&neighbor_sets) {
+            let own_lrd = lrd.get(&node.uuid).copied().unwrap_or(0.0);
+            let lof = if neighbors.is_empty() || own_lrd <= f32::EPSILON {
+                1.0
+            } else {
+                neighbors.iter()
+                    .map(|(other, _)| lrd.get(&other.uuid).copied().unwrap_or(0.0) / own_lrd)
+                    .sum::<f32>() / neighbors.len() as f32
+            };
+            scores.push((node.clone(), lof));
+        }
+
+        println!("🎯 Computed LOF scores for {} nodes (k={})", scores.len(), k);
+        Ok(scores)
+    }
+
     // Private helper methods
 
     async fn get_all_nodes_with_embeddings(&self) -> Result<Vec<(KGNode, Vec<f32>)>> {
@@ -322,6 +622,36 @@ impl VectorSearchEngine {
         Ok(Vec::new())
     }
 
+    /// A plain distance (not similarity) matching this engine's configured
+    /// `distance_metric`, delegating to the corresponding `Proximity` impl
+    /// in [`metric`] so this and `find_outliers` share one definition of
+    /// each metric instead of maintaining separate matches that can (and
+    /// did — `find_outliers` previously treated `Manhattan` as `Cosine`)
+    /// silently drift apart.
+    fn proximity_distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self.distance_metric {
+            DistanceMetric::Cosine => CosineDistance.distance(a, b),
+            DistanceMetric::DotProduct => DotProductDistance.distance(a, b),
+            DistanceMetric::Euclidean => EuclideanDistance.distance(a, b),
+            DistanceMetric::Manhattan => ManhattanDistance.distance(a, b),
+        }
+    }
+
+    /// `proximity_distance` bound to `self`'s current metric as a
+    /// standalone closure, for the VP-tree, which needs a metric obeying
+    /// the triangle inequality rather than a bounded similarity score
+    /// (dot product doesn't, hence `DotProductDistance`'s cosine-distance
+    /// approximation — see its doc comment).
+    fn distance_fn(&self) -> impl Fn(&[f32], &[f32]) -> f32 {
+        let metric = self.distance_metric.clone();
+        move |a: &[f32], b: &[f32]| match metric {
+            DistanceMetric::Cosine => CosineDistance.distance(a, b),
+            DistanceMetric::DotProduct => DotProductDistance.distance(a, b),
+            DistanceMetric::Euclidean => EuclideanDistance.distance(a, b),
+            DistanceMetric::Manhattan => ManhattanDistance.distance(a, b),
+        }
+    }
+
     fn calculate_similarity(&self, vec1: &[f32], vec2: &[f32]) -> Result<f32> {
         if vec1.len() != vec2.len() {
             return Err(anyhow::anyhow!("Vector dimensions must match"));
@@ -345,20 +675,24 @@ impl VectorSearchEngine {
         Ok(similarity.clamp(0.0, 1.0))
     }
 
-    fn k_means_clustering(&self, node_embeddings: &[(KGNode, Vec<f32>)], k: usize, max_iterations: usize) -> Result<Vec<Vec<KGNode>>> {
+    /// k-means clustering with k-means++ seeding (spreads initial centroids
+    /// apart by sampling each one with probability proportional to its
+    /// squared distance from the nearest already-chosen centroid, instead
+    /// of the previous `node_embeddings[i % len]` — the first k nodes,
+    /// which clustered badly on ordered input and varied only with input
+    /// order rather than genuine randomness). Returns each cluster's nodes
+    /// alongside the final inertia (within-cluster sum of squared
+    /// distances) so callers can pick `k` via the elbow method.
+    fn k_means_clustering(&self, node_embeddings: &[(KGNode, Vec<f32>)], k: usize, max_iterations: usize) -> Result<(Vec<Vec<KGNode>>, f32)> {
         if node_embeddings.is_empty() || k == 0 {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), 0.0));
         }
 
         let embedding_dim = node_embeddings[0].1.len();
-        let mut centroids = Vec::new();
-        let mut assignments = vec![0; node_embeddings.len()];
-
-        // Initialize centroids randomly
-        for i in 0..k {
-            let idx = i % node_embeddings.len();
-            centroids.push(node_embeddings[idx].1.clone());
-        }
+        let mut rng_state = Self::seed_from_time();
+        let mut centroids = Self::k_means_plus_plus_seed(node_embeddings, k.min(node_embeddings.len()), &mut rng_state);
+        let k = centroids.len();
+        let mut assignments = vec![0usize; node_embeddings.len()];
 
         for _iteration in 0..max_iterations {
             let mut changed = false;
@@ -382,40 +716,134 @@ impl VectorSearchEngine {
                 }
             }
 
-            if !changed {
-                break;
-            }
-
-            // Update centroids
+            // Update centroids, re-seeding any that ended up with no
+            // assigned points to the globally worst-served point (farthest
+            // from its own assigned centroid) instead of leaving it to be
+            // silently dropped at the end.
             for cluster_id in 0..k {
-                let cluster_points: Vec<_> = node_embeddings.iter()
-                    .enumerate()
-                    .filter(|(i, _)| assignments[*i] == cluster_id)
-                    .map(|(_, (_, embedding))| embedding)
+                let cluster_indices: Vec<usize> = (0..node_embeddings.len())
+                    .filter(|&i| assignments[i] == cluster_id)
                     .collect();
 
-                if !cluster_points.is_empty() {
-                    let mut new_centroid = vec![0.0; embedding_dim];
-                    for point in &cluster_points {
-                        for (i, &value) in point.iter().enumerate() {
-                            new_centroid[i] += value;
-                        }
+                if cluster_indices.is_empty() {
+                    if let Some(farthest) = (0..node_embeddings.len()).max_by(|&a, &b| {
+                        let dist_a = euclidean_distance(&node_embeddings[a].1, &centroids[assignments[a]]);
+                        let dist_b = euclidean_distance(&node_embeddings[b].1, &centroids[assignments[b]]);
+                        dist_a.partial_cmp(&dist_b).unwrap_or(Ordering::Equal)
+                    }) {
+                        centroids[cluster_id] = node_embeddings[farthest].1.clone();
+                        changed = true;
                     }
-                    for value in &mut new_centroid {
-                        *value /= cluster_points.len() as f32;
+                    continue;
+                }
+
+                let mut new_centroid = vec![0.0; embedding_dim];
+                for &i in &cluster_indices {
+                    for (d, &value) in node_embeddings[i].1.iter().enumerate() {
+                        new_centroid[d] += value;
                     }
-                    centroids[cluster_id] = new_centroid;
+                }
+                for value in &mut new_centroid {
+                    *value /= cluster_indices.len() as f32;
+                }
+                centroids[cluster_id] = new_centroid;
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        // Final assignment pass so `clusters`/inertia reflect the centroids
+        // exactly as they ended up, including any last-iteration re-seeds.
+        for (i, (_, embedding)) in node_embeddings.iter().enumerate() {
+            let mut best_cluster = 0;
+            let mut best_distance = f32::INFINITY;
+            for (j, centroid) in centroids.iter().enumerate() {
+                let distance = euclidean_distance(embedding, centroid);
+                if distance < best_distance {
+                    best_distance = distance;
+                    best_cluster = j;
                 }
             }
+            assignments[i] = best_cluster;
         }
 
-        // Group nodes by cluster
         let mut clusters = vec![Vec::new(); k];
-        for (i, (node, _)) in node_embeddings.iter().enumerate() {
+        let mut inertia = 0.0f32;
+        for (i, (node, embedding)) in node_embeddings.iter().enumerate() {
+            let distance = euclidean_distance(embedding, &centroids[assignments[i]]);
+            inertia += distance * distance;
             clusters[assignments[i]].push(node.clone());
         }
 
-        // Filter out empty clusters
-        Ok(clusters.into_iter().filter(|cluster| !cluster.is_empty()).collect())
+        Ok((clusters.into_iter().filter(|cluster| !cluster.is_empty()).collect(), inertia))
+    }
+
+    /// Picks `k` initial centroids via k-means++: the first uniformly at
+    /// random, then each subsequent one sampled with probability
+    /// proportional to its squared distance from the nearest
+    /// already-chosen centroid — spreading centroids apart rather than
+    /// clustering them near each other the way a uniform-random pick
+    /// sometimes does.
+    fn k_means_plus_plus_seed(node_embeddings: &[(KGNode, Vec<f32>)], k: usize, rng_state: &mut u64) -> Vec<Vec<f32>> {
+        let mut centroids: Vec<Vec<f32>> = Vec::with_capacity(k);
+        let first = (Self::next_rand_u64(rng_state) as usize) % node_embeddings.len();
+        centroids.push(node_embeddings[first].1.clone());
+
+        while centroids.len() < k {
+            let sq_distances: Vec<f32> = node_embeddings.iter()
+                .map(|(_, embedding)| {
+                    centroids.iter()
+                        .map(|centroid| {
+                            let d = euclidean_distance(embedding, centroid);
+                            d * d
+                        })
+                        .fold(f32::INFINITY, f32::min)
+                })
+                .collect();
+
+            let total: f32 = sq_distances.iter().sum();
+            if total <= f32::EPSILON {
+                // Every remaining point already coincides with a chosen
+                // centroid; any point is as good as any other as the next seed.
+                let idx = (Self::next_rand_u64(rng_state) as usize) % node_embeddings.len();
+                centroids.push(node_embeddings[idx].1.clone());
+                continue;
+            }
+
+            let roll = (Self::next_rand_u64(rng_state) as f64 / u64::MAX as f64) as f32 * total;
+            let mut cumulative = 0.0;
+            let mut chosen = node_embeddings.len() - 1;
+            for (i, &sq_distance) in sq_distances.iter().enumerate() {
+                cumulative += sq_distance;
+                if cumulative >= roll {
+                    chosen = i;
+                    break;
+                }
+            }
+            centroids.push(node_embeddings[chosen].1.clone());
+        }
+
+        centroids
+    }
+
+    /// xorshift64 — good enough spread for k-means++ seed sampling, which
+    /// doesn't need cryptographic randomness, and avoids pulling in an RNG
+    /// crate for it.
+    fn next_rand_u64(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    fn seed_from_time() -> u64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        (nanos ^ 0x9E3779B97F4A7C15) | 1
     }
-} 
\ No newline at end of file
+}
\ No newline at end of file
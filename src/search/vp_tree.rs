@@ -0,0 +1,168 @@
+//! Vantage-point tree: an exact nearest-neighbor index over any metric
+//! space obeying the triangle inequality, backing
+//! `VectorSearchEngine::approximate_knn_search`. Unlike a kd-tree, it only
+//! needs a distance function (not coordinates), so it works with
+//! cosine/Manhattan-style distances a coordinate-only index couldn't.
+//!
+//! Build: pick a vantage point, compute its distance to every remaining
+//! point, take the median distance `mu`, and recurse on the inner
+//! (`dist < mu`) and outer (`dist >= mu`) partitions. Query: descend with a
+//! bounded max-heap of the `k` best candidates and a shrinking search
+//! radius `tau` (the current worst distance in the heap, `infinity` until
+//! it's full), pruning whichever child subtree the triangle inequality
+//! proves can't contain anything closer than `tau`.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+struct VpNode<T> {
+    item: T,
+    vantage: Vec<f32>,
+    mu: f32,
+    inner: Option<Box<VpNode<T>>>,
+    outer: Option<Box<VpNode<T>>>,
+}
+
+pub struct VpTree<T> {
+    root: Option<Box<VpNode<T>>>,
+}
+
+impl<T: Clone> VpTree<T> {
+    /// Consumes `items` and recursively partitions them into a VP-tree
+    /// using `distance` as the metric. Each level picks the last remaining
+    /// item as its vantage point — arbitrary, but avoids pulling in an RNG
+    /// dependency purely to pick a starting point.
+    pub fn build(items: Vec<(T, Vec<f32>)>, distance: &dyn Fn(&[f32], &[f32]) -> f32) -> Self {
+        Self { root: Self::build_node(items, distance) }
+    }
+
+    fn build_node(mut items: Vec<(T, Vec<f32>)>, distance: &dyn Fn(&[f32], &[f32]) -> f32) -> Option<Box<VpNode<T>>> {
+        let (vantage_item, vantage_embedding) = items.pop()?;
+
+        if items.is_empty() {
+            return Some(Box::new(VpNode {
+                item: vantage_item,
+                vantage: vantage_embedding,
+                mu: 0.0,
+                inner: None,
+                outer: None,
+            }));
+        }
+
+        let dists: Vec<f32> = items.iter().map(|(_, embedding)| distance(&vantage_embedding, embedding)).collect();
+        let mut sorted_dists = dists.clone();
+        sorted_dists.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let mu = sorted_dists[sorted_dists.len() / 2];
+
+        let mut inner_items = Vec::new();
+        let mut outer_items = Vec::new();
+        for (entry, dist) in items.into_iter().zip(dists) {
+            if dist < mu {
+                inner_items.push(entry);
+            } else {
+                outer_items.push(entry);
+            }
+        }
+
+        Some(Box::new(VpNode {
+            item: vantage_item,
+            vantage: vantage_embedding,
+            mu,
+            inner: Self::build_node(inner_items, distance),
+            outer: Self::build_node(outer_items, distance),
+        }))
+    }
+
+    /// Exact k-nearest-neighbor search: descends the tree pruning whole
+    /// subtrees the triangle inequality proves can't improve on the
+    /// current worst candidate, giving exact results in roughly O(log n)
+    /// per query instead of scanning every indexed point.
+    pub fn k_nearest(&self, query: &[f32], k: usize, distance: &dyn Fn(&[f32], &[f32]) -> f32) -> Vec<(T, f32)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap: BinaryHeap<HeapEntry<T>> = BinaryHeap::new();
+        let mut tau = f32::INFINITY;
+
+        if let Some(root) = &self.root {
+            Self::search_node(root, query, k, distance, &mut heap, &mut tau);
+        }
+
+        heap.into_sorted_vec().into_iter().map(|entry| (entry.item, entry.distance)).collect()
+    }
+
+    fn search_node(
+        node: &VpNode<T>,
+        query: &[f32],
+        k: usize,
+        distance: &dyn Fn(&[f32], &[f32]) -> f32,
+        heap: &mut BinaryHeap<HeapEntry<T>>,
+        tau: &mut f32,
+    ) {
+        let d = distance(query, &node.vantage);
+
+        if d < *tau {
+            heap.push(HeapEntry { item: node.item.clone(), distance: d });
+            if heap.len() > k {
+                heap.pop();
+            }
+            if heap.len() == k {
+                *tau = heap.peek().map(|entry| entry.distance).unwrap_or(f32::INFINITY);
+            }
+        }
+
+        // `mu` is the vantage point's median distance to its children: if
+        // the query falls inside that radius, the inner child is searched
+        // first (it's the more likely source of closer points), and the
+        // outer child only if the search ball could still reach past `mu`;
+        // symmetric otherwise.
+        if d < node.mu {
+            if let Some(inner) = &node.inner {
+                Self::search_node(inner, query, k, distance, heap, tau);
+            }
+            if d + *tau >= node.mu {
+                if let Some(outer) = &node.outer {
+                    Self::search_node(outer, query, k, distance, heap, tau);
+                }
+            }
+        } else {
+            if let Some(outer) = &node.outer {
+                Self::search_node(outer, query, k, distance, heap, tau);
+            }
+            if d - *tau <= node.mu {
+                if let Some(inner) = &node.inner {
+                    Self::search_node(inner, query, k, distance, heap, tau);
+                }
+            }
+        }
+    }
+}
+
+/// A candidate in the bounded max-heap `k_nearest` prunes against — `Ord`
+/// compares by distance so the heap's max (the worst candidate still kept)
+/// is what gets evicted once the heap grows past `k`.
+struct HeapEntry<T> {
+    item: T,
+    distance: f32,
+}
+
+impl<T> PartialEq for HeapEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl<T> Eq for HeapEntry<T> {}
+
+impl<T> PartialOrd for HeapEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for HeapEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.partial_cmp(&other.distance).unwrap_or(Ordering::Equal)
+    }
+}
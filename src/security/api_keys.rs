@@ -0,0 +1,121 @@
+//! Scoped API keys for the HTTP/SSE transport.
+//!
+//! Complements [`super::auth::AuthConfig`]'s single shared key with a proper
+//! key registry: each key is created with a set of [`ApiKeyScope`]s, is
+//! persisted (hashed, never in plaintext) in `GraphStorage`, and can be
+//! listed or revoked independently of the others. The `api_key_auth`
+//! middleware in `mcp::server` resolves the `Authorization: Bearer <key>`
+//! header against this registry and attaches the matched key's scopes to
+//! the request so handlers can deny write-class operations to read-only
+//! keys. Modeled after Magnetar's and Garage's admin `key.rs`
+//! (create/list/revoke keys with permissions).
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// A permission an API key can carry. `Admin` implies `Write`, which in turn
+/// implies `Read` — see [`ApiKeyScope::implies`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    Read,
+    Write,
+    Admin,
+}
+
+impl ApiKeyScope {
+    /// Whether a key holding this scope may perform an operation that
+    /// `required` gates.
+    pub fn implies(&self, required: ApiKeyScope) -> bool {
+        match self {
+            ApiKeyScope::Admin => true,
+            ApiKeyScope::Write => matches!(required, ApiKeyScope::Write | ApiKeyScope::Read),
+            ApiKeyScope::Read => matches!(required, ApiKeyScope::Read),
+        }
+    }
+
+    pub fn parse(s: &str) -> anyhow::Result<ApiKeyScope> {
+        match s {
+            "read" => Ok(ApiKeyScope::Read),
+            "write" => Ok(ApiKeyScope::Write),
+            "admin" => Ok(ApiKeyScope::Admin),
+            other => Err(anyhow::anyhow!("Unknown API key scope '{}'; expected one of: read, write, admin", other)),
+        }
+    }
+}
+
+/// Metadata for a stored key, as returned by listing — never carries the
+/// plaintext key material.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_at: String,
+    pub revoked: bool,
+}
+
+/// The result of creating a key: its metadata plus the plaintext, which is
+/// shown exactly once and never recoverable afterwards (only its hash is
+/// persisted).
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub record: ApiKeyRecord,
+    pub key: String,
+}
+
+/// Generates a new key's plaintext (`kgmcp_<64 hex chars>`) and the SHA-256
+/// hash of it that should be persisted instead of the plaintext itself.
+pub fn generate_key_material() -> (String, String) {
+    let plaintext = format!("kgmcp_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let hash = hash_key(&plaintext);
+    (plaintext, hash)
+}
+
+/// SHA-256 hex digest of a plaintext key, used both when persisting a new
+/// key and when looking one up from an `Authorization` header.
+pub fn hash_key(plaintext: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(plaintext.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Serializes scopes to the `scopes` column's storage format (comma-separated).
+pub fn scopes_to_column(scopes: &[ApiKeyScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| match s {
+            ApiKeyScope::Read => "read",
+            ApiKeyScope::Write => "write",
+            ApiKeyScope::Admin => "admin",
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parses the `scopes` column's storage format back into scopes, skipping
+/// anything unrecognized rather than failing the whole row read.
+pub fn scopes_from_column(column: &str) -> Vec<ApiKeyScope> {
+    column
+        .split(',')
+        .filter_map(|s| ApiKeyScope::parse(s.trim()).ok())
+        .collect()
+}
+
+/// The scopes resolved for the current request by `api_key_auth`, carried
+/// through axum's request extensions.
+#[derive(Debug, Clone)]
+pub struct ResolvedScopes(pub Vec<ApiKeyScope>);
+
+impl ResolvedScopes {
+    /// All scopes, used when authentication is disabled (open access).
+    pub fn unrestricted() -> Self {
+        Self(vec![ApiKeyScope::Admin])
+    }
+
+    pub fn allows(&self, required: ApiKeyScope) -> bool {
+        self.0.iter().any(|s| s.implies(required))
+    }
+}
@@ -1,44 +1,197 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::{Arc, OnceLock, RwLock};
 use std::time::{Duration, Instant};
-use uuid::Uuid;
+use tracing::warn;
+
+use super::api_keys::ApiKeyScope;
+
+/// Fixed reference instant the process started at, used to express bucket
+/// timestamps as a `u32` of elapsed seconds instead of a full `Instant` (see
+/// `RateLimitInfo`).
+fn process_start() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+/// Operations that require authentication when `admin_operations_require_auth`
+/// is set. Also the base of `OperationClass::classify`'s admin bucket, so the
+/// two stay in sync.
+const ADMIN_OPERATIONS: &[&str] = &[
+    "delete_episode",
+    "delete_entity_edge",
+    "clear_graph",
+    "manage_graph",
+];
+
+/// Coarse operation class a rate limit tier applies to. Distinct from
+/// `ApiKeyScope` (which governs authorization, not throughput): a flood of
+/// cheap `search` calls shouldn't be throttled the same as an `add_episode`
+/// write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationClass {
+    Search,
+    Read,
+    Write,
+    Admin,
+}
+
+impl OperationClass {
+    /// Classifies an MCP operation name into its rate-limit tier. Mirrors
+    /// `ADMIN_OPERATIONS`, then falls back to a best-effort split of the
+    /// rest: `search`-ish operations are cheap lookups, `get_`/`list_`/
+    /// `analyze`-prefixed operations are other reads, everything else is
+    /// assumed to mutate the graph.
+    pub fn classify(operation: &str) -> Self {
+        if ADMIN_OPERATIONS.contains(&operation) {
+            return OperationClass::Admin;
+        }
+        if operation.contains("search") {
+            return OperationClass::Search;
+        }
+        if operation.starts_with("get_") || operation.starts_with("list_") || operation.starts_with("analyze") {
+            return OperationClass::Read;
+        }
+        OperationClass::Write
+    }
+}
+
+/// A rate limit tier's token bucket parameters: refills at
+/// `requests_per_minute / 60` tokens per second up to `burst` capacity.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RateLimitTier {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+}
+
+/// A single entry in `AuthConfig::api_keys`: the role a key carries, reusing
+/// `ApiKeyScope`'s Read ⊆ Write ⊆ Admin hierarchy (the same scope model the
+/// persisted key registry in `api_keys` uses), plus an optional override of
+/// the class-based rate limit tier so e.g. a free-tier key can carry a lower
+/// quota than a privileged one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyPolicy {
+    pub scope: ApiKeyScope,
+    #[serde(default)]
+    pub rate_limit_tier: Option<RateLimitTier>,
+}
+
+/// The minimum `ApiKeyScope` an operation of this class requires. `Search`
+/// and other reads only need `Read`; `Write` needs `Write`; `Admin` needs
+/// `Admin` (which, via `ApiKeyScope::implies`, only an admin-scoped key
+/// satisfies).
+fn required_scope(class: OperationClass) -> ApiKeyScope {
+    match class {
+        OperationClass::Admin => ApiKeyScope::Admin,
+        OperationClass::Write => ApiKeyScope::Write,
+        OperationClass::Read | OperationClass::Search => ApiKeyScope::Read,
+    }
+}
 
 /// Authentication configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     pub enabled: bool,
-    pub api_key: Option<String>,
+    /// The legacy shared key, sourced via `secrets::resolve_auth_token` (a
+    /// token file, `AUTH_TOKEN`, or this field set directly) rather than
+    /// hardcoded, and redacted from `Debug`/log output by `SecretString`.
+    pub api_key: Option<super::secrets::SecretString>,
+    /// Registry of individually-scoped keys, each with its own role and
+    /// optional rate-limit override (see `ApiKeyPolicy`), keyed by the
+    /// plaintext key. Takes priority over `api_key`/`admin_operations_require_auth`
+    /// when non-empty — see `AuthManager::authorize` — so operators can
+    /// migrate from a single shared key to per-tenant keys without a config
+    /// format break.
+    #[serde(default)]
+    pub api_keys: HashMap<String, ApiKeyPolicy>,
+    /// Fallback tier for any `OperationClass` with no entry in
+    /// `rate_limit_tiers` — kept as flat fields rather than folded into the
+    /// map so existing configs that only know about a single limit (e.g.
+    /// `ServerConfig::to_auth_config`) keep working unchanged.
     pub rate_limit_requests_per_minute: u32,
     pub rate_limit_burst: u32,
+    /// Per-operation-class overrides of the fallback tier above, so e.g.
+    /// `Search` can get a high ceiling while `Admin` stays tightly capped.
+    #[serde(default)]
+    pub rate_limit_tiers: HashMap<OperationClass, RateLimitTier>,
+    /// Network prefix length used to bucket IPv6 clients for rate limiting
+    /// (see `get_client_id`). A /64 is the smallest block most ISPs and
+    /// cloud providers hand a single customer, so keying on the full address
+    /// would let an attacker evade limits by cycling host bits within one
+    /// allocation.
+    #[serde(default = "default_ipv6_rate_limit_prefix_len")]
+    pub ipv6_rate_limit_prefix_len: u8,
+    /// How often `AuthManager::spawn_cleanup`'s background task prunes
+    /// stale buckets.
+    #[serde(default = "default_rate_limit_cleanup_interval_secs")]
+    pub rate_limit_cleanup_interval_secs: u64,
+    /// A bucket is considered stale (and dropped) once this many seconds
+    /// have passed since it was last checked.
+    #[serde(default = "default_rate_limit_cleanup_threshold_secs")]
+    pub rate_limit_cleanup_threshold_secs: u64,
     pub admin_operations_require_auth: bool,
 }
 
+fn default_ipv6_rate_limit_prefix_len() -> u8 {
+    64
+}
+
+fn default_rate_limit_cleanup_interval_secs() -> u64 {
+    60
+}
+
+fn default_rate_limit_cleanup_threshold_secs() -> u64 {
+    300
+}
+
+impl AuthConfig {
+    /// The tier to enforce for `class`: its `rate_limit_tiers` override if
+    /// one is configured, otherwise the flat fallback tier.
+    fn tier_for(&self, class: OperationClass) -> RateLimitTier {
+        self.rate_limit_tiers.get(&class).copied().unwrap_or(RateLimitTier {
+            requests_per_minute: self.rate_limit_requests_per_minute,
+            burst: self.rate_limit_burst,
+        })
+    }
+}
+
 impl Default for AuthConfig {
     fn default() -> Self {
         Self {
             enabled: false, // Disabled by default for backward compatibility
             api_key: None,
+            api_keys: HashMap::new(),
             rate_limit_requests_per_minute: 60,
             rate_limit_burst: 10,
+            rate_limit_tiers: HashMap::new(),
+            ipv6_rate_limit_prefix_len: default_ipv6_rate_limit_prefix_len(),
+            rate_limit_cleanup_interval_secs: default_rate_limit_cleanup_interval_secs(),
+            rate_limit_cleanup_threshold_secs: default_rate_limit_cleanup_threshold_secs(),
             admin_operations_require_auth: true,
         }
     }
 }
 
-/// Rate limiting information for a client
-#[derive(Debug, Clone)]
+/// A constant-size token bucket tracking one client's rate limit, replacing
+/// a `Vec<Instant>` of every request in the window (which grew without
+/// bound for a busy client and cost an O(n) scan per check). `last_checked`
+/// is seconds elapsed since `process_start()`, truncated to 32 bits since a
+/// bucket only needs to track recent refills, not wall-clock time.
+#[derive(Debug, Clone, Copy)]
 struct RateLimitInfo {
-    requests: Vec<Instant>,
-    last_request: Instant,
+    tokens: f32,
+    last_checked: u32,
 }
 
 impl RateLimitInfo {
-    fn new() -> Self {
+    /// A fresh bucket starts full, so a client's first request is never
+    /// penalized for buckets created just now.
+    fn new(capacity: f32) -> Self {
         Self {
-            requests: Vec::new(),
-            last_request: Instant::now(),
+            tokens: capacity,
+            last_checked: process_start().elapsed().as_secs() as u32,
         }
     }
 }
@@ -46,26 +199,50 @@ impl RateLimitInfo {
 /// Authentication and authorization manager
 pub struct AuthManager {
     config: AuthConfig,
-    rate_limits: Arc<RwLock<HashMap<String, RateLimitInfo>>>,
+    /// The legacy shared key, held separately from `config` (and seeded
+    /// from `config.api_key`) so `spawn_token_watcher` can hot-swap it when
+    /// the backing token file changes without needing `&mut self`.
+    api_key: Arc<RwLock<Option<super::secrets::SecretString>>>,
+    /// One token bucket per `(client_id, operation class)` pair, so classes
+    /// with their own `rate_limit_tiers` entry don't share a bucket with
+    /// (and get starved by) a different class for the same client.
+    rate_limits: Arc<RwLock<HashMap<(String, OperationClass), RateLimitInfo>>>,
 }
 
 impl AuthManager {
     pub fn new(config: AuthConfig) -> Self {
+        let api_key = Arc::new(RwLock::new(config.api_key.clone()));
         Self {
             config,
+            api_key,
             rate_limits: Arc::new(RwLock::new(HashMap::new())),
         }
     }
-    
+
+    /// Watches `path` for changes and hot-swaps the legacy shared key this
+    /// manager validates against (see `secrets::spawn_token_file_watcher`),
+    /// so a rotated token file takes effect without a restart. Watching
+    /// lasts as long as the returned `notify` watcher is kept alive; drop it
+    /// to stop watching.
+    pub fn spawn_token_watcher(&self, path: std::path::PathBuf) -> Result<notify::RecommendedWatcher> {
+        super::secrets::spawn_token_file_watcher(path, self.api_key.clone())
+    }
+
     /// Validate API key if authentication is enabled
     pub fn validate_api_key(&self, provided_key: Option<&str>) -> Result<bool> {
         if !self.config.enabled {
             return Ok(true); // Authentication disabled
         }
-        
-        match (&self.config.api_key, provided_key) {
+
+        let api_key = self.api_key.read()
+            .map_err(|_| anyhow!("Failed to acquire api key lock"))?;
+
+        match (api_key.as_ref(), provided_key) {
             (Some(expected), Some(provided)) => {
-                Ok(expected == provided)
+                // Constant-time: `provided` is attacker-controlled, and a
+                // plain `==` would leak how many leading bytes matched via
+                // response timing.
+                Ok(expected.ct_eq(provided))
             },
             (Some(_), None) => Ok(false), // API key required but not provided
             (None, _) => Ok(true), // No API key configured
@@ -77,112 +254,187 @@ impl AuthManager {
         if !self.config.enabled {
             return false;
         }
-        
-        // Administrative operations that require authentication
-        let admin_operations = [
-            "delete_episode",
-            "delete_entity_edge", 
-            "clear_graph",
-            "manage_graph",
-        ];
-        
+
         if self.config.admin_operations_require_auth {
-            admin_operations.contains(&operation)
+            ADMIN_OPERATIONS.contains(&operation)
         } else {
             false
         }
     }
-    
-    /// Check rate limits for a client
-    pub fn check_rate_limit(&self, client_id: &str) -> Result<bool> {
+
+    /// Resolves `provided_key` against the `AuthConfig::api_keys` registry,
+    /// if it's configured.
+    fn resolve_key_policy(&self, provided_key: Option<&str>) -> Option<&ApiKeyPolicy> {
+        provided_key.and_then(|key| self.config.api_keys.get(key))
+    }
+
+    /// Authorizes `operation` for `provided_key`. When `AuthConfig::api_keys`
+    /// has entries, the key must resolve to a policy whose `scope` implies
+    /// the scope `operation`'s class requires (see `required_scope`) — a
+    /// role → allowed-operations policy that replaces the flat
+    /// `admin_operations` allow-list. Falls back to the legacy single shared
+    /// `api_key` / `admin_operations_require_auth` check when no registry is
+    /// configured, so configs that predate it keep working unchanged.
+    pub fn authorize(&self, operation: &str, provided_key: Option<&str>) -> Result<bool> {
+        if !self.config.enabled {
+            return Ok(true);
+        }
+
+        if !self.config.api_keys.is_empty() {
+            let required = required_scope(OperationClass::classify(operation));
+            return Ok(self
+                .resolve_key_policy(provided_key)
+                .is_some_and(|policy| policy.scope.implies(required)));
+        }
+
+        if self.requires_auth(operation) {
+            self.validate_api_key(provided_key)
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// Checks and consumes one token from a rate limit bucket for
+    /// `operation`'s class (see `OperationClass::classify`). The bucket is
+    /// keyed by `client_id` unless `provided_key` resolves to an
+    /// `AuthConfig::api_keys` entry, in which case the key itself is the
+    /// identity (so a key's quota travels with it regardless of which client
+    /// presents it) and that entry's `rate_limit_tier` override applies if
+    /// set. Each `(identity, class)` pair gets its own bucket, refilling at
+    /// `requests_per_minute / 60` tokens per second up to `burst` capacity.
+    pub fn check_rate_limit(&self, client_id: &str, operation: &str, provided_key: Option<&str>) -> Result<bool> {
         let mut rate_limits = self.rate_limits.write()
             .map_err(|_| anyhow!("Failed to acquire rate limit lock"))?;
-        
-        let now = Instant::now();
-        let window = Duration::from_secs(60); // 1 minute window
-        
-        let rate_info = rate_limits.entry(client_id.to_string())
-            .or_insert_with(RateLimitInfo::new);
-        
-        // Remove old requests outside the window
-        rate_info.requests.retain(|&request_time| {
-            now.duration_since(request_time) < window
-        });
-        
-        // Check if we're within limits
-        if rate_info.requests.len() >= self.config.rate_limit_requests_per_minute as usize {
-            return Ok(false); // Rate limit exceeded
-        }
-        
-        // Check burst limit (requests in last 10 seconds)
-        let burst_window = Duration::from_secs(10);
-        let recent_requests = rate_info.requests.iter()
-            .filter(|&&request_time| now.duration_since(request_time) < burst_window)
-            .count();
-        
-        if recent_requests >= self.config.rate_limit_burst as usize {
-            return Ok(false); // Burst limit exceeded
+
+        let class = OperationClass::classify(operation);
+        let (identity, tier) = match (provided_key, self.resolve_key_policy(provided_key)) {
+            (Some(key), Some(policy)) => (key, policy.rate_limit_tier.unwrap_or_else(|| self.config.tier_for(class))),
+            _ => (client_id, self.config.tier_for(class)),
+        };
+        let capacity = tier.burst as f32;
+        let refill_rate = tier.requests_per_minute as f32 / 60.0;
+        let now_secs = process_start().elapsed().as_secs() as u32;
+
+        let bucket = rate_limits.entry((identity.to_string(), class))
+            .or_insert_with(|| RateLimitInfo::new(capacity));
+
+        let elapsed = now_secs.saturating_sub(bucket.last_checked) as f32;
+        bucket.tokens = (bucket.tokens + elapsed * refill_rate).clamp(0.0, capacity);
+        bucket.last_checked = now_secs;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(true)
+        } else {
+            Ok(false)
         }
-        
-        // Add current request
-        rate_info.requests.push(now);
-        rate_info.last_request = now;
-        
-        Ok(true)
     }
     
-    /// Extract client identifier from request
+    /// Extract a rate-limit bucketing key from request headers. An explicit
+    /// `x-client-id` is trusted verbatim (it identifies a specific caller,
+    /// not a network). Otherwise the client's IP (from `x-forwarded-for` or
+    /// `x-real-ip`) is parsed and used as the key: IPv4 addresses key
+    /// per-address, while IPv6 addresses are bucketed by network prefix
+    /// (`ipv6_rate_limit_prefix_len`, default /64) so an attacker can't
+    /// evade limits just by cycling host bits within one allocation.
+    /// Requests with no parseable IP at all fall back to a single shared
+    /// "unidentified" bucket rather than a fresh UUID per request, so that
+    /// path can't be used to mint unlimited free quota either.
     pub fn get_client_id(&self, headers: &HashMap<String, String>) -> String {
-        // Try to get client ID from headers, fallback to IP or generate one
-        headers.get("x-client-id")
-            .or_else(|| headers.get("x-forwarded-for"))
+        if let Some(client_id) = headers.get("x-client-id") {
+            return client_id.clone();
+        }
+
+        let ip = headers.get("x-forwarded-for")
             .or_else(|| headers.get("x-real-ip"))
-            .cloned()
-            .unwrap_or_else(|| Uuid::new_v4().to_string())
+            .and_then(|raw| raw.trim().parse::<IpAddr>().ok());
+
+        match ip {
+            Some(IpAddr::V4(addr)) => addr.to_string(),
+            Some(IpAddr::V6(addr)) => Self::ipv6_network_key(addr, self.config.ipv6_rate_limit_prefix_len),
+            None => "unidentified".to_string(),
+        }
+    }
+
+    /// Collapses an IPv6 address down to its `/prefix_len` network, so every
+    /// address within the same allocation maps to the same rate-limit key.
+    fn ipv6_network_key(addr: Ipv6Addr, prefix_len: u8) -> String {
+        let prefix_len = prefix_len.min(128);
+        let mask = if prefix_len == 0 { 0 } else { u128::MAX << (128 - prefix_len) };
+        let network = Ipv6Addr::from(u128::from(addr) & mask);
+        format!("{network}/{prefix_len}")
     }
     
-    /// Clean up old rate limit entries
+    /// Clean up stale rate limit entries (clients that haven't been checked
+    /// in a while) so `rate_limits` doesn't grow forever with one-off clients.
+    /// `retain` prunes in place without reallocating the map.
     pub fn cleanup_rate_limits(&self) -> Result<()> {
         let mut rate_limits = self.rate_limits.write()
             .map_err(|_| anyhow!("Failed to acquire rate limit lock"))?;
-        
-        let now = Instant::now();
-        let cleanup_threshold = Duration::from_secs(300); // 5 minutes
-        
-        rate_limits.retain(|_, rate_info| {
-            now.duration_since(rate_info.last_request) < cleanup_threshold
+
+        let now_secs = process_start().elapsed().as_secs() as u32;
+        let cleanup_threshold_secs = self.config.rate_limit_cleanup_threshold_secs as u32;
+
+        rate_limits.retain(|_, bucket| {
+            now_secs.saturating_sub(bucket.last_checked) < cleanup_threshold_secs
         });
-        
+
         Ok(())
     }
-    
-    /// Get rate limit status for a client
-    pub fn get_rate_limit_status(&self, client_id: &str) -> Result<RateLimitStatus> {
+
+    /// Spawns a background task that calls `cleanup_rate_limits` on a
+    /// `rate_limit_cleanup_interval_secs` interval, so pruning happens on its
+    /// own instead of depending on some other caller remembering to invoke
+    /// it. Mirrors `IngestionWatcher::spawn`'s cancellable-handle pattern;
+    /// abort the returned handle to stop the task on shutdown.
+    ///
+    /// `rate_limits` stays a single `RwLock<HashMap<..>>` rather than a
+    /// sharded map: cleanup only ever takes the write lock briefly and on
+    /// its own schedule, and at the client counts this server expects, one
+    /// lock held for a `retain` pass is not a meaningful contention source.
+    pub fn spawn_cleanup(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let interval = Duration::from_secs(self.config.rate_limit_cleanup_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.cleanup_rate_limits() {
+                    warn!("Rate limit bucket cleanup failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Get rate limit status for `operation`'s class, keyed the same way
+    /// `check_rate_limit` keys its bucket (see its doc comment).
+    pub fn get_rate_limit_status(&self, client_id: &str, operation: &str, provided_key: Option<&str>) -> Result<RateLimitStatus> {
         let rate_limits = self.rate_limits.read()
             .map_err(|_| anyhow!("Failed to acquire rate limit lock"))?;
-        
-        if let Some(rate_info) = rate_limits.get(client_id) {
-            let now = Instant::now();
-            let window = Duration::from_secs(60);
-            
-            let current_requests = rate_info.requests.iter()
-                .filter(|&&request_time| now.duration_since(request_time) < window)
-                .count();
-            
-            Ok(RateLimitStatus {
-                requests_used: current_requests as u32,
-                requests_limit: self.config.rate_limit_requests_per_minute,
-                reset_time: rate_info.requests.first()
-                    .map(|&first| first + window)
-                    .unwrap_or(now),
-            })
+
+        let class = OperationClass::classify(operation);
+        let (identity, tier) = match (provided_key, self.resolve_key_policy(provided_key)) {
+            (Some(key), Some(policy)) => (key, policy.rate_limit_tier.unwrap_or_else(|| self.config.tier_for(class))),
+            _ => (client_id, self.config.tier_for(class)),
+        };
+        let capacity = tier.burst as f32;
+        let refill_rate = tier.requests_per_minute as f32 / 60.0;
+
+        let key = (identity.to_string(), class);
+        let tokens = rate_limits.get(&key).map(|bucket| bucket.tokens).unwrap_or(capacity);
+        let requests_used = (capacity - tokens).max(0.0).round() as u32;
+
+        let reset_time = if tokens >= 1.0 || refill_rate <= 0.0 {
+            Instant::now()
         } else {
-            Ok(RateLimitStatus {
-                requests_used: 0,
-                requests_limit: self.config.rate_limit_requests_per_minute,
-                reset_time: now + Duration::from_secs(60),
-            })
-        }
+            let seconds_to_next_token = (1.0 - tokens) / refill_rate;
+            Instant::now() + Duration::from_secs_f32(seconds_to_next_token)
+        };
+
+        Ok(RateLimitStatus {
+            requests_used,
+            requests_limit: tier.requests_per_minute,
+            reset_time,
+        })
     }
 }
 
@@ -223,18 +475,17 @@ pub fn authenticate_request(
     api_key: Option<&str>,
     client_id: &str,
 ) -> Result<AuthResult> {
-    // Check rate limits first
-    if !auth_manager.check_rate_limit(client_id)? {
+    // Check rate limits first, against the bucket for this operation's class
+    if !auth_manager.check_rate_limit(client_id, operation, api_key)? {
         return Ok(AuthResult::RateLimited);
     }
-    
-    // Check if operation requires authentication
-    if auth_manager.requires_auth(operation) {
-        if !auth_manager.validate_api_key(api_key)? {
-            return Ok(AuthResult::Denied("Invalid or missing API key".to_string()));
-        }
+
+    // Check the caller is authorized for this operation (per-key role if
+    // `AuthConfig::api_keys` is configured, else the legacy shared-key check)
+    if !auth_manager.authorize(operation, api_key)? {
+        return Ok(AuthResult::Denied("Invalid or missing API key".to_string()));
     }
-    
+
     Ok(AuthResult::Allowed)
 }
 
@@ -258,7 +509,7 @@ mod tests {
     fn test_auth_enabled() {
         let config = AuthConfig {
             enabled: true,
-            api_key: Some("test-key".to_string()),
+            api_key: Some(crate::security::secrets::SecretString::new("test-key".to_string())),
             admin_operations_require_auth: true,
             ..Default::default()
         };
@@ -280,11 +531,157 @@ mod tests {
         let auth_manager = AuthManager::new(config);
         
         let client_id = "test-client";
-        
+
         // First request should be allowed
-        assert!(auth_manager.check_rate_limit(client_id).unwrap());
-        
+        assert!(auth_manager.check_rate_limit(client_id, "add_episode", None).unwrap());
+
         // Second request should hit burst limit
-        assert!(!auth_manager.check_rate_limit(client_id).unwrap());
+        assert!(!auth_manager.check_rate_limit(client_id, "add_episode", None).unwrap());
+    }
+
+    #[test]
+    fn test_rate_limit_tiers_are_independent_per_operation_class() {
+        let mut rate_limit_tiers = HashMap::new();
+        rate_limit_tiers.insert(OperationClass::Search, RateLimitTier { requests_per_minute: 600, burst: 100 });
+        let config = AuthConfig {
+            rate_limit_requests_per_minute: 2,
+            rate_limit_burst: 1,
+            rate_limit_tiers,
+            ..Default::default()
+        };
+        let auth_manager = AuthManager::new(config);
+        let client_id = "test-client";
+
+        // The default tier's burst of 1 is exhausted by a write...
+        assert!(auth_manager.check_rate_limit(client_id, "add_episode", None).unwrap());
+        assert!(!auth_manager.check_rate_limit(client_id, "add_episode", None).unwrap());
+
+        // ...but `search` has its own, much larger tier, so it isn't affected.
+        assert!(auth_manager.check_rate_limit(client_id, "search_memory", None).unwrap());
+        assert!(auth_manager.check_rate_limit(client_id, "search_memory", None).unwrap());
+    }
+
+    #[test]
+    fn test_operation_class_classification() {
+        assert_eq!(OperationClass::classify("delete_episode"), OperationClass::Admin);
+        assert_eq!(OperationClass::classify("search_memory"), OperationClass::Search);
+        assert_eq!(OperationClass::classify("get_episodes"), OperationClass::Read);
+        assert_eq!(OperationClass::classify("add_episode"), OperationClass::Write);
+    }
+
+    #[test]
+    fn test_get_client_id_prefers_explicit_client_id() {
+        let auth_manager = AuthManager::new(AuthConfig::default());
+        let mut headers = HashMap::new();
+        headers.insert("x-client-id".to_string(), "caller-42".to_string());
+        headers.insert("x-forwarded-for".to_string(), "203.0.113.7".to_string());
+
+        assert_eq!(auth_manager.get_client_id(&headers), "caller-42");
+    }
+
+    #[test]
+    fn test_get_client_id_keys_ipv4_per_address() {
+        let auth_manager = AuthManager::new(AuthConfig::default());
+        let mut headers = HashMap::new();
+        headers.insert("x-forwarded-for".to_string(), "203.0.113.7".to_string());
+
+        assert_eq!(auth_manager.get_client_id(&headers), "203.0.113.7");
+    }
+
+    #[test]
+    fn test_get_client_id_groups_ipv6_addresses_by_prefix() {
+        let auth_manager = AuthManager::new(AuthConfig::default());
+        let mut first = HashMap::new();
+        first.insert("x-real-ip".to_string(), "2001:db8::1".to_string());
+        let mut second = HashMap::new();
+        second.insert("x-real-ip".to_string(), "2001:db8::ffff:ffff:ffff:ffff".to_string());
+
+        // Both addresses share a /64, so cycling host bits doesn't escape the bucket.
+        assert_eq!(auth_manager.get_client_id(&first), auth_manager.get_client_id(&second));
+    }
+
+    #[test]
+    fn test_get_client_id_falls_back_to_shared_unidentified_bucket() {
+        let auth_manager = AuthManager::new(AuthConfig::default());
+        let headers = HashMap::new();
+
+        assert_eq!(auth_manager.get_client_id(&headers), "unidentified");
+    }
+
+    #[test]
+    fn test_cleanup_rate_limits_keeps_recently_checked_buckets() {
+        let auth_manager = AuthManager::new(AuthConfig::default());
+        auth_manager.check_rate_limit("test-client", "add_episode", None).unwrap();
+
+        auth_manager.cleanup_rate_limits().unwrap();
+
+        // A bucket touched moments ago is well under the default 5 minute
+        // threshold, so cleanup must not have evicted it.
+        let status = auth_manager.get_rate_limit_status("test-client", "add_episode", None).unwrap();
+        assert_eq!(status.requests_used, 1);
+    }
+
+    #[test]
+    fn test_authorize_enforces_per_key_role() {
+        let mut api_keys = HashMap::new();
+        api_keys.insert("reader-key".to_string(), ApiKeyPolicy { scope: ApiKeyScope::Read, rate_limit_tier: None });
+        api_keys.insert("admin-key".to_string(), ApiKeyPolicy { scope: ApiKeyScope::Admin, rate_limit_tier: None });
+        let config = AuthConfig { enabled: true, api_keys, ..Default::default() };
+        let auth_manager = AuthManager::new(config);
+
+        assert!(auth_manager.authorize("search_memory", Some("reader-key")).unwrap());
+        assert!(!auth_manager.authorize("delete_episode", Some("reader-key")).unwrap());
+        assert!(auth_manager.authorize("delete_episode", Some("admin-key")).unwrap());
+        assert!(!auth_manager.authorize("search_memory", Some("unknown-key")).unwrap());
+        assert!(!auth_manager.authorize("search_memory", None).unwrap());
+    }
+
+    #[test]
+    fn test_check_rate_limit_uses_per_key_tier_override() {
+        let mut api_keys = HashMap::new();
+        api_keys.insert(
+            "premium-key".to_string(),
+            ApiKeyPolicy {
+                scope: ApiKeyScope::Write,
+                rate_limit_tier: Some(RateLimitTier { requests_per_minute: 600, burst: 2 }),
+            },
+        );
+        let config = AuthConfig {
+            rate_limit_requests_per_minute: 2,
+            rate_limit_burst: 1,
+            api_keys,
+            ..Default::default()
+        };
+        let auth_manager = AuthManager::new(config);
+
+        // The key's own burst of 2 survives two requests, beyond the default burst of 1.
+        assert!(auth_manager.check_rate_limit("some-client", "add_episode", Some("premium-key")).unwrap());
+        assert!(auth_manager.check_rate_limit("some-client", "add_episode", Some("premium-key")).unwrap());
+        assert!(!auth_manager.check_rate_limit("some-client", "add_episode", Some("premium-key")).unwrap());
+    }
+
+    #[test]
+    fn test_spawn_token_watcher_hot_swaps_api_key() {
+        let dir = std::env::temp_dir().join(format!(
+            "kg-mcp-server-auth-watcher-test-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("token");
+        std::fs::write(&path, "original-token").unwrap();
+
+        let config = AuthConfig { enabled: true, ..Default::default() };
+        let auth_manager = AuthManager::new(config);
+        let _watcher = auth_manager.spawn_token_watcher(path.clone()).unwrap();
+
+        std::fs::write(&path, "rotated-token").unwrap();
+        // The watcher's callback runs on a background thread; give it a
+        // moment to observe the write and swap the key in before asserting.
+        std::thread::sleep(Duration::from_millis(500));
+
+        assert!(auth_manager.validate_api_key(Some("rotated-token")).unwrap());
+        assert!(!auth_manager.validate_api_key(Some("original-token")).unwrap());
+
+        std::fs::remove_dir_all(&dir).ok();
     }
 }
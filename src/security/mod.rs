@@ -0,0 +1,10 @@
+pub mod api_keys;
+pub mod auth;
+pub mod secrets;
+
+pub use api_keys::{ApiKeyRecord, ApiKeyScope, CreatedApiKey, ResolvedScopes};
+pub use auth::{
+    authenticate_request, ApiKeyPolicy, AuthConfig, AuthManager, AuthResult, OperationClass,
+    RateLimitStatus, RateLimitTier,
+};
+pub use secrets::{resolve_auth_token, SecretString, AUTH_TOKEN_ENV_VAR};
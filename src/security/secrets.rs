@@ -0,0 +1,174 @@
+//! Secret values for auth configuration, sourced from a file path or
+//! environment variable instead of being committed straight into
+//! `SecurityConfig`/`AuthConfig`.
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::error;
+
+/// Environment variable `resolve_auth_token` falls back to when no
+/// `auth_token_file` is configured.
+pub const AUTH_TOKEN_ENV_VAR: &str = "AUTH_TOKEN";
+
+/// A string that must never reach logs or `Debug` output. Wraps
+/// `AuthConfig::api_key` (and anything else resolved through this module) so
+/// an accidental `{:?}` of the surrounding config can't leak it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// The real value. Named `expose_secret` rather than something shorter
+    /// so every call site reads as a deliberate decision to handle a secret.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Compares against `candidate` (e.g. a bearer token a client supplied)
+    /// in constant time. Plain `==` short-circuits on the first differing
+    /// byte, which leaks timing information proportional to how much of an
+    /// attacker's guess matched the real secret; this always walks every
+    /// byte of the shorter comparison instead. Lengths differing is itself
+    /// a much coarser signal than that, so it's fine to return early there.
+    pub fn ct_eq(&self, candidate: &str) -> bool {
+        let expected = self.0.as_bytes();
+        let actual = candidate.as_bytes();
+        if expected.len() != actual.len() {
+            return false;
+        }
+        let mut diff: u8 = 0;
+        for (a, b) in expected.iter().zip(actual.iter()) {
+            diff |= a ^ b;
+        }
+        diff == 0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(\"[REDACTED]\")")
+    }
+}
+
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+/// Resolves the auth token from, in priority order: `token_file` (read from
+/// disk and trimmed of surrounding whitespace), the `AUTH_TOKEN` environment
+/// variable, then `inline` (the legacy `SecurityConfig::api_key` value
+/// committed straight into config). Returns `Ok(None)` if none of the three
+/// are set; callers that require a secret when auth is enabled (see
+/// `ServerConfig::validate`) turn that into an error themselves.
+pub fn resolve_auth_token(
+    token_file: Option<&Path>,
+    inline: Option<&str>,
+) -> Result<Option<SecretString>> {
+    if let Some(path) = token_file {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read auth token file: {}", path.display()))?;
+        return Ok(Some(SecretString::new(contents.trim().to_string())));
+    }
+
+    if let Ok(value) = std::env::var(AUTH_TOKEN_ENV_VAR) {
+        if !value.is_empty() {
+            return Ok(Some(SecretString::new(value)));
+        }
+    }
+
+    Ok(inline.map(|value| SecretString::new(value.to_string())))
+}
+
+/// Watches `path` for changes and re-resolves the token into `current` on
+/// every event, so a rotated token file takes effect without a server
+/// restart. Mirrors `IngestionWatcher`'s `notify::recommended_watcher`
+/// setup; unlike that watcher this one has no debounce loop, since a
+/// rotation firing the swap a couple of times in quick succession is
+/// harmless.
+pub fn spawn_token_file_watcher(
+    path: PathBuf,
+    current: Arc<RwLock<Option<SecretString>>>,
+) -> Result<RecommendedWatcher> {
+    let watch_path = path.clone();
+    let mut watcher: RecommendedWatcher =
+        notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    match resolve_auth_token(Some(&watch_path), None) {
+                        Ok(token) => {
+                            if let Ok(mut slot) = current.write() {
+                                *slot = token;
+                            }
+                        }
+                        Err(e) => error!("Failed to reload auth token file: {}", e),
+                    }
+                }
+            }
+            Err(e) => error!("Auth token file watch error: {}", e),
+        })
+        .context("Failed to create auth token file watcher")?;
+
+    watcher
+        .watch(&path, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch auth token file: {}", path.display()))?;
+
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_string_debug_is_redacted() {
+        let secret = SecretString::new("super-secret-value".to_string());
+        assert_eq!(format!("{:?}", secret), "SecretString(\"[REDACTED]\")");
+    }
+
+    #[test]
+    fn test_resolve_auth_token_prefers_file_over_env_and_inline() {
+        let dir = std::env::temp_dir().join(format!(
+            "kg-mcp-server-secrets-test-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("token");
+        std::fs::write(&path, "file-token\n").unwrap();
+
+        let resolved = resolve_auth_token(Some(&path), Some("inline-token")).unwrap();
+        assert_eq!(resolved.unwrap().expose_secret(), "file-token");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_resolve_auth_token_falls_back_to_inline() {
+        let resolved = resolve_auth_token(None, Some("inline-token")).unwrap();
+        assert_eq!(resolved.unwrap().expose_secret(), "inline-token");
+    }
+
+    #[test]
+    fn test_resolve_auth_token_none_when_nothing_configured() {
+        // Guard against a leftover AUTH_TOKEN from the outer environment
+        // making this test flaky; only assert when it's genuinely unset.
+        if std::env::var(AUTH_TOKEN_ENV_VAR).is_err() {
+            let resolved = resolve_auth_token(None, None).unwrap();
+            assert!(resolved.is_none());
+        }
+    }
+}
@@ -1,8 +1,14 @@
 use anyhow::Result;
+use rand::Rng;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tracing::{debug, warn, error};
 
+const STATE_CLOSED: u8 = 0;
+const STATE_OPEN: u8 = 1;
+const STATE_HALF_OPEN: u8 = 2;
+
 /// Circuit breaker states
 #[derive(Debug, Clone, PartialEq)]
 pub enum CircuitState {
@@ -15,9 +21,12 @@ pub enum CircuitState {
 #[derive(Debug, Clone)]
 pub struct CircuitBreakerConfig {
     pub failure_threshold: u32,
+    /// Base wait before probing in HalfOpen - the first Open trip uses this
+    /// value unmodified; later consecutive trips grow it via `backoff`.
     pub recovery_timeout: Duration,
     pub success_threshold: u32,
     pub timeout: Duration,
+    pub backoff: BackoffPolicy,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -27,16 +36,76 @@ impl Default for CircuitBreakerConfig {
             recovery_timeout: Duration::from_secs(60),
             success_threshold: 3,
             timeout: Duration::from_secs(30),
+            backoff: BackoffPolicy::default(),
         }
     }
 }
 
-/// Circuit breaker for fault tolerance
+/// Geometric backoff with jitter for repeated Open transitions, the same
+/// shape as the retry-interval strategies a load client would use against a
+/// flapping dependency - a constant `recovery_timeout` hammers a still-down
+/// dependency at a fixed cadence; this backs off further with each
+/// consecutive trip instead.
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    pub multiplier: f64,
+    pub max_backoff: Duration,
+    /// Randomizes the computed wait by a factor in `[1 - jitter, 1 + jitter]`
+    /// so many breakers tripping at once don't all probe in lockstep.
+    pub jitter: f64,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            multiplier: 2.0,
+            max_backoff: Duration::from_secs(300),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Effective wait for the `trips`-th consecutive Open transition (1 =
+    /// first trip, using `base` unmodified): `base * multiplier^(trips-1)`,
+    /// capped at `max_backoff`, then jittered by a random factor in
+    /// `[1-jitter, 1+jitter]`.
+    fn effective_wait(&self, base: Duration, trips: u32) -> Duration {
+        let exponent = trips.saturating_sub(1) as i32;
+        let scaled = base.mul_f64(self.multiplier.powi(exponent)).min(self.max_backoff);
+        let jitter_factor = rand::thread_rng().gen_range((1.0 - self.jitter)..=(1.0 + self.jitter));
+        scaled.mul_f64(jitter_factor.max(0.0))
+    }
+}
+
+/// Circuit breaker for fault tolerance.
+///
+/// State lives entirely in atomics rather than behind independent `Mutex`es,
+/// so `is_open`/`on_success`/`on_failure` never observe a torn combination of
+/// state and timestamp the way four separately-locked fields could, and the
+/// success path (`can_execute`/`call` when closed) never blocks on a lock.
+/// Every state transition (Closed->Open, HalfOpen->Closed, HalfOpen->Open) is
+/// gated behind a `compare_exchange` so that when multiple threads race to
+/// cross a threshold at once, exactly one of them performs the move and the
+/// rest just observe it already applied - the same single-writer-via-CAS
+/// pattern `mcp::performance::ConnectionPool` uses its semaphore for, applied
+/// here at the level of a single state byte instead of a permit count.
 pub struct CircuitBreaker {
-    state: Arc<Mutex<CircuitState>>,
-    failure_count: Arc<Mutex<u32>>,
-    success_count: Arc<Mutex<u32>>,
-    last_failure_time: Arc<Mutex<Option<Instant>>>,
+    state: AtomicU8,
+    failure_count: AtomicU32,
+    success_count: AtomicU32,
+    /// Nanoseconds elapsed since `created_at` as of the last recorded
+    /// failure; `0` is the "no failure yet" sentinel; `Instant` itself isn't
+    /// atomic-friendly, so this plus `created_at` reconstructs one.
+    last_failure_nanos: AtomicU64,
+    /// Consecutive Open transitions without a fully-closed HalfOpen probe in
+    /// between; drives `BackoffPolicy::effective_wait`. Reset to zero when a
+    /// probe succeeds enough to close the circuit, or by `reset`.
+    consecutive_open_trips: AtomicU32,
+    /// Nanoseconds of the backoff computed for the most recent Open
+    /// transition; `0` means the breaker has never tripped.
+    current_backoff_nanos: AtomicU64,
+    created_at: Instant,
     config: CircuitBreakerConfig,
     name: String,
 }
@@ -44,15 +113,24 @@ pub struct CircuitBreaker {
 impl CircuitBreaker {
     pub fn new(name: String, config: CircuitBreakerConfig) -> Self {
         Self {
-            state: Arc::new(Mutex::new(CircuitState::Closed)),
-            failure_count: Arc::new(Mutex::new(0)),
-            success_count: Arc::new(Mutex::new(0)),
-            last_failure_time: Arc::new(Mutex::new(None)),
+            state: AtomicU8::new(STATE_CLOSED),
+            failure_count: AtomicU32::new(0),
+            success_count: AtomicU32::new(0),
+            last_failure_nanos: AtomicU64::new(0),
+            consecutive_open_trips: AtomicU32::new(0),
+            current_backoff_nanos: AtomicU64::new(0),
+            created_at: Instant::now(),
             config,
             name,
         }
     }
 
+    /// Nanoseconds elapsed since `created_at`, the unit `last_failure_nanos`
+    /// and `current_backoff_nanos` are stored in.
+    fn now_nanos(&self) -> u64 {
+        self.created_at.elapsed().as_nanos().min(u64::MAX as u128) as u64
+    }
+
     /// Execute a function with circuit breaker protection
     pub async fn call<F, T, E>(&self, operation: F) -> Result<T>
     where
@@ -99,38 +177,41 @@ impl CircuitBreaker {
 
     /// Get current circuit state
     pub fn get_state(&self) -> CircuitState {
-        let state = self.state.lock().unwrap();
-        state.clone()
+        match self.state.load(Ordering::Acquire) {
+            STATE_OPEN => CircuitState::Open,
+            STATE_HALF_OPEN => CircuitState::HalfOpen,
+            _ => CircuitState::Closed,
+        }
     }
 
     /// Get failure statistics
     pub fn get_stats(&self) -> CircuitBreakerStats {
-        let state = self.state.lock().unwrap();
-        let failure_count = *self.failure_count.lock().unwrap();
-        let success_count = *self.success_count.lock().unwrap();
-        let last_failure = *self.last_failure_time.lock().unwrap();
+        let last_failure_nanos = self.last_failure_nanos.load(Ordering::Acquire);
+        let last_failure_time = (last_failure_nanos != 0)
+            .then(|| self.created_at + Duration::from_nanos(last_failure_nanos));
+
+        let backoff_nanos = self.current_backoff_nanos.load(Ordering::Acquire);
+        let current_backoff = (backoff_nanos != 0).then(|| Duration::from_nanos(backoff_nanos));
 
         CircuitBreakerStats {
             name: self.name.clone(),
-            state: state.clone(),
-            failure_count,
-            success_count,
-            last_failure_time: last_failure,
+            state: self.get_state(),
+            failure_count: self.failure_count.load(Ordering::Acquire),
+            success_count: self.success_count.load(Ordering::Acquire),
+            last_failure_time,
             config: self.config.clone(),
+            current_backoff,
         }
     }
 
     /// Reset circuit breaker to closed state
     pub fn reset(&self) {
-        let mut state = self.state.lock().unwrap();
-        let mut failure_count = self.failure_count.lock().unwrap();
-        let mut success_count = self.success_count.lock().unwrap();
-        let mut last_failure = self.last_failure_time.lock().unwrap();
-
-        *state = CircuitState::Closed;
-        *failure_count = 0;
-        *success_count = 0;
-        *last_failure = None;
+        self.state.store(STATE_CLOSED, Ordering::Release);
+        self.failure_count.store(0, Ordering::Release);
+        self.success_count.store(0, Ordering::Release);
+        self.last_failure_nanos.store(0, Ordering::Release);
+        self.consecutive_open_trips.store(0, Ordering::Release);
+        self.current_backoff_nanos.store(0, Ordering::Release);
 
         debug!("Circuit breaker '{}' has been reset", self.name);
     }
@@ -138,82 +219,112 @@ impl CircuitBreaker {
     // Private methods
 
     fn is_open(&self) -> bool {
-        let state = self.state.lock().unwrap();
-        match *state {
-            CircuitState::Open => {
-                // Check if we should transition to half-open
-                if let Some(last_failure) = *self.last_failure_time.lock().unwrap() {
-                    if last_failure.elapsed() >= self.config.recovery_timeout {
-                        drop(state);
-                        self.transition_to_half_open();
-                        return false;
-                    }
-                }
-                true
-            },
-            _ => false,
+        if self.state.load(Ordering::Acquire) != STATE_OPEN {
+            return false;
+        }
+
+        // Compare against the jittered backoff computed for this trip
+        // rather than the static `recovery_timeout`.
+        let last_failure_nanos = self.last_failure_nanos.load(Ordering::Acquire);
+        if last_failure_nanos == 0 {
+            return true;
+        }
+        let backoff_nanos = self.current_backoff_nanos.load(Ordering::Acquire);
+        let wait = if backoff_nanos != 0 {
+            Duration::from_nanos(backoff_nanos)
+        } else {
+            self.config.recovery_timeout
+        };
+        let elapsed = Duration::from_nanos(self.now_nanos().saturating_sub(last_failure_nanos));
+        if elapsed < wait {
+            return true;
         }
+
+        // Only the thread that wins this CAS performs the Open->HalfOpen
+        // move; everyone else just observes it already applied.
+        if self
+            .state
+            .compare_exchange(STATE_OPEN, STATE_HALF_OPEN, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.success_count.store(0, Ordering::Release);
+            debug!("Circuit breaker '{}' transitioned to HALF-OPEN", self.name);
+        }
+        false
     }
 
     fn is_half_open(&self) -> bool {
-        let state = self.state.lock().unwrap();
-        matches!(*state, CircuitState::HalfOpen)
+        self.state.load(Ordering::Acquire) == STATE_HALF_OPEN
     }
 
     fn on_success(&self) {
-        let mut state = self.state.lock().unwrap();
-        let mut success_count = self.success_count.lock().unwrap();
-        let mut failure_count = self.failure_count.lock().unwrap();
-
-        match *state {
-            CircuitState::HalfOpen => {
-                *success_count += 1;
-                if *success_count >= self.config.success_threshold {
-                    *state = CircuitState::Closed;
-                    *success_count = 0;
-                    *failure_count = 0;
+        match self.state.load(Ordering::Acquire) {
+            STATE_HALF_OPEN => {
+                let success_count = self.success_count.fetch_add(1, Ordering::AcqRel) + 1;
+                if success_count >= self.config.success_threshold
+                    && self
+                        .state
+                        .compare_exchange(STATE_HALF_OPEN, STATE_CLOSED, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                {
+                    self.success_count.store(0, Ordering::Release);
+                    self.failure_count.store(0, Ordering::Release);
+                    self.consecutive_open_trips.store(0, Ordering::Release);
+                    self.current_backoff_nanos.store(0, Ordering::Release);
                     debug!("Circuit breaker '{}' transitioned to CLOSED", self.name);
                 }
             },
-            CircuitState::Closed => {
-                // Reset failure count on success
-                *failure_count = 0;
+            STATE_CLOSED => {
+                self.failure_count.store(0, Ordering::Release);
             },
             _ => {}
         }
     }
 
     fn on_failure(&self) {
-        let mut state = self.state.lock().unwrap();
-        let mut failure_count = self.failure_count.lock().unwrap();
-        let mut last_failure = self.last_failure_time.lock().unwrap();
-
-        *failure_count += 1;
-        *last_failure = Some(Instant::now());
-
-        match *state {
-            CircuitState::Closed => {
-                if *failure_count >= self.config.failure_threshold {
-                    *state = CircuitState::Open;
-                    warn!("Circuit breaker '{}' transitioned to OPEN after {} failures", 
-                          self.name, failure_count);
+        let failure_count = self.failure_count.fetch_add(1, Ordering::AcqRel) + 1;
+        // `max(1)` keeps the stored value out of the "no failure yet" `0`
+        // sentinel even for a failure recorded in the first nanosecond of
+        // this breaker's life.
+        self.last_failure_nanos.store(self.now_nanos().max(1), Ordering::Release);
+
+        match self.state.load(Ordering::Acquire) {
+            STATE_CLOSED => {
+                if failure_count >= self.config.failure_threshold
+                    && self
+                        .state
+                        .compare_exchange(STATE_CLOSED, STATE_OPEN, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                {
+                    let wait = self.trip_open();
+                    warn!("Circuit breaker '{}' transitioned to OPEN after {} failures, next probe in {:?}",
+                          self.name, failure_count, wait);
                 }
             },
-            CircuitState::HalfOpen => {
-                *state = CircuitState::Open;
-                warn!("Circuit breaker '{}' transitioned back to OPEN from HALF-OPEN", self.name);
+            STATE_HALF_OPEN => {
+                if self
+                    .state
+                    .compare_exchange(STATE_HALF_OPEN, STATE_OPEN, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+                {
+                    let wait = self.trip_open();
+                    warn!("Circuit breaker '{}' transitioned back to OPEN from HALF-OPEN, next probe in {:?}", self.name, wait);
+                }
             },
             _ => {}
         }
     }
 
-    fn transition_to_half_open(&self) {
-        let mut state = self.state.lock().unwrap();
-        let mut success_count = self.success_count.lock().unwrap();
-
-        *state = CircuitState::HalfOpen;
-        *success_count = 0;
-        debug!("Circuit breaker '{}' transitioned to HALF-OPEN", self.name);
+    /// Records a new consecutive Open trip and computes this trip's
+    /// jittered backoff via `BackoffPolicy::effective_wait`, storing it for
+    /// `is_open` to compare against. Returns the computed wait for logging.
+    /// Only called by the thread that just won the CAS into `STATE_OPEN`, so
+    /// no further synchronization is needed here.
+    fn trip_open(&self) -> Duration {
+        let trips = self.consecutive_open_trips.fetch_add(1, Ordering::AcqRel) + 1;
+        let wait = self.config.backoff.effective_wait(self.config.recovery_timeout, trips);
+        self.current_backoff_nanos.store(wait.as_nanos().min(u64::MAX as u128) as u64, Ordering::Release);
+        wait
     }
 }
 
@@ -226,6 +337,9 @@ pub struct CircuitBreakerStats {
     pub success_count: u32,
     pub last_failure_time: Option<Instant>,
     pub config: CircuitBreakerConfig,
+    /// The backoff computed for the most recent Open transition (see
+    /// `BackoffPolicy`); `None` if this breaker has never tripped.
+    pub current_backoff: Option<Duration>,
 }
 
 /// Circuit breaker registry for managing multiple breakers
@@ -263,6 +377,27 @@ impl CircuitBreakerRegistry {
             breaker.reset();
         }
     }
+
+    /// Renders `kg_circuit_breaker_state{name}` in Prometheus text exposition
+    /// format, one gauge sample per registered breaker: `0` Closed, `1` Open,
+    /// `2` HalfOpen - so an operator can graph trips without parsing logs.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP kg_circuit_breaker_state Circuit breaker state (0=closed, 1=open, 2=half_open).\n");
+        out.push_str("# TYPE kg_circuit_breaker_state gauge\n");
+        for stats in self.get_all_stats() {
+            let value = match stats.state {
+                CircuitState::Closed => 0,
+                CircuitState::Open => 1,
+                CircuitState::HalfOpen => 2,
+            };
+            out.push_str(&format!(
+                "kg_circuit_breaker_state{{name=\"{}\"}} {}\n",
+                stats.name, value
+            ));
+        }
+        out
+    }
 }
 
 impl Default for CircuitBreakerRegistry {
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::debug;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
@@ -8,6 +9,35 @@ use chrono::{DateTime, Utc};
 use crate::graph::{KGNode, KGEdge, Episode};
 use crate::embeddings::LocalEmbeddingEngine;
 
+/// Source of "now" for every temporal check in this module. Exists so
+/// `validate_content`'s fact-window/TTL/temporal-consistency logic can be
+/// driven by a `FixedClock` in tests and by `validate_content_as_of` for
+/// auditing how a claim would have validated at a past moment, instead
+/// of every call silently reaching for `Utc::now()`.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Production `Clock` backed by the real wall clock.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Test `Clock` that always returns the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
 /// Hallucination detection configuration
 #[derive(Debug, Clone)]
 pub struct HallucinationDetectorConfig {
@@ -18,6 +48,33 @@ pub struct HallucinationDetectorConfig {
     pub source_credibility_weight: f32,
     pub contradiction_detection: bool,
     pub uncertainty_quantification: bool,
+    /// How long past a fact's `not_after` its `OutdatedInformation` impact
+    /// score ramps linearly from 0.0 to 1.0; fully past this window it's
+    /// treated as maximally unreliable rather than growing worse forever.
+    pub outdated_grace_period: Duration,
+    /// Once a fact's `last_verified` is older than this, `verify_facts`
+    /// downgrades its confidence toward 0.5 and flags it for
+    /// re-verification instead of trusting it as eternally current.
+    /// `None` disables TTL-based downgrading.
+    pub fact_ttl: Option<Duration>,
+    /// Per-hop multiplier applied on top of the trust-level weight when
+    /// propagating credibility through the web-of-trust graph; keeps
+    /// distant sources from inheriting a root's full trust just because
+    /// a long chain of `High` edges connects them.
+    pub trust_decay_per_hop: f32,
+    /// Credibility returned for a source that the trust graph can't
+    /// reach from any configured root (including when no source id was
+    /// given at all).
+    pub unreachable_source_credibility: f32,
+    /// Numerator/denominator of the quorum fraction that the combined
+    /// credibility of agreeing cross-reference sources must exceed,
+    /// relative to the total credibility of all consulted sources
+    /// (default 1/3, as in light-client quorum checks).
+    pub trust_threshold: (u32, u32),
+    /// Cosine similarity at or below which a candidate that shares
+    /// overlapping claims with the content is treated as contradicting
+    /// it rather than merely irrelevant.
+    pub cross_reference_contradiction_bound: f32,
 }
 
 impl Default for HallucinationDetectorConfig {
@@ -30,6 +87,12 @@ impl Default for HallucinationDetectorConfig {
             source_credibility_weight: 0.3,
             contradiction_detection: true,
             uncertainty_quantification: true,
+            outdated_grace_period: Duration::from_secs(7 * 24 * 3600), // 1 week
+            fact_ttl: None,
+            trust_decay_per_hop: 0.85,
+            unreachable_source_credibility: 0.5,
+            trust_threshold: (1, 3),
+            cross_reference_contradiction_bound: 0.15,
         }
     }
 }
@@ -46,6 +109,108 @@ pub struct ValidationResult {
     pub recommendations: Vec<String>,
 }
 
+/// A node in the derivation tree returned by `validate_content_traced`.
+/// The root node is the overall accept/reject decision; its children are
+/// the six validation stages, each recording the multiplier it
+/// contributed to the running confidence; each stage's own children are
+/// the individual evidence/contradiction/uncertainty items it produced.
+/// Serializable so downstream tooling can render a "why was this
+/// accepted/rejected" explanation without re-deriving it from the flat
+/// `ValidationResult` vectors.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProofNode {
+    pub label: String,
+    /// This node's multiplier against the running confidence score
+    /// (1.0 for leaf evidence/contradiction/uncertainty nodes, which
+    /// only report their own local score via `confidence_after`).
+    pub multiplier: f32,
+    /// The running confidence score after this node was applied (for
+    /// leaves, the item's own local confidence/residual score).
+    pub confidence_after: f32,
+    /// Set on the root, or on the stage that introduced a `Critical`
+    /// contradiction: this is what vetoed validity outright regardless
+    /// of `confidence_after`.
+    pub vetoed: bool,
+    /// Set on the root if the final confidence fell below
+    /// `confidence_threshold`.
+    pub below_threshold: bool,
+    pub detail: String,
+    pub children: Vec<ProofNode>,
+}
+
+impl ProofNode {
+    /// Builds a stage node from the `ValidationResult` that stage
+    /// produced, flattening its evidence/contradictions/uncertainty
+    /// factors into child leaves.
+    fn stage(label: &str, multiplier: f32, confidence_after: f32, stage_result: &ValidationResult) -> Self {
+        let mut children = Vec::new();
+
+        for evidence in &stage_result.evidence {
+            children.push(ProofNode {
+                label: "evidence".to_string(),
+                multiplier: 1.0,
+                confidence_after: evidence.confidence,
+                vetoed: false,
+                below_threshold: false,
+                detail: format!("{:?} @ {}: {}", evidence.source_type, evidence.timestamp, evidence.content),
+                children: Vec::new(),
+            });
+        }
+
+        for contradiction in &stage_result.contradictions {
+            let vetoed = matches!(contradiction.severity, ContradictionSeverity::Critical);
+            children.push(ProofNode {
+                label: "contradiction".to_string(),
+                multiplier: 1.0,
+                confidence_after: severity_score(&contradiction.severity),
+                vetoed,
+                below_threshold: false,
+                detail: format!(
+                    "{:?} ({:?}): \"{}\" vs \"{}\"",
+                    contradiction.contradiction_type, contradiction.severity,
+                    contradiction.statement1, contradiction.statement2
+                ),
+                children: Vec::new(),
+            });
+        }
+
+        for factor in &stage_result.uncertainty_factors {
+            children.push(ProofNode {
+                label: "uncertainty".to_string(),
+                multiplier: 1.0,
+                confidence_after: 1.0 - factor.impact_score,
+                vetoed: false,
+                below_threshold: false,
+                detail: format!("{:?}: {}", factor.factor_type, factor.description),
+                children: Vec::new(),
+            });
+        }
+
+        let vetoed = children.iter().any(|c| c.vetoed);
+        ProofNode {
+            label: label.to_string(),
+            multiplier,
+            confidence_after,
+            vetoed,
+            below_threshold: false,
+            detail: format!("stage multiplier {:.3}, running confidence {:.3}", multiplier, confidence_after),
+            children,
+        }
+    }
+}
+
+/// Rough numeric severity used only to give contradiction leaves a
+/// sortable/comparable `confidence_after` in the proof tree; not used
+/// anywhere in the actual confidence-scoring pipeline.
+fn severity_score(severity: &ContradictionSeverity) -> f32 {
+    match severity {
+        ContradictionSeverity::Low => 0.75,
+        ContradictionSeverity::Medium => 0.5,
+        ContradictionSeverity::High => 0.25,
+        ContradictionSeverity::Critical => 0.0,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ValidationType {
     FactualAccuracy,
@@ -75,6 +240,149 @@ pub enum SourceType {
     Documentation,
 }
 
+/// A discrete trust assertion one source can issue toward another.
+/// Ordered loosely most-to-least trusting; `Distrust` is not "less than
+/// `None`" in the propagation math, it's a hard veto (see
+/// `TrustGraph::effective_credibility`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustLevel {
+    High,
+    Medium,
+    Low,
+    None,
+    Distrust,
+}
+
+impl TrustLevel {
+    /// Multiplier applied at the hop this level's edge covers.
+    fn weight(self) -> f32 {
+        match self {
+            TrustLevel::High => 0.9,
+            TrustLevel::Medium => 0.6,
+            TrustLevel::Low => 0.3,
+            TrustLevel::None => 0.0,
+            TrustLevel::Distrust => 0.0,
+        }
+    }
+}
+
+/// A value that only ever moves forward in time: later `set` calls are
+/// ignored if they carry an older (or equal) `updated_at` than what's
+/// already stored, so a stale trust assertion can't clobber a newer one
+/// arriving out of order.
+#[derive(Debug, Clone)]
+pub struct Timestamped<T> {
+    pub value: T,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<T> Timestamped<T> {
+    fn new(value: T, updated_at: DateTime<Utc>) -> Self {
+        Self { value, updated_at }
+    }
+
+    /// Overwrites `value` only if `updated_at` is strictly newer than the
+    /// timestamp already stored.
+    fn update(&mut self, value: T, updated_at: DateTime<Utc>) {
+        if updated_at > self.updated_at {
+            self.value = value;
+            self.updated_at = updated_at;
+        }
+    }
+}
+
+/// Signed graph of source-to-source trust assertions. Credibility for a
+/// given source is derived transitively by propagating outward from a
+/// configured set of anchor/root sources rather than trusting a single
+/// flat score per source.
+#[derive(Debug, Default)]
+struct TrustGraph {
+    /// Keeps only the most recent edge per (from, to) pair.
+    edges: HashMap<(String, String), Timestamped<TrustLevel>>,
+    /// `from -> [to, ...]` adjacency derived from `edges`, kept in sync
+    /// on every `add_edge` so traversal doesn't have to scan all edges.
+    outgoing: HashMap<String, Vec<String>>,
+    roots: Vec<String>,
+}
+
+impl TrustGraph {
+    fn add_edge(&mut self, from: String, to: String, level: TrustLevel, at: DateTime<Utc>) {
+        let key = (from.clone(), to.clone());
+        match self.edges.get_mut(&key) {
+            Some(existing) => existing.update(level, at),
+            None => {
+                self.edges.insert(key, Timestamped::new(level, at));
+                self.outgoing.entry(from).or_default().push(to);
+            }
+        }
+    }
+
+    fn set_roots(&mut self, roots: Vec<String>) {
+        self.roots = roots;
+    }
+
+    /// Best (maximum) credibility score reachable from any root, found
+    /// via a Dijkstra-style best-first search that maximizes the product
+    /// of per-hop trust weights times `decay_per_hop^distance`, rather
+    /// than a plain BFS shortest-path, since more hops through strong
+    /// trust can still beat fewer hops through weak trust. A path that
+    /// crosses a `Distrust` edge is clamped to zero and explored no
+    /// further, so distrust can't be "routed around" by a longer path
+    /// through the same node via another edge — other paths that avoid
+    /// the distrusted edge entirely are unaffected.
+    fn effective_credibility(&self, source_id: &str, decay_per_hop: f32) -> Option<f32> {
+        if self.roots.iter().any(|r| r == source_id) {
+            return Some(1.0);
+        }
+
+        struct HeapEntry(String, f32);
+        impl PartialEq for HeapEntry {
+            fn eq(&self, other: &Self) -> bool {
+                self.1 == other.1
+            }
+        }
+        impl Eq for HeapEntry {}
+        impl PartialOrd for HeapEntry {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for HeapEntry {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.1.partial_cmp(&other.1).unwrap_or(std::cmp::Ordering::Equal)
+            }
+        }
+
+        let mut best: HashMap<&str, f32> = HashMap::new();
+        let mut heap = std::collections::BinaryHeap::new();
+        for root in &self.roots {
+            best.insert(root.as_str(), 1.0);
+            heap.push(HeapEntry(root.clone(), 1.0));
+        }
+
+        while let Some(HeapEntry(node, score)) = heap.pop() {
+            if score < *best.get(node.as_str()).unwrap_or(&0.0) {
+                continue; // stale entry, a better score for `node` was already processed
+            }
+            let Some(neighbors) = self.outgoing.get(&node) else { continue };
+            for next in neighbors {
+                let Some(edge) = self.edges.get(&(node.clone(), next.clone())) else { continue };
+                let next_score = if edge.value == TrustLevel::Distrust {
+                    0.0
+                } else {
+                    score * edge.value.weight() * decay_per_hop
+                };
+                if next_score > *best.get(next.as_str()).unwrap_or(&0.0) {
+                    best.insert(next.as_str(), next_score);
+                    heap.push(HeapEntry(next.clone(), next_score));
+                }
+            }
+        }
+
+        best.get(source_id).copied()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Contradiction {
     pub statement1: String,
@@ -121,18 +429,105 @@ pub enum UncertaintyType {
 pub struct HallucinationDetector {
     config: HallucinationDetectorConfig,
     embedding_engine: Option<Arc<LocalEmbeddingEngine>>,
-    fact_database: Arc<std::sync::RwLock<HashMap<String, FactEntry>>>,
-    source_credibility: Arc<std::sync::RwLock<HashMap<String, f32>>>,
+    fact_database: Arc<std::sync::RwLock<HashMap<String, FactTimeline>>>,
+    trust_graph: Arc<std::sync::RwLock<TrustGraph>>,
     contradiction_patterns: Vec<ContradictionPattern>,
+    clock: Arc<dyn Clock>,
 }
 
+/// One revision of a fact's accepted confidence/evidence, valid for the
+/// `[valid_from, valid_to)` slice of the fact's own revision timeline.
+/// This is a distinct time axis from `not_before`/`not_after`: those
+/// describe when the underlying real-world claim holds true, while
+/// `valid_from`/`valid_to` describe when *this recorded revision* was
+/// the system's accepted answer — the same real-world validity window
+/// can be re-verified multiple times, each producing a new revision.
 #[derive(Debug, Clone)]
-struct FactEntry {
-    fact: String,
-    confidence: f32,
-    sources: Vec<Evidence>,
-    last_verified: DateTime<Utc>,
-    verification_count: u32,
+pub struct FactEntry {
+    pub fact: String,
+    pub confidence: f32,
+    pub sources: Vec<Evidence>,
+    pub last_verified: DateTime<Utc>,
+    pub verification_count: u32,
+    /// Start of this fact's validity window; `None` means it's always been
+    /// true. A reference time before this counts as insufficient evidence
+    /// rather than support, since the fact didn't hold yet.
+    pub not_before: Option<DateTime<Utc>>,
+    /// End of this fact's validity window; `None` means it never expires.
+    /// A reference time after this produces an `OutdatedInformation`
+    /// uncertainty factor instead of supporting evidence.
+    pub not_after: Option<DateTime<Utc>>,
+    /// Start of this revision's slice of the fact's timeline.
+    pub valid_from: DateTime<Utc>,
+    /// End of this revision's slice; `None` means it's the current
+    /// (most recent) revision.
+    pub valid_to: Option<DateTime<Utc>>,
+}
+
+impl FactEntry {
+    fn contains(&self, ts: DateTime<Utc>) -> bool {
+        self.valid_from <= ts && self.valid_to.is_none_or(|valid_to| ts < valid_to)
+    }
+}
+
+/// Non-overlapping, `valid_from`-ordered history of revisions for a
+/// single fact key — the "time travel" log that lets `fact_as_of` answer
+/// what a fact's accepted confidence was at an arbitrary instant.
+#[derive(Debug, Clone, Default)]
+struct FactTimeline {
+    revisions: Vec<FactEntry>,
+}
+
+impl FactTimeline {
+    /// Appends `revision` as the new current revision, closing the
+    /// previous current revision's `valid_to` at `revision.valid_from`.
+    fn push(&mut self, mut revision: FactEntry) {
+        if let Some(last) = self.revisions.last_mut() {
+            last.valid_to = Some(revision.valid_from);
+        }
+        revision.valid_to = None;
+        self.revisions.push(revision);
+        debug_assert!(self.is_sorted_and_non_overlapping());
+    }
+
+    /// The revision whose `[valid_from, valid_to)` interval contains `ts`.
+    fn as_of(&self, ts: DateTime<Utc>) -> Option<&FactEntry> {
+        self.revisions.iter().find(|revision| revision.contains(ts))
+    }
+
+    /// Invariant check: revisions are sorted by `valid_from` and each
+    /// one's `valid_to` equals the next one's `valid_from` (or `None`
+    /// for the last), so the timeline never has gaps or overlaps.
+    fn is_sorted_and_non_overlapping(&self) -> bool {
+        self.revisions.windows(2).all(|pair| {
+            pair[0].valid_from < pair[1].valid_from && pair[0].valid_to == Some(pair[1].valid_from)
+        })
+    }
+
+    /// Merges consecutive revisions that carry identical confidence and
+    /// validity window, collapsing a run of no-op re-verifications into
+    /// a single revision spanning their combined interval.
+    fn compact(&mut self) {
+        let mut compacted: Vec<FactEntry> = Vec::with_capacity(self.revisions.len());
+        for revision in self.revisions.drain(..) {
+            let merge_into_last = compacted.last().is_some_and(|last: &FactEntry| {
+                last.confidence == revision.confidence
+                    && last.not_before == revision.not_before
+                    && last.not_after == revision.not_after
+            });
+            if merge_into_last {
+                let last = compacted.last_mut().expect("checked above");
+                last.valid_to = revision.valid_to;
+                last.verification_count += revision.verification_count;
+                last.last_verified = revision.last_verified;
+                last.sources.extend(revision.sources);
+            } else {
+                compacted.push(revision);
+            }
+        }
+        self.revisions = compacted;
+        debug_assert!(self.is_sorted_and_non_overlapping());
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -142,21 +537,124 @@ struct ContradictionPattern {
     severity: ContradictionSeverity,
 }
 
+/// A cross-reference voting candidate flattened out of
+/// `ValidationContext`'s related episodes/nodes/edges.
+struct CrossRefCandidate {
+    uuid: Uuid,
+    text: String,
+    timestamp: DateTime<Utc>,
+    source_type: SourceType,
+    trust_source_id: String,
+    /// Reuses `Episode::embedding` when already cached; `None` for nodes
+    /// and edges, which don't carry one, so the caller re-encodes.
+    embedding: Option<Vec<f32>>,
+}
+
+/// Whether `a` and `b` look like they're talking about the same thing
+/// even though their embeddings diverge — a cheap lexical-overlap proxy
+/// used to tell "irrelevant" (low similarity, no shared claims) apart
+/// from "contradicts" (low similarity, same claims) before flagging a
+/// cross-reference contradiction.
+fn claims_overlap(a: &str, b: &str) -> bool {
+    let tokens = |s: &str| -> std::collections::HashSet<String> {
+        s.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 3)
+            .map(|w| w.to_string())
+            .collect()
+    };
+    let a_tokens = tokens(a);
+    let b_tokens = tokens(b);
+    if a_tokens.is_empty() || b_tokens.is_empty() {
+        return false;
+    }
+
+    let intersection = a_tokens.intersection(&b_tokens).count();
+    let union = a_tokens.union(&b_tokens).count();
+    (intersection as f32 / union.max(1) as f32) >= 0.2
+}
+
 impl HallucinationDetector {
     pub fn new(config: HallucinationDetectorConfig, embedding_engine: Option<Arc<LocalEmbeddingEngine>>) -> Self {
+        Self::with_clock(config, embedding_engine, Arc::new(SystemClock))
+    }
+
+    /// Like `new`, but with an injectable `Clock` — used by tests that
+    /// need deterministic timestamps instead of the real wall clock.
+    pub fn with_clock(
+        config: HallucinationDetectorConfig,
+        embedding_engine: Option<Arc<LocalEmbeddingEngine>>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         let contradiction_patterns = Self::initialize_contradiction_patterns();
-        
+
         Self {
             config,
             embedding_engine,
             fact_database: Arc::new(std::sync::RwLock::new(HashMap::new())),
-            source_credibility: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            trust_graph: Arc::new(std::sync::RwLock::new(TrustGraph::default())),
             contradiction_patterns,
+            clock,
         }
     }
 
-    /// Validate content for hallucinations and inconsistencies
-    pub async fn validate_content(&self, content: &str, _context: &ValidationContext) -> Result<ValidationResult> {
+    /// Validate content for hallucinations and inconsistencies, treating
+    /// `context.timestamp` as the point in time to validate as of.
+    pub async fn validate_content(&self, content: &str, context: &ValidationContext) -> Result<ValidationResult> {
+        self.validate_content_as_of(content, context, context.timestamp).await
+    }
+
+    /// Like `validate_content`, but evaluates fact validity windows, TTL
+    /// expiry, and temporal consistency against `as_of` instead of
+    /// `context.timestamp`. This is what makes the temporal path
+    /// replayable: pass a past instant to audit why content would (or
+    /// wouldn't) have validated at that moment, independent of whatever
+    /// timestamp `context` itself carries.
+    pub async fn validate_content_as_of(
+        &self,
+        content: &str,
+        context: &ValidationContext,
+        as_of: DateTime<Utc>,
+    ) -> Result<ValidationResult> {
+        let (result, _proof) = self.validate_core(content, context, as_of).await?;
+        Ok(result)
+    }
+
+    /// Like `validate_content`, but also returns the `ProofNode`
+    /// derivation tree explaining how each of the six stages moved the
+    /// running `confidence_score` — see `validate_content_traced_as_of`.
+    pub async fn validate_content_traced(
+        &self,
+        content: &str,
+        context: &ValidationContext,
+    ) -> Result<(ValidationResult, ProofNode)> {
+        self.validate_content_traced_as_of(content, context, context.timestamp).await
+    }
+
+    /// Like `validate_content_as_of`, but also returns a `ProofNode`
+    /// tree: the root is the overall accept/reject decision, its
+    /// children are the six validation stages each recording the
+    /// multiplier they contributed to the running confidence, and each
+    /// stage's children are the individual evidence/contradiction/
+    /// uncertainty items it produced. Turns the chain of
+    /// `confidence_score *= x` multiplications into something an
+    /// auditor can walk to see exactly which stage (or which critical
+    /// contradiction) decided the outcome.
+    pub async fn validate_content_traced_as_of(
+        &self,
+        content: &str,
+        context: &ValidationContext,
+        as_of: DateTime<Utc>,
+    ) -> Result<(ValidationResult, ProofNode)> {
+        self.validate_core(content, context, as_of).await
+    }
+
+    async fn validate_core(
+        &self,
+        content: &str,
+        context: &ValidationContext,
+        as_of: DateTime<Utc>,
+    ) -> Result<(ValidationResult, ProofNode)> {
         let mut validation_result = ValidationResult {
             is_valid: true,
             confidence_score: 1.0,
@@ -166,67 +664,137 @@ impl HallucinationDetector {
             uncertainty_factors: Vec::new(),
             recommendations: Vec::new(),
         };
+        let mut stages = Vec::new();
 
         // 1. Fact verification
         if self.config.fact_verification_enabled {
-            let fact_validation = self.verify_facts(content).await?;
-            validation_result.evidence.extend(fact_validation.evidence);
-            validation_result.confidence_score *= fact_validation.confidence_score;
+            let fact_validation = self.verify_facts(content, as_of).await?;
+            let multiplier = fact_validation.confidence_score;
+            validation_result.evidence.extend(fact_validation.evidence.clone());
+            validation_result.contradictions.extend(fact_validation.contradictions.clone());
+            validation_result.confidence_score *= multiplier;
+            stages.push(ProofNode::stage("fact_verification", multiplier, validation_result.confidence_score, &fact_validation));
         }
 
         // 2. Cross-reference validation
-        let cross_ref_validation = self.cross_reference_validation(content).await?;
-        validation_result.evidence.extend(cross_ref_validation.evidence);
-        validation_result.confidence_score *= cross_ref_validation.confidence_score;
+        let cross_ref_validation = self.cross_reference_validation(content, context).await?;
+        let multiplier = cross_ref_validation.confidence_score;
+        validation_result.evidence.extend(cross_ref_validation.evidence.clone());
+        validation_result.uncertainty_factors.extend(cross_ref_validation.uncertainty_factors.clone());
+        validation_result.contradictions.extend(cross_ref_validation.contradictions.clone());
+        validation_result.confidence_score *= multiplier;
+        stages.push(ProofNode::stage("cross_reference", multiplier, validation_result.confidence_score, &cross_ref_validation));
 
         // 3. Temporal consistency check
         if self.config.temporal_consistency_check {
-            let temporal_validation = self.check_temporal_consistency(content).await?;
-            let temporal_contradictions = temporal_validation.contradictions;
-            if !temporal_contradictions.is_empty() {
-                validation_result.contradictions.extend(temporal_contradictions);
-                validation_result.confidence_score *= 0.8;
-            }
+            let temporal_validation = self.check_temporal_consistency(content, as_of).await?;
+            validation_result.uncertainty_factors.extend(temporal_validation.uncertainty_factors.clone());
+            let multiplier = if !temporal_validation.contradictions.is_empty() { 0.8 } else { 1.0 };
+            validation_result.contradictions.extend(temporal_validation.contradictions.clone());
+            validation_result.confidence_score *= multiplier;
+            stages.push(ProofNode::stage("temporal_consistency", multiplier, validation_result.confidence_score, &temporal_validation));
         }
 
         // 4. Contradiction detection
         if self.config.contradiction_detection {
             let contradictions = self.detect_contradictions(content).await?;
-            validation_result.contradictions.extend(contradictions);
-            if !validation_result.contradictions.is_empty() {
-                validation_result.confidence_score *= 0.7;
-            }
+            validation_result.contradictions.extend(contradictions.clone());
+            let multiplier = if !validation_result.contradictions.is_empty() { 0.7 } else { 1.0 };
+            validation_result.confidence_score *= multiplier;
+            let stage_result = ValidationResult {
+                is_valid: true,
+                confidence_score: multiplier,
+                validation_type: ValidationType::LogicalConsistency,
+                evidence: Vec::new(),
+                contradictions,
+                uncertainty_factors: Vec::new(),
+                recommendations: Vec::new(),
+            };
+            stages.push(ProofNode::stage("contradiction_detection", multiplier, validation_result.confidence_score, &stage_result));
         }
 
         // 5. Uncertainty quantification
         if self.config.uncertainty_quantification {
-            validation_result.uncertainty_factors = self.quantify_uncertainty(content).await?;
+            let new_factors = self.quantify_uncertainty(content).await?;
+            validation_result.uncertainty_factors.extend(new_factors.clone());
             let uncertainty_penalty = validation_result.uncertainty_factors.iter()
                 .map(|f| f.impact_score)
                 .sum::<f32>() / validation_result.uncertainty_factors.len().max(1) as f32;
-            validation_result.confidence_score *= 1.0 - uncertainty_penalty * 0.3;
+            let multiplier = 1.0 - uncertainty_penalty * 0.3;
+            validation_result.confidence_score *= multiplier;
+            let stage_result = ValidationResult {
+                is_valid: true,
+                confidence_score: multiplier,
+                validation_type: ValidationType::SemanticCoherence,
+                evidence: Vec::new(),
+                contradictions: Vec::new(),
+                uncertainty_factors: new_factors,
+                recommendations: Vec::new(),
+            };
+            stages.push(ProofNode::stage("uncertainty_quantification", multiplier, validation_result.confidence_score, &stage_result));
         }
 
         // 6. Source credibility assessment
-        let credibility_score = self.assess_source_credibility().await?;
-        validation_result.confidence_score = validation_result.confidence_score * (1.0 - self.config.source_credibility_weight) + 
+        let credibility_score = self.assess_source_credibility(context.source_id.as_deref()).await?;
+        let before_credibility = validation_result.confidence_score;
+        validation_result.confidence_score = validation_result.confidence_score * (1.0 - self.config.source_credibility_weight) +
                                            credibility_score * self.config.source_credibility_weight;
+        // Not a pure multiplier (it's a weighted blend with `credibility_score`),
+        // but the ratio is the closest apples-to-apples "multiplier" for the tree.
+        let effective_multiplier = if before_credibility.abs() > f32::EPSILON {
+            validation_result.confidence_score / before_credibility
+        } else {
+            1.0
+        };
+        stages.push(ProofNode {
+            label: "source_credibility".to_string(),
+            multiplier: effective_multiplier,
+            confidence_after: validation_result.confidence_score,
+            vetoed: false,
+            below_threshold: false,
+            detail: format!(
+                "blended credibility {:.3} into confidence with weight {:.3}",
+                credibility_score, self.config.source_credibility_weight
+            ),
+            children: Vec::new(),
+        });
 
         // Final validation decision
-        validation_result.is_valid = validation_result.confidence_score >= self.config.confidence_threshold &&
-                                   !validation_result.contradictions.iter().any(|c| matches!(c.severity, ContradictionSeverity::Critical));
+        let critical_contradiction = validation_result.contradictions.iter()
+            .any(|c| matches!(c.severity, ContradictionSeverity::Critical));
+        let below_threshold = validation_result.confidence_score < self.config.confidence_threshold;
+        validation_result.is_valid = !below_threshold && !critical_contradiction;
 
         // Generate recommendations
         validation_result.recommendations = self.generate_recommendations(&validation_result);
 
-        debug!("Content validation completed: valid={}, confidence={:.3}", 
+        debug!("Content validation completed: valid={}, confidence={:.3}",
                validation_result.is_valid, validation_result.confidence_score);
 
-        Ok(validation_result)
+        let root = ProofNode {
+            label: if validation_result.is_valid { "accepted".to_string() } else { "rejected".to_string() },
+            multiplier: 1.0,
+            confidence_after: validation_result.confidence_score,
+            vetoed: critical_contradiction,
+            below_threshold,
+            detail: if critical_contradiction {
+                "rejected: a critical contradiction vetoed validity".to_string()
+            } else if below_threshold {
+                format!("rejected: confidence {:.3} below threshold {:.3}", validation_result.confidence_score, self.config.confidence_threshold)
+            } else {
+                format!("accepted: confidence {:.3} meets threshold {:.3}", validation_result.confidence_score, self.config.confidence_threshold)
+            },
+            children: stages,
+        };
+
+        Ok((validation_result, root))
     }
 
-    /// Verify facts against known database
-    async fn verify_facts(&self, content: &str) -> Result<ValidationResult> {
+    /// Verify facts against known database. `reference_time` (typically
+    /// `ValidationContext::timestamp`) is compared against each matching
+    /// fact's validity window and TTL rather than always trusting
+    /// `fact_entry.confidence` as-is.
+    async fn verify_facts(&self, content: &str, reference_time: DateTime<Utc>) -> Result<ValidationResult> {
         let mut result = ValidationResult {
             is_valid: true,
             confidence_score: 1.0,
@@ -242,12 +810,45 @@ impl HallucinationDetector {
         let fact_db = self.fact_database.read().unwrap();
 
         for claim in claims {
-            if let Some(fact_entry) = fact_db.get(&claim) {
-                if fact_entry.confidence >= self.config.confidence_threshold {
+            let timeline = fact_db.get(&claim);
+            if let Some(fact_entry) = timeline.and_then(|timeline| timeline.as_of(reference_time)) {
+                // A stale fact is downgraded toward neutral confidence and
+                // flagged for re-verification before any window check, so an
+                // expired-but-still-in-window fact doesn't get treated as
+                // fully trustworthy just because `not_after` hasn't hit yet.
+                let mut effective_confidence = fact_entry.confidence;
+                if let Some(ttl) = self.config.fact_ttl {
+                    let age_secs = (reference_time - fact_entry.last_verified).num_seconds();
+                    if age_secs > ttl.as_secs() as i64 {
+                        effective_confidence = (effective_confidence + 0.5) / 2.0;
+                        result.uncertainty_factors.push(UncertaintyFactor {
+                            factor_type: UncertaintyType::OutdatedInformation,
+                            description: format!("Fact exceeded its TTL and needs re-verification: {}", claim),
+                            impact_score: 0.4,
+                        });
+                    }
+                }
+
+                if fact_entry.not_before.is_some_and(|not_before| reference_time < not_before) {
+                    result.uncertainty_factors.push(UncertaintyFactor {
+                        factor_type: UncertaintyType::InsufficientEvidence,
+                        description: format!("Fact is not yet valid as of the reference time: {}", claim),
+                        impact_score: 0.5,
+                    });
+                } else if let Some(not_after) = fact_entry.not_after.filter(|&not_after| reference_time > not_after) {
+                    let overdue_secs = (reference_time - not_after).num_seconds().max(0) as f64;
+                    let grace_secs = self.config.outdated_grace_period.as_secs_f64().max(f64::EPSILON);
+                    let impact_score = (overdue_secs / grace_secs).clamp(0.0, 1.0) as f32;
+                    result.uncertainty_factors.push(UncertaintyFactor {
+                        factor_type: UncertaintyType::OutdatedInformation,
+                        description: format!("Fact is past its validity window: {}", claim),
+                        impact_score,
+                    });
+                } else if effective_confidence >= self.config.confidence_threshold {
                     result.evidence.push(Evidence {
                         source_id: Uuid::new_v4(),
                         content: fact_entry.fact.clone(),
-                        confidence: fact_entry.confidence,
+                        confidence: effective_confidence,
                         source_type: SourceType::SystemGenerated,
                         timestamp: fact_entry.last_verified,
                     });
@@ -255,9 +856,31 @@ impl HallucinationDetector {
                     result.uncertainty_factors.push(UncertaintyFactor {
                         factor_type: UncertaintyType::LowSourceCredibility,
                         description: format!("Low confidence fact: {}", claim),
-                        impact_score: 1.0 - fact_entry.confidence,
+                        impact_score: 1.0 - effective_confidence,
                     });
                 }
+            } else if let Some(timeline) = timeline.filter(|timeline| !timeline.revisions.is_empty()) {
+                // The claim has a history, just not one covering this
+                // instant: it was (or will be) accepted at a different
+                // point in time, which is a temporal mismatch rather
+                // than a wholly unverified claim.
+                let other_revision = timeline
+                    .revisions
+                    .iter()
+                    .min_by_key(|revision| (revision.valid_from - reference_time).num_seconds().abs());
+                result.contradictions.push(Contradiction {
+                    statement1: claim.clone(),
+                    statement2: other_revision
+                        .map(|revision| format!(
+                            "accepted from {} to {}",
+                            revision.valid_from,
+                            revision.valid_to.map(|t| t.to_string()).unwrap_or_else(|| "now".to_string())
+                        ))
+                        .unwrap_or_default(),
+                    contradiction_type: ContradictionType::Temporal,
+                    severity: ContradictionSeverity::Medium,
+                    evidence: Vec::new(),
+                });
             } else {
                 result.uncertainty_factors.push(UncertaintyFactor {
                     factor_type: UncertaintyType::InsufficientEvidence,
@@ -279,8 +902,16 @@ impl HallucinationDetector {
         Ok(result)
     }
 
-    /// Cross-reference with existing knowledge
-    async fn cross_reference_validation(&self, content: &str) -> Result<ValidationResult> {
+    /// Cross-reference `content` against `context`'s related episodes,
+    /// nodes and edges using a light-client-style quorum check: each
+    /// candidate above `cross_reference_threshold` cosine similarity
+    /// "votes" that the content is corroborated, and the combined
+    /// effective credibility of the voting sources must exceed
+    /// `trust_threshold` of the total credibility consulted. Candidates
+    /// that share overlapping claims with the content but diverge from
+    /// it (similarity at or below `cross_reference_contradiction_bound`)
+    /// are flagged as contradictions instead of silently ignored.
+    async fn cross_reference_validation(&self, content: &str, context: &ValidationContext) -> Result<ValidationResult> {
         let mut result = ValidationResult {
             is_valid: true,
             confidence_score: 1.0,
@@ -291,25 +922,131 @@ impl HallucinationDetector {
             recommendations: Vec::new(),
         };
 
-        // Use semantic similarity to find related content
-        if let Some(ref engine) = self.embedding_engine {
-            let _content_embedding = engine.encode_text(content).await?;
-            
-            // In a real implementation, you would compare with existing episodes and nodes
-            // For now, we'll add a placeholder uncertainty factor
+        let Some(ref engine) = self.embedding_engine else {
+            return Ok(result);
+        };
+
+        let candidates = self.cross_reference_candidates(context);
+        if candidates.is_empty() {
             result.uncertainty_factors.push(UncertaintyFactor {
                 factor_type: UncertaintyType::InsufficientEvidence,
                 description: "No cross-references found".to_string(),
                 impact_score: 0.3,
             });
             result.confidence_score = 0.7;
+            return Ok(result);
+        }
+
+        let content_embedding = engine.encode_text(content).await?;
+
+        let mut total_credibility = 0.0f32;
+        let mut agreeing_credibility = 0.0f32;
+        for candidate in candidates {
+            let candidate_embedding = match candidate.embedding {
+                Some(embedding) => embedding,
+                None => engine.encode_text(&candidate.text).await?,
+            };
+            let similarity = crate::embeddings::cosine_similarity(&content_embedding, &candidate_embedding);
+            let credibility = self.assess_source_credibility(Some(&candidate.trust_source_id)).await?;
+            total_credibility += credibility;
+
+            if similarity >= self.config.cross_reference_threshold {
+                agreeing_credibility += credibility;
+                result.evidence.push(Evidence {
+                    source_id: candidate.uuid,
+                    content: candidate.text,
+                    confidence: similarity,
+                    source_type: candidate.source_type,
+                    timestamp: candidate.timestamp,
+                });
+            } else if similarity <= self.config.cross_reference_contradiction_bound
+                && claims_overlap(content, &candidate.text)
+            {
+                result.contradictions.push(Contradiction {
+                    statement1: content.to_string(),
+                    statement2: candidate.text,
+                    contradiction_type: ContradictionType::Semantic,
+                    severity: if credibility >= self.config.confidence_threshold {
+                        ContradictionSeverity::High
+                    } else {
+                        ContradictionSeverity::Medium
+                    },
+                    evidence: Vec::new(),
+                });
+            }
+        }
+
+        let (numerator, denominator) = self.config.trust_threshold;
+        let quorum_met = total_credibility > 0.0
+            && agreeing_credibility * denominator as f32 > total_credibility * numerator as f32;
+
+        if quorum_met {
+            result.confidence_score = (agreeing_credibility / total_credibility).clamp(0.0, 1.0);
+        } else {
+            result.uncertainty_factors.push(UncertaintyFactor {
+                factor_type: UncertaintyType::InsufficientEvidence,
+                description: format!(
+                    "Cross-reference quorum not met: {:.2} of {:.2} consulted credibility agrees (need > {}/{})",
+                    agreeing_credibility, total_credibility, numerator, denominator
+                ),
+                impact_score: 0.4,
+            });
+            result.confidence_score = 0.5;
         }
 
         Ok(result)
     }
 
-    /// Check temporal consistency
-    async fn check_temporal_consistency(&self, content: &str) -> Result<ValidationResult> {
+    /// Flatten `context`'s related episodes/nodes/edges into the
+    /// candidates `cross_reference_validation` votes over. The trust
+    /// source id is the item's `group_id` (falling back to `"unknown"`),
+    /// reusing the grouping that already scopes nodes/edges/episodes to
+    /// a tenant/project elsewhere in the graph.
+    fn cross_reference_candidates(&self, context: &ValidationContext) -> Vec<CrossRefCandidate> {
+        let mut candidates = Vec::new();
+
+        for episode in &context.related_episodes {
+            candidates.push(CrossRefCandidate {
+                uuid: episode.uuid,
+                text: episode.content.clone(),
+                timestamp: episode.created_at,
+                source_type: SourceType::UserGenerated,
+                trust_source_id: episode.group_id.clone().unwrap_or_else(|| "unknown".to_string()),
+                embedding: episode.embedding.clone(),
+            });
+        }
+
+        for node in &context.related_nodes {
+            candidates.push(CrossRefCandidate {
+                uuid: node.uuid,
+                text: node.summary.clone(),
+                timestamp: node.updated_at,
+                source_type: SourceType::SystemGenerated,
+                trust_source_id: node.group_id.clone().unwrap_or_else(|| "unknown".to_string()),
+                embedding: None,
+            });
+        }
+
+        for edge in &context.related_edges {
+            candidates.push(CrossRefCandidate {
+                uuid: edge.uuid,
+                text: edge.summary.clone(),
+                timestamp: edge.updated_at,
+                source_type: SourceType::SystemGenerated,
+                trust_source_id: edge.group_id.clone().unwrap_or_else(|| "unknown".to_string()),
+                embedding: None,
+            });
+        }
+
+        candidates
+    }
+
+    /// Check temporal consistency of `content` as of `as_of`: both the
+    /// existing pairwise contradiction check between extracted temporal
+    /// references, and a check that no explicit date reference lands
+    /// after `as_of` (a forward-dated claim can't yet be corroborated at
+    /// that point in time).
+    async fn check_temporal_consistency(&self, content: &str, as_of: DateTime<Utc>) -> Result<ValidationResult> {
         let mut result = ValidationResult {
             is_valid: true,
             confidence_score: 1.0,
@@ -322,7 +1059,7 @@ impl HallucinationDetector {
 
         // Extract temporal references
         let temporal_refs = self.extract_temporal_references(content);
-        
+
         // Check for temporal contradictions
         for (i, ref1) in temporal_refs.iter().enumerate() {
             for ref2 in temporal_refs.iter().skip(i + 1) {
@@ -338,6 +1075,19 @@ impl HallucinationDetector {
             }
         }
 
+        // Flag explicit dates that are still in the future as of `as_of`
+        for date_ref in &temporal_refs {
+            if let Ok(date) = chrono::NaiveDate::parse_from_str(date_ref, "%Y-%m-%d") {
+                if date > as_of.date_naive() {
+                    result.uncertainty_factors.push(UncertaintyFactor {
+                        factor_type: UncertaintyType::IncompleteContext,
+                        description: format!("Referenced date {} is after the as-of time {}", date_ref, as_of),
+                        impact_score: 0.3,
+                    });
+                }
+            }
+        }
+
         Ok(result)
     }
 
@@ -383,10 +1133,19 @@ impl HallucinationDetector {
         Ok(factors)
     }
 
-    /// Assess source credibility
-    async fn assess_source_credibility(&self) -> Result<f32> {
-        // Default credibility for system-generated content
-        Ok(0.7)
+    /// Assess the credibility of `source_id` by propagating trust through
+    /// the web-of-trust graph from the configured root sources. Falls
+    /// back to `unreachable_source_credibility` when no source id was
+    /// given, or when the graph has no path from any root to it.
+    async fn assess_source_credibility(&self, source_id: Option<&str>) -> Result<f32> {
+        let Some(source_id) = source_id else {
+            return Ok(self.config.unreachable_source_credibility);
+        };
+
+        let trust_graph = self.trust_graph.read().unwrap();
+        Ok(trust_graph
+            .effective_credibility(source_id, self.config.trust_decay_per_hop)
+            .unwrap_or(self.config.unreachable_source_credibility))
     }
 
     // Helper methods
@@ -471,33 +1230,75 @@ impl HallucinationDetector {
         ]
     }
 
-    /// Update fact database with new verified information
-    pub async fn update_fact_database(&self, fact: String, confidence: f32, evidence: Vec<Evidence>) -> Result<()> {
+    /// Update the fact database with new verified information. Rather
+    /// than averaging into the existing record in place, this appends a
+    /// new revision to the fact's timeline, valid from now onward, and
+    /// closes the previous current revision's `valid_to` at that
+    /// instant — preserving the old confidence/evidence as history
+    /// instead of discarding it. `not_before`/`not_after` set this
+    /// revision's real-world validity window (see `FactEntry`); pass
+    /// `None` for either bound that doesn't apply.
+    pub async fn update_fact_database(
+        &self,
+        fact: String,
+        confidence: f32,
+        evidence: Vec<Evidence>,
+        not_before: Option<DateTime<Utc>>,
+        not_after: Option<DateTime<Utc>>,
+    ) -> Result<()> {
         let mut fact_db = self.fact_database.write().unwrap();
-        
-        let entry = FactEntry {
-            fact: fact.clone(),
+        let now = self.clock.now();
+        let timeline = fact_db.entry(fact.clone()).or_default();
+
+        let verification_count = timeline.revisions.last().map_or(1, |last| last.verification_count + 1);
+        timeline.push(FactEntry {
+            fact,
             confidence,
             sources: evidence,
-            last_verified: Utc::now(),
-            verification_count: 1,
-        };
+            last_verified: now,
+            verification_count,
+            not_before,
+            not_after,
+            valid_from: now,
+            valid_to: None,
+        });
 
-        if let Some(existing) = fact_db.get_mut(&fact) {
-            existing.verification_count += 1;
-            existing.last_verified = Utc::now();
-            existing.confidence = (existing.confidence + confidence) / 2.0; // Average confidence
-        } else {
-            fact_db.insert(fact, entry);
+        Ok(())
+    }
+
+    /// The fact's accepted revision as of `ts`, i.e. the one whose
+    /// `[valid_from, valid_to)` interval contains it — `None` if the key
+    /// has no history at all, or none of its revisions cover `ts`.
+    pub async fn fact_as_of(&self, key: &str, ts: DateTime<Utc>) -> Option<FactEntry> {
+        let fact_db = self.fact_database.read().unwrap();
+        fact_db.get(key).and_then(|timeline| timeline.as_of(ts)).cloned()
+    }
+
+    /// Compacts a fact's revision timeline in place, merging consecutive
+    /// revisions that carry identical confidence and validity window
+    /// (see `FactTimeline::compact`). A no-op if `key` has no history.
+    pub async fn compact_fact_timeline(&self, key: &str) -> Result<()> {
+        let mut fact_db = self.fact_database.write().unwrap();
+        if let Some(timeline) = fact_db.get_mut(key) {
+            timeline.compact();
         }
+        Ok(())
+    }
 
+    /// Record that `from` vouches for (or distrusts) `to` at `level`,
+    /// timestamped now. Only replaces an existing edge for this
+    /// (from, to) pair if it's newer (see `Timestamped::update`).
+    pub async fn add_trust_edge(&self, from: String, to: String, level: TrustLevel) -> Result<()> {
+        let mut trust_graph = self.trust_graph.write().unwrap();
+        trust_graph.add_edge(from, to, level, self.clock.now());
         Ok(())
     }
 
-    /// Update source credibility scores
-    pub async fn update_source_credibility(&self, source_id: String, credibility: f32) -> Result<()> {
-        let mut credibility_db = self.source_credibility.write().unwrap();
-        credibility_db.insert(source_id, credibility.clamp(0.0, 1.0));
+    /// Configure the anchor sources that credibility is propagated
+    /// outward from; a source in this set is always fully credible.
+    pub async fn set_trust_roots(&self, roots: Vec<String>) -> Result<()> {
+        let mut trust_graph = self.trust_graph.write().unwrap();
+        trust_graph.set_roots(roots);
         Ok(())
     }
 }
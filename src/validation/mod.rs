@@ -14,6 +14,11 @@ pub use hallucination_detector::{
     ContradictionSeverity,
     UncertaintyFactor,
     UncertaintyType,
+    Clock,
+    SystemClock,
+    FixedClock,
+    FactEntry,
+    ProofNode,
 };
 
 pub use input_validator::{InputValidator, ValidationError};
\ No newline at end of file
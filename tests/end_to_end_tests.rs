@@ -10,6 +10,11 @@ use kg_mcp_server::{
     HybridSearchEngine,
     MemoryOptimizer,
     mcp::handlers::handle_tool_request,
+    mcp::errors::{McpError, ToolRateLimiter},
+    mcp::search_queue::SearchQueue,
+    mcp::workers::WorkerManager,
+    indexing::StreamIngestionManager,
+    metrics::RecentEventsBuffer,
     graph::{Episode, EpisodeSource},
 };
 
@@ -285,4 +290,102 @@ mod e2e_tests {
 
         Ok(())
     }
+
+    /// Firing more concurrent `search_memory` calls than the configured
+    /// `search_queue_size` should shed some of the overflow as
+    /// `McpError::QueueFull` (how many depends on how fast the admitted calls
+    /// finish), not queue them indefinitely or fail the rest.
+    #[tokio::test]
+    async fn test_search_queue_sheds_load_over_capacity() -> Result<()> {
+        let mut config = ServerConfig::default();
+        config.search_queue_size = 2;
+
+        let storage = Arc::new(GraphStorage::new("test_e2e_queue.db", &config.database)?);
+        let embedding_engine = Arc::new(LocalEmbeddingEngine::new(config.clone())?);
+        let search_engine = Arc::new(HybridSearchEngine::new(
+            crate::search::TextSearchEngine::new(storage.clone())?,
+            crate::search::VectorSearchEngine::new(None)?,
+            None,
+        ));
+        let memory_optimizer = Arc::new(MemoryOptimizer::new(config.memory.clone().into()));
+        let rate_limiter = Arc::new(ToolRateLimiter::new(config.tool_rate_limit.clone()));
+        let worker_manager = Arc::new(WorkerManager::new());
+        let recent_events = Arc::new(RecentEventsBuffer::default());
+        let search_queue = Arc::new(SearchQueue::new(config.search_queue_size));
+        let stream_manager = Arc::new(StreamIngestionManager::new());
+
+        let search_params = json!({
+            "operation": "nodes",
+            "query": "queue load test",
+            "max_results": 10
+        });
+
+        // `SearchQueue`'s concurrency permits come from
+        // `available_parallelism`, not `search_queue_size`, so to actually
+        // exercise the waiting-capacity eviction path we need more
+        // in-flight calls than both the concurrency permits and the queue
+        // capacity combined.
+        let total_calls = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2)
+            + config.search_queue_size
+            + 4;
+
+        let mut handles = Vec::with_capacity(total_calls);
+        for _ in 0..total_calls {
+            let storage = storage.clone();
+            let embedding_engine = embedding_engine.clone();
+            let search_engine = search_engine.clone();
+            let memory_optimizer = memory_optimizer.clone();
+            let rate_limiter = rate_limiter.clone();
+            let worker_manager = worker_manager.clone();
+            let recent_events = recent_events.clone();
+            let search_queue = search_queue.clone();
+            let stream_manager = stream_manager.clone();
+            let params = search_params.clone();
+
+            handles.push(tokio::spawn(async move {
+                handle_tool_request(
+                    "mcp_kg-mcp-server_search_memory",
+                    params,
+                    &storage,
+                    &embedding_engine,
+                    &search_engine,
+                    &memory_optimizer,
+                    &rate_limiter,
+                    &worker_manager,
+                    &recent_events,
+                    &search_queue,
+                    &stream_manager,
+                    None,
+                ).await
+            }));
+        }
+
+        let mut shed_count = 0;
+        let mut ok_count = 0;
+        for handle in handles {
+            let result = handle.await.expect("spawned call should not panic");
+            match result {
+                Ok(_) => ok_count += 1,
+                Err(err) => {
+                    assert!(
+                        err.downcast_ref::<McpError>()
+                            .map(|e| matches!(e, McpError::QueueFull { .. }))
+                            .unwrap_or(false),
+                        "the only expected failure mode under overload is QueueFull, got: {:?}",
+                        err
+                    );
+                    shed_count += 1;
+                }
+            }
+        }
+
+        assert!(shed_count > 0, "overflowing the queue should shed at least one call");
+        assert!(ok_count > 0, "calls within capacity should still succeed");
+        assert_eq!(ok_count + shed_count, total_calls);
+
+        // Clean up
+        std::fs::remove_file("test_e2e_queue.db").ok();
+
+        Ok(())
+    }
 }